@@ -0,0 +1,139 @@
+// about this system - the machine's name, os, cpu and memory at the top of the performance
+// tab, copyable as plain text for bug reports, and the load and uptime line of the footer
+use chrono::{DateTime, Local};
+use iced::{
+    widget::{Button, Column, Row, Text},
+    Element, Length,
+};
+use sysinfo::System;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::format::{self, format_bytes, format_duration};
+use crate::Message;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemInfo {
+    pub hostname: String,
+    pub os: String,
+    pub kernel: String,
+    pub cpu: String,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub total_memory: u64,
+    // unix time, the uptime is counted from it whenever it is shown
+    pub boot_time: u64,
+}
+
+impl SystemInfo {
+    // the values sysinfo doesn't know are left as "unknown"
+    pub fn read(system: &System) -> SystemInfo {
+        let unknown = || String::from("unknown");
+        SystemInfo {
+            hostname: System::host_name().unwrap_or_else(unknown),
+            os: System::long_os_version().unwrap_or_else(unknown),
+            kernel: System::kernel_version().unwrap_or_else(unknown),
+            cpu: system
+                .cpus()
+                .first()
+                .map(|cpu| cpu.brand().trim().to_string())
+                .filter(|brand| !brand.is_empty())
+                .unwrap_or_else(unknown),
+            physical_cores: system.physical_core_count(),
+            logical_cores: system.cpus().len(),
+            total_memory: system.total_memory(),
+            boot_time: System::boot_time(),
+        }
+    }
+
+    // (label, value) pairs in display order, uptime as of `now`
+    pub fn fields(&self, now: u64) -> Vec<(&'static str, String)> {
+        let cores = match self.physical_cores {
+            Some(physical) => format!("{} physical, {} logical", physical, self.logical_cores),
+            None => format!("{} logical", self.logical_cores),
+        };
+        let boot = DateTime::from_timestamp(self.boot_time as i64, 0)
+            .map(|at| format::date_time(&at.with_timezone(&Local)))
+            .unwrap_or_default();
+        vec![
+            ("Hostname", self.hostname.clone()),
+            ("OS", self.os.clone()),
+            ("Kernel", self.kernel.clone()),
+            ("CPU", self.cpu.clone()),
+            ("Cores", cores),
+            ("Memory", format_bytes(self.total_memory as f64)),
+            ("Boot time", boot),
+            ("Uptime", format_uptime(now.saturating_sub(self.boot_time))),
+        ]
+    }
+
+    // one "Label: value" line per field
+    pub fn text(&self, now: u64) -> String {
+        self.fields(now).iter().map(|(label, value)| format!("{}: {}\n", label, value)).collect()
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default()
+}
+
+// e.g. "3 days 4:05:06", the days are left out under a day
+pub fn format_uptime(seconds: u64) -> String {
+    match seconds / 86_400 {
+        0 => format_duration(seconds),
+        1 => format!("1 day {}", format_duration(seconds % 86_400)),
+        days => format!("{} days {}", days, format_duration(seconds % 86_400)),
+    }
+}
+
+// e.g. "load 0.52 0.61 0.70, up 3 days 4:05:06", the load only on unix where it exists
+pub fn load_and_uptime(load: Option<(f64, f64, f64)>, uptime: u64) -> String {
+    match load {
+        Some((one, five, fifteen)) => format!("load {:.2} {:.2} {:.2}, up {}", one, five, fifteen, format_uptime(uptime)),
+        None => format!("up {}", format_uptime(uptime)),
+    }
+}
+
+// the line for this machine right now
+pub fn current_load_and_uptime() -> String {
+    let load = cfg!(unix).then(|| {
+        let load = System::load_average();
+        (load.one, load.five, load.fifteen)
+    });
+    load_and_uptime(load, System::uptime())
+}
+
+// construct the panel with its refresh and copy buttons
+pub fn view(info: &SystemInfo) -> Element<'_, Message> {
+    let now = now();
+    let fields = info.fields(now).into_iter().fold(Column::new().spacing(2), |column, (label, value)| {
+        column.push(Row::new().spacing(10).push(Text::new(label).width(Length::Fixed(100.0))).push(Text::new(value)))
+    });
+    Column::new()
+        .spacing(5)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new("About this system"))
+                .push(Button::new("Refresh").on_press(Message::RefreshSystemInfo))
+                .push(Button::new("Copy").on_press(Message::CopyText(info.text(now))))
+        )
+        .push(fields)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_text_counts_the_uptime_from_the_boot_time() {
+        let info = SystemInfo { hostname: String::from("box"), physical_cores: Some(4), logical_cores: 8, boot_time: 1_000, ..SystemInfo::default() };
+        let text = info.text(1_000 + 90_061);
+        assert!(text.starts_with("Hostname: box\n"));
+        assert!(text.contains("Cores: 4 physical, 8 logical\n"));
+        assert!(text.ends_with("Uptime: 1 day 1:01:01\n"));
+        assert_eq!(format_uptime(59), "0:00:59");
+        assert_eq!(load_and_uptime(Some((0.5, 1.0, 12.5)), 3_600), "load 0.50 1.00 12.50, up 1:00:00");
+    }
+}
@@ -0,0 +1,136 @@
+// detects the desktop's accent color so the app can use it for buttons and highlights
+use iced::{theme::Palette, Color, Theme};
+use serde::{Deserialize, Serialize};
+
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    // base palette the accent is applied to
+    #[default]
+    Dark,
+    Light,
+    // white on black with saturated colors, for low vision
+    HighContrast,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 3] = [ThemeChoice::Dark, ThemeChoice::Light, ThemeChoice::HighContrast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeChoice::Dark => "Dark",
+            ThemeChoice::Light => "Light",
+            ThemeChoice::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn base(self) -> Theme {
+        match self {
+            ThemeChoice::Dark => Theme::Dark,
+            ThemeChoice::Light => Theme::Light,
+            ThemeChoice::HighContrast => Theme::custom(
+                String::from("High contrast"),
+                Palette {
+                    background: Color::BLACK,
+                    text: Color::WHITE,
+                    primary: Color::from_rgb8(0x00, 0x3c, 0xff),
+                    success: Color::from_rgb8(0x00, 0xc8, 0x00),
+                    danger: Color::from_rgb8(0xff, 0x40, 0x40),
+                },
+            ),
+        }
+    }
+}
+
+// builds the app theme, replacing the palette's primary color with the accent if given. the
+// high contrast theme keeps its own, an accent can be as dark as its background
+pub fn theme(choice: ThemeChoice, accent: Option<Color>) -> Theme {
+    match accent.filter(|_| choice != ThemeChoice::HighContrast) {
+        Some(accent) => Theme::custom(
+            format!("{} (system accent)", choice.label()),
+            Palette {
+                primary: accent,
+                ..choice.base().palette()
+            },
+        ),
+        None => choice.base(),
+    }
+}
+
+// asks the platform for its accent color, None if it has none or it can't be read
+pub fn detect() -> Option<Color> {
+    if cfg!(target_os = "windows") {
+        windows_accent()
+    } else if cfg!(target_os = "macos") {
+        macos_accent()
+    } else {
+        kde_accent().or_else(gnome_accent)
+    }
+}
+
+// runs a command and returns its trimmed stdout if it succeeded
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// GNOME 47+ stores a named accent, e.g. 'blue'
+fn gnome_accent() -> Option<Color> {
+    let name = command_output("gsettings", &["get", "org.gnome.desktop.interface", "accent-color"])?;
+    let rgb = match name.trim_matches('\'') {
+        "blue" => (0x35, 0x84, 0xe4),
+        "teal" => (0x21, 0x90, 0xa4),
+        "green" => (0x3a, 0x94, 0x4a),
+        "yellow" => (0xc8, 0x88, 0x00),
+        "orange" => (0xed, 0x5b, 0x00),
+        "red" => (0xe6, 0x2d, 0x42),
+        "pink" => (0xd5, 0x61, 0x99),
+        "purple" => (0x91, 0x41, 0xac),
+        "slate" => (0x6f, 0x83, 0x96),
+        _ => return None,
+    };
+    Some(Color::from_rgb8(rgb.0, rgb.1, rgb.2))
+}
+
+// KDE writes "AccentColor=r,g,b" to the [General] group of kdeglobals
+fn kde_accent() -> Option<Color> {
+    let path = dirs::config_dir()?.join("kdeglobals");
+    let contents = std::fs::read_to_string(path).ok()?;
+    let value = contents
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("AccentColor="))?;
+    let parts: Vec<u8> = value.split(',').filter_map(|part| part.trim().parse().ok()).collect();
+    match parts[..] {
+        [r, g, b, ..] => Some(Color::from_rgb8(r, g, b)),
+        _ => None,
+    }
+}
+
+// the DWM AccentColor value is a DWORD in 0xAABBGGRR order
+fn windows_accent() -> Option<Color> {
+    let output = command_output("reg", &["query", r"HKCU\Software\Microsoft\Windows\DWM", "/v", "AccentColor"])?;
+    let hex = output.split_whitespace().last()?.trim_start_matches("0x");
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::from_rgb8(value as u8, (value >> 8) as u8, (value >> 16) as u8))
+}
+
+// macOS stores an index into its fixed accent list, missing means the default blue
+fn macos_accent() -> Option<Color> {
+    let index = command_output("defaults", &["read", "-g", "AppleAccentColor"]).unwrap_or_default();
+    let rgb = match index.as_str() {
+        "-1" => (0x8c, 0x8c, 0x8c),
+        "0" => (0xff, 0x52, 0x57),
+        "1" => (0xf7, 0x82, 0x1b),
+        "2" => (0xff, 0xc6, 0x00),
+        "3" => (0x62, 0xba, 0x46),
+        "5" => (0xa5, 0x50, 0xa7),
+        "6" => (0xf7, 0x4f, 0x9e),
+        _ => (0x00, 0x7a, 0xff),
+    };
+    Some(Color::from_rgb8(rgb.0, rgb.1, rgb.2))
+}
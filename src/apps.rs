@@ -0,0 +1,188 @@
+// apps and background processes - the processes tab can list the programs the user opened
+// apart from everything else, like Windows' Task Manager does
+//
+// a process is an app if it owns a visible top-level window on windows, runs the main
+// executable of an application bundle on macOS, or runs a program a desktop entry launches
+// on linux (window lists aren't available under wayland). its descendants go with it, so a
+// browser's helpers and a terminal's shells are listed under the app
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::process::ProcessInfo;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Group {
+    Apps,
+    Background,
+    // a systemd slice, service or scope by its cgroup path, see slices.rs
+    Unit(String),
+    // processes whose details couldn't be read, listed last
+    Denied,
+}
+
+impl Group {
+    pub fn label(&self) -> &str {
+        match self {
+            Group::Apps => "Apps",
+            Group::Background => "Background processes",
+            Group::Unit(path) => path.rsplit('/').find(|part| !part.is_empty()).unwrap_or("/"),
+            Group::Denied => "Access denied",
+        }
+    }
+}
+
+// a line of the process list while it is grouped: a group's heading or a process
+#[derive(Debug, Clone)]
+pub enum ListRow<'a> {
+    Group { group: Group, count: usize, collapsed: bool },
+    // a unit's heading, indented by its depth, with the totals of the processes under it
+    Unit { group: Group, depth: usize, count: usize, cpu: f32, memory: u64, collapsed: bool },
+    Process(&'a ProcessInfo),
+}
+
+// deepest parent chain followed to find an app, pid reuse could otherwise make a loop
+const MAX_DEPTH: usize = 64;
+
+// whether the process is an app itself, the system's own processes never are
+pub fn is_app(process: &ProcessInfo) -> bool {
+    if process.system {
+        return false;
+    }
+    if cfg!(windows) {
+        process.window_title.is_some()
+    } else if cfg!(target_os = "macos") {
+        process.exe.as_deref().is_some_and(is_bundle_executable)
+    } else {
+        let programs = desktop_programs();
+        let exe = process.exe.as_deref().and_then(Path::file_name).map(|name| name.to_string_lossy());
+        exe.is_some_and(|exe| programs.contains_key(exe.as_ref())) || programs.contains_key(&process.name)
+    }
+}
+
+// e.g. /Applications/Safari.app/Contents/MacOS/Safari, but not the helper apps nested in a
+// bundle's Frameworks
+fn is_bundle_executable(exe: &Path) -> bool {
+    let path = exe.to_string_lossy();
+    path.matches(".app/").count() == 1 && path.contains(".app/Contents/MacOS/")
+}
+
+// splits the rows into apps (with their descendants) and background processes, keeping the
+// order of each part. `all` is the whole table, so an app filtered out of the rows still
+// takes its descendants along
+pub fn partition<'a>(rows: &[&'a ProcessInfo], all: &[ProcessInfo]) -> (Vec<&'a ProcessInfo>, Vec<&'a ProcessInfo>) {
+    let parents: HashMap<u32, Option<u32>> = all.iter().map(|process| (process.pid, process.ppid)).collect();
+    let apps: HashSet<u32> = all.iter().filter(|process| process.app).map(|process| process.pid).collect();
+    let in_app = |process: &ProcessInfo| {
+        let mut pid = Some(process.pid);
+        for _ in 0..MAX_DEPTH {
+            match pid {
+                Some(current) if apps.contains(&current) => return true,
+                Some(current) => pid = parents.get(&current).copied().flatten(),
+                None => break,
+            }
+        }
+        false
+    };
+    rows.iter().partition(|process| in_app(process))
+}
+
+// wrappers a desktop entry may run a program through, which would make every process of
+// theirs count as an app
+const LAUNCHERS: [&str; 8] = ["sh", "bash", "env", "python", "python3", "perl", "flatpak", "snap"];
+
+// file names of the programs the installed desktop entries start with the icon each entry
+// names, read once
+pub fn desktop_programs() -> &'static HashMap<String, Option<String>> {
+    static PROGRAMS: OnceLock<HashMap<String, Option<String>>> = OnceLock::new();
+    PROGRAMS.get_or_init(|| {
+        let mut programs = HashMap::new();
+        for dir in application_dirs() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|extension| extension == "desktop") {
+                    if let Some((program, icon)) = std::fs::read_to_string(&path).ok().and_then(|contents| parse_entry(&contents)) {
+                        // the first entry for a program wins, the user's own come first
+                        programs.entry(program).or_insert(icon);
+                    }
+                }
+            }
+        }
+        programs
+    })
+}
+
+// the XDG data dirs, the user's first, with flatpak's and snap's exports
+pub fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+    let system = std::env::var("XDG_DATA_DIRS").unwrap_or_else(|_| String::from("/usr/local/share:/usr/share"));
+    dirs.extend(system.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from));
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    dirs.push(PathBuf::from("/var/lib/snapd/desktop"));
+    dirs
+}
+
+fn application_dirs() -> Vec<PathBuf> {
+    data_dirs().into_iter().map(|dir| dir.join("applications")).collect()
+}
+
+// the file name of the program a shown application entry runs and its icon, e.g. "firefox"
+// for "Exec=env MOZ_ENABLE_WAYLAND=1 /usr/lib/firefox/firefox %u"
+pub fn parse_entry(contents: &str) -> Option<(String, Option<String>)> {
+    let mut in_entry = false;
+    let mut exec = None;
+    let mut icon = None;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        match line.split_once('=').map(|(key, value)| (key.trim(), value.trim())) {
+            Some(("Type", kind)) if kind != "Application" => return None,
+            Some(("NoDisplay" | "Hidden", "true")) => return None,
+            Some(("Exec", value)) => exec = Some(value.to_string()),
+            Some(("Icon", value)) if !value.is_empty() => icon = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    let program = exec?
+        .split_whitespace()
+        .map(|word| word.trim_matches('"'))
+        // variables set by env, and env itself, come before the program
+        .find(|word| !word.contains('=') && *word != "env")
+        .and_then(|program| Path::new(program).file_name().map(|name| name.to_string_lossy().to_string()))
+        .filter(|program| !LAUNCHERS.contains(&program.as_str()))?;
+    Some((program, icon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    #[test]
+    fn entries_name_their_program_and_apps_take_their_children() {
+        let firefox = "[Desktop Entry]\nType=Application\nExec=env MOZ_ENABLE_WAYLAND=1 /usr/lib/firefox/firefox %u\nIcon=firefox-esr\n[Desktop Action new]\nExec=sh\n";
+        assert_eq!(parse_entry(firefox), Some((String::from("firefox"), Some(String::from("firefox-esr")))));
+        assert_eq!(parse_entry("[Desktop Entry]\nExec=nm-applet\nNoDisplay=true\n"), None);
+        assert_eq!(parse_entry("[Desktop Entry]\nExec=sh -c \"echo hi\"\n"), None);
+        assert!(is_bundle_executable(Path::new("/Applications/Safari.app/Contents/MacOS/Safari")));
+        assert!(!is_bundle_executable(Path::new("/Applications/Chrome.app/Contents/Frameworks/Helper.app/Contents/MacOS/Helper")));
+
+        let app = ProcessInfo { app: true, ..mock::process(10, "firefox", "alice", 0, 0.0) };
+        let child = ProcessInfo { ppid: Some(10), ..mock::process(11, "Web Content", "alice", 0, 0.0) };
+        let daemon = ProcessInfo { ppid: Some(1), ..mock::process(20, "sshd", "root", 0, 0.0) };
+        let all = vec![app, child, daemon];
+        let rows: Vec<&ProcessInfo> = all.iter().collect();
+        let (apps, background) = partition(&rows[1..], &all);
+        assert_eq!((apps.len(), apps[0].pid), (1, 11));
+        assert_eq!(background[0].pid, 20);
+    }
+}
@@ -0,0 +1,134 @@
+// autostart on login - the platform's own entry for starting the app, so it also shows up
+// (and can be turned off) in the desktop's startup settings
+//
+// an XDG .desktop file in ~/.config/autostart on linux, a value under the Run key of the
+// registry on windows and a LaunchAgent on macOS. the entry is the source of truth, nothing
+// is kept in the config, and it only says whether to start with --minimized
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// flag the entry adds when the window should start hidden in the tray
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+const LAUNCH_AGENT: &str = "com.conborli.task-manager";
+
+// the installed entry, None if there isn't one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Autostart {
+    pub minimized: bool,
+}
+
+fn entry_file() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|home| home.join("Library/LaunchAgents").join(format!("{}.plist", LAUNCH_AGENT)))
+    } else {
+        dirs::config_dir().map(|dir| dir.join("autostart/conborli.desktop"))
+    }
+}
+
+pub fn installed() -> Option<Autostart> {
+    let contents = if cfg!(target_os = "windows") {
+        let output = Command::new("reg").args(["query", RUN_KEY, "/v", "conborli"]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout).to_string()
+    } else {
+        fs::read_to_string(entry_file()?).ok()?
+    };
+    Some(Autostart { minimized: contents.contains(MINIMIZED_FLAG) })
+}
+
+// writes (or replaces) the entry for the running executable
+pub fn install(minimized: bool) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|err| format!("failed to find the executable: {}", err))?;
+    let exe = exe.display().to_string();
+    if cfg!(target_os = "windows") {
+        let mut command = format!("\"{}\"", exe);
+        if minimized {
+            command = format!("{} {}", command, MINIMIZED_FLAG);
+        }
+        return reg(&["add", RUN_KEY, "/v", "conborli", "/t", "REG_SZ", "/d", &command, "/f"]);
+    }
+    let path = entry_file().ok_or("no config directory on this platform")?;
+    let contents = if cfg!(target_os = "macos") {
+        launch_agent(&exe, minimized)
+    } else {
+        desktop_entry(&exe, minimized)
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("failed to create {}: {}", dir.display(), err))?;
+    }
+    fs::write(&path, contents).map_err(|err| format!("failed to write {}: {}", path.display(), err))
+}
+
+pub fn remove() -> Result<(), String> {
+    if cfg!(target_os = "windows") {
+        return reg(&["delete", RUN_KEY, "/v", "conborli", "/f"]);
+    }
+    match entry_file() {
+        Some(path) if path.exists() => fs::remove_file(&path).map_err(|err| format!("failed to remove {}: {}", path.display(), err)),
+        _ => Ok(()),
+    }
+}
+
+fn reg(args: &[&str]) -> Result<(), String> {
+    let output = Command::new("reg").args(args).output().map_err(|err| format!("failed to run reg: {}", err))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("reg failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+// the Exec key quotes arguments like a shell, with \ escaping " ` $ and \
+fn desktop_entry(exe: &str, minimized: bool) -> String {
+    let mut quoted = String::from("\"");
+    for c in exe.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    if minimized {
+        quoted = format!("{} {}", quoted, MINIMIZED_FLAG);
+    }
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Task Manager\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        quoted.replace('%', "%%")
+    )
+}
+
+fn launch_agent(exe: &str, minimized: bool) -> String {
+    let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let mut arguments = format!("        <string>{}</string>\n", escape(exe));
+    if minimized {
+        arguments.push_str(&format!("        <string>{}</string>\n", MINIMIZED_FLAG));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n    <key>Label</key>\n    <string>{}</string>\n    <key>ProgramArguments</key>\n    <array>\n{}    </array>\n    <key>RunAtLoad</key>\n    <true/>\n</dict>\n\
+         </plist>\n",
+        LAUNCH_AGENT, arguments
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_quote_the_executable() {
+        let entry = desktop_entry("/opt/my apps/con$borli", true);
+        assert!(entry.contains("Exec=\"/opt/my apps/con\\$borli\" --minimized\n"));
+        assert!(!desktop_entry("/usr/bin/conborli", false).contains(MINIMIZED_FLAG));
+        let agent = launch_agent("/Applications/A&B.app/conborli", false);
+        assert!(agent.contains("<string>/Applications/A&amp;B.app/conborli</string>"));
+        assert!(!agent.contains(MINIMIZED_FLAG));
+    }
+}
@@ -0,0 +1,110 @@
+// cpu and memory cells drawn as a bar filled to the value with the number over it, so a heavy
+// process stands out when scanning the table. the fill is graded from the theme's success
+// color through the warning highlight to the critical one as the value grows
+use iced::mouse;
+use iced::widget::canvas::{self, Canvas, Frame, Geometry};
+use iced::{alignment, Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+
+use crate::process::{ProcessInfo, SortColumn};
+use crate::style::Highlights;
+use crate::Message;
+
+// what the bars are measured against and drawn with, the same for every row
+#[derive(Debug, Clone, Copy)]
+pub struct Bars {
+    // the machine's memory in MB, a full memory bar
+    pub total_memory: u64,
+    pub height: f32,
+    pub text_size: f32,
+    pub warning: Color,
+    pub critical: Color,
+}
+
+impl Bars {
+    pub fn new(total_memory: u64, text_size: f32, colors: &Highlights) -> Bars {
+        Bars { total_memory, height: (text_size * 1.3).round(), text_size, warning: colors.warning, critical: colors.critical }
+    }
+
+    // how full the column's bar is, None for the columns drawn as text
+    pub fn fraction(&self, process: &ProcessInfo, column: SortColumn) -> Option<f32> {
+        match column {
+            SortColumn::Cpu => Some(process.cpu / 100.0),
+            SortColumn::Memory if self.total_memory > 0 => Some(process.memory as f32 / self.total_memory as f32),
+            _ => None,
+        }
+        .map(|fraction| fraction.clamp(0.0, 1.0))
+    }
+}
+
+// from `low` at nothing to `warning` at half and `critical` when full
+pub fn grade(fraction: f32, low: Color, warning: Color, critical: Color) -> Color {
+    let mix = |from: Color, to: Color, t: f32| Color {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
+    };
+    let fraction = fraction.clamp(0.0, 1.0);
+    if fraction < 0.5 {
+        mix(low, warning, fraction * 2.0)
+    } else {
+        mix(warning, critical, (fraction - 0.5) * 2.0)
+    }
+}
+
+struct BarCell {
+    fraction: f32,
+    text: String,
+    bars: Bars,
+}
+
+impl canvas::Program<Message> for BarCell {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = frame.size();
+        frame.fill_rectangle(Point::ORIGIN, size, palette.background.weak.color);
+        if self.fraction > 0.0 {
+            // a sliver stays visible for values that round to nothing
+            let width = (size.width * self.fraction).max(2.0);
+            let color = grade(self.fraction, palette.success.base.color, self.bars.warning, self.bars.critical);
+            frame.fill_rectangle(Point::ORIGIN, Size::new(width, size.height), Color { a: 0.7, ..color });
+        }
+        frame.fill_text(canvas::Text {
+            content: self.text.clone(),
+            position: Point::new(4.0, size.height / 2.0),
+            color: palette.background.base.text,
+            size: self.bars.text_size.into(),
+            vertical_alignment: alignment::Vertical::Center,
+            ..canvas::Text::default()
+        });
+        vec![frame.into_geometry()]
+    }
+}
+
+pub fn view<'a>(fraction: f32, text: String, width: Length, bars: Bars) -> Element<'a, Message> {
+    Canvas::new(BarCell { fraction, text, bars }).width(width).height(Length::Fixed(bars.height)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn bars_fill_by_share_and_grade_to_critical() {
+        let bars = Bars::new(16_000, 14.0, &Highlights::default());
+        let process = mock::process(1, "a", "u", 4000, 250.0);
+        assert_eq!(bars.fraction(&process, SortColumn::Memory), Some(0.25));
+        assert_eq!(bars.fraction(&process, SortColumn::Cpu), Some(1.0));
+        assert_eq!(bars.fraction(&process, SortColumn::Pid), None);
+
+        let (low, warning, critical) = (Color::BLACK, Color::from_rgb(1.0, 1.0, 0.0), Color::from_rgb(1.0, 0.0, 0.0));
+        assert_eq!(grade(0.0, low, warning, critical), low);
+        assert_eq!(grade(0.5, low, warning, critical), warning);
+        assert_eq!(grade(1.0, low, warning, critical), critical);
+        assert_eq!(grade(0.25, low, warning, critical), Color::from_rgb(0.5, 0.5, 0.0));
+    }
+}
@@ -0,0 +1,52 @@
+// battery status shown in the footer, polled on a slower interval than processes
+use starship_battery::units::ratio::percent;
+use starship_battery::units::time::second;
+use starship_battery::{Manager, State};
+
+use crate::format::format_duration;
+
+#[derive(Debug, Clone)]
+pub struct BatteryStatus {
+    // holds the charge info of one battery
+    pub charge: f32,
+    pub state: State,
+    // seconds until full while charging or until empty while discharging
+    pub time_remaining: Option<u64>,
+}
+
+// reads every battery the platform reports, an empty list on desktops or on errors
+pub fn read(manager: &Manager) -> Vec<BatteryStatus> {
+    let Ok(batteries) = manager.batteries() else {
+        return Vec::new();
+    };
+    batteries
+        .flatten()
+        .map(|battery| {
+            let time = match battery.state() {
+                State::Charging => battery.time_to_full(),
+                State::Discharging => battery.time_to_empty(),
+                _ => None,
+            };
+            BatteryStatus {
+                charge: battery.state_of_charge().get::<percent>(),
+                state: battery.state(),
+                time_remaining: time.map(|time| time.get::<second>() as u64),
+            }
+        })
+        .collect()
+}
+
+// running off a battery, none of them charging or full on the charger
+pub fn on_battery(batteries: &[BatteryStatus]) -> bool {
+    batteries.iter().any(|status| status.state == State::Discharging)
+}
+
+// one line summary for the footer, e.g. "Battery 85% (discharging, 2:31:00 left)"
+pub fn summary(status: &BatteryStatus) -> String {
+    let state = status.state.to_string().to_lowercase();
+    match (status.state, status.time_remaining) {
+        (State::Charging, Some(seconds)) => format!("Battery {:.0}% ({}, full in {})", status.charge, state, format_duration(seconds)),
+        (_, Some(seconds)) => format!("Battery {:.0}% ({}, {} left)", status.charge, state, format_duration(seconds)),
+        _ => format!("Battery {:.0}% ({})", status.charge, state),
+    }
+}
@@ -0,0 +1,112 @@
+// --bench: times the parts of a process refresh on this machine over a number of rounds and
+// prints them as json, to pick the refresh settings with and to compare builds against
+//
+// "full" refreshes everything sysinfo knows about each process, "targeted" only what the
+// table reads (see provider.rs), so the difference is what the targeted refresh saves
+use serde::Serialize;
+use sysinfo::{ProcessRefreshKind, System, Users};
+
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::filter::Filter;
+use crate::process::{self, SortColumn};
+use crate::provider::{ProcessProvider, SystemProvider};
+
+// searched for in the filter timing, fielded and plain words like a typical search
+const QUERY: &str = "cpu>1 or user:root or sh";
+
+// milliseconds over the rounds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    pub mean: f64,
+    pub min: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl Stats {
+    fn of(samples: &[Duration]) -> Stats {
+        let mut ms: Vec<f64> = samples.iter().map(|sample| sample.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let at = |share: f64| ms.get(((ms.len() as f64 * share).ceil() as usize).saturating_sub(1)).copied().unwrap_or_default();
+        Stats {
+            mean: ms.iter().sum::<f64>() / ms.len().max(1) as f64,
+            min: ms.first().copied().unwrap_or_default(),
+            p95: at(0.95),
+            max: ms.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub iterations: usize,
+    pub processes: usize,
+    pub cpus: usize,
+    pub full_refresh: Stats,
+    pub targeted_refresh: Stats,
+    // building the rows from a refreshed system, part of both refreshes above
+    pub rows: Stats,
+    pub sort: Stats,
+    pub filter: Stats,
+}
+
+pub fn run(iterations: usize, config: &Config) -> Report {
+    let users = Users::new_with_refreshed_list();
+    let mut system = System::new();
+    system.refresh_cpu();
+    // the first refresh fills the process list, later ones only update it
+    system.refresh_processes();
+    let (mut full, mut targeted, mut rows, mut sort, mut filter) = (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let mut search = Filter::default();
+    search.text = String::from(QUERY);
+    let mut processes = Vec::new();
+    for _ in 0..iterations.max(1) {
+        let started = Instant::now();
+        system.refresh_processes_specifics(ProcessRefreshKind::everything());
+        std::hint::black_box(process::collect(&system, &users));
+        full.push(started.elapsed());
+
+        let started = Instant::now();
+        std::hint::black_box(SystemProvider.collect(&mut system, &users));
+        targeted.push(started.elapsed());
+
+        let started = Instant::now();
+        processes = process::collect(&system, &users);
+        rows.push(started.elapsed());
+
+        let started = Instant::now();
+        process::sort(&mut processes, SortColumn::Cpu, false, config.secondary_sort);
+        sort.push(started.elapsed());
+
+        let started = Instant::now();
+        let shown = processes.iter().filter(|process| search.matches(process)).count();
+        filter.push(started.elapsed());
+        std::hint::black_box(shown);
+    }
+    Report {
+        iterations: iterations.max(1),
+        processes: processes.len(),
+        cpus: system.cpus().len(),
+        full_refresh: Stats::of(&full),
+        targeted_refresh: Stats::of(&targeted),
+        rows: Stats::of(&rows),
+        sort: Stats::of(&sort),
+        filter: Stats::of(&filter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_the_samples() {
+        let samples: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        let stats = Stats::of(&samples);
+        assert_eq!((stats.min, stats.p95, stats.max), (1.0, 19.0, 20.0));
+        assert!((stats.mean - 10.5).abs() < 1e-9);
+        assert_eq!(Stats::of(&[]).max, 0.0);
+    }
+}
@@ -0,0 +1,116 @@
+// boosting one process - its priority raised, optionally the rest of its user's processes
+// lowered, and everything put back after the configured time or when the boost is ended.
+// for giving a game or a build the machine without renicing by hand
+use iced::{
+    alignment,
+    widget::{Button, Row, Text},
+    Element,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::format;
+use crate::priority;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// nice value of the boosted process, and the one the others are lowered to
+const BOOSTED_NICE: i32 = -10;
+const LOWERED_NICE: i32 = 10;
+
+#[derive(Debug, Clone)]
+pub struct Boost {
+    pub pid: u32,
+    pub name: String,
+    // the nice values to put back, by pid with the name it had, the boosted one first
+    changed: Vec<(u32, String, i32)>,
+    // None until ended by hand
+    pub until: Option<Instant>,
+}
+
+impl Boost {
+    // raises the process and lowers its user's others, the ones that can't be changed are
+    // left as they are. fails only when the process itself can't be raised
+    pub fn start(processes: &[ProcessInfo], pid: u32, lower_others: bool, minutes: u64, now: Instant) -> Result<Boost, String> {
+        let process = processes.iter().find(|process| process.pid == pid).ok_or_else(|| format!("process {} isn't in the table", pid))?;
+        let original = process.nice.ok_or_else(|| String::from("changing priority is not supported on this platform"))?;
+        priority::set_nice(pid, BOOSTED_NICE.min(original))?;
+        let mut changed = vec![(pid, process.name.clone(), original)];
+        if lower_others {
+            let kept = descendants(processes, pid);
+            for other in processes.iter().filter(|other| other.user == process.user && !kept.contains(&other.pid)) {
+                if let Some(nice) = other.nice.filter(|&nice| nice < LOWERED_NICE) {
+                    if priority::set_nice(other.pid, LOWERED_NICE).is_ok() {
+                        changed.push((other.pid, other.name.clone(), nice));
+                    }
+                }
+            }
+        }
+        let until = (minutes > 0).then(|| now + Duration::from_secs(minutes * 60));
+        Ok(Boost { pid, name: process.name.clone(), changed, until })
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.until.is_some_and(|until| until <= now)
+    }
+
+    // processes lowered next to the boosted one
+    pub fn lowered(&self) -> usize {
+        self.changed.len() - 1
+    }
+
+    // puts the nice values back on the processes still running under the same name, returns
+    // the errors
+    pub fn revert(&self, processes: &[ProcessInfo]) -> Vec<String> {
+        let running: HashMap<u32, &str> = processes.iter().map(|process| (process.pid, process.name.as_str())).collect();
+        self.changed
+            .iter()
+            .filter(|(pid, name, _)| running.get(pid) == Some(&name.as_str()))
+            .filter_map(|&(pid, _, nice)| priority::set_nice(pid, nice).err())
+            .collect()
+    }
+}
+
+// the process and everything it started, which keep their priority
+fn descendants(processes: &[ProcessInfo], pid: u32) -> HashSet<u32> {
+    let mut found = HashSet::from([pid]);
+    loop {
+        let before = found.len();
+        found.extend(processes.iter().filter(|process| process.ppid.is_some_and(|ppid| found.contains(&ppid))).map(|process| process.pid).collect::<Vec<_>>());
+        if found.len() == before {
+            return found;
+        }
+    }
+}
+
+// the boost with its time left and a button ending it early
+pub fn view(boost: &Boost, now: Instant) -> Element<'_, Message> {
+    let mut text = format!("Boosting {} ({})", boost.name, boost.pid);
+    if boost.lowered() > 0 {
+        text.push_str(&format!(", {} other processes lowered", boost.lowered()));
+    }
+    if let Some(until) = boost.until {
+        text.push_str(&format!(" · {} left", format::format_duration(until.saturating_duration_since(now).as_secs())));
+    }
+    Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(text))
+        .push(Button::new("End boost").on_press(Message::EndBoost))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    #[test]
+    fn descendants_keep_their_priority() {
+        let child = |pid, ppid| ProcessInfo { ppid: Some(ppid), ..mock::process(pid, "p", "alice", 0, 0.0) };
+        let processes = [mock::process(1, "game", "alice", 0, 0.0), child(2, 1), child(3, 2), child(4, 9)];
+        assert_eq!(descendants(&processes, 1), HashSet::from([1, 2, 3]));
+    }
+}
@@ -0,0 +1,185 @@
+// cgroup and namespaces of a process, shown in the details pane - where systemd or a
+// container runtime put it, the memory and cpu limits it runs under, and which namespaces
+// separate it from the rest of the system
+//
+// cgroup v2 (the unified hierarchy) is read from the process's own group and limits set
+// higher up apply too, so the tightest one on the way to the root is shown. on v1 hosts the
+// memory and cpu controllers' groups are read instead
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// memory use from this share of the limit on counts as near it
+pub const NEAR_LIMIT: f64 = 0.9;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+// namespaces listed in /proc/<pid>/ns, in display order
+const NAMESPACES: [&str; 8] = ["cgroup", "ipc", "mnt", "net", "pid", "time", "user", "uts"];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Isolation {
+    // e.g. /user.slice/user-1000.slice/session-2.scope
+    pub cgroup: Option<String>,
+    // bytes in use by the group and its tightest limit, None when unlimited
+    pub memory_current: Option<u64>,
+    pub memory_limit: Option<u64>,
+    // cpus worth of time per period, e.g. 0.5, None when unlimited
+    pub cpu_quota: Option<f64>,
+    // (namespace, inode id, whether it differs from init's), unreadable ones are left out
+    pub namespaces: Vec<(&'static str, u64, bool)>,
+}
+
+impl Isolation {
+    pub fn near_memory_limit(&self) -> bool {
+        match (self.memory_current, self.memory_limit) {
+            (Some(current), Some(limit)) if limit > 0 => current as f64 / limit as f64 >= NEAR_LIMIT,
+            _ => false,
+        }
+    }
+}
+
+// the v2 path ("0::/path") and the v1 path of each controller ("4:memory:/path")
+pub fn parse_cgroup_file(contents: &str) -> (Option<String>, HashMap<String, String>) {
+    let mut unified = None;
+    let mut controllers = HashMap::new();
+    for line in contents.lines() {
+        let mut parts = line.splitn(3, ':');
+        let (Some(_), Some(names), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if names.is_empty() {
+            unified = Some(path.to_string());
+        } else {
+            for name in names.split(',') {
+                controllers.insert(name.to_string(), path.to_string());
+            }
+        }
+    }
+    (unified, controllers)
+}
+
+// memory.max: a byte count or "max"
+pub fn parse_memory_max(contents: &str) -> Option<u64> {
+    contents.trim().parse().ok()
+}
+
+// cpu.max: "<quota> <period>" in microseconds, the quota can be "max"
+pub fn parse_cpu_max(contents: &str) -> Option<f64> {
+    let mut parts = contents.split_whitespace();
+    let quota: f64 = parts.next()?.parse().ok()?;
+    let period: f64 = parts.next()?.parse().ok()?;
+    (period > 0.0).then(|| quota / period)
+}
+
+// the group and its ancestors up to the hierarchy root, innermost first
+fn ancestors(root: &Path, path: &str) -> Vec<PathBuf> {
+    let mut dir = root.join(path.trim_start_matches('/'));
+    let mut dirs = Vec::new();
+    while dir.starts_with(root) {
+        dirs.push(dir.clone());
+        if !dir.pop() {
+            break;
+        }
+    }
+    dirs
+}
+
+fn read(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+// the smallest limit any group on the way to the root sets
+fn tightest<T: PartialOrd>(dirs: &[PathBuf], file: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    dirs.iter()
+        .filter_map(|dir| parse(&read(&dir.join(file))?))
+        .fold(None, |tightest, limit| match tightest {
+            Some(current) if current <= limit => Some(current),
+            _ => Some(limit),
+        })
+}
+
+#[cfg(target_os = "linux")]
+pub fn for_pid(pid: u32) -> Result<Isolation, String> {
+    let contents = read(Path::new(&format!("/proc/{}/cgroup", pid))).ok_or_else(|| format!("process {} is gone", pid))?;
+    let (unified, controllers) = parse_cgroup_file(&contents);
+    let root = Path::new(CGROUP_ROOT);
+    let mut isolation = Isolation::default();
+    match (controllers.get("memory"), &unified) {
+        // v1, or a hybrid host whose v2 tree has no controllers
+        (Some(memory), _) => {
+            let memory_root = root.join("memory");
+            let dirs = ancestors(&memory_root, memory);
+            isolation.memory_current = read(&memory_root.join(memory.trim_start_matches('/')).join("memory.usage_in_bytes"))
+                .and_then(|usage| usage.trim().parse().ok());
+            // no limit reads as a huge page-aligned number
+            isolation.memory_limit = tightest(&dirs, "memory.limit_in_bytes", parse_memory_max).filter(|&limit| limit < i64::MAX as u64 / 2);
+            if let Some(cpu) = controllers.get("cpu") {
+                let cpu_root = root.join("cpu");
+                let dir = cpu_root.join(cpu.trim_start_matches('/'));
+                let quota = read(&dir.join("cpu.cfs_quota_us")).and_then(|quota| quota.trim().parse::<f64>().ok());
+                let period = read(&dir.join("cpu.cfs_period_us")).and_then(|period| period.trim().parse::<f64>().ok());
+                isolation.cpu_quota = match (quota, period) {
+                    (Some(quota), Some(period)) if quota > 0.0 && period > 0.0 => Some(quota / period),
+                    _ => None,
+                };
+            }
+            isolation.cgroup = Some(memory.clone());
+        }
+        (None, Some(path)) => {
+            let dirs = ancestors(root, path);
+            isolation.memory_current = read(&root.join(path.trim_start_matches('/')).join("memory.current"))
+                .and_then(|current| current.trim().parse().ok());
+            isolation.memory_limit = tightest(&dirs, "memory.max", parse_memory_max);
+            isolation.cpu_quota = tightest(&dirs, "cpu.max", parse_cpu_max);
+            isolation.cgroup = Some(path.clone());
+        }
+        (None, None) => {}
+    }
+    let namespace = |pid: &str, name: &str| {
+        let link = fs::read_link(format!("/proc/{}/ns/{}", pid, name)).ok()?;
+        namespace_id(&link.to_string_lossy())
+    };
+    isolation.namespaces = NAMESPACES
+        .iter()
+        .filter_map(|&name| {
+            let id = namespace(&pid.to_string(), name)?;
+            // init's are only readable as root, without them nothing is marked
+            let separate = namespace("1", name).is_some_and(|init| init != id);
+            Some((name, id, separate))
+        })
+        .collect();
+    Ok(isolation)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn for_pid(_pid: u32) -> Result<Isolation, String> {
+    Err(String::from("cgroups and namespaces only exist on linux"))
+}
+
+// the inode from a link like "net:[4026531840]"
+pub fn namespace_id(link: &str) -> Option<u64> {
+    link.split_once(":[")?.1.strip_suffix(']')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cgroup_files_and_limits_parse() {
+        let (unified, controllers) = parse_cgroup_file("12:memory:/docker/abc\n4:cpu,cpuacct:/docker/abc\n0::/system.slice/docker.service\n");
+        assert_eq!(unified.as_deref(), Some("/system.slice/docker.service"));
+        assert_eq!(controllers.get("cpuacct").map(String::as_str), Some("/docker/abc"));
+        assert_eq!(parse_memory_max("max\n"), None);
+        assert_eq!(parse_memory_max("536870912\n"), Some(536_870_912));
+        assert_eq!(parse_cpu_max("50000 100000\n"), Some(0.5));
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(namespace_id("net:[4026531840]"), Some(4_026_531_840));
+        let dirs = ancestors(Path::new("/sys/fs/cgroup"), "/user.slice/app.scope");
+        assert_eq!(dirs.len(), 3);
+        assert_eq!(dirs[2], Path::new("/sys/fs/cgroup"));
+        let near = Isolation { memory_current: Some(95), memory_limit: Some(100), ..Isolation::default() };
+        assert!(near.near_memory_limit());
+    }
+}
@@ -0,0 +1,250 @@
+// line charts of the performance tab, drawn on a canvas, with a marker at every kill and
+// rule match. hovering shows the value and time of the nearest point, or what a marker was.
+// a chart can also be drawn offscreen into a png, to paste a spike into a chat or bug report
+use chrono::{DateTime, Local};
+use iced::mouse;
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::{alignment, Color, Element, Length, Point as Position, Rectangle, Renderer, Size, Theme};
+
+use std::path::{Path as FilePath, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::format;
+use crate::events::{Event, EventKind};
+use crate::timeseries::{Point, Range};
+use crate::Message;
+
+const HEIGHT: f32 = 120.0;
+
+// how close the pointer has to be to a marker to show its text instead of the value
+const MARKER_REACH: f32 = 4.0;
+
+struct Chart {
+    points: Vec<Point>,
+    markers: Vec<Event>,
+    range: Range,
+    // top of the value axis, 100 for percentages
+    max: f32,
+    unit: &'static str,
+}
+
+impl Chart {
+    // where a point goes, the latest one at the right edge
+    fn position(&self, point: &Point, size: Size) -> Position {
+        let y = size.height * (1.0 - (point.value / self.max).clamp(0.0, 1.0));
+        Position::new(self.x(point.time, size.width), y)
+    }
+
+    fn x(&self, time: DateTime<Local>, width: f32) -> f32 {
+        let latest = self.points.last().map_or(time, |last| last.time);
+        let age = latest.signed_duration_since(time).num_milliseconds() as f32 / 1000.0;
+        width * (1.0 - age / self.range.span().as_secs_f32())
+    }
+}
+
+impl canvas::Program<Message> for Chart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = frame.size();
+        frame.fill_rectangle(Position::ORIGIN, size, palette.background.weak.color);
+        let line = Path::new(|path| {
+            for (index, point) in self.points.iter().enumerate() {
+                let position = self.position(point, size);
+                if index == 0 {
+                    path.move_to(position);
+                } else {
+                    path.line_to(position);
+                }
+            }
+        });
+        frame.stroke(&line, Stroke::default().with_width(1.5).with_color(palette.primary.base.color));
+        for marker in &self.markers {
+            let x = self.x(marker.time, size.width);
+            let color = match marker.kind {
+                EventKind::Kill => palette.danger.base.color,
+                _ => palette.secondary.strong.color,
+            };
+            frame.stroke(&Path::line(Position::new(x, 0.0), Position::new(x, size.height)), Stroke::default().with_color(color));
+            frame.fill(&Path::circle(Position::new(x, 3.0), 3.0), color);
+        }
+
+        let text_color = palette.background.base.text;
+        let label = |frame: &mut Frame, x: f32, content: String| {
+            // the label stays inside the chart on either side of what it describes
+            let left = x > size.width / 2.0;
+            frame.fill_text(canvas::Text {
+                content,
+                position: Position::new(if left { x - 6.0 } else { x + 6.0 }, 4.0),
+                color: text_color,
+                size: 12.0.into(),
+                horizontal_alignment: if left { alignment::Horizontal::Right } else { alignment::Horizontal::Left },
+                ..canvas::Text::default()
+            });
+        };
+        let Some(cursor) = cursor.position_in(bounds) else {
+            return vec![frame.into_geometry()];
+        };
+        let marker = self.markers.iter().rev().find(|marker| (self.x(marker.time, size.width) - cursor.x).abs() <= MARKER_REACH);
+        if let Some(marker) = marker {
+            label(&mut frame, self.x(marker.time, size.width), format!("{} · {}", format::time(&marker.time), marker.text));
+            return vec![frame.into_geometry()];
+        }
+        let hovered = self.points.iter().min_by(|a, b| {
+            let distance = |point: &Point| (self.position(point, size).x - cursor.x).abs();
+            distance(a).total_cmp(&distance(b))
+        });
+        if let Some(point) = hovered {
+            let position = self.position(point, size);
+            let guide = Path::line(Position::new(position.x, 0.0), Position::new(position.x, size.height));
+            frame.stroke(&guide, Stroke::default().with_color(Color { a: 0.5, ..palette.background.base.text }));
+            frame.fill(&Path::circle(position, 3.0), palette.primary.strong.color);
+            label(&mut frame, position.x, format!("{} · {}{}", format::time(&point.time), format::decimal(f64::from(point.value), 1), self.unit));
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+pub fn view<'a>(points: Vec<Point>, markers: Vec<Event>, range: Range, max: f32, unit: &'static str) -> Element<'a, Message> {
+    Canvas::new(Chart { points, markers, range, max, unit }).width(Length::Fill).height(Length::Fixed(HEIGHT)).into()
+}
+
+// size of an exported chart, the title goes above the plot
+const IMAGE_WIDTH: u32 = 800;
+const IMAGE_PLOT_HEIGHT: u32 = 240;
+const IMAGE_TITLE_HEIGHT: u32 = 32;
+
+// the chart drawn offscreen as a png, in light colors whatever the theme, with the title, the
+// range and the latest value across the top
+pub fn png(title: &str, points: Vec<Point>, markers: Vec<Event>, range: Range, max: f32, unit: &'static str) -> Result<Vec<u8>, String> {
+    use tiny_skia::{Color as Fill, Paint, PathBuilder, Pixmap, Rect, Stroke as Line, Transform};
+
+    let chart = Chart { points, markers, range, max, unit };
+    let mut pixmap = Pixmap::new(IMAGE_WIDTH, IMAGE_TITLE_HEIGHT + IMAGE_PLOT_HEIGHT).ok_or("the chart image has no size")?;
+    pixmap.fill(Fill::WHITE);
+    let paint = |r: u8, g: u8, b: u8| {
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(r, g, b, 255);
+        paint.anti_alias = true;
+        paint
+    };
+    let plot = Transform::from_translate(0.0, IMAGE_TITLE_HEIGHT as f32);
+    let size = Size::new(IMAGE_WIDTH as f32, IMAGE_PLOT_HEIGHT as f32);
+    if let Some(background) = Rect::from_xywh(0.0, 0.0, size.width, size.height) {
+        pixmap.fill_rect(background, &paint(240, 240, 240), plot, None);
+    }
+
+    let mut line = PathBuilder::new();
+    for (index, point) in chart.points.iter().enumerate() {
+        let position = chart.position(point, size);
+        if index == 0 {
+            line.move_to(position.x, position.y);
+        } else {
+            line.line_to(position.x, position.y);
+        }
+    }
+    if let Some(line) = line.finish() {
+        pixmap.stroke_path(&line, &paint(52, 101, 164), &Line { width: 1.5, ..Line::default() }, plot, None);
+    }
+    for marker in &chart.markers {
+        let x = chart.x(marker.time, size.width);
+        let color = match marker.kind {
+            EventKind::Kill => paint(204, 0, 0),
+            _ => paint(117, 80, 123),
+        };
+        if let Some(rule) = Rect::from_xywh(x, 0.0, 1.0, size.height) {
+            pixmap.fill_rect(rule, &color, plot, None);
+        }
+        if let Some(dot) = PathBuilder::from_circle(x, 3.0, 3.0) {
+            pixmap.fill_path(&dot, &color, tiny_skia::FillRule::Winding, plot, None);
+        }
+    }
+
+    let latest = chart.points.last().map(|point| format!(" · {}{} at {}", format::decimal(f64::from(point.value), 1), unit, format::time(&point.time))).unwrap_or_default();
+    draw_text(&mut pixmap, &format!("{} · last {}{}", title, range.label(), latest));
+    pixmap.encode_png().map_err(|err| format!("failed to encode the chart: {}", err))
+}
+
+// one line of dark text in the title strip
+fn draw_text(pixmap: &mut tiny_skia::Pixmap, text: &str) {
+    use cosmic_text::{Attrs, Buffer, Color as Ink, FontSystem, Metrics, Shaping, SwashCache};
+
+    let mut fonts = FontSystem::new();
+    let mut cache = SwashCache::new();
+    let mut buffer = Buffer::new(&mut fonts, Metrics::new(16.0, 20.0));
+    buffer.set_size(&mut fonts, IMAGE_WIDTH as f32 - 16.0, IMAGE_TITLE_HEIGHT as f32);
+    buffer.set_text(&mut fonts, text, Attrs::new(), Shaping::Advanced);
+    buffer.shape_until_scroll(&mut fonts);
+    buffer.draw(&mut fonts, &mut cache, Ink::rgb(30, 30, 30), |x, y, width, height, ink| {
+        let mut paint = tiny_skia::Paint::default();
+        paint.set_color_rgba8(ink.r(), ink.g(), ink.b(), ink.a());
+        if let Some(rect) = tiny_skia::Rect::from_xywh(x as f32 + 8.0, y as f32 + 6.0, width as f32, height as f32) {
+            pixmap.fill_rect(rect, &paint, tiny_skia::Transform::identity(), None);
+        }
+    });
+}
+
+// where a saved chart goes, pictures then home then the working directory
+pub fn image_path(name: &str, now: DateTime<Local>) -> PathBuf {
+    let dir = dirs::picture_dir().or_else(dirs::home_dir).unwrap_or_default();
+    dir.join(format!("conborli-{}-{}.png", name.to_lowercase().replace(' ', "-"), now.format("%Y%m%d-%H%M%S")))
+}
+
+pub fn save_png(png: &[u8], path: &FilePath) -> Result<(), String> {
+    std::fs::write(path, png).map_err(|err| format!("failed to save {}: {}", path.display(), err))
+}
+
+// puts the png on the clipboard through the platform's tool, iced's clipboard only takes
+// text: wl-copy or xclip on linux, osascript on macOS and powershell on windows. the last two
+// read it back from a temporary file
+pub fn copy_png(png: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    let file = std::env::temp_dir().join(format!("conborli-chart-{}.png", std::process::id()));
+    let mut command = if cfg!(target_os = "windows") {
+        save_png(png, &file)?;
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Windows.Forms, System.Drawing; [System.Windows.Forms.Clipboard]::SetImage([System.Drawing.Image]::FromFile($env:CONBORLI_CHART))",
+        ]);
+        command.env("CONBORLI_CHART", &file);
+        command
+    } else if cfg!(target_os = "macos") {
+        save_png(png, &file)?;
+        let mut command = Command::new("osascript");
+        command.args(["-e", "set the clipboard to (read (POSIX file (system attribute \"CONBORLI_CHART\")) as «class PNGf»)"]);
+        command.env("CONBORLI_CHART", &file);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", "if [ -n \"$WAYLAND_DISPLAY\" ]; then wl-copy --type image/png; else xclip -selection clipboard -t image/png; fi"]);
+        command
+    };
+    let mut child = command.stdin(Stdio::piped()).spawn().map_err(|err| format!("failed to copy the chart: {}", err))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(png).map_err(|err| format!("failed to copy the chart: {}", err))?;
+    }
+    // xclip stays to serve the clipboard, so it is reaped in the background
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charts_export_as_png() {
+        let start = Local::now();
+        let points: Vec<Point> = (0..30).map(|second| Point { time: start + chrono::Duration::seconds(second), value: second as f32 * 3.0 }).collect();
+        let marker = Event { time: start + chrono::Duration::seconds(10), kind: EventKind::Kill, text: String::from("You killed a (1)") };
+        let png = png("CPU", points, vec![marker], Range::Minute, 100.0, "%").unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        assert!(image_path("CPU package", start).to_string_lossy().contains("conborli-cpu-package-"));
+    }
+}
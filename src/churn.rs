@@ -0,0 +1,147 @@
+// process churn - how many processes started and exited since launch and in the last minute,
+// and a log of each spawn and exit, from the pid sets of consecutive refreshes
+use chrono::{DateTime, Local};
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::format;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// events kept in the log
+const LOG_LENGTH: usize = 500;
+
+// window of the recent counts
+const RECENT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Started,
+    Exited,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChurnEvent {
+    pub at: DateTime<Local>,
+    pub change: Change,
+    pub pid: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Churn {
+    // pids and names of the last table, empty until the first one arrives
+    last: HashMap<u32, String>,
+    seeded: bool,
+    pub started: u64,
+    pub exited: u64,
+    // when the changes of the last minute happened, oldest first
+    recent: VecDeque<(Instant, Change)>,
+    // newest last
+    pub log: VecDeque<ChurnEvent>,
+}
+
+impl Churn {
+    // compares the table with the previous one, the first table only sets the baseline
+    pub fn record(&mut self, processes: &[ProcessInfo], now: Instant) {
+        let current: HashMap<u32, String> = processes.iter().map(|process| (process.pid, process.name.clone())).collect();
+        if self.seeded {
+            let mut events: Vec<(Change, u32, &String)> = self
+                .last
+                .iter()
+                .filter(|(pid, _)| !current.contains_key(pid))
+                .map(|(&pid, name)| (Change::Exited, pid, name))
+                .chain(
+                    current
+                        .iter()
+                        .filter(|(pid, _)| !self.last.contains_key(pid))
+                        .map(|(&pid, name)| (Change::Started, pid, name)),
+                )
+                .collect();
+            // exits before starts, each by pid, so a refresh reads in a stable order
+            events.sort_by_key(|&(change, pid, _)| (change == Change::Started, pid));
+            let at = Local::now();
+            for (change, pid, name) in events {
+                match change {
+                    Change::Started => self.started += 1,
+                    Change::Exited => self.exited += 1,
+                }
+                self.recent.push_back((now, change));
+                self.log.push_back(ChurnEvent { at, change, pid, name: name.clone() });
+            }
+            while self.log.len() > LOG_LENGTH {
+                self.log.pop_front();
+            }
+        }
+        while self.recent.front().is_some_and(|&(at, _)| now.duration_since(at) > RECENT) {
+            self.recent.pop_front();
+        }
+        self.last = current;
+        self.seeded = true;
+    }
+
+    // (started, exited) within the last minute
+    pub fn last_minute(&self) -> (usize, usize) {
+        let started = self.recent.iter().filter(|(_, change)| *change == Change::Started).count();
+        (started, self.recent.len() - started)
+    }
+}
+
+// construct the churn line above the process table, with the event log when it is open
+pub fn view(churn: &Churn, log_open: bool) -> Element<'_, Message> {
+    let (started, exited) = churn.last_minute();
+    let summary = Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(format!(
+            "{} processes started and {} exited since launch, {} and {} in the last minute",
+            churn.started, churn.exited, started, exited
+        )))
+        .push(Button::new(if log_open { "Hide events" } else { "Show events" }).on_press(Message::ToggleChurnLog));
+    if !log_open {
+        return summary.into();
+    }
+    // newest first
+    let log = churn.log.iter().rev().fold(Column::new().spacing(2), |column, event| {
+        let change = match event.change {
+            Change::Started => "started",
+            Change::Exited => "exited",
+        };
+        column.push(Text::new(format!("{}  {} {} ({})", format::time(&event.at), change, event.name, event.pid)))
+    });
+    Column::new()
+        .spacing(5)
+        .push(summary)
+        .push(Scrollable::new(log).height(Length::Fixed(150.0)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn counts_starts_and_exits_after_the_first_table() {
+        let mut churn = Churn::default();
+        let now = Instant::now();
+        let table = |pids: &[u32]| pids.iter().map(|&pid| mock::process(pid, "p", "u", 1, 0.0)).collect::<Vec<_>>();
+        churn.record(&table(&[1, 2, 3]), now);
+        assert_eq!((churn.started, churn.exited), (0, 0));
+        churn.record(&table(&[1, 3, 4, 5]), now + Duration::from_secs(1));
+        assert_eq!((churn.started, churn.exited), (2, 1));
+        let log: Vec<(Change, u32)> = churn.log.iter().map(|event| (event.change, event.pid)).collect();
+        assert_eq!(log, vec![(Change::Exited, 2), (Change::Started, 4), (Change::Started, 5)]);
+        assert_eq!(churn.last_minute(), (2, 1));
+        // the minute has passed
+        churn.record(&table(&[1, 3, 4, 5]), now + Duration::from_secs(62));
+        assert_eq!(churn.last_minute(), (0, 0));
+        assert_eq!(churn.started, 2);
+    }
+}
@@ -0,0 +1,199 @@
+// command-line flags - startup overrides for this run, not written back to the config
+use clap::Parser;
+
+use crate::accent::ThemeChoice;
+use crate::config::Config;
+use crate::links::Link;
+use crate::logging::LogLevel;
+use crate::process::SortColumn;
+#[cfg(feature = "sim")]
+use crate::sim::SimSpec;
+use crate::Tab;
+
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "conborli", version, about = "A task manager for the desktop")]
+pub struct Cli {
+    /// Column to sort the process table by, e.g. cpu, memory, name, pid
+    #[arg(long, value_name = "COLUMN", value_parser = parse_column)]
+    pub sort: Option<SortColumn>,
+    /// Sort in descending order
+    #[arg(long)]
+    pub desc: bool,
+    /// Seconds between process table refreshes
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+    /// Text typed into the process search box
+    #[arg(long, value_name = "TEXT")]
+    pub filter: Option<String>,
+    /// Base color theme
+    #[arg(long, value_enum)]
+    pub theme: Option<ThemeChoice>,
+    /// Tab shown at startup, e.g. processes, performance, network
+    #[arg(long, value_parser = parse_tab)]
+    pub tab: Option<Tab>,
+    /// View to open, e.g. "processes?sort=cpu&order=desc&filter=node", also in a running window
+    #[arg(long, value_name = "LINK", value_parser = Link::parse)]
+    pub open: Option<Link>,
+    /// Show the process table in the terminal instead of opening a window
+    #[arg(long)]
+    pub tui: bool,
+    /// Start hidden in the tray, or minimized without one
+    #[arg(long)]
+    pub minimized: bool,
+    /// Open another window even if conborli is already running
+    #[arg(long)]
+    pub new_instance: bool,
+    /// Keep collecting history and checking alert rules in the background without a window
+    #[arg(long, conflicts_with_all = ["tui", "stop_daemon"])]
+    pub daemon: bool,
+    /// Stop the background monitor started with --daemon
+    #[arg(long)]
+    pub stop_daemon: bool,
+    /// Only observe: refuse killing, renicing, suspending and other changes to processes
+    #[arg(long)]
+    pub read_only: bool,
+    /// Show how long collecting, sorting and drawing the process table took in the footer
+    #[arg(long)]
+    pub debug_timings: bool,
+    /// Log messages of this level and above to the logs folder of the config directory
+    #[arg(long, value_enum, value_name = "LEVEL", default_value_t)]
+    pub log_level: LogLevel,
+    /// Time full and targeted refreshes, sorting and filtering over N rounds and print json
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "20", hide = true)]
+    pub bench: Option<usize>,
+    /// Serve the http api on this address, e.g. 127.0.0.1:7878
+    #[cfg(feature = "server")]
+    #[arg(long, visible_alias = "listen", value_name = "ADDR")]
+    pub serve: Option<String>,
+    /// Require this bearer token on every api request, needed unless ADDR is a loopback address.
+    /// Without it only local clients get in, not web pages or other host names
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "TOKEN", requires = "serve")]
+    pub token: Option<String>,
+    /// Also serve prometheus metrics on /metrics, for the top N processes by cpu and by memory
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10", requires = "serve")]
+    pub metrics: Option<usize>,
+    /// Only serve the api, without opening a window, so other machines can show this one
+    #[cfg(feature = "server")]
+    #[arg(long, visible_alias = "agent", requires = "serve")]
+    pub headless: bool,
+    /// Show a made up, reproducible process table, e.g. "seed=7,processes=200,spawn=2,exit=0.01,period=30"
+    #[cfg(feature = "sim")]
+    #[arg(long, value_name = "SPEC", num_args = 0..=1, default_missing_value = "", value_parser = SimSpec::parse)]
+    pub simulate: Option<SimSpec>,
+}
+
+// config values a flag replaced, put back before saving unless they were changed in the app
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    interval: Option<(u64, u64)>,
+    theme: Option<(ThemeChoice, ThemeChoice)>,
+    // theme file the flag switched off
+    custom_theme: Option<String>,
+    // read-only mode came from the flag, the settings tab can't turn it off then
+    read_only: Option<bool>,
+}
+
+impl Cli {
+    // applies the flags that live in the config, remembering what they replaced
+    pub fn apply(&self, config: &mut Config) -> Overrides {
+        let mut overrides = Overrides::default();
+        if let Some(interval) = self.interval {
+            overrides.interval = Some((config.refresh_intervals.processes, interval));
+            config.refresh_intervals.processes = interval;
+        }
+        if let Some(theme) = self.theme {
+            overrides.theme = Some((config.theme, theme));
+            overrides.custom_theme = config.custom_theme.take();
+            config.theme = theme;
+        }
+        if self.read_only {
+            overrides.read_only = Some(config.read_only);
+            config.read_only = true;
+        }
+        overrides
+    }
+}
+
+impl Overrides {
+    pub fn read_only(&self) -> bool {
+        self.read_only.is_some()
+    }
+
+    pub fn restore(&self, config: &mut Config) {
+        if let Some(original) = self.read_only {
+            config.read_only = original;
+        }
+        if let Some((original, applied)) = self.interval {
+            if config.refresh_intervals.processes == applied {
+                config.refresh_intervals.processes = original;
+            }
+        }
+        if let Some((original, applied)) = self.theme {
+            if config.theme == applied && config.custom_theme.is_none() {
+                config.theme = original;
+                config.custom_theme = self.custom_theme.clone();
+            }
+        }
+    }
+}
+
+// lowercased variant names with spaces, dashes and underscores ignored, e.g. "cpu-time"
+pub fn normalize(text: &str) -> String {
+    text.chars().filter(|c| !matches!(c, ' ' | '-' | '_')).flat_map(char::to_lowercase).collect()
+}
+
+pub fn parse_column(text: &str) -> Result<SortColumn, String> {
+    let wanted = match normalize(text).as_str() {
+        "mem" => String::from("memory"),
+        other => other.to_string(),
+    };
+    let columns = SortColumn::with_plugins();
+    columns
+        .iter()
+        .copied()
+        .find(|column| normalize(&column.key()) == wanted)
+        .ok_or_else(|| {
+            let names: Vec<String> = columns.iter().map(|column| column.key().to_lowercase()).collect();
+            format!("unknown column, expected one of {}", names.join(", "))
+        })
+}
+
+pub fn parse_tab(text: &str) -> Result<Tab, String> {
+    Tab::ALL
+        .into_iter()
+        .find(|tab| normalize(&format!("{:?}", tab)) == normalize(text))
+        .ok_or_else(|| {
+            let names: Vec<String> = Tab::ALL.iter().map(|tab| format!("{:?}", tab).to_lowercase()).collect();
+            format!("unknown tab, expected one of {}", names.join(", "))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_documented_example() {
+        let cli = Cli::try_parse_from([
+            "conborli", "--sort", "cpu", "--desc", "--interval", "2", "--filter", "chrome", "--theme", "light", "--tab", "performance",
+        ])
+        .unwrap();
+        assert_eq!(cli.sort, Some(SortColumn::Cpu));
+        assert!(cli.desc);
+        assert_eq!(cli.filter.as_deref(), Some("chrome"));
+        assert_eq!(cli.tab, Some(Tab::Performance));
+        let mut config = Config::default();
+        let overrides = cli.apply(&mut config);
+        assert_eq!((config.refresh_intervals.processes, config.theme), (2, ThemeChoice::Light));
+        overrides.restore(&mut config);
+        assert_eq!(config.refresh_intervals.processes, Config::default().refresh_intervals.processes);
+        assert_eq!(config.theme, ThemeChoice::Dark);
+        assert!(Cli::try_parse_from(["conborli", "--sort", "nope"]).is_err());
+        assert!(Cli::try_parse_from(["conborli", "--interval", "0"]).is_err());
+        let cli = Cli::try_parse_from(["conborli", "--open", "details?sort=memory&pid=1"]).unwrap();
+        assert_eq!(cli.open.map(|link| (link.tab, link.pid)), Some((Tab::Details, Some(1))));
+        assert!(Cli::try_parse_from(["conborli", "--open", "details?sort=nope"]).is_err());
+    }
+}
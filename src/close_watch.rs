@@ -0,0 +1,91 @@
+// one process read every 250 ms into a panel above the footer, for following a pid closely
+// while the table stays on its own interval. it keeps its own sysinfo system, so the cpu
+// deltas of the table aren't shortened by the fast reads
+use iced::{
+    alignment,
+    widget::{Button, Row, Text},
+    Element,
+};
+use sysinfo::{Pid, System};
+
+use std::time::Duration;
+
+use crate::format;
+use crate::Message;
+
+pub const INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug)]
+pub struct CloseWatch {
+    pub pid: u32,
+    pub name: String,
+    system: System,
+    // latest readings, cpu in percent of one core
+    pub cpu: f32,
+    pub memory: u64,
+    // highest cpu since the watch started
+    pub peak_cpu: f32,
+    // the process exited, the last readings stay shown
+    pub exited: bool,
+}
+
+impl CloseWatch {
+    // takes the first reading right away, the first cpu value needs a previous one
+    pub fn new(pid: u32, name: String) -> CloseWatch {
+        let mut watch = CloseWatch { pid, name, system: System::new(), cpu: 0.0, memory: 0, peak_cpu: 0.0, exited: false };
+        watch.sample();
+        watch
+    }
+
+    pub fn sample(&mut self) {
+        if self.exited {
+            return;
+        }
+        let pid = Pid::from_u32(self.pid);
+        self.system.refresh_cpu_usage();
+        if !self.system.refresh_process(pid) {
+            self.exited = true;
+            return;
+        }
+        if let Some(process) = self.system.process(pid) {
+            self.cpu = process.cpu_usage();
+            self.memory = process.memory();
+            self.peak_cpu = self.peak_cpu.max(self.cpu);
+        }
+    }
+}
+
+// the readouts with a stop button, cpu scaled like the table's
+pub fn view(watch: &CloseWatch, whole_machine: bool) -> Element<'_, Message> {
+    let scale = if whole_machine { watch.system.cpus().len().max(1) as f32 } else { 1.0 };
+    let readout = if watch.exited {
+        format!("{} ({}) exited", watch.name, watch.pid)
+    } else {
+        format!(
+            "Watching {} ({}) · CPU {}% (peak {}%) · Memory {}",
+            watch.name,
+            watch.pid,
+            format::decimal(f64::from(watch.cpu / scale), 1),
+            format::decimal(f64::from(watch.peak_cpu / scale), 1),
+            format::format_bytes(watch.memory as f64)
+        )
+    };
+    Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(readout))
+        .push(Button::new("Stop").on_press(Message::StopCloseWatch))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_running_process_and_notices_a_missing_one() {
+        let own = CloseWatch::new(std::process::id(), String::from("tests"));
+        assert!(!own.exited && own.memory > 0);
+        assert!(CloseWatch::new(u32::MAX, String::from("gone")).exited);
+    }
+}
@@ -0,0 +1,110 @@
+// shared collector - one collection pass as every frontend does it, and the latest process
+// table and machine totals published for the api server
+//
+// the gui publishes what it collects on every process refresh, in headless mode the server
+// collects on its own instead
+use serde::Serialize;
+use sysinfo::{System, Users};
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use crate::config::Config;
+use crate::process::{self, ProcessInfo};
+use crate::provider::ProcessProvider;
+
+// collects, scales to the whole machine if configured and smooths the cpu readings, the
+// caller refreshes cpu and memory totals when it needs them
+pub fn collect(
+    provider: &mut dyn ProcessProvider,
+    system: &mut System,
+    users: &Users,
+    cpu_history: &mut HashMap<u32, f32>,
+    config: &Config,
+) -> Vec<ProcessInfo> {
+    let mut processes = provider.collect(system, users);
+    if config.cpu_of_whole_machine {
+        process::scale_cpu(&mut processes, system.cpus().len());
+    }
+    process::smooth_cpu(&mut processes, cpu_history, config.cpu_smoothing);
+    processes
+}
+
+// takes a first cpu reading and waits, cpu usage needs a previous reading to compare against.
+// blocks, so only for frontends that have nothing to show before the first collect
+pub fn warm_up(system: &mut System) {
+    system.refresh_cpu();
+    system.refresh_processes();
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SystemSummary {
+    // cpu percent of the whole machine and of each core
+    pub cpu: f32,
+    pub cores: Vec<f32>,
+    // bytes
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    // seconds since boot
+    pub uptime: u64,
+    pub processes: usize,
+}
+
+impl SystemSummary {
+    pub fn read(system: &System, processes: usize) -> SystemSummary {
+        SystemSummary {
+            cpu: system.global_cpu_info().cpu_usage(),
+            cores: system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            memory_used: system.used_memory(),
+            memory_total: system.total_memory(),
+            swap_used: system.used_swap(),
+            swap_total: system.total_swap(),
+            uptime: System::uptime(),
+            processes,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Published {
+    pub system: SystemSummary,
+    pub processes: Vec<ProcessInfo>,
+    // bumped on every publish, lets readers tell a new table from one they have seen
+    pub generation: u64,
+    // what the api's kills are checked against, kept current by the frontend
+    pub read_only: bool,
+    pub critical: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Shared(Arc<RwLock<Published>>);
+
+impl Shared {
+    pub fn publish(&self, system: &System, processes: &[ProcessInfo]) {
+        if let Ok(mut published) = self.0.write() {
+            published.system = SystemSummary::read(system, processes.len());
+            published.processes = processes.to_vec();
+            published.generation += 1;
+        }
+    }
+
+    // read-only mode and critical names as the frontend has them now
+    pub fn set_policy(&self, read_only: bool, critical: &[String]) {
+        if let Ok(mut published) = self.0.write() {
+            if published.read_only != read_only || published.critical != critical {
+                published.read_only = read_only;
+                published.critical = critical.to_vec();
+            }
+        }
+    }
+
+    // a poisoned lock only means a publisher panicked mid-write, the data is still usable
+    #[cfg(feature = "server")]
+    pub fn read(&self) -> std::sync::RwLockReadGuard<'_, Published> {
+        self.0.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,444 @@
+// column layout of the process table - which columns are shown and how each cell renders
+use iced::{
+    alignment,
+    theme,
+    widget::{tooltip, Button, Checkbox, Column, Row, Text, TextInput, Tooltip},
+    Alignment, Color, Element, Length,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::bar_cell::{self, Bars};
+use crate::format::{self, format_duration};
+use crate::icons::Icon;
+use crate::limits::{self, Limit};
+use crate::notes::{self, Note};
+use crate::process::{ProcessInfo, SortColumn};
+use crate::procfs::Counters;
+use crate::Message;
+
+// widest a column can be made, relative to the narrowest
+pub const MAX_PORTION: u16 = 8;
+
+// relative width of the column until the user picks another
+pub fn portion(column: SortColumn) -> u16 {
+    match column {
+        SortColumn::Name | SortColumn::Container => 2,
+        SortColumn::Plugin(id) => id.provider().portion(),
+        _ => 1,
+    }
+}
+
+// relative widths the user set in the column picker, keyed by SortColumn::key. the header
+// and every row take their widths from here so they stay lined up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ColumnWidths(BTreeMap<String, u16>);
+
+impl ColumnWidths {
+    pub fn portion(&self, column: SortColumn) -> u16 {
+        self.0.get(&column.key()).map_or_else(|| portion(column), |&portion| portion.clamp(1, MAX_PORTION))
+    }
+
+    // only widths that differ from the default are stored
+    pub fn set(&mut self, column: SortColumn, width: u16) {
+        let width = width.clamp(1, MAX_PORTION);
+        if width == portion(column) {
+            self.0.remove(&column.key());
+        } else {
+            self.0.insert(column.key(), width);
+        }
+    }
+}
+
+// how the numbers of a column are written, picked per column in the column picker. the table,
+// the totals row, copied rows and the terminal ui all format through `Formatter`, so they agree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberFormat {
+    Megabytes,
+    Gibibytes,
+    // share of the machine's memory
+    Percent,
+    OneDecimal,
+    Whole,
+    Plain,
+    // padded to seven digits, the longest linux pid
+    ZeroPadded,
+}
+
+impl NumberFormat {
+    // the formats a column offers, its default first, none for columns without a choice
+    pub fn options(column: SortColumn) -> &'static [NumberFormat] {
+        use NumberFormat::*;
+        match column {
+            SortColumn::Memory | SortColumn::VirtualMemory | SortColumn::SharedMemory | SortColumn::Swap => &[Megabytes, Gibibytes, Percent],
+            SortColumn::GpuMemory => &[Megabytes, Gibibytes],
+            SortColumn::Cpu | SortColumn::Gpu => &[OneDecimal, Whole],
+            SortColumn::Pid | SortColumn::Ppid => &[Plain, ZeroPadded],
+            _ => &[],
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NumberFormat::Megabytes => "MB",
+            NumberFormat::Gibibytes => "GiB",
+            NumberFormat::Percent => "%",
+            NumberFormat::OneDecimal => "0.0",
+            NumberFormat::Whole => "0",
+            NumberFormat::Plain => "123",
+            NumberFormat::ZeroPadded => "0000123",
+        }
+    }
+}
+
+// the number formats the user picked, keyed by SortColumn::key like the widths
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ColumnFormats(BTreeMap<String, NumberFormat>);
+
+impl ColumnFormats {
+    // None for columns without a choice
+    pub fn get(&self, column: SortColumn) -> Option<NumberFormat> {
+        let options = NumberFormat::options(column);
+        let picked = self.0.get(&column.key()).filter(|format| options.contains(format));
+        picked.or(options.first()).copied()
+    }
+
+    // only formats that differ from the default are stored
+    pub fn set(&mut self, column: SortColumn, format: NumberFormat) {
+        match NumberFormat::options(column) {
+            [default, ..] if *default == format => {
+                self.0.remove(&column.key());
+            }
+            options if options.contains(&format) => {
+                self.0.insert(column.key(), format);
+            }
+            _ => {}
+        }
+    }
+}
+
+// writes cell values in the formats picked for their columns
+#[derive(Debug, Clone, Copy)]
+pub struct Formatter<'a> {
+    pub formats: &'a ColumnFormats,
+    // the machine's memory in MB for percentages, 0 when unknown (a remote host's), which
+    // writes them in MB
+    pub total_memory: u64,
+}
+
+impl Formatter<'_> {
+    fn memory_format(&self, column: SortColumn) -> Option<NumberFormat> {
+        match self.formats.get(column) {
+            Some(NumberFormat::Percent) if self.total_memory == 0 => Some(NumberFormat::Megabytes),
+            format => format,
+        }
+    }
+
+    // MB as the column's unit
+    pub fn memory(&self, column: SortColumn, megabytes: u64) -> String {
+        match self.memory_format(column) {
+            Some(NumberFormat::Gibibytes) => format::decimal(megabytes as f64 / 1024.0, 2),
+            Some(NumberFormat::Percent) => format::decimal(megabytes as f64 / self.total_memory as f64 * 100.0, 1),
+            _ => format::integer(megabytes),
+        }
+    }
+
+    pub fn percent(&self, column: SortColumn, value: f32) -> String {
+        let places = if self.formats.get(column) == Some(NumberFormat::Whole) { 0 } else { 1 };
+        format::decimal(f64::from(value), places)
+    }
+
+    pub fn pid(&self, column: SortColumn, pid: u32) -> String {
+        if self.formats.get(column) == Some(NumberFormat::ZeroPadded) {
+            format!("{:07}", pid)
+        } else {
+            pid.to_string()
+        }
+    }
+
+    // the column's title with the unit it is written in
+    pub fn title(&self, column: SortColumn) -> String {
+        let title = column.title();
+        match self.memory_format(column) {
+            Some(NumberFormat::Gibibytes) => title.replace("(MB)", "(GiB)"),
+            Some(NumberFormat::Percent) => title.replace("(MB)", "(%)"),
+            _ => title.to_string(),
+        }
+    }
+}
+
+// a column dragged onto another takes its place, the ones between shift over
+pub fn move_column(columns: &mut Vec<SortColumn>, dragged: SortColumn, target: SortColumn) {
+    let (Some(from), Some(to)) = (columns.iter().position(|&c| c == dragged), columns.iter().position(|&c| c == target)) else {
+        return;
+    };
+    let column = columns.remove(from);
+    columns.insert(to, column);
+}
+
+// header label with ▲ or ▼ on the column the table is sorted by
+pub fn header_label(column: SortColumn, keys: &[(SortColumn, bool)], formatter: Formatter) -> String {
+    let title = formatter.title(column);
+    let Some(position) = keys.iter().position(|&(key, _)| key == column) else {
+        return title;
+    };
+    let arrow = if keys[position].1 { "▲" } else { "▼" };
+    // the place in the sort chain, only once there is more than one key
+    if keys.len() > 1 {
+        format!("{} {}{}", title, arrow, position + 1)
+    } else {
+        format!("{} {}", title, arrow)
+    }
+}
+
+// text value of one column, used by the table and when copying rows
+pub fn cell_text(process: &ProcessInfo, column: SortColumn, formatter: Formatter) -> String {
+    if process.access_denied && !matches!(column, SortColumn::Pid | SortColumn::Ppid | SortColumn::Name | SortColumn::User) {
+        return String::from("–");
+    }
+    match column {
+        SortColumn::Pid => formatter.pid(column, process.pid),
+        SortColumn::Ppid => process.ppid.map(|ppid| formatter.pid(column, ppid)).unwrap_or_default(),
+        SortColumn::Name => process.name.clone(),
+        SortColumn::User => process.user.clone(),
+        SortColumn::Memory => formatter.memory(column, process.memory),
+        SortColumn::VirtualMemory => formatter.memory(column, process.virtual_memory),
+        SortColumn::SharedMemory => process.shared_memory.map(|shared| formatter.memory(column, shared)).unwrap_or_else(|| String::from("n/a")),
+        SortColumn::Swap => process.swap.map(|swap| formatter.memory(column, swap)).unwrap_or_else(|| String::from("n/a")),
+        SortColumn::OomScore => process.oom_score.map(|score| score.to_string()).unwrap_or_else(|| String::from("n/a")),
+        SortColumn::Cpu => formatter.percent(column, process.cpu),
+        SortColumn::Priority => process.nice.map(|nice| nice.to_string()).unwrap_or_else(|| String::from("n/a")),
+        SortColumn::MemoryGrowth => format::signed_integer(process.memory_growth),
+        SortColumn::CpuTrend => format::signed_decimal(f64::from(process.cpu_trend), 1),
+        SortColumn::CpuDelta => format::signed_decimal(f64::from(process.cpu_delta), 1),
+        SortColumn::MemoryDelta => format::signed_integer(process.memory_delta),
+        SortColumn::CpuTime => process.cpu_time.map(format_duration).unwrap_or_else(|| String::from("n/a")),
+        SortColumn::Uptime => format_duration(process.uptime),
+        SortColumn::Gpu => formatter.percent(column, process.gpu),
+        SortColumn::GpuMemory => formatter.memory(column, process.gpu_memory),
+        SortColumn::Container => process.container.clone().unwrap_or_default(),
+        SortColumn::GrowthRate => format::decimal(f64::from(process.growth_rate), 1),
+        SortColumn::VoluntarySwitches => counter(process, |counters| counters.voluntary_switches),
+        SortColumn::InvoluntarySwitches => counter(process, |counters| counters.involuntary_switches),
+        SortColumn::Threads => counter(process, |counters| u64::from(counters.threads)),
+        SortColumn::IoWait => counter(process, |counters| counters.io_wait),
+        SortColumn::RunQueueWait => counter(process, |counters| counters.run_queue_wait),
+        SortColumn::WindowTitle => process.window_title.clone().unwrap_or_default(),
+        SortColumn::Description => process.description.clone().unwrap_or_default(),
+        SortColumn::Energy => process.energy.map(|energy| format::decimal(f64::from(energy), 0)).unwrap_or_else(|| String::from("n/a")),
+        SortColumn::AppNap => String::from(if process.napping == Some(true) { "Napping" } else { "" }),
+        SortColumn::Plugin(id) => process.custom.get(id.as_str()).map(ToString::to_string).unwrap_or_default(),
+    }
+}
+
+fn counter(process: &ProcessInfo, value: impl Fn(&Counters) -> u64) -> String {
+    process.counters.as_ref().map(|counters| format::integer(value(counters))).unwrap_or_else(|| String::from("n/a"))
+}
+
+// renders the value of one column for a process
+// `matched` are the char indices of the name the search matched, drawn in `color`
+pub fn cell<'a>(
+    process: &'a ProcessInfo,
+    column: SortColumn,
+    widths: &ColumnWidths,
+    formatter: Formatter,
+    name: &NameStyle,
+    bars: Option<Bars>,
+) -> Element<'a, Message> {
+    let width = Length::FillPortion(widths.portion(column));
+    if let Some((bars, fraction)) = bars.and_then(|bars| Some((bars, bars.fraction(process, column)?))) {
+        return bar_cell::view(fraction, cell_text(process, column, formatter), width, bars);
+    }
+    match column {
+        SortColumn::Priority => priority_cell(process, width),
+        SortColumn::Ppid => parent_cell(process, width, cell_text(process, column, formatter)),
+        SortColumn::Name => name_cell(process, width, name),
+        _ => Text::new(cell_text(process, column, formatter)).width(width).into(),
+    }
+}
+
+// aggregate of a column over the shown rows: memory sums, cpu and gpu average, blank for
+// columns that don't add up
+pub fn total_text(processes: &[&ProcessInfo], column: SortColumn, formatter: Formatter) -> String {
+    let average = |value: fn(&ProcessInfo) -> f32| {
+        let sum: f32 = processes.iter().map(|process| value(process)).sum();
+        format!("avg {}", formatter.percent(column, if processes.is_empty() { 0.0 } else { sum / processes.len() as f32 }))
+    };
+    let sum = |value: fn(&ProcessInfo) -> u64| format!("Σ {}", formatter.memory(column, processes.iter().map(|process| value(process)).sum::<u64>()));
+    match column {
+        SortColumn::Name => format!("{} processes", processes.len()),
+        SortColumn::Memory => sum(|process| process.memory),
+        SortColumn::VirtualMemory => sum(|process| process.virtual_memory),
+        SortColumn::SharedMemory => sum(|process| process.shared_memory.unwrap_or_default()),
+        SortColumn::Swap => sum(|process| process.swap.unwrap_or_default()),
+        SortColumn::GpuMemory => sum(|process| process.gpu_memory),
+        SortColumn::Cpu => average(|process| process.cpu),
+        SortColumn::Gpu => average(|process| process.gpu),
+        _ => String::new(),
+    }
+}
+
+// the totals of the shown rows, lined up under the header
+pub fn totals_row<'a>(processes: &[&ProcessInfo], columns: &[SortColumn], widths: &ColumnWidths, formatter: Formatter, spacing: u16) -> Element<'a, Message> {
+    columns
+        .iter()
+        .fold(Row::new().spacing(spacing), |row, &column| {
+            row.push(Text::new(total_text(processes, column, formatter)).width(Length::FillPortion(widths.portion(column))))
+        })
+        .into()
+}
+
+// the visible columns of a process as one tab separated line
+pub fn row_text(process: &ProcessInfo, columns: &[SortColumn], formatter: Formatter) -> String {
+    columns
+        .iter()
+        .map(|&column| cell_text(process, column, formatter))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+// how a row's name is drawn besides its text
+pub struct NameStyle<'a> {
+    // chars the search matched, drawn in the highlight color
+    pub matched: &'a [usize],
+    pub highlight: Color,
+    pub icon: Option<&'a Icon>,
+    // the user's tag and note on the program, see notes.rs
+    pub note: Option<&'a Note>,
+    // the cpu or memory cap put on it, see limits.rs
+    pub limit: Option<&'a Limit>,
+}
+
+// name with its icon and a shield on processes running as root or SYSTEM, hovering shows
+// where it came from
+
+fn name_cell<'a>(process: &'a ProcessInfo, width: Length, style: &NameStyle) -> Element<'a, Message> {
+    let mut name = Row::new().width(width).spacing(4).align_items(Alignment::Center);
+    if let Some(icon) = style.icon {
+        name = name.push(icon.view());
+    }
+    if process.elevated {
+        name = name.push(Text::new("🛡"));
+    }
+    let mut text = Row::new();
+    for (segment, highlighted) in segments(&process.name, style.matched) {
+        text = text.push(if highlighted { Text::new(segment).style(theme::Text::Color(style.highlight)) } else { Text::new(segment) });
+    }
+    name = name.push(text);
+    if process.not_responding {
+        name = name.push(Text::new("(Not responding)"));
+    }
+    if process.access_denied {
+        name = name.push(Text::new("(Access denied)"));
+    }
+    if let Some(note) = style.note {
+        name = name.push(notes::badge(note));
+    }
+    if let Some(limit) = style.limit {
+        name = name.push(limits::badge(limit));
+    }
+    let path = |path: &Option<PathBuf>| path.as_ref().map(|path| path.display().to_string()).unwrap_or_else(|| String::from("n/a"));
+    let cmd = if process.cmd.is_empty() { "n/a" } else { process.cmd.as_str() };
+    let details = format!("Command line: {}\nExecutable: {}\nWorking directory: {}", cmd, path(&process.exe), path(&process.cwd));
+    Tooltip::new(name, Text::new(details), tooltip::Position::FollowCursor)
+        .style(theme::Container::Box)
+        .padding(8)
+        .into()
+}
+
+// the text split into runs of matched and unmatched chars
+fn segments(text: &str, matched: &[usize]) -> Vec<(String, bool)> {
+    let mut segments: Vec<(String, bool)> = Vec::new();
+    for (index, c) in text.chars().enumerate() {
+        let highlighted = matched.contains(&index);
+        match segments.last_mut() {
+            Some((run, last)) if *last == highlighted => run.push(c),
+            _ => segments.push((c.to_string(), highlighted)),
+        }
+    }
+    segments
+}
+
+// parent pid as a link to the parent's row
+fn parent_cell(process: &ProcessInfo, width: Length, text: String) -> Element<'_, Message> {
+    match process.ppid {
+        Some(ppid) => Button::new(Text::new(text))
+            .on_press(Message::JumpToProcess(ppid))
+            .style(theme::Button::Text)
+            .padding(0)
+            .width(width)
+            .into(),
+        None => Text::new("").width(width).into(),
+    }
+}
+
+// nice value with arrows to lower (▼) or raise (▲) it in place, ▲ means more cpu time
+fn priority_cell(process: &ProcessInfo, width: Length) -> Element<'_, Message> {
+    match process.nice {
+        Some(nice) => Row::new()
+            .spacing(2)
+            .push(Tooltip::new(
+                Button::new("▼").on_press(Message::AdjustPriority(process.pid, 1)).padding(2),
+                "Lower priority",
+                tooltip::Position::Bottom,
+            ))
+            .push(Text::new(nice.to_string()).width(Length::Fixed(30.0)).horizontal_alignment(alignment::Horizontal::Center))
+            .push(Tooltip::new(
+                Button::new("▲").on_press(Message::AdjustPriority(process.pid, -1)).padding(2),
+                "Raise priority",
+                tooltip::Position::Bottom,
+            ))
+            .width(width)
+            .into(),
+        None => Text::new("n/a").width(width).into(),
+    }
+}
+
+// quick column toggle opened from the header, filtered by the search text, with -/+ buttons
+// for the width of each column and the number formats of the columns that offer some
+pub fn picker_view<'a>(visible: &[SortColumn], widths: &ColumnWidths, formats: &ColumnFormats, search: &str) -> Element<'a, Message> {
+    let needle = search.to_lowercase();
+    let options = SortColumn::with_plugins()
+        .into_iter()
+        .filter(|column| column.title().to_lowercase().contains(&needle))
+        .fold(Column::new().spacing(5), |list, column| {
+            let width = widths.portion(column);
+            list.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(alignment::Alignment::Center)
+                    .push(
+                        Checkbox::new(column.title(), visible.contains(&column))
+                            .on_toggle(move |shown| Message::ToggleColumn(column, shown))
+                            .width(Length::Fixed(200.0)),
+                    )
+                    .push(Button::new("-").on_press_maybe((width > 1).then_some(Message::SetColumnWidth(column, width - 1))))
+                    .push(Text::new(format!("width {}", width)))
+                    .push(Button::new("+").on_press_maybe((width < MAX_PORTION).then_some(Message::SetColumnWidth(column, width + 1))))
+                    .push(NumberFormat::options(column).iter().fold(Row::new().spacing(5), |row, &format| {
+                        let button = Button::new(format.label());
+                        row.push(if formats.get(column) == Some(format) { button } else { button.on_press(Message::SetColumnFormat(column, format)) })
+                    })),
+            )
+        });
+    Column::new()
+        .spacing(10)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    TextInput::new("Search columns", search)
+                        .on_input(Message::ColumnSearchChanged)
+                        .width(Length::Fixed(250.0)),
+                )
+                .push(Button::new("Close").on_press(Message::ToggleColumnPicker)),
+        )
+        .push(options)
+        .into()
+}
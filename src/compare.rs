@@ -0,0 +1,102 @@
+// two processes side by side - their readings, command lines and users next to each other and
+// the last five minutes of cpu and memory charted on the same axes, for picking which of two
+// similar workers to kill. opened from "Compare with selected" in a row's menu
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Text},
+    Element, Length,
+};
+
+use std::time::Instant;
+
+use crate::chart;
+use crate::format::{self, format_duration};
+use crate::history::History;
+use crate::process::ProcessInfo;
+use crate::row_menu::RowAction;
+use crate::timeseries::{Point, Range};
+use crate::Message;
+
+// the charts show the history buffers, which keep the trend window
+const RANGE: Range = Range::FiveMinutes;
+
+// the fields compared, "n/a" where unknown
+fn fields(process: &ProcessInfo) -> Vec<(&'static str, String)> {
+    let unknown = || String::from("n/a");
+    vec![
+        ("User", process.user.clone()),
+        ("Command line", process.cmd.clone()),
+        ("Memory", format!("{} MB", process.memory)),
+        ("CPU", format!("{}%", format::decimal(f64::from(process.cpu), 1))),
+        ("Memory growth", format!("{:+} MB in 5 min", process.memory_growth)),
+        ("Executable", process.exe.as_ref().map(|exe| exe.display().to_string()).unwrap_or_else(unknown)),
+        ("Priority", process.nice.map(|nice| nice.to_string()).unwrap_or_else(unknown)),
+        ("CPU time", process.cpu_time.map(format_duration).unwrap_or_else(unknown)),
+        ("Running for", format_duration(process.uptime)),
+    ]
+}
+
+// top of the shared axis, 100 for cpu unless a process went past one core's worth
+fn axis(series: [&[Point]; 2], floor: f32) -> f32 {
+    series.iter().flat_map(|points| points.iter().map(|point| point.value)).fold(floor, f32::max)
+}
+
+// one column per process, with its kill button when killing is allowed
+fn side<'a>(pid: u32, process: Option<&'a ProcessInfo>, killable: bool) -> Column<'a, Message> {
+    let Some(process) = process else {
+        return Column::new().push(Text::new(format!("Process {} exited", pid)));
+    };
+    let title = Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(format!("{} ({})", process.name, process.pid)).width(Length::Fill))
+        .push(Button::new("Kill").on_press_maybe(killable.then_some(Message::RowAction(pid, RowAction::Kill))));
+    fields(process).into_iter().fold(Column::new().spacing(5).push(title), |column, (label, value)| {
+        column.push(Row::new().spacing(10).push(Text::new(label).width(Length::Fixed(120.0))).push(Text::new(value)))
+    })
+}
+
+// construct the comparison panel of the two pids, a process that exited keeps its place
+pub fn view<'a>(pids: [u32; 2], processes: [Option<&'a ProcessInfo>; 2], history: &History, killable: bool, now: Instant) -> Element<'a, Message> {
+    let [(left_cpu, left_memory), (right_cpu, right_memory)] = pids.map(|pid| history.points(pid, now));
+    let cpu_max = axis([&left_cpu, &right_cpu], 100.0);
+    let memory_max = axis([&left_memory, &right_memory], 1.0) * 1.1;
+    let charts = |left: Vec<Point>, right: Vec<Point>, max: f32, unit: &'static str| {
+        Row::new()
+            .spacing(20)
+            .push(chart::view(left, Vec::new(), RANGE, max, unit))
+            .push(chart::view(right, Vec::new(), RANGE, max, unit))
+    };
+    let header = Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(format!("Comparing the last {}", RANGE.label())).width(Length::Fill))
+        .push(Button::new("Close").on_press(Message::CloseComparison));
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(
+            Row::new()
+                .spacing(20)
+                .push(side(pids[0], processes[0], killable).width(Length::FillPortion(1)))
+                .push(side(pids[1], processes[1], killable).width(Length::FillPortion(1)))
+        )
+        .push(Text::new(format!("CPU, up to {}%", format::decimal(f64::from(cpu_max), 0))))
+        .push(charts(left_cpu, right_cpu, cpu_max, "%"))
+        .push(Text::new(format!("Memory, up to {} MB", format::decimal(f64::from(memory_max), 0))))
+        .push(charts(left_memory, right_memory, memory_max, " MB"))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_charts_share_the_highest_value() {
+        let at = |value| Point { time: chrono::Local::now(), value };
+        assert_eq!(axis([&[at(20.0), at(60.0)], &[at(40.0)]], 100.0), 100.0);
+        assert_eq!(axis([&[at(20.0)], &[at(250.0)]], 100.0), 250.0);
+        assert_eq!(axis([&[], &[]], 1.0), 1.0);
+    }
+}
@@ -0,0 +1,401 @@
+// persistent settings, stored as toml in the platform config directory
+use serde::{Deserialize, Serialize};
+
+use crate::accent::ThemeChoice;
+use crate::columns::{ColumnFormats, ColumnWidths};
+use crate::dump;
+use crate::filter::{self, FilterPreset, IgnoreList, NoiseFilter, Pin};
+use crate::format::{Clock, NumberStyle};
+use crate::hotkey::DEFAULT_HOTKEY;
+use crate::layout::Layout;
+use crate::notes::Note;
+use crate::plugins;
+use crate::process::SortColumn;
+use crate::recorder::RecorderConfig;
+use crate::remote::RemoteHost;
+use crate::rules::Rule;
+use crate::scheduler::RefreshIntervals;
+use crate::themes;
+use crate::style::{Density, DEFAULT_TEXT_SIZE};
+use crate::summary::SummaryFormat;
+use crate::watchdog::WatchEntry;
+use crate::Tab;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// name used for the implicit display when none are configured, or when the
+// window is outside of every configured display
+pub const DEFAULT_DISPLAY: &str = "default";
+
+// newest version of the settings file `export` writes that `import` understands
+const SETTINGS_VERSION: u32 = 1;
+
+// the whole setup in one file for moving it to another machine, with the theme files as
+// (file name, contents) since the config only names the picked one
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsFile {
+    version: u32,
+    config: Config,
+    #[serde(default)]
+    themes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // extra zoom applied on top of the scaling the OS already does for the monitor
+    pub ui_scale: f64,
+    // displays described by the user, used to tell which monitor the window is on
+    pub displays: Vec<DisplayProfile>,
+    // last window geometry used on each display, keyed by display name
+    pub window_geometry: BTreeMap<String, WindowGeometry>,
+    // display the window was on when the app was last closed
+    pub last_display: String,
+    // whether the window was maximized and which tab was shown when the app was last closed
+    pub maximized: bool,
+    pub tab: Tab,
+    // where the mini window was last dragged to
+    pub mini_position: Option<(i32, i32)>,
+    // global shortcut that raises the window, e.g. "ctrl+shift+Escape"
+    pub hotkey: String,
+    // icon in the system tray, closing the window hides it there instead of quitting,
+    // and starting hidden (minimized when there is no tray)
+    pub tray_icon: bool,
+    pub close_to_tray: bool,
+    pub start_minimized: bool,
+    // dark or light palette
+    pub theme: ThemeChoice,
+    // use the desktop's accent color for buttons and highlights
+    pub use_system_accent: bool,
+    // theme file picked in the settings tab, replaces `theme` and the accent color, see themes.rs
+    pub custom_theme: Option<String>,
+    // default text size in logical pixels, applied on the next start
+    pub text_size: u16,
+    // row height and spacing of the views
+    pub density: Density,
+    // simple or advanced mode, simple overrides the columns, density and tabs, see layout.rs
+    pub layout: Layout,
+    // decimal and thousands separators and the clock of the views, the system's by default
+    pub number_style: NumberStyle,
+    pub clock: Clock,
+    // columns shown in the process table
+    #[serde(deserialize_with = "plugins::known_columns")]
+    pub columns: Vec<SortColumn>,
+    // relative column widths set in the column picker
+    pub column_widths: ColumnWidths,
+    // number formats picked in the column picker, e.g. memory in GiB, see columns.rs
+    pub column_formats: ColumnFormats,
+    // (column, ascending) keys the process table was last sorted by, first key first
+    #[serde(deserialize_with = "plugins::known_sort_keys")]
+    pub sort: Vec<(SortColumn, bool)>,
+    // tiebreaker for rows equal in the sort column, pid is always the last resort
+    #[serde(deserialize_with = "plugins::known_column")]
+    pub secondary_sort: SortColumn,
+    // holds the row order and new rows back while the pointer is over the list or a row is selected
+    pub freeze_order: bool,
+    // kills wait a few seconds with an undo button before the signal is sent, see soft_kill.rs
+    pub undo_kills: bool,
+    // only observes: kills, renices, suspends, signals, restarts and the like are refused, rules and scripts included
+    pub read_only: bool,
+    // top cpu and memory users listed above the process table
+    pub heat_panel: bool,
+    // memory sums and cpu averages of the shown rows below the header
+    pub totals_row: bool,
+    // cpu and memory cells drawn as bars with the number over them, see bar_cell.rs
+    pub bar_cells: bool,
+    // percent of memory in use that starts low memory mode, 0 for never, see low_memory.rs
+    pub low_memory_percent: u8,
+    // low memory mode brings the window back from the tray
+    pub low_memory_shows_window: bool,
+    // the session summary written to the summaries folder on exit, see summary.rs
+    pub summary_on_exit: SummaryFormat,
+    // closing the window starts the background monitor, see daemon.rs
+    pub background_monitor: bool,
+    // window width in logical pixels below which the process table drops columns and
+    // goes to two-line rows, 0 for never, see layout.rs
+    pub narrow_width: u32,
+    // lists apps and background processes apart, see apps.rs
+    pub group_apps: bool,
+    // lists the processes under their systemd slices, services and scopes instead, linux
+    // only, see slices.rs
+    pub group_slices: bool,
+    // program icons before the names, see icons.rs
+    pub process_icons: bool,
+    // load averages and uptime appended to the window title, for the taskbar
+    pub load_in_title: bool,
+    // hides idle processes from the process table
+    pub noise_filter: NoiseFilter,
+    // named searches with their filters and sort, see the presets row of the processes tab
+    pub filter_presets: Vec<FilterPreset>,
+    // process names never shown in the table or matched by rules, managed in the settings tab
+    pub ignored: IgnoreList,
+    // names added to the built-in critical processes, whose kill needs an extra confirmation, see critical.rs
+    pub critical: Vec<String>,
+    // views listed in the tray menu, e.g. "processes?sort=cpu&order=desc", see links.rs
+    pub tray_links: Vec<String>,
+    // tags and notes the user attached to programs, by executable path, see notes.rs
+    pub notes: BTreeMap<String, Note>,
+    // how long a boost lasts in minutes, 0 until it is ended, and whether it lowers the
+    // user's other processes, see boost.rs
+    pub boost_minutes: u64,
+    pub boost_lowers_others: bool,
+    // processes shown above the sorted rows whatever the sort order
+    #[serde(serialize_with = "filter::save_names", deserialize_with = "filter::load_names")]
+    pub pinned: Vec<Pin>,
+    // lists kernel threads and processes of system accounts, off so the table shows user applications
+    pub show_system_processes: bool,
+    // cpu percent relative to the whole machine (at most 100) instead of to one core
+    pub cpu_of_whole_machine: bool,
+    // samples averaged into the displayed cpu percent, 1 shows the raw value
+    pub cpu_smoothing: u32,
+    // memory increases in a row after which a process is flagged as a suspected leak
+    pub leak_samples: u32,
+    // seconds between refreshes of each data source
+    pub refresh_intervals: RefreshIntervals,
+    // stretches the intervals while the machine is under heavy load, see scheduler.rs
+    pub adaptive_refresh: bool,
+    // refreshes less often and stops the performance charts on battery or while hidden
+    pub battery_saver: bool,
+    // stops collecting while the window isn't focused, not just while it is minimized or hidden
+    pub pause_when_unfocused: bool,
+    // lets features like reputation lookups contact remote services, off by default
+    pub allow_network_access: bool,
+    // alert/kill rules, see rules.rs for the format
+    pub rules: Vec<Rule>,
+    // opt-in sqlite log of machine and process samples
+    pub recorder: RecorderConfig,
+    // programs started again when they exit, see watchdog.rs
+    pub watched: Vec<WatchEntry>,
+    // where the dump and kill action writes core dumps
+    pub dump_dir: PathBuf,
+    // machines running an agent that the process table can switch to
+    pub hosts: Vec<RemoteHost>,
+    // names of the user scripts that run on every refresh, see scripts.rs
+    pub scripts: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayProfile {
+    // holds the bounds of a monitor in logical desktop coordinates
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    // optional zoom for this display, replaces `ui_scale` while the window is on it
+    pub scale: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    // window position and size in logical pixels
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            ui_scale: 1.0,
+            displays: Vec::new(),
+            window_geometry: BTreeMap::new(),
+            last_display: DEFAULT_DISPLAY.to_string(),
+            maximized: false,
+            tab: Tab::Processes,
+            mini_position: None,
+            hotkey: DEFAULT_HOTKEY.to_string(),
+            tray_icon: true,
+            close_to_tray: false,
+            start_minimized: false,
+            theme: ThemeChoice::Dark,
+            use_system_accent: true,
+            custom_theme: None,
+            text_size: DEFAULT_TEXT_SIZE,
+            density: Density::Normal,
+            layout: Layout::default(),
+            number_style: NumberStyle::default(),
+            clock: Clock::default(),
+            columns: SortColumn::DEFAULT_VISIBLE.to_vec(),
+            column_widths: ColumnWidths::default(),
+            column_formats: ColumnFormats::default(),
+            sort: Vec::new(),
+            secondary_sort: SortColumn::Pid,
+            freeze_order: true,
+            undo_kills: false,
+            read_only: false,
+            heat_panel: true,
+            totals_row: false,
+            bar_cells: true,
+            summary_on_exit: SummaryFormat::Off,
+            background_monitor: false,
+            low_memory_percent: 90,
+            low_memory_shows_window: false,
+            narrow_width: 800,
+            group_apps: false,
+            group_slices: false,
+            process_icons: true,
+            load_in_title: false,
+            noise_filter: NoiseFilter::default(),
+            filter_presets: Vec::new(),
+            ignored: IgnoreList::default(),
+            critical: Vec::new(),
+            tray_links: Vec::new(),
+            notes: BTreeMap::new(),
+            boost_minutes: 30,
+            boost_lowers_others: false,
+            pinned: Vec::new(),
+            show_system_processes: false,
+            cpu_of_whole_machine: false,
+            cpu_smoothing: 3,
+            leak_samples: 12,
+            refresh_intervals: RefreshIntervals::default(),
+            adaptive_refresh: true,
+            battery_saver: true,
+            pause_when_unfocused: false,
+            allow_network_access: false,
+            rules: Vec::new(),
+            recorder: RecorderConfig::default(),
+            watched: Vec::new(),
+            dump_dir: dump::default_dir(),
+            hosts: Vec::new(),
+            scripts: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    // directory holding the config file and other app files, e.g. ~/.config/conborli on Linux
+    pub fn dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("conborli"))
+    }
+
+    // location of the config file
+    pub fn path() -> Option<PathBuf> {
+        Config::dir().map(|dir| dir.join("config.toml"))
+    }
+
+    // reads the config file, falling back to defaults if it is missing or invalid
+    pub fn load() -> Config {
+        let Some(path) = Config::path() else {
+            return Config::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|err| {
+                eprintln!("ignoring invalid config {}: {}", path.display(), err);
+                Config::default()
+            }),
+            Err(_) => Config::default(),
+        }
+    }
+
+    // writes the config file, creating the directory if needed
+    pub fn save(&self) -> Result<(), String> {
+        let path = Config::path().ok_or("no config directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        let contents = toml::to_string_pretty(self).map_err(|err| err.to_string())?;
+        fs::write(&path, contents).map_err(|err| err.to_string())
+    }
+
+    // finds the display containing the given point, if one was configured
+    pub fn display_at(&self, x: i32, y: i32) -> Option<&DisplayProfile> {
+        self.displays.iter().find(|display| {
+            x >= display.x
+                && y >= display.y
+                && x < display.x + display.width as i32
+                && y < display.y + display.height as i32
+        })
+    }
+
+    // name of the display containing the given point
+    pub fn display_name_at(&self, x: i32, y: i32) -> String {
+        self.display_at(x, y)
+            .map(|display| display.name.clone())
+            .unwrap_or_else(|| DEFAULT_DISPLAY.to_string())
+    }
+
+    // zoom to use while the window is on the given display
+    pub fn scale_for(&self, display: &str) -> f64 {
+        self.displays
+            .iter()
+            .find(|profile| profile.name == display)
+            .and_then(|profile| profile.scale)
+            .unwrap_or(self.ui_scale)
+    }
+
+    // writes the settings file, everything but this machine's displays and window positions,
+    // returns the number of themes included
+    pub fn export(&self, path: &Path) -> Result<usize, String> {
+        let mut config = self.clone();
+        config.keep_local(&Config::default());
+        let file = SettingsFile { version: SETTINGS_VERSION, config, themes: themes::read_files()? };
+        let body = toml::to_string_pretty(&file).map_err(|err| err.to_string())?;
+        let header = "# conborli settings, written by Export settings and read by Import settings\n\n";
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        fs::write(path, format!("{}{}", header, body)).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(file.themes.len())
+    }
+
+    // reads a settings file and installs its themes, the caller keeps its own displays with `keep_local`
+    pub fn import(path: &Path) -> Result<Config, String> {
+        let contents = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let file: SettingsFile = toml::from_str(&contents).map_err(|err| format!("{}: {}", path.display(), err))?;
+        if file.version > SETTINGS_VERSION {
+            return Err(format!(
+                "{} uses settings format version {}, this build understands up to {}",
+                path.display(),
+                file.version,
+                SETTINGS_VERSION
+            ));
+        }
+        themes::write_files(&file.themes)?;
+        Ok(file.config)
+    }
+
+    // takes the displays, window positions and last tab from the other config, they belong
+    // to a machine rather than to a setup
+    pub fn keep_local(&mut self, local: &Config) {
+        self.displays = local.displays.clone();
+        self.window_geometry = local.window_geometry.clone();
+        self.last_display = local.last_display.clone();
+        self.maximized = local.maximized;
+        self.mini_position = local.mini_position;
+        self.tab = local.tab;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_files_round_trip_without_the_machine_parts() {
+        let mut config = Config { columns: vec![SortColumn::Name, SortColumn::Cpu], critical: vec![String::from("sshd")], ..Config::default() };
+        config.window_geometry.insert(String::from("laptop"), WindowGeometry { x: 1, y: 2, width: 3, height: 4 });
+        config.keep_local(&Config::default());
+        let themes = BTreeMap::from([(String::from("night.toml"), String::from("base = \"dark\"\n"))]);
+        let text = toml::to_string_pretty(&SettingsFile { version: SETTINGS_VERSION, config, themes }).unwrap();
+        let file: SettingsFile = toml::from_str(&text).unwrap();
+        assert_eq!(file.config.columns, vec![SortColumn::Name, SortColumn::Cpu]);
+        assert_eq!(file.config.critical, vec![String::from("sshd")]);
+        assert!(file.config.window_geometry.is_empty());
+        assert_eq!(file.themes.len(), 1);
+    }
+
+    #[test]
+    fn only_name_pins_are_saved() {
+        let config = Config { pinned: vec![Pin::Name(String::from("postgres")), Pin::Pid { pid: 20, start_time: 5 }], ..Config::default() };
+        let loaded: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(loaded.pinned, vec![Pin::Name(String::from("postgres"))]);
+        // pid pins written by older versions
+        let loaded: Config = toml::from_str("pinned = [{ pid = 42 }, { name = \"sshd\" }]").unwrap();
+        assert_eq!(loaded.pinned, vec![Pin::Name(String::from("sshd"))]);
+    }
+}
@@ -0,0 +1,253 @@
+// connections tab - every tcp/udp socket on the machine mapped back to its process, and the
+// links between local processes found in the tcp connections over loopback, so a developer can
+// see which of their services talks to which (e.g. "node → postgres:5432")
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+use crate::process::ProcessInfo;
+use crate::sockets::Socket;
+use crate::Message;
+
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    // holds one socket and its owner, pid is None when the owner can't be read
+    pub pid: Option<u32>,
+    pub process: String,
+    pub socket: Socket,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionColumn {
+    // defines columns which can be sorted in the connections tab
+    Pid,
+    Process,
+    Protocol,
+    LocalPort,
+    Remote,
+    State,
+}
+
+// joins the sockets with the process names of the current process list
+pub fn collect(sockets: Vec<(Option<u32>, Socket)>, processes: &[ProcessInfo]) -> Vec<ConnectionInfo> {
+    let names: HashMap<u32, &str> = processes.iter().map(|process| (process.pid, process.name.as_str())).collect();
+    sockets
+        .into_iter()
+        .map(|(pid, socket)| ConnectionInfo {
+            pid,
+            process: pid.and_then(|pid| names.get(&pid)).map(|name| name.to_string()).unwrap_or_default(),
+            socket,
+        })
+        .collect()
+}
+
+// a local process connected to one listening on a loopback port, names are empty and pids
+// None when the owner can't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub client_pid: Option<u32>,
+    pub client: String,
+    pub server_pid: Option<u32>,
+    pub server: String,
+    pub port: u16,
+    // open connections between the two
+    pub connections: usize,
+}
+
+impl Link {
+    pub fn label(&self) -> String {
+        let name = |name: &str| if name.is_empty() { String::from("?") } else { name.to_string() };
+        let label = format!("{} → {}:{}", name(&self.client), name(&self.server), self.port);
+        if self.connections > 1 {
+            format!("{} ({} connections)", label, self.connections)
+        } else {
+            label
+        }
+    }
+}
+
+// ipv4 peers of dual stack sockets show up as ::ffff:127.0.0.1
+fn is_loopback(ip: IpAddr) -> bool {
+    ip.to_canonical().is_loopback()
+}
+
+// pairs the client end of every established loopback tcp connection with the process listening
+// on the port it connects to. the server's accepted end of the same connection is left out, it
+// connects from the listening port rather than to it
+pub fn links(connections: &[ConnectionInfo]) -> Vec<Link> {
+    let listeners: HashMap<u16, &ConnectionInfo> = connections
+        .iter()
+        .filter(|connection| connection.socket.protocol.starts_with("tcp") && connection.socket.is_listening())
+        .filter(|connection| connection.socket.local.ip().is_unspecified() || is_loopback(connection.socket.local.ip()))
+        .map(|connection| (connection.socket.local.port(), connection))
+        .collect();
+    let mut links: BTreeMap<(u16, Option<u32>, Option<u32>), Link> = BTreeMap::new();
+    for connection in connections.iter().filter(|connection| connection.socket.protocol.starts_with("tcp") && connection.socket.is_connected()) {
+        let (local, remote) = (connection.socket.local, connection.socket.remote);
+        if !is_loopback(remote.ip()) || listeners.contains_key(&local.port()) {
+            continue;
+        }
+        let Some(server) = listeners.get(&remote.port()) else {
+            continue;
+        };
+        // a process talking to itself isn't a dependency
+        if connection.pid.is_some() && connection.pid == server.pid {
+            continue;
+        }
+        links
+            .entry((remote.port(), connection.pid, server.pid))
+            .or_insert_with(|| Link {
+                client_pid: connection.pid,
+                client: connection.process.clone(),
+                server_pid: server.pid,
+                server: server.process.clone(),
+                port: remote.port(),
+                connections: 0,
+            })
+            .connections += 1;
+    }
+    links.into_values().collect()
+}
+
+// sorts the connections based on the selected column and order (asc/desc)
+pub fn sort(connections: &mut [ConnectionInfo], column: ConnectionColumn, ascending: bool) {
+    connections.sort_by(|a, b| {
+        let cmp = match column {
+            ConnectionColumn::Pid => a.pid.cmp(&b.pid),
+            ConnectionColumn::Process => a.process.cmp(&b.process),
+            ConnectionColumn::Protocol => a.socket.protocol.cmp(b.socket.protocol),
+            ConnectionColumn::LocalPort => a.socket.local.port().cmp(&b.socket.local.port()),
+            ConnectionColumn::Remote => a.socket.remote.cmp(&b.socket.remote),
+            ConnectionColumn::State => a.socket.state.cmp(b.socket.state),
+        }
+        .then_with(|| a.socket.inode.cmp(&b.socket.inode));
+        if ascending {
+            cmp
+        } else {
+            cmp.reverse()
+        }
+    });
+}
+
+// socket table, with the owning process columns when `show_process` is set
+pub fn table<'a>(sockets: impl Iterator<Item = (Option<u32>, &'a str, &'a Socket)>, show_process: bool) -> Element<'a, Message> {
+    sockets
+        .fold(Column::new().spacing(5), |column, (pid, process, socket)| {
+            let mut row = Row::new().spacing(10);
+            if show_process {
+                row = row
+                    .push(Text::new(pid.map(|pid| pid.to_string()).unwrap_or_else(|| String::from("?"))).width(Length::FillPortion(1)))
+                    .push(Text::new(process).width(Length::FillPortion(2)));
+            }
+            column.push(
+                row.push(Text::new(socket.protocol).width(Length::FillPortion(1)))
+                    .push(Text::new(socket.local.to_string()).width(Length::FillPortion(3)))
+                    .push(Text::new(socket.remote.to_string()).width(Length::FillPortion(3)))
+                    .push(Text::new(socket.state).width(Length::FillPortion(2)))
+            )
+        })
+        .into()
+}
+
+// header matching `table`, sortable on the connections tab only
+fn header<'a>(show_process: bool, sortable: bool) -> Row<'a, Message> {
+    let button = |label: &'a str, column: ConnectionColumn, portion: u16| {
+        Button::new(label)
+            .on_press_maybe(sortable.then_some(Message::SortConnections(column)))
+            .width(Length::FillPortion(portion))
+    };
+    let mut header = Row::new().spacing(10);
+    if show_process {
+        header = header
+            .push(button("PID", ConnectionColumn::Pid, 1))
+            .push(button("Process", ConnectionColumn::Process, 2));
+    }
+    header
+        .push(button("Protocol", ConnectionColumn::Protocol, 1))
+        .push(button("Local", ConnectionColumn::LocalPort, 3))
+        .push(button("Remote", ConnectionColumn::Remote, 3))
+        .push(button("State", ConnectionColumn::State, 2))
+}
+
+// construct the connections tab layout
+pub fn view<'a>(connections: &'a Result<Vec<ConnectionInfo>, String>) -> Element<'a, Message> {
+    let connections = match connections {
+        Ok(connections) => connections,
+        Err(err) => return Text::new(err).into(),
+    };
+    let listening = connections.iter().filter(|connection| connection.socket.is_listening()).count();
+    let established = connections.iter().filter(|connection| connection.socket.is_connected()).count();
+    let links = links(connections);
+    let links = (!links.is_empty()).then(|| {
+        let rows = links.iter().fold(Column::new().spacing(2), |column, link| column.push(Text::new(link.label())));
+        Column::new()
+            .spacing(5)
+            .push(Text::new(format!("{} local links over loopback", links.len())))
+            .push(Scrollable::new(rows).height(Length::Fixed(100.0)))
+    });
+    Column::new()
+        .spacing(10)
+        .push(Text::new(format!("{} sockets, {} listening, {} established", connections.len(), listening, established)))
+        .push_maybe(links)
+        .push(header(true, true))
+        .push(Scrollable::new(table(
+            connections
+                .iter()
+                .map(|connection| (connection.pid, connection.process.as_str(), &connection.socket)),
+            true,
+        )))
+        .into()
+}
+
+// sockets of one process for the details pane, listening ones first
+pub fn process_view(sockets: &Result<Vec<Socket>, String>) -> Element<'_, Message> {
+    let sockets = match sockets {
+        Ok(sockets) => sockets,
+        Err(err) => return Text::new(err).into(),
+    };
+    let mut sorted: Vec<&Socket> = sockets.iter().collect();
+    sorted.sort_by(|a, b| match (a.is_listening(), b.is_listening()) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => a.local.port().cmp(&b.local.port()),
+    });
+    Column::new()
+        .spacing(5)
+        .push(Text::new(format!("{} sockets", sorted.len())))
+        .push(header(false, false))
+        .push(Scrollable::new(table(sorted.into_iter().map(|socket| (None, "", socket)), false)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(pid: u32, process: &str, local: &str, remote: &str, state: &'static str) -> ConnectionInfo {
+        let socket = Socket { protocol: "tcp", local: local.parse().unwrap(), remote: remote.parse().unwrap(), state, inode: 0 };
+        ConnectionInfo { pid: Some(pid), process: process.to_string(), socket }
+    }
+
+    #[test]
+    fn loopback_clients_link_to_the_listener() {
+        let connections = vec![
+            connection(10, "postgres", "0.0.0.0:5432", "0.0.0.0:0", "LISTEN"),
+            connection(10, "postgres", "127.0.0.1:5432", "127.0.0.1:40001", "ESTABLISHED"),
+            connection(10, "postgres", "127.0.0.1:5432", "127.0.0.1:40002", "ESTABLISHED"),
+            connection(20, "node", "127.0.0.1:40001", "127.0.0.1:5432", "ESTABLISHED"),
+            connection(20, "node", "127.0.0.1:40002", "127.0.0.1:5432", "ESTABLISHED"),
+            connection(20, "node", "[::]:3000", "[::]:0", "LISTEN"),
+            connection(30, "curl", "[::ffff:127.0.0.1]:40003", "[::ffff:127.0.0.1]:3000", "ESTABLISHED"),
+            // not over loopback, and to nothing listening
+            connection(30, "curl", "192.168.1.2:40004", "93.184.216.34:443", "ESTABLISHED"),
+            connection(30, "curl", "127.0.0.1:40005", "127.0.0.1:9999", "ESTABLISHED"),
+        ];
+        let labels: Vec<String> = links(&connections).iter().map(Link::label).collect();
+        assert_eq!(labels, vec!["curl → node:3000", "node → postgres:5432 (2 connections)"]);
+    }
+}
@@ -0,0 +1,156 @@
+// container awareness - which docker/podman/lxc/kubernetes container a process runs in
+//
+// detected from the process's cgroup path on linux. docker and podman name their cgroups
+// after the container id, so the human names are looked up with `docker ps` / `podman ps`
+// in the background whenever an id shows up that hasn't been seen yet
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContainerId {
+    // "docker", "podman", "lxc" or "kubernetes", and the id (or name for lxc)
+    pub runtime: &'static str,
+    pub id: String,
+}
+
+// finds the container in a /proc/<pid>/cgroup file, None for host processes
+pub fn detect(cgroup: &str) -> Option<ContainerId> {
+    cgroup.lines().find_map(|line| {
+        // "hierarchy-id:controllers:path", the path is what matters
+        let path = line.splitn(3, ':').nth(2)?;
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+        let found = segments.iter().enumerate().find_map(|(index, segment)| {
+            parse_segment(segment).or_else(|| {
+                // cgroup v1 layouts put the id or name in the next segment
+                let next = segments.get(index + 1)?;
+                match *segment {
+                    "docker" if is_id(next) => Some(container("docker", next)),
+                    "lxc" => Some(container("lxc", next)),
+                    _ => None,
+                }
+            })
+        });
+        // kubepods/<qos>/pod<uid>/<id> on cgroup v1
+        found.or_else(|| {
+            let last = segments.last()?;
+            (segments.contains(&"kubepods") && is_id(last)).then(|| container("kubernetes", last))
+        })
+    })
+}
+
+// one path segment like "docker-<id>.scope", "libpod-<id>.scope" or "lxc.payload.<name>"
+fn parse_segment(segment: &str) -> Option<ContainerId> {
+    let scope = segment.strip_suffix(".scope").unwrap_or(segment);
+    if let Some(id) = scope.strip_prefix("docker-").filter(|id| is_id(id)) {
+        Some(container("docker", id))
+    } else if let Some(id) = scope.strip_prefix("libpod-").filter(|id| is_id(id)) {
+        Some(container("podman", id))
+    } else if let Some(id) = scope.strip_prefix("cri-containerd-").or_else(|| scope.strip_prefix("crio-")).filter(|id| is_id(id)) {
+        Some(container("kubernetes", id))
+    } else {
+        segment.strip_prefix("lxc.payload.").map(|name| container("lxc", name))
+    }
+}
+
+fn container(runtime: &'static str, id: &str) -> ContainerId {
+    ContainerId { runtime, id: id.to_string() }
+}
+
+// container ids are 64 hex digits
+fn is_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// cgroup of the process, None off linux or when it can't be read
+pub fn for_pid(pid: u32) -> Option<ContainerId> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    detect(&std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?)
+}
+
+#[derive(Debug, Default)]
+pub struct ContainerNames {
+    // names reported by the runtimes, keyed by full id
+    names: HashMap<String, String>,
+    // ids already looked up (found or not), so a nameless id isn't queried every refresh
+    looked_up: HashSet<String>,
+}
+
+impl ContainerNames {
+    // "runtime:name", or the short id when the name isn't known (yet)
+    pub fn label(&self, container: &ContainerId) -> String {
+        match self.names.get(&container.id) {
+            Some(name) => format!("{}:{}", container.runtime, name),
+            None if container.id.len() > 12 => format!("{}:{}", container.runtime, &container.id[..12]),
+            None => format!("{}:{}", container.runtime, container.id),
+        }
+    }
+
+    // true if any of the docker/podman ids hasn't been looked up yet, marking them as looked up
+    pub fn needs_lookup<'a>(&mut self, containers: impl Iterator<Item = &'a ContainerId>) -> bool {
+        let mut needed = false;
+        for container in containers.filter(|container| matches!(container.runtime, "docker" | "podman")) {
+            needed |= self.looked_up.insert(container.id.clone());
+        }
+        needed
+    }
+
+    pub fn extend(&mut self, names: HashMap<String, String>) {
+        self.names.extend(names);
+    }
+}
+
+// asks docker and podman for the names of their running containers, blocks on both
+pub fn lookup_names() -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for program in ["docker", "podman"] {
+        let Ok(output) = Command::new(program)
+            .args(["ps", "--no-trunc", "--format", "{{.ID}} {{.Names}}"])
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        names.extend(parse_names(&String::from_utf8_lossy(&output.stdout)));
+    }
+    names
+}
+
+// "<id> <name>" lines of `ps --format "{{.ID}} {{.Names}}"`
+fn parse_names(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(id, name)| (id.to_string(), name.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn containers_are_found_in_cgroup_paths() {
+        let id = "a".repeat(64);
+        let found = |cgroup: String| detect(&cgroup).map(|container| (container.runtime, container.id));
+        assert_eq!(found(format!("0::/system.slice/docker-{}.scope\n", id)), Some(("docker", id.clone())));
+        assert_eq!(found(format!("0::/machine.slice/libpod-{}.scope\n", id)), Some(("podman", id.clone())));
+        assert_eq!(found(format!("11:memory:/docker/{}\n", id)), Some(("docker", id.clone())));
+        assert_eq!(found(format!("4:pids:/kubepods/burstable/pod1234/{}\n", id)), Some(("kubernetes", id.clone())));
+        assert_eq!(found(String::from("0::/lxc.payload.web/init.scope\n")), Some(("lxc", String::from("web"))));
+        assert_eq!(found(String::from("0::/user.slice/user-1000.slice/session-2.scope\n")), None);
+        // too short to be a container id
+        assert_eq!(found(String::from("0::/system.slice/docker-abc.scope\n")), None);
+    }
+
+    #[test]
+    fn runtime_listings_map_ids_to_names() {
+        let names = parse_names("abc123 web\ndef456 db \n\n");
+        assert_eq!(names.get("abc123").map(String::as_str), Some("web"));
+        assert_eq!(names.get("def456").map(String::as_str), Some("db"));
+        assert_eq!(names.len(), 2);
+    }
+}
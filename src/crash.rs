@@ -0,0 +1,113 @@
+// crash reports - a panic hook writes the message, a backtrace, the version and os and the
+// latest events into the crashes folder of the config directory, and the next launch offers
+// to open the report instead of the window having just disappeared
+use iced::{
+    alignment,
+    widget::{Button, Row, Text},
+    Element,
+};
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::Message;
+
+// latest event lines kept for a report
+const RECENT_EVENTS: usize = 50;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+fn dir() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("crashes"))
+}
+
+// holds the path of the report the next launch hasn't shown yet
+fn pending_file() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("pending"))
+}
+
+// an event line for the next report, see events.rs
+pub fn remember(line: String) {
+    if let Ok(mut recent) = RECENT.lock() {
+        recent.push_back(line);
+        if recent.len() > RECENT_EVENTS {
+            recent.pop_front();
+        }
+    }
+}
+
+// writes a report on panics, then runs the default hook so the message still reaches stderr
+pub fn install() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!("{}", info);
+        if let Err(err) = write_report(info) {
+            eprintln!("can't write the crash report: {}", err);
+        }
+        default(info);
+    }));
+}
+
+fn write_report(info: &PanicHookInfo) -> Result<PathBuf, String> {
+    let dir = dir().ok_or_else(|| String::from("no config directory"))?;
+    fs::create_dir_all(&dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+    let path = dir.join(format!("crash-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    // a panic while the events were being added leaves them out rather than waiting for them
+    let recent: Vec<String> = RECENT.try_lock().map(|recent| recent.iter().cloned().collect()).unwrap_or_default();
+    fs::write(&path, report(&info.to_string(), &recent)).map_err(|err| format!("{}: {}", path.display(), err))?;
+    if let Some(pending) = pending_file() {
+        fs::write(pending, path.to_string_lossy().as_bytes()).map_err(|err| err.to_string())?;
+    }
+    Ok(path)
+}
+
+fn report(panic: &str, recent: &[String]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "conborli {} crashed at {}", env!("CARGO_PKG_VERSION"), chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+    let os = sysinfo::System::long_os_version().unwrap_or_else(|| String::from(std::env::consts::OS));
+    let _ = writeln!(report, "os: {} ({})", os, std::env::consts::ARCH);
+    let _ = writeln!(report, "\n{}\n\nbacktrace:\n{}", panic, Backtrace::force_capture());
+    let _ = writeln!(report, "recent events:");
+    for line in recent {
+        let _ = writeln!(report, "  {}", line);
+    }
+    report
+}
+
+// the report of a crash since the last launch, shown once
+pub fn take_pending() -> Option<PathBuf> {
+    let pending = pending_file()?;
+    let path = fs::read_to_string(&pending).ok()?;
+    let _ = fs::remove_file(pending);
+    let path = PathBuf::from(path.trim());
+    path.exists().then_some(path)
+}
+
+// the notice about the last crash, with its report
+pub fn view(report: &Path) -> Element<'_, Message> {
+    Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(format!("The task manager crashed last time, a report was saved to {}", report.display())))
+        .push(Button::new("Open report").on_press(Message::OpenCrashReport))
+        .push(Button::new("Dismiss").on_press(Message::DismissCrashReport))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_have_the_panic_and_the_events() {
+        let report = report("panicked at src/main.rs:1:1:\noops", &[String::from("12:00:00 Kill You killed a (1)")]);
+        assert!(report.starts_with(&format!("conborli {} crashed", env!("CARGO_PKG_VERSION"))));
+        assert!(report.contains("oops") && report.contains("backtrace:") && report.ends_with("  12:00:00 Kill You killed a (1)\n"));
+    }
+}
@@ -0,0 +1,67 @@
+// processes whose kill takes the session or the whole machine down with it - init, the
+// kernel's threads, and the windows and macOS processes the login session is built on. the
+// kill preview asks for an extra confirmation before killing one, and every other kill leaves
+// them alone, see kill_guard.rs. users can add their own names in the settings tab
+use crate::process::ProcessInfo;
+
+// compared ignoring case, windows names with their .exe
+const BUILT_IN: [&str; 19] = [
+    "systemd",
+    "init",
+    "launchd",
+    "kernel_task",
+    "WindowServer",
+    "loginwindow",
+    "System",
+    "Registry",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "dwm.exe",
+    "svchost.exe",
+    "Xorg",
+    "Xwayland",
+    "dbus-daemon",
+];
+
+pub fn is_critical(process: &ProcessInfo, extra: &[String]) -> bool {
+    process.pid == 1
+        || is_kernel_thread(process)
+        || BUILT_IN.iter().any(|name| name.eq_ignore_ascii_case(&process.name))
+        || extra.iter().any(|name| name.eq_ignore_ascii_case(&process.name))
+}
+
+// kthreadd and its children, which run in the kernel and have no executable
+fn is_kernel_thread(process: &ProcessInfo) -> bool {
+    cfg!(target_os = "linux") && (process.pid == 2 || process.ppid == Some(2))
+}
+
+// adds a user's name to the list, once
+pub fn add(list: &mut Vec<String>, name: &str) {
+    let name = name.trim();
+    if !name.is_empty() && !list.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+        list.push(name.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    #[test]
+    fn init_session_processes_and_user_names_are_critical() {
+        assert!(is_critical(&mock::process(1, "bash", "root", 0, 0.0), &[]));
+        assert!(is_critical(&mock::process(500, "CSRSS.EXE", "SYSTEM", 0, 0.0), &[]));
+        assert!(!is_critical(&mock::process(900, "postgres", "postgres", 0, 0.0), &[]));
+        let mut extra = Vec::new();
+        add(&mut extra, " postgres ");
+        add(&mut extra, "Postgres");
+        assert_eq!(extra, vec![String::from("postgres")]);
+        assert!(is_critical(&mock::process(900, "postgres", "postgres", 0, 0.0), &extra));
+    }
+}
@@ -0,0 +1,434 @@
+// background monitor - `conborli --daemon` keeps sampling the machine for the performance
+// charts, checking the alert rules and feeding the metrics recorder while no window is open. a
+// window launched later attaches to it and takes over the samples and alerts it missed, so
+// closing the window doesn't leave a gap. with the setting on, closing the window starts one
+//
+// like single instance (instance.rs) the daemon listens on a localhost port it writes to
+// daemon.lock next to the config, along with a secret made up at startup. the lock is only
+// readable by its owner, and every request starts with the secret, so other users of the
+// machine can't stop the daemon or take its alerts. a window connects and says "attach", reads
+// one line of json with the last hour of samples and the alerts since the last attach, then
+// keeps the connection open. while a window is attached it notifies and records itself, so the
+// daemon only keeps sampling. "stop" ends the daemon. kill and command rules only act while a
+// window is open
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sysinfo::{System, Users};
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::hash::BuildHasher;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::collector;
+use crate::config::Config;
+use crate::filter::IgnoreList;
+use crate::notify;
+use crate::process::ProcessInfo;
+use crate::provider::SystemProvider;
+use crate::recorder::Recorder;
+use crate::rules::{self, Rule, RuleAction};
+use crate::run;
+
+const ATTACH: &str = "attach";
+const STOP: &str = "stop";
+// sent back to a stop, so a port reused by another program isn't mistaken for the daemon
+const REPLY: &str = "conborli";
+
+// how long a window waits for the daemon to answer
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// samples kept for the next window, the longest chart range
+const KEEP_MINUTES: i64 = 60;
+
+// alerts kept until a window attaches, the oldest go first
+const ALERTS: usize = 1000;
+
+// connections served at once, more are closed right away. each attached window holds one
+const CONNECTIONS: usize = 16;
+
+// machine-wide percents of one refresh
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub time: DateTime<Local>,
+    pub cpu: f32,
+    // None when the system reported no memory
+    pub memory: Option<f32>,
+}
+
+impl Sample {
+    // from an already refreshed system
+    fn of(system: &System, time: DateTime<Local>) -> Sample {
+        let total = system.total_memory();
+        let memory = (total > 0).then(|| system.used_memory() as f32 / total as f32 * 100.0);
+        Sample { time, cpu: system.global_cpu_info().cpu_usage(), memory }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub time: DateTime<Local>,
+    pub text: String,
+}
+
+// what an attaching window takes over
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Backlog {
+    pub samples: Vec<Sample>,
+    pub alerts: Vec<Alert>,
+}
+
+#[derive(Debug, Default)]
+struct Monitor {
+    samples: VecDeque<Sample>,
+    alerts: VecDeque<Alert>,
+    // (rule, pid) pairs that already fired, so a rule fires once per process like in the window
+    hits: HashSet<(String, u32)>,
+}
+
+impl Monitor {
+    fn record(&mut self, sample: Sample) {
+        let oldest = sample.time - chrono::Duration::minutes(KEEP_MINUTES);
+        self.samples.push_back(sample);
+        while self.samples.front().is_some_and(|sample| sample.time < oldest) {
+            self.samples.pop_front();
+        }
+    }
+
+    // the action, rule name and alert text of every rule that newly matched a process
+    fn check(&mut self, rules: &[Rule], processes: &[ProcessInfo], ignored: &IgnoreList) -> Vec<(RuleAction, String, String)> {
+        let live: HashSet<u32> = processes.iter().map(|process| process.pid).collect();
+        self.hits.retain(|(_, pid)| live.contains(pid));
+        let mut fired = Vec::new();
+        for (rule, process) in rules::evaluate(rules, processes).filter(|(_, process)| !ignored.ignores(process)) {
+            if !self.hits.insert((rule.name.clone(), process.pid)) {
+                continue;
+            }
+            let mut text = format!("{} matched {} ({})", rule.name, process.name, process.pid);
+            if matches!(rule.action, RuleAction::Kill | RuleAction::Command) {
+                text.push_str(", only acted on while the window is open");
+            }
+            fired.push((rule.action, rule.name.clone(), text));
+        }
+        fired
+    }
+
+    fn alert(&mut self, time: DateTime<Local>, text: String) {
+        self.alerts.push_back(Alert { time, text });
+        if self.alerts.len() > ALERTS {
+            self.alerts.pop_front();
+        }
+    }
+
+    // the alerts are handed over once, the samples to every window that attaches
+    fn backlog(&mut self) -> Backlog {
+        Backlog { samples: self.samples.iter().copied().collect(), alerts: self.alerts.drain(..).collect() }
+    }
+}
+
+// e.g. ~/.config/conborli/daemon.lock
+fn lock_file() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("daemon.lock"))
+}
+
+// where the daemon listens and the secret it expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lock {
+    addr: SocketAddr,
+    secret: String,
+}
+
+impl Lock {
+    // "<port> <secret>"
+    fn parse(text: &str) -> Option<Lock> {
+        let (port, secret) = text.trim().split_once(' ')?;
+        Some(Lock { addr: SocketAddr::from((Ipv4Addr::LOCALHOST, port.parse::<u16>().ok()?)), secret: secret.to_string() })
+    }
+
+    fn connect(&self, request: &str) -> Option<BufReader<TcpStream>> {
+        let mut stream = TcpStream::connect_timeout(&self.addr, TIMEOUT).ok()?;
+        stream.set_read_timeout(Some(TIMEOUT)).ok()?;
+        writeln!(stream, "{} {}", self.secret, request).ok()?;
+        Some(BufReader::new(stream))
+    }
+}
+
+// the running daemon's lock, if there is one
+fn read_lock() -> Option<Lock> {
+    Lock::parse(&fs::read_to_string(lock_file()?).ok()?)
+}
+
+// replaces the lock with one only the current user can read
+fn write_lock(path: &Path, lock: &Lock) -> std::io::Result<()> {
+    let _ = fs::remove_file(path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    write!(options.open(path)?, "{} {}", lock.addr.port(), lock.secret)
+}
+
+// 128 bits from the randomly keyed hashers of the standard library
+fn secret() -> String {
+    let hash = |value: u64| RandomState::new().hash_one((value, std::process::id(), std::time::SystemTime::now()));
+    format!("{:016x}{:016x}", hash(1), hash(2))
+}
+
+// compares in time that depends only on the length
+fn same(given: &[u8], secret: &[u8]) -> bool {
+    given.len() == secret.len() && given.iter().zip(secret).fold(0, |differ, (a, b)| differ | (a ^ b)) == 0
+}
+
+// a window attached to the daemon, which stays attached until every clone is dropped
+#[derive(Debug, Clone)]
+pub struct Attached {
+    _stream: Arc<TcpStream>,
+    pub backlog: Backlog,
+}
+
+// None when no daemon answers, a lock left behind by a crash points at a port nobody answers
+// on. waits for the daemon's answer, so run it off the ui thread
+pub fn attach() -> Option<Attached> {
+    attach_to(&read_lock()?)
+}
+
+fn attach_to(lock: &Lock) -> Option<Attached> {
+    let mut reader = lock.connect(ATTACH)?;
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let backlog = serde_json::from_str(&line).ok()?;
+    let stream = reader.into_inner();
+    // the connection only stays open from here on, nothing more is read
+    stream.set_read_timeout(None).ok()?;
+    Some(Attached { _stream: Arc::new(stream), backlog })
+}
+
+// asks a running daemon to end, false if none answered
+pub fn stop() -> bool {
+    read_lock().and_then(|lock| lock.connect(STOP)).is_some_and(|mut reader| {
+        let mut reply = String::new();
+        reader.read_line(&mut reply).is_ok() && reply.trim() == REPLY
+    })
+}
+
+// starts a daemon that outlives this process
+pub fn spawn() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|err| format!("can't find the task manager's executable: {}", err))?;
+    let mut command = std::process::Command::new(&exe);
+    command.arg("--daemon").stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    // out of the terminal's process group, so ctrl-c there doesn't end it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    run::spawn_detached(command).map(drop).map_err(|err| format!("failed to start the background monitor: {}", err))
+}
+
+// answers one connection, an attached window is counted until it disconnects. a request
+// without the secret goes unanswered
+fn serve(stream: TcpStream, secret: &str, monitor: &Mutex<Monitor>, attached: &AtomicUsize, stop: &AtomicBool) {
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    if reader.read_line(&mut request).is_err() {
+        return;
+    }
+    let (given, request) = request.trim().split_once(' ').unwrap_or_default();
+    if !same(given.as_bytes(), secret.as_bytes()) {
+        return;
+    }
+    match request {
+        ATTACH => {
+            let Ok(backlog) = monitor.lock().map(|mut monitor| monitor.backlog()) else {
+                return;
+            };
+            let Ok(line) = serde_json::to_string(&backlog) else {
+                return;
+            };
+            if writeln!(&stream, "{}", line).is_err() {
+                return;
+            }
+            attached.fetch_add(1, Ordering::SeqCst);
+            // the window never writes again, the read ends when it closes
+            let _ = stream.set_read_timeout(None);
+            let _ = reader.read_to_end(&mut Vec::new());
+            attached.fetch_sub(1, Ordering::SeqCst);
+        }
+        STOP => {
+            stop.store(true, Ordering::SeqCst);
+            let _ = writeln!(&stream, "{}", REPLY);
+        }
+        _ => {}
+    }
+}
+
+// runs until asked to stop, refusing to start next to another daemon
+pub fn run(config: &Config) -> Result<(), String> {
+    let path = lock_file().ok_or("no config directory on this system")?;
+    if read_lock().is_some_and(|lock| TcpStream::connect_timeout(&lock.addr, TIMEOUT).is_ok()) {
+        return Err(String::from("the background monitor is already running"));
+    }
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|err| format!("can't listen for windows: {}", err))?;
+    let lock = Lock { addr: listener.local_addr().map_err(|err| err.to_string())?, secret: secret() };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+    }
+    write_lock(&path, &lock).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    let monitor = Arc::new(Mutex::new(Monitor::default()));
+    let attached = Arc::new(AtomicUsize::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let (monitor, attached, stop) = (monitor.clone(), attached.clone(), stop.clone());
+        let secret = Arc::new(lock.secret);
+        let open = Arc::new(AtomicUsize::new(0));
+        thread::Builder::new()
+            .name(String::from("background monitor"))
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    // dropping the stream closes it
+                    if open.fetch_add(1, Ordering::SeqCst) >= CONNECTIONS {
+                        open.fetch_sub(1, Ordering::SeqCst);
+                        continue;
+                    }
+                    let (secret, monitor, attached, stop, open) = (secret.clone(), monitor.clone(), attached.clone(), stop.clone(), open.clone());
+                    thread::spawn(move || {
+                        serve(stream, &secret, &monitor, &attached, &stop);
+                        open.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+            })
+            .map_err(|err| err.to_string())?;
+    }
+
+    let mut recorder = match config.recorder.enabled.then(|| Recorder::open(&config.recorder)) {
+        Some(Err(err)) => {
+            tracing::warn!("not recording metrics: {}", err);
+            None
+        }
+        opened => opened.and_then(Result::ok),
+    };
+    let mut system = System::new();
+    let users = Users::new_with_refreshed_list();
+    let mut provider = SystemProvider;
+    let mut cpu_history = HashMap::new();
+    let interval = Duration::from_secs(config.refresh_intervals.processes.max(1));
+    collector::warm_up(&mut system);
+    while !stop.load(Ordering::SeqCst) {
+        system.refresh_cpu();
+        system.refresh_memory();
+        let processes = collector::collect(&mut provider, &mut system, &users, &mut cpu_history, config);
+        let now = Local::now();
+        // an attached window does the notifying and recording itself
+        let quiet = attached.load(Ordering::SeqCst) > 0;
+        if let Ok(mut monitor) = monitor.lock() {
+            monitor.record(Sample::of(&system, now));
+            let fired = monitor.check(&config.rules, &processes, &config.ignored);
+            for (action, rule, text) in fired.into_iter().filter(|_| !quiet) {
+                let sent = match action {
+                    RuleAction::Notify => notify::send(&rule, &text),
+                    RuleAction::Sound => notify::sound(),
+                    RuleAction::Log | RuleAction::Kill | RuleAction::Command => Ok(()),
+                };
+                let text = match sent {
+                    Ok(()) => text,
+                    Err(err) => format!("{}, {}", text, err),
+                };
+                tracing::info!("{}", text);
+                monitor.alert(now, text);
+            }
+        }
+        if let Some(recorder) = recorder.as_mut().filter(|_| !quiet) {
+            if let Err(err) = recorder.record(&system, &processes) {
+                tracing::warn!("failed to record metrics: {}", err);
+            }
+        }
+        thread::sleep(interval);
+    }
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+    use crate::rules::Condition;
+
+    #[test]
+    fn rules_fire_once_and_the_backlog_hands_alerts_over_once() {
+        let mut monitor = Monitor::default();
+        let start = Local::now();
+        for minutes in [0, 30, 61] {
+            monitor.record(Sample { time: start + chrono::Duration::minutes(minutes), cpu: 10.0, memory: Some(50.0) });
+        }
+        let rule = |name: &str, action| Rule {
+            name: name.to_string(),
+            enabled: true,
+            condition: Condition { name: Some(String::from("cargo")), ..Condition::default() },
+            action,
+            command: String::new(),
+        };
+        let rules = [rule("builds", RuleAction::Notify), rule("kill builds", RuleAction::Kill)];
+        let processes = [mock::process(20, "cargo", "u", 10, 0.0), mock::process(30, "bash", "u", 10, 0.0)];
+        let fired = monitor.check(&rules, &processes, &IgnoreList::default());
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[1].2, "kill builds matched cargo (20), only acted on while the window is open");
+        assert!(monitor.check(&rules, &processes, &IgnoreList::default()).is_empty());
+        monitor.alert(start, fired[0].2.clone());
+
+        let backlog = monitor.backlog();
+        // the first sample is more than an hour older than the last
+        assert_eq!(backlog.samples.len(), 2);
+        assert_eq!(backlog.alerts.len(), 1);
+        assert!(monitor.backlog().alerts.is_empty());
+    }
+
+    #[test]
+    fn a_window_attaches_until_it_disconnects() {
+        let monitor = Mutex::new(Monitor::default());
+        monitor.lock().unwrap().alert(Local::now(), String::from("builds matched cargo (20)"));
+        let (attached, stop) = (AtomicUsize::new(0), AtomicBool::new(false));
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let lock = Lock { addr: listener.local_addr().unwrap(), secret: secret() };
+        assert_eq!(Lock::parse(&format!("{} {}", lock.addr.port(), lock.secret)), Some(lock.clone()));
+        thread::scope(|scope| {
+            let server = scope.spawn(|| serve(listener.accept().unwrap().0, &lock.secret, &monitor, &attached, &stop));
+            let window = attach_to(&lock).unwrap();
+            assert_eq!(window.backlog.alerts[0].text, "builds matched cargo (20)");
+            drop(window);
+            server.join().unwrap();
+        });
+        assert_eq!(attached.load(Ordering::SeqCst), 0);
+        assert!(!stop.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn requests_without_the_secret_are_ignored() {
+        let monitor = Mutex::new(Monitor::default());
+        let (attached, stop) = (AtomicUsize::new(0), AtomicBool::new(false));
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let lock = Lock { addr: listener.local_addr().unwrap(), secret: secret() };
+        let guess = Lock { secret: secret(), ..lock.clone() };
+        assert_ne!(guess.secret, lock.secret);
+        thread::scope(|scope| {
+            let server = scope.spawn(|| {
+                for _ in 0..2 {
+                    serve(listener.accept().unwrap().0, &lock.secret, &monitor, &attached, &stop);
+                }
+            });
+            assert!(attach_to(&guess).is_none());
+            let mut reply = String::new();
+            assert!(guess.connect(STOP).is_some_and(|mut reader| reader.read_line(&mut reply).is_ok()));
+            assert!(reply.is_empty());
+            server.join().unwrap();
+        });
+        assert!(!stop.load(Ordering::SeqCst));
+    }
+}
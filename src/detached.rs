@@ -0,0 +1,70 @@
+// processes opened in windows of their own - the general details of one process above its
+// cpu and memory charts, so it can be kept in view while the main window browses the list.
+// each window follows its process until closed and says so when it exits, a newer process that
+// gets the pid isn't shown in its place. only Escape, which closes the window, and F5 act in it,
+// the other shortcuts are the main window's
+use iced::{
+    widget::{Column, Text},
+    window, Element, Length, Size,
+};
+
+use std::time::Instant;
+
+use crate::chart;
+use crate::details;
+use crate::format;
+use crate::history::History;
+use crate::process::ProcessInfo;
+use crate::provider::Identity;
+use crate::timeseries::{Point, Range};
+use crate::Message;
+
+// the history buffers keep the trend window
+const RANGE: Range = Range::FiveMinutes;
+
+#[derive(Debug, Clone)]
+pub struct Detached {
+    // the process the window was opened for, its name kept for after it exits
+    pub pid: u32,
+    pub identity: Identity,
+}
+
+impl Detached {
+    pub fn of(process: &ProcessInfo) -> Detached {
+        Detached { pid: process.pid, identity: Identity::of(process) }
+    }
+
+    // the process in the table, None once it exited or its pid was reused
+    pub fn process<'a>(&self, processes: &'a [ProcessInfo]) -> Option<&'a ProcessInfo> {
+        processes.iter().find(|process| process.pid == self.pid && Identity::of(process).is(&self.identity))
+    }
+}
+
+// closing one only closes that window, see `Message::DetachedClosed`
+pub fn settings() -> window::Settings {
+    window::Settings { size: Size::new(640.0, 560.0), exit_on_close_request: true, ..window::Settings::default() }
+}
+
+pub fn title(detached: &Detached) -> String {
+    format!("{} ({}) - Task Manager", detached.identity.name, detached.pid)
+}
+
+// construct the window of the process, `process` is None once it exited
+pub fn view<'a>(detached: &Detached, process: Option<&'a ProcessInfo>, history: &History, now: Instant) -> Element<'a, Message> {
+    let Some(process) = process else {
+        return Text::new(format!("{} ({}) exited", detached.identity.name, detached.pid)).into();
+    };
+    let (cpu, memory) = history.points(process.pid, now);
+    let top = |points: &[Point], floor: f32| points.iter().map(|point| point.value).fold(floor, f32::max);
+    let (cpu_max, memory_max) = (top(&cpu, 100.0), top(&memory, 1.0) * 1.1);
+    Column::new()
+        .spacing(10)
+        .padding(10)
+        .push(Text::new(format!("{} ({})", process.name, process.pid)).size(20))
+        .push(Column::new().push(details::general_view(process)).height(Length::Fixed(220.0)))
+        .push(Text::new(format!("CPU, last {}", RANGE.label())))
+        .push(chart::view(cpu, Vec::new(), RANGE, cpu_max, "%"))
+        .push(Text::new(format!("Memory, last {}, up to {} MB", RANGE.label(), format::decimal(f64::from(memory_max), 0))))
+        .push(chart::view(memory, Vec::new(), RANGE, memory_max, " MB"))
+        .into()
+}
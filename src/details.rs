@@ -0,0 +1,443 @@
+// details pane under the process table - everything known about the selected process
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+
+use std::time::Instant;
+
+use crate::cgroups::{self, Isolation};
+use crate::connections;
+use crate::format::{self, format_bytes};
+use crate::environment::{self, Difference, Variable};
+use crate::format::format_duration;
+use crate::integrity::{self, Integrity};
+use crate::limits;
+use crate::maps::{self, Region};
+use crate::oom;
+use crate::open_files::{self, FileKind, OpenFile};
+use crate::process::ProcessInfo;
+use crate::sockets::{self, Socket};
+use crate::threads::{self, ThreadColumn, ThreadInfo, ThreadSampler};
+use crate::Message;
+
+// height of the pane, the process table gets the rest
+pub const PANE_HEIGHT: f32 = 260.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailSection {
+    // defines the sections of the details pane
+    General,
+    Threads,
+    // open files and sockets
+    Resources,
+    // tcp/udp sockets with addresses and state
+    Connections,
+    Environment,
+    // cgroup limits and namespaces
+    Cgroup,
+    // mapped files and memory regions
+    MemoryMap,
+    // hash and signature of the executable
+    Integrity,
+}
+
+impl DetailSection {
+    pub const ALL: [DetailSection; 8] = [
+        DetailSection::General,
+        DetailSection::Threads,
+        DetailSection::Resources,
+        DetailSection::Connections,
+        DetailSection::Environment,
+        DetailSection::Cgroup,
+        DetailSection::MemoryMap,
+        DetailSection::Integrity,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            DetailSection::General => "General",
+            DetailSection::Threads => "Threads",
+            DetailSection::Resources => "Resources",
+            DetailSection::Connections => "Connections",
+            DetailSection::Environment => "Environment",
+            DetailSection::Cgroup => "Cgroup",
+            DetailSection::MemoryMap => "Memory map",
+            DetailSection::Integrity => "Integrity",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DetailsPane {
+    // closed by the user, reopened from the toolbar
+    pub hidden: bool,
+    pub section: DetailSection,
+    // threads of the selected process, Err where they can't be listed
+    pub threads: Result<Vec<ThreadInfo>, String>,
+    pub thread_sort_column: ThreadColumn,
+    pub thread_sort_ascending: bool,
+    sampler: ThreadSampler,
+    // open descriptors and sockets of the selected process, and the text filtering both
+    pub open_files: Result<Vec<OpenFile>, String>,
+    pub sockets: Result<Vec<Socket>, String>,
+    pub resource_filter: String,
+    // environment variables of the selected process and the text searching them
+    pub environment: Result<Vec<Variable>, String>,
+    pub environment_filter: String,
+    // only the variables that differ from the parent's, and the parent's pid and variables,
+    // only read while that is shown
+    pub environment_diff: bool,
+    pub parent_environment: Option<(u32, Result<Vec<Variable>, String>)>,
+    // cgroup and namespaces of the selected process
+    pub isolation: Result<Isolation, String>,
+    // the oom_score_adj of the selected process and the value being typed, see oom.rs
+    pub oom_adj: Option<i32>,
+    pub oom_adj_input: String,
+    // whether the selected process is in a job object, windows only
+    pub in_job: Option<bool>,
+    pub regions: Result<Vec<Region>, String>,
+    // the last executable check by pid, and the pid being checked. only run on request as it
+    // reads the whole file
+    pub integrity: Option<(u32, Result<Integrity, String>)>,
+    pub checking: Option<u32>,
+}
+
+impl Default for DetailsPane {
+    fn default() -> Self {
+        DetailsPane {
+            hidden: false,
+            section: DetailSection::General,
+            threads: Ok(Vec::new()),
+            thread_sort_column: ThreadColumn::Cpu,
+            thread_sort_ascending: false,
+            sampler: ThreadSampler::default(),
+            open_files: Ok(Vec::new()),
+            sockets: Ok(Vec::new()),
+            resource_filter: String::new(),
+            environment: Ok(Vec::new()),
+            environment_filter: String::new(),
+            environment_diff: false,
+            parent_environment: None,
+            isolation: Ok(Isolation::default()),
+            oom_adj: None,
+            oom_adj_input: String::new(),
+            in_job: None,
+            regions: Ok(Vec::new()),
+            integrity: None,
+            checking: None,
+        }
+    }
+}
+
+impl DetailsPane {
+    // refreshes the per-process data of the section that is shown
+    pub fn refresh(&mut self, pid: u32, now: Instant) {
+        if self.hidden {
+            return;
+        }
+        match self.section {
+            DetailSection::General | DetailSection::Integrity => {}
+            DetailSection::Threads => {
+                self.threads = self.sampler.sample(pid, now);
+                self.sort_threads();
+            }
+            DetailSection::Resources => {
+                self.open_files = open_files::for_pid(pid);
+                self.sockets = sockets::for_pid(pid);
+            }
+            DetailSection::Connections => self.sockets = sockets::for_pid(pid),
+            DetailSection::Environment => {
+                self.environment = environment::for_pid(pid);
+                self.parent_environment = if self.environment_diff { environment::parent(pid) } else { None };
+            }
+            DetailSection::Cgroup => {
+                self.isolation = cgroups::for_pid(pid);
+                self.oom_adj = oom::adj(pid);
+                self.in_job = limits::in_job(pid);
+            }
+            DetailSection::MemoryMap => self.regions = maps::for_pid(pid),
+        }
+    }
+
+    // sorts by the column, toggling the order if it is already the sort column
+    pub fn sort_threads_by(&mut self, column: ThreadColumn) {
+        if self.thread_sort_column == column {
+            self.thread_sort_ascending = !self.thread_sort_ascending;
+        } else {
+            self.thread_sort_column = column;
+            self.thread_sort_ascending = true;
+        }
+        self.sort_threads();
+    }
+
+    fn sort_threads(&mut self) {
+        if let Ok(threads) = &mut self.threads {
+            threads::sort(threads, self.thread_sort_column, self.thread_sort_ascending);
+        }
+    }
+}
+
+// construct the pane for the selected process
+// `network` is whether the settings allow lookups that leave the machine
+pub fn view<'a>(pane: &'a DetailsPane, process: &'a ProcessInfo, network: bool) -> Element<'a, Message> {
+    let sections = DetailSection::ALL.iter().fold(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(format!("{} ({})", process.name, process.pid)).width(Length::Fill)),
+        |row, &section| {
+            let button = Button::new(section.label());
+            row.push(if section == pane.section {
+                button
+            } else {
+                button.on_press(Message::SelectDetailSection(section))
+            })
+        },
+    )
+    .push(Button::new("Hide").on_press(Message::ToggleDetails));
+    let body = match pane.section {
+        DetailSection::General => general_view(process),
+        DetailSection::Threads => threads_view(pane),
+        DetailSection::Resources => resources_view(pane),
+        DetailSection::Connections => connections::process_view(&pane.sockets),
+        DetailSection::Environment => environment_view(pane),
+        DetailSection::Cgroup => cgroup_view(pane, process),
+        DetailSection::MemoryMap => maps::view(&pane.regions),
+        DetailSection::Integrity => integrity::view(
+            process,
+            pane.integrity.as_ref().filter(|(pid, _)| *pid == process.pid).map(|(_, checked)| checked),
+            pane.checking == Some(process.pid),
+            network,
+        ),
+    };
+    Column::new()
+        .spacing(10)
+        .push(sections)
+        .push(body)
+        .height(Length::Fixed(PANE_HEIGHT))
+        .into()
+}
+
+pub fn general_view(process: &ProcessInfo) -> Element<'_, Message> {
+    let unknown = || String::from("n/a");
+    let fields = [
+        ("User", process.user.clone()),
+        ("Command line", process.cmd.clone()),
+        ("Executable", process.exe.as_ref().map(|exe| exe.display().to_string()).unwrap_or_else(unknown)),
+        ("Working directory", process.cwd.as_ref().map(|cwd| cwd.display().to_string()).unwrap_or_else(unknown)),
+        ("Memory", format!("{} MB", process.memory)),
+        ("CPU", format!("{}% (raw {}%)", format::decimal(f64::from(process.cpu), 1), format::decimal(f64::from(process.cpu_raw), 1))),
+        ("Priority", process.nice.map(|nice| nice.to_string()).unwrap_or_else(unknown)),
+        ("CPU time", process.cpu_time.map(format_duration).unwrap_or_else(unknown)),
+        ("Running for", format_duration(process.uptime)),
+    ];
+    let rows = fields.into_iter().fold(Column::new().spacing(5), |column, (label, value)| {
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(label).width(Length::Fixed(120.0)))
+                .push(Text::new(value))
+        )
+    });
+    Scrollable::new(rows).into()
+}
+
+fn threads_view(pane: &DetailsPane) -> Element<'_, Message> {
+    let threads = match &pane.threads {
+        Ok(threads) => threads,
+        Err(err) => return Text::new(err).into(),
+    };
+    // displays buttons for sorting the thread list
+    let header = Row::new()
+        .spacing(10)
+        .push(Button::new("TID").on_press(Message::SortThreads(ThreadColumn::Tid)).width(Length::FillPortion(1)))
+        .push(Button::new("Name").on_press(Message::SortThreads(ThreadColumn::Name)).width(Length::FillPortion(2)))
+        .push(Button::new("State").on_press(Message::SortThreads(ThreadColumn::State)).width(Length::FillPortion(1)))
+        .push(Button::new("CPU (%)").on_press(Message::SortThreads(ThreadColumn::Cpu)).width(Length::FillPortion(1)));
+    let rows = threads.iter().fold(Column::new().spacing(5), |column, thread| {
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(thread.tid.to_string()).width(Length::FillPortion(1)))
+                .push(Text::new(&thread.name).width(Length::FillPortion(2)))
+                .push(Text::new(&thread.state).width(Length::FillPortion(1)))
+                .push(Text::new(format::decimal(f64::from(thread.cpu), 1)).width(Length::FillPortion(1)))
+        )
+    });
+    Column::new()
+        .spacing(5)
+        .push(Text::new(format!("{} threads", threads.len())))
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
+
+// open files and sockets, the filter matches any part of the path or address
+fn resources_view(pane: &DetailsPane) -> Element<'_, Message> {
+    let needle = pane.resource_filter.to_lowercase();
+    let matches = |text: &str| text.to_lowercase().contains(&needle);
+    let mut rows = Column::new().spacing(5);
+    match &pane.open_files {
+        Ok(files) => {
+            for file in files.iter().filter(|file| file.kind != FileKind::Socket && matches(&file.target)) {
+                rows = rows.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new(file.fd.to_string()).width(Length::Fixed(60.0)))
+                        .push(Text::new(file.kind.label()).width(Length::Fixed(80.0)))
+                        .push(Text::new(&file.target))
+                );
+            }
+        }
+        Err(err) => rows = rows.push(Text::new(format!("Open files unavailable: {}", err))),
+    }
+    match &pane.sockets {
+        Ok(sockets) => {
+            for socket in sockets {
+                let local = socket.local.to_string();
+                let remote = socket.remote.to_string();
+                if !matches(&local) && !matches(&remote) && !matches(socket.protocol) {
+                    continue;
+                }
+                rows = rows.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new("").width(Length::Fixed(60.0)))
+                        .push(Text::new(socket.protocol).width(Length::Fixed(80.0)))
+                        .push(Text::new(format!("{} -> {} {}", local, remote, socket.state)))
+                );
+            }
+        }
+        Err(err) => rows = rows.push(Text::new(format!("Sockets unavailable: {}", err))),
+    }
+    Column::new()
+        .spacing(5)
+        .push(
+            TextInput::new("Filter by path or address", &pane.resource_filter)
+                .on_input(Message::ResourceFilterChanged)
+                .width(Length::Fixed(300.0))
+        )
+        .push(Scrollable::new(rows))
+        .into()
+}
+
+// variables matching the search, each with a button copying it as KEY=value
+fn environment_view(pane: &DetailsPane) -> Element<'_, Message> {
+    let variables = match &pane.environment {
+        Ok(variables) => variables,
+        Err(err) => return Text::new(format!("Environment unavailable: {}", err)).into(),
+    };
+    let diff = Checkbox::new("Only differences from the parent", pane.environment_diff).on_toggle(Message::ToggleEnvironmentDiff);
+    if pane.environment_diff {
+        return Column::new().spacing(5).push(diff).push(environment_diff_view(pane, variables)).into();
+    }
+    let shown: Vec<&Variable> = variables.iter().filter(|variable| variable.matches(&pane.environment_filter)).collect();
+    let rows = shown.iter().fold(Column::new().spacing(5), |column, variable| {
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Button::new("Copy").on_press(Message::CopyText(variable.line())))
+                .push(Text::new(&variable.key).width(Length::Fixed(220.0)))
+                .push(Text::new(&variable.value))
+        )
+    });
+    let summary = if variables.is_empty() {
+        String::from("No variables readable, other users' processes need root")
+    } else {
+        format!("{} of {} variables", shown.len(), variables.len())
+    };
+    let all = shown.iter().map(|variable| variable.line()).collect::<Vec<_>>().join("\n");
+    Column::new()
+        .spacing(5)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    TextInput::new("Search names and values", &pane.environment_filter)
+                        .on_input(Message::EnvironmentFilterChanged)
+                        .width(Length::Fixed(300.0))
+                )
+                .push(Button::new("Copy shown").on_press_maybe((!shown.is_empty()).then_some(Message::CopyText(all))))
+                .push(Text::new(summary))
+                .push(diff)
+        )
+        .push(Scrollable::new(rows))
+        .into()
+}
+
+// what the process has that its parent doesn't, lacks or has set otherwise
+fn environment_diff_view<'a>(pane: &'a DetailsPane, variables: &[Variable]) -> Element<'a, Message> {
+    let (parent, parent_variables) = match &pane.parent_environment {
+        Some((parent, Ok(parent_variables))) => (*parent, parent_variables),
+        Some((parent, Err(err))) => return Text::new(format!("Environment of the parent {} unavailable: {}", parent, err)).into(),
+        None => return Text::new("The process has no parent to compare with").into(),
+    };
+    let differences = environment::diff(variables, parent_variables);
+    let shown: Vec<&Difference> = differences.iter().filter(|difference| difference.matches(&pane.environment_filter)).collect();
+    let rows = shown.iter().fold(Column::new().spacing(5), |column, difference| {
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(difference.key.clone()).width(Length::Fixed(220.0)))
+                .push(Text::new(difference.value.clone().unwrap_or_default()).width(Length::FillPortion(2)))
+                .push(Text::new(difference.describe()).width(Length::FillPortion(2)))
+        )
+    });
+    Column::new()
+        .spacing(5)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    TextInput::new("Search names and values", &pane.environment_filter)
+                        .on_input(Message::EnvironmentFilterChanged)
+                        .width(Length::Fixed(300.0))
+                )
+                .push(Text::new(format!("{} of {} variables differ from the parent {}", shown.len(), differences.len(), parent)))
+        )
+        .push(Scrollable::new(rows))
+        .into()
+}
+
+// the group's path and limits, then one line per namespace
+fn cgroup_view<'a>(pane: &'a DetailsPane, process: &ProcessInfo) -> Element<'a, Message> {
+    let mut top = Column::new().spacing(5).push(oom::view(process.pid, process.oom_score, pane.oom_adj, &pane.oom_adj_input));
+    if let Some(in_job) = pane.in_job {
+        top = top.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new("Job object").width(Length::Fixed(120.0)))
+                .push(Text::new(if in_job { "inside a job object" } else { "none" })),
+        );
+    }
+    let isolation = match &pane.isolation {
+        Ok(isolation) => isolation,
+        Err(err) => return top.push(Text::new(format!("Cgroup unavailable: {}", err))).into(),
+    };
+    let unlimited = || String::from("no limit");
+    let memory = match (isolation.memory_current, isolation.memory_limit) {
+        (Some(current), Some(limit)) => {
+            let near = if isolation.near_memory_limit() { ", near the limit" } else { "" };
+            format!("{} of {} ({:.0}%{})", format_bytes(current as f64), format_bytes(limit as f64), current as f64 / limit as f64 * 100.0, near)
+        }
+        (Some(current), None) => format!("{}, no limit", format_bytes(current as f64)),
+        (None, limit) => limit.map(|limit| format!("limit {}", format_bytes(limit as f64))).unwrap_or_else(unlimited),
+    };
+    let mut fields = vec![
+        ("Cgroup", isolation.cgroup.clone().unwrap_or_else(|| String::from("n/a"))),
+        ("Memory", memory),
+        ("CPU quota", isolation.cpu_quota.map(|cpus| format!("{:.2} CPUs", cpus)).unwrap_or_else(unlimited)),
+    ];
+    for &(name, id, separate) in &isolation.namespaces {
+        fields.push((name, if separate { format!("{} (separate from init)", id) } else { id.to_string() }));
+    }
+    let rows = fields.into_iter().fold(top, |column, (label, value)| {
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(label).width(Length::Fixed(120.0)))
+                .push(Text::new(value))
+        )
+    });
+    Scrollable::new(rows).into()
+}
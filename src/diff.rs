@@ -0,0 +1,152 @@
+// differences between two process snapshots, by pid, and applying a snapshot to the table
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::process::{self, ProcessInfo, SortColumn};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessDiff {
+    // pids only in the new snapshot, in its order
+    pub added: Vec<u32>,
+    // pids only in the old snapshot, in its order
+    pub removed: Vec<u32>,
+    // pids in both whose name, memory or cpu changed, in the new snapshot's order
+    pub changed: Vec<u32>,
+}
+
+// compares two snapshots, pids are expected to be unique within each
+pub fn diff(old: &[ProcessInfo], new: &[ProcessInfo]) -> ProcessDiff {
+    let old_by_pid: HashMap<u32, &ProcessInfo> = old.iter().map(|process| (process.pid, process)).collect();
+    let new_by_pid: HashMap<u32, &ProcessInfo> = new.iter().map(|process| (process.pid, process)).collect();
+    let mut result = ProcessDiff::default();
+    for process in new {
+        match old_by_pid.get(&process.pid) {
+            None => result.added.push(process.pid),
+            Some(previous) if differs(previous, process) => result.changed.push(process.pid),
+            Some(_) => {}
+        }
+    }
+    result.removed = old
+        .iter()
+        .map(|process| process.pid)
+        .filter(|pid| !new_by_pid.contains_key(pid))
+        .collect();
+    result
+}
+
+// compares bit patterns so a NaN cpu reading equals itself
+fn differs(a: &ProcessInfo, b: &ProcessInfo) -> bool {
+    a.name != b.name || a.memory != b.memory || a.cpu.to_bits() != b.cpu.to_bits()
+}
+
+// what applying a fresh snapshot changed in the table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Applied {
+    // pids new to the table, appended in the snapshot's order
+    pub added: Vec<u32>,
+    // a row was added or a value compared by the sort keys changed, so the order may be off
+    pub reorder: bool,
+}
+
+// updates the table in place: rows of exited processes are dropped, the others keep their
+// position and take the new values, new processes go last
+pub fn apply(table: &mut Vec<ProcessInfo>, fresh: Vec<ProcessInfo>, keys: &[SortColumn]) -> Applied {
+    let order: Vec<u32> = fresh.iter().map(|process| process.pid).collect();
+    let mut fresh: HashMap<u32, ProcessInfo> = fresh.into_iter().map(|process| (process.pid, process)).collect();
+    let mut applied = Applied::default();
+    table.retain_mut(|row| match fresh.remove(&row.pid) {
+        Some(new) => {
+            applied.reorder |= keys.iter().any(|&key| process::compare(row, &new, key) != Ordering::Equal);
+            *row = new;
+            true
+        }
+        None => false,
+    });
+    for pid in order {
+        if let Some(new) = fresh.remove(&pid) {
+            applied.added.push(pid);
+            table.push(new);
+        }
+    }
+    applied.reorder |= !applied.added.is_empty();
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    use std::collections::HashSet;
+
+    use crate::provider::mock;
+
+    // up to 40 processes with unique pids picked from a small range so snapshots overlap
+    fn snapshot() -> impl Strategy<Value = Vec<ProcessInfo>> {
+        prop::collection::hash_map(0u32..60, (0u64..4, prop::num::f32::ANY), 0..40).prop_map(|processes| {
+            processes
+                .into_iter()
+                .map(|(pid, (memory, cpu))| mock::process(pid, "p", "u", memory, cpu))
+                .collect()
+        })
+    }
+
+    fn pids(processes: &[ProcessInfo]) -> HashSet<u32> {
+        processes.iter().map(|process| process.pid).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn diff_against_itself_is_empty(snapshot in snapshot()) {
+            prop_assert_eq!(diff(&snapshot, &snapshot), ProcessDiff::default());
+        }
+
+        // old minus removed plus added is exactly the new pid set, with nothing counted twice
+        #[test]
+        fn diff_reconstructs_the_new_pids(old in snapshot(), new in snapshot()) {
+            let result = diff(&old, &new);
+            let mut reconstructed = pids(&old);
+            for pid in &result.removed {
+                prop_assert!(reconstructed.remove(pid));
+            }
+            for pid in &result.added {
+                prop_assert!(reconstructed.insert(*pid));
+            }
+            prop_assert_eq!(reconstructed, pids(&new));
+            for pid in &result.changed {
+                prop_assert!(pids(&old).contains(pid) && pids(&new).contains(pid));
+                prop_assert!(!result.added.contains(pid) && !result.removed.contains(pid));
+            }
+        }
+
+        // the table ends up with exactly the new rows, and needs no sort if nothing moved
+        #[test]
+        fn applying_a_snapshot_yields_its_rows(old in snapshot(), new in snapshot()) {
+            let mut table = old.clone();
+            let applied = apply(&mut table, new.clone(), &[SortColumn::Memory]);
+            prop_assert_eq!(pids(&table), pids(&new));
+            prop_assert_eq!(applied.added, diff(&old, &new).added);
+            for row in &table {
+                let expected = new.iter().find(|process| process.pid == row.pid).unwrap();
+                prop_assert_eq!(row.memory, expected.memory);
+            }
+            let mut same = new.clone();
+            prop_assert!(!apply(&mut same, new, &[SortColumn::Memory]).reorder);
+        }
+
+        // the diff of a sequence of snapshots accounts for every pid ever seen without ghost rows
+        #[test]
+        fn chained_diffs_track_the_live_set(snapshots in prop::collection::vec(snapshot(), 1..6)) {
+            let mut live: HashSet<u32> = HashSet::new();
+            let mut previous: Vec<ProcessInfo> = Vec::new();
+            for snapshot in &snapshots {
+                let result = diff(&previous, snapshot);
+                live.retain(|pid| !result.removed.contains(pid));
+                live.extend(&result.added);
+                prop_assert_eq!(&live, &pids(snapshot));
+                previous = snapshot.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// disks tab - lists mounted disks with space usage and read/write activity
+use iced::{
+    widget::{Column, ProgressBar, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use sysinfo::Disks;
+
+use std::collections::HashMap;
+
+use crate::format::format_bytes;
+use crate::Message;
+
+#[derive(Debug, Clone)]
+pub struct DiskInfo {
+    // holds info about each disk/partition
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub available: u64,
+    // bytes per second read/written since the previous refresh, None when the
+    // platform doesn't expose per-device counters
+    pub read_rate: Option<f64>,
+    pub write_rate: Option<f64>,
+}
+
+// cumulative bytes read and written per device, used to compute rates between refreshes
+pub type IoCounters = HashMap<String, (u64, u64)>;
+
+// builds the disk list from sysinfo, combining it with the I/O counters of the
+// previous refresh to work out read/write rates over `elapsed` seconds
+pub fn collect(disks: &Disks, previous: &IoCounters, current: &IoCounters, elapsed: f64) -> Vec<DiskInfo> {
+    disks
+        .iter()
+        .map(|disk| {
+            let name = disk.name().to_string_lossy().to_string();
+            let device = device_name(&name);
+            let (read_rate, write_rate) = match (previous.get(device), current.get(device)) {
+                (Some(before), Some(now)) if elapsed > 0.0 => (
+                    Some(now.0.saturating_sub(before.0) as f64 / elapsed),
+                    Some(now.1.saturating_sub(before.1) as f64 / elapsed),
+                ),
+                _ => (None, None),
+            };
+            DiskInfo {
+                name,
+                mount_point: disk.mount_point().display().to_string(),
+                file_system: disk.file_system().to_string_lossy().to_string(),
+                total: disk.total_space(),
+                available: disk.available_space(),
+                read_rate,
+                write_rate,
+            }
+        })
+        .collect()
+}
+
+// strips the /dev/ prefix so names match the kernel's device names
+fn device_name(name: &str) -> &str {
+    name.strip_prefix("/dev/").unwrap_or(name)
+}
+
+// reads cumulative read/write bytes per device from /proc/diskstats
+#[cfg(target_os = "linux")]
+pub fn read_io_counters() -> IoCounters {
+    // sector counts in diskstats are always in 512 byte units
+    const SECTOR_SIZE: u64 = 512;
+    let Ok(contents) = std::fs::read_to_string("/proc/diskstats") else {
+        return IoCounters::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // major minor name reads merged sectors_read ms writes merged sectors_written ...
+            if fields.len() < 10 {
+                return None;
+            }
+            let read = fields[5].parse::<u64>().ok()? * SECTOR_SIZE;
+            let written = fields[9].parse::<u64>().ok()? * SECTOR_SIZE;
+            Some((fields[2].to_string(), (read, written)))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_io_counters() -> IoCounters {
+    IoCounters::new()
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    match rate {
+        Some(rate) => format!("{}/s", format_bytes(rate)),
+        None => String::from("n/a"),
+    }
+}
+
+// construct the disks tab layout
+pub fn view(disks: &[DiskInfo]) -> Element<'_, Message> {
+    let header = Row::new()
+        .spacing(10)
+        .push(Text::new("Disk").width(Length::FillPortion(2)))
+        .push(Text::new("Mounted on").width(Length::FillPortion(2)))
+        .push(Text::new("Filesystem").width(Length::FillPortion(1)))
+        .push(Text::new("Used / Total").width(Length::FillPortion(2)))
+        .push(Text::new("Usage").width(Length::FillPortion(2)))
+        .push(Text::new("Read").width(Length::FillPortion(1)))
+        .push(Text::new("Write").width(Length::FillPortion(1)));
+    // displays each disk in a row with a usage bar
+    let rows = disks.iter().fold(
+        Column::new().spacing(5),
+        |column, disk| {
+            let used = disk.total.saturating_sub(disk.available);
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(&disk.name).width(Length::FillPortion(2)))
+                    .push(Text::new(&disk.mount_point).width(Length::FillPortion(2)))
+                    .push(Text::new(&disk.file_system).width(Length::FillPortion(1)))
+                    .push(Text::new(format!("{} / {}", format_bytes(used as f64), format_bytes(disk.total as f64))).width(Length::FillPortion(2)))
+                    .push(ProgressBar::new(0.0..=disk.total.max(1) as f32, used as f32).height(Length::Fixed(16.0)).width(Length::FillPortion(2)))
+                    .push(Text::new(format_rate(disk.read_rate)).width(Length::FillPortion(1)))
+                    .push(Text::new(format_rate(disk.write_rate)).width(Length::FillPortion(1)))
+            )
+        },
+    );
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
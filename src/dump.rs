@@ -0,0 +1,174 @@
+// dump and kill action - saves a core dump (minidump on windows) of a hung process to a
+// directory before killing it, so the hang can be debugged afterwards
+//
+// unix uses gdb's gcore, which leaves the process running until it is killed. without gcore
+// the process is sent SIGABRT instead, and the kernel writes the core wherever its
+// core_pattern says, if core dumps are enabled at all
+use iced::{
+    widget::{Button, Row, Text, TextInput},
+    Element, Length,
+};
+use sysinfo::{Pid, System};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::kill_guard::Target;
+use crate::provider::Identity;
+use crate::Message;
+
+// e.g. ~/conborli-dumps
+pub fn default_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(std::env::temp_dir).join("conborli-dumps")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpDialog {
+    pub target: Target,
+    // directory typed into the dialog, remembered in the config once a dump is written
+    pub dir: String,
+}
+
+impl DumpDialog {
+    pub fn new(target: Target, dir: &Path) -> DumpDialog {
+        DumpDialog { target, dir: dir.display().to_string() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dumped {
+    // the dump file, the process was killed afterwards
+    File(PathBuf),
+    // gcore is missing so the process was aborted, the core is up to the kernel
+    Aborted,
+}
+
+// characters of the process name that are safe in a file name on every platform
+fn file_stem(name: &str, pid: u32) -> String {
+    let name: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+    format!("{}-{}", name, pid)
+}
+
+// writes the dump and then kills the process, blocks for as long as the dump takes so run it
+// off the ui thread. the target passed the kill checks when it was confirmed, it is looked up
+// again before the dump and before the kill in case its pid went to another process meanwhile
+pub fn dump_and_kill(target: &Target, dir: PathBuf) -> Result<Dumped, String> {
+    let mut system = System::new();
+    let pid = Pid::from_u32(target.pid);
+    let mut still_there = || {
+        system.refresh_process(pid);
+        system
+            .process(pid)
+            .filter(|process| Identity { name: process.name().to_string(), start_time: process.start_time() }.is(&target.identity))
+            .is_some()
+    };
+    if !still_there() {
+        return Err(format!("{} exited before it was dumped", target.label()));
+    }
+    fs::create_dir_all(&dir).map_err(|err| format!("failed to create {}: {}", dir.display(), err))?;
+    let dumped = write_dump(target.pid, &dir.join(file_stem(&target.identity.name, target.pid)))?;
+    if let Dumped::File(_) = dumped {
+        if !still_there() {
+            return Err(format!("dumped, but {} exited before it was killed", target.label()));
+        }
+        if !system.process(pid).is_some_and(|process| process.kill()) {
+            return Err(format!("dumped, but failed to kill {}", target.label()));
+        }
+    }
+    Ok(dumped)
+}
+
+#[cfg(unix)]
+fn write_dump(pid: u32, stem: &Path) -> Result<Dumped, String> {
+    use std::io::ErrorKind;
+    use std::process::Command;
+
+    use crate::signals;
+
+    // linux's gcore appends the pid to the name it is given, macOS's writes exactly that path
+    let (prefix, path) = if cfg!(target_os = "linux") {
+        (stem.to_path_buf(), stem.with_extension(pid.to_string()))
+    } else {
+        let path = stem.with_extension("core");
+        (path.clone(), path)
+    };
+    let output = Command::new("gcore").arg("-o").arg(&prefix).arg(pid.to_string()).output();
+    match output {
+        Ok(output) if output.status.success() && path.exists() => Ok(Dumped::File(path)),
+        Ok(output) => Err(format!("gcore failed: {}", String::from_utf8_lossy(&output.stderr).trim())),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            let abort = signals::ALL.iter().find(|signal| signal.number == libc::SIGABRT).expect("SIGABRT is listed");
+            signals::send(pid, *abort).map(|()| Dumped::Aborted)
+        }
+        Err(err) => Err(format!("failed to run gcore: {}", err)),
+    }
+}
+
+#[cfg(windows)]
+fn write_dump(pid: u32, stem: &Path) -> Result<Dumped, String> {
+    use std::os::windows::io::AsRawHandle;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::Diagnostics::Debug::{MiniDumpWithFullMemory, MiniDumpWriteDump};
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
+
+    let path = stem.with_extension("dmp");
+    let file = fs::File::create(&path).map_err(|err| format!("failed to create {}: {}", path.display(), err))?;
+    let process = unsafe { OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid) };
+    if process == 0 {
+        return Err(format!("failed to open process {}: {}", pid, std::io::Error::last_os_error()));
+    }
+    let written = unsafe {
+        MiniDumpWriteDump(
+            process,
+            pid,
+            file.as_raw_handle() as HANDLE,
+            MiniDumpWithFullMemory,
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+        )
+    };
+    let error = std::io::Error::last_os_error();
+    unsafe { CloseHandle(process) };
+    if written == 0 {
+        drop(file);
+        let _ = fs::remove_file(&path);
+        return Err(format!("failed to write the minidump: {}", error));
+    }
+    Ok(Dumped::File(path))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn write_dump(_pid: u32, _stem: &Path) -> Result<Dumped, String> {
+    Err(String::from("dumps are not supported on this platform"))
+}
+
+// construct the dialog asking where the dump goes
+pub fn view(dialog: &DumpDialog) -> Element<'_, Message> {
+    Row::new()
+        .spacing(10)
+        .push(Text::new(format!("Dump {} and kill it, into", dialog.target.label())))
+        .push(
+            TextInput::new("directory", &dialog.dir)
+                .on_input(Message::DumpDirChanged)
+                .on_submit(Message::ConfirmDump)
+                .width(Length::Fixed(350.0))
+        )
+        .push(Button::new("Dump and kill").on_press_maybe((!dialog.dir.trim().is_empty()).then_some(Message::ConfirmDump)))
+        .push(Button::new("Cancel").on_press(Message::CloseDump))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_files_are_named_after_the_process() {
+        assert_eq!(file_stem("node", 42), "node-42");
+        assert_eq!(file_stem("Web Content/2", 7), "Web_Content_2-7");
+        let stem = Path::new("/tmp/dumps").join(file_stem("my.app", 9));
+        assert_eq!(stem.with_extension("9"), Path::new("/tmp/dumps/my_app-9.9"));
+    }
+}
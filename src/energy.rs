@@ -0,0 +1,186 @@
+// energy and App Nap columns on macOS, for triaging battery drain by energy instead of cpu
+//
+// the kernel bills each process for the energy its cpu time used (ri_billed_energy, in
+// nanojoules, Apple silicon only), so the column is the power drawn between two refreshes.
+// App Nap throttles a hidden idle app by putting it in the darwin background state, which
+// the process's bsd info flags show
+//
+// on linux, built with the `rapl` feature, the column is an estimate instead: the package
+// energy counters of the powercap interface (intel-rapl, which AMD cpus fill in too) give the
+// power of the whole cpu, which is split between processes by their share of the cpu usage.
+// the counters are root-only since linux 5.10
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::process::ProcessInfo;
+
+// the energy column is apportioned from the package power rather than measured per process
+pub const ESTIMATED: bool = cfg!(all(feature = "rapl", target_os = "linux"));
+
+const POWERCAP: &str = "/sys/class/powercap";
+
+// previous energy reading per pid
+#[derive(Debug, Default)]
+pub struct EnergySampler {
+    last: HashMap<u32, (u64, Instant)>,
+    // previous reading of each rapl package, in microjoules
+    packages: HashMap<PathBuf, (u64, Instant)>,
+    // power of all cpu packages at the last refresh, in watts
+    pub package_watts: Option<f32>,
+}
+
+impl EnergySampler {
+    // fills in the energy and App Nap values of a fresh table, the first reading of a
+    // process has no energy value yet
+    pub fn fill(&mut self, processes: &mut [ProcessInfo], now: Instant) {
+        if ESTIMATED {
+            self.package_watts = self.read_packages(Path::new(POWERCAP), now);
+            if let Some(watts) = self.package_watts {
+                apportion(processes, watts);
+            }
+            return;
+        }
+        if !cfg!(target_os = "macos") {
+            return;
+        }
+        let mut current = HashMap::with_capacity(processes.len());
+        for process in processes.iter_mut() {
+            process.napping = napping(process.pid);
+            let Some(energy) = billed_energy(process.pid) else {
+                continue;
+            };
+            if let Some(&(last, at)) = self.last.get(&process.pid) {
+                process.energy = power_mw(last, energy, now.duration_since(at).as_secs_f64());
+            }
+            current.insert(process.pid, (energy, now));
+        }
+        self.last = current;
+    }
+
+    // the power of every package since the previous reading, none until two readings are in
+    // or when the counters can't be read
+    fn read_packages(&mut self, powercap: &Path, now: Instant) -> Option<f32> {
+        let mut watts = None;
+        let mut current = HashMap::new();
+        for package in packages(powercap) {
+            let Some(energy) = read_number(&package.join("energy_uj")) else {
+                continue;
+            };
+            if let Some(&(last, at)) = self.packages.get(&package) {
+                // the counter wraps at max_energy_range_uj
+                let used = match energy.checked_sub(last) {
+                    Some(used) => Some(used),
+                    None => read_number(&package.join("max_energy_range_uj")).map(|range| range.saturating_sub(last) + energy),
+                };
+                let seconds = now.duration_since(at).as_secs_f64();
+                if let Some(used) = used.filter(|_| seconds > 0.0) {
+                    *watts.get_or_insert(0.0) += (used as f64 / 1e6 / seconds) as f32;
+                }
+            }
+            current.insert(package, (energy, now));
+        }
+        self.packages = current;
+        watts
+    }
+}
+
+// the package domains, intel-rapl:0 and so on, without their core and dram subdomains
+fn packages(powercap: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(powercap) else {
+        return Vec::new();
+    };
+    let mut packages: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("intel-rapl:") && name.matches(':').count() == 1))
+        .collect();
+    packages.sort();
+    packages
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// splits the package power between processes by their cpu usage, in milliwatts
+pub fn apportion(processes: &mut [ProcessInfo], watts: f32) {
+    let total: f32 = processes.iter().map(|process| process.cpu.max(0.0)).sum();
+    for process in processes.iter_mut() {
+        process.energy = Some(if total > 0.0 { watts * 1000.0 * process.cpu.max(0.0) / total } else { 0.0 });
+    }
+}
+
+// milliwatts drawn between two readings in nanojoules
+pub fn power_mw(last: u64, energy: u64, seconds: f64) -> Option<f32> {
+    (seconds > 0.0 && energy >= last).then(|| ((energy - last) as f64 / seconds / 1e6) as f32)
+}
+
+#[cfg(target_os = "macos")]
+fn billed_energy(pid: u32) -> Option<u64> {
+    let mut info: libc::rusage_info_v4 = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::proc_pid_rusage(pid as libc::c_int, libc::RUSAGE_INFO_V4, &mut info as *mut libc::rusage_info_v4 as *mut libc::rusage_info_t)
+    };
+    (result == 0).then_some(info.ri_billed_energy)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn billed_energy(_pid: u32) -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn napping(pid: u32) -> Option<bool> {
+    // from sys/proc_info.h, set by the process itself and by the system respectively
+    const PROC_FLAG_DARWINBG: u32 = 0x8000;
+    const PROC_FLAG_EXT_DARWINBG: u32 = 0x10000;
+
+    let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+    let read = unsafe {
+        libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDTBSDINFO, 0, &mut info as *mut libc::proc_bsdinfo as *mut libc::c_void, size)
+    };
+    (read == size).then_some(info.pbi_flags & (PROC_FLAG_DARWINBG | PROC_FLAG_EXT_DARWINBG) != 0)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn napping(_pid: u32) -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_is_the_energy_difference_over_time() {
+        assert_eq!(power_mw(1_000_000_000, 3_000_000_000, 2.0), Some(1000.0));
+        // a reused pid reads less than before
+        assert_eq!(power_mw(5, 1, 2.0), None);
+        assert_eq!(power_mw(1, 5, 0.0), None);
+    }
+
+    #[test]
+    fn package_power_is_split_by_cpu_usage() {
+        let powercap = std::env::temp_dir().join(format!("conborli-rapl-{}", std::process::id()));
+        let package = powercap.join("intel-rapl:0");
+        fs::create_dir_all(package.join("intel-rapl:0:0")).unwrap();
+        fs::create_dir_all(powercap.join("intel-rapl:0:0")).unwrap();
+        fs::write(package.join("max_energy_range_uj"), "1000000000").unwrap();
+        fs::write(package.join("energy_uj"), "999000000").unwrap();
+        let mut sampler = EnergySampler::default();
+        let start = Instant::now();
+        assert_eq!(sampler.read_packages(&powercap, start), None);
+        // wrapped past the range, 3 J over 2 s
+        fs::write(package.join("energy_uj"), "2000000").unwrap();
+        let watts = sampler.read_packages(&powercap, start + std::time::Duration::from_secs(2));
+        fs::remove_dir_all(&powercap).unwrap();
+        assert_eq!(watts, Some(1.5));
+
+        let mut processes = [ProcessInfo { cpu: 30.0, ..ProcessInfo::default() }, ProcessInfo { cpu: 10.0, ..ProcessInfo::default() }];
+        apportion(&mut processes, 2.0);
+        assert_eq!(processes.map(|process| process.energy), [Some(1500.0), Some(500.0)]);
+    }
+}
@@ -0,0 +1,134 @@
+// environment variables of a process, shown in the details pane, all of them or only where
+// they differ from its parent's, e.g. what a launcher left out that the terminal had
+//
+// sysinfo only reads them when asked to, so the selected process is looked up in a system of
+// its own instead of slowing down every process refresh
+use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub key: String,
+    pub value: String,
+}
+
+impl Variable {
+    // "KEY=value", split at the first = since values may contain more of them
+    fn parse(entry: &str) -> Variable {
+        let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+        Variable { key: key.to_string(), value: value.to_string() }
+    }
+
+    // the search matches any part of the name or value, ignoring case
+    pub fn matches(&self, needle: &str) -> bool {
+        let needle = needle.to_lowercase();
+        self.key.to_lowercase().contains(&needle) || self.value.to_lowercase().contains(&needle)
+    }
+
+    // the line as a shell would take it
+    pub fn line(&self) -> String {
+        format!("{}={}", self.key, self.value)
+    }
+}
+
+// a variable set differently than in the parent, None where one of them doesn't have it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub key: String,
+    pub value: Option<String>,
+    pub parent: Option<String>,
+}
+
+impl Difference {
+    // the search matches the name or either value, ignoring case
+    pub fn matches(&self, needle: &str) -> bool {
+        let needle = needle.to_lowercase();
+        [Some(&self.key), self.value.as_ref(), self.parent.as_ref()].into_iter().flatten().any(|text| text.to_lowercase().contains(&needle))
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.value, &self.parent) {
+            (Some(_), None) => String::from("added"),
+            (None, Some(parent)) => format!("removed, parent has {}", parent),
+            (_, Some(parent)) => format!("changed, parent has {}", parent),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+// every variable sorted by name. other users' processes come back empty without root
+pub fn for_pid(pid: u32) -> Result<Vec<Variable>, String> {
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    if !system.refresh_process_specifics(pid, ProcessRefreshKind::new().with_environ(UpdateKind::Always)) {
+        return Err(format!("process {} is gone", pid));
+    }
+    let process = system.process(pid).ok_or_else(|| format!("process {} is gone", pid))?;
+    Ok(parse_all(process.environ()))
+}
+
+// the parent's pid and its variables, None for a process without a parent
+pub fn parent(pid: u32) -> Option<(u32, Result<Vec<Variable>, String>)> {
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    system.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    let parent = system.process(pid)?.parent()?.as_u32();
+    Some((parent, for_pid(parent)))
+}
+
+// the variables added, removed or changed against the parent's, by name
+pub fn diff(variables: &[Variable], parent: &[Variable]) -> Vec<Difference> {
+    let own: BTreeMap<&str, &str> = variables.iter().map(|variable| (variable.key.as_str(), variable.value.as_str())).collect();
+    let theirs: BTreeMap<&str, &str> = parent.iter().map(|variable| (variable.key.as_str(), variable.value.as_str())).collect();
+    let keys: BTreeSet<&str> = own.keys().chain(theirs.keys()).copied().collect();
+    keys.into_iter()
+        .filter(|key| own.get(key) != theirs.get(key))
+        .map(|key| Difference { key: key.to_string(), value: own.get(key).map(|value| value.to_string()), parent: theirs.get(key).map(|value| value.to_string()) })
+        .collect()
+}
+
+fn parse_all(entries: &[String]) -> Vec<Variable> {
+    let mut variables: Vec<Variable> = entries.iter().filter(|entry| !entry.is_empty()).map(|entry| Variable::parse(entry)).collect();
+    variables.sort_by(|a, b| a.key.cmp(&b.key));
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_split_at_the_first_equals_sign() {
+        let entries = [String::from("PATH=/bin:/usr/bin"), String::from("OPTS=a=b"), String::new(), String::from("EMPTY=")];
+        let variables = parse_all(&entries);
+        let keys: Vec<&str> = variables.iter().map(|variable| variable.key.as_str()).collect();
+        assert_eq!(keys, vec!["EMPTY", "OPTS", "PATH"]);
+        assert_eq!(variables[1].value, "a=b");
+        assert_eq!(variables[1].line(), "OPTS=a=b");
+        assert!(variables[2].matches("usr"));
+        assert!(variables[2].matches("path"));
+        assert!(!variables[0].matches("bin"));
+    }
+
+    #[test]
+    fn differences_against_the_parent() {
+        let own = parse_all(&[String::from("PATH=/opt/bin:/bin"), String::from("HOME=/root"), String::from("DEBUG=1")]);
+        let parent = parse_all(&[String::from("PATH=/bin"), String::from("HOME=/root"), String::from("DISPLAY=:0")]);
+        let differences = diff(&own, &parent);
+        let described: Vec<(&str, String)> = differences.iter().map(|difference| (difference.key.as_str(), difference.describe())).collect();
+        assert_eq!(
+            described,
+            vec![("DEBUG", String::from("added")), ("DISPLAY", String::from("removed, parent has :0")), ("PATH", String::from("changed, parent has /bin"))]
+        );
+        assert!(diff(&own, &own).is_empty());
+    }
+
+    #[test]
+    fn reads_our_own_environment() {
+        let variables = for_pid(std::process::id()).unwrap();
+        if let Some((key, _)) = std::env::vars().next() {
+            assert!(variables.iter().any(|variable| variable.key == key));
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// what the task manager did and saw, in order - processes starting and exiting, kills, rule
+// matches and errors. the events tab lists them and the performance charts mark the kills and
+// matches, so a spike can be matched with what caused or ended it
+use chrono::{DateTime, Local};
+use iced::{
+    alignment,
+    widget::{Button, Checkbox, Column, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use crate::crash;
+use crate::format;
+use crate::Message;
+
+// events kept, the oldest are dropped first
+const CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Started,
+    Exited,
+    // a process was killed, by the user, a rule, a script or a scheduled action
+    Kill,
+    // a rule matched a process without killing it
+    Alert,
+    // an action that failed, e.g. a renice without the rights to it
+    Error,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 5] = [EventKind::Started, EventKind::Exited, EventKind::Kill, EventKind::Alert, EventKind::Error];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Started => "Started",
+            EventKind::Exited => "Exited",
+            EventKind::Kill => "Kills",
+            EventKind::Alert => "Alerts",
+            EventKind::Error => "Errors",
+        }
+    }
+
+    // starts and exits are too many to mark on a chart
+    pub fn marks_charts(self) -> bool {
+        matches!(self, EventKind::Kill | EventKind::Alert)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub time: DateTime<Local>,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    // every event of each kind ever pushed, the log itself only keeps the last ones
+    totals: HashMap<EventKind, u64>,
+}
+
+impl Event {
+    // one line of the exported log, e.g. "2024-05-01 12:00:03  Kills  You killed firefox (30)"
+    pub fn line(&self) -> String {
+        format!("{}  {}  {}", self.time.format("%Y-%m-%d %H:%M:%S"), self.kind.label(), self.text)
+    }
+}
+
+impl EventLog {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Event> {
+        self.events.iter()
+    }
+
+    // writes the events of the kinds as text lines, oldest first
+    pub fn export(&self, kinds: &HashSet<EventKind>, path: &Path) -> Result<usize, String> {
+        let lines: Vec<String> = self.events.iter().filter(|event| kinds.contains(&event.kind)).map(Event::line).collect();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        let text: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(path, text).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(lines.len())
+    }
+
+    pub fn push(&mut self, kind: EventKind, text: impl Into<String>) {
+        self.push_at(Local::now(), kind, text);
+    }
+
+    pub fn push_at(&mut self, time: DateTime<Local>, kind: EventKind, text: impl Into<String>) {
+        let text = text.into();
+        match kind {
+            EventKind::Error => tracing::warn!("{}", text),
+            EventKind::Kill | EventKind::Alert => tracing::info!("{}", text),
+            EventKind::Started | EventKind::Exited => tracing::debug!("{} {}", kind.label(), text),
+        }
+        crash::remember(format!("{} {} {}", time.format("%H:%M:%S"), kind.label(), text));
+        *self.totals.entry(kind).or_default() += 1;
+        self.events.push_back(Event { time, kind, text });
+        if self.events.len() > CAPACITY {
+            self.events.pop_front();
+        }
+    }
+
+    pub fn total(&self, kind: EventKind) -> u64 {
+        self.totals.get(&kind).copied().unwrap_or(0)
+    }
+
+    // events from the time on, oldest first
+    pub fn since(&self, time: DateTime<Local>) -> impl Iterator<Item = &Event> {
+        let start = self.events.partition_point(|event| event.time < time);
+        self.events.range(start..)
+    }
+}
+
+// construct the events tab, newest first, only the kinds checked
+pub fn view<'a>(log: &'a EventLog, kinds: &HashSet<EventKind>, export_path: &str) -> Element<'a, Message> {
+    let filters = EventKind::ALL.iter().fold(Row::new().spacing(10), |row, &kind| {
+        row.push(Checkbox::new(kind.label(), kinds.contains(&kind)).on_toggle(move |shown| Message::ToggleEventKind(kind, shown)))
+    });
+    let export = Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(TextInput::new("text file", export_path).on_input(Message::EventExportPathChanged).width(Length::FillPortion(3)))
+        .push(Button::new("Export shown events").on_press(Message::ExportEvents));
+    let shown: Vec<&Event> = log.iter().rev().filter(|event| kinds.contains(&event.kind)).collect();
+    let list = if shown.is_empty() {
+        Column::new().push(Text::new("No events yet"))
+    } else {
+        shown.into_iter().fold(Column::new().spacing(2), |column, event| {
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(format::time(&event.time)).width(Length::Fixed(90.0)))
+                    .push(Text::new(event.kind.label()).width(Length::Fixed(70.0)))
+                    .push(Text::new(&event.text))
+            )
+        })
+    };
+    Column::new()
+        .spacing(10)
+        .push(filters)
+        .push(export)
+        .push(Scrollable::new(list).height(Length::Fill))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn since_skips_older_events_and_the_log_stays_bounded() {
+        let start = Local::now();
+        let mut log = EventLog::default();
+        for second in 0..CAPACITY as i64 + 10 {
+            log.push_at(start + chrono::Duration::seconds(second), EventKind::Kill, second.to_string());
+        }
+        assert_eq!(log.since(start).next().map(|event| event.text.as_str()), Some("10"));
+        let recent: Vec<&str> = log.since(start + chrono::Duration::seconds(CAPACITY as i64 + 8)).map(|event| event.text.as_str()).collect();
+        assert_eq!(recent, vec!["1008", "1009"]);
+    }
+}
@@ -0,0 +1,185 @@
+// executables tab - processes grouped by the path of their executable with per-executable
+// totals, so two programs that happen to share a name stay apart. processes whose
+// executable can't be read are grouped under their name instead
+use chrono::{DateTime, Local};
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::critical;
+use crate::format;
+use crate::kill_guard::Target;
+use crate::process::ProcessInfo;
+use crate::row_menu::RowAction;
+use crate::Message;
+
+// what the processes are grouped by
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Executable {
+    Path(PathBuf),
+    // the executable is unreadable, e.g. another user's process
+    Name(String),
+}
+
+impl Executable {
+    pub fn of(process: &ProcessInfo) -> Executable {
+        match &process.exe {
+            Some(path) => Executable::Path(path.clone()),
+            None => Executable::Name(process.name.clone()),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Executable::Path(path) => path.display().to_string(),
+            Executable::Name(name) => format!("{} (path unreadable)", name),
+        }
+    }
+}
+
+// when each executable was first seen running since launch, older ones are kept so a
+// program that restarts keeps its time
+#[derive(Debug, Default)]
+pub struct FirstSeen(HashMap<Executable, DateTime<Local>>);
+
+impl FirstSeen {
+    pub fn record(&mut self, processes: &[ProcessInfo], now: DateTime<Local>) {
+        for process in processes {
+            self.0.entry(Executable::of(process)).or_insert(now);
+        }
+    }
+
+    pub fn get(&self, executable: &Executable) -> Option<DateTime<Local>> {
+        self.0.get(executable).copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutableSummary<'a> {
+    pub executable: Executable,
+    pub processes: Vec<&'a ProcessInfo>,
+    pub memory: u64,
+    pub cpu: f32,
+}
+
+// groups processes by executable, the heaviest memory users first
+pub fn aggregate(processes: &[ProcessInfo]) -> Vec<ExecutableSummary<'_>> {
+    let mut executables: BTreeMap<Executable, ExecutableSummary> = BTreeMap::new();
+    for process in processes {
+        let executable = Executable::of(process);
+        let summary = executables.entry(executable.clone()).or_insert_with(|| ExecutableSummary {
+            executable,
+            processes: Vec::new(),
+            memory: 0,
+            cpu: 0.0,
+        });
+        summary.processes.push(process);
+        summary.memory += process.memory;
+        summary.cpu += process.cpu;
+    }
+    let mut summaries: Vec<ExecutableSummary> = executables.into_values().collect();
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.memory));
+    summaries
+}
+
+// construct the executables tab layout, an expanded executable lists its instances
+pub fn view<'a>(processes: &'a [ProcessInfo], expanded: &HashSet<Executable>, first_seen: &FirstSeen, critical: &[String]) -> Element<'a, Message> {
+    let header = Row::new()
+        .spacing(10)
+        .push(Text::new("Executable").width(Length::FillPortion(4)))
+        .push(Text::new("Instances").width(Length::FillPortion(1)))
+        .push(Text::new("Memory (MB)").width(Length::FillPortion(1)))
+        .push(Text::new("CPU (%)").width(Length::FillPortion(1)))
+        .push(Text::new("First seen").width(Length::FillPortion(1)));
+    let rows = aggregate(processes).into_iter().fold(Column::new().spacing(5), |column, summary| {
+        let is_expanded = expanded.contains(&summary.executable);
+        let toggle = if is_expanded { "▾" } else { "▸" };
+        let seen = first_seen.get(&summary.executable).map(|seen| format::time(&seen)).unwrap_or_default();
+        let mut column = column.push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    Button::new(Text::new(format!("{} {}", toggle, summary.executable.label())))
+                        .on_press(Message::ToggleExecutableExpanded(summary.executable.clone()))
+                        .width(Length::FillPortion(4))
+                )
+                .push(Text::new(summary.processes.len().to_string()).width(Length::FillPortion(1)))
+                .push(Text::new(summary.memory.to_string()).width(Length::FillPortion(1)))
+                .push(Text::new(format::decimal(f64::from(summary.cpu), 1)).width(Length::FillPortion(1)))
+                .push(Text::new(seen).width(Length::FillPortion(1)))
+        );
+        // the instances, indented under their summary row
+        if is_expanded {
+            for process in &summary.processes {
+                // critical processes go through the kill preview and its extra confirmation
+                let kill = if critical::is_critical(process, critical) {
+                    Message::RowAction(process.pid, RowAction::Kill)
+                } else {
+                    Message::KillProcess(Target::of(process))
+                };
+                column = column.push(
+                    Row::new()
+                        .spacing(10)
+                        .padding([0, 0, 0, 30])
+                        .push(Text::new(format!("{}  {}  {}", process.pid, process.name, process.user)).width(Length::FillPortion(4)))
+                        .push(Button::new("Show").on_press(Message::ShowProcess(process.pid)).width(Length::FillPortion(1)))
+                        .push(Text::new(process.memory.to_string()).width(Length::FillPortion(1)))
+                        .push(Text::new(format::decimal(f64::from(process.cpu), 1)).width(Length::FillPortion(1)))
+                        .push(Row::new().push(Button::new("Kill").on_press(kill)).width(Length::FillPortion(1)))
+                );
+            }
+        }
+        column
+    });
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_names_from_different_paths_stay_apart() {
+        let process = |pid: u32, exe: Option<&str>, memory: u64| ProcessInfo {
+            pid,
+            name: String::from("python"),
+            exe: exe.map(PathBuf::from),
+            memory,
+            cpu: 1.5,
+            ..ProcessInfo::default()
+        };
+        let processes = [
+            process(1, Some("/usr/bin/python"), 10),
+            process(2, Some("/opt/venv/bin/python"), 50),
+            process(3, Some("/usr/bin/python"), 30),
+            process(4, None, 5),
+        ];
+        let summaries = aggregate(&processes);
+        let totals: Vec<(String, usize, u64)> =
+            summaries.iter().map(|summary| (summary.executable.label(), summary.processes.len(), summary.memory)).collect();
+        assert_eq!(
+            totals,
+            vec![
+                (String::from("/opt/venv/bin/python"), 1, 50),
+                (String::from("/usr/bin/python"), 2, 40),
+                (String::from("python (path unreadable)"), 1, 5),
+            ]
+        );
+        assert_eq!(summaries[1].cpu, 3.0);
+
+        let mut first_seen = FirstSeen::default();
+        let launched = Local::now();
+        first_seen.record(&processes[..1], launched);
+        first_seen.record(&processes, launched + chrono::Duration::seconds(5));
+        assert_eq!(first_seen.get(&Executable::of(&processes[0])), Some(launched));
+        assert!(first_seen.get(&Executable::of(&processes[1])).is_some_and(|seen| seen > launched));
+    }
+}
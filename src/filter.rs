@@ -0,0 +1,229 @@
+// filtering of the process table by the search box and the low-activity thresholds
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::cell::RefCell;
+
+use crate::plugins;
+use crate::process::{ProcessInfo, SortColumn};
+use crate::query::Query;
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    // search box query, see query.rs for the syntax
+    pub text: String,
+    // host processes, containerized ones, or both
+    pub scope: Scope,
+    // only processes whose memory has been rising for a while, see history.rs
+    pub suspected_leaks: bool,
+    // only processes running as root or SYSTEM
+    pub elevated: bool,
+    // the text as last parsed, so a query is parsed once per change rather than once per row
+    parsed: RefCell<Option<(String, Result<Query, String>)>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scope {
+    #[default]
+    All,
+    Host,
+    Containers,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::All, Scope::Host, Scope::Containers];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::All => "All",
+            Scope::Host => "Host",
+            Scope::Containers => "Containers",
+        }
+    }
+}
+
+impl Filter {
+    // whether the process should be shown
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        let in_scope = match self.scope {
+            Scope::All => true,
+            Scope::Host => process.container.is_none(),
+            Scope::Containers => process.container.is_some(),
+        };
+        if !in_scope || (self.suspected_leaks && !process.suspected_leak) || (self.elevated && !process.elevated) {
+            return false;
+        }
+        if self.text.is_empty() {
+            return true;
+        }
+        self.with_query(|query| match query {
+            Ok(query) => query.matches(process),
+            // half-typed queries like "/chrom(" search for the text as it is meanwhile
+            Err(_) => {
+                let needle = self.text.to_lowercase();
+                process.name.to_lowercase().contains(&needle) || process.user.to_lowercase().contains(&needle)
+            }
+        })
+    }
+
+    // true while the query has words to order the rows by
+    pub fn ranks(&self) -> bool {
+        !self.text.is_empty() && self.with_query(|query| query.as_ref().is_ok_and(Query::ranks))
+    }
+
+    // true while the query looks for open files or ports, see holders.rs
+    pub fn looks_up_holders(&self) -> bool {
+        !self.text.is_empty() && self.with_query(|query| query.as_ref().is_ok_and(Query::looks_up_holders))
+    }
+
+    // how well the process matches the words, higher first
+    pub fn score(&self, process: &ProcessInfo) -> i32 {
+        self.with_query(|query| query.as_ref().map_or(0, |query| query.score(&process.name)))
+    }
+
+    // char indices of the name to highlight
+    pub fn name_matches(&self, name: &str) -> Vec<usize> {
+        if self.text.is_empty() {
+            return Vec::new();
+        }
+        self.with_query(|query| query.as_ref().map(|query| query.name_matches(name)).unwrap_or_default())
+    }
+
+    // why the text doesn't parse as a query, shown under the search box
+    pub fn error(&self) -> Option<String> {
+        self.with_query(|query| query.as_ref().err().cloned())
+    }
+
+    fn with_query<T>(&self, f: impl FnOnce(&Result<Query, String>) -> T) -> T {
+        let mut parsed = self.parsed.borrow_mut();
+        if parsed.as_ref().is_some_and(|(text, _)| *text != self.text) {
+            *parsed = None;
+        }
+        let (_, query) = parsed.get_or_insert_with(|| (self.text.clone(), Query::parse(&self.text)));
+        f(query)
+    }
+}
+
+// search, filters and sort saved under a name, picked from the processes tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    pub text: String,
+    #[serde(default)]
+    pub scope: Scope,
+    #[serde(default)]
+    pub suspected_leaks: bool,
+    #[serde(default)]
+    pub elevated: bool,
+    #[serde(deserialize_with = "plugins::known_column")]
+    pub sort_column: SortColumn,
+    pub sort_ascending: bool,
+}
+
+// process names left out of the table and the rules, compared ignoring case. the processes
+// are still collected, so watched programs and the api see them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IgnoreList(Vec<String>);
+
+impl IgnoreList {
+    pub fn ignores(&self, process: &ProcessInfo) -> bool {
+        self.0.iter().any(|name| name.eq_ignore_ascii_case(&process.name))
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn add(&mut self, name: &str) {
+        let name = name.trim();
+        if !name.is_empty() && !self.0.iter().any(|ignored| ignored.eq_ignore_ascii_case(name)) {
+            self.0.push(name.to_string());
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.0.retain(|ignored| ignored != name);
+    }
+}
+
+// a process kept above the sorted rows. a pid pin holds the start time too so it doesn't carry
+// over to a newer process that gets the pid, and it isn't saved: it is dropped when the process
+// exits, and nothing it pointed at is left after a restart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pin {
+    Name(String),
+    Pid { pid: u32, start_time: u64 },
+}
+
+impl Pin {
+    pub fn of_process(process: &ProcessInfo) -> Pin {
+        Pin::Pid { pid: process.pid, start_time: process.start_time }
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Pin::Name(name) => &process.name == name,
+            Pin::Pid { pid, start_time } => process.pid == *pid && process.start_time == *start_time,
+        }
+    }
+
+    // whether the pin still points at something in the table
+    pub fn alive(&self, processes: &[ProcessInfo]) -> bool {
+        match self {
+            Pin::Name(_) => true,
+            Pin::Pid { .. } => processes.iter().any(|process| self.matches(process)),
+        }
+    }
+}
+
+// only name pins are written to the config
+pub fn save_names<S: Serializer>(pinned: &[Pin], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(pinned.iter().filter(|pin| matches!(pin, Pin::Name(_))))
+}
+
+// pid pins of older configs are dropped instead of failing the whole config
+pub fn load_names<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Pin>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Name {
+            name: String,
+        },
+        Other(serde::de::IgnoredAny),
+    }
+    let entries = Vec::<Entry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Name { name } => Some(Pin::Name(name)),
+            Entry::Other(_) => None,
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseFilter {
+    // hides processes that are below both minimums
+    pub enabled: bool,
+    pub min_cpu: f32,
+    pub min_memory_mb: u64,
+}
+
+impl Default for NoiseFilter {
+    fn default() -> Self {
+        NoiseFilter {
+            enabled: false,
+            min_cpu: 0.1,
+            min_memory_mb: 10,
+        }
+    }
+}
+
+impl NoiseFilter {
+    // whether the process is idle enough to be hidden
+    pub fn hides(&self, process: &ProcessInfo) -> bool {
+        self.enabled && process.cpu < self.min_cpu && process.memory < self.min_memory_mb
+    }
+}
@@ -0,0 +1,113 @@
+// focus mode - desktop notifications and the alert sound are held back while it is on and
+// collected into a digest instead, shown as a badge in the footer until it is read. the
+// alerts still go to the event log as they fire, the digest only keeps what would have popped up
+use chrono::{DateTime, Local};
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use crate::format;
+use crate::notify;
+use crate::Message;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Held {
+    pub time: DateTime<Local>,
+    pub title: String,
+    pub body: String,
+    // a sound rather than a notification
+    pub sound: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Focus {
+    pub on: bool,
+    // the digest panel is shown
+    pub open: bool,
+    digest: Vec<Held>,
+}
+
+impl Focus {
+    // shows the notification, or keeps it for the digest
+    pub fn send(&mut self, title: &str, body: &str, now: DateTime<Local>) -> Result<(), String> {
+        if self.on {
+            self.digest.push(Held { time: now, title: title.to_string(), body: body.to_string(), sound: false });
+            return Ok(());
+        }
+        notify::send(title, body)
+    }
+
+    // plays the alert sound, or keeps what it was for in the digest
+    pub fn sound(&mut self, title: &str, body: &str, now: DateTime<Local>) -> Result<(), String> {
+        if self.on {
+            self.digest.push(Held { time: now, title: title.to_string(), body: body.to_string(), sound: true });
+            return Ok(());
+        }
+        notify::sound()
+    }
+
+    // leaving focus mode keeps the digest until it is read
+    pub fn toggle(&mut self) {
+        self.on = !self.on;
+    }
+
+    pub fn clear(&mut self) {
+        self.digest.clear();
+        self.open = false;
+    }
+}
+
+// the footer button, the number of held alerts once there are some
+pub fn badge(focus: &Focus) -> Element<'_, Message> {
+    let label = match (focus.on, focus.digest.len()) {
+        (true, 0) => String::from("Focus mode on"),
+        (true, held) => format!("Focus mode on, {} held", held),
+        (false, 0) => String::from("Focus mode"),
+        (false, held) => format!("{} alerts while focused", held),
+    };
+    let message = if focus.digest.is_empty() { Message::ToggleFocusMode } else { Message::ToggleFocusDigest };
+    Button::new(Text::new(label)).on_press(message).into()
+}
+
+// the held alerts, oldest first
+pub fn view(focus: &Focus) -> Element<'_, Message> {
+    let rows = focus.digest.iter().fold(Column::new().spacing(5), |column, held| {
+        let kind = if held.sound { "sound" } else { "notification" };
+        column.push(Text::new(format!("{}  {}: {} ({})", format::time(&held.time), held.title, held.body, kind)))
+    });
+    Column::new()
+        .spacing(10)
+        .push(
+            Row::new()
+                .spacing(10)
+                .align_items(alignment::Alignment::Center)
+                .push(Text::new(format!("{} alerts held back in focus mode", focus.digest.len())).width(Length::Fill))
+                .push(Button::new(if focus.on { "Leave focus mode" } else { "Focus mode" }).on_press(Message::ToggleFocusMode))
+                .push(Button::new("Show in events").on_press(Message::ShowAlertEvents))
+                .push(Button::new("Clear").on_press(Message::ClearFocusDigest))
+                .push(Button::new("Close").on_press(Message::ToggleFocusDigest)),
+        )
+        .push(Scrollable::new(rows).height(Length::Fixed(150.0)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alerts_are_held_only_in_focus_mode() {
+        let mut focus = Focus::default();
+        let now = Local::now();
+        focus.toggle();
+        focus.send("rule", "matched a (1)", now).unwrap();
+        focus.sound("loud", "matched b (2)", now).unwrap();
+        focus.toggle();
+        assert!(!focus.on);
+        assert_eq!(focus.digest.iter().map(|held| (held.title.as_str(), held.sound)).collect::<Vec<_>>(), vec![("rule", false), ("loud", true)]);
+        focus.clear();
+        assert!(focus.digest.is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+// shared helpers for turning numbers into display strings
+//
+// decimals, thousands and clock times follow a locale set once from the config: the system's
+// (from the LC_* and LANG variables) or one picked in the settings tab. values written to
+// files and the api are left alone
+use chrono::{DateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::sync::RwLock;
+
+// how decimals and thousands are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumberStyle {
+    #[default]
+    System,
+    // 1,234.5
+    Point,
+    // 1.234,5
+    Comma,
+}
+
+impl NumberStyle {
+    pub const ALL: [NumberStyle; 3] = [NumberStyle::System, NumberStyle::Point, NumberStyle::Comma];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NumberStyle::System => "System",
+            NumberStyle::Point => "1,234.5",
+            NumberStyle::Comma => "1.234,5",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Clock {
+    #[default]
+    System,
+    H24,
+    H12,
+}
+
+impl Clock {
+    pub const ALL: [Clock; 3] = [Clock::System, Clock::H24, Clock::H12];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Clock::System => "System",
+            Clock::H24 => "24 hour",
+            Clock::H12 => "12 hour",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Locale {
+    decimal: char,
+    grouping: char,
+    clock_24h: bool,
+}
+
+static LOCALE: RwLock<Locale> = RwLock::new(Locale { decimal: '.', grouping: ',', clock_24h: true });
+
+// languages writing a decimal comma, by their code in e.g. "de_DE.UTF-8"
+const COMMA_LANGUAGES: [&str; 24] = [
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv", "nb", "nl", "pl", "pt", "ro", "ru", "sv",
+    "uk",
+];
+
+// regions whose clock has am and pm
+const TWELVE_HOUR_REGIONS: [&str; 6] = ["en_US", "en_CA", "en_AU", "en_NZ", "en_PH", "en_IN"];
+
+// first set locale variable of the category, "C" where none is
+fn locale_variable(category: &str) -> String {
+    ["LC_ALL", category, "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_else(|| String::from("C"))
+}
+
+// applies the settings, "System" looks at the environment again
+pub fn set_locale(numbers: NumberStyle, clock: Clock) {
+    let comma = match numbers {
+        NumberStyle::System => {
+            let locale = locale_variable("LC_NUMERIC");
+            COMMA_LANGUAGES.iter().any(|language| locale.split(['_', '.', '@']).next() == Some(*language))
+        }
+        NumberStyle::Point => false,
+        NumberStyle::Comma => true,
+    };
+    let clock_24h = match clock {
+        Clock::System => {
+            let locale = locale_variable("LC_TIME");
+            !TWELVE_HOUR_REGIONS.iter().any(|region| locale.starts_with(region))
+        }
+        Clock::H24 => true,
+        Clock::H12 => false,
+    };
+    let locale = if comma { Locale { decimal: ',', grouping: '.', clock_24h } } else { Locale { decimal: '.', grouping: ',', clock_24h } };
+    if let Ok(mut current) = LOCALE.write() {
+        *current = locale;
+    }
+}
+
+fn locale() -> Locale {
+    LOCALE.read().map(|locale| *locale).unwrap_or(Locale { decimal: '.', grouping: ',', clock_24h: true })
+}
+
+// digits with the locale's thousands separator, a leading sign is kept
+fn group(digits: &str, separator: char) -> String {
+    let (sign, digits) = digits.split_at(digits.find(|c: char| c.is_ascii_digit()).unwrap_or(0));
+    let mut grouped = String::from(sign);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+fn localize(text: &str, locale: Locale) -> String {
+    match text.split_once('.') {
+        Some((whole, fraction)) => format!("{}{}{}", group(whole, locale.grouping), locale.decimal, fraction),
+        None => group(text, locale.grouping),
+    }
+}
+
+// e.g. 1,234.5 or 1.234,5
+pub fn decimal(value: f64, places: usize) -> String {
+    localize(&format!("{:.*}", places, value), locale())
+}
+
+// the same with a + before positive values, for growths and trends
+pub fn signed_decimal(value: f64, places: usize) -> String {
+    localize(&format!("{:+.*}", places, value), locale())
+}
+
+pub fn integer(value: u64) -> String {
+    localize(&value.to_string(), locale())
+}
+
+pub fn signed_integer(value: i64) -> String {
+    localize(&format!("{:+}", value), locale())
+}
+
+// hours, minutes and seconds of the time of day, e.g. 14:05:09 or 2:05:09 PM
+pub fn time<Tz: TimeZone>(at: &DateTime<Tz>) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    at.format(if locale().clock_24h { "%H:%M:%S" } else { "%-I:%M:%S %p" }).to_string()
+}
+
+// the date and the time of day, e.g. 2024-05-01 14:05:09
+pub fn date_time<Tz: TimeZone>(at: &DateTime<Tz>) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    format!("{} {}", at.format("%Y-%m-%d"), time(at))
+}
+
+// formats a byte count using binary units (KiB, MiB, ...)
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", value as u64, UNITS[unit])
+    } else {
+        format!("{} {}", decimal(value, 1), UNITS[unit])
+    }
+}
+
+// formats a number of seconds as h:mm:ss
+pub fn format_duration(seconds: u64) -> String {
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_and_thousands_follow_the_locale() {
+        let comma = Locale { decimal: ',', grouping: '.', clock_24h: true };
+        assert_eq!(localize("-1234567.5", comma), "-1.234.567,5");
+        assert_eq!(localize("+123", comma), "+123");
+        let point = Locale { decimal: '.', grouping: ',', clock_24h: true };
+        assert_eq!(localize("1234", point), "1,234");
+        assert_eq!(localize("0.25", point), "0.25");
+    }
+}
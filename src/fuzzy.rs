@@ -0,0 +1,64 @@
+// fuzzy matching for the search box - the pattern's characters have to appear in the text in
+// order, ignoring case. a plain substring scores best, then runs of consecutive characters and
+// characters at word starts, so "ffx" finds firefox and "code" ranks "code" above "vscode"
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub score: i32,
+    // char indices of the matched characters in the text
+    pub chars: Vec<usize>,
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+pub fn find(pattern: &str, text: &str) -> Option<Match> {
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    let text: Vec<char> = text.chars().collect();
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return None;
+    }
+    let folded: Vec<char> = text.iter().map(|&c| fold(c)).collect();
+    let word_start = |index: usize| {
+        index == 0 || !text[index - 1].is_alphanumeric() || (text[index - 1].is_lowercase() && text[index].is_uppercase())
+    };
+    if let Some(start) = folded.windows(pattern.len()).position(|window| window == pattern.as_slice()) {
+        let bonus = if start == 0 { 20 } else if word_start(start) { 10 } else { 0 };
+        return Some(Match { score: 10 * pattern.len() as i32 + bonus, chars: (start..start + pattern.len()).collect() });
+    }
+    let mut chars: Vec<usize> = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut next = 0;
+    for &wanted in &pattern {
+        let index = next + folded[next..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        match chars.last() {
+            Some(&last) if last + 1 == index => score += 5,
+            // skipped characters cost a little, up to a point
+            Some(&last) => score -= (index - last - 1).min(5) as i32,
+            None => {}
+        }
+        if word_start(index) {
+            score += 3;
+        }
+        chars.push(index);
+        next = index + 1;
+    }
+    Some(Match { score, chars })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsequences_match_and_substrings_rank_first() {
+        let firefox = find("ffx", "firefox").unwrap();
+        assert_eq!(firefox.chars, vec![0, 4, 6]);
+        assert_eq!(find("FOX", "FireFox").unwrap().chars, vec![4, 5, 6]);
+        assert!(find("xff", "firefox").is_none());
+        assert!(find("code", "code").unwrap().score > find("code", "vscode").unwrap().score);
+        assert!(find("code", "vscode").unwrap().score > find("cde", "vscode").unwrap().score);
+    }
+}
@@ -0,0 +1,208 @@
+// gpu usage - devices for the performance tab and per-process utilization/vram
+//
+// nvidia cards go through NVML when built with the `nvidia` feature (the library is loaded at
+// runtime, so the binary still starts without the driver). amd cards are read from sysfs and
+// the per-client `drm-*` keys the amdgpu driver writes to /proc/<pid>/fdinfo
+use std::collections::HashMap;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct GpuDevice {
+    // holds the totals of one card
+    pub name: String,
+    // percent busy, None if the driver doesn't say
+    pub utilization: Option<u32>,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub temperature: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessGpu {
+    // utilization in percent of one gpu, summed over cards
+    pub utilization: f32,
+    // video memory in bytes, summed over cards
+    pub memory: u64,
+}
+
+#[derive(Default)]
+pub struct GpuMonitor {
+    #[cfg(feature = "nvidia")]
+    nvml: Option<nvml_wrapper::Nvml>,
+    #[cfg(feature = "nvidia")]
+    last_nvml_sample: Option<u64>,
+    // drm client id -> engine nanoseconds, to turn the fdinfo counters into rates
+    amd_clients: HashMap<(u32, String), u64>,
+    last_amd_sample: Option<Instant>,
+}
+
+impl GpuMonitor {
+    pub fn new() -> GpuMonitor {
+        GpuMonitor {
+            #[cfg(feature = "nvidia")]
+            nvml: nvml_wrapper::Nvml::init().ok(),
+            ..GpuMonitor::default()
+        }
+    }
+
+    // every card the backends can see
+    pub fn devices(&self) -> Vec<GpuDevice> {
+        let mut devices = Vec::new();
+        #[cfg(feature = "nvidia")]
+        devices.extend(self.nvidia_devices());
+        devices.extend(amd_devices());
+        devices
+    }
+
+    // gpu use of every process that has any, keyed by pid
+    pub fn processes(&mut self, pids: impl Iterator<Item = u32>, now: Instant) -> HashMap<u32, ProcessGpu> {
+        let mut usage: HashMap<u32, ProcessGpu> = HashMap::new();
+        #[cfg(feature = "nvidia")]
+        self.nvidia_processes(&mut usage);
+        self.amd_processes(pids, now, &mut usage);
+        usage
+    }
+
+    #[cfg(feature = "nvidia")]
+    fn nvidia_devices(&self) -> Vec<GpuDevice> {
+        use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+
+        let Some(nvml) = &self.nvml else {
+            return Vec::new();
+        };
+        (0..nvml.device_count().unwrap_or(0))
+            .filter_map(|index| nvml.device_by_index(index).ok())
+            .map(|device| {
+                let memory = device.memory_info().ok();
+                GpuDevice {
+                    name: device.name().unwrap_or_else(|_| String::from("NVIDIA GPU")),
+                    utilization: device.utilization_rates().ok().map(|rates| rates.gpu),
+                    memory_used: memory.as_ref().map(|memory| memory.used).unwrap_or(0),
+                    memory_total: memory.as_ref().map(|memory| memory.total).unwrap_or(0),
+                    temperature: device.temperature(TemperatureSensor::Gpu).ok(),
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "nvidia")]
+    fn nvidia_processes(&mut self, usage: &mut HashMap<u32, ProcessGpu>) {
+        use nvml_wrapper::enums::device::UsedGpuMemory;
+
+        let Some(nvml) = &self.nvml else {
+            return;
+        };
+        let mut newest = self.last_nvml_sample;
+        for device in (0..nvml.device_count().unwrap_or(0)).filter_map(|index| nvml.device_by_index(index).ok()) {
+            let compute = device.running_compute_processes().unwrap_or_default();
+            let graphics = device.running_graphics_processes().unwrap_or_default();
+            for process in compute.into_iter().chain(graphics) {
+                if let UsedGpuMemory::Used(bytes) = process.used_gpu_memory {
+                    let entry = usage.entry(process.pid).or_default();
+                    entry.memory = entry.memory.max(bytes);
+                }
+            }
+            // samples newer than the last call, the driver keeps a short buffer of them
+            for sample in device.process_utilization_stats(self.last_nvml_sample).unwrap_or_default() {
+                usage.entry(sample.pid).or_default().utilization += sample.sm_util as f32;
+                newest = newest.max(Some(sample.timestamp));
+            }
+        }
+        self.last_nvml_sample = newest;
+    }
+
+    // walks /proc/<pid>/fdinfo of every process, rates come from the engine time counters
+    fn amd_processes(&mut self, pids: impl Iterator<Item = u32>, now: Instant, usage: &mut HashMap<u32, ProcessGpu>) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+        let elapsed = self.last_amd_sample.map(|last| now.duration_since(last).as_secs_f64());
+        let mut clients = HashMap::new();
+        for pid in pids {
+            for client in amd_clients(pid) {
+                let key = (pid, client.id.clone());
+                let entry = usage.entry(pid).or_default();
+                entry.memory += client.vram;
+                if let (Some(elapsed), Some(&last)) = (elapsed, self.amd_clients.get(&key)) {
+                    if elapsed > 0.0 {
+                        let busy = client.engine_ns.saturating_sub(last) as f64 / 1e9 / elapsed;
+                        entry.utilization += (busy * 100.0) as f32;
+                    }
+                }
+                clients.insert(key, client.engine_ns);
+            }
+        }
+        // processes without any amd client have nothing worth showing
+        usage.retain(|_, gpu| *gpu != ProcessGpu::default());
+        self.amd_clients = clients;
+        self.last_amd_sample = Some(now);
+    }
+}
+
+// cards under /sys/class/drm driven by amdgpu
+fn amd_devices() -> Vec<GpuDevice> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().and_then(|value| value.trim().parse::<u64>().ok());
+    let mut devices: Vec<GpuDevice> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().to_str()?.to_string();
+            // card0, card1, ... but not the connectors like card0-DP-1
+            if !name.starts_with("card") || name.contains('-') {
+                return None;
+            }
+            let device = entry.path().join("device");
+            let total = read(device.join("mem_info_vram_total"))?;
+            Some(GpuDevice {
+                name: format!("AMD GPU ({})", name),
+                utilization: read(device.join("gpu_busy_percent")).map(|busy| busy as u32),
+                memory_used: read(device.join("mem_info_vram_used")).unwrap_or(0),
+                memory_total: total,
+                temperature: None,
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+struct DrmClient {
+    id: String,
+    vram: u64,
+    engine_ns: u64,
+}
+
+// amdgpu clients of the process, one per open render node, deduplicated by client id
+fn amd_clients(pid: u32) -> Vec<DrmClient> {
+    let Ok(entries) = std::fs::read_dir(format!("/proc/{}/fdinfo", pid)) else {
+        return Vec::new();
+    };
+    let mut clients: HashMap<String, DrmClient> = HashMap::new();
+    for entry in entries.flatten() {
+        let Ok(info) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if !info.lines().any(|line| line.starts_with("drm-driver:") && line.ends_with("amdgpu")) {
+            continue;
+        }
+        let mut client = DrmClient { id: String::new(), vram: 0, engine_ns: 0 };
+        for line in info.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            let number = || value.split_whitespace().next().and_then(|number| number.parse::<u64>().ok()).unwrap_or(0);
+            match key {
+                "drm-client-id" => client.id = value.to_string(),
+                // reported in KiB
+                "drm-memory-vram" => client.vram = number() * 1024,
+                "drm-engine-gfx" | "drm-engine-compute" => client.engine_ns += number(),
+                _ => {}
+            }
+        }
+        clients.insert(client.id.clone(), client);
+    }
+    clients.into_values().collect()
+}
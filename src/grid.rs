@@ -0,0 +1,108 @@
+// the details tab - every column of every process in one dense grid, like the Details tab of
+// Windows' Task Manager. it has its own sort and search, so the processes tab can stay a
+// short list of the columns the user picked while this one is sorted and searched apart
+use iced::widget::{scrollable, Button, Column, Container, MouseArea, Row, Text, TextInput};
+use iced::{alignment, Color, Element, Length};
+
+use crate::columns::{self, ColumnWidths, Formatter, NameStyle};
+use crate::filter::Filter;
+use crate::icons::IconCache;
+use crate::process::{self, ProcessInfo, SortColumn};
+use crate::style::{self, Ui};
+use crate::virtual_list::{self, ListWindow};
+use crate::Message;
+
+#[derive(Debug)]
+pub struct DetailsGrid {
+    // column and whether ascending, independent of the processes tab's sort
+    pub sort: (SortColumn, bool),
+    pub filter: Filter,
+    pub window: ListWindow,
+}
+
+impl DetailsGrid {
+    pub fn new(ui: Ui) -> DetailsGrid {
+        DetailsGrid {
+            sort: (SortColumn::Pid, true),
+            filter: Filter::default(),
+            window: ListWindow { row_height: ui.row_height(), ..ListWindow::default() },
+        }
+    }
+
+    // sorts by the column, toggling the order if it is already the sort column
+    pub fn sort_by(&mut self, column: SortColumn) {
+        self.sort = if self.sort.0 == column { (column, !self.sort.1) } else { (column, true) };
+    }
+
+    // the processes matching this tab's search in this tab's order
+    pub fn rows<'a>(&self, processes: &'a [ProcessInfo]) -> Vec<&'a ProcessInfo> {
+        let (column, ascending) = self.sort;
+        let mut rows: Vec<&ProcessInfo> = processes.iter().filter(|process| self.filter.matches(process)).collect();
+        rows.sort_by(|a, b| {
+            let cmp = process::compare(a, b, column);
+            if ascending { cmp } else { cmp.reverse() }.then_with(|| a.pid.cmp(&b.pid))
+        });
+        rows
+    }
+}
+
+pub fn list_id() -> scrollable::Id {
+    scrollable::Id::new("details-grid")
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn view<'a>(
+    grid: &'a DetailsGrid,
+    processes: &'a [ProcessInfo],
+    widths: &ColumnWidths,
+    formatter: Formatter,
+    icons: Option<&'a IconCache>,
+    selected: Option<u32>,
+    ui: Ui,
+    highlight: Color,
+) -> Element<'a, Message> {
+    let columns = SortColumn::with_plugins();
+    let rows = grid.rows(processes);
+    let keys = [grid.sort];
+    let header = columns.iter().fold(Row::new().spacing(ui.spacing()), |row, &column| {
+        row.push(
+            Button::new(Text::new(columns::header_label(column, &keys, formatter)).size(12))
+                .style(style::header(ui.colors.header))
+                .padding(2)
+                .on_press(Message::GridSort(column))
+                .width(Length::FillPortion(widths.portion(column)))
+        )
+    });
+    let list = virtual_list::view(list_id(), &rows, &grid.window, Message::GridScrolled, |&process| {
+        let matched = grid.filter.name_matches(&process.name);
+        let name = NameStyle { matched: &matched, highlight, icon: icons.and_then(|icons| icons.get(process)), note: None, limit: None };
+        let cells = columns.iter().fold(Row::new().spacing(ui.spacing()), |row, &column| {
+            row.push(columns::cell(process, column, widths, formatter, &name, None))
+        });
+        let mut row = Container::new(cells).height(Length::Fixed(grid.window.row_height)).center_y();
+        if selected == Some(process.pid) {
+            row = row.style(style::selected_row(ui.colors.row));
+        }
+        MouseArea::new(row)
+            .on_press(Message::SelectProcess(process.pid))
+            .on_right_press(Message::OpenProperties(process.pid))
+            .into()
+    });
+    let search = TextInput::new("Search this tab, e.g. mem>500 user:root", &grid.filter.text)
+        .on_input(Message::GridSearchChanged)
+        .width(Length::Fixed(300.0));
+    let mut toolbar = Row::new()
+        .spacing(ui.spacing())
+        .align_items(alignment::Alignment::Center)
+        .push(search)
+        .push(Text::new(format!("{} of {} processes", rows.len(), processes.len())));
+    if let Some(error) = grid.filter.error() {
+        toolbar = toolbar.push(Text::new(error).size(12));
+    }
+    Column::new()
+        .spacing(ui.spacing())
+        .push(toolbar)
+        .push(header)
+        .push(list)
+        .into()
+}
@@ -0,0 +1,70 @@
+// heat panel - the biggest cpu and memory users above the process table, whatever the table
+// is sorted or filtered by, so it can stay sorted by name without hiding a runaway process
+use iced::{
+    theme,
+    widget::{Button, Column, Row, Text},
+    Element, Length,
+};
+
+use std::cmp::Ordering;
+
+use crate::format;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// processes listed per resource
+pub const TOP: usize = 5;
+
+// the n largest by the key, largest first, ties broken by pid so the list doesn't flicker
+pub fn top<'a>(processes: impl Iterator<Item = &'a ProcessInfo>, n: usize, key: impl Fn(&ProcessInfo) -> f64) -> Vec<&'a ProcessInfo> {
+    let mut top: Vec<&ProcessInfo> = processes.collect();
+    top.sort_by(|a, b| key(b).partial_cmp(&key(a)).unwrap_or(Ordering::Equal).then(a.pid.cmp(&b.pid)));
+    top.truncate(n);
+    top
+}
+
+fn list<'a>(title: &'static str, processes: Vec<&'a ProcessInfo>, value: impl Fn(&ProcessInfo) -> String) -> Column<'a, Message> {
+    processes.into_iter().fold(Column::new().spacing(2).push(Text::new(title)), |column, process| {
+        column.push(
+            Button::new(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(&process.name).width(Length::Fill))
+                    .push(Text::new(value(process)))
+            )
+            .style(theme::Button::Text)
+            .padding(0)
+            .on_press(Message::JumpToProcess(process.pid))
+        )
+    })
+}
+
+// construct the panel, clicking a process selects its row
+pub fn view<'a>(processes: impl Iterator<Item = &'a ProcessInfo> + Clone) -> Element<'a, Message> {
+    let cpu = top(processes.clone(), TOP, |process| f64::from(process.cpu));
+    let memory = top(processes, TOP, |process| process.memory as f64);
+    Row::new()
+        .spacing(30)
+        .push(list("Top CPU", cpu, |process| format!("{}%", format::decimal(f64::from(process.cpu), 1))).width(Length::FillPortion(1)))
+        .push(list("Top memory", memory, |process| format!("{} MB", process.memory)).width(Length::FillPortion(1)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn top_takes_the_largest_and_breaks_ties_by_pid() {
+        let processes = [
+            mock::process(1, "a", "u", 10, 5.0),
+            mock::process(2, "b", "u", 30, 1.0),
+            mock::process(3, "c", "u", 20, 5.0),
+            mock::process(4, "d", "u", 5, 9.0),
+        ];
+        let pids = |top: Vec<&ProcessInfo>| top.iter().map(|process| process.pid).collect::<Vec<_>>();
+        assert_eq!(pids(top(processes.iter(), 3, |process| f64::from(process.cpu))), vec![4, 1, 3]);
+        assert_eq!(pids(top(processes.iter(), 2, |process| process.memory as f64)), vec![2, 3]);
+    }
+}
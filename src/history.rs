@@ -0,0 +1,143 @@
+// per-pid history buffers - recent samples of each process, used for trends and to spot
+// processes whose memory keeps growing
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::process::ProcessInfo;
+use crate::timeseries::Point;
+
+// how far back trends look
+pub const TREND_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+// samples in a row a process has to look stuck before it is shown as not responding
+pub const NOT_RESPONDING_AFTER: u32 = 3;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    // one reading of a process
+    at: Instant,
+    memory: u64,
+    cpu: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Streak {
+    // samples in a row that grew, equal readings don't count but don't break the run either
+    increases: u32,
+    // reading before the run started
+    since: Instant,
+    start_memory: u64,
+    last_memory: u64,
+}
+
+impl Streak {
+    fn start(now: Instant, memory: u64) -> Streak {
+        Streak { increases: 0, since: now, start_memory: memory, last_memory: memory }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    // samples of each live pid, oldest first, no older than the trend window
+    samples: HashMap<u32, VecDeque<Sample>>,
+    // current memory growth run of each live pid, not bounded by the trend window
+    streaks: HashMap<u32, Streak>,
+    // samples in a row each stuck pid looked stuck, see ProcessInfo::stuck
+    stuck: HashMap<u32, u32>,
+}
+
+impl History {
+    // appends the current readings, dropping exited pids and samples past the window
+    pub fn record(&mut self, processes: &[ProcessInfo], now: Instant) {
+        let mut samples = HashMap::with_capacity(processes.len());
+        let mut streaks = HashMap::with_capacity(processes.len());
+        let mut stuck = HashMap::new();
+        for process in processes {
+            if process.stuck {
+                stuck.insert(process.pid, self.stuck.get(&process.pid).map_or(1, |count| count + 1));
+            }
+            let streak = match self.streaks.remove(&process.pid) {
+                Some(streak) if process.memory < streak.last_memory => Streak::start(now, process.memory),
+                Some(streak) if process.memory > streak.last_memory => {
+                    Streak { increases: streak.increases + 1, last_memory: process.memory, ..streak }
+                }
+                Some(streak) => streak,
+                None => Streak::start(now, process.memory),
+            };
+            streaks.insert(process.pid, streak);
+            let mut buffer = self.samples.remove(&process.pid).unwrap_or_default();
+            while buffer.front().is_some_and(|sample| now.duration_since(sample.at) > TREND_WINDOW) {
+                buffer.pop_front();
+            }
+            buffer.push_back(Sample { at: now, memory: process.memory, cpu: process.cpu });
+            samples.insert(process.pid, buffer);
+        }
+        self.samples = samples;
+        self.streaks = streaks;
+        self.stuck = stuck;
+    }
+
+    // the cpu and memory samples of a pid as chart points, oldest first
+    pub fn points(&self, pid: u32, now: Instant) -> (Vec<Point>, Vec<Point>) {
+        let wall = chrono::Local::now();
+        let samples = self.samples.get(&pid).into_iter().flatten();
+        samples
+            .map(|sample| {
+                let time = wall - chrono::Duration::from_std(now.saturating_duration_since(sample.at)).unwrap_or_default();
+                (Point { time, value: sample.cpu }, Point { time, value: sample.memory as f32 })
+            })
+            .unzip()
+    }
+
+    // fills in the trend and leak fields of each process, a run of `leak_samples` increases
+    // marks it as a suspected leak
+    pub fn annotate(&self, processes: &mut [ProcessInfo], leak_samples: u32, now: Instant) {
+        for process in processes {
+            if let Some(buffer) = self.samples.get(&process.pid) {
+                if let (Some(first), Some(last)) = (buffer.front(), buffer.back()) {
+                    process.memory_growth = last.memory as i64 - first.memory as i64;
+                    process.cpu_trend = last.cpu - first.cpu;
+                }
+                // a new pid has nothing to compare with yet
+                let mut latest = buffer.iter().rev();
+                if let (Some(last), Some(previous)) = (latest.next(), latest.next()) {
+                    process.memory_delta = last.memory as i64 - previous.memory as i64;
+                    process.cpu_delta = last.cpu - previous.cpu;
+                }
+            }
+            if let Some(streak) = self.streaks.get(&process.pid) {
+                let minutes = now.duration_since(streak.since).as_secs_f32() / 60.0;
+                process.growth_rate = if minutes > 0.0 {
+                    (streak.last_memory - streak.start_memory) as f32 / minutes
+                } else {
+                    0.0
+                };
+                process.suspected_leak = streak.increases >= leak_samples.max(1);
+            }
+            process.not_responding = self.stuck.get(&process.pid).is_some_and(|&count| count >= NOT_RESPONDING_AFTER);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deltas_compare_the_last_two_samples() {
+        let process = |memory: u64, cpu: f32| ProcessInfo { pid: 7, memory, cpu, ..ProcessInfo::default() };
+        let start = Instant::now();
+        let mut history = History::default();
+        let mut current = [process(100, 5.0)];
+        history.record(&current, start);
+        history.annotate(&mut current, 3, start);
+        assert_eq!((current[0].memory_delta, current[0].cpu_delta), (0, 0.0));
+
+        history.record(&[process(130, 20.0)], start + Duration::from_secs(1));
+        let mut current = [process(120, 12.5)];
+        history.record(&current, start + Duration::from_secs(2));
+        history.annotate(&mut current, 3, start + Duration::from_secs(2));
+        assert_eq!((current[0].memory_delta, current[0].cpu_delta), (-10, -7.5));
+        assert_eq!((current[0].memory_growth, current[0].cpu_trend), (20, 7.5));
+    }
+}
@@ -0,0 +1,46 @@
+// reverse lookups for the search box - the processes that have a file open or listen on a
+// port, read from their open descriptors and the socket tables (Linux only). every process's
+// descriptors are read, so this only runs while the query has a file or port condition
+use std::collections::HashMap;
+
+use crate::open_files::{self, FileKind};
+use crate::process::ProcessInfo;
+use crate::sockets;
+
+// fills `open_paths` and `listening_ports`, processes that aren't readable keep them empty
+pub fn fill(processes: &mut [ProcessInfo]) {
+    let ports = sockets::listening_ports().unwrap_or_default();
+    for process in processes {
+        let files = open_files::for_pid(process.pid).unwrap_or_default();
+        process.listening_ports = ports_of(&files, &ports);
+        process.open_paths = files.into_iter().filter(|file| file.kind == FileKind::File).map(|file| file.target).collect();
+    }
+}
+
+// the ports of the listening sockets among the descriptors, a forked server's workers share
+// their parent's so each of them holds the port
+fn ports_of(files: &[open_files::OpenFile], ports: &HashMap<u64, u16>) -> Vec<u16> {
+    let mut held: Vec<u16> = files
+        .iter()
+        .filter(|file| file.kind == FileKind::Socket)
+        .filter_map(|file| ports.get(&sockets::inode(&file.target)?).copied())
+        .collect();
+    held.sort_unstable();
+    held.dedup();
+    held
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use open_files::OpenFile;
+
+    #[test]
+    fn listening_sockets_give_their_ports_once() {
+        let socket = |fd, target: &str| OpenFile { fd, target: target.to_string(), kind: FileKind::Socket };
+        let files = [socket(3, "socket:[100]"), socket(4, "socket:[100]"), socket(5, "socket:[200]"), socket(6, "socket:[300]")];
+        let ports = HashMap::from([(100, 8080), (300, 53)]);
+        assert_eq!(ports_of(&files, &ports), vec![53, 8080]);
+    }
+}
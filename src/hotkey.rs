@@ -0,0 +1,84 @@
+// global shortcut that brings the window to the front from anywhere on the desktop
+//
+// the shortcut only works while the app is running; to also start it with the same keys,
+// bind the shortcut to the `conborli` command in the desktop environment's keyboard settings
+use global_hotkey::{
+    hotkey::HotKey,
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+};
+
+use iced::futures::{channel::mpsc, SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+
+use std::str::FromStr;
+
+use crate::Message;
+
+// shortcut used when the config doesn't set one, matching Windows Task Manager
+pub const DEFAULT_HOTKEY: &str = "ctrl+shift+Escape";
+
+pub struct Hotkey {
+    // platform registration, None if the platform refused to create a manager (e.g. Wayland)
+    manager: Option<GlobalHotKeyManager>,
+    // the currently registered shortcut
+    registered: Option<HotKey>,
+}
+
+impl Hotkey {
+    // creates the platform manager, registering nothing yet
+    pub fn new() -> Hotkey {
+        Hotkey {
+            manager: GlobalHotKeyManager::new().ok(),
+            registered: None,
+        }
+    }
+
+    // placeholder that registers nothing, used until `new` is called for the real window
+    pub fn inactive() -> Hotkey {
+        Hotkey {
+            manager: None,
+            registered: None,
+        }
+    }
+
+    // replaces the registered shortcut with the one described by `spec`, e.g. "ctrl+shift+Escape".
+    // the old one is only let go once the new one is registered, so a shortcut the platform
+    // refuses leaves the old one working
+    pub fn set(&mut self, spec: &str) -> Result<(), String> {
+        let manager = self.manager.as_ref().ok_or("global shortcuts are not supported on this platform")?;
+        let hotkey = HotKey::from_str(spec).map_err(|err| err.to_string())?;
+        if self.registered == Some(hotkey) {
+            return Ok(());
+        }
+        manager.register(hotkey).map_err(|err| err.to_string())?;
+        if let Some(old) = self.registered.replace(hotkey) {
+            let _ = manager.unregister(old);
+        }
+        Ok(())
+    }
+}
+
+// emits a message every time the registered shortcut is pressed
+pub fn subscription() -> Subscription<Message> {
+    struct HotkeyEvents;
+
+    subscription::channel(std::any::TypeId::of::<HotkeyEvents>(), 10, |mut output| async move {
+        // the hotkey receiver blocks, so it gets its own thread and forwards presses here
+        let (sender, mut presses) = mpsc::unbounded();
+        std::thread::spawn(move || {
+            while let Ok(event) = GlobalHotKeyEvent::receiver().recv() {
+                if event.state() == HotKeyState::Pressed && sender.unbounded_send(()).is_err() {
+                    break;
+                }
+            }
+        });
+        loop {
+            if presses.next().await.is_some() {
+                let _ = output.send(Message::HotkeyPressed).await;
+            } else {
+                // the forwarding thread is gone, nothing more will arrive
+                iced::futures::future::pending::<()>().await;
+            }
+        }
+    })
+}
@@ -0,0 +1,208 @@
+// program icons in the name column, looked up once per executable and kept for the session
+//
+// linux: the Icon= of the desktop entry that starts the program (or the program's own name),
+// found in the hicolor theme or pixmaps of the XDG data dirs. windows: the executable's first
+// icon resource, read into pixels. macOS: the bundle's icns, converted to a png with sips into
+// the cache dir since iced can't decode icns
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use iced::widget::{image, svg, Image, Svg};
+use iced::{Element, Length};
+
+use crate::process::ProcessInfo;
+
+// size the icons are drawn at, about a line of text
+const SIZE: f32 = 16.0;
+
+#[derive(Debug, Clone)]
+pub enum Icon {
+    Raster(image::Handle),
+    Vector(svg::Handle),
+}
+
+impl Icon {
+    fn from_path(path: PathBuf) -> Icon {
+        if path.extension().is_some_and(|extension| extension == "svg") {
+            Icon::Vector(svg::Handle::from_path(path))
+        } else {
+            Icon::Raster(image::Handle::from_path(path))
+        }
+    }
+
+    pub fn view<'a, Message: 'a>(&self) -> Element<'a, Message> {
+        match self {
+            Icon::Raster(handle) => Image::new(handle.clone()).width(Length::Fixed(SIZE)).height(Length::Fixed(SIZE)).into(),
+            Icon::Vector(handle) => Svg::new(handle.clone()).width(Length::Fixed(SIZE)).height(Length::Fixed(SIZE)).into(),
+        }
+    }
+}
+
+// icon per executable path, None once looked up without finding one
+#[derive(Debug, Default)]
+pub struct IconCache {
+    icons: HashMap<PathBuf, Option<Icon>>,
+}
+
+impl IconCache {
+    // looks up the executables the cache hasn't seen yet
+    pub fn resolve(&mut self, processes: &[ProcessInfo]) {
+        for process in processes {
+            if let Some(exe) = &process.exe {
+                if !self.icons.contains_key(exe) {
+                    let icon = lookup(exe, &process.name);
+                    self.icons.insert(exe.clone(), icon);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, process: &ProcessInfo) -> Option<&Icon> {
+        self.icons.get(process.exe.as_ref()?)?.as_ref()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lookup(exe: &Path, name: &str) -> Option<Icon> {
+    let program = exe.file_name().map(|program| program.to_string_lossy().to_string()).unwrap_or_else(|| name.to_string());
+    let programs = crate::apps::desktop_programs();
+    let icon = programs.get(&program).or_else(|| programs.get(name)).cloned().flatten().unwrap_or(program);
+    // an entry may name the icon file itself
+    if Path::new(&icon).is_absolute() {
+        return Path::new(&icon).is_file().then(|| Icon::from_path(PathBuf::from(&icon)));
+    }
+    theme_candidates(&crate::apps::data_dirs(), &icon).into_iter().find(|path| path.is_file()).map(Icon::from_path)
+}
+
+// where an icon name may be, the sizes nearest the drawn one first, then vectors and pixmaps
+#[cfg(target_os = "linux")]
+pub fn theme_candidates(data_dirs: &[PathBuf], icon: &str) -> Vec<PathBuf> {
+    const SIZES: [&str; 7] = ["16x16", "24x24", "32x32", "48x48", "64x64", "128x128", "256x256"];
+    let mut candidates = Vec::new();
+    for dir in data_dirs {
+        let theme = dir.join("icons/hicolor");
+        candidates.extend(SIZES.iter().map(|size| theme.join(size).join("apps").join(format!("{}.png", icon))));
+        candidates.push(theme.join("scalable/apps").join(format!("{}.svg", icon)));
+    }
+    for dir in data_dirs {
+        candidates.extend(["png", "svg"].iter().map(|extension| dir.join("pixmaps").join(format!("{}.{}", icon, extension))));
+    }
+    candidates
+}
+
+#[cfg(target_os = "macos")]
+fn lookup(exe: &Path, _name: &str) -> Option<Icon> {
+    use std::process::Command;
+
+    // /Applications/Safari.app/Contents/MacOS/Safari -> /Applications/Safari.app
+    let bundle = exe.ancestors().find(|dir| dir.extension().is_some_and(|extension| extension == "app"))?;
+    let cached = dirs::cache_dir()?.join("conborli/icons").join(format!("{}.png", bundle.file_stem()?.to_string_lossy()));
+    if !cached.is_file() {
+        // defaults reads binary plists too
+        let output = Command::new("defaults").arg("read").arg(bundle.join("Contents/Info")).arg("CFBundleIconFile").output().ok()?;
+        let file = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || file.is_empty() {
+            return None;
+        }
+        let mut icns = bundle.join("Contents/Resources").join(&file);
+        if icns.extension().is_none() {
+            icns.set_extension("icns");
+        }
+        std::fs::create_dir_all(cached.parent()?).ok()?;
+        let converted = Command::new("sips")
+            .args(["-s", "format", "png", "-Z", "32"])
+            .arg(&icns)
+            .arg("--out")
+            .arg(&cached)
+            .output()
+            .ok()?;
+        if !converted.status.success() {
+            return None;
+        }
+    }
+    Some(Icon::from_path(cached))
+}
+
+#[cfg(windows)]
+fn lookup(exe: &Path, _name: &str) -> Option<Icon> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Graphics::Gdi::{
+        DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+    use windows_sys::Win32::UI::Shell::ExtractIconExW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
+    let path: Vec<u16> = exe.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut small: HICON = 0;
+    if unsafe { ExtractIconExW(path.as_ptr(), 0, std::ptr::null_mut(), &mut small, 1) } == 0 || small == 0 {
+        return None;
+    }
+    let mut info: ICONINFO = unsafe { std::mem::zeroed() };
+    let read = unsafe { GetIconInfo(small, &mut info) } != 0;
+    unsafe { DestroyIcon(small) };
+    if !read {
+        return None;
+    }
+    let mut pixels = None;
+    let mut bitmap: BITMAP = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<BITMAP>() as i32;
+    if info.hbmColor != 0 && unsafe { GetObjectW(info.hbmColor, size, &mut bitmap as *mut BITMAP as *mut _) } == size {
+        let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+        let mut header: BITMAPINFO = unsafe { std::mem::zeroed() };
+        header.bmiHeader = BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            // negative for rows top to bottom
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let mut bgra = vec![0u8; (width * height * 4) as usize];
+        let dc = unsafe { GetDC(0) };
+        let lines = unsafe { GetDIBits(dc, info.hbmColor, 0, height as u32, bgra.as_mut_ptr() as *mut _, &mut header, DIB_RGB_COLORS) };
+        unsafe { ReleaseDC(0, dc) };
+        if lines == height {
+            pixels = Some((width as u32, height as u32, to_rgba(bgra)));
+        }
+    }
+    unsafe {
+        DeleteObject(info.hbmColor);
+        DeleteObject(info.hbmMask);
+    }
+    let (width, height, rgba) = pixels?;
+    Some(Icon::Raster(image::Handle::from_pixels(width, height, rgba)))
+}
+
+// gdi's bgra to rgba, icons without an alpha channel come back fully transparent
+#[cfg(windows)]
+fn to_rgba(mut pixels: Vec<u8>) -> Vec<u8> {
+    let no_alpha = pixels.chunks(4).all(|pixel| pixel[3] == 0);
+    for pixel in pixels.chunks_mut(4) {
+        pixel.swap(0, 2);
+        if no_alpha {
+            pixel[3] = 255;
+        }
+    }
+    pixels
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn lookup(_exe: &Path, _name: &str) -> Option<Icon> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn theme_sizes_come_before_pixmaps() {
+        let candidates = theme_candidates(&[PathBuf::from("/usr/share")], "firefox");
+        assert_eq!(candidates[0], PathBuf::from("/usr/share/icons/hicolor/16x16/apps/firefox.png"));
+        assert!(candidates.contains(&PathBuf::from("/usr/share/icons/hicolor/scalable/apps/firefox.svg")));
+        assert_eq!(candidates.last(), Some(&PathBuf::from("/usr/share/pixmaps/firefox.svg")));
+    }
+}
@@ -0,0 +1,262 @@
+// kill impact preview - what else is affected before a process is killed
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Text},
+    Element,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::process::Command;
+
+use crate::kill_guard::Target;
+use crate::process::ProcessInfo;
+use crate::provider::Identity;
+use crate::sockets;
+use crate::Message;
+
+#[derive(Debug, Clone)]
+pub struct KillImpact {
+    // the process about to be killed
+    pub pid: u32,
+    pub name: String,
+    // the process as it was when the preview opened, the kill is refused once the pid is reused
+    pub identity: Identity,
+    // every process below it in the tree, as (pid, name)
+    pub descendants: Vec<(u32, String)>,
+    // kills the descendants too instead of orphaning them
+    pub tree: bool,
+    // service and connection details, None until the background check finishes
+    pub external: Option<ExternalImpact>,
+    // critical processes among those killed, as "name (pid)", see critical.rs
+    pub critical: Vec<String>,
+    // the user checked that they understand, the kill button stays disabled until then
+    pub acknowledged: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExternalImpact {
+    // systemd unit that owns the process, and the units that depend on it
+    pub unit: Option<String>,
+    pub dependents: Result<Vec<String>, String>,
+    // open connections and listening sockets, formatted for display
+    pub connections: Result<Vec<String>, String>,
+}
+
+impl KillImpact {
+    // the process tree part, from the table so it works for another machine's processes too
+    pub fn new(processes: &[ProcessInfo], target: Target, tree: bool) -> KillImpact {
+        let pid = target.pid;
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for process in processes {
+            if let Some(parent) = process.ppid.filter(|&parent| parent != process.pid) {
+                children.entry(parent).or_default().push(process.pid);
+            }
+        }
+        let name_of = |pid: u32| processes.iter().find(|process| process.pid == pid).map(|process| process.name.clone()).unwrap_or_default();
+        let mut descendants = Vec::new();
+        // a table with a ppid cycle would otherwise be walked forever
+        let mut visited = HashSet::from([pid]);
+        let mut pending = children.get(&pid).cloned().unwrap_or_default();
+        while let Some(child) = pending.pop() {
+            if !visited.insert(child) {
+                continue;
+            }
+            descendants.push((child, name_of(child)));
+            pending.extend(children.get(&child).into_iter().flatten());
+        }
+        descendants.sort();
+        KillImpact { pid, name: target.identity.name.clone(), identity: target.identity, descendants, tree, external: None, critical: Vec::new(), acknowledged: false }
+    }
+}
+
+// looks up the owning unit, its reverse dependencies and the sockets, blocks on systemctl
+pub fn external(pid: u32) -> ExternalImpact {
+    let unit = owning_unit(pid);
+    let dependents = match &unit {
+        Some(unit) => reverse_dependencies(unit),
+        None => Ok(Vec::new()),
+    };
+    let connections = sockets::for_pid(pid).map(|sockets| {
+        sockets
+            .iter()
+            .filter(|socket| socket.is_listening() || socket.is_connected())
+            .map(|socket| {
+                if socket.is_listening() {
+                    format!("{} listening on {}", socket.protocol, socket.local)
+                } else {
+                    format!("{} {} -> {}", socket.protocol, socket.local, socket.remote)
+                }
+            })
+            .collect()
+    });
+    ExternalImpact { unit, dependents, connections }
+}
+
+fn owning_unit(pid: u32) -> Option<String> {
+    service_of_cgroup(&fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?)
+}
+
+// the service unit in a cgroup file, e.g. "0::/system.slice/nginx.service"
+fn service_of_cgroup(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .filter_map(|line| line.rsplit('/').next())
+        .find(|unit| unit.ends_with(".service"))
+        .map(str::to_string)
+}
+
+// units that require or want the unit, so stopping it affects them
+fn reverse_dependencies(unit: &str) -> Result<Vec<String>, String> {
+    let output = Command::new("systemctl")
+        .args(["list-dependencies", "--reverse", "--plain", "--no-legend", "--no-pager", unit])
+        .output()
+        .map_err(|err| format!("failed to run systemctl: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("systemctl failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(dependents(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// the first line of the listing is the unit itself
+fn dependents(listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .skip(1)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+// construct the preview shown above the process table, with the confirm button
+pub fn view(impact: &KillImpact) -> Element<'_, Message> {
+    let mut content = Column::new()
+        .spacing(5)
+        .push(Text::new(if impact.tree {
+            format!("Kill {} ({}) and everything below it?", impact.name, impact.pid)
+        } else {
+            format!("Kill {} ({})?", impact.name, impact.pid)
+        }));
+    content = content.push(Text::new(if impact.descendants.is_empty() {
+        String::from("No child processes")
+    } else if impact.tree {
+        format!(
+            "{} child processes will be killed first: {}",
+            impact.descendants.len(),
+            list(impact.descendants.iter().map(|(pid, name)| format!("{} ({})", name, pid)))
+        )
+    } else {
+        format!(
+            "{} child processes will be orphaned (reparented, not killed): {}",
+            impact.descendants.len(),
+            list(impact.descendants.iter().map(|(pid, name)| format!("{} ({})", name, pid)))
+        )
+    }));
+    match &impact.external {
+        None => content = content.push(Text::new("Checking services and connections...")),
+        Some(external) => {
+            content = content.push(Text::new(match (&external.unit, &external.dependents) {
+                (None, _) => String::from("Not part of a service"),
+                (Some(unit), Ok(dependents)) if dependents.is_empty() => format!("Part of {}, nothing depends on it", unit),
+                (Some(unit), Ok(dependents)) => format!("Part of {}, which {} depend on", unit, list(dependents.iter().cloned())),
+                (Some(unit), Err(err)) => format!("Part of {}, dependents unknown: {}", unit, err),
+            }));
+            content = content.push(Text::new(match &external.connections {
+                Ok(connections) if connections.is_empty() => String::from("No open connections"),
+                Ok(connections) => format!("{} sockets will close: {}", connections.len(), list(connections.iter().cloned())),
+                Err(err) => format!("Connections unknown: {}", err),
+            }));
+        }
+    }
+    let confirmed = impact.critical.is_empty() || impact.acknowledged;
+    if !impact.critical.is_empty() {
+        content = content
+            .push(Text::new(format!(
+                "{} {} critical to the system, killing it can end the session or crash the machine",
+                list(impact.critical.iter().cloned()),
+                if impact.critical.len() == 1 { "is" } else { "are" }
+            )))
+            .push(Checkbox::new("I understand, kill it anyway", impact.acknowledged).on_toggle(Message::AcknowledgeCritical));
+    }
+    content
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Button::new(if impact.tree { "Kill tree" } else { "Kill" }).on_press_maybe(confirmed.then(|| {
+                    let target = Target { pid: impact.pid, identity: impact.identity.clone(), confirmed_critical: impact.acknowledged };
+                    if impact.tree {
+                        Message::KillTree(target)
+                    } else {
+                        Message::KillProcess(target)
+                    }
+                })))
+                .push(Button::new("Cancel").on_press(Message::CancelKill))
+        )
+        .into()
+}
+
+// the process and everything below it in the table, children before their parents so
+// nothing is orphaned and reparented while the tree is being killed
+pub fn tree_order(processes: &[ProcessInfo], pid: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for process in processes {
+        if let Some(ppid) = process.ppid.filter(|&ppid| ppid != process.pid) {
+            children.entry(ppid).or_default().push(process.pid);
+        }
+    }
+    // parents before children, then reversed
+    let mut order = vec![pid];
+    let mut next = 0;
+    while let Some(&parent) = order.get(next) {
+        for &child in children.get(&parent).into_iter().flatten() {
+            if !order.contains(&child) {
+                order.push(child);
+            }
+        }
+        next += 1;
+    }
+    order.reverse();
+    order
+}
+
+// first few entries joined by commas, with a count of the rest
+fn list(items: impl Iterator<Item = String>) -> String {
+    const SHOWN: usize = 5;
+    let items: Vec<String> = items.collect();
+    let mut text = items.iter().take(SHOWN).cloned().collect::<Vec<_>>().join(", ");
+    if items.len() > SHOWN {
+        text.push_str(&format!(" and {} more", items.len() - SHOWN));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn trees_are_killed_from_the_leaves_up() {
+        let process = |pid, ppid| ProcessInfo { ppid: Some(ppid), ..mock::process(pid, "p", "u", 1, 0.0) };
+        let table = [process(1, 0), process(2, 1), process(3, 2), process(4, 1), process(5, 5), process(6, 9)];
+        let order = tree_order(&table, 1);
+        let position = |pid| order.iter().position(|&p| p == pid).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position(3) < position(2) && position(2) < position(1) && position(4) < position(1));
+        assert_eq!(tree_order(&table, 5), vec![5]);
+    }
+
+    #[test]
+    fn parent_cycles_end_the_descendant_walk() {
+        let process = |pid, ppid| ProcessInfo { ppid: Some(ppid), ..mock::process(pid, "p", "u", 1, 0.0) };
+        let table = [process(1, 3), process(2, 1), process(3, 2), process(4, 2)];
+        let impact = KillImpact::new(&table, Target::of(&table[0]), true);
+        assert_eq!(impact.descendants.iter().map(|(pid, _)| *pid).collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn services_come_from_the_cgroup_and_the_reverse_listing() {
+        assert_eq!(service_of_cgroup("0::/system.slice/nginx.service\n").as_deref(), Some("nginx.service"));
+        assert_eq!(service_of_cgroup("12:pids:/user.slice\n0::/user.slice/user-1000.slice/session-2.scope\n"), None);
+        let listing = "nginx.service\n  multi-user.target\n  graphical.target\n\n";
+        assert_eq!(dependents(listing), vec![String::from("multi-user.target"), String::from("graphical.target")]);
+    }
+}
@@ -0,0 +1,153 @@
+// single instance - launching the app while it runs brings the running window to the front
+// instead of opening a second window with its own collector
+//
+// the running instance listens on a localhost port and writes the port to instance.lock next
+// to the config. a later launch connects to it, says "focus" and exits once it hears back,
+// with its --open link after the word if it was given one, see links.rs. a lock left behind by a crash points at a port nobody answers on, so it is taken over
+use iced::futures::{channel::mpsc, SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::links::Link;
+use crate::Message;
+
+const REQUEST: &str = "focus";
+// sent back, so a port reused by another program isn't mistaken for a running instance
+const REPLY: &str = "conborli";
+
+// how long a launch waits for the running instance to answer
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// the port later launches connect to, bound by `focus_or_claim`
+static LISTENER: OnceLock<TcpListener> = OnceLock::new();
+
+// e.g. ~/.config/conborli/instance.lock
+fn lock_file() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("instance.lock"))
+}
+
+// true if a running instance answered and was asked to come to the front, and to open the
+// link if there is one, otherwise this launch becomes the instance the next ones find
+pub fn focus_or_claim(link: Option<&Link>) -> bool {
+    let Some(path) = lock_file() else {
+        return false;
+    };
+    let running = fs::read_to_string(&path).ok().and_then(|port| port.trim().parse::<u16>().ok());
+    if running.is_some_and(|port| ask(SocketAddr::from((Ipv4Addr::LOCALHOST, port)), link)) {
+        return true;
+    }
+    // without the lock every launch opens a window, as before
+    if let Ok(listener) = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        let written = listener.local_addr().and_then(|addr| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, addr.port().to_string())
+        });
+        if written.is_ok() {
+            let _ = LISTENER.set(listener);
+        }
+    }
+    false
+}
+
+// asks the instance on the port to come to the front
+fn ask(addr: SocketAddr, link: Option<&Link>) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let request = match link {
+        Some(link) => format!("{} {}", REQUEST, link),
+        None => REQUEST.to_string(),
+    };
+    if writeln!(stream, "{}", request).is_err() {
+        return false;
+    }
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == REPLY
+}
+
+// Some if the connection came from a later launch, which gets its answer, with the link it
+// asked to open. a link that doesn't parse still brings the window up
+fn answer(stream: TcpStream) -> Option<Option<Link>> {
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let mut request = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request).ok()?;
+    let (word, link) = request.trim().split_once(' ').unwrap_or((request.trim(), ""));
+    if word != REQUEST {
+        return None;
+    }
+    writeln!(&stream, "{}", REPLY).ok()?;
+    Some(Link::parse(link).ok().filter(|_| !link.is_empty()))
+}
+
+// emits a message every time another launch asks for the window, nothing if this launch
+// didn't claim the lock
+pub fn subscription() -> Subscription<Message> {
+    struct Launches;
+
+    subscription::channel(std::any::TypeId::of::<Launches>(), 10, |mut output| async move {
+        // accept blocks, so it gets its own thread and forwards requests here
+        let (sender, mut launches) = mpsc::unbounded();
+        if let Some(listener) = LISTENER.get() {
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    if let Some(link) = answer(stream) {
+                        if sender.unbounded_send(link).is_err() {
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+        loop {
+            if let Some(link) = launches.next().await {
+                let _ = output.send(Message::AnotherInstanceLaunched(link)).await;
+            } else {
+                // no listener, or its thread is gone, nothing more will arrive
+                iced::futures::future::pending::<()>().await;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_launch_is_answered_and_a_stranger_is_not_trusted() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || answer(listener.accept().unwrap().0));
+        assert!(ask(addr, None));
+        assert_eq!(server.join().unwrap(), Some(None));
+
+        // a link rides along with the request
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || answer(listener.accept().unwrap().0));
+        let link = Link::parse("processes?sort=cpu&filter=node").unwrap();
+        assert!(ask(addr, Some(&link)));
+        assert_eq!(server.join().unwrap(), Some(Some(link)));
+
+        // something else listening on the port answers with something else
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stranger = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = writeln!(stream, "HTTP/1.1 400 Bad Request");
+        });
+        assert!(!ask(addr, None));
+        stranger.join().unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+// integrity section of the details pane - the sha-256 of the process's executable, its
+// authenticode signature on windows, and a lookup of the hash on virustotal, for telling a
+// suspicious process from the program it claims to be
+use iced::{
+    widget::{Button, Column, Row, Text},
+    Element, Length,
+};
+use sha2::{Digest, Sha256};
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::online;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Integrity {
+    pub exe: PathBuf,
+    // lowercase hex
+    pub sha256: String,
+    // the authenticode status and signer, None off windows
+    pub signature: Option<Result<String, String>>,
+}
+
+// hashes and, on windows, verifies the executable. reads the whole file, so it is run off the
+// ui thread
+pub fn check(exe: PathBuf) -> Result<Integrity, String> {
+    let sha256 = sha256(&exe)?;
+    let signature = cfg!(windows).then(|| signature(&exe));
+    Ok(Integrity { exe, sha256, signature })
+}
+
+fn sha256(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|err| format!("{}: {}", path.display(), err))?;
+        if read == 0 {
+            return Ok(format!("{:x}", hasher.finalize()));
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+// asks powershell, the path goes through the environment so it needs no quoting
+fn signature(exe: &Path) -> Result<String, String> {
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", "$s = Get-AuthenticodeSignature -LiteralPath $env:CONBORLI_EXE; \"$($s.Status)|$($s.SignerCertificate.Subject)\""])
+        .env("CONBORLI_EXE", exe)
+        .output()
+        .map_err(|err| format!("failed to run powershell: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("powershell: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(parse_signature(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// "Valid|CN=Microsoft Corporation, O=..." into "Valid, signed by Microsoft Corporation"
+fn parse_signature(output: &str) -> String {
+    let (status, subject) = output.trim().split_once('|').unwrap_or((output.trim(), ""));
+    let signer = subject.split(", ").find_map(|part| part.strip_prefix("CN=")).unwrap_or(subject).trim_matches('"');
+    if signer.is_empty() {
+        status.to_string()
+    } else {
+        format!("{}, signed by {}", status, signer)
+    }
+}
+
+pub fn virustotal_url(sha256: &str) -> String {
+    format!("https://www.virustotal.com/gui/file/{}", sha256)
+}
+
+// the result of the last check when it was of this executable, otherwise a button starting one.
+// the virustotal lookup sends the hash out, so it needs network access allowed in the settings
+pub fn view<'a>(
+    process: &'a ProcessInfo,
+    checked: Option<&'a Result<Integrity, String>>,
+    checking: bool,
+    network: bool,
+) -> Element<'a, Message> {
+    let Some(exe) = &process.exe else {
+        return Text::new("The executable of this process can't be read").into();
+    };
+    let check = Button::new(if checking { "Checking..." } else { "Check executable" })
+        .on_press_maybe((!checking).then(|| Message::CheckIntegrity(process.pid, exe.clone())));
+    let field = |label, value: String| Row::new().spacing(10).push(Text::new(label).width(Length::Fixed(120.0))).push(Text::new(value));
+    let mut column = Column::new().spacing(5).push(field("Executable", exe.display().to_string()));
+    match checked {
+        Some(Ok(integrity)) if integrity.exe == *exe => {
+            let signature = match &integrity.signature {
+                Some(Ok(signature)) => signature.clone(),
+                Some(Err(err)) => format!("can't be checked: {}", err),
+                None => String::from("only checked on windows"),
+            };
+            column = column
+                .push(field("SHA-256", integrity.sha256.clone()))
+                .push(field("Signature", signature))
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Button::new("Copy hash").on_press(Message::CopyText(integrity.sha256.clone())))
+                        .push(
+                            Button::new("Look up on VirusTotal")
+                                .on_press_maybe(network.then(|| Message::LookUpHash(integrity.sha256.clone())))
+                        )
+                        .push(check)
+                        .push_maybe((!network).then(|| Text::new(online::DISABLED_REASON))),
+                );
+        }
+        Some(Err(err)) => column = column.push(Text::new(err)).push(check),
+        _ => column = column.push(check),
+    }
+    column.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_the_file_and_reads_the_signer() {
+        let path = std::env::temp_dir().join(format!("conborli-integrity-{}", std::process::id()));
+        std::fs::write(&path, "abc").unwrap();
+        let hash = sha256(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(hash.unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(parse_signature("Valid|CN=Microsoft Corporation, O=Microsoft Corporation, C=US\r\n"), "Valid, signed by Microsoft Corporation");
+        assert_eq!(parse_signature("NotSigned|\r\n"), "NotSigned");
+    }
+}
@@ -0,0 +1,121 @@
+// jobs - the processes started from the run dialog, each followed with everything it starts
+// as a named group with its totals and a button terminating all of it. a descendant stays in
+// its job when its parent exits and it is reparented, until it exits itself
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Text},
+    Element,
+};
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::format::{self, format_duration};
+use crate::impact;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    pub root: u32,
+    pub started: Instant,
+    // every pid seen in the job with its name, since pids get reused after exiting
+    members: HashMap<u32, String>,
+}
+
+impl Job {
+    pub fn new(name: String, root: u32, started: Instant) -> Job {
+        Job { name, root, started, members: HashMap::new() }
+    }
+
+    // adds what the members started since the last refresh and drops the members that exited
+    pub fn update(&mut self, processes: &[ProcessInfo]) {
+        let running: HashMap<u32, &str> = processes.iter().map(|process| (process.pid, process.name.as_str())).collect();
+        self.members.retain(|pid, name| running.get(pid) == Some(&name.as_str()));
+        // the root is adopted by the first refresh that sees it
+        let mut roots: Vec<u32> = self.members.keys().copied().collect();
+        if self.members.is_empty() && running.contains_key(&self.root) {
+            roots.push(self.root);
+        }
+        for root in roots {
+            for pid in impact::tree_order(processes, root) {
+                if let Some(name) = running.get(&pid) {
+                    self.members.entry(pid).or_insert_with(|| name.to_string());
+                }
+            }
+        }
+    }
+
+    // every member exited
+    pub fn is_finished(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn members<'a>(&self, processes: &'a [ProcessInfo]) -> Vec<&'a ProcessInfo> {
+        processes.iter().filter(|process| self.members.get(&process.pid) == Some(&process.name)).collect()
+    }
+
+    // the members in an order that kills children before their parents
+    pub fn kill_order(&self, processes: &[ProcessInfo]) -> Vec<u32> {
+        let mut order: Vec<u32> = Vec::with_capacity(self.members.len());
+        for &pid in self.members.keys() {
+            for member in impact::tree_order(processes, pid) {
+                if self.members.contains_key(&member) && !order.contains(&member) {
+                    order.push(member);
+                }
+            }
+        }
+        order
+    }
+}
+
+// a line per job with its totals, the index is the job's in `jobs`
+pub fn view<'a>(jobs: &'a [Job], processes: &[ProcessInfo], killable: bool, now: Instant) -> Element<'a, Message> {
+    jobs.iter()
+        .enumerate()
+        .fold(Column::new().spacing(5), |column, (index, job)| {
+            let members = job.members(processes);
+            let cpu: f32 = members.iter().map(|process| process.cpu).sum();
+            let memory: u64 = members.iter().map(|process| process.memory).sum();
+            let text = format!(
+                "{} · {} processes · {}% CPU · {} MB · running for {}",
+                job.name,
+                members.len(),
+                format::decimal(f64::from(cpu), 1),
+                memory,
+                format_duration(now.saturating_duration_since(job.started).as_secs())
+            );
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(alignment::Alignment::Center)
+                    .push(Text::new(text))
+                    .push(Button::new("Show").on_press(Message::ShowProcess(job.root)))
+                    .push(Button::new("Terminate group").on_press_maybe(killable.then_some(Message::TerminateJob(index))))
+                    .push(Button::new("Forget").on_press(Message::ForgetJob(index)))
+            )
+        })
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    #[test]
+    fn jobs_keep_reparented_descendants() {
+        let child = |pid, ppid, name| ProcessInfo { ppid: Some(ppid), ..mock::process(pid, name, "dev", 10, 1.0) };
+        let mut job = Job::new(String::from("npm run dev"), 10, Instant::now());
+        job.update(&[mock::process(10, "npm", "dev", 10, 1.0), child(11, 10, "node"), child(12, 11, "esbuild"), child(13, 1, "bash")]);
+        assert_eq!(job.kill_order(&[mock::process(10, "npm", "dev", 10, 1.0), child(11, 10, "node"), child(12, 11, "esbuild")]), vec![12, 11, 10]);
+        // npm exits and node is reparented to init
+        let later = [child(11, 1, "node"), child(12, 11, "esbuild"), child(14, 12, "sh")];
+        job.update(&later);
+        assert_eq!(job.members(&later).len(), 3);
+        job.update(&[]);
+        assert!(job.is_finished());
+    }
+}
@@ -0,0 +1,49 @@
+// keyboard shortcuts of the main window, see detached.rs for a process's own window
+use iced::keyboard::{self, key::Named, Key, Modifiers};
+use iced::Subscription;
+
+use crate::{CopyField, Message};
+
+// arrows, Page Up/Down and Home/End move the selection, Delete kills it and Shift+Delete its
+// tree, Enter opens its properties, Ctrl+F searches, Tab moves between inputs, Ctrl+Tab
+// switches tabs, Ctrl+C copies the selected row, Space pauses live updates, F5 refreshes,
+// Ctrl+1..9 sorts, Ctrl+Shift+P opens the command palette. together with the row menu that
+// leaves nothing that needs the mouse
+//
+// iced 0.12 has no accessibility tree yet, so screen readers can't see the widgets. icon
+// buttons get a tooltip naming them, which is what a label would say
+
+// wrapped so the shortcut reaches the window that has the focus
+pub fn subscription() -> Subscription<Message> {
+    keyboard::on_key_press(|key, modifiers| handle(key, modifiers).map(|message| Message::Shortcut(Box::new(message))))
+}
+
+fn handle(key: Key, modifiers: Modifiers) -> Option<Message> {
+    match key.as_ref() {
+        Key::Named(Named::ArrowUp) => Some(Message::MoveSelection(-1)),
+        Key::Named(Named::ArrowDown) => Some(Message::MoveSelection(1)),
+        Key::Named(Named::PageUp) => Some(Message::MoveSelection(-20)),
+        Key::Named(Named::PageDown) => Some(Message::MoveSelection(20)),
+        Key::Named(Named::Home) => Some(Message::MoveSelection(i32::MIN)),
+        Key::Named(Named::End) => Some(Message::MoveSelection(i32::MAX)),
+        Key::Named(Named::Delete) if modifiers.shift() => Some(Message::KillSelectedTree),
+        Key::Named(Named::Delete) => Some(Message::KillSelected),
+        Key::Named(Named::Enter) => Some(Message::OpenSelectedProperties),
+        Key::Named(Named::Tab) if modifiers.command() => Some(Message::CycleTab(if modifiers.shift() { -1 } else { 1 })),
+        Key::Named(Named::Tab) if modifiers.shift() => Some(Message::FocusPrevious),
+        Key::Named(Named::Tab) => Some(Message::FocusNext),
+        Key::Named(Named::F5) => Some(Message::RefreshNow),
+        Key::Named(Named::Space) => Some(Message::TogglePause),
+        Key::Named(Named::Escape) => Some(Message::ClearSelection),
+        Key::Character("p" | "P") if modifiers.command() && modifiers.shift() => Some(Message::OpenPalette),
+        Key::Character("f") if modifiers.command() => Some(Message::FocusSearch),
+        Key::Character("c") if modifiers.command() => Some(Message::CopySelected(CopyField::Row)),
+        // Ctrl+1 sorts by the first visible column, Ctrl+2 by the second, ...
+        Key::Character(digit) if modifiers.command() => digit
+            .parse::<usize>()
+            .ok()
+            .filter(|index| (1..=9).contains(index))
+            .map(|index| Message::SortByIndex(index - 1)),
+        _ => None,
+    }
+}
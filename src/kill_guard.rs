@@ -0,0 +1,148 @@
+// the checks every kill goes through, whoever asks for it - the kill preview, the users and
+// executables tabs, the undo delay, rules, scripts, schedules, signals, dump-and-kill, restarts,
+// the terminal table and the http api
+//
+// a kill is aimed at a process, not a pid: the target keeps the identity of the process as it
+// was when it was picked, and the kill is refused when the pid is gone from the table or now
+// belongs to a newer process. nothing is killed in read-only mode, even a kill asked for
+// before it was turned on. the task manager never kills itself, and critical processes (see
+// critical.rs) are only killed after the extra confirmation of the kill preview
+use sysinfo::System;
+
+use std::fmt;
+
+use crate::critical;
+use crate::process::ProcessInfo;
+use crate::provider::{Identity, ProcessProvider};
+
+// the process a kill or signal is aimed at, captured when it was picked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub pid: u32,
+    pub identity: Identity,
+    // the kill preview's "kill it anyway" was checked
+    pub confirmed_critical: bool,
+}
+
+impl Target {
+    pub fn of(process: &ProcessInfo) -> Target {
+        Target { pid: process.pid, identity: Identity::of(process), confirmed_critical: false }
+    }
+
+    // "firefox (30)"
+    pub fn label(&self) -> String {
+        format!("{} ({})", self.identity.name, self.pid)
+    }
+}
+
+// what the checks depend on besides the target
+#[derive(Debug, Clone)]
+pub struct Policy {
+    pub read_only: bool,
+    // the table is this machine's, so the task manager's own pid means something in it
+    pub local: bool,
+    // the user's names on top of the built-in ones
+    pub critical: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    ReadOnly,
+    Itself,
+    Critical,
+    Exited,
+    // the name of the process that has the pid now
+    Reused(String),
+    Failed,
+}
+
+impl Refusal {
+    // e.g. "Didn't kill firefox (30), it has already exited"
+    pub fn message(&self, action: &str, target: &Target) -> String {
+        match self {
+            Refusal::Failed => format!("Failed to {} {}", action, target.label()),
+            refusal => format!("Didn't {} {}, {}", action, target.label(), refusal),
+        }
+    }
+}
+
+// the reason, to follow "didn't kill x" or a rule's match
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::ReadOnly => f.write_str("read-only mode is on"),
+            Refusal::Itself => f.write_str("the task manager doesn't kill itself"),
+            Refusal::Critical => f.write_str("critical processes are only killed from the kill preview"),
+            Refusal::Exited => f.write_str("it has already exited"),
+            Refusal::Reused(name) => write!(f, "its pid now belongs to a newer process, {}", name),
+            Refusal::Failed => f.write_str("the kill failed"),
+        }
+    }
+}
+
+// whether the target may be killed or signalled now, `processes` is the current table
+pub fn check(target: &Target, policy: &Policy, processes: &[ProcessInfo], provider: &mut dyn ProcessProvider) -> Result<(), Refusal> {
+    if policy.read_only {
+        return Err(Refusal::ReadOnly);
+    }
+    if policy.local && target.pid == std::process::id() {
+        return Err(Refusal::Itself);
+    }
+    let Some(process) = processes.iter().find(|process| process.pid == target.pid) else {
+        return Err(Refusal::Exited);
+    };
+    // read again, the table may be older than the pid's current owner
+    match provider.identify(target.pid) {
+        Some(found) if found.is(&target.identity) => {}
+        Some(found) => return Err(Refusal::Reused(found.name)),
+        None => return Err(Refusal::Exited),
+    }
+    if !target.confirmed_critical && critical::is_critical(process, &policy.critical) {
+        return Err(Refusal::Critical);
+    }
+    Ok(())
+}
+
+pub fn kill(target: &Target, policy: &Policy, processes: &[ProcessInfo], provider: &mut dyn ProcessProvider, system: &System) -> Result<(), Refusal> {
+    check(target, policy, processes, provider)?;
+    if provider.kill(system, target.pid) {
+        Ok(())
+    } else {
+        Err(Refusal::Failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::{self, MockProvider};
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn kills_reach_only_the_process_that_was_picked() {
+        let mut table = vec![mock::process(20, "postgres", "u", 10, 0.0), mock::process(std::process::id(), "conborli", "u", 10, 0.0)];
+        table[0].start_time = 1_600_000_000;
+        let live = Rc::new(RefCell::new(table.clone()));
+        let mut provider = MockProvider { processes: live.clone(), killed: Rc::default() };
+        let policy = Policy { read_only: false, local: true, critical: Vec::new() };
+        let target = Target::of(&table[0]);
+
+        assert_eq!(check(&Target::of(&table[1]), &policy, &table, &mut provider), Err(Refusal::Itself));
+        assert_eq!(check(&target, &Policy { read_only: true, ..policy.clone() }, &table, &mut provider), Err(Refusal::ReadOnly));
+        let guarded = Policy { critical: vec![String::from("postgres")], ..policy.clone() };
+        assert_eq!(check(&target, &guarded, &table, &mut provider), Err(Refusal::Critical));
+        assert_eq!(check(&Target { confirmed_critical: true, ..target.clone() }, &guarded, &table, &mut provider), Ok(()));
+        assert_eq!(check(&Target::of(&mock::process(40, "gone", "u", 0, 0.0)), &policy, &table, &mut provider), Err(Refusal::Exited));
+        live.borrow_mut()[0].start_time = 1_700_000_000;
+        assert_eq!(check(&target, &policy, &table, &mut provider), Err(Refusal::Reused(String::from("postgres"))));
+        assert_eq!(
+            Refusal::Reused(String::from("postgres")).message("kill", &target),
+            "Didn't kill postgres (20), its pid now belongs to a newer process, postgres"
+        );
+        live.borrow_mut()[0].start_time = 1_600_000_000;
+        assert_eq!(kill(&target, &policy, &table, &mut provider, &System::new()), Ok(()));
+        assert_eq!(*provider.killed.borrow(), vec![20]);
+    }
+}
@@ -0,0 +1,184 @@
+// kill by pattern dialog - a glob or regex over process names (or whole command lines), a live
+// preview of what it matches with their totals, and one button to kill them all. the task
+// manager itself and critical processes are listed but never killed from here
+use iced::{
+    widget::{Button, Checkbox, Column, PickList, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+use regex::{Regex, RegexBuilder};
+
+use std::fmt;
+
+use crate::critical;
+use crate::format;
+use crate::kill_guard::Target;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// rows the preview lists, the totals count every match
+const PREVIEW_ROWS: usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Syntax {
+    // * and ? wildcards over the whole name
+    #[default]
+    Glob,
+    // found anywhere in the name
+    Regex,
+}
+
+impl Syntax {
+    pub const ALL: [Syntax; 2] = [Syntax::Glob, Syntax::Regex];
+}
+
+impl fmt::Display for Syntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Syntax::Glob => "Glob",
+            Syntax::Regex => "Regex",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KillPatternDialog {
+    pub pattern: String,
+    pub syntax: Syntax,
+    // match the command line instead of the name
+    pub command_line: bool,
+}
+
+// a glob as an anchored regex, ignoring case like the search box
+pub fn compile(pattern: &str, syntax: Syntax) -> Result<Regex, String> {
+    let source = match syntax {
+        Syntax::Glob => {
+            let body: String = pattern
+                .chars()
+                .map(|c| match c {
+                    '*' => String::from(".*"),
+                    '?' => String::from("."),
+                    c => regex::escape(&c.to_string()),
+                })
+                .collect();
+            format!("^{}$", body)
+        }
+        Syntax::Regex => pattern.to_string(),
+    };
+    RegexBuilder::new(&source).case_insensitive(true).build().map_err(|err| format!("invalid pattern {}: {}", pattern, err))
+}
+
+#[derive(Debug, Default)]
+pub struct Matches<'a> {
+    // what the kill button kills
+    pub killable: Vec<&'a ProcessInfo>,
+    // matched but protected, the task manager itself and critical processes
+    pub spared: Vec<&'a ProcessInfo>,
+}
+
+impl Matches<'_> {
+    pub fn targets(&self) -> Vec<Target> {
+        self.killable.iter().map(|process| Target::of(process)).collect()
+    }
+}
+
+// the processes the dialog's pattern matches, none while it is empty
+pub fn matches<'a>(dialog: &KillPatternDialog, processes: &'a [ProcessInfo], critical: &[String]) -> Result<Matches<'a>, String> {
+    let mut matches = Matches::default();
+    if dialog.pattern.trim().is_empty() {
+        return Ok(matches);
+    }
+    let regex = compile(dialog.pattern.trim(), dialog.syntax)?;
+    let own = std::process::id();
+    for process in processes {
+        let text = if dialog.command_line { &process.cmd } else { &process.name };
+        if !regex.is_match(text) {
+            continue;
+        }
+        if process.pid == own || critical::is_critical(process, critical) {
+            matches.spared.push(process);
+        } else {
+            matches.killable.push(process);
+        }
+    }
+    Ok(matches)
+}
+
+pub fn view<'a>(dialog: &'a KillPatternDialog, processes: &'a [ProcessInfo], critical: &[String]) -> Element<'a, Message> {
+    let mut content = Column::new()
+        .spacing(10)
+        .push(Text::new("Kill by pattern"))
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    TextInput::new("Pattern, e.g. chrom* or ^python3?$", &dialog.pattern)
+                        .on_input(Message::KillPatternChanged)
+                        .width(Length::Fixed(400.0))
+                )
+                .push(PickList::new(&Syntax::ALL[..], Some(dialog.syntax), Message::SetKillPatternSyntax))
+                .push(Checkbox::new("Match command line", dialog.command_line).on_toggle(Message::ToggleKillPatternCommandLine))
+        );
+    let kill = match matches(dialog, processes, critical) {
+        Err(err) => {
+            content = content.push(Text::new(err));
+            None
+        }
+        Ok(matches) => {
+            let memory: u64 = matches.killable.iter().map(|process| process.memory).sum();
+            let cpu: f32 = matches.killable.iter().map(|process| process.cpu).sum();
+            content = content.push(Text::new(format!(
+                "{} processes, {} MB, {}% CPU",
+                matches.killable.len(),
+                format::integer(memory),
+                format::decimal(f64::from(cpu), 1)
+            )));
+            let rows = matches.killable.iter().take(PREVIEW_ROWS).fold(Column::new().spacing(2), |column, process| {
+                column.push(Text::new(format!("{}  {}  {}  {} MB  {}%", process.pid, process.name, process.user, process.memory, format::decimal(f64::from(process.cpu), 1))))
+            });
+            content = content.push(Scrollable::new(rows).height(Length::Fixed(200.0)));
+            if matches.killable.len() > PREVIEW_ROWS {
+                content = content.push(Text::new(format!("and {} more", matches.killable.len() - PREVIEW_ROWS)));
+            }
+            if !matches.spared.is_empty() {
+                let names: Vec<String> = matches.spared.iter().map(|process| format!("{} ({})", process.name, process.pid)).collect();
+                content = content.push(Text::new(format!("Not killed, critical or this task manager: {}", names.join(", "))));
+            }
+            (!matches.killable.is_empty()).then(|| format!("Kill {} processes", matches.killable.len()))
+        }
+    };
+    let kill_button = match kill {
+        Some(label) => Button::new(Text::new(label)).on_press(Message::KillByPattern),
+        None => Button::new("Kill"),
+    };
+    content
+        .push(Row::new().spacing(10).push(kill_button).push(Button::new("Cancel").on_press(Message::CloseKillPattern)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn patterns_match_names_or_command_lines() {
+        let mut processes = vec![
+            mock::process(10, "chrome", "u", 300, 1.0),
+            mock::process(11, "Chromium", "u", 200, 2.0),
+            mock::process(12, "python3", "u", 50, 0.0),
+            mock::process(13, "init", "root", 5, 0.0),
+            mock::process(std::process::id(), "conborli", "u", 80, 0.5),
+        ];
+        processes[2].cmd = String::from("python3 -m http.server");
+        let dialog = |pattern: &str, syntax: Syntax, command_line: bool| KillPatternDialog { pattern: pattern.to_string(), syntax, command_line };
+        let pids = |dialog: KillPatternDialog| matches(&dialog, &processes, &[String::from("init")]).map(|matches| (matches.targets().iter().map(|target| target.pid).collect::<Vec<_>>(), matches.spared.len()));
+
+        assert_eq!(pids(dialog("chrom*", Syntax::Glob, false)), Ok((vec![10, 11], 0)));
+        assert_eq!(pids(dialog("chrom", Syntax::Glob, false)), Ok((vec![], 0)));
+        assert_eq!(pids(dialog("^py.*3$", Syntax::Regex, false)), Ok((vec![12], 0)));
+        assert_eq!(pids(dialog("*http.server*", Syntax::Glob, true)), Ok((vec![12], 0)));
+        assert_eq!(pids(dialog("*", Syntax::Glob, false)), Ok((vec![10, 11, 12], 2)));
+        assert_eq!(pids(dialog("  ", Syntax::Regex, false)), Ok((vec![], 0)));
+        assert!(pids(dialog("(", Syntax::Regex, false)).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+// simple and advanced modes - simple shows big rows of name, cpu and memory with only the
+// everyday tabs and actions, advanced everything as configured. the simple mode only changes
+// what is shown, the configured columns, density and panels are kept for advanced
+//
+// either mode also fits the process table to a narrow window, e.g. one tiled to half the
+// screen: below the configured width the pid column goes and rows take two lines, the name
+// on its own, and below three quarters of it the memory column goes as well
+use serde::{Deserialize, Serialize};
+
+use crate::process::SortColumn;
+use crate::row_menu::RowAction;
+use crate::style::Density;
+use crate::Tab;
+
+const SIMPLE_COLUMNS: [SortColumn; 3] = [SortColumn::Name, SortColumn::Cpu, SortColumn::Memory];
+
+// dropped from a narrow window's table in this order
+const NARROW_HIDDEN: [SortColumn; 2] = [SortColumn::Pid, SortColumn::Memory];
+
+const SIMPLE_TABS: [Tab; 3] = [Tab::Processes, Tab::Performance, Tab::Settings];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Layout {
+    Simple,
+    #[default]
+    Advanced,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 2] = [Layout::Simple, Layout::Advanced];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Layout::Simple => "Simple",
+            Layout::Advanced => "Advanced",
+        }
+    }
+
+    pub fn is_simple(self) -> bool {
+        self == Layout::Simple
+    }
+
+    // the process table's columns
+    pub fn columns(self, configured: &[SortColumn]) -> &[SortColumn] {
+        match self {
+            Layout::Simple => &SIMPLE_COLUMNS,
+            Layout::Advanced => configured,
+        }
+    }
+
+    // rows big enough to hit with a finger
+    pub fn density(self, configured: Density) -> Density {
+        match self {
+            Layout::Simple => Density::Comfortable,
+            Layout::Advanced => configured,
+        }
+    }
+
+    pub fn tabs(self) -> &'static [Tab] {
+        match self {
+            Layout::Simple => &SIMPLE_TABS,
+            Layout::Advanced => &Tab::ALL,
+        }
+    }
+
+    // the row menu of the simple mode kills and shows the details, nothing finer
+    pub fn offers(self, action: RowAction) -> bool {
+        !self.is_simple() || matches!(action, RowAction::Kill | RowAction::Details)
+    }
+}
+
+// how the process table fits the window, see `Config::narrow_width`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fit {
+    Wide,
+    Narrow,
+    Narrower,
+}
+
+impl Fit {
+    // a threshold of 0 keeps every width wide
+    pub fn for_width(width: f64, threshold: u32) -> Fit {
+        let threshold = f64::from(threshold);
+        if width >= threshold {
+            Fit::Wide
+        } else if width >= threshold * 0.75 {
+            Fit::Narrow
+        } else {
+            Fit::Narrower
+        }
+    }
+
+    // the columns left of the mode's, in their order
+    pub fn columns(self, columns: &[SortColumn]) -> Vec<SortColumn> {
+        let hidden = match self {
+            Fit::Wide => &NARROW_HIDDEN[..0],
+            Fit::Narrow => &NARROW_HIDDEN[..1],
+            Fit::Narrower => &NARROW_HIDDEN[..],
+        };
+        columns.iter().copied().filter(|column| !hidden.contains(column)).collect()
+    }
+
+    // the name on a line of its own above the other cells
+    pub fn two_lines(self) -> bool {
+        self != Fit::Wide
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_mode_replaces_what_advanced_keeps() {
+        let configured = [SortColumn::Pid, SortColumn::User];
+        assert_eq!(Layout::Simple.columns(&configured), &SIMPLE_COLUMNS);
+        assert_eq!(Layout::Advanced.columns(&configured), &configured);
+        assert_eq!(Layout::Simple.density(Density::Compact), Density::Comfortable);
+        assert!(Layout::Simple.tabs().contains(&Tab::Settings) && !Layout::Simple.tabs().contains(&Tab::Rules));
+        assert!(!Layout::Simple.offers(RowAction::Suspend) && Layout::Advanced.offers(RowAction::Suspend));
+    }
+
+    #[test]
+    fn narrow_windows_drop_the_pid_then_the_memory() {
+        let configured = [SortColumn::Pid, SortColumn::Name, SortColumn::Cpu, SortColumn::Memory];
+        assert_eq!(Fit::for_width(1024.0, 800), Fit::Wide);
+        assert_eq!(Fit::for_width(700.0, 800), Fit::Narrow);
+        assert_eq!(Fit::for_width(500.0, 800), Fit::Narrower);
+        assert_eq!(Fit::for_width(300.0, 0), Fit::Wide);
+        assert_eq!(Fit::Wide.columns(&configured), configured);
+        assert_eq!(Fit::Narrow.columns(&configured), [SortColumn::Name, SortColumn::Cpu, SortColumn::Memory]);
+        assert_eq!(Fit::Narrower.columns(&configured), [SortColumn::Name, SortColumn::Cpu]);
+        assert!(Fit::Narrow.two_lines() && !Fit::Wide.two_lines());
+    }
+}
@@ -0,0 +1,373 @@
+// capping a running process's cpu or memory. on linux it is moved into a transient systemd
+// scope of its own (conborli-limit-<pid>.scope) carrying the limits, changing them later sets
+// the scope's properties. the user's own manager is asked unless running as root, so other
+// users' processes need root. the scopes outlive the task manager, at launch the limits are
+// read back from the cgroups of the ones still running
+//
+// on windows it is assigned to a job object with a hard cpu rate cap and a job memory limit.
+// a process can't leave a job, so lifting the limits clears them on the job it stays in
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Text, TextInput},
+    Element, Length,
+};
+
+use std::fs;
+use std::process::Command;
+#[cfg(windows)]
+use std::sync::Mutex;
+
+use crate::process::ProcessInfo;
+use crate::slices;
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limit {
+    // percent of one cpu, 200 is two full cpus
+    pub cpu_percent: Option<u32>,
+    pub memory_mb: Option<u64>,
+}
+
+impl Limit {
+    pub fn is_empty(&self) -> bool {
+        self.cpu_percent.is_none() && self.memory_mb.is_none()
+    }
+
+    // "50% CPU, 512 MB"
+    pub fn describe(&self) -> String {
+        let cpu = self.cpu_percent.map(|percent| format!("{}% CPU", percent));
+        let memory = self.memory_mb.map(|mb| format!("{} MB", mb));
+        cpu.into_iter().chain(memory).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LimitDialog {
+    pub pid: u32,
+    pub name: String,
+    // tells the process apart from a later one with its pid, see provider::Identity
+    pub start_time: u64,
+    // whether it already runs inside a job object, windows only
+    pub in_job: Option<bool>,
+    // empty for no limit
+    pub cpu: String,
+    pub memory: String,
+}
+
+impl LimitDialog {
+    pub fn new(process: &ProcessInfo, current: Option<&Limit>) -> LimitDialog {
+        let current = current.copied().unwrap_or_default();
+        LimitDialog {
+            pid: process.pid,
+            name: process.name.clone(),
+            start_time: process.start_time,
+            in_job: in_job(process.pid),
+            cpu: current.cpu_percent.map(|percent| percent.to_string()).unwrap_or_default(),
+            memory: current.memory_mb.map(|mb| mb.to_string()).unwrap_or_default(),
+        }
+    }
+
+    pub fn to_limit(&self) -> Result<Limit, String> {
+        let cpu = self.cpu.trim().trim_end_matches('%').trim();
+        let memory = self.memory.trim();
+        let limit = Limit {
+            cpu_percent: (!cpu.is_empty())
+                .then(|| cpu.parse().ok().filter(|&percent| percent > 0).ok_or_else(|| format!("{} is not a cpu percentage like 50", self.cpu.trim())))
+                .transpose()?,
+            memory_mb: (!memory.is_empty())
+                .then(|| memory.parse().ok().filter(|&mb| mb > 0).ok_or_else(|| format!("{} is not a number of megabytes", memory)))
+                .transpose()?,
+        };
+        if limit.is_empty() {
+            return Err(String::from("enter a cpu or memory limit"));
+        }
+        Ok(limit)
+    }
+}
+
+fn unit(pid: u32) -> String {
+    format!("conborli-limit-{}.scope", pid)
+}
+
+// the processes among `pids` that sit in a scope of their own with limits on it, reads a
+// file per process so run it off the ui thread
+pub fn existing(pids: &[u32]) -> Vec<(u32, Limit)> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    pids.iter()
+        .filter_map(|&pid| {
+            let cgroup = slices::for_pid(pid).filter(|cgroup| cgroup.ends_with(&format!("/{}", unit(pid))))?;
+            let read = |file: &str| fs::read_to_string(format!("/sys/fs/cgroup{}/{}", cgroup, file)).ok();
+            let limit = Limit {
+                cpu_percent: read("cpu.max").and_then(|text| parse_cpu_max(&text)),
+                memory_mb: read("memory.max").and_then(|text| parse_memory_max(&text)),
+            };
+            (!limit.is_empty()).then_some((pid, limit))
+        })
+        .collect()
+}
+
+// "50000 100000" is half a cpu, "max 100000" no limit
+fn parse_cpu_max(text: &str) -> Option<u32> {
+    let (quota, period) = text.trim().split_once(' ')?;
+    let (quota, period) = (quota.parse::<u64>().ok()?, period.parse::<u64>().ok()?);
+    (period > 0).then(|| (quota * 100 / period) as u32)
+}
+
+// bytes, or "max" for no limit
+fn parse_memory_max(text: &str) -> Option<u64> {
+    text.trim().parse::<u64>().ok().map(|bytes| bytes / 1024 / 1024)
+}
+
+// puts the limits on the process, an empty limit lifts them
+pub fn apply(pid: u32, start_time: u64, limit: &Limit) -> Result<(), String> {
+    if cfg!(windows) {
+        return apply_job(pid, start_time, limit);
+    }
+    if !cfg!(target_os = "linux") {
+        return Err(String::from("limiting processes is only supported on Linux and Windows"));
+    }
+    let unit = unit(pid);
+    let scoped = slices::for_pid(pid).is_some_and(|cgroup| cgroup.ends_with(&format!("/{}", unit)));
+    if scoped {
+        run("systemctl", &set_property_args(&unit, limit))
+    } else if limit.is_empty() {
+        Ok(())
+    } else {
+        run("busctl", &transient_unit_args(&unit, pid, limit))
+    }
+}
+
+fn run(program: &str, args: &[String]) -> Result<(), String> {
+    let mut command = Command::new(program);
+    if !is_root() {
+        command.arg("--user");
+    }
+    let output = command.args(args).output().map_err(|err| format!("failed to run {}: {}", program, err))?;
+    if !output.status.success() {
+        return Err(format!("{}: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_root() -> bool {
+    false
+}
+
+// the manager's StartTransientUnit with the pid and the limits as the scope's properties
+fn transient_unit_args(unit: &str, pid: u32, limit: &Limit) -> Vec<String> {
+    let mut properties = vec![vec![String::from("PIDs"), String::from("au"), String::from("1"), pid.to_string()]];
+    if let Some(percent) = limit.cpu_percent {
+        properties.push(vec![String::from("CPUQuotaPerSecUSec"), String::from("t"), (u64::from(percent) * 10_000).to_string()]);
+    }
+    if let Some(mb) = limit.memory_mb {
+        properties.push(vec![String::from("MemoryMax"), String::from("t"), (mb * 1024 * 1024).to_string()]);
+    }
+    let mut args: Vec<String> = ["call", "org.freedesktop.systemd1", "/org/freedesktop/systemd1", "org.freedesktop.systemd1.Manager", "StartTransientUnit", "ssa(sv)a(sa(sv))", unit, "fail"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    args.push(properties.len().to_string());
+    args.extend(properties.into_iter().flatten());
+    // no auxiliary units
+    args.push(String::from("0"));
+    args
+}
+
+// the limits of an existing scope, the missing ones lifted
+fn set_property_args(unit: &str, limit: &Limit) -> Vec<String> {
+    vec![
+        String::from("set-property"),
+        String::from("--runtime"),
+        unit.to_string(),
+        format!("CPUQuota={}", limit.cpu_percent.map(|percent| format!("{}%", percent)).unwrap_or_default()),
+        format!("MemoryMax={}", limit.memory_mb.map(|mb| format!("{}M", mb)).unwrap_or_else(|| String::from("infinity"))),
+    ]
+}
+
+// jobs made by `apply_job` by pid and start time, kept open so their limits can be changed
+// later and closed by `prune` once the process is gone
+#[cfg(windows)]
+static JOBS: Mutex<Vec<(u32, u64, isize)>> = Mutex::new(Vec::new());
+
+#[cfg(windows)]
+fn apply_job(pid: u32, start_time: u64, limit: &Limit) -> Result<(), String> {
+    use std::ffi::c_void;
+    use std::mem::{size_of, zeroed};
+    use std::ptr;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectCpuRateControlInformation, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_CPU_RATE_CONTROL_ENABLE,
+        JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP, JOB_OBJECT_LIMIT_JOB_MEMORY,
+    };
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+    let mut jobs = JOBS.lock().map_err(|_| String::from("the job list is unusable after a panic"))?;
+    let job = match jobs.iter().find(|(job_pid, started, _)| *job_pid == pid && *started == start_time) {
+        Some(&(_, _, job)) => job,
+        None if limit.is_empty() => return Ok(()),
+        None => unsafe {
+            let job = CreateJobObjectW(ptr::null(), ptr::null());
+            if job == 0 {
+                return Err(format!("failed to create a job object: {}", std::io::Error::last_os_error()));
+            }
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process == 0 {
+                let error = std::io::Error::last_os_error();
+                CloseHandle(job);
+                return Err(format!("failed to open process {}: {}", pid, error));
+            }
+            let assigned = AssignProcessToJobObject(job, process);
+            let error = std::io::Error::last_os_error();
+            CloseHandle(process);
+            if assigned == 0 {
+                CloseHandle(job);
+                return Err(format!("failed to put process {} in a job object: {}", pid, error));
+            }
+            jobs.push((pid, start_time, job));
+            job
+        },
+    };
+    // the rate is in hundredths of a percent of the whole machine, the limit of one cpu
+    let cpus = std::thread::available_parallelism().map_or(1, |cpus| cpus.get() as u32);
+    let mut cpu: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = unsafe { zeroed() };
+    if let Some(percent) = limit.cpu_percent {
+        cpu.ControlFlags = JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+        cpu.Anonymous.CpuRate = (percent * 100 / cpus).clamp(1, 10_000);
+    }
+    let mut memory: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { zeroed() };
+    if let Some(mb) = limit.memory_mb {
+        memory.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+        memory.JobMemoryLimit = (mb * 1024 * 1024) as usize;
+    }
+    let set_cpu = unsafe {
+        SetInformationJobObject(job, JobObjectCpuRateControlInformation, &cpu as *const _ as *const c_void, size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32)
+    };
+    if set_cpu == 0 {
+        return Err(format!("failed to set the cpu limit: {}", std::io::Error::last_os_error()));
+    }
+    let set_memory = unsafe {
+        SetInformationJobObject(job, JobObjectExtendedLimitInformation, &memory as *const _ as *const c_void, size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32)
+    };
+    if set_memory == 0 {
+        return Err(format!("failed to set the memory limit: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn apply_job(_pid: u32, _start_time: u64, _limit: &Limit) -> Result<(), String> {
+    Err(String::from("job objects are only supported on Windows"))
+}
+
+// closes the jobs of processes that are no longer in the table, a later process given the
+// same pid starts without one
+#[cfg(windows)]
+pub fn prune(processes: &[ProcessInfo]) {
+    use windows_sys::Win32::Foundation::CloseHandle;
+
+    if let Ok(mut jobs) = JOBS.lock() {
+        jobs.retain(|&(pid, start_time, job)| {
+            let alive = processes.iter().any(|process| process.pid == pid && process.start_time == start_time);
+            if !alive {
+                unsafe { CloseHandle(job) };
+            }
+            alive
+        });
+    }
+}
+
+#[cfg(not(windows))]
+pub fn prune(_processes: &[ProcessInfo]) {}
+
+// whether the process is inside any job object, e.g. one of a browser's or a build tool's,
+// None where it can't be opened or off windows
+#[cfg(windows)]
+pub fn in_job(pid: u32) -> Option<bool> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::IsProcessInJob;
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if process == 0 {
+        return None;
+    }
+    let mut result = 0;
+    let checked = unsafe { IsProcessInJob(process, 0, &mut result) };
+    unsafe { CloseHandle(process) };
+    (checked != 0).then_some(result != 0)
+}
+
+#[cfg(not(windows))]
+pub fn in_job(_pid: u32) -> Option<bool> {
+    None
+}
+
+// shown next to the name of a limited process
+pub fn badge(limit: &Limit) -> Element<'static, Message> {
+    Text::new(format!("⛓ {}", limit.describe())).size(12).into()
+}
+
+// construct the dialog shown above the process table
+pub fn view(dialog: &LimitDialog) -> Element<'_, Message> {
+    Column::new()
+        .spacing(10)
+        .push(Text::new(match dialog.in_job {
+            Some(true) => format!("Limit {} ({}), which already runs inside a job object", dialog.name, dialog.pid),
+            _ => format!("Limit {} ({})", dialog.name, dialog.pid),
+        }))
+        .push(
+            Row::new()
+                .spacing(10)
+                .align_items(alignment::Alignment::Center)
+                .push(Text::new("CPU (%)"))
+                .push(TextInput::new("no limit", &dialog.cpu).on_input(Message::LimitCpuChanged).on_submit(Message::ApplyLimit).width(Length::Fixed(100.0)))
+                .push(Text::new("Memory (MB)"))
+                .push(TextInput::new("no limit", &dialog.memory).on_input(Message::LimitMemoryChanged).on_submit(Message::ApplyLimit).width(Length::Fixed(100.0))),
+        )
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Button::new("Apply").on_press(Message::ApplyLimit))
+                .push(Button::new("Remove limits").on_press(Message::RemoveLimit))
+                .push(Button::new("Cancel").on_press(Message::CloseLimit)),
+        )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_become_scope_properties() {
+        let dialog = |cpu: &str, memory: &str| LimitDialog { pid: 42, name: String::from("make"), start_time: 0, in_job: None, cpu: cpu.to_string(), memory: memory.to_string() };
+        let limit = dialog("50%", "512").to_limit().unwrap();
+        assert_eq!(limit, Limit { cpu_percent: Some(50), memory_mb: Some(512) });
+        assert!(dialog("", "").to_limit().is_err() && dialog("half", "").to_limit().is_err());
+        let args = transient_unit_args("conborli-limit-42.scope", 42, &limit);
+        assert_eq!(
+            args[5..].join(" "),
+            "ssa(sv)a(sa(sv)) conborli-limit-42.scope fail 3 PIDs au 1 42 CPUQuotaPerSecUSec t 500000 MemoryMax t 536870912 0"
+        );
+        let lifted = set_property_args("conborli-limit-42.scope", &Limit { cpu_percent: Some(150), memory_mb: None });
+        assert_eq!(lifted[3..], [String::from("CPUQuota=150%"), String::from("MemoryMax=infinity")]);
+    }
+
+    #[test]
+    fn limits_are_read_back_from_the_cgroup() {
+        assert_eq!(parse_cpu_max("50000 100000\n"), Some(50));
+        assert_eq!(parse_cpu_max("150000 100000"), Some(150));
+        assert_eq!(parse_cpu_max("max 100000"), None);
+        assert_eq!(parse_memory_max("536870912\n"), Some(512));
+        assert_eq!(parse_memory_max("max"), None);
+    }
+}
@@ -0,0 +1,166 @@
+// links to a view - a tab with its sort, search and selection on one line, e.g.
+// "processes?sort=cpu&order=desc&filter=node" or "details?sort=memory&pid=4242". taken by
+// --open, handed to the running window by a later launch, and kept in the tray menu
+//
+// the processes and details tabs each have their own sort, search and selection, the network
+// and connections tabs their own sort. the other tabs only open
+use std::fmt;
+
+use crate::cli;
+use crate::connections::ConnectionColumn;
+use crate::network::NetworkColumn;
+use crate::process::SortColumn;
+use crate::Tab;
+
+const NETWORK_COLUMNS: [NetworkColumn; 5] =
+    [NetworkColumn::Name, NetworkColumn::RxRate, NetworkColumn::TxRate, NetworkColumn::TotalRx, NetworkColumn::TotalTx];
+
+const CONNECTION_COLUMNS: [ConnectionColumn; 6] = [
+    ConnectionColumn::Pid,
+    ConnectionColumn::Process,
+    ConnectionColumn::Protocol,
+    ConnectionColumn::LocalPort,
+    ConnectionColumn::Remote,
+    ConnectionColumn::State,
+];
+
+// a sort column of the tab the link opens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sort {
+    Process(SortColumn),
+    Network(NetworkColumn),
+    Connection(ConnectionColumn),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub tab: Tab,
+    pub sort: Option<Sort>,
+    pub ascending: bool,
+    pub filter: Option<String>,
+    pub pid: Option<u32>,
+}
+
+impl Link {
+    pub fn parse(text: &str) -> Result<Link, String> {
+        let (tab, query) = text.trim().split_once('?').unwrap_or((text.trim(), ""));
+        let tab = cli::parse_tab(tab)?;
+        let mut link = Link { tab, sort: None, ascending: true, filter: None, pid: None };
+        let process_table = matches!(tab, Tab::Processes | Tab::Details);
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(value)?;
+            match key {
+                "sort" => {
+                    link.sort = Some(match tab {
+                        Tab::Processes | Tab::Details => Sort::Process(cli::parse_column(&value)?),
+                        Tab::Network => Sort::Network(find(&NETWORK_COLUMNS, &value)?),
+                        Tab::Connections => Sort::Connection(find(&CONNECTION_COLUMNS, &value)?),
+                        _ => return Err(format!("the {:?} tab has no sort", tab)),
+                    })
+                }
+                "order" => {
+                    link.ascending = match value.as_str() {
+                        "asc" => true,
+                        "desc" => false,
+                        _ => return Err(format!("unknown order {:?}, expected asc or desc", value)),
+                    }
+                }
+                "filter" if process_table => link.filter = Some(value),
+                "pid" if process_table => link.pid = Some(value.parse().map_err(|_| format!("{:?} is not a pid", value))?),
+                "filter" | "pid" => return Err(format!("the {:?} tab has no {}", tab, key)),
+                _ => return Err(format!("unknown key {:?}, expected sort, order, filter or pid", key)),
+            }
+        }
+        Ok(link)
+    }
+}
+
+// the shape `parse` reads, so a view can be saved and opened again
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pairs = Vec::new();
+        match self.sort {
+            Some(Sort::Process(column)) => pairs.push(format!("sort={}", encode(&column.key().to_lowercase()))),
+            Some(Sort::Network(column)) => pairs.push(format!("sort={}", format!("{:?}", column).to_lowercase())),
+            Some(Sort::Connection(column)) => pairs.push(format!("sort={}", format!("{:?}", column).to_lowercase())),
+            None => {}
+        }
+        if !self.ascending {
+            pairs.push(String::from("order=desc"));
+        }
+        if let Some(filter) = self.filter.as_deref().filter(|filter| !filter.is_empty()) {
+            pairs.push(format!("filter={}", encode(filter)));
+        }
+        if let Some(pid) = self.pid {
+            pairs.push(format!("pid={}", pid));
+        }
+        write!(f, "{}", format!("{:?}", self.tab).to_lowercase())?;
+        if !pairs.is_empty() {
+            write!(f, "?{}", pairs.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+fn find<T: Copy + fmt::Debug>(columns: &[T], name: &str) -> Result<T, String> {
+    columns.iter().copied().find(|column| cli::normalize(&format!("{:?}", column)) == cli::normalize(name)).ok_or_else(|| {
+        let names: Vec<String> = columns.iter().map(|column| format!("{:?}", column).to_lowercase()).collect();
+        format!("unknown column, expected one of {}", names.join(", "))
+    })
+}
+
+// %XX escapes and + for a space, as in a url's query
+fn decode(text: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.bytes();
+    while let Some(byte) = rest.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex: Vec<u8> = rest.by_ref().take(2).collect();
+                let value = std::str::from_utf8(&hex).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                bytes.push(value.filter(|_| hex.len() == 2).ok_or_else(|| format!("bad escape in {:?}", text))?);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| format!("{:?} is not utf-8 once unescaped", text))
+}
+
+// escapes what `decode` or the query would read differently
+fn encode(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ' ' => String::from("+"),
+            '%' | '&' | '=' | '+' | '?' | '#' => format!("%{:02X}", c as u8),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_link_reads_back_what_it_writes() {
+        let link = Link::parse("processes?sort=cpu&order=desc&filter=node+server%26co").unwrap();
+        assert_eq!(link.tab, Tab::Processes);
+        assert_eq!(link.sort, Some(Sort::Process(SortColumn::Cpu)));
+        assert!(!link.ascending);
+        assert_eq!(link.filter.as_deref(), Some("node server&co"));
+        assert_eq!(Link::parse(&link.to_string()).unwrap(), link);
+
+        let link = Link::parse("Network?sort=rx-rate").unwrap();
+        assert_eq!((link.sort, link.ascending), (Some(Sort::Network(NetworkColumn::RxRate)), true));
+        assert_eq!(Link::parse("details?pid=42").unwrap().pid, Some(42));
+        assert_eq!(Link::parse("performance").unwrap().to_string(), "performance");
+
+        assert!(Link::parse("performance?sort=cpu").is_err());
+        assert!(Link::parse("network?filter=eth").is_err());
+        assert!(Link::parse("processes?colour=red").is_err());
+        assert!(Link::parse("processes?filter=%4").is_err());
+        assert!(Link::parse("nowhere").is_err());
+    }
+}
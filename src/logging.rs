@@ -0,0 +1,64 @@
+// a log file for bug reports - refresh timings, kills, api requests and errors written with
+// `tracing` to daily files in the logs folder of the config directory, the last week kept.
+// warnings and errors are logged unless --log-level asks for more or for nothing
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+// days of logs kept
+const KEPT_FILES: usize = 7;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogLevel {
+    Off,
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+pub fn dir() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("logs"))
+}
+
+// starts writing the log, lines are written on a background thread until the guard drops
+pub fn init(level: LogLevel) -> Result<Option<WorkerGuard>, String> {
+    if level == LogLevel::Off {
+        return Ok(None);
+    }
+    let dir = dir().ok_or_else(|| String::from("no config directory for the logs"))?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("conborli")
+        .filename_suffix("log")
+        .max_log_files(KEPT_FILES)
+        .build(&dir)
+        .map_err(|err| format!("{}: {}", dir.display(), err))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::fmt()
+        .with_max_level(level.filter())
+        .with_ansi(false)
+        .with_writer(writer)
+        .try_init()
+        .map_err(|err| err.to_string())?;
+    Ok(Some(guard))
+}
@@ -0,0 +1,131 @@
+// low memory mode - once the machine's memory use crosses the threshold in settings, a banner
+// over every tab lists the biggest memory users and offers to kill the largest ones that are
+// safe to kill, leaving out critical processes, ignored ones and the task manager itself.
+// crossing the threshold also fires an alert and, when picked in settings, brings the window
+// back from the tray. the banner goes when use drops a few points below the threshold, or
+// when it is dismissed, until the next crossing
+use iced::{
+    alignment, theme,
+    widget::{Button, Column, Row, Text},
+    Color, Element, Length,
+};
+
+use crate::critical;
+use crate::filter::IgnoreList;
+use crate::format;
+use crate::heat;
+use crate::kill_guard::Target;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// points below the threshold memory use has to fall to end the mode, so use hovering around
+// the threshold doesn't fire an alert on every refresh
+const HYSTERESIS: f32 = 5.0;
+
+// processes offered to kill
+const CANDIDATES: usize = 3;
+
+#[derive(Debug, Default)]
+pub struct LowMemory {
+    pub active: bool,
+    pub dismissed: bool,
+    // percent of memory in use at the last reading
+    pub used: f32,
+}
+
+impl LowMemory {
+    // true when this reading crossed the threshold, 0 turns the mode off
+    pub fn update(&mut self, used: f32, threshold: u8) -> bool {
+        self.used = used;
+        if threshold == 0 {
+            self.active = false;
+            return false;
+        }
+        let threshold = f32::from(threshold);
+        if !self.active && used >= threshold {
+            self.active = true;
+            self.dismissed = false;
+            return true;
+        }
+        if self.active && used < threshold - HYSTERESIS {
+            self.active = false;
+        }
+        false
+    }
+
+    pub fn shown(&self) -> bool {
+        self.active && !self.dismissed
+    }
+}
+
+// the largest memory users that are safe to kill, largest first
+pub fn candidates<'a>(processes: &'a [ProcessInfo], critical: &[String], ignored: &IgnoreList) -> Vec<&'a ProcessInfo> {
+    let own = std::process::id();
+    let killable = processes.iter().filter(|process| process.pid != own && !critical::is_critical(process, critical) && !ignored.ignores(process));
+    heat::top(killable, CANDIDATES, |process| process.memory as f64)
+}
+
+// construct the banner, without kill buttons in read-only mode or for a remote host
+pub fn view<'a>(low: &LowMemory, processes: &'a [ProcessInfo], candidates: Vec<&'a ProcessInfo>, can_kill: bool, color: Color) -> Element<'a, Message> {
+    let top = heat::top(processes.iter(), heat::TOP, |process| process.memory as f64);
+    let users: Vec<String> = top.iter().map(|process| format!("{} {} MB", process.name, format::integer(process.memory))).collect();
+    let kills = candidates.into_iter().fold(Row::new().spacing(10).push(Text::new("Free memory:")), |row, process| {
+        let label = format!("Kill {} ({}), {} MB", process.name, process.pid, format::integer(process.memory));
+        row.push(Button::new(Text::new(label)).on_press_maybe(can_kill.then_some(Message::KillProcess(Target::of(process)))))
+    });
+    Column::new()
+        .spacing(5)
+        .push(
+            Row::new()
+                .spacing(10)
+                .align_items(alignment::Alignment::Center)
+                .push(
+                    Text::new(format!("Memory is running low, {}% in use", format::decimal(f64::from(low.used), 0)))
+                        .style(theme::Text::Color(color))
+                        .width(Length::Fill)
+                )
+                .push(Button::new("Dismiss").on_press(Message::DismissLowMemory)),
+        )
+        .push(Text::new(format!("Biggest users: {}", users.join(", "))))
+        .push(kills)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn the_mode_starts_at_the_threshold_and_ends_below_it() {
+        let mut low = LowMemory::default();
+        assert!(!low.update(80.0, 90));
+        assert!(low.update(91.0, 90));
+        assert!(!low.update(95.0, 90));
+        low.dismissed = true;
+        // still within the hysteresis
+        assert!(!low.update(87.0, 90));
+        assert!(low.active && !low.shown());
+        assert!(!low.update(84.0, 90));
+        assert!(!low.active);
+        assert!(low.update(90.0, 90));
+        assert!(low.shown());
+        assert!(!low.update(99.0, 0));
+        assert!(!low.active);
+    }
+
+    #[test]
+    fn candidates_leave_out_protected_processes() {
+        let processes = vec![
+            mock::process(1, "systemd", "root", 9000, 0.0),
+            mock::process(20, "firefox", "u", 4000, 0.0),
+            mock::process(30, "postgres", "u", 3000, 0.0),
+            mock::process(std::process::id(), "conborli", "u", 8000, 0.0),
+            mock::process(50, "slack", "u", 2000, 0.0),
+            mock::process(60, "code", "u", 1000, 0.0),
+        ];
+        let pids = |critical: &[String]| candidates(&processes, critical, &IgnoreList::default()).iter().map(|process| process.pid).collect::<Vec<_>>();
+        assert_eq!(pids(&[]), vec![20, 30, 50]);
+        assert_eq!(pids(&[String::from("postgres")]), vec![20, 50, 60]);
+    }
+}
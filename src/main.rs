@@ -1,14 +1,199 @@
 // builds the GUI
-use iced::{ theme::Theme,
-    alignment, executor, time, Application, Command, Element, Length, Settings, Subscription,
-    widget::{Button, Column, Container, Row, Scrollable, Text},
+use iced::{ theme::{self, Theme},
+    alignment, clipboard, event, keyboard, multi_window::Application, time, window, Command, Element, Event, Length, Settings, Size, Subscription,
+    widget::{focus_next, focus_previous, scrollable, text_input, tooltip, Button, Checkbox, Column, Container, MouseArea, PickList, Row, Space, Text, TextInput, Tooltip},
 };
 
+use serde::{Deserialize, Serialize};
+
 // gathers info about system
-use sysinfo::{Pid, System};
+use sysinfo::{Components, Disks, Networks, Pid, System, Users};
 
 // from std library to define time intervals
-use std::time::Duration;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+mod about;
+mod accent;
+mod apps;
+mod autostart;
+mod bar_cell;
+mod battery;
+mod boost;
+mod bench;
+mod cgroups;
+mod chart;
+mod churn;
+mod cli;
+mod close_watch;
+mod compare;
+mod collector;
+mod columns;
+mod config;
+mod connections;
+mod containers;
+mod crash;
+mod critical;
+mod daemon;
+mod details;
+mod detached;
+mod diff;
+mod disks;
+mod dump;
+mod energy;
+mod environment;
+mod events;
+mod executables;
+mod focus;
+mod filter;
+mod format;
+mod fuzzy;
+mod gpu;
+mod grid;
+mod heat;
+mod history;
+mod holders;
+mod hotkey;
+mod icons;
+mod impact;
+mod instance;
+mod integrity;
+mod jobs;
+mod keys;
+mod kill_guard;
+mod kill_pattern;
+mod layout;
+mod limits;
+mod links;
+mod logging;
+mod low_memory;
+mod maps;
+#[cfg(feature = "server")]
+mod metrics;
+mod network;
+mod notes;
+mod notify;
+mod oom;
+mod online;
+mod open;
+mod open_files;
+mod palette;
+mod performance;
+mod pick_window;
+mod plugins;
+mod priority;
+mod process;
+mod profiling;
+mod properties;
+mod procfs;
+mod provider;
+mod query;
+mod recorder;
+mod remote;
+mod restart;
+mod rule_editor;
+mod row_menu;
+mod rules;
+mod run;
+mod scheduled;
+mod scheduler;
+mod scripts;
+mod security;
+mod sensors;
+#[cfg(feature = "server")]
+mod server;
+mod services;
+mod session;
+mod settings;
+#[cfg(unix)]
+mod signals;
+#[cfg(any(test, feature = "sim"))]
+mod sim;
+mod slices;
+mod snapshot;
+mod soft_kill;
+mod sockets;
+mod startup;
+#[cfg(feature = "server")]
+mod stream;
+mod style;
+mod summary;
+#[cfg(test)]
+mod tests;
+mod themes;
+mod threads;
+mod timeseries;
+mod tray;
+mod tui;
+mod users;
+mod virtual_list;
+mod watchdog;
+mod widget;
+
+use battery::BatteryStatus;
+use config::{Config, WindowGeometry};
+use about::SystemInfo;
+use accent::ThemeChoice;
+use apps::{Group, ListRow};
+use autostart::Autostart;
+use boost::Boost;
+use clap::Parser;
+use cli::{Cli, Overrides};
+use churn::Churn;
+use close_watch::CloseWatch;
+use collector::Shared;
+use connections::{ConnectionColumn, ConnectionInfo};
+use containers::{ContainerId, ContainerNames};
+use details::{DetailSection, DetailsPane};
+use detached::Detached;
+use jobs::Job;
+use dump::{DumpDialog, Dumped};
+use energy::EnergySampler;
+use events::{EventKind, EventLog};
+use executables::{Executable, FirstSeen};
+use disks::{DiskInfo, IoCounters};
+use filter::{Filter, FilterPreset, Pin, Scope};
+use gpu::{GpuDevice, GpuMonitor};
+use grid::DetailsGrid;
+use history::History;
+use hotkey::Hotkey;
+use icons::IconCache;
+use impact::{ExternalImpact, KillImpact};
+use kill_guard::Policy;
+use layout::{Fit, Layout};
+use links::{Link, Sort};
+use network::{NetworkColumn, NetworkInfo};
+use notes::NoteDraft;
+use online::OnlineFeature;
+use palette::Palette;
+use notify::ExitWatches;
+use process::{ProcessInfo, SortColumn};
+use profiling::Timings;
+use properties::Properties;
+use provider::{ProcessProvider, SystemProvider};
+use remote::{RemoteHost, RemoteProvider};
+use recorder::Recorder;
+use rule_editor::{RuleDraft, RuleEvent, RuleField};
+use row_menu::RowAction;
+use rules::RuleAction;
+use run::RunDialog;
+use scripts::Scripts;
+use scheduled::{ActionKind, ScheduleDialog, ScheduledAction, Target};
+use scheduler::{Load, Scheduler, Source};
+use sensors::SensorInfo;
+use threads::ThreadColumn;
+use timeseries::{MachineHistory, Series};
+use tray::{Tray, TrayEvent};
+use services::{ServiceAction, ServiceInfo};
+use session::{Playback, Recording};
+use snapshot::Snapshot;
+use soft_kill::PendingKill;
+use style::{Highlights, Ui};
+use themes::CustomTheme;
+use virtual_list::ListWindow;
+use watchdog::{WatchEntry, Watchdog};
+use widget::{Compact, Totals};
 
 struct TaskManager {
     // list of current running procersses using the ProcessInfo struct
@@ -17,189 +202,4802 @@ struct TaskManager {
     sort_column: SortColumn,
     // indicates sorting as ascending or descending
     sort_ascending: bool,
+    // further (column, ascending) keys breaking ties of the sort column, added by shift-clicking headers
+    sort_then: Vec<(SortColumn, bool)>,
+    // held modifier keys, shift-clicking a header extends the sort instead of replacing it
+    modifiers: keyboard::Modifiers,
+    // search box contents used to filter the process list
+    filter: Filter,
+    // pid of the focused row, kept by pid so it survives re-sorting
+    selected_pid: Option<u32>,
+    // pointer is over the process list
+    list_hovered: bool,
+    // scroll position of the process list, decides which rows are built
+    list_window: ListWindow,
+    // sort, search and scroll position of the details tab
+    grid: DetailsGrid,
+    // details of the selected process shown under the list
+    details: DetailsPane,
+    // processes that appeared while the order was frozen, hidden until it thaws
+    deferred_pids: HashSet<u32>,
+    // last smoothed cpu percent of each pid
+    cpu_history: HashMap<u32, f32>,
+    // recent samples of each pid, for the trend columns
+    history: History,
+    // stops the scheduler tick so the list holds still, manual refreshes still work
+    paused: bool,
+    // the selection of whichever of the processes and details tabs isn't shown, each keeps its own
+    other_selection: Option<u32>,
+    // the first scan still running in the background, see startup.rs
+    startup: Option<startup::Stage>,
+    // source of the process rows, swapped for canned data in tests
+    provider: Box<dyn ProcessProvider>,
     // instance of sysinfo to gather and refresh system data
     system: System,
+    // user accounts, used to show who owns each process
+    users: Users,
+    // groups of the processes tab folded to their heading
+    collapsed_groups: HashSet<Group>,
+    // users expanded in the users tab and the one whose kill all awaits confirmation
+    expanded_users: HashSet<String>,
+    // executables tab: the groups listing their instances, and when each executable was first seen
+    expanded_executables: HashSet<Executable>,
+    first_seen: FirstSeen,
+    confirm_kill_user: Option<String>,
+    // which tab is currently shown
+    tab: Tab,
+    // sysinfo network interfaces and the rows derived from them
+    networks: Networks,
+    network_rows: Vec<NetworkInfo>,
+    network_sort_column: NetworkColumn,
+    network_sort_ascending: bool,
+    // when the network was last refreshed, used to compute per-second rates
+    last_network_refresh: Instant,
+    // sockets of every process, only collected while the connections tab is shown
+    connection_rows: Result<Vec<ConnectionInfo>, String>,
+    // the walk of /proc for their owners is running in the background
+    reading_sockets: bool,
+    // the security tab's findings, collected while it is shown
+    security: security::Audit,
+    connection_sort_column: ConnectionColumn,
+    connection_sort_ascending: bool,
+    // sysinfo disks, only refreshed while the disks tab is shown
+    disks: Disks,
+    disk_rows: Vec<DiskInfo>,
+    disk_io: IoCounters,
+    last_disk_refresh: Instant,
+    // sysinfo temperature sensors, refreshed on tick
+    components: Components,
+    sensor_rows: Vec<SensorInfo>,
+    // system services, only listed while the services tab is shown
+    services: Vec<ServiceInfo>,
+    services_error: Option<String>,
+    // decides which data sources to refresh on each tick
+    scheduler: Scheduler,
+    // durations shown in the footer, only with --debug-timings
+    timings: Option<Timings>,
+    // persisted settings, saved when the window is closed
+    config: Config,
+    // config values replaced by command-line flags for this run only
+    overrides: Overrides,
+    // display the window is currently on and its current geometry
+    current_display: String,
+    geometry: WindowGeometry,
+    // logical width of the main window, maximized or not, for fitting the process table
+    window_width: u32,
+    // size from the last resize until it is known whether the window was maximized
+    pending_size: Option<(u32, u32)>,
+    // whether the window is shrunk to the mini window
+    compact: Option<Compact>,
+    // global shortcut registration, the text being edited in settings and the last error
+    hotkey: Hotkey,
+    hotkey_input: String,
+    hotkey_status: Option<String>,
+    // tray icon, inactive when turned off or unsupported, and whether the window is hidden to it
+    tray: Tray,
+    window_hidden: bool,
+    // whether the window has the focus and is minimized, collection pauses in the background
+    focused: bool,
+    minimized: bool,
+    // the platform's start on login entry, read at startup and after changing it
+    autostart: Option<Autostart>,
+    // where the http api reads the table from, only set when it is served
+    shared: Option<Shared>,
+    // remote host whose processes are shown, None for this computer, and the address being typed
+    host: Option<usize>,
+    host_input: String,
+    // started by a message besides its own Command, e.g. kills sent to an agent
+    deferred: Vec<Command<Message>>,
+    // preset last picked or saved, and the name typed for the next one
+    preset: Option<String>,
+    preset_input: String,
+    // compiled user scripts and their log
+    scripts: Scripts,
+    // text size the window started with and the density, shared by the views
+    ui: Ui,
+    // theme files found in the themes directory
+    themes: Vec<CustomTheme>,
+    // names of docker/podman containers by id, and whether new ids need a lookup
+    container_names: ContainerNames,
+    container_lookup_pending: bool,
+    // the container of each process by pid and start time, detected in the background as it
+    // reads one cgroup file per process, and whether a detection is running
+    container_ids: HashMap<(u32, u64), Option<ContainerId>>,
+    detecting_containers: bool,
+    // stored process table snapshots, the name typed for the next one and the compared pair,
+    // a target of None compares against the live table
+    snapshots: Vec<Snapshot>,
+    snapshot_name_input: String,
+    snapshot_base: Option<String>,
+    snapshot_target: Option<String>,
+    // session being recorded, the one replayed in the processes tab instead of the live table,
+    // the stored recordings and the one picked for replay
+    recording: Option<Recording>,
+    playback: Option<Playback>,
+    recordings: Vec<String>,
+    picked_recording: Option<String>,
+    // processes started and exited on this machine, and whether their log is shown
+    churn: Churn,
+    churn_log_open: bool,
+    // processes that send a desktop notification when they exit
+    exit_watches: ExitWatches,
+    // notifications held back in focus mode
+    focus: focus::Focus,
+    // whether this machine's memory use is past the threshold in settings
+    low_memory: low_memory::LowMemory,
+    // peaks of this run, and the summary panel when it is shown
+    session: summary::Session,
+    session_summary: Option<summary::Summary>,
+    // the background monitor this window attached to at launch, see daemon.rs
+    daemon: Option<daemon::Attached>,
+    // previous energy readings for the macOS energy column
+    energy: EnergySampler,
+    icons: IconCache,
+    // processes stopped from the row menu, which offers to resume them instead
+    suspended: HashSet<u32>,
+    // metrics database, None while recording is off, and what the history tab shows from it
+    recorder: Option<Recorder>,
+    history_window: recorder::Window,
+    history_rank: recorder::Rank,
+    recorded_peaks: Option<Result<Vec<recorder::Peak>, String>>,
+    history_export_input: String,
+    // cpu and memory over time for the performance charts, and the range they show
+    machine_history: MachineHistory,
+    // what happened since launch, the kinds the events tab shows and the file it exports to
+    events: EventLog,
+    event_kinds: HashSet<EventKind>,
+    event_export_input: String,
+    chart_range: timeseries::Range,
+    // gpu backends and the cards they reported on the last performance refresh
+    gpu: GpuMonitor,
+    gpu_devices: Vec<GpuDevice>,
+    // battery backend, None if the platform has none, and the last reading
+    battery_manager: Option<starship_battery::Manager>,
+    batteries: Vec<BatteryStatus>,
+    // whether the quick column toggle is open and its search text
+    column_picker_open: bool,
+    // header being dragged to another place, see `Message::DragColumn`
+    dragged_column: Option<SortColumn>,
+    column_search: String,
+    // run new task dialog, None while closed
+    run_dialog: Option<RunDialog>,
+    kill_pattern: Option<kill_pattern::KillPatternDialog>,
+    // the tag and note being edited, see notes.rs
+    note_draft: Option<NoteDraft>,
+    // open while the command palette is shown
+    palette: Option<Palette>,
+    // processes opened in windows of their own, and the one with the keyboard focus, None
+    // while the main window has it
+    detached: HashMap<window::Id, Detached>,
+    focused_detached: Option<window::Id>,
+    // processes started from the run dialog with their descendants
+    jobs: Vec<Job>,
+    // what killing the process would affect, shown until the kill is confirmed or cancelled
+    kill_preview: Option<KillImpact>,
+    // confirmed kills still in their undo delay
+    pending_kills: Vec<PendingKill>,
+    // the process read every 250 ms for the panel above the footer
+    close_watch: Option<CloseWatch>,
+    // the two pids compared side by side, the selected one first
+    comparison: Option<[u32; 2]>,
+    // the boosted process, put back when its time is up
+    boost: Option<Boost>,
+    // report of a crash since the last launch, until it is opened or dismissed
+    crash_report: Option<std::path::PathBuf>,
+    // properties dialog of one process, and the last row click to tell double clicks apart
+    properties: Option<Properties>,
+    last_click: Option<(u32, Instant)>,
+    // file used by the rules export/import buttons in settings
+    rules_path_input: String,
+    // file used by the settings export/import buttons
+    settings_path_input: String,
+    // process name typed into the ignore list on the settings tab
+    ignore_input: String,
+    // process name typed into the critical list on the settings tab
+    critical_input: String,
+    // link typed in to be listed in the tray menu
+    tray_link_input: String,
+    // rule open in the editor, recent rule matches and the (rule, pid) pairs already acted on
+    rule_draft: Option<RuleDraft>,
+    rule_log: VecDeque<RuleEvent>,
+    rule_hits: HashSet<(String, u32)>,
+    // live state and restart log of the watched processes
+    watchdog: Watchdog,
+    // limit dialog, None while closed, and the limits put on processes by pid
+    limit_dialog: Option<limits::LimitDialog>,
+    limits: HashMap<u32, limits::Limit>,
+    // schedule dialog, None while closed, the actions waiting for their time and the next id
+    schedule_dialog: Option<ScheduleDialog>,
+    scheduled: Vec<ScheduledAction>,
+    next_action_id: u64,
+    // send signal dialog, None while closed
+    #[cfg(unix)]
+    signal_dialog: Option<signals::SignalDialog>,
+    // dump and kill dialog, None while closed
+    dump_dialog: Option<DumpDialog>,
+    // result of the last action (e.g. a failed renice), shown in the footer
+    status_message: Option<String>,
+    // hostname, os and hardware for the about panel, read at startup and on request
+    system_info: SystemInfo,
+    // accent color reported by the desktop and the theme built from the settings
+    system_accent: Option<iced::Color>,
+    theme: Theme,
 }
 
-#[derive(Debug, Clone)]
-struct ProcessInfo {
-    // holds info about each process
-    pid: u32,
-    name: String,
-    memory: u64,
-    cpu: f32,
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum Tab {
+    // defines the tabs shown at the top of the window
+    #[default]
+    Processes,
+    // every column with its own sort and search, see grid.rs
+    Details,
+    Performance,
+    Network,
+    Connections,
+    Disks,
+    Sensors,
+    Services,
+    Users,
+    // processes grouped by executable path, see executables.rs
+    Executables,
+    Rules,
+    Scripts,
+    Snapshots,
+    History,
+    // what happened since launch, see events.rs
+    Events,
+    // processes worth auditing, see security.rs
+    Security,
+    Settings,
+}
+
+impl Tab {
+    const ALL: [Tab; 17] = [
+        Tab::Processes,
+        Tab::Details,
+        Tab::Performance,
+        Tab::Network,
+        Tab::Connections,
+        Tab::Disks,
+        Tab::Sensors,
+        Tab::Services,
+        Tab::Users,
+        Tab::Executables,
+        Tab::Rules,
+        Tab::Scripts,
+        Tab::Snapshots,
+        Tab::History,
+        Tab::Events,
+        Tab::Security,
+        Tab::Settings,
+    ];
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum SortColumn {
-    // defines columns which can be sorted
+enum CopyField {
+    // parts of a process that can be copied to the clipboard
     Pid,
     Name,
-    Memory,
-    Cpu,
+    CommandLine,
+    // every visible column, tab separated
+    Row,
 }
 
 #[derive(Debug, Clone)]
 
 // different types of messages/events the app can handle
 enum Message {
+    // the first sample of the background startup scan, then the second one being due
+    StartupRead(startup::Scanned),
+    StartupSampled,
+    // the background monitor answered at launch, None when none is running
+    DaemonAttached(Option<daemon::Attached>),
     // changes the sorting based on the selected column    
     Sort(SortColumn),
+    ModifiersChanged(keyboard::Modifiers),
+
+    // kills the process it was aimed at, unless its pid has been reused since
+    KillProcess(kill_guard::Target),
+    // an agent's answer to a kill sent to it, and who asked for the kill
+    RemoteKilled(kill_guard::Target, String, Result<(), String>),
+    // background part of the preview of what killing the process would affect
+    KillImpactReady(u32, ExternalImpact),
+    CancelKill,
+    // the "I understand" box of the preview of a critical process
+    AcknowledgeCritical(bool),
+    // kills the process and everything below it, after the same preview
+    KillTree(kill_guard::Target),
+    // takes back a kill that is still waiting, and sends the ones whose time has come
+    UndoKill(u32),
+    SendPendingKills,
+    // the fast reading of the closely watched process, and ending the watch
+    SampleCloseWatch,
+    StopCloseWatch,
+    CloseComparison,
+    // a process's own window was closed, or gained or lost the focus
+    DetachedClosed(window::Id),
+    DetachedFocused(window::Id, bool),
+    // a keyboard shortcut, acted on by the window that has the focus, see keys.rs
+    Shortcut(Box<Message>),
+    // kills everything in a job started from the run dialog, or stops following it
+    TerminateJob(usize),
+    ForgetJob(usize),
+    // the command palette, its search, and running the entry at an index of its matches
+    OpenPalette,
+    PaletteQueryChanged(String),
+    RunPaletteEntry(usize),
+    // editing the tag and note of a program
+    SetNoteTag(Option<notes::Tag>),
+    NoteTextChanged(String),
+    SaveNote,
+    CancelNote,
+    // puts the boosted process and the lowered ones back
+    EndBoost,
+    // the notice about the last crash
+    OpenCrashReport,
+    DismissCrashReport,
+    // entry picked from a row's "⋮" menu
+    RowAction(u32, RowAction),
+
+    // search box edits
+    SearchChanged(String),
+
+    // row selection: click, keyboard movement by the given number of rows, and clearing it
+    SelectProcess(u32),
+    MoveSelection(i32),
+    // properties dialog of a process, opened by double or right clicking its row
+    OpenProperties(u32),
+    CloseProperties,
+    // selects a process, e.g. the parent from the ppid column, and scrolls to its row
+    JumpToProcess(u32),
+    // clicking a window on screen to select its process, or to open the kill preview for it
+    PickWindow(bool),
+    WindowPicked(bool, Result<u32, String>),
+    // pointer entered/left the process list, which freezes/thaws the row order
+    ListHovered(bool),
+    ListScrolled(scrollable::Viewport),
+    // details tab: sort by a column, search, list scrolled
+    GridSort(SortColumn),
+    GridSearchChanged(String),
+    GridScrolled(scrollable::Viewport),
+
+    // details pane: show/hide it, switch section, sort the thread list
+    ToggleDetails,
+    SelectDetailSection(DetailSection),
+    SortThreads(ThreadColumn),
+    ResourceFilterChanged(String),
+    EnvironmentFilterChanged(String),
+    ToggleEnvironmentDiff(bool),
+    // the oom adjustment typed into the cgroup section, and setting it
+    OomAdjInputChanged(String),
+    SetOomAdj(u32, i32),
+    // hashes the executable in the background, see integrity.rs
+    CheckIntegrity(u32, PathBuf),
+    IntegrityChecked(u32, Result<integrity::Integrity, String>),
+    ToggleFreezeOrder(bool),
+    ToggleUndoKills(bool),
+    SetBoostMinutes(u64),
+    ToggleBoostLowersOthers(bool),
+    ToggleReadOnly(bool),
+    ToggleHeatPanel(bool),
+    ToggleTotalsRow(bool),
+    ToggleBarCells(bool),
+    // window width below which the process table narrows, 0 for never
+    SetNarrowWidth(u32),
+    ToggleProcessIcons(bool),
+    ToggleLoadInTitle(bool),
+    ToggleChurnLog,
+    // column used to order rows that tie in the sort column
+    SetSecondarySort(SortColumn),
+    ClearSelection,
+
+    // keyboard shortcuts: kill the selected row (or its tree), open its properties, focus the
+    // search box, move the focus between inputs, switch tabs, refresh now, and sort by the
+    // n-th visible column
+    KillSelected,
+    KillSelectedTree,
+    OpenSelectedProperties,
+    FocusSearch,
+    FocusNext,
+    FocusPrevious,
+    CycleTab(i32),
+
+    // copies part (or all) of the selected row to the clipboard
+    CopySelected(CopyField),
+    // copies the text as is, e.g. an environment variable from the details pane
+    CopyText(String),
+    // opens the virustotal page of the hash in the browser, if network access is allowed
+    LookUpHash(String),
+    // reads the about this system panel again
+    RefreshSystemInfo,
+    // opens the directory holding the process's executable
+    OpenFileLocation(u32),
+    // kills the process and starts it again with the same command line
+    RestartProcess(kill_guard::Target),
+    RestartDone(Result<u32, String>),
+    // schedule dialog for a process, its fields, and adding or dropping pending actions
+    OpenSchedule(u32),
+    // limit dialog: the typed cpu and memory caps, applying or lifting them
+    LimitCpuChanged(String),
+    LimitMemoryChanged(String),
+    ApplyLimit,
+    RemoveLimit,
+    CloseLimit,
+    // the limit now on the process, empty once lifted
+    LimitApplied(u32, String, Result<limits::Limit, String>),
+    // limits left on processes by an earlier run
+    LimitsFound(Vec<(u32, limits::Limit)>),
+    SetScheduleKind(ActionKind),
+    ToggleScheduleByName(bool),
+    ScheduleWhenChanged(String),
+    ConfirmSchedule,
+    CloseSchedule,
+    CancelScheduled(u64),
+    // fired every second while actions are pending
+    RunDueActions,
+    // send signal dialog for a process, the picked signal, sending it and closing the dialog
+    #[cfg(unix)]
+    OpenSignals(kill_guard::Target),
+    #[cfg(unix)]
+    SelectSignal(signals::Signal),
+    #[cfg(unix)]
+    SendSignal,
+    #[cfg(unix)]
+    CloseSignals,
+    // dump and kill dialog for a process, its directory, starting the dump and its result
+    OpenDump(u32),
+    DumpDirChanged(String),
+    ConfirmDump,
+    CloseDump,
+    DumpDone(String, Result<Dumped, String>),
+    // desktop notification when the process exits, without restarting it
+    NotifyOnExit(u32),
+    CancelExitNotification(u32),
+    // focus mode holds notifications back, the digest lists them
+    ToggleFocusMode,
+    ToggleFocusDigest,
+    // hides the low memory banner until memory use next crosses the threshold
+    DismissLowMemory,
+    SetLowMemoryThreshold(u8),
+    ToggleLowMemoryShowsWindow(bool),
+    ShowSessionSummary,
+    CloseSessionSummary,
+    SaveSessionSummary(summary::SummaryFormat),
+    // written when the app closes, off for none
+    SetSummaryOnExit(summary::SummaryFormat),
+    ToggleBackgroundMonitor(bool),
+    ClearFocusDigest,
+    ShowAlertEvents,
+    // restarts the process whenever it exits, or stops doing so
+    WatchProcess(u32),
+    Unwatch(usize),
+
+    // run new task dialog
+    OpenRunDialog,
+    CloseRunDialog,
+    RunCommandChanged(String),
+    RunDirChanged(String),
+    RunGroupNameChanged(String),
+    RunNiceChanged(String),
+    RunAffinityChanged(String),
+    RunEnvironmentChanged(String),
+    ToggleRunElevated(bool),
+    RunTask,
+
+    // kill by pattern dialog, its pattern and what it matches, and killing every match
+    OpenKillPattern,
+    KillPatternChanged(String),
+    SetKillPatternSyntax(kill_pattern::Syntax),
+    ToggleKillPatternCommandLine(bool),
+    KillByPattern,
+    CloseKillPattern,
+    RefreshNow,
+    SortByIndex(usize),
+
+    // opens/closes the column picker, filters it, and shows/hides a column
+    ToggleColumnPicker,
+    ColumnSearchChanged(String),
+    ToggleColumn(SortColumn, bool),
+    // relative width of a column, set from the column picker
+    SetColumnWidth(SortColumn, u16),
+    // how a column's numbers are written, also set from the column picker
+    SetColumnFormat(SortColumn, columns::NumberFormat),
+    // dragging a header by its grip onto another one moves the column there, a release
+    // anywhere else ends the drag
+    DragColumn(SortColumn),
+    DropColumn(SortColumn),
+    EndColumnDrag,
+
+    // users tab: expand a user, kill everything they own (after confirming), sign them out
+    ToggleUserExpanded(String),
+    ToggleExecutableExpanded(Executable),
+    ConfirmKillUserProcesses(String),
+    KillUserProcesses(String),
+    SignOutUser(String),
+    SignOutDone(Result<(), String>),
 
-    // kills the process with the given PID
-    KillProcess(u32),
+    // changes the nice value of the process with the given PID by the given amount
+    AdjustPriority(u32, i32),
 
-    // triggers an update or refresh of the process list every few seconds
+    // wakes the scheduler, which refreshes whatever data sources are due
     Tick,
+    // stops/resumes the tick
+    TogglePause,
+    // names of docker/podman containers looked up in the background
+    ContainerNamesFound(HashMap<String, String>),
+    // the containers of processes not seen before, by pid and start time
+    ContainersDetected(Vec<((u32, u64), Option<ContainerId>)>),
+    // shows host processes, containerized ones, or both
+    SetScope(Scope),
+    // shows only suspected leaks, and how many growing samples in a row make one
+    ToggleLeakFilter(bool),
+    // shows only processes running as root or SYSTEM
+    ToggleElevatedFilter(bool),
+    // lists or hides kernel threads and processes of system accounts
+    ToggleSystemProcesses(bool),
+    // lists apps apart from background processes, and folds or unfolds one of the groups
+    ToggleGroupApps(bool),
+    ToggleGroupSlices(bool),
+    ToggleGroup(Group),
+    // starts the task manager again with administrator rights and closes this one
+    RelaunchElevated,
+    // keeps a process above the sorted rows, and drops every pin matching the pid's process
+    Pin(Pin),
+    Unpin(u32),
+
+    // ignore list: a name typed in settings, adding it or a selected process's name, removing one
+    IgnoreInputChanged(String),
+    AddIgnored,
+    IgnoreProcess(String),
+    RemoveIgnored(String),
+
+    // critical list: a name typed in settings, adding it, removing one
+    CriticalInputChanged(String),
+    AddCritical,
+    RemoveCritical(String),
+
+    // filter presets: apply one, type a name, save the current filters under it, delete one
+    ApplyPreset(String),
+    PresetInputChanged(String),
+    SavePreset,
+    DeletePreset(String),
+    SetLeakSamples(u32),
+
+    // changes how often a data source is refreshed
+    SetRefreshInterval(Source, u64),
+    ToggleAdaptiveRefresh(bool),
+    ToggleBatterySaver(bool),
+    TogglePauseWhenUnfocused(bool),
+    // number of samples the cpu column is averaged over
+    SetCpuSmoothing(u32),
+    // switches the cpu column between % of one core and % of the machine
+    ToggleCpuOfWholeMachine(bool),
+    // low-activity filter switch and its thresholds
+    ToggleNoiseFilter(bool),
+    SetNoiseMinCpu(f32),
+    SetNoiseMinMemory(u64),
+
+    // switches to the given tab
+    SelectTab(Tab),
+
+    // changes the sorting of the network tab based on the selected column
+    SortNetwork(NetworkColumn),
+    SortConnections(ConnectionColumn),
+    // every socket with its owner, read in the background for the connections tab
+    SocketsRead(Result<Vec<(Option<u32>, sockets::Socket)>, String>),
+
+    // starts/stops/restarts the named service, and reports back when done
+    ServiceAction(String, ServiceAction),
+    ServiceActionDone(Result<(), String>),
+
+    // changes the ui scale from the settings tab
+    SetUiScale(f64),
+
+    // drops the remembered window geometry of the given display
+    ForgetDisplayGeometry(String),
+
+    // window events used to track position/size per display
+    WindowMoved(i32, i32),
+    WindowResized(u32, u32),
+    MaximizedFetched(bool),
+    WindowCloseRequested,
+    // focus changes, and whether losing the focus was minimizing, to pause in the background
+    WindowFocused(bool),
+    WindowMinimized(Option<bool>),
+
+    // switches between the full window and the mini window
+    SetCompact(Option<Compact>),
+
+    // the global shortcut was pressed somewhere on the desktop
+    HotkeyPressed,
+    // the app was launched again while this window runs, maybe with a view to open
+    AnotherInstanceLaunched(Option<Link>),
+    // tray menu actions, and the tray settings
+    Tray(TrayEvent),
+    // the views of the tray menu: type a link, add it or the processes tab's view, remove one
+    TrayLinkInputChanged(String),
+    AddTrayLink,
+    AddProcessesView,
+    RemoveTrayLink(usize),
+    ToggleTrayIcon(bool),
+    ToggleCloseToTray(bool),
+    ToggleStartMinimized(bool),
+    // installs or removes the autostart entry, and whether it starts hidden
+    ToggleAutostart(bool),
+    ToggleAutostartMinimized(bool),
+
+    // edits and applies the global shortcut from the settings tab
+    HotkeyInputChanged(String),
+    ApplyHotkey,
+
+    // base palette, and switching between the system accent color and the palette's own
+    SetTheme(ThemeChoice),
+    ToggleSystemAccent(bool),
+
+    // picks a theme file, and reads the themes directory again
+    SetCustomTheme(String),
+    ReloadThemes,
+
+    // default text size for the next start, and the row height and spacing of the views
+    SetTextSize(u16),
+    SetDensity(style::Density),
+    // simple or advanced mode, from the switcher at the end of the tab row
+    SetLayout(Layout),
+    // number and clock formats, see format.rs
+    SetNumberStyle(format::NumberStyle),
+    SetClock(format::Clock),
+
+    // allows or forbids features that contact remote services
+    ToggleNetworkAccess(bool),
+    // switches the process table to an agent or back to this computer, adds or removes an agent
+    SelectHost(Option<usize>),
+    // turns a user script on or off, and reads the scripts directory again
+    ToggleScript(String, bool),
+    ReloadScripts,
+    HostInputChanged(String),
+    AddHost,
+    RemoveHost(usize),
+    // snapshot name typed, saving the live table under it, picking the compared pair, deleting one
+    SnapshotNameChanged(String),
+    TakeSnapshot,
+    SelectSnapshotBase(String),
+    SelectSnapshotTarget(Option<String>),
+    DeleteSnapshot(String),
+    // session recording on/off, and replaying a stored one: picking it, starting, play/pause,
+    // moving the slider and going back to the live table
+    StartRecording,
+    StopRecording,
+    PickRecording(String),
+    StartPlayback,
+    TogglePlaying,
+    SeekPlayback(u32),
+    StopPlayback,
+    // turns the metrics recorder on/off and sets how many days of samples it keeps
+    ToggleRecorder(bool),
+    SetRetentionDays(u32),
+    // time span of the performance charts
+    SelectChartRange(timeseries::Range),
+    // performance chart drawn offscreen, onto the clipboard or into a png file
+    CopyChart(performance::ChartKind),
+    SaveChart(performance::ChartKind),
+    ChartExported(Result<String, String>),
+    // kinds shown in the events tab, and the export of the shown ones
+    ToggleEventKind(EventKind, bool),
+    EventExportPathChanged(String),
+    ExportEvents,
+    // window queried by the history tab, and the csv export of its samples
+    SelectHistoryWindow(recorder::Window),
+    // what the history tab ranks by
+    SelectHistoryRank(recorder::Rank),
+    // switches to the processes tab and selects the process, from the history tab or the palette
+    ShowProcess(u32),
+    HistoryExportPathChanged(String),
+    ExportHistory,
+
+    // opening the rule editor for a new or existing rule, removing or switching a rule
+    NewRule,
+    EditRule(usize),
+    DeleteRule(usize),
+    ToggleRule(usize, bool),
+    // editor fields, saving the rule or closing the editor without saving
+    RuleDraftChanged(RuleField, String),
+    SetRuleDraftAction(RuleAction),
+    SaveRule,
+    CancelRule,
+    // rule set file path, and writing/reading the rules to/from it
+    RulesPathChanged(String),
+    ExportRules,
+    ImportRules,
+    // settings file path, and writing/reading the whole configuration to/from it
+    SettingsPathChanged(String),
+    ExportSettings,
+    ImportSettings,
+}
+
+impl Message {
+    // what read-only mode refuses, including the dialogs that lead to a change
+    fn changes_processes(&self) -> bool {
+        match self {
+            Message::RowAction(_, action) => {
+                !matches!(action, RowAction::WatchClosely | RowAction::Compare | RowAction::OpenWindow | RowAction::Note | RowAction::Details)
+            }
+            #[cfg(unix)]
+            Message::OpenSignals(_) | Message::SendSignal => true,
+            Message::KillProcess(_)
+            | Message::KillTree(_)
+            | Message::KillSelected
+            | Message::KillSelectedTree
+            | Message::SendPendingKills
+            | Message::RestartProcess(_)
+            | Message::OpenSchedule(_)
+            | Message::ConfirmSchedule
+            | Message::ConfirmKillUserProcesses(_)
+            | Message::KillUserProcesses(_)
+            | Message::SignOutUser(_)
+            | Message::AdjustPriority(..)
+            | Message::SetOomAdj(..)
+            | Message::ApplyLimit
+            | Message::RemoveLimit
+            | Message::ServiceAction(..)
+            | Message::OpenRunDialog
+            | Message::RunTask
+            | Message::OpenKillPattern
+            | Message::KillByPattern
+            | Message::TerminateJob(_)
+            | Message::WatchProcess(_)
+            | Message::OpenDump(_)
+            | Message::ConfirmDump
+            | Message::PickWindow(true) => true,
+            _ => false,
+        }
+    }
 }
 
 impl TaskManager {
-    // refresh funciton - refreshes the process list by calling self.system.refresh_all(), 
-    // and updates the processes vector with the latest system info
+    // refreshes every data source the scheduler says is due and someone looks at
     fn refresh(&mut self) {
-        self.system.refresh_all();
-        self.processes = self.system
-            .processes()
-            .iter()
-            .map(|(pid, process)| ProcessInfo {
-                pid: pid.as_u32(),
-                name: process.name().to_string(),
-                memory: process.memory() / 1024 / 1024,
-                cpu: process.cpu_usage(),
-            })
+        self.throttle();
+        let idle = self.is_idle();
+        let now = Instant::now();
+        for source in self.scheduler.due(&self.config.refresh_intervals, now) {
+            // a source whose tab is hidden isn't marked, so it refreshes as soon as the tab is shown
+            let ran = match source {
+                Source::Processes if idle => {
+                    self.refresh_tray_only();
+                    true
+                }
+                Source::Processes => {
+                    self.refresh_processes();
+                    true
+                }
+                _ if idle => false,
+                Source::Network if self.tab == Tab::Network || self.tab == Tab::Connections => {
+                    self.refresh_network();
+                    // sockets change at the pace of traffic, so they share the network interval
+                    if self.tab == Tab::Connections {
+                        self.refresh_connections();
+                    }
+                    true
+                }
+                Source::Network if self.tab == Tab::Security => {
+                    self.refresh_security();
+                    true
+                }
+                // disks and services are only collected while their tab is shown
+                Source::Disks if self.tab == Tab::Disks => {
+                    self.refresh_disks();
+                    true
+                }
+                Source::Services if self.tab == Tab::Services => {
+                    self.refresh_services();
+                    true
+                }
+                Source::Sensors if self.tab == Tab::Sensors => {
+                    self.refresh_sensors();
+                    true
+                }
+                Source::Battery => {
+                    self.refresh_batteries();
+                    true
+                }
+                _ => false,
+            };
+            if ran {
+                self.scheduler.mark(source, now);
+            }
+        }
+    }
+
+    // stretches the intervals on battery or with the window out of sight, see scheduler.rs
+    fn throttle(&mut self) {
+        let throttled = self.config.battery_saver && (battery::on_battery(&self.batteries) || self.window_hidden || self.minimized);
+        self.scheduler.throttle(throttled);
+    }
+
+    // out of sight with nothing acting on the tables, so collecting would only cost the
+    // machine cpu. the table goes stale until the window comes back
+    fn is_idle(&self) -> bool {
+        let background = self.window_hidden || self.minimized || (self.config.pause_when_unfocused && !self.focused);
+        background && !self.works_in_background()
+    }
+
+    // rules, watches, scripts, recordings and the api act on every refresh, seen or not
+    fn works_in_background(&self) -> bool {
+        self.recorder.is_some()
+            || self.recording.is_some()
+            || self.shared.is_some()
+            || !self.exit_watches.is_empty()
+            || !self.config.watched.is_empty()
+            || !self.config.scripts.is_empty()
+            || self.config.rules.iter().any(|rule| rule.enabled)
+    }
+
+    // while idle, only the tray icon's tooltip is kept current
+    fn refresh_tray_only(&mut self) {
+        if self.tray.is_active() {
+            self.system.refresh_cpu();
+            self.system.refresh_memory();
+            self.update_tray_tooltip();
+        }
+    }
+
+    fn update_tray_tooltip(&mut self) {
+        if self.tray.is_active() {
+            self.tray.set_tooltip(format!(
+                "CPU {:.0}% · RAM {} of {}",
+                self.system.global_cpu_info().cpu_usage(),
+                format::format_bytes(self.system.used_memory() as f64),
+                format::format_bytes(self.system.total_memory() as f64)
+            ));
+        }
+    }
+
+    // refresh funciton - refreshes the process list along with overall cpu and memory,
+    // and updates the processes vector with the latest system info
+    fn refresh_processes(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            playback.step();
+            self.show_playback_frame();
+            return;
+        }
+        self.system.refresh_cpu();
+        self.system.refresh_memory();
+        // the fresh rows are applied to the sorted table at the end, so it rarely needs a sort
+        let mut table = std::mem::take(&mut self.processes);
+        // an agent's table arrives scaled and smoothed, and the rest of this machine's
+        // integrations (gpus, containers, watched programs, recorder, api) don't apply to it
+        let local = self.host.is_none();
+        let started = Instant::now();
+        self.processes = if local {
+            collector::collect(self.provider.as_mut(), &mut self.system, &self.users, &mut self.cpu_history, &self.config)
+        } else {
+            self.provider.collect(&mut self.system, &self.users)
+        };
+        if let Some(timings) = &mut self.timings {
+            timings.collect = started.elapsed();
+        }
+        tracing::debug!(rows = self.processes.len(), ms = started.elapsed().as_secs_f64() * 1000.0, "collected the process table");
+        if local && self.config.adaptive_refresh {
+            self.scheduler.adapt(Load {
+                cpu: self.system.global_cpu_info().cpu_usage(),
+                processes: self.processes.len(),
+                collect_time: started.elapsed(),
+            });
+        }
+        self.history.record(&self.processes, Instant::now());
+        // the charts hold still while the battery saver is on
+        if local && !self.scheduler.throttled() {
+            self.machine_history.record(&self.system, chrono::Local::now());
+        }
+        if local {
+            self.session.record(&self.system, &self.processes, chrono::Local::now());
+        }
+        self.history.annotate(&mut self.processes, self.config.leak_samples, Instant::now());
+        if local && self.scheduler.backed_off() {
+            // the expensive columns keep their last values until the load drops
+            process::carry_expensive_columns(&table, &mut self.processes);
+        } else if local {
+            self.refresh_gpu();
+            self.refresh_containers();
+            self.refresh_slices();
+            self.refresh_holders();
+            self.energy.fill(&mut self.processes, Instant::now());
+            if let Some(watts) = self.energy.package_watts {
+                self.machine_history.power.push(chrono::Local::now(), watts);
+            }
+            let shown = self.shown_columns();
+            if shown.contains(&SortColumn::SharedMemory) {
+                process::fill_shared_memory(&mut self.processes);
+            }
+            plugins::fill(&mut self.processes, &shown);
+        }
+        // a remote host's executables aren't on this machine
+        if local && self.config.process_icons {
+            self.icons.resolve(&self.processes);
+        }
+        self.first_seen.record(&self.processes, chrono::Local::now());
+        // rules and scripts act on this machine, an agent's processes aren't theirs to kill
+        if local {
+            self.apply_rules();
+            self.run_scripts();
+            // starting a watched program again is a change too, read-only mode only observes
+            if !self.config.read_only {
+                let system = &self.system;
+                let argv = |pid: u32| system.process(Pid::from(pid as usize)).map(|process| process.cmd());
+                self.watchdog.supervise(&self.config.watched, &self.processes, argv, Instant::now());
+            }
+            let before = chrono::Local::now();
+            self.churn.record(&self.processes, Instant::now());
+            for event in self.churn.log.iter().rev().take_while(|event| event.at >= before) {
+                let kind = if event.change == churn::Change::Started { EventKind::Started } else { EventKind::Exited };
+                self.events.push_at(event.at, kind, format!("{} ({})", event.name, event.pid));
+            }
+            let processes = &self.processes;
+            self.suspended.retain(|&pid| processes.iter().any(|process| process.pid == pid));
+            self.limits.retain(|pid, _| processes.iter().any(|process| process.pid == *pid));
+            limits::prune(processes);
+            self.config.pinned.retain(|pin| pin.alive(processes));
+            for job in &mut self.jobs {
+                job.update(processes);
+            }
+            if let Some(finished) = self.jobs.iter().find(|job| job.is_finished()) {
+                self.status_message = Some(format!("{} finished", finished.name));
+            }
+            self.jobs.retain(|job| !job.is_finished());
+            for exited in self.exit_watches.take_exited(&self.processes, Instant::now()) {
+                let body = format!("{} ({}) exited after {} watched", exited.name, exited.pid, format::format_duration(exited.watched_for.as_secs()));
+                self.status_message = Some(match self.focus.send("Process exited", &body, chrono::Local::now()) {
+                    Ok(()) => body,
+                    Err(err) => format!("{}, {}", body, err),
+                });
+            }
+            self.refresh_details();
+            if let Some(pid) = self.properties.as_ref().map(|properties| properties.pid) {
+                // the dialog closes when the process exits
+                self.properties = self
+                    .processes
+                    .iter()
+                    .find(|process| process.pid == pid)
+                    .and_then(|process| Properties::read(&self.system, process));
+            }
+            if let Some(shared) = &self.shared {
+                shared.publish(&self.system, &self.processes);
+            }
+            self.share_policy();
+            if let Some(recorder) = &self.recorder {
+                if let Err(err) = recorder.record(&self.system, &self.processes) {
+                    self.report_error(format!("failed to record metrics: {}", err));
+                }
+            }
+            if let Some(recording) = &mut self.recording {
+                if let Err(err) = recording.write(&self.processes) {
+                    let error = format!("stopped recording {}: {}", recording.name, err);
+                    self.report_error(error);
+                    self.recording = None;
+                }
+            }
+        }
+        self.update_tray_tooltip();
+        let fresh = std::mem::take(&mut self.processes);
+        let keys: Vec<SortColumn> = self.sort_keys().into_iter().map(|(column, _)| column).chain([self.config.secondary_sort]).collect();
+        let applied = diff::apply(&mut table, fresh, &keys);
+        self.processes = table;
+        if self.order_frozen() {
+            // values update in place, rows that weren't there before wait for the thaw
+            self.deferred_pids.extend(applied.added);
+        } else if applied.reorder || !self.deferred_pids.is_empty() {
+            self.sort_processes();
+        }
+    }
+
+    // puts the replayed frame in the table, rules, scripts and the history only see live tables
+    fn show_playback_frame(&mut self) {
+        if let Some(playback) = &self.playback {
+            self.processes = playback.frame().processes.clone();
+            self.sort_processes();
+        }
+    }
+
+    // draws the chart offscreen, fonts are loaded for the title so it is done off the ui thread.
+    // copied to the clipboard without a path
+    fn export_chart(&self, kind: performance::ChartKind, path: Option<PathBuf>) -> Command<Message> {
+        let points = kind.points(&self.machine_history, self.chart_range);
+        let max = kind.max(&points);
+        let markers = performance::markers(&self.events, self.chart_range);
+        let range = self.chart_range;
+        Command::perform(
+            async move {
+                let png = chart::png(kind.label(), points, markers, range, max, kind.unit())?;
+                match path {
+                    Some(path) => chart::save_png(&png, &path).map(|()| format!("saved the {} chart to {}", kind.label(), path.display())),
+                    None => chart::copy_png(&png).map(|()| format!("copied the {} chart", kind.label())),
+                }
+            },
+            Message::ChartExported,
+        )
+    }
+
+    // a table read from this machine right now, not an agent's or a recording's
+    fn is_live_local(&self) -> bool {
+        self.host.is_none() && self.playback.is_none()
+    }
+
+    // the picked number formats, an agent's processes are measured against memory this
+    // machine doesn't know so their memory stays in MB
+    fn formatter(&self) -> columns::Formatter<'_> {
+        let total_memory = if self.host.is_none() { self.system.total_memory() / 1024 / 1024 } else { 0 };
+        columns::Formatter { formats: &self.config.column_formats, total_memory }
+    }
+
+    // runs the enabled rules against the fresh table, each rule acts on a pid only once
+    fn apply_rules(&mut self) {
+        let matches: Vec<(String, RuleAction, String, kill_guard::Target)> = rules::evaluate(&self.config.rules, &self.processes)
+            .filter(|(_, process)| !self.config.ignored.ignores(process))
+            .map(|(rule, process)| (rule.name.clone(), rule.action, rule.command.clone(), kill_guard::Target::of(process)))
             .collect();
-        self.sort_processes();
+        let live: HashSet<u32> = self.processes.iter().map(|process| process.pid).collect();
+        self.rule_hits.retain(|(_, pid)| live.contains(pid));
+        for (rule, action, command, target) in matches {
+            let (pid, name) = (target.pid, target.identity.name.clone());
+            if !self.rule_hits.insert((rule.clone(), pid)) {
+                continue;
+            }
+            let (kind, text) = match action {
+                RuleAction::Log => (EventKind::Alert, format!("{} matched {} ({})", rule, name, pid)),
+                RuleAction::Notify => {
+                    let text = format!("{} matched {} ({})", rule, name, pid);
+                    match self.focus.send(&rule, &text, chrono::Local::now()) {
+                        Ok(()) => (EventKind::Alert, text),
+                        Err(err) => (EventKind::Alert, format!("{}, {}", text, err)),
+                    }
+                }
+                RuleAction::Sound => {
+                    let text = format!("{} matched {} ({})", rule, name, pid);
+                    match self.focus.sound(&rule, &text, chrono::Local::now()) {
+                        Ok(()) => (EventKind::Alert, text),
+                        Err(err) => (EventKind::Alert, format!("{}, {}", text, err)),
+                    }
+                }
+                // a command may do anything, so read-only mode doesn't run it either
+                RuleAction::Command if self.config.read_only => {
+                    (EventKind::Alert, format!("{} matched {} ({}), no command run in read-only mode", rule, name, pid))
+                }
+                RuleAction::Command => match rules::run_command(&command, pid, &name) {
+                    Ok(()) => (EventKind::Alert, format!("{} matched {} ({}), ran {}", rule, name, pid, command.trim())),
+                    Err(err) => (EventKind::Alert, format!("{} matched {} ({}), {}", rule, name, pid, err)),
+                },
+                RuleAction::Kill => match self.kill_target(&target) {
+                    Ok(()) => {
+                        self.processes.retain(|process| process.pid != pid);
+                        (EventKind::Kill, format!("{} killed {} ({})", rule, name, pid))
+                    }
+                    Err(refusal) => (EventKind::Alert, format!("{} matched {} ({}), {}", rule, name, pid, refusal)),
+                },
+            };
+            self.events.push(kind, text.clone());
+            self.rule_log.push_back(RuleEvent { at: Instant::now(), text });
+            if self.rule_log.len() > rule_editor::LOG_LENGTH {
+                self.rule_log.pop_front();
+            }
+        }
     }
 
-    // Sorts the process list based on the selected sort column and order (asc/desc)
-    fn sort_processes(&mut self) {
-        self.processes.sort_by(|a, b| {
-            let cmp = match self.sort_column {
-                SortColumn::Pid => a.pid.cmp(&b.pid),
-                SortColumn::Name => a.name.cmp(&b.name),
-                SortColumn::Memory => a.memory.cmp(&b.memory),
-                SortColumn::Cpu => a.cpu.partial_cmp(&b.cpu).unwrap_or(std::cmp::Ordering::Equal),
+    // runs the enabled user scripts, they can't kill the task manager either
+    fn run_scripts(&mut self) {
+        for (script, target) in self.scripts.run(&self.config.scripts, &mut self.processes) {
+            let text = match self.kill_target(&target) {
+                Ok(()) => {
+                    self.processes.retain(|process| process.pid != target.pid);
+                    let text = format!("{} killed {}", script, target.label());
+                    self.events.push(EventKind::Kill, text.clone());
+                    text
+                }
+                Err(refusal) => format!("{}: {}", script, refusal.message("kill", &target)),
             };
-            if self.sort_ascending {
-                cmp
-            } else {
-                cmp.reverse()
+            self.scripts.record(text);
+        }
+    }
+
+    // kills or restarts the targets of the actions whose time has come
+    fn run_due_actions(&mut self) -> Command<Message> {
+        if self.boost.as_ref().is_some_and(|boost| boost.is_due(Instant::now())) {
+            self.end_boost();
+        }
+        let mut restarts = Vec::new();
+        let mut killed_any = false;
+        for action in scheduled::take_due(&mut self.scheduled, chrono::Local::now()) {
+            // a pid target keeps the process it was scheduled for, a name target takes whatever runs under the name now
+            let targets: Vec<kill_guard::Target> = match &action.target {
+                Target::Pid(target) => vec![target.clone()],
+                Target::Name(name) => {
+                    self.processes.iter().filter(|process| process.name == *name).map(kill_guard::Target::of).collect()
+                }
+            };
+            if matches!(action.target, Target::Name(_)) && targets.is_empty() {
+                self.status_message = Some(format!("{}: no such process is running", action.describe()));
+                continue;
             }
-        });
+            match action.kind {
+                ActionKind::Kill => {
+                    let killed = targets.iter().filter(|target| self.kill_logged(target, "A scheduled action")).count();
+                    killed_any |= killed > 0;
+                    self.status_message = Some(format!("{}: killed {} of {}", action.describe(), killed, targets.len()));
+                }
+                ActionKind::Restart => restarts.extend(targets.iter().map(|target| self.restart(target))),
+            }
+        }
+        if killed_any {
+            self.refresh_processes();
+            self.scheduler.mark(Source::Processes, Instant::now());
+        }
+        Command::batch(restarts)
     }
-}
 
-// defining how the GUI behaves
-impl Application for TaskManager {
-    type Executor = iced::executor::Default;
-    type Message = Message;
-    type Flags = ();
-    type Theme = iced::theme::Theme;
+    // opens the metrics database, recording stays off if it can't be opened
+    fn start_recorder(&mut self) {
+        match Recorder::open(&self.config.recorder) {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(err) => {
+                self.config.recorder.enabled = false;
+                self.report_error(format!("failed to open the metrics database: {}", err));
+            }
+        }
+    }
 
-    // new initializes a new TaskManager instance, refreshing the process list immediately
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        let mut task_manager = TaskManager {
-            processes: Vec::new(),
-            sort_column: SortColumn::Pid,
-            sort_ascending: true,
-            system: System::new_all(),
+    // re-runs the history tab query against the metrics database
+    fn refresh_recorded(&mut self) {
+        self.recorded_peaks = self.recorder.as_ref().map(|recorder| recorder.peaks(self.history_window, self.history_rank));
+    }
+
+    // what the command palette offers: actions first, then the processes in the table
+    fn palette_entries(&self) -> Vec<palette::Entry> {
+        use palette::Entry;
+        let mut entries: Vec<Entry> = self.config.layout.tabs().iter().map(|&tab| Entry::new(format!("Go to {:?} tab", tab), Message::SelectTab(tab))).collect();
+        entries.extend(
+            self.config.layout.columns(&self.config.columns).iter().map(|&column| Entry::new(format!("Sort by {}", column.title()), Message::Sort(column))),
+        );
+        if let Some(process) = self.selected_pid.and_then(|pid| self.processes.iter().find(|process| process.pid == pid)) {
+            entries.push(Entry::new(format!("Kill selected: {} ({})", process.name, process.pid), Message::KillSelected));
+            entries.push(Entry::new(format!("Kill selected tree: {} ({})", process.name, process.pid), Message::KillSelectedTree));
+        }
+        entries.extend(ThemeChoice::ALL.iter().map(|&choice| Entry::new(format!("Theme: {}", choice.label()), Message::SetTheme(choice))));
+        entries.extend([
+            Entry::new("Search processes", Message::FocusSearch),
+            Entry::new("Refresh now", Message::RefreshNow),
+            Entry::new(if self.paused { "Resume live updates" } else { "Pause live updates" }, Message::TogglePause),
+            Entry::new("Run new task", Message::OpenRunDialog),
+            Entry::new("Kill by pattern", Message::OpenKillPattern),
+            Entry::new(if self.details.hidden { "Show details pane" } else { "Hide details pane" }, Message::ToggleDetails),
+            Entry::new("Choose columns", Message::ToggleColumnPicker),
+            Entry::new("Toggle system processes", Message::ToggleSystemProcesses(!self.config.show_system_processes)),
+            Entry::new("Take snapshot", Message::TakeSnapshot),
+            Entry::new("Export events", Message::ExportEvents),
+            Entry::new("Export recorded samples as csv", Message::ExportHistory),
+            Entry::new("Mini mode", Message::SetCompact(Some(Compact::Mini))),
+            Entry::new(if self.focus.on { "Leave focus mode" } else { "Focus mode" }, Message::ToggleFocusMode),
+            Entry::new("Session summary", Message::ShowSessionSummary),
+        ]);
+        entries.extend(self.processes.iter().map(|process| Entry::new(format!("{} ({})", process.name, process.pid), Message::ShowProcess(process.pid))));
+        entries
+    }
+
+    // columns whose values have to be collected, all of them while the details tab is shown
+    fn shown_columns(&self) -> Vec<SortColumn> {
+        if self.tab == Tab::Details {
+            SortColumn::with_plugins()
+        } else {
+            self.config.layout.columns(&self.config.columns).to_vec()
+        }
+    }
+
+    // per-process gpu use is costly to collect (every fdinfo of every process), so it is only
+    // read while a gpu column is shown, the cards only while the performance tab is
+    fn refresh_gpu(&mut self) {
+        let columns = [SortColumn::Gpu, SortColumn::GpuMemory];
+        if self.shown_columns().iter().any(|column| columns.contains(column)) {
+            let usage = self.gpu.processes(self.processes.iter().map(|process| process.pid), Instant::now());
+            for process in &mut self.processes {
+                let gpu = usage.get(&process.pid).copied().unwrap_or_default();
+                process.gpu = gpu.utilization;
+                process.gpu_memory = gpu.memory / 1024 / 1024;
+            }
+        }
+        if self.tab == Tab::Performance {
+            self.gpu_devices = self.gpu.devices();
+        }
+    }
+
+    // reads every process's cgroup, only while the container column or scope filter needs it
+    fn refresh_containers(&mut self) {
+        if !self.shown_columns().contains(&SortColumn::Container) && self.filter.scope == Scope::All {
+            return;
+        }
+        let processes = &self.processes;
+        self.container_ids
+            .retain(|&(pid, start_time), _| processes.iter().any(|process| process.pid == pid && process.start_time == start_time));
+        let unknown: Vec<(u32, u64)> = processes
+            .iter()
+            .map(|process| (process.pid, process.start_time))
+            .filter(|key| !self.container_ids.contains_key(key))
+            .collect();
+        if !unknown.is_empty() && !std::mem::replace(&mut self.detecting_containers, true) {
+            let detect = async move { unknown.into_iter().map(|key| (key, containers::for_pid(key.0))).collect() };
+            self.deferred.push(Command::perform(detect, Message::ContainersDetected));
+        }
+        self.container_lookup_pending |= self.container_names.needs_lookup(self.container_ids.values().flatten());
+        for process in &mut self.processes {
+            let id = self.container_ids.get(&(process.pid, process.start_time)).and_then(Option::as_ref);
+            process.container = id.map(|id| self.container_names.label(id));
+        }
+    }
+
+    // reads every process's cgroup path, only while the table is grouped by slice
+    fn refresh_slices(&mut self) {
+        if self.host.is_none() && self.playback.is_none() && self.config.group_slices {
+            for process in &mut self.processes {
+                process.cgroup = slices::for_pid(process.pid);
+            }
+        }
+    }
+
+    // reads every process's open files and sockets, only while the search looks for them
+    fn refresh_holders(&mut self) {
+        if self.host.is_none() && self.playback.is_none() && self.filter.looks_up_holders() {
+            holders::fill(&mut self.processes);
+        }
+    }
+
+    // samples the details of the selected process
+    fn refresh_details(&mut self) {
+        if let Some(pid) = self.selected_pid {
+            self.details.refresh(pid, Instant::now());
+        }
+    }
+
+    // whether refreshes should leave the row order alone
+    fn order_frozen(&self) -> bool {
+        self.config.freeze_order && (self.list_hovered || self.selected_pid.is_some())
+    }
+
+    // re-sorts and shows deferred rows once the pointer left and nothing is selected
+    fn thaw_order(&mut self) {
+        if !self.order_frozen() {
+            self.sort_processes();
+        }
+    }
+
+    // re-reads every socket and its owner
+    // finding the owners reads the descriptors of every process, so it runs off the ui thread,
+    // one walk at a time
+    fn refresh_connections(&mut self) {
+        if !std::mem::replace(&mut self.reading_sockets, true) {
+            self.deferred.push(Command::perform(async { sockets::all_with_owners() }, Message::SocketsRead));
+        }
+    }
+
+    fn refresh_security(&mut self) {
+        self.security = security::audit(&self.processes, sockets::all_with_owners(), security::world_writable);
+    }
+
+    // refreshes the interfaces and their rates since the last network refresh
+    fn refresh_network(&mut self) {
+        let elapsed = self.last_network_refresh.elapsed().as_secs_f64();
+        self.last_network_refresh = Instant::now();
+        self.networks.refresh();
+        self.network_rows = network::collect(&self.networks, elapsed);
+        network::sort(&mut self.network_rows, self.network_sort_column, self.network_sort_ascending);
+    }
+
+    fn refresh_sensors(&mut self) {
+        self.components.refresh();
+        self.sensor_rows = sensors::collect(&self.components);
+    }
+
+    // rebuilds the theme after the accent setting changed
+    fn update_theme(&mut self) {
+        let custom = self
+            .config
+            .custom_theme
+            .as_ref()
+            .and_then(|name| self.themes.iter().find(|theme| &theme.name == name))
+            .and_then(|theme| theme.colors.as_ref().ok().map(|colors| (theme.name.as_str(), colors)));
+        if let Some((name, colors)) = custom {
+            self.theme = colors.theme(name);
+            self.ui.colors = colors.highlights();
+            return;
+        }
+        let accent = if self.config.use_system_accent { self.system_accent } else { None };
+        self.theme = accent::theme(self.config.theme, accent);
+        self.ui.colors = if self.config.theme == ThemeChoice::HighContrast { Highlights::high_contrast() } else { Highlights::default() };
+    }
+
+    // writes or removes the entry, then reads back what is actually installed
+    fn set_autostart(&mut self, autostart: Option<Autostart>) {
+        let result = match autostart {
+            Some(autostart) => autostart::install(autostart.minimized),
+            None => autostart::remove(),
         };
-        task_manager.refresh();
-        (task_manager, Command::none())
+        self.status_message = result.err();
+        self.autostart = autostart::installed();
     }
 
-    // defines the window title as Task Manager
-    fn title(&self) -> String {
-        String::from("Task Manager")
+    // shows the tray icon, the reason goes to the footer if the desktop has no tray
+    // the processes and details tabs trade selections when switching between them
+    fn switch_selection(&mut self, tab: Tab) {
+        let tables = [Tab::Processes, Tab::Details];
+        if self.tab != tab && tables.contains(&self.tab) && tables.contains(&tab) {
+            std::mem::swap(&mut self.selected_pid, &mut self.other_selection);
+            self.refresh_details();
+        }
     }
 
-    fn theme(&self) -> Theme {
-        Theme::Dark
+    // the tab's sort, search and selection as a link, see links.rs
+    fn link_for(&self, tab: Tab) -> Link {
+        let selection = if tab == self.tab { self.selected_pid } else { self.other_selection };
+        let mut link = Link { tab, sort: None, ascending: true, filter: None, pid: None };
+        match tab {
+            Tab::Processes => {
+                link = Link { sort: Some(Sort::Process(self.sort_column)), ascending: self.sort_ascending, filter: Some(self.filter.text.clone()), pid: selection, ..link };
+            }
+            Tab::Details => {
+                link = Link { sort: Some(Sort::Process(self.grid.sort.0)), ascending: self.grid.sort.1, filter: Some(self.grid.filter.text.clone()), pid: selection, ..link };
+            }
+            Tab::Network => link = Link { sort: Some(Sort::Network(self.network_sort_column)), ascending: self.network_sort_ascending, ..link },
+            Tab::Connections => link = Link { sort: Some(Sort::Connection(self.connection_sort_column)), ascending: self.connection_sort_ascending, ..link },
+            _ => {}
+        }
+        link
     }
 
-    // handles incoming messages (killing or sorting)
-    fn update(&mut self, message: Message) -> Command<Message> {
-        match message {
-            // refreshes the program every 5 seconds
-            Message::Tick => {
-                self.refresh();
-                Command::none()
+    // switches to the link's tab and puts its sort, search and selection on it, without the
+    // refreshes of the tab, so it can be used before the first scan. a sort order without a
+    // column applies to the tab's current column
+    fn apply_link(&mut self, link: &Link) {
+        self.switch_selection(link.tab);
+        self.tab = link.tab;
+        self.config.tab = link.tab;
+        match (link.tab, link.sort) {
+            (Tab::Processes, sort) => {
+                self.sort_column = if let Some(Sort::Process(column)) = sort { column } else { self.sort_column };
+                self.sort_ascending = link.ascending;
+                self.sort_then.clear();
+                self.config.sort = self.sort_keys();
+                self.sort_processes();
             }
-            // sorts the list by the given column
-            Message::Sort(column) => {
-                if self.sort_column == column {
-                    self.sort_ascending = !self.sort_ascending;
-                } else {
-                    self.sort_column = column;
-                    self.sort_ascending = true;
+            (Tab::Details, sort) => {
+                let column = if let Some(Sort::Process(column)) = sort { column } else { self.grid.sort.0 };
+                self.grid.sort = (column, link.ascending);
+            }
+            (Tab::Network, sort) => {
+                if let Some(Sort::Network(column)) = sort {
+                    self.network_sort_column = column;
                 }
-                self.sort_processes();
-                Command::none()
+                self.network_sort_ascending = link.ascending;
+                network::sort(&mut self.network_rows, self.network_sort_column, self.network_sort_ascending);
             }
-            // attempts to kill the process with the given PID
-            Message::KillProcess(pid) => {
-                if let Some(process) = self.system.process(Pid::from(pid as usize)) {
-                    let _ = process.kill();
+            (Tab::Connections, sort) => {
+                if let Some(Sort::Connection(column)) = sort {
+                    self.connection_sort_column = column;
+                }
+                self.connection_sort_ascending = link.ascending;
+                if let Ok(rows) = &mut self.connection_rows {
+                    connections::sort(rows, self.connection_sort_column, self.connection_sort_ascending);
                 }
-                self.refresh();
-                Command::none()
             }
+            _ => {}
+        }
+        if let Some(filter) = &link.filter {
+            match link.tab {
+                Tab::Details => self.grid.filter.text = filter.clone(),
+                _ => {
+                    self.filter.text = filter.clone();
+                    self.refresh_holders();
+                }
+            }
+        }
+        if link.pid.is_some() {
+            self.selected_pid = link.pid;
+            self.refresh_details();
         }
     }
-    // construct the GUI layout
-    fn view(&self) -> Element<Message> {
-        // displays buttons for sorting the process list by PID, name, memory, and CPU
-        let header = Row::new()
-        
-            
-            // space inbetween header buttons
-            .spacing(10)
-            .push(Button::new("PID").on_press(Message::Sort(SortColumn::Pid)).width(Length::FillPortion(1)))
-            .push(Button::new("Name").on_press(Message::Sort(SortColumn::Name)).width(Length::FillPortion(2)))
-            .push(Button::new("Memory (MB)").on_press(Message::Sort(SortColumn::Memory)).width(Length::FillPortion(1)))
-            .push(Button::new("CPU (%)").on_press(Message::Sort(SortColumn::Cpu)).width(Length::FillPortion(1)));
-        // displays each porcess in a row with it's PID, name, memory, CPU usage, and Kill button
-        let processes = self.processes.iter().fold(
-            Column::new().spacing(5),
-            |column, process| {
-                column.push(
-                    Row::new()
-                    .spacing(10)
-                        .push(Text::new(process.pid.to_string()).width(Length::FillPortion(1)))
-                        .push(Text::new(&process.name).width(Length::FillPortion(2)))
-                        .push(Text::new(process.memory.to_string()).width(Length::FillPortion(1)))
-                        .push(Text::new(format!("{:.1}", process.cpu)).width(Length::FillPortion(1)))
-                        .push(
-                            Button::new("Kill")
-                                .on_press(Message::KillProcess(process.pid))
-                                .width(Length::Shrink)
-                        )
-                )
-            },
-        );
-        // process list is scrollable
-        let content = Column::new()
-            .spacing(10)
-            .push(header)
-            .push(Scrollable::new(processes));
 
-        Container::new(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .padding(30)
-            .center_x()
-            .center_y()
-            .align_x(alignment::Horizontal::Center)
-            .align_y(alignment::Vertical::Center)
-            .into()
+    // the link from the tray or a later launch, in the window brought to the front
+    fn open_link(&mut self, link: &Link) -> Command<Message> {
+        self.apply_link(link);
+        let mut commands = vec![self.update(Message::SelectTab(link.tab)), self.set_window_hidden(false)];
+        if let (Tab::Processes, Some(pid)) = (link.tab, link.pid) {
+            commands.push(self.reveal(pid));
+        }
+        Command::batch(commands)
     }
-    // sets up a timer that triggers a tick message every 5 seconds to refresh the process list
-    fn subscription(&self) -> Subscription<Message> {
-        time::every(Duration::from_secs(5)).map(|_| Message::Tick)
+
+    // lists the view in the tray menu, once
+    fn add_tray_link(&mut self, link: Link) {
+        let link = link.to_string();
+        if !self.config.tray_links.contains(&link) {
+            self.config.tray_links.push(link);
+            self.tray.set_links(self.config.tray_links.clone());
+        }
     }
-}
 
-// entry point of the application
-fn main() -> iced::Result {
-    TaskManager::run(Settings::default())
+    fn start_tray(&mut self) {
+        match Tray::start(self.config.tray_links.clone()) {
+            Ok(tray) => self.tray = tray,
+            Err(err) => self.report_error(err),
+        }
+    }
+
+    // starts low memory mode when this machine's memory use crosses the threshold, with an
+    // alert and, when asked for, the window back from the tray. memory is read by both the
+    // full and the tray-only refresh, so this works with the window hidden
+    fn check_low_memory(&mut self) -> Command<Message> {
+        let total = self.system.total_memory();
+        if total == 0 {
+            return Command::none();
+        }
+        let used = self.system.used_memory() as f32 / total as f32 * 100.0;
+        if !self.low_memory.update(used, self.config.low_memory_percent) {
+            return Command::none();
+        }
+        let text = format!("Memory use reached {}%", format::decimal(f64::from(used), 0));
+        let text = match self.focus.send("Memory is running low", &text, chrono::Local::now()) {
+            Ok(()) => text,
+            Err(err) => format!("{}, {}", text, err),
+        };
+        self.events.push(EventKind::Alert, text);
+        if self.config.low_memory_shows_window && (self.window_hidden || self.minimized) {
+            self.set_window_hidden(false)
+        } else {
+            Command::none()
+        }
+    }
+
+    // hides the window to the tray, or minimizes it when there is no tray icon to bring it back
+    fn set_window_hidden(&mut self, hidden: bool) -> Command<Message> {
+        let idle = self.is_idle();
+        self.window_hidden = hidden;
+        if !hidden {
+            if idle {
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+            }
+            return Command::batch([
+                window::change_mode(window::Id::MAIN, window::Mode::Windowed),
+                window::minimize(window::Id::MAIN, false),
+                window::gain_focus(window::Id::MAIN),
+            ]);
+        }
+        if self.tray.is_active() {
+            window::change_mode(window::Id::MAIN, window::Mode::Hidden)
+        } else {
+            window::minimize(window::Id::MAIN, true)
+        }
+    }
+
+    // the mini window stays on top, the full window goes back to where it was
+    fn set_compact(&mut self, compact: Option<Compact>) -> Command<Message> {
+        self.compact = compact;
+        let (target, level) = match compact {
+            Some(Compact::Mini) => (widget::mini_geometry(&self.config, self.geometry), window::Level::AlwaysOnTop),
+            None => (self.geometry, window::Level::Normal),
+        };
+        Command::batch([
+            window::change_level(window::Id::MAIN, level),
+            window::resize(window::Id::MAIN, Size::new(target.width as f32, target.height as f32)),
+            window::move_to(window::Id::MAIN, iced::Point::new(target.x as f32, target.y as f32)),
+        ])
+    }
+
+    // puts the priorities a boost changed back
+    fn end_boost(&mut self) {
+        if let Some(boost) = self.boost.take() {
+            for err in boost.revert(&self.processes) {
+                self.report_error(err);
+            }
+        }
+    }
+
+    // the charts and alerts of the time the background monitor ran without a window. the
+    // window's own first samples are dropped so the charts stay in time order
+    fn take_backlog(&mut self, backlog: &daemon::Backlog) {
+        if !backlog.samples.is_empty() {
+            self.machine_history.cpu = Series::default();
+            self.machine_history.memory = Series::default();
+        }
+        for sample in &backlog.samples {
+            self.machine_history.cpu.push(sample.time, sample.cpu);
+            if let Some(memory) = sample.memory {
+                self.machine_history.memory.push(sample.time, memory);
+            }
+        }
+        for alert in &backlog.alerts {
+            self.events.push_at(alert.time, EventKind::Alert, alert.text.clone());
+        }
+        self.status_message = Some(format!("Attached to the background monitor, {} alerts while the window was closed", backlog.alerts.len()));
+    }
+
+    // saves the config and closes the windows, which ends the app
+    fn quit(&mut self) -> Command<Message> {
+        self.end_boost();
+        self.config.last_display = self.current_display.clone();
+        let mut config = self.config.clone();
+        self.overrides.restore(&mut config);
+        if let Err(err) = config.save() {
+            eprintln!("failed to save config: {}", err);
+        }
+        if self.config.summary_on_exit != summary::SummaryFormat::Off {
+            let summary = self.session.summary(&self.events, &self.churn, chrono::Local::now());
+            if let Err(err) = summary.save(self.config.summary_on_exit) {
+                eprintln!("failed to save the session summary: {}", err);
+            }
+        }
+        // an attached monitor keeps running on its own
+        if self.config.background_monitor && self.daemon.is_none() {
+            if let Err(err) = daemon::spawn() {
+                eprintln!("{}", err);
+            }
+        }
+        let detached: Vec<Command<Message>> = self.detached.drain().map(|(id, _)| window::close(id)).collect();
+        Command::batch(detached.into_iter().chain([window::close(window::Id::MAIN)]))
+    }
+
+    // re-reads the charge of every battery
+    fn refresh_batteries(&mut self) {
+        if let Some(manager) = &self.battery_manager {
+            self.batteries = battery::read(manager);
+        }
+    }
+
+    // re-lists the system services
+    fn refresh_services(&mut self) {
+        match services::collect() {
+            Ok(list) => {
+                self.services = list;
+                self.services_error = None;
+            }
+            Err(err) => self.services_error = Some(err),
+        }
+    }
+
+    // refreshes the mounted disks and their I/O rates since the last disks refresh
+    fn refresh_disks(&mut self) {
+        let elapsed = self.last_disk_refresh.elapsed().as_secs_f64();
+        self.last_disk_refresh = Instant::now();
+        self.disks.refresh_list();
+        let io = disks::read_io_counters();
+        self.disk_rows = disks::collect(&self.disks, &self.disk_io, &io, elapsed);
+        self.disk_io = io;
+    }
+
+    // Sorts the process list based on the selected sort column and order (asc/desc)
+    fn sort_processes(&mut self) {
+        let started = Instant::now();
+        let keys = self.sort_keys();
+        process::sort_by_keys(&mut self.processes, &keys, self.config.secondary_sort);
+        if let Some(timings) = &mut self.timings {
+            timings.sort = started.elapsed();
+        }
+        self.deferred_pids.clear();
+    }
+
+    // processes in list order, without the ones of folded groups
+    fn visible_processes(&self) -> Vec<&ProcessInfo> {
+        self.list_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                ListRow::Process(process) => Some(process),
+                ListRow::Group { .. } | ListRow::Unit { .. } => None,
+            })
+            .collect()
+    }
+
+    // the lines of the process list, with a heading above each group while apps or slices are
+    // grouped, and the processes that couldn't be read under their own heading at the end
+    fn list_rows(&self) -> Vec<ListRow<'_>> {
+        let (filtered, denied): (Vec<&ProcessInfo>, Vec<&ProcessInfo>) = self.filtered_processes().into_iter().partition(|process| !process.access_denied);
+        let mut rows = if self.config.group_slices {
+            slices::list(&filtered, &self.collapsed_groups)
+        } else if self.config.group_apps {
+            let (apps, background) = apps::partition(&filtered, &self.processes);
+            self.group_rows([(Group::Apps, apps), (Group::Background, background)])
+        } else {
+            filtered.into_iter().map(ListRow::Process).collect()
+        };
+        if !denied.is_empty() {
+            rows.extend(self.group_rows([(Group::Denied, denied)]));
+        }
+        rows
+    }
+
+    // a heading per group, followed by its processes unless it is folded
+    fn group_rows<'a, const N: usize>(&self, groups: [(Group, Vec<&'a ProcessInfo>); N]) -> Vec<ListRow<'a>> {
+        let mut rows = Vec::new();
+        for (group, processes) in groups {
+            let collapsed = self.collapsed_groups.contains(&group);
+            rows.push(ListRow::Group { group, count: processes.len(), collapsed });
+            if !collapsed {
+                rows.extend(processes.into_iter().map(ListRow::Process));
+            }
+        }
+        rows
+    }
+
+    // processes that pass the filter, in their sorted order
+    fn filtered_processes(&self) -> Vec<&ProcessInfo> {
+        let mut visible: Vec<&ProcessInfo> = self
+            .processes
+            .iter()
+            .filter(|process| !self.deferred_pids.contains(&process.pid))
+            .filter(|process| (self.shows_system_processes() || !process.system) && !self.config.ignored.ignores(process))
+            .filter(|process| self.filter.matches(process) && !self.config.noise_filter.hides(process))
+            .collect();
+        // while searching for words the best matches come first, ties stay in table order
+        if self.filter.ranks() {
+            visible.sort_by_cached_key(|process| std::cmp::Reverse(self.filter.score(process)));
+        }
+        // pinned rows first, the stable sort keeps both parts in table order
+        visible.sort_by_key(|process| !self.is_pinned(process));
+        visible
+    }
+
+    // auditing elevated processes needs the root-owned ones, which mostly count as system
+    fn shows_system_processes(&self) -> bool {
+        self.config.show_system_processes || self.filter.elevated
+    }
+
+    fn is_pinned(&self, process: &ProcessInfo) -> bool {
+        self.config.pinned.iter().any(|pin| pin.matches(process))
+    }
+
+    // processes matching the search that the low-activity filter hides
+    fn hidden_process_count(&self) -> usize {
+        self.processes
+            .iter()
+            .filter(|process| !self.deferred_pids.contains(&process.pid))
+            .filter(|process| (self.shows_system_processes() || !process.system) && !self.config.ignored.ignores(process))
+            .filter(|process| self.filter.matches(process) && self.config.noise_filter.hides(process))
+            .count()
+    }
+
+    // system processes matching the search that are hidden while they aren't shown
+    fn hidden_system_count(&self) -> usize {
+        if self.shows_system_processes() {
+            return 0;
+        }
+        self.processes
+            .iter()
+            .filter(|process| process.system && !self.config.ignored.ignores(process) && self.filter.matches(process))
+            .count()
+    }
+
+    // the sort column followed by the keys added to it
+    fn sort_keys(&self) -> Vec<(SortColumn, bool)> {
+        std::iter::once((self.sort_column, self.sort_ascending)).chain(self.sort_then.iter().copied()).collect()
+    }
+
+    // sorts by the column, toggling the order if it is already the sort column
+    fn sort_by(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.sort_ascending = !self.sort_ascending;
+        } else {
+            self.sort_column = column;
+            self.sort_ascending = true;
+        }
+        self.sort_then.clear();
+        self.config.sort = self.sort_keys();
+        self.sort_processes();
+    }
+
+    // appends the column to the sort chain, or toggles its order if it is in the chain already
+    fn then_sort_by(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.sort_ascending = !self.sort_ascending;
+        } else if let Some(key) = self.sort_then.iter_mut().find(|(key, _)| *key == column) {
+            key.1 = !key.1;
+        } else {
+            self.sort_then.push((column, true));
+        }
+        self.config.sort = self.sort_keys();
+        self.sort_processes();
+    }
+
+    // shows the error in the footer and keeps it in the event log
+    fn report_error(&mut self, error: String) {
+        self.events.push(EventKind::Error, error.clone());
+        self.status_message = Some(error);
+    }
+
+    // kills right away, or after the undo delay when that is turned on
+    fn request_kill(&mut self, target: kill_guard::Target, tree: bool) {
+        if self.config.undo_kills {
+            self.pending_kills.retain(|pending| pending.target.pid != target.pid);
+            self.pending_kills.push(PendingKill::new(target, tree, Instant::now()));
+        } else {
+            self.kill_now(&target, tree);
+        }
+    }
+
+    // the tree's root is checked first, its children are only killed while it is still the
+    // process the kill was aimed at
+    fn kill_now(&mut self, target: &kill_guard::Target, tree: bool) {
+        if tree {
+            match self.check_target(target) {
+                Ok(()) => {
+                    for pid in impact::tree_order(&self.processes, target.pid) {
+                        let member = match self.processes.iter().find(|process| process.pid == pid) {
+                            Some(_) if pid == target.pid => target.clone(),
+                            // the preview listed the tree's critical processes with the root's
+                            Some(process) => kill_guard::Target { confirmed_critical: target.confirmed_critical, ..kill_guard::Target::of(process) },
+                            None => continue,
+                        };
+                        self.kill_logged(&member, "You");
+                    }
+                }
+                Err(refusal) => self.report_error(refusal.message("kill", target)),
+            }
+        } else {
+            self.kill_logged(target, "You");
+        }
+        self.refresh_processes();
+        self.scheduler.mark(Source::Processes, Instant::now());
+    }
+
+    fn kill_policy(&self) -> Policy {
+        Policy { read_only: self.config.read_only, local: self.host.is_none(), critical: self.config.critical.clone() }
+    }
+
+    // lets the api's kills follow read-only mode and the critical list as they change
+    fn share_policy(&self) {
+        if let Some(shared) = &self.shared {
+            shared.set_policy(self.config.read_only, &self.config.critical);
+        }
+    }
+
+    // whether the target may be killed or signalled now, see kill_guard.rs
+    fn check_target(&mut self, target: &kill_guard::Target) -> Result<(), kill_guard::Refusal> {
+        let policy = self.kill_policy();
+        kill_guard::check(target, &policy, &self.processes, self.provider.as_mut())
+    }
+
+    fn kill_target(&mut self, target: &kill_guard::Target) -> Result<(), kill_guard::Refusal> {
+        let policy = self.kill_policy();
+        kill_guard::kill(target, &policy, &self.processes, self.provider.as_mut(), &self.system)
+    }
+
+    // kills the process and notes who did it in the event log, or reports why it wasn't. an
+    // agent's process is checked here and the kill sent off the ui thread, its answer logged
+    // when it comes
+    fn kill_logged(&mut self, target: &kill_guard::Target, by: &str) -> bool {
+        if let Some(host) = self.host.and_then(|index| self.config.hosts.get(index)).cloned() {
+            if let Err(refusal) = self.check_target(target) {
+                self.report_error(refusal.message("kill", target));
+                return false;
+            }
+            let (pid, target, by) = (target.pid, target.clone(), by.to_string());
+            self.deferred.push(Command::perform(async move { remote::kill(&host, pid) }, move |result| {
+                Message::RemoteKilled(target.clone(), by.clone(), result)
+            }));
+            return true;
+        }
+        match self.kill_target(target) {
+            Ok(()) => {
+                self.events.push(EventKind::Kill, format!("{} killed {}", by, target.label()));
+                true
+            }
+            Err(refusal) => {
+                self.report_error(refusal.message("kill", target));
+                false
+            }
+        }
+    }
+
+    // restarts the target once the kill checks pass, its command line is read before it's killed
+    fn restart(&mut self, target: &kill_guard::Target) -> Command<Message> {
+        if let Err(refusal) = self.check_target(target) {
+            self.report_error(refusal.message("restart", target));
+            return Command::none();
+        }
+        let pid = target.pid;
+        let spec = self.system.process(Pid::from(pid as usize)).and_then(|process| restart::RestartSpec::capture(pid, process));
+        match spec {
+            Some(spec) => Command::perform(async move { restart::restart(spec) }, Message::RestartDone),
+            None => {
+                self.report_error(format!("can't read the command line of process {}", pid));
+                Command::none()
+            }
+        }
+    }
+
+    // a watch entry already starts the process again when it exits
+    fn is_watched(&self, process: &ProcessInfo) -> bool {
+        let Some(live) = self.system.process(Pid::from(process.pid as usize)) else {
+            return false;
+        };
+        self.config.watched.iter().any(|entry| entry.matches(process.exe.as_deref(), live.cmd()))
+    }
+
+    // the row's process as a kill target, None when the pid isn't in the table
+    fn target_of(&self, pid: u32) -> Option<kill_guard::Target> {
+        self.processes.iter().find(|process| process.pid == pid).map(kill_guard::Target::of)
+    }
+
+    // state with nothing collected yet, desktop integration (accent, hotkey) is set up by `new`
+    fn with_provider(config: Config, provider: Box<dyn ProcessProvider>) -> TaskManager {
+        let current_display = config.last_display.clone();
+        let ui = Ui::new(config.text_size, config.layout.density(config.density));
+        let geometry = config
+            .window_geometry
+            .get(&current_display)
+            .copied()
+            .unwrap_or(DEFAULT_GEOMETRY);
+        TaskManager {
+            processes: Vec::new(),
+            sort_column: config.sort.first().map_or(SortColumn::Pid, |&(column, _)| column),
+            sort_ascending: config.sort.first().is_none_or(|&(_, ascending)| ascending),
+            sort_then: config.sort.iter().skip(1).copied().collect(),
+            modifiers: keyboard::Modifiers::default(),
+            filter: Filter::default(),
+            selected_pid: None,
+            list_hovered: false,
+            list_window: ListWindow { row_height: ui.row_height(), ..ListWindow::default() },
+            grid: DetailsGrid::new(ui),
+            details: DetailsPane::default(),
+            deferred_pids: HashSet::new(),
+            cpu_history: HashMap::new(),
+            history: History::default(),
+            paused: false,
+            other_selection: None,
+            startup: None,
+            provider,
+            system: System::new(),
+            users: Users::new_with_refreshed_list(),
+            // unreadable processes start folded away
+            collapsed_groups: HashSet::from([Group::Denied]),
+            expanded_users: HashSet::new(),
+            expanded_executables: HashSet::new(),
+            first_seen: FirstSeen::default(),
+            confirm_kill_user: None,
+            tab: Tab::Processes,
+            networks: Networks::new_with_refreshed_list(),
+            network_rows: Vec::new(),
+            network_sort_column: NetworkColumn::Name,
+            network_sort_ascending: true,
+            last_network_refresh: Instant::now(),
+            connection_rows: Ok(Vec::new()),
+            reading_sockets: false,
+            security: security::Audit::default(),
+            connection_sort_column: ConnectionColumn::Pid,
+            connection_sort_ascending: true,
+            disks: Disks::new(),
+            disk_rows: Vec::new(),
+            disk_io: IoCounters::new(),
+            last_disk_refresh: Instant::now(),
+            components: Components::new_with_refreshed_list(),
+            sensor_rows: Vec::new(),
+            services: Vec::new(),
+            services_error: None,
+            scheduler: Scheduler::default(),
+            timings: None,
+            config,
+            overrides: Overrides::default(),
+            current_display,
+            window_width: geometry.width,
+            geometry,
+            pending_size: None,
+            compact: None,
+            hotkey: Hotkey::inactive(),
+            hotkey_input: String::new(),
+            hotkey_status: None,
+            tray: Tray::inactive(),
+            autostart: None,
+            window_hidden: false,
+            focused: true,
+            minimized: false,
+            shared: None,
+            host: None,
+            host_input: String::new(),
+            deferred: Vec::new(),
+            preset: None,
+            preset_input: String::new(),
+            scripts: Scripts::default(),
+            ui,
+            themes: Vec::new(),
+            column_picker_open: false,
+            dragged_column: None,
+            run_dialog: None,
+            kill_pattern: None,
+            note_draft: None,
+            palette: None,
+            detached: HashMap::new(),
+            focused_detached: None,
+            jobs: Vec::new(),
+            kill_preview: None,
+            pending_kills: Vec::new(),
+            close_watch: None,
+            comparison: None,
+            boost: None,
+            crash_report: None,
+            properties: None,
+            last_click: None,
+            column_search: String::new(),
+            ignore_input: String::new(),
+            critical_input: String::new(),
+            tray_link_input: String::new(),
+            rules_path_input: Config::dir()
+                .map(|dir| dir.join("rules.toml").display().to_string())
+                .unwrap_or_default(),
+            settings_path_input: Config::dir()
+                .map(|dir| dir.join("settings-export.toml").display().to_string())
+                .unwrap_or_default(),
+            rule_draft: None,
+            rule_log: VecDeque::new(),
+            rule_hits: HashSet::new(),
+            watchdog: Watchdog::default(),
+            limit_dialog: None,
+            limits: HashMap::new(),
+            schedule_dialog: None,
+            scheduled: Vec::new(),
+            next_action_id: 0,
+            #[cfg(unix)]
+            signal_dialog: None,
+            dump_dialog: None,
+            status_message: None,
+            container_names: ContainerNames::default(),
+            container_lookup_pending: false,
+            container_ids: HashMap::new(),
+            detecting_containers: false,
+            snapshots: Vec::new(),
+            snapshot_name_input: String::new(),
+            snapshot_base: None,
+            snapshot_target: None,
+            recording: None,
+            playback: None,
+            recordings: Vec::new(),
+            picked_recording: None,
+            churn: Churn::default(),
+            churn_log_open: false,
+            exit_watches: ExitWatches::default(),
+            focus: focus::Focus::default(),
+            low_memory: low_memory::LowMemory::default(),
+            session: summary::Session::new(chrono::Local::now()),
+            session_summary: None,
+            daemon: None,
+            energy: EnergySampler::default(),
+            icons: IconCache::default(),
+            suspended: HashSet::new(),
+            recorder: None,
+            history_window: recorder::Window::Day,
+            history_rank: recorder::Rank::Cpu,
+            recorded_peaks: None,
+            history_export_input: Config::dir()
+                .map(|dir| dir.join("metrics.csv").display().to_string())
+                .unwrap_or_default(),
+            machine_history: MachineHistory::default(),
+            events: EventLog::default(),
+            event_kinds: EventKind::ALL.into_iter().collect(),
+            event_export_input: Config::dir()
+                .map(|dir| dir.join("events.txt").display().to_string())
+                .unwrap_or_default(),
+            chart_range: timeseries::Range::default(),
+            gpu: GpuMonitor::new(),
+            gpu_devices: Vec::new(),
+            battery_manager: starship_battery::Manager::new().ok(),
+            batteries: Vec::new(),
+            system_info: SystemInfo::default(),
+            system_accent: None,
+            theme: Theme::Dark,
+        }
+    }
+
+    // how the process table fits the window at the current zoom, see layout.rs
+    fn fit(&self) -> Fit {
+        Fit::for_width(f64::from(self.window_width) / self.config.scale_for(&self.current_display), self.config.narrow_width)
+    }
+
+    // rows of a narrow window take two lines
+    fn fit_rows(&mut self) {
+        let lines = if self.fit().two_lines() { 2.0 } else { 1.0 };
+        self.list_window.row_height = self.ui.row_height() * lines;
+    }
+
+    // selects a visible row and scrolls the list so it is in view
+    fn reveal(&mut self, pid: u32) -> Command<Message> {
+        let index = self.row_index(pid);
+        let len = self.list_rows().len();
+        self.selected_pid = Some(pid);
+        self.refresh_details();
+        match index.and_then(|index| self.list_window.scroll_to(index, len)) {
+            Some(y) => scrollable::scroll_to(process_list_id(), scrollable::AbsoluteOffset { x: 0.0, y }),
+            None => Command::none(),
+        }
+    }
+
+    // line of the selected process in the list
+    fn selected_index(&self) -> Option<usize> {
+        self.row_index(self.selected_pid?)
+    }
+
+    // line of a process in the list, group headings included
+    fn row_index(&self, pid: u32) -> Option<usize> {
+        self.list_rows().iter().position(|row| matches!(row, ListRow::Process(process) if process.pid == pid))
+    }
+
+    // scrolls along with the selected row when a refresh or sort moved it, `before` is its
+    // index beforehand, so the selection stays where the user was looking. otherwise the list
+    // is put back at the offset it last reported, since rows or panels coming and going above
+    // it can make iced rebuild the scrollable at the top
+    fn keep_scroll_position(&mut self, before: Option<usize>) -> Command<Message> {
+        self.list_window.offset = self.list_window.offset(self.list_rows().len());
+        let followed = before.zip(self.selected_index()).and_then(|(from, to)| self.list_window.follow(from, to));
+        let offset = followed.or((self.list_window.offset > 0.0).then_some(self.list_window.offset));
+        match offset {
+            Some(y) => {
+                self.list_window.offset = y;
+                scrollable::scroll_to(process_list_id(), scrollable::AbsoluteOffset { x: 0.0, y })
+            }
+            None => Command::none(),
+        }
+    }
+
+    // back to the top of the list when the filter changed the rows it shows
+    fn scroll_to_top(&mut self) -> Command<Message> {
+        self.list_window.offset = 0.0;
+        scrollable::scroll_to(process_list_id(), scrollable::AbsoluteOffset { x: 0.0, y: 0.0 })
+    }
+
+    // opens the kill preview, services and sockets are looked up in the background
+    fn preview_kill(&mut self, pid: u32, tree: bool) -> Command<Message> {
+        // a replayed pid may belong to another process by now
+        if self.playback.is_some() {
+            return Command::none();
+        }
+        let Some(target) = self.target_of(pid) else {
+            return Command::none();
+        };
+        let mut preview = KillImpact::new(&self.processes, target, tree);
+        let affected: Vec<u32> = if tree { impact::tree_order(&self.processes, pid) } else { vec![pid] };
+        preview.critical = self
+            .processes
+            .iter()
+            .filter(|process| affected.contains(&process.pid) && critical::is_critical(process, &self.config.critical))
+            .map(|process| format!("{} ({})", process.name, process.pid))
+            .collect();
+        // services and sockets can only be looked up on this machine
+        if self.host.is_some() {
+            let connections = Err(String::from("only checked for this computer's processes"));
+            preview.external = Some(ExternalImpact { unit: None, dependents: Ok(Vec::new()), connections });
+            self.kill_preview = Some(preview);
+            return Command::none();
+        }
+        self.kill_preview = Some(preview);
+        Command::perform(async move { impact::external(pid) }, move |external| Message::KillImpactReady(pid, external))
+    }
+
+    // the whole window, timed by `view`
+    fn window_view(&self) -> Element<'_, Message> {
+        if let Some(Compact::Mini) = self.compact {
+            let totals = Totals {
+                cpu: self.system.global_cpu_info().cpu_usage(),
+                used_memory: self.system.used_memory(),
+                total_memory: self.system.total_memory(),
+            };
+            return widget::mini_view(totals, &self.processes);
+        }
+
+        // displays a button for each tab of the mode, then the mode switcher
+        let tabs = self
+            .config
+            .layout
+            .tabs()
+            .iter()
+            .fold(Row::new().spacing(self.ui.spacing()), |row, &tab| {
+                row.push(Button::new(Text::new(format!("{:?}", tab))).on_press(Message::SelectTab(tab)))
+            })
+            .push(Button::new("Mini mode").on_press(Message::SetCompact(Some(Compact::Mini))));
+        let tabs = Layout::ALL.iter().fold(tabs, |row, &layout| {
+            row.push(Button::new(layout.label()).on_press_maybe((layout != self.config.layout).then_some(Message::SetLayout(layout))))
+        });
+
+        // a skeleton of the table until the startup scan is in
+        let body = if let Some(stage) = self.startup {
+            startup::view(stage, self.ui)
+        } else {
+            match self.tab {
+                Tab::Processes => self.processes_view(),
+                Tab::Details => grid::view(
+                    &self.grid,
+                    &self.processes,
+                    &self.config.column_widths,
+                    self.formatter(),
+                    self.config.process_icons.then_some(&self.icons),
+                    self.selected_pid,
+                    self.ui,
+                    self.theme.palette().primary,
+                ),
+                Tab::Performance => performance::view(&self.system, &self.gpu_devices, &self.system_info, &self.machine_history, &self.events, self.chart_range),
+                Tab::Network => network::view(&self.network_rows),
+                Tab::Connections => connections::view(&self.connection_rows),
+                Tab::Disks => disks::view(&self.disk_rows),
+                Tab::Sensors => sensors::view(&self.sensor_rows, self.ui.colors),
+                Tab::Services => services::view(&self.services, self.services_error.as_deref(), self.ui.colors),
+                Tab::Users => users::view(&self.processes, &self.expanded_users, self.confirm_kill_user.as_deref(), &self.config.critical),
+                Tab::Executables => executables::view(&self.processes, &self.expanded_executables, &self.first_seen, &self.config.critical),
+                Tab::Rules => Column::new()
+                    .spacing(20)
+                    .push(rule_editor::view(&self.config.rules, self.rule_draft.as_ref(), &self.rule_log))
+                    .push(scheduled::view(&self.scheduled))
+                    .push(watchdog::view(&self.config.watched, &self.watchdog))
+                    .into(),
+                Tab::Snapshots => snapshot::view(
+                    &self.snapshots,
+                    &self.processes,
+                    &self.snapshot_name_input,
+                    self.snapshot_base.as_deref(),
+                    self.snapshot_target.as_deref(),
+                ),
+                Tab::Scripts => scripts::view(&self.scripts, &self.config.scripts),
+                Tab::History => {
+                    let running: HashSet<(u32, &str)> = self.processes.iter().map(|process| (process.pid, process.name.as_str())).collect();
+                    recorder::view(self.history_window, self.history_rank, self.recorded_peaks.as_ref(), &running, &self.history_export_input)
+                }
+                Tab::Events => events::view(&self.events, &self.event_kinds, &self.event_export_input),
+                Tab::Security => security::view(&self.security),
+                Tab::Settings => settings::view(self),
+            }
+        };
+
+        // status line below the active tab
+        let mut footer = self.batteries.iter().fold(
+            Row::new().spacing(20),
+            |row, status| row.push(Text::new(battery::summary(status))),
+        );
+        // an agent's table comes without its machine's load
+        if self.host.is_none() {
+            footer = footer.push(Text::new(about::current_load_and_uptime()));
+            let seconds = self.scheduler.effective(&self.config.refresh_intervals, Source::Processes);
+            footer = footer.push(Text::new(if self.scheduler.throttled() {
+                format!("refreshing every {} s, battery saver on", seconds)
+            } else if self.scheduler.backed_off() {
+                format!("refreshing every {} s, slowed down under load", seconds)
+            } else {
+                format!("refreshing every {} s", seconds)
+            }));
+        }
+        if self.is_live_local() {
+            if let Some(usage) = profiling::own_usage(&self.processes) {
+                footer = footer.push(Text::new(usage));
+            }
+        }
+        if let Some(timings) = &self.timings {
+            footer = footer.push(Text::new(timings.text()));
+        }
+        if self.paused {
+            footer = footer.push(Text::new("Live updates paused"));
+        }
+        let hidden = self.hidden_process_count();
+        if self.tab == Tab::Processes && hidden > 0 {
+            footer = footer.push(Text::new(format!("{} low-activity processes hidden", hidden)));
+        }
+        let system = self.hidden_system_count();
+        if self.tab == Tab::Processes && system > 0 {
+            footer = footer.push(Text::new(format!("{} system processes hidden", system)));
+        }
+        if !self.exit_watches.is_empty() {
+            footer = footer.push(Text::new(format!("notifying when {} marked processes exit", self.exit_watches.len())));
+        }
+        if self.config.read_only {
+            footer = footer.push(Text::new("read-only"));
+        }
+        footer = footer.push(focus::badge(&self.focus));
+        if let Some(message) = &self.status_message {
+            footer = footer.push(Text::new(message));
+        }
+
+        let content = Column::new()
+            .spacing(self.ui.spacing())
+            .push(tabs)
+            .push_maybe(self.crash_report.as_deref().map(crash::view))
+            .push_maybe(self.low_memory.shown().then(|| {
+                // the banner is about this machine, so a remote host's table isn't offered
+                let processes: &[ProcessInfo] = if self.host.is_none() { &self.processes } else { &[] };
+                let candidates = low_memory::candidates(processes, &self.config.critical, &self.config.ignored);
+                low_memory::view(&self.low_memory, processes, candidates, !self.config.read_only, self.ui.colors.critical)
+            }))
+            .push_maybe(self.palette.as_ref().map(|shown| palette::view(shown, &palette::matches(self.palette_entries(), &shown.query))))
+            // the tab takes the remaining height so the footer stays visible
+            .push(Container::new(body).height(Length::Fill))
+            .push_maybe((!self.pending_kills.is_empty()).then(|| soft_kill::view(&self.pending_kills, Instant::now())))
+            .push_maybe(self.close_watch.as_ref().map(|watch| close_watch::view(watch, self.config.cpu_of_whole_machine)))
+            .push_maybe(self.comparison.map(|pids| {
+                let processes = pids.map(|pid| self.processes.iter().find(|process| process.pid == pid));
+                compare::view(pids, processes, &self.history, self.is_live_local() && !self.config.read_only, Instant::now())
+            }))
+            .push_maybe(self.boost.as_ref().map(|boost| boost::view(boost, Instant::now())))
+            .push_maybe(self.focus.open.then(|| focus::view(&self.focus)))
+            .push_maybe(self.session_summary.as_ref().map(summary::view))
+            .push_maybe((!self.jobs.is_empty()).then(|| jobs::view(&self.jobs, &self.processes, !self.config.read_only, Instant::now())))
+            .push(footer);
+
+        Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(self.ui.window_padding())
+            .center_x()
+            .center_y()
+            .align_x(alignment::Horizontal::Center)
+            .align_y(alignment::Vertical::Center)
+            .into()
+    }
+
+    // builds the processes tab with buttons for sorting the process list by each visible column
+    fn processes_view(&self) -> Element<'_, Message> {
+        let simple = self.config.layout.is_simple();
+        let fit = self.fit();
+        let columns = fit.columns(self.config.layout.columns(&self.config.columns));
+        let sort_keys = self.sort_keys();
+        // an agent's processes are measured against memory this machine doesn't know, so
+        // only their cpu is drawn as a bar
+        let formatter = self.formatter();
+        let bars = self.config.bar_cells.then(|| bar_cell::Bars::new(formatter.total_memory, self.ui.text_size, &self.ui.colors));
+        let header = columns.iter().fold(
+            // space inbetween header buttons
+            Row::new().spacing(self.ui.spacing()),
+            |row, &column| {
+                let width = Length::FillPortion(self.config.column_widths.portion(column));
+                let button = Button::new(Text::new(columns::header_label(column, &sort_keys, formatter)))
+                    .style(if self.dragged_column == Some(column) { theme::Button::Primary } else { style::header(self.ui.colors.header) })
+                    .on_press(Message::Sort(column))
+                    .width(Length::Fill);
+                // the simple mode's columns are fixed
+                if simple {
+                    return row.push(button.width(width));
+                }
+                let grip = Tooltip::new(MouseArea::new(Text::new("⠿")).on_press(Message::DragColumn(column)), "Drag to move the column", tooltip::Position::Bottom);
+                row.push(
+                    MouseArea::new(Row::new().spacing(2).align_items(alignment::Alignment::Center).push(grip).push(button).width(width))
+                        .on_release(Message::DropColumn(column))
+                )
+            },
+        )
+        // opens the column picker, right clicking the header does the same, the simple mode has fixed columns
+        .push_maybe((!simple).then(|| Tooltip::new(Button::new("☰").on_press(Message::ToggleColumnPicker), "Columns", tooltip::Position::Bottom)));
+        let mut header = MouseArea::new(header);
+        if !simple {
+            header = header.on_right_press(Message::ToggleColumnPicker);
+        }
+        // displays each porcess in a row with the visible columns and its "⋮" action menu,
+        // clicking a row selects it, only rows in the viewport are built
+        let visible = self.visible_processes();
+        // follows the filter, e.g. all of one browser's processes
+        let totals = (self.config.totals_row && !simple).then(|| columns::totals_row(&visible, &columns, &self.config.column_widths, formatter, self.ui.spacing()));
+        let highlight = self.theme.palette().primary;
+        let processes = virtual_list::view(process_list_id(), &self.list_rows(), &self.list_window, Message::ListScrolled, |row| {
+            let process = match row {
+                ListRow::Process(process) => *process,
+                ListRow::Group { group, count, collapsed } => {
+                    let heading = format!("{} {} ({})", if *collapsed { "▸" } else { "▾" }, group.label(), count);
+                    // reading them takes the rights they were refused with
+                    let escalate = (*group == Group::Denied && !run::is_elevated() && !cfg!(target_os = "macos"))
+                        .then(|| Button::new("Restart as administrator").on_press(Message::RelaunchElevated));
+                    let heading = Row::new()
+                        .spacing(10)
+                        .align_items(alignment::Alignment::Center)
+                        .push(Button::new(Text::new(heading)).style(theme::Button::Text).on_press(Message::ToggleGroup(group.clone())))
+                        .push_maybe(escalate);
+                    return Container::new(heading)
+                        .height(Length::Fixed(self.list_window.row_height))
+                        .center_y()
+                        .into();
+                }
+                ListRow::Unit { group, depth, count, cpu, memory, collapsed } => {
+                    let heading = format!(
+                        "{} {} ({}) · CPU {}% · {}",
+                        if *collapsed { "▸" } else { "▾" },
+                        group.label(),
+                        count,
+                        format::decimal(f64::from(*cpu), 1),
+                        format::format_bytes(*memory as f64 * 1024.0 * 1024.0)
+                    );
+                    return Container::new(Button::new(Text::new(heading)).style(theme::Button::Text).on_press(Message::ToggleGroup(group.clone())))
+                        .height(Length::Fixed(self.list_window.row_height))
+                        .padding([0, 0, 0, 16 * *depth as u16])
+                        .center_y()
+                        .into();
+                }
+            };
+            let matched = self.filter.name_matches(&process.name);
+            let name = columns::NameStyle {
+                matched: &matched,
+                highlight,
+                icon: self.config.process_icons.then(|| self.icons.get(process)).flatten(),
+                note: self.config.notes.get(&notes::key(process)),
+                limit: self.limits.get(&process.pid),
+            };
+            let menu = row_menu::view(
+                process.pid,
+                row_menu::actions(self.is_live_local(), self.playback.is_none() && !self.config.read_only, self.suspended.contains(&process.pid))
+                    .into_iter()
+                    .filter(|&action| self.config.layout.offers(action))
+                    .collect(),
+                self.ui.button_padding(),
+            );
+            // a narrow window gives the name the whole first line, the other cells stay under
+            // their headers on the second
+            let row: Element<'_, Message> = if fit.two_lines() {
+                let cells = columns.iter().fold(Row::new().spacing(self.ui.spacing()), |row, &column| {
+                    if column == SortColumn::Name {
+                        row.push(Space::with_width(Length::FillPortion(self.config.column_widths.portion(column))))
+                    } else {
+                        row.push(columns::cell(process, column, &self.config.column_widths, formatter, &name, bars))
+                    }
+                });
+                let name_line = Row::new()
+                    .spacing(self.ui.spacing())
+                    .push_maybe(columns.contains(&SortColumn::Name).then(|| columns::cell(process, SortColumn::Name, &self.config.column_widths, formatter, &name, bars)))
+                    .push(menu);
+                Column::new().push(name_line).push(cells).into()
+            } else {
+                columns
+                    .iter()
+                    .fold(Row::new().spacing(self.ui.spacing()), |row, &column| {
+                        row.push(columns::cell(process, column, &self.config.column_widths, formatter, &name, bars))
+                    })
+                    .push(menu)
+                    .into()
+            };
+            let mut row = Container::new(row)
+                .height(Length::Fixed(self.list_window.row_height))
+                .center_y();
+            if self.selected_pid == Some(process.pid) {
+                row = row.style(style::selected_row(self.ui.colors.row));
+            } else if process.not_responding {
+                row = row.style(style::not_responding_row(self.ui.colors.warning));
+            } else if self.is_pinned(process) {
+                row = row.style(style::pinned_row);
+            }
+            MouseArea::new(row)
+                .on_press(Message::SelectProcess(process.pid))
+                .on_right_press(Message::OpenProperties(process.pid))
+                .into()
+        });
+        let search = TextInput::new("Search (Ctrl+F), e.g. cpu>50 user:root", &self.filter.text)
+            .id(search_input_id())
+            .on_input(Message::SearchChanged)
+            .width(Length::Fixed(300.0));
+        // a query that doesn't parse says why below the box
+        let search: Element<'_, Message> = match self.filter.error() {
+            Some(error) => Column::new().push(search).push(Text::new(error).size(12)).into(),
+            None => search.into(),
+        };
+        let scopes = Scope::ALL.iter().fold(Row::new().spacing(2), |row, &scope| {
+            let button = Button::new(scope.label());
+            row.push(if scope == self.filter.scope { button } else { button.on_press(Message::SetScope(scope)) })
+        });
+        let leaks = Checkbox::new("Suspected leaks", self.filter.suspected_leaks).on_toggle(Message::ToggleLeakFilter);
+        let system = Checkbox::new("System processes", self.config.show_system_processes).on_toggle(Message::ToggleSystemProcesses);
+        let elevated = Checkbox::new("🛡 Elevated only", self.filter.elevated).on_toggle(Message::ToggleElevatedFilter);
+        let grouped = Checkbox::new("Group apps", self.config.group_apps).on_toggle(Message::ToggleGroupApps);
+        let slices = cfg!(target_os = "linux").then(|| Checkbox::new("Group by slice", self.config.group_slices).on_toggle(Message::ToggleGroupSlices));
+        let mut toolbar = Row::new()
+            .spacing(self.ui.spacing())
+            .align_items(alignment::Alignment::Center)
+            .push(search)
+            .push(scopes)
+            .push(leaks)
+            .push(system)
+            .push(elevated)
+            .push(grouped)
+            .push_maybe(slices)
+            .push_maybe(cfg!(target_os = "linux").then(|| {
+                Tooltip::new(Button::new("Pick window").on_press(Message::PickWindow(false)), "Click a window to select its process", tooltip::Position::Bottom)
+            }))
+            .push_maybe(cfg!(target_os = "linux").then(|| {
+                Tooltip::new(Button::new("Kill window").on_press(Message::PickWindow(true)), "Click a window to kill its process", tooltip::Position::Bottom)
+            }))
+            .push(Button::new(if self.paused { "Resume (Space)" } else { "Pause (Space)" }).on_press(Message::TogglePause))
+            .push(Button::new("Refresh now").on_press(Message::RefreshNow))
+            .push(Button::new("Run new task").on_press(Message::OpenRunDialog))
+            .push(Button::new("Kill by pattern").on_press(Message::OpenKillPattern));
+        // actions for the selected row
+        let selected = self
+            .selected_pid
+            .and_then(|pid| self.processes.iter().find(|process| process.pid == pid));
+        if selected.is_some() {
+            toolbar = toolbar
+                .push(Button::new("Copy PID").on_press(Message::CopySelected(CopyField::Pid)))
+                .push(Button::new("Copy name").on_press(Message::CopySelected(CopyField::Name)))
+                .push(Button::new("Copy command line").on_press(Message::CopySelected(CopyField::CommandLine)))
+                .push(Button::new("Copy row").on_press(Message::CopySelected(CopyField::Row)));
+        }
+        if let Some(process) = selected {
+            toolbar = if self.is_pinned(process) {
+                toolbar.push(Button::new("Unpin").on_press(Message::Unpin(process.pid)))
+            } else {
+                toolbar
+                    .push(Button::new("Pin name").on_press(Message::Pin(Pin::Name(process.name.clone()))))
+                    .push(Button::new("Pin PID").on_press(Message::Pin(Pin::of_process(process))))
+            };
+            toolbar = toolbar.push(Button::new("Ignore").on_press(Message::IgnoreProcess(process.name.clone())));
+        }
+        // the rest act on this machine, so they aren't offered for an agent's processes
+        if let Some(process) = selected.filter(|_| self.is_live_local()) {
+            if self.details.hidden {
+                toolbar = toolbar.push(Button::new("Details").on_press(Message::ToggleDetails));
+            }
+            toolbar = toolbar.push(Button::new("Properties").on_press(Message::OpenProperties(process.pid)));
+            toolbar = toolbar
+                .push(
+                    Button::new("Open file location")
+                        .on_press_maybe(process.exe.as_ref().map(|_| Message::OpenFileLocation(process.pid)))
+                )
+                .push(
+                    Button::new("Restart")
+                        .on_press_maybe(process.exe.as_ref().map(|_| Message::RestartProcess(kill_guard::Target::of(process))))
+                )
+                .push(Button::new("Schedule").on_press(Message::OpenSchedule(process.pid)))
+                .push(
+                    Button::new("Watch").on_press_maybe(
+                        (process.exe.is_some() && !self.is_watched(process))
+                            .then_some(Message::WatchProcess(process.pid))
+                    )
+                );
+            #[cfg(unix)]
+            {
+                toolbar = toolbar.push(Button::new("Send signal").on_press(Message::OpenSignals(kill_guard::Target::of(process))));
+            }
+            toolbar = toolbar.push(Button::new("Dump and kill").on_press(Message::OpenDump(process.pid)));
+            toolbar = toolbar.push(if self.exit_watches.contains(process.pid) {
+                Button::new("Don't notify on exit").on_press(Message::CancelExitNotification(process.pid))
+            } else {
+                Button::new("Notify on exit").on_press(Message::NotifyOnExit(process.pid))
+            });
+        }
+        // process list is scrollable
+        let error = self.host.and(self.provider.error());
+        // saved filter presets, picking one applies its search, filters and sort
+        let names: Vec<String> = self.config.filter_presets.iter().map(|preset| preset.name.clone()).collect();
+        let presets = Row::new()
+            .spacing(self.ui.spacing())
+            .align_items(alignment::Alignment::Center)
+            .push(Text::new("Presets"))
+            .push(PickList::new(names, self.preset.clone(), Message::ApplyPreset).placeholder("Pick a preset").width(Length::Fixed(200.0)))
+            .push(
+                TextInput::new("preset name, e.g. Browsers", &self.preset_input)
+                    .on_input(Message::PresetInputChanged)
+                    .on_submit(Message::SavePreset)
+                    .width(Length::Fixed(250.0))
+            )
+            .push(Button::new("Save preset").on_press_maybe((!self.preset_input.trim().is_empty()).then_some(Message::SavePreset)))
+            .push_maybe(self.preset.clone().map(|name| Button::new("Delete preset").on_press(Message::DeletePreset(name))));
+        let mut content = Column::new()
+            .spacing(self.ui.spacing())
+            .push(remote::switcher(&self.config.hosts, self.host, &self.host_input, error))
+            .push(toolbar)
+            .push(presets)
+            .push(session::bar_view(
+                self.recording.as_ref(),
+                self.playback.as_ref(),
+                &self.recordings,
+                self.picked_recording.as_ref(),
+            ));
+        if let Some(dialog) = &self.run_dialog {
+            content = content.push(run::view(dialog));
+        }
+        if let Some(dialog) = &self.kill_pattern {
+            content = content.push(kill_pattern::view(dialog, &self.processes, &self.config.critical));
+        }
+        if let Some(draft) = &self.note_draft {
+            content = content.push(notes::view(draft));
+        }
+        if let Some(properties) = &self.properties {
+            content = content.push(properties::view(properties));
+        }
+        if let Some(preview) = &self.kill_preview {
+            content = content.push(impact::view(preview));
+        }
+        if let Some(dialog) = &self.schedule_dialog {
+            content = content.push(scheduled::dialog_view(dialog));
+        }
+        if let Some(dialog) = &self.limit_dialog {
+            content = content.push(limits::view(dialog));
+        }
+        #[cfg(unix)]
+        if let Some(dialog) = &self.signal_dialog {
+            content = content.push(signals::view(dialog));
+        }
+        if let Some(dialog) = &self.dump_dialog {
+            content = content.push(dump::view(dialog));
+        }
+        if self.column_picker_open && !simple {
+            content = content.push(columns::picker_view(&self.config.columns, &self.config.column_widths, &self.config.column_formats, &self.column_search));
+        }
+        let ignored = &self.config.ignored;
+        content
+            .push_maybe(
+                (self.config.heat_panel && !simple)
+                    .then(|| heat::view(self.processes.iter().filter(move |process| !ignored.ignores(process))))
+            )
+            .push_maybe((self.is_live_local() && !simple).then(|| churn::view(&self.churn, self.churn_log_open)))
+            .push(header)
+            .push_maybe(totals)
+            .push(
+                MouseArea::new(processes)
+                    .on_enter(Message::ListHovered(true))
+                    .on_exit(Message::ListHovered(false))
+            )
+            .push_maybe(
+                selected
+                    .filter(|_| !self.details.hidden && self.is_live_local())
+                    .map(|process| details::view(&self.details, process, self.config.allow_network_access))
+            )
+            .into()
+    }
+}
+
+// defining how the GUI behaves
+impl Application for TaskManager {
+    type Executor = iced::executor::Default;
+    type Message = Message;
+    type Flags = (Config, Cli, Option<Shared>);
+    type Theme = iced::theme::Theme;
+
+    // new initializes a new TaskManager instance, refreshing the process list immediately
+    fn new((mut config, cli, shared): (Config, Cli, Option<Shared>)) -> (Self, Command<Message>) {
+        let overrides = cli.apply(&mut config);
+        let mut task_manager = TaskManager::with_provider(config, Box::new(SystemProvider));
+        #[cfg(feature = "sim")]
+        if let Some(spec) = cli.simulate.clone() {
+            task_manager.status_message = Some(format!("showing simulated processes, seed {}", spec.seed));
+            task_manager.provider = Box::new(sim::SimProvider::new(spec));
+        }
+        task_manager.overrides = overrides;
+        task_manager.shared = shared;
+        if let Some(column) = cli.sort {
+            task_manager.sort_column = column;
+            task_manager.sort_then.clear();
+        }
+        if cli.sort.is_some() || cli.desc {
+            task_manager.sort_ascending = !cli.desc;
+        }
+        if let Some(text) = cli.filter {
+            task_manager.filter.text = text;
+        }
+        if let Some(tab) = cli.tab {
+            task_manager.config.tab = tab;
+        }
+        if let Some(link) = &cli.open {
+            task_manager.apply_link(link);
+        }
+        task_manager.system_accent = accent::detect();
+        task_manager.themes = themes::load_all();
+        task_manager.hotkey = Hotkey::new();
+        task_manager.update_theme();
+        task_manager.fit_rows();
+        task_manager.hotkey_input = task_manager.config.hotkey.clone();
+        let spec = task_manager.config.hotkey.clone();
+        task_manager.hotkey_status = task_manager.hotkey.set(&spec).err();
+        if task_manager.config.tray_icon {
+            task_manager.start_tray();
+        }
+        task_manager.snapshots = snapshot::load_all();
+        task_manager.recordings = session::list();
+        task_manager.scripts.load();
+        task_manager.crash_report = crash::take_pending();
+        if task_manager.config.recorder.enabled {
+            task_manager.start_recorder();
+        }
+        if cli.debug_timings {
+            task_manager.timings = Some(Timings::default());
+        }
+        // cpu usage is the difference between two refreshes, so the first table waits for a
+        // second sample. both happen in the background while the window shows a skeleton
+        task_manager.startup = Some(startup::Stage::Reading);
+        let mut startup = vec![
+            Command::perform(async { startup::read() }, Message::StartupRead),
+            Command::perform(async { daemon::attach() }, Message::DaemonAttached),
+        ];
+        if task_manager.config.maximized {
+            startup.push(window::maximize(window::Id::MAIN, true));
+        }
+        task_manager.autostart = autostart::installed();
+        if task_manager.config.start_minimized || cli.minimized {
+            startup.push(task_manager.set_window_hidden(true));
+        }
+        (task_manager, Command::batch(startup))
+    }
+
+    // defines the window title as Task Manager, with this machine's load if enabled, and the
+    // process for its own windows
+    fn title(&self, id: window::Id) -> String {
+        if let Some(detached) = self.detached.get(&id) {
+            return detached::title(detached);
+        }
+        if self.config.load_in_title && self.host.is_none() {
+            return format!("Task Manager - {}", about::current_load_and_uptime());
+        }
+        String::from("Task Manager")
+    }
+
+    fn theme(&self, _id: window::Id) -> Theme {
+        self.theme.clone()
+    }
+
+    // zoom on top of the monitor's DPI scaling, can differ per display
+    fn scale_factor(&self, _id: window::Id) -> f64 {
+        self.config.scale_for(&self.current_display)
+    }
+
+    // handles incoming messages, then starts what they left for later
+    fn update(&mut self, message: Message) -> Command<Message> {
+        let command = self.handle(message);
+        if self.deferred.is_empty() {
+            command
+        } else {
+            Command::batch(std::iter::once(command).chain(self.deferred.drain(..)).collect::<Vec<_>>())
+        }
+    }
+
+    // construct the GUI layout, of the main window or a process's own
+    fn view(&self, id: window::Id) -> Element<'_, Message> {
+        if let Some(detached) = self.detached.get(&id) {
+            return detached::view(detached, detached.process(&self.processes), &self.history, Instant::now());
+        }
+        let started = Instant::now();
+        let view = self.window_view();
+        if let Some(timings) = &self.timings {
+            timings.view.set(started.elapsed());
+        }
+        view
+    }
+    // sets up the scheduler's base tick, each data source has its own interval on top of it
+    // also listens for window events to remember geometry per display
+    fn subscription(&self) -> Subscription<Message> {
+        // shortcuts and window events keep working while paused, only the tick stops. it
+        // also waits for the startup scan, a refresh before it would be a full scan of its own
+        let tick = if self.paused || self.startup.is_some() {
+            Subscription::none()
+        } else {
+            time::every(scheduler::BASE_TICK).map(|_| Message::Tick)
+        };
+        // runs next to the tick and isn't paused with it, so pending actions happen on time
+        let timers = if self.scheduled.is_empty() && self.boost.as_ref().is_none_or(|boost| boost.until.is_none()) {
+            Subscription::none()
+        } else {
+            time::every(scheduler::BASE_TICK).map(|_| Message::RunDueActions)
+        };
+        // the banner counts down, so pending kills are checked more often than the tick
+        let kills = if self.pending_kills.is_empty() {
+            Subscription::none()
+        } else {
+            time::every(Duration::from_millis(250)).map(|_| Message::SendPendingKills)
+        };
+        // a single process, on its own faster interval while the window is looked at
+        let close_watch = if self.close_watch.as_ref().is_none_or(|watch| watch.exited) || self.is_idle() {
+            Subscription::none()
+        } else {
+            time::every(close_watch::INTERVAL).map(|_| Message::SampleCloseWatch)
+        };
+        // a header drop captures the release, so only one elsewhere ends the drag here
+        let column_drag = if self.dragged_column.is_none() {
+            Subscription::none()
+        } else {
+            event::listen_with(|event, status| match (event, status) {
+                (Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Left)), event::Status::Ignored) => Some(Message::EndColumnDrag),
+                _ => None,
+            })
+        };
+        Subscription::batch([
+            tick,
+            timers,
+            kills,
+            close_watch,
+            column_drag,
+            hotkey::subscription(),
+            instance::subscription(),
+            tray::subscription(),
+            keys::subscription(),
+            // the geometry and focus are the main window's, the others only report closing
+            event::listen_with(|event, _status| match event {
+                Event::Window(id, window::Event::Closed) if id != window::Id::MAIN => Some(Message::DetachedClosed(id)),
+                Event::Window(id, window::Event::Focused) if id != window::Id::MAIN => Some(Message::DetachedFocused(id, true)),
+                Event::Window(id, window::Event::Unfocused) if id != window::Id::MAIN => Some(Message::DetachedFocused(id, false)),
+                Event::Window(id, _) if id != window::Id::MAIN => None,
+                Event::Window(_, window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+                Event::Window(_, window::Event::Resized { width, height }) => Some(Message::WindowResized(width, height)),
+                Event::Window(_, window::Event::CloseRequested) => Some(Message::WindowCloseRequested),
+                Event::Window(_, window::Event::Focused) => Some(Message::WindowFocused(true)),
+                Event::Window(_, window::Event::Unfocused) => Some(Message::WindowFocused(false)),
+                Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => Some(Message::ModifiersChanged(modifiers)),
+                _ => None,
+            }),
+        ])
+    }
+}
+
+impl TaskManager {
+    // handles incoming messages (killing or sorting)
+    fn handle(&mut self, message: Message) -> Command<Message> {
+        if self.config.read_only && message.changes_processes() {
+            self.status_message = Some(String::from("read-only mode, processes can't be changed"));
+            return Command::none();
+        }
+        // while the palette is open the arrows, Enter and Escape act on it
+        if let Some(shown) = self.palette.clone() {
+            match message {
+                Message::MoveSelection(delta) => {
+                    let last = palette::matches(self.palette_entries(), &shown.query).len().saturating_sub(1);
+                    let selected = (shown.selected as i64 + i64::from(delta)).clamp(0, last as i64) as usize;
+                    self.palette = Some(Palette { selected, ..shown });
+                    return Command::none();
+                }
+                Message::OpenSelectedProperties => return self.update(Message::RunPaletteEntry(shown.selected)),
+                Message::ClearSelection => {
+                    self.palette = None;
+                    return Command::none();
+                }
+                _ => {}
+            }
+        }
+        match message {
+            // refreshes the sources that are due
+            Message::Tick => {
+                let selected = self.selected_index();
+                self.refresh();
+                let scroll = Command::batch([self.keep_scroll_position(selected), self.check_low_memory()]);
+                // container names come from the runtimes' cli, so they are fetched off the ui thread
+                if std::mem::take(&mut self.container_lookup_pending) {
+                    Command::batch([scroll, Command::perform(async { containers::lookup_names() }, Message::ContainerNamesFound)])
+                } else {
+                    scroll
+                }
+            }
+            Message::DaemonAttached(attached) => {
+                if let Some(attached) = attached {
+                    self.take_backlog(&attached.backlog);
+                    self.daemon = Some(attached);
+                }
+                Command::none()
+            }
+            Message::StartupRead(scanned) => {
+                if let Some(system) = scanned.take() {
+                    self.system = system;
+                }
+                self.system_info = SystemInfo::read(&self.system);
+                self.startup = Some(startup::Stage::Sampling);
+                Command::perform(async { startup::wait_for_cpu() }, |()| Message::StartupSampled)
+            }
+            Message::StartupSampled => {
+                self.startup = None;
+                self.refresh();
+                let pids: Vec<u32> = self.processes.iter().map(|process| process.pid).collect();
+                let limits = Command::perform(async move { limits::existing(&pids) }, Message::LimitsFound);
+                // reopen on the tab used last, with the refreshes that tab needs
+                Command::batch([limits, self.update(Message::SelectTab(self.config.tab))])
+            }
+            Message::ContainerNamesFound(names) => {
+                self.container_names.extend(names);
+                self.refresh_containers();
+                Command::none()
+            }
+            Message::ContainersDetected(found) => {
+                self.detecting_containers = false;
+                self.container_ids.extend(found);
+                self.refresh_containers();
+                Command::none()
+            }
+            Message::SetScope(scope) => {
+                self.filter.scope = scope;
+                self.refresh_containers();
+                self.scroll_to_top()
+            }
+            Message::TogglePause => {
+                self.paused = !self.paused;
+                Command::none()
+            }
+            Message::SetRefreshInterval(source, seconds) => {
+                self.config.refresh_intervals.set(source, seconds);
+                Command::none()
+            }
+            Message::TogglePauseWhenUnfocused(enabled) => {
+                self.config.pause_when_unfocused = enabled;
+                Command::none()
+            }
+            Message::ToggleAdaptiveRefresh(enabled) => {
+                self.config.adaptive_refresh = enabled;
+                if !enabled {
+                    self.scheduler.reset_backoff();
+                }
+                Command::none()
+            }
+            Message::ToggleBatterySaver(enabled) => {
+                self.config.battery_saver = enabled;
+                self.throttle();
+                Command::none()
+            }
+            Message::ToggleNoiseFilter(enabled) => {
+                self.config.noise_filter.enabled = enabled;
+                Command::none()
+            }
+            Message::SetNoiseMinCpu(cpu) => {
+                self.config.noise_filter.min_cpu = cpu.max(0.0);
+                Command::none()
+            }
+            Message::SetNoiseMinMemory(memory) => {
+                self.config.noise_filter.min_memory_mb = memory;
+                Command::none()
+            }
+            Message::ApplyPreset(name) => {
+                if let Some(preset) = self.config.filter_presets.iter().find(|preset| preset.name == name) {
+                    self.filter.text = preset.text.clone();
+                    self.filter.scope = preset.scope;
+                    self.filter.suspected_leaks = preset.suspected_leaks;
+                    self.filter.elevated = preset.elevated;
+                    self.sort_column = preset.sort_column;
+                    self.sort_ascending = preset.sort_ascending;
+                    self.sort_then.clear();
+                    self.config.sort = self.sort_keys();
+                    self.sort_processes();
+                    self.preset = Some(name);
+                    return self.scroll_to_top();
+                }
+                Command::none()
+            }
+            Message::PresetInputChanged(text) => {
+                self.preset_input = text;
+                Command::none()
+            }
+            Message::SavePreset if self.preset_input.trim().is_empty() => Command::none(),
+            // a preset with the same name is replaced
+            Message::SavePreset => {
+                let preset = FilterPreset {
+                    name: std::mem::take(&mut self.preset_input).trim().to_string(),
+                    text: self.filter.text.clone(),
+                    scope: self.filter.scope,
+                    suspected_leaks: self.filter.suspected_leaks,
+                    elevated: self.filter.elevated,
+                    sort_column: self.sort_column,
+                    sort_ascending: self.sort_ascending,
+                };
+                self.preset = Some(preset.name.clone());
+                match self.config.filter_presets.iter_mut().find(|existing| existing.name == preset.name) {
+                    Some(existing) => *existing = preset,
+                    None => self.config.filter_presets.push(preset),
+                }
+                Command::none()
+            }
+            Message::DeletePreset(name) => {
+                self.config.filter_presets.retain(|preset| preset.name != name);
+                if self.preset.as_ref() == Some(&name) {
+                    self.preset = None;
+                }
+                Command::none()
+            }
+            Message::IgnoreInputChanged(text) => {
+                self.ignore_input = text;
+                Command::none()
+            }
+            Message::AddIgnored => {
+                self.config.ignored.add(&std::mem::take(&mut self.ignore_input));
+                Command::none()
+            }
+            Message::IgnoreProcess(name) => {
+                self.config.ignored.add(&name);
+                self.selected_pid = None;
+                Command::none()
+            }
+            Message::RemoveIgnored(name) => {
+                self.config.ignored.remove(&name);
+                Command::none()
+            }
+            Message::CriticalInputChanged(text) => {
+                self.critical_input = text;
+                Command::none()
+            }
+            Message::AddCritical => {
+                critical::add(&mut self.config.critical, &std::mem::take(&mut self.critical_input));
+                self.share_policy();
+                Command::none()
+            }
+            Message::RemoveCritical(name) => {
+                self.config.critical.retain(|known| *known != name);
+                self.share_policy();
+                Command::none()
+            }
+            Message::Pin(pin) => {
+                if !self.config.pinned.contains(&pin) {
+                    self.config.pinned.push(pin);
+                }
+                Command::none()
+            }
+            Message::Unpin(pid) => {
+                if let Some(process) = self.processes.iter().find(|process| process.pid == pid) {
+                    self.config.pinned.retain(|pin| !pin.matches(process));
+                }
+                Command::none()
+            }
+            Message::ToggleSystemProcesses(shown) => {
+                self.config.show_system_processes = shown;
+                Command::none()
+            }
+            Message::ToggleGroupApps(grouped) => {
+                self.config.group_apps = grouped;
+                self.config.group_slices &= !grouped;
+                Command::none()
+            }
+            Message::ToggleGroupSlices(grouped) => {
+                self.config.group_slices = grouped;
+                self.config.group_apps &= !grouped;
+                self.refresh_slices();
+                Command::none()
+            }
+            Message::RelaunchElevated => match run::relaunch_elevated() {
+                Ok(()) => self.quit(),
+                Err(err) => {
+                    self.report_error(err);
+                    Command::none()
+                }
+            },
+            Message::ToggleGroup(group) => {
+                if !self.collapsed_groups.remove(&group) {
+                    self.collapsed_groups.insert(group);
+                }
+                Command::none()
+            }
+            Message::ToggleElevatedFilter(enabled) => {
+                self.filter.elevated = enabled;
+                Command::none()
+            }
+            Message::ToggleLeakFilter(enabled) => {
+                self.filter.suspected_leaks = enabled;
+                Command::none()
+            }
+            // the flags are recomputed from the existing runs on the next refresh
+            Message::SetBoostMinutes(minutes) => {
+                self.config.boost_minutes = minutes;
+                Command::none()
+            }
+            Message::ToggleBoostLowersOthers(lowers) => {
+                self.config.boost_lowers_others = lowers;
+                Command::none()
+            }
+            Message::SetLeakSamples(samples) => {
+                self.config.leak_samples = samples.max(1);
+                Command::none()
+            }
+            // the next refresh starts from raw values so the averages aren't mixed across scales
+            Message::ToggleCpuOfWholeMachine(whole) => {
+                self.config.cpu_of_whole_machine = whole;
+                self.cpu_history.clear();
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                Command::none()
+            }
+            Message::SetCpuSmoothing(samples) => {
+                self.config.cpu_smoothing = samples.clamp(1, process::MAX_CPU_SMOOTHING);
+                Command::none()
+            }
+            // sorts the list by the given column
+            Message::Sort(column) => {
+                let selected = self.selected_index();
+                if self.modifiers.shift() {
+                    self.then_sort_by(column);
+                } else {
+                    self.sort_by(column);
+                }
+                self.keep_scroll_position(selected)
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Command::none()
+            }
+            // attempts to kill the process with the given PID
+            Message::KillProcess(target) => {
+                self.kill_preview = None;
+                self.request_kill(target, false);
+                Command::none()
+            }
+            Message::RemoteKilled(target, by, result) => {
+                match result {
+                    Ok(()) => self.events.push(EventKind::Kill, format!("{} killed {}", by, target.label())),
+                    Err(err) => self.report_error(format!("Failed to kill {}: {}", target.label(), err)),
+                }
+                Command::none()
+            }
+            Message::KillTree(target) => {
+                self.kill_preview = None;
+                self.request_kill(target, true);
+                Command::none()
+            }
+            Message::SampleCloseWatch => {
+                if let Some(watch) = &mut self.close_watch {
+                    watch.sample();
+                }
+                Command::none()
+            }
+            Message::StopCloseWatch => {
+                self.close_watch = None;
+                Command::none()
+            }
+            Message::CloseComparison => {
+                self.comparison = None;
+                Command::none()
+            }
+            Message::DetachedClosed(id) => {
+                self.detached.remove(&id);
+                if self.focused_detached == Some(id) {
+                    self.focused_detached = None;
+                }
+                Command::none()
+            }
+            Message::DetachedFocused(id, focused) => {
+                if focused {
+                    self.focused_detached = Some(id);
+                } else if self.focused_detached == Some(id) {
+                    self.focused_detached = None;
+                }
+                Command::none()
+            }
+            Message::Shortcut(message) => match self.focused_detached {
+                None => self.handle(*message),
+                // a process's own window has no selection, Escape closes it and F5 refreshes
+                Some(id) => match *message {
+                    Message::ClearSelection => {
+                        self.detached.remove(&id);
+                        self.focused_detached = None;
+                        window::close(id)
+                    }
+                    Message::RefreshNow => self.handle(Message::RefreshNow),
+                    _ => Command::none(),
+                },
+            },
+            Message::TerminateJob(index) => {
+                if let Some(job) = self.jobs.get(index).cloned() {
+                    let by = format!("You (terminating {})", job.name);
+                    let targets: Vec<kill_guard::Target> = job.kill_order(&self.processes).into_iter().filter_map(|pid| self.target_of(pid)).collect();
+                    let failed = targets.iter().filter(|target| !self.kill_logged(target, &by)).count();
+                    if failed > 0 {
+                        self.report_error(format!("{} processes of {} couldn't be killed", failed, job.name));
+                    }
+                    self.refresh_processes();
+                    self.scheduler.mark(Source::Processes, Instant::now());
+                }
+                Command::none()
+            }
+            Message::ForgetJob(index) => {
+                if index < self.jobs.len() {
+                    self.jobs.remove(index);
+                }
+                Command::none()
+            }
+            Message::OpenPalette => {
+                self.palette = Some(Palette::default());
+                text_input::focus(palette::input_id())
+            }
+            Message::PaletteQueryChanged(query) => {
+                self.palette = Some(Palette { query, selected: 0 });
+                Command::none()
+            }
+            Message::RunPaletteEntry(index) => {
+                let Some(shown) = self.palette.take() else {
+                    return Command::none();
+                };
+                match palette::matches(self.palette_entries(), &shown.query).into_iter().nth(index) {
+                    Some(entry) => self.update(entry.message),
+                    None => Command::none(),
+                }
+            }
+            Message::SetNoteTag(tag) => {
+                if let Some(draft) = &mut self.note_draft {
+                    draft.tag = tag;
+                }
+                Command::none()
+            }
+            Message::NoteTextChanged(text) => {
+                if let Some(draft) = &mut self.note_draft {
+                    draft.text = text;
+                }
+                Command::none()
+            }
+            // an empty note removes the program's entry
+            Message::SaveNote => {
+                if let Some(draft) = self.note_draft.take() {
+                    match draft.to_note() {
+                        Some(note) => self.config.notes.insert(draft.key, note),
+                        None => self.config.notes.remove(&draft.key),
+                    };
+                }
+                Command::none()
+            }
+            Message::CancelNote => {
+                self.note_draft = None;
+                Command::none()
+            }
+            Message::EndBoost => {
+                self.end_boost();
+                Command::none()
+            }
+            Message::OpenCrashReport => {
+                if let Some(report) = self.crash_report.take() {
+                    if let Err(err) = open::open_path(&report) {
+                        self.report_error(err);
+                    }
+                }
+                Command::none()
+            }
+            Message::DismissCrashReport => {
+                self.crash_report = None;
+                Command::none()
+            }
+            Message::UndoKill(pid) => {
+                self.pending_kills.retain(|kill| kill.target.pid != pid);
+                Command::none()
+            }
+            Message::SendPendingKills => {
+                // the process may have exited and its pid been reused meanwhile, the kill checks it
+                for kill in soft_kill::take_due(&mut self.pending_kills, Instant::now()) {
+                    self.kill_now(&kill.target, kill.tree);
+                }
+                Command::none()
+            }
+            Message::ToggleUndoKills(enabled) => {
+                self.config.undo_kills = enabled;
+                Command::none()
+            }
+            // kills already waiting or previewed are dropped with the switch
+            Message::ToggleReadOnly(enabled) => {
+                self.config.read_only = enabled;
+                if enabled {
+                    self.pending_kills.clear();
+                    self.kill_preview = None;
+                }
+                self.share_policy();
+                Command::none()
+            }
+            Message::RowAction(pid, action) => match action {
+                RowAction::Kill => self.preview_kill(pid, false),
+                RowAction::KillTree => self.preview_kill(pid, true),
+                RowAction::Suspend | RowAction::Resume => {
+                    let suspend = action == RowAction::Suspend;
+                    let verb = if suspend { "suspend" } else { "resume" };
+                    let checked = match self.target_of(pid) {
+                        Some(target) => self.check_target(&target).map_err(|refusal| refusal.message(verb, &target)),
+                        None => Err(format!("Didn't {} process {}, it has already exited", verb, pid)),
+                    };
+                    match checked.and_then(|()| row_menu::suspend(pid, suspend)) {
+                        Ok(()) => {
+                            if suspend {
+                                self.suspended.insert(pid);
+                            } else {
+                                self.suspended.remove(&pid);
+                            }
+                            self.status_message = None;
+                        }
+                        Err(err) => self.report_error(err),
+                    }
+                    Command::none()
+                }
+                RowAction::RaisePriority => self.update(Message::AdjustPriority(pid, -1)),
+                RowAction::LowerPriority => self.update(Message::AdjustPriority(pid, 1)),
+                RowAction::Boost => {
+                    self.end_boost();
+                    match Boost::start(&self.processes, pid, self.config.boost_lowers_others, self.config.boost_minutes, Instant::now()) {
+                        Ok(boost) => self.boost = Some(boost),
+                        Err(err) => self.report_error(err),
+                    }
+                    self.refresh_processes();
+                    Command::none()
+                }
+                RowAction::Limit => {
+                    self.limit_dialog = self
+                        .processes
+                        .iter()
+                        .find(|process| process.pid == pid)
+                        .map(|process| limits::LimitDialog::new(process, self.limits.get(&pid)));
+                    Command::none()
+                }
+                RowAction::WatchClosely => {
+                    let name = self.processes.iter().find(|process| process.pid == pid).map(|process| process.name.clone());
+                    self.close_watch = name.map(|name| CloseWatch::new(pid, name));
+                    Command::none()
+                }
+                // the first pick only selects the process, the second opens the comparison
+                RowAction::Compare => {
+                    match self.selected_pid.filter(|&selected| selected != pid && self.processes.iter().any(|process| process.pid == selected)) {
+                        Some(selected) => {
+                            self.comparison = Some([selected, pid]);
+                            self.status_message = None;
+                        }
+                        None => {
+                            self.selected_pid = Some(pid);
+                            self.refresh_details();
+                            self.status_message = Some(String::from("Selected, pick Compare with selected on the other process"));
+                        }
+                    }
+                    Command::none()
+                }
+                RowAction::OpenWindow => {
+                    let Some(process) = self.processes.iter().find(|process| process.pid == pid) else {
+                        return Command::none();
+                    };
+                    let (id, spawn) = window::spawn(detached::settings());
+                    self.detached.insert(id, Detached::of(process));
+                    spawn
+                }
+                RowAction::Note => {
+                    self.note_draft = self
+                        .processes
+                        .iter()
+                        .find(|process| process.pid == pid)
+                        .map(|process| NoteDraft::new(process, self.config.notes.get(&notes::key(process))));
+                    Command::none()
+                }
+                RowAction::Details => {
+                    self.selected_pid = Some(pid);
+                    self.details.hidden = false;
+                    self.refresh_details();
+                    Command::none()
+                }
+            },
+            // ignored if the preview was closed or moved to another process meanwhile
+            Message::KillImpactReady(pid, external) => {
+                if let Some(preview) = self.kill_preview.as_mut().filter(|preview| preview.pid == pid) {
+                    preview.external = Some(external);
+                }
+                Command::none()
+            }
+            Message::CancelKill => {
+                self.kill_preview = None;
+                Command::none()
+            }
+            Message::AcknowledgeCritical(acknowledged) => {
+                if let Some(preview) = self.kill_preview.as_mut() {
+                    preview.acknowledged = acknowledged;
+                }
+                Command::none()
+            }
+            Message::SearchChanged(text) => {
+                self.filter.text = text;
+                self.refresh_holders();
+                self.scroll_to_top()
+            }
+            Message::SelectProcess(pid) => {
+                let now = Instant::now();
+                let double = self
+                    .last_click
+                    .is_some_and(|(clicked, at)| clicked == pid && now.duration_since(at) < DOUBLE_CLICK);
+                self.last_click = if double { None } else { Some((pid, now)) };
+                self.selected_pid = Some(pid);
+                self.refresh_details();
+                if double {
+                    return self.update(Message::OpenProperties(pid));
+                }
+                Command::none()
+            }
+            // reads this machine, so there are no properties for an agent's processes
+            Message::OpenProperties(pid) => {
+                if self.is_live_local() {
+                    self.properties = self
+                        .processes
+                        .iter()
+                        .find(|process| process.pid == pid)
+                        .and_then(|process| Properties::read(&self.system, process));
+                }
+                Command::none()
+            }
+            Message::CloseProperties => {
+                self.properties = None;
+                Command::none()
+            }
+            // moves within the filtered list, starting from the top if nothing is selected
+            // scrolls the list when the selection leaves the viewport
+            Message::MoveSelection(delta) => {
+                let visible = self.visible_processes();
+                if visible.is_empty() {
+                    return Command::none();
+                }
+                let current = self
+                    .selected_pid
+                    .and_then(|pid| visible.iter().position(|process| process.pid == pid));
+                let index = match current {
+                    Some(index) => (index as i64 + delta as i64).clamp(0, visible.len() as i64 - 1) as usize,
+                    None => 0,
+                };
+                let pid = visible[index].pid;
+                self.reveal(pid)
+            }
+            Message::JumpToProcess(pid) => {
+                if self.visible_processes().iter().any(|process| process.pid == pid) {
+                    return self.reveal(pid);
+                }
+                self.status_message = Some(match self.processes.iter().find(|process| process.pid == pid) {
+                    Some(process) => format!("{} ({}) is hidden by the current filters", process.name, pid),
+                    None => format!("process {} isn't in the table", pid),
+                });
+                Command::none()
+            }
+            Message::PickWindow(kill) => Command::perform(async { pick_window::pick() }, move |picked| Message::WindowPicked(kill, picked)),
+            Message::WindowPicked(kill, Ok(pid)) => {
+                self.tab = Tab::Processes;
+                let jump = self.update(Message::JumpToProcess(pid));
+                if kill {
+                    return Command::batch([jump, self.update(Message::RowAction(pid, RowAction::Kill))]);
+                }
+                jump
+            }
+            Message::WindowPicked(_, Err(err)) => {
+                self.report_error(err);
+                Command::none()
+            }
+            Message::ClearSelection => {
+                self.selected_pid = None;
+                self.thaw_order();
+                Command::none()
+            }
+            Message::ToggleDetails => {
+                self.details.hidden = !self.details.hidden;
+                self.refresh_details();
+                Command::none()
+            }
+            Message::SelectDetailSection(section) => {
+                self.details.section = section;
+                self.refresh_details();
+                Command::none()
+            }
+            Message::ResourceFilterChanged(filter) => {
+                self.details.resource_filter = filter;
+                Command::none()
+            }
+            Message::ToggleEnvironmentDiff(diff) => {
+                self.details.environment_diff = diff;
+                self.refresh_details();
+                Command::none()
+            }
+            Message::EnvironmentFilterChanged(filter) => {
+                self.details.environment_filter = filter;
+                Command::none()
+            }
+            Message::OomAdjInputChanged(input) => {
+                self.details.oom_adj_input = input;
+                Command::none()
+            }
+            Message::SetOomAdj(pid, adj) => {
+                match oom::set_adj(pid, adj) {
+                    Ok(()) => {
+                        self.details.oom_adj_input.clear();
+                        self.status_message = Some(format!("OOM adjustment of {} set to {}", pid, adj));
+                    }
+                    Err(err) => self.report_error(err),
+                }
+                self.refresh_details();
+                Command::none()
+            }
+            Message::CheckIntegrity(pid, exe) => {
+                self.details.checking = Some(pid);
+                Command::perform(async move { integrity::check(exe) }, move |checked| Message::IntegrityChecked(pid, checked))
+            }
+            Message::IntegrityChecked(pid, checked) => {
+                if self.details.checking == Some(pid) {
+                    self.details.checking = None;
+                }
+                self.details.integrity = Some((pid, checked));
+                Command::none()
+            }
+            Message::SortThreads(column) => {
+                self.details.sort_threads_by(column);
+                Command::none()
+            }
+            Message::ListScrolled(viewport) => {
+                self.list_window.update(viewport);
+                Command::none()
+            }
+            Message::SelectChartRange(range) => {
+                self.chart_range = range;
+                Command::none()
+            }
+            Message::CopyChart(kind) => self.export_chart(kind, None),
+            Message::SaveChart(kind) => self.export_chart(kind, Some(chart::image_path(kind.label(), chrono::Local::now()))),
+            Message::ChartExported(result) => {
+                self.status_message = Some(result.unwrap_or_else(|err| err));
+                Command::none()
+            }
+            Message::ToggleEventKind(kind, shown) => {
+                if shown {
+                    self.event_kinds.insert(kind);
+                } else {
+                    self.event_kinds.remove(&kind);
+                }
+                Command::none()
+            }
+            Message::EventExportPathChanged(path) => {
+                self.event_export_input = path;
+                Command::none()
+            }
+            Message::ExportEvents => {
+                let path = std::path::PathBuf::from(&self.event_export_input);
+                match self.events.export(&self.event_kinds, &path) {
+                    Ok(count) => self.status_message = Some(format!("exported {} events to {}", count, path.display())),
+                    Err(err) => self.report_error(format!("failed to export events: {}", err)),
+                }
+                Command::none()
+            }
+            Message::GridSort(column) => {
+                self.grid.sort_by(column);
+                Command::none()
+            }
+            Message::GridSearchChanged(text) => {
+                self.grid.filter.text = text;
+                Command::none()
+            }
+            Message::GridScrolled(viewport) => {
+                self.grid.window.update(viewport);
+                Command::none()
+            }
+            Message::ListHovered(hovered) => {
+                self.list_hovered = hovered;
+                self.thaw_order();
+                Command::none()
+            }
+            Message::SetSecondarySort(column) => {
+                self.config.secondary_sort = column;
+                self.sort_processes();
+                Command::none()
+            }
+            Message::ToggleFreezeOrder(freeze) => {
+                self.config.freeze_order = freeze;
+                self.thaw_order();
+                Command::none()
+            }
+            Message::ToggleHeatPanel(shown) => {
+                self.config.heat_panel = shown;
+                Command::none()
+            }
+            Message::ToggleBarCells(shown) => {
+                self.config.bar_cells = shown;
+                Command::none()
+            }
+            Message::ToggleTotalsRow(shown) => {
+                self.config.totals_row = shown;
+                Command::none()
+            }
+            Message::SetNarrowWidth(width) => {
+                self.config.narrow_width = width;
+                self.fit_rows();
+                Command::none()
+            }
+            Message::ToggleProcessIcons(shown) => {
+                self.config.process_icons = shown;
+                if shown {
+                    self.icons.resolve(&self.processes);
+                }
+                Command::none()
+            }
+            Message::ToggleLoadInTitle(shown) => {
+                self.config.load_in_title = shown;
+                Command::none()
+            }
+            Message::ToggleChurnLog => {
+                self.churn_log_open = !self.churn_log_open;
+                Command::none()
+            }
+            Message::KillSelected => match self.selected_pid {
+                Some(pid) => self.preview_kill(pid, false),
+                None => Command::none(),
+            },
+            Message::KillSelectedTree => match self.selected_pid {
+                Some(pid) => self.preview_kill(pid, true),
+                None => Command::none(),
+            },
+            Message::OpenSelectedProperties => match self.selected_pid {
+                Some(pid) => self.update(Message::OpenProperties(pid)),
+                None => Command::none(),
+            },
+            Message::FocusNext => focus_next(),
+            Message::FocusPrevious => focus_previous(),
+            // wraps around at either end
+            Message::CycleTab(step) => {
+                let tabs = self.config.layout.tabs();
+                let current = tabs.iter().position(|&tab| tab == self.tab).unwrap_or_default() as i32;
+                let next = (current + step).rem_euclid(tabs.len() as i32) as usize;
+                self.update(Message::SelectTab(tabs[next]))
+            }
+            Message::CopySelected(field) => {
+                let selected = self
+                    .selected_pid
+                    .and_then(|pid| self.processes.iter().find(|process| process.pid == pid));
+                match selected {
+                    Some(process) => clipboard::write(match field {
+                        CopyField::Pid => process.pid.to_string(),
+                        CopyField::Name => process.name.clone(),
+                        CopyField::CommandLine => process.cmd.clone(),
+                        CopyField::Row => columns::row_text(process, self.config.layout.columns(&self.config.columns), self.formatter()),
+                    }),
+                    None => Command::none(),
+                }
+            }
+            Message::CopyText(text) => clipboard::write(text),
+            Message::LookUpHash(sha256) => {
+                let opened = online::ensure_allowed(&self.config, OnlineFeature::ReputationLookup)
+                    .and_then(|()| open::open_url(&integrity::virustotal_url(&sha256)));
+                if let Err(err) = opened {
+                    self.report_error(err);
+                }
+                Command::none()
+            }
+            Message::RefreshSystemInfo => {
+                self.system.refresh_memory();
+                self.system_info = SystemInfo::read(&self.system);
+                Command::none()
+            }
+            Message::OpenFileLocation(pid) => {
+                let exe = self
+                    .processes
+                    .iter()
+                    .find(|process| process.pid == pid)
+                    .and_then(|process| process.exe.clone());
+                self.status_message = match exe {
+                    Some(exe) => open::open_containing_dir(&exe).err(),
+                    None => Some(format!("the executable of process {} is unknown", pid)),
+                };
+                Command::none()
+            }
+            Message::RestartProcess(target) => self.restart(&target),
+            Message::OpenSchedule(pid) => {
+                self.schedule_dialog = self
+                    .processes
+                    .iter()
+                    .find(|process| process.pid == pid)
+                    .map(|process| ScheduleDialog::new(kill_guard::Target::of(process)));
+                Command::none()
+            }
+            Message::LimitCpuChanged(cpu) => {
+                if let Some(dialog) = &mut self.limit_dialog {
+                    dialog.cpu = cpu;
+                }
+                Command::none()
+            }
+            Message::LimitMemoryChanged(memory) => {
+                if let Some(dialog) = &mut self.limit_dialog {
+                    dialog.memory = memory;
+                }
+                Command::none()
+            }
+            // systemctl and busctl wait for the service manager, so they run off the ui thread
+            Message::ApplyLimit => {
+                let Some(dialog) = &self.limit_dialog else {
+                    return Command::none();
+                };
+                match dialog.to_limit() {
+                    Ok(limit) => {
+                        let (pid, start_time, name) = (dialog.pid, dialog.start_time, dialog.name.clone());
+                        Command::perform(async move { limits::apply(pid, start_time, &limit).map(|()| limit) }, move |result| {
+                            Message::LimitApplied(pid, name.clone(), result)
+                        })
+                    }
+                    Err(err) => {
+                        self.report_error(err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::RemoveLimit => {
+                let Some(dialog) = self.limit_dialog.take() else {
+                    return Command::none();
+                };
+                let lifted = limits::Limit::default();
+                Command::perform(async move { limits::apply(dialog.pid, dialog.start_time, &lifted).map(|()| lifted) }, move |result| {
+                    Message::LimitApplied(dialog.pid, dialog.name.clone(), result)
+                })
+            }
+            Message::LimitApplied(pid, name, result) => {
+                match result {
+                    Ok(limit) if limit.is_empty() => {
+                        self.limits.remove(&pid);
+                        self.status_message = Some(format!("Limits of {} ({}) lifted", name, pid));
+                    }
+                    Ok(limit) => {
+                        self.status_message = Some(format!("{} ({}) limited to {}", name, pid, limit.describe()));
+                        self.limits.insert(pid, limit);
+                        if self.limit_dialog.as_ref().is_some_and(|dialog| dialog.pid == pid) {
+                            self.limit_dialog = None;
+                        }
+                    }
+                    Err(err) => self.report_error(err),
+                }
+                self.refresh_details();
+                Command::none()
+            }
+            Message::LimitsFound(found) => {
+                for (pid, limit) in found {
+                    self.limits.entry(pid).or_insert(limit);
+                }
+                Command::none()
+            }
+            Message::CloseLimit => {
+                self.limit_dialog = None;
+                Command::none()
+            }
+            Message::SetScheduleKind(kind) => {
+                if let Some(dialog) = &mut self.schedule_dialog {
+                    dialog.kind = kind;
+                }
+                Command::none()
+            }
+            Message::ToggleScheduleByName(by_name) => {
+                if let Some(dialog) = &mut self.schedule_dialog {
+                    dialog.by_name = by_name;
+                }
+                Command::none()
+            }
+            Message::ScheduleWhenChanged(when) => {
+                if let Some(dialog) = &mut self.schedule_dialog {
+                    dialog.when = when;
+                }
+                Command::none()
+            }
+            // a time that doesn't parse keeps the dialog open with the reason in the footer
+            Message::ConfirmSchedule => {
+                let Some(dialog) = &self.schedule_dialog else {
+                    return Command::none();
+                };
+                match dialog.to_action(self.next_action_id, chrono::Local::now()) {
+                    Ok(action) => {
+                        self.status_message =
+                            Some(format!("{} at {}", action.describe(), format::time(&action.due)));
+                        self.next_action_id += 1;
+                        self.scheduled.push(action);
+                        self.schedule_dialog = None;
+                    }
+                    Err(err) => self.report_error(err),
+                }
+                Command::none()
+            }
+            Message::CloseSchedule => {
+                self.schedule_dialog = None;
+                Command::none()
+            }
+            #[cfg(unix)]
+            Message::OpenSignals(target) => {
+                self.signal_dialog = Some(signals::SignalDialog::new(target));
+                Command::none()
+            }
+            #[cfg(unix)]
+            Message::SelectSignal(signal) => {
+                if let Some(dialog) = &mut self.signal_dialog {
+                    dialog.signal = Some(signal);
+                }
+                Command::none()
+            }
+            // the dialog closes either way, the footer tells whether the signal arrived
+            #[cfg(unix)]
+            Message::SendSignal => {
+                if let Some(dialog) = self.signal_dialog.take() {
+                    if let Some(signal) = dialog.signal {
+                        let sent = self
+                            .check_target(&dialog.target)
+                            .map_err(|refusal| refusal.message(&format!("send {} to", signal.name), &dialog.target))
+                            .and_then(|()| signals::send(dialog.target.pid, signal));
+                        self.status_message = Some(match sent {
+                            Ok(()) => format!("sent {} to {}", signal.name, dialog.target.label()),
+                            Err(err) => err,
+                        });
+                    }
+                    self.refresh_processes();
+                }
+                Command::none()
+            }
+            #[cfg(unix)]
+            Message::CloseSignals => {
+                self.signal_dialog = None;
+                Command::none()
+            }
+            Message::NotifyOnExit(pid) => {
+                if let Some(process) = self.processes.iter().find(|process| process.pid == pid) {
+                    self.exit_watches.mark(pid, &process.name, Instant::now());
+                }
+                Command::none()
+            }
+            Message::CancelExitNotification(pid) => {
+                self.exit_watches.unmark(pid);
+                Command::none()
+            }
+            Message::ToggleFocusMode => {
+                self.focus.toggle();
+                Command::none()
+            }
+            Message::ShowSessionSummary => {
+                self.session_summary = Some(self.session.summary(&self.events, &self.churn, chrono::Local::now()));
+                Command::none()
+            }
+            Message::CloseSessionSummary => {
+                self.session_summary = None;
+                Command::none()
+            }
+            Message::SaveSessionSummary(format) => {
+                let summary = self.session.summary(&self.events, &self.churn, chrono::Local::now());
+                match summary.save(format) {
+                    Ok(path) => self.status_message = Some(format!("Saved the session summary to {}", path.display())),
+                    Err(err) => self.report_error(format!("Failed to save the session summary: {}", err)),
+                }
+                Command::none()
+            }
+            Message::SetSummaryOnExit(format) => {
+                self.config.summary_on_exit = format;
+                Command::none()
+            }
+            Message::ToggleBackgroundMonitor(enabled) => {
+                self.config.background_monitor = enabled;
+                Command::none()
+            }
+            Message::DismissLowMemory => {
+                self.low_memory.dismissed = true;
+                Command::none()
+            }
+            Message::SetLowMemoryThreshold(percent) => {
+                self.config.low_memory_percent = percent.min(99);
+                Command::none()
+            }
+            Message::ToggleLowMemoryShowsWindow(shows) => {
+                self.config.low_memory_shows_window = shows;
+                Command::none()
+            }
+            Message::ToggleFocusDigest => {
+                self.focus.open = !self.focus.open;
+                Command::none()
+            }
+            Message::ClearFocusDigest => {
+                self.focus.clear();
+                Command::none()
+            }
+            Message::ShowAlertEvents => {
+                self.event_kinds = HashSet::from([EventKind::Alert]);
+                self.update(Message::SelectTab(Tab::Events))
+            }
+            Message::OpenDump(pid) => {
+                self.dump_dialog = self
+                    .processes
+                    .iter()
+                    .find(|process| process.pid == pid)
+                    .map(|process| DumpDialog::new(kill_guard::Target::of(process), &self.config.dump_dir));
+                Command::none()
+            }
+            Message::DumpDirChanged(dir) => {
+                if let Some(dialog) = &mut self.dump_dialog {
+                    dialog.dir = dir;
+                }
+                Command::none()
+            }
+            // the dump can take a while for a big process, it is written off the ui thread
+            Message::ConfirmDump => {
+                let Some(dialog) = self.dump_dialog.take() else {
+                    return Command::none();
+                };
+                if let Err(refusal) = self.check_target(&dialog.target) {
+                    self.report_error(refusal.message("dump and kill", &dialog.target));
+                    return Command::none();
+                }
+                let dir = std::path::PathBuf::from(dialog.dir.trim());
+                self.config.dump_dir = dir.clone();
+                let label = dialog.target.label();
+                self.status_message = Some(format!("dumping {}…", label));
+                Command::perform(async move { dump::dump_and_kill(&dialog.target, dir) }, move |result| {
+                    Message::DumpDone(label.clone(), result)
+                })
+            }
+            Message::CloseDump => {
+                self.dump_dialog = None;
+                Command::none()
+            }
+            Message::DumpDone(label, result) => {
+                self.status_message = Some(match result {
+                    Ok(Dumped::File(path)) => format!("dumped {} to {} and killed it", label, path.display()),
+                    Ok(Dumped::Aborted) => format!("gcore isn't installed, sent SIGABRT to {} so the kernel dumps its core", label),
+                    Err(err) => err,
+                });
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                Command::none()
+            }
+            Message::CancelScheduled(id) => {
+                self.scheduled.retain(|action| action.id != id);
+                Command::none()
+            }
+            Message::RunDueActions => self.run_due_actions(),
+            Message::WatchProcess(pid) => {
+                let entry = self.system.process(Pid::from(pid as usize)).and_then(WatchEntry::capture);
+                self.status_message = Some(match entry {
+                    Some(entry) => {
+                        let message = format!("watching {}, it is started again whenever it exits", entry.name);
+                        self.config.watched.push(entry);
+                        message
+                    }
+                    None => format!("can't read the command line of process {}", pid),
+                });
+                Command::none()
+            }
+            Message::Unwatch(index) => {
+                if index < self.config.watched.len() {
+                    self.config.watched.remove(index);
+                }
+                Command::none()
+            }
+            Message::RestartDone(result) => {
+                self.status_message = Some(match result {
+                    Ok(pid) => format!("restarted as process {}", pid),
+                    Err(err) => err,
+                });
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                Command::none()
+            }
+            Message::FocusSearch => {
+                self.tab = Tab::Processes;
+                text_input::focus(search_input_id())
+            }
+            Message::RefreshNow => {
+                let selected = self.selected_index();
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                self.keep_scroll_position(selected)
+            }
+            Message::SortByIndex(index) => {
+                if let Some(&column) = self.config.layout.columns(&self.config.columns).get(index) {
+                    self.sort_by(column);
+                }
+                Command::none()
+            }
+            Message::ToggleExecutableExpanded(executable) => {
+                if !self.expanded_executables.remove(&executable) {
+                    self.expanded_executables.insert(executable);
+                }
+                Command::none()
+            }
+            Message::ToggleUserExpanded(user) => {
+                if !self.expanded_users.remove(&user) {
+                    self.expanded_users.insert(user);
+                }
+                Command::none()
+            }
+            Message::ConfirmKillUserProcesses(user) => {
+                self.confirm_kill_user = Some(user);
+                Command::none()
+            }
+            Message::KillUserProcesses(user) => {
+                // the session's critical processes belong to a user too
+                let targets: Vec<kill_guard::Target> = self
+                    .processes
+                    .iter()
+                    .filter(|process| process.user == user && !critical::is_critical(process, &self.config.critical))
+                    .map(kill_guard::Target::of)
+                    .collect();
+                for target in targets {
+                    self.kill_logged(&target, "You");
+                }
+                self.confirm_kill_user = None;
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                Command::none()
+            }
+            // logind may ask for authentication, so run it off the ui thread
+            Message::SignOutUser(user) => Command::perform(
+                async move { users::sign_out(&user) },
+                Message::SignOutDone,
+            ),
+            Message::SignOutDone(result) => {
+                self.status_message = result.err();
+                Command::none()
+            }
+            // renices the process and updates the row right away instead of waiting for the next tick
+            Message::AdjustPriority(pid, delta) => {
+                if let Some(process) = self.processes.iter_mut().find(|process| process.pid == pid) {
+                    if let Some(nice) = process.nice {
+                        let target = (nice + delta).clamp(priority::MIN_NICE, priority::MAX_NICE);
+                        match priority::set_nice(pid, target) {
+                            Ok(()) => {
+                                process.nice = Some(target);
+                                self.status_message = None;
+                            }
+                            Err(err) => self.report_error(err),
+                        }
+                    }
+                }
+                if self.sort_keys().iter().any(|&(column, _)| column == SortColumn::Priority) {
+                    self.sort_processes();
+                }
+                Command::none()
+            }
+            Message::OpenRunDialog => {
+                self.run_dialog = Some(RunDialog::default());
+                Command::none()
+            }
+            Message::CloseRunDialog => {
+                self.run_dialog = None;
+                Command::none()
+            }
+            Message::RunCommandChanged(command) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.command = command;
+                }
+                Command::none()
+            }
+            Message::RunDirChanged(dir) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.working_dir = dir;
+                }
+                Command::none()
+            }
+            Message::RunGroupNameChanged(name) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.group_name = name;
+                }
+                Command::none()
+            }
+            Message::RunNiceChanged(nice) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.nice = nice;
+                }
+                Command::none()
+            }
+            Message::RunAffinityChanged(affinity) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.affinity = affinity;
+                }
+                Command::none()
+            }
+            Message::RunEnvironmentChanged(environment) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.environment = environment;
+                }
+                Command::none()
+            }
+            Message::ToggleRunElevated(elevated) => {
+                if let Some(dialog) = &mut self.run_dialog {
+                    dialog.elevated = elevated;
+                }
+                Command::none()
+            }
+            // the dialog stays open on failure so the command can be fixed
+            Message::RunTask => {
+                if let Some(dialog) = &self.run_dialog {
+                    match run::spawn(dialog) {
+                        Ok(pid) => {
+                            self.status_message = Some(format!("started process {}", pid));
+                            let name = Some(dialog.group_name.trim()).filter(|name| !name.is_empty()).unwrap_or(dialog.command.trim());
+                            self.jobs.push(Job::new(name.to_string(), pid, Instant::now()));
+                            self.run_dialog = None;
+                            self.refresh_processes();
+                            self.scheduler.mark(Source::Processes, Instant::now());
+                        }
+                        Err(err) => self.report_error(err),
+                    }
+                }
+                Command::none()
+            }
+            Message::OpenKillPattern => {
+                self.kill_pattern = Some(kill_pattern::KillPatternDialog::default());
+                Command::none()
+            }
+            Message::CloseKillPattern => {
+                self.kill_pattern = None;
+                Command::none()
+            }
+            Message::KillPatternChanged(pattern) => {
+                if let Some(dialog) = &mut self.kill_pattern {
+                    dialog.pattern = pattern;
+                }
+                Command::none()
+            }
+            Message::SetKillPatternSyntax(syntax) => {
+                if let Some(dialog) = &mut self.kill_pattern {
+                    dialog.syntax = syntax;
+                }
+                Command::none()
+            }
+            Message::ToggleKillPatternCommandLine(command_line) => {
+                if let Some(dialog) = &mut self.kill_pattern {
+                    dialog.command_line = command_line;
+                }
+                Command::none()
+            }
+            // kills what the preview shows right now, the dialog stays open on a bad pattern
+            Message::KillByPattern => {
+                let Some(dialog) = self.kill_pattern.clone() else {
+                    return Command::none();
+                };
+                match kill_pattern::matches(&dialog, &self.processes, &self.config.critical).map(|matches| matches.targets()) {
+                    Ok(targets) => {
+                        let killed = targets.iter().filter(|target| self.kill_logged(target, "You")).count();
+                        self.status_message = Some(format!("killed {} of {} processes matching {}", killed, targets.len(), dialog.pattern.trim()));
+                        self.kill_pattern = None;
+                        self.refresh_processes();
+                        self.scheduler.mark(Source::Processes, Instant::now());
+                    }
+                    Err(err) => self.report_error(err),
+                }
+                Command::none()
+            }
+            Message::ToggleColumnPicker => {
+                self.column_picker_open = !self.column_picker_open;
+                self.column_search.clear();
+                Command::none()
+            }
+            Message::ColumnSearchChanged(search) => {
+                self.column_search = search;
+                Command::none()
+            }
+            Message::SetColumnWidth(column, width) => {
+                self.config.column_widths.set(column, width);
+                Command::none()
+            }
+            Message::SetColumnFormat(column, format) => {
+                self.config.column_formats.set(column, format);
+                Command::none()
+            }
+            Message::DragColumn(column) => {
+                self.dragged_column = Some(column);
+                Command::none()
+            }
+            Message::DropColumn(target) => {
+                if let Some(dragged) = self.dragged_column.take() {
+                    columns::move_column(&mut self.config.columns, dragged, target);
+                }
+                Command::none()
+            }
+            Message::EndColumnDrag => {
+                self.dragged_column = None;
+                Command::none()
+            }
+            // shown columns go at the end, where they can be dragged from, and the last one is
+            // never hidden
+            Message::ToggleColumn(column, shown) => {
+                if shown {
+                    self.config.columns.push(column);
+                    plugins::fill(&mut self.processes, &self.config.columns);
+                } else if self.config.columns.len() > 1 {
+                    self.config.columns.retain(|&c| c != column);
+                }
+                Command::none()
+            }
+            // switches the visible tab
+            Message::SelectTab(tab) => {
+                self.switch_selection(tab);
+                self.tab = tab;
+                self.config.tab = tab;
+                // sources gated on their tab are refreshed right away instead of on the next due tick
+                match tab {
+                    Tab::Disks => {
+                        self.refresh_disks();
+                        self.scheduler.mark(Source::Disks, Instant::now());
+                    }
+                    Tab::Services => {
+                        self.refresh_services();
+                        self.scheduler.mark(Source::Services, Instant::now());
+                    }
+                    Tab::Connections => self.refresh_connections(),
+                    Tab::Security => self.refresh_security(),
+                    Tab::Network => self.refresh_network(),
+                    Tab::Sensors => self.refresh_sensors(),
+                    Tab::Performance => self.gpu_devices = self.gpu.devices(),
+                    // accounts rarely change, re-read them when the tab is opened
+                    Tab::Users => self.users.refresh_list(),
+                    Tab::History => self.refresh_recorded(),
+                    _ => {}
+                }
+                Command::none()
+            }
+            // sorts the network tab by the given column
+            Message::SortNetwork(column) => {
+                if self.network_sort_column == column {
+                    self.network_sort_ascending = !self.network_sort_ascending;
+                } else {
+                    self.network_sort_column = column;
+                    self.network_sort_ascending = true;
+                }
+                network::sort(&mut self.network_rows, self.network_sort_column, self.network_sort_ascending);
+                Command::none()
+            }
+            Message::SortConnections(column) => {
+                if self.connection_sort_column == column {
+                    self.connection_sort_ascending = !self.connection_sort_ascending;
+                } else {
+                    self.connection_sort_column = column;
+                    self.connection_sort_ascending = true;
+                }
+                if let Ok(rows) = &mut self.connection_rows {
+                    connections::sort(rows, self.connection_sort_column, self.connection_sort_ascending);
+                }
+                Command::none()
+            }
+            Message::SocketsRead(sockets) => {
+                self.reading_sockets = false;
+                self.connection_rows = sockets.map(|sockets| {
+                    let mut rows = connections::collect(sockets, &self.processes);
+                    connections::sort(&mut rows, self.connection_sort_column, self.connection_sort_ascending);
+                    rows
+                });
+                Command::none()
+            }
+            // service managers may ask for authentication, so run the action off the ui thread
+            Message::ServiceAction(name, action) => Command::perform(
+                async move { services::perform(&name, action) },
+                Message::ServiceActionDone,
+            ),
+            Message::ServiceActionDone(result) => {
+                self.status_message = result.err();
+                self.refresh_services();
+                Command::none()
+            }
+            Message::SetUiScale(scale) => {
+                self.config.ui_scale = scale;
+                self.fit_rows();
+                Command::none()
+            }
+            Message::ForgetDisplayGeometry(display) => {
+                self.config.window_geometry.remove(&display);
+                Command::none()
+            }
+            // when the window lands on another display, restore the size last used there
+            Message::WindowMoved(x, y) => {
+                // the mini window's position is kept apart
+                if let Some(Compact::Mini) = self.compact {
+                    self.config.mini_position = Some((x, y));
+                    return Command::none();
+                }
+                // a maximized window keeps the geometry it is restored to
+                if self.config.maximized {
+                    return Command::none();
+                }
+                self.geometry.x = x;
+                self.geometry.y = y;
+                let display = self.config.display_name_at(x, y);
+                let command = if display != self.current_display {
+                    self.current_display = display;
+                    match self.config.window_geometry.get(&self.current_display) {
+                        Some(saved) => {
+                            self.geometry.width = saved.width;
+                            self.geometry.height = saved.height;
+                            window::resize(window::Id::MAIN, Size::new(saved.width as f32, saved.height as f32))
+                        }
+                        None => Command::none(),
+                    }
+                } else {
+                    Command::none()
+                };
+                self.config.window_geometry.insert(self.current_display.clone(), self.geometry);
+                command
+            }
+            Message::WindowResized(width, height) => {
+                if self.compact.is_some() {
+                    return Command::none();
+                }
+                self.window_width = width;
+                self.fit_rows();
+                self.pending_size = Some((width, height));
+                // resizes are also how maximizing shows up, so ask which one this was
+                window::fetch_maximized(window::Id::MAIN, Message::MaximizedFetched)
+            }
+            Message::MaximizedFetched(maximized) => {
+                self.config.maximized = maximized;
+                if let Some((width, height)) = self.pending_size.take().filter(|_| !maximized) {
+                    self.geometry.width = width;
+                    self.geometry.height = height;
+                    self.config.window_geometry.insert(self.current_display.clone(), self.geometry);
+                }
+                Command::none()
+            }
+            Message::WindowFocused(focused) => {
+                let idle = self.is_idle();
+                self.focused = focused;
+                if focused {
+                    self.focused_detached = None;
+                    self.minimized = false;
+                    // the tables are as old as the time spent in the background
+                    if idle {
+                        self.refresh_processes();
+                        self.scheduler.mark(Source::Processes, Instant::now());
+                    }
+                    Command::none()
+                } else {
+                    // there is no minimize event, but minimizing takes the focus away
+                    window::fetch_minimized(window::Id::MAIN, Message::WindowMinimized)
+                }
+            }
+            Message::WindowMinimized(minimized) => {
+                self.minimized = minimized.unwrap_or(false);
+                Command::none()
+            }
+            // saves the config before closing the window
+            Message::WindowCloseRequested => {
+                if self.config.close_to_tray && self.tray.is_active() {
+                    self.set_window_hidden(true)
+                } else {
+                    self.quit()
+                }
+            }
+            Message::Tray(TrayEvent::ToggleWindow) => self.set_window_hidden(!self.window_hidden),
+            Message::Tray(TrayEvent::OpenLink(index)) => match self.config.tray_links.get(index).map(|link| Link::parse(link)) {
+                Some(Ok(link)) => self.open_link(&link),
+                Some(Err(err)) => {
+                    self.report_error(format!("can't open the view: {}", err));
+                    self.set_window_hidden(false)
+                }
+                None => Command::none(),
+            },
+            Message::Tray(TrayEvent::Quit) => self.quit(),
+            Message::TrayLinkInputChanged(text) => {
+                self.tray_link_input = text;
+                Command::none()
+            }
+            Message::AddTrayLink => {
+                match Link::parse(&self.tray_link_input) {
+                    Ok(link) => {
+                        self.tray_link_input.clear();
+                        self.add_tray_link(link);
+                    }
+                    Err(err) => self.status_message = Some(format!("not a view link: {}", err)),
+                }
+                Command::none()
+            }
+            Message::AddProcessesView => {
+                self.add_tray_link(self.link_for(Tab::Processes));
+                Command::none()
+            }
+            Message::RemoveTrayLink(index) => {
+                if index < self.config.tray_links.len() {
+                    self.config.tray_links.remove(index);
+                    self.tray.set_links(self.config.tray_links.clone());
+                }
+                Command::none()
+            }
+            Message::ToggleTrayIcon(enabled) => {
+                self.config.tray_icon = enabled;
+                if enabled {
+                    self.start_tray();
+                } else {
+                    self.tray = Tray::inactive();
+                }
+                Command::none()
+            }
+            Message::ToggleCloseToTray(enabled) => {
+                self.config.close_to_tray = enabled;
+                Command::none()
+            }
+            Message::ToggleStartMinimized(enabled) => {
+                self.config.start_minimized = enabled;
+                Command::none()
+            }
+            Message::ToggleAutostart(enabled) => {
+                self.set_autostart(enabled.then_some(Autostart { minimized: false }));
+                Command::none()
+            }
+            Message::ToggleAutostartMinimized(minimized) => {
+                self.set_autostart(Some(Autostart { minimized }));
+                Command::none()
+            }
+            Message::SetCompact(compact) => self.set_compact(compact),
+            // un-minimizes and focuses the window
+            Message::AnotherInstanceLaunched(None) => self.set_window_hidden(false),
+            Message::AnotherInstanceLaunched(Some(link)) => self.open_link(&link),
+            Message::HotkeyPressed => self.set_window_hidden(false),
+            Message::HotkeyInputChanged(input) => {
+                self.hotkey_input = input;
+                Command::none()
+            }
+            // only keeps the new shortcut in the config if it could be registered
+            Message::ApplyHotkey => {
+                match self.hotkey.set(&self.hotkey_input) {
+                    Ok(()) => {
+                        self.config.hotkey = self.hotkey_input.clone();
+                        self.hotkey_status = Some(String::from("registered"));
+                    }
+                    Err(err) => self.hotkey_status = Some(err),
+                }
+                Command::none()
+            }
+            Message::ToggleScript(name, enabled) => {
+                self.config.scripts.retain(|script| *script != name);
+                if enabled {
+                    self.config.scripts.push(name);
+                }
+                Command::none()
+            }
+            Message::ReloadScripts => {
+                self.scripts.load();
+                Command::none()
+            }
+            Message::SelectHost(index) => {
+                if let Err(err) = index.map_or(Ok(()), |_| online::ensure_allowed(&self.config, OnlineFeature::RemoteHosts)) {
+                    self.report_error(err);
+                    return Command::none();
+                }
+                self.host = index.filter(|&index| index < self.config.hosts.len());
+                self.provider = match self.host {
+                    Some(index) => {
+                        let interval = Duration::from_secs(self.config.refresh_intervals.processes.max(1));
+                        Box::new(RemoteProvider::connect(self.config.hosts[index].clone(), interval))
+                    }
+                    None => Box::new(SystemProvider),
+                };
+                // pids of different machines have nothing in common
+                self.history = History::default();
+                self.cpu_history.clear();
+                self.deferred_pids.clear();
+                self.rule_hits.clear();
+                self.selected_pid = None;
+                self.kill_preview = None;
+                self.schedule_dialog = None;
+                self.limit_dialog = None;
+                self.properties = None;
+                #[cfg(unix)]
+                {
+                    self.signal_dialog = None;
+                }
+                self.dump_dialog = None;
+                self.playback = None;
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                Command::none()
+            }
+            Message::HostInputChanged(text) => {
+                self.host_input = text;
+                Command::none()
+            }
+            Message::AddHost if self.host_input.trim().is_empty() => Command::none(),
+            Message::AddHost => {
+                let host = RemoteHost::new(&std::mem::take(&mut self.host_input));
+                let index = match self.config.hosts.iter().position(|known| known.addr == host.addr) {
+                    Some(index) => index,
+                    None => {
+                        self.config.hosts.push(host);
+                        self.config.hosts.len() - 1
+                    }
+                };
+                self.update(Message::SelectHost(Some(index)))
+            }
+            Message::RemoveHost(index) => {
+                if index < self.config.hosts.len() {
+                    self.config.hosts.remove(index);
+                }
+                self.update(Message::SelectHost(None))
+            }
+            Message::SetTheme(choice) => {
+                self.config.theme = choice;
+                self.config.custom_theme = None;
+                self.update_theme();
+                Command::none()
+            }
+            Message::ToggleSystemAccent(enabled) => {
+                self.config.use_system_accent = enabled;
+                self.update_theme();
+                Command::none()
+            }
+            Message::SetCustomTheme(name) => {
+                self.config.custom_theme = Some(name);
+                self.update_theme();
+                Command::none()
+            }
+            Message::ReloadThemes => {
+                self.themes = themes::load_all();
+                self.update_theme();
+                Command::none()
+            }
+            Message::SetTextSize(size) => {
+                self.config.text_size = size.clamp(style::MIN_TEXT_SIZE, style::MAX_TEXT_SIZE);
+                Command::none()
+            }
+            // a tab the mode doesn't list is left for the processes tab
+            Message::SetLayout(layout) => {
+                self.config.layout = layout;
+                self.ui.density = layout.density(self.config.density);
+                self.fit_rows();
+                self.column_picker_open = false;
+                if !layout.tabs().contains(&self.tab) {
+                    return self.update(Message::SelectTab(Tab::Processes));
+                }
+                Command::none()
+            }
+            Message::SetNumberStyle(style) => {
+                self.config.number_style = style;
+                format::set_locale(style, self.config.clock);
+                Command::none()
+            }
+            Message::SetClock(clock) => {
+                self.config.clock = clock;
+                format::set_locale(self.config.number_style, clock);
+                Command::none()
+            }
+            Message::SetDensity(density) => {
+                self.config.density = density;
+                self.ui.density = self.config.layout.density(density);
+                self.fit_rows();
+                Command::none()
+            }
+            // a remote host shown until now goes back to this computer
+            Message::ToggleNetworkAccess(allowed) => {
+                self.config.allow_network_access = allowed;
+                if !allowed && self.host.is_some() {
+                    return self.update(Message::SelectHost(None));
+                }
+                Command::none()
+            }
+            Message::SnapshotNameChanged(name) => {
+                self.snapshot_name_input = name;
+                Command::none()
+            }
+            // a snapshot with the same name is replaced
+            Message::TakeSnapshot => {
+                let snapshot = Snapshot::take(&self.snapshot_name_input, &self.processes);
+                if snapshot.name.is_empty() {
+                    return Command::none();
+                }
+                self.status_message = Some(match snapshot::save(&snapshot) {
+                    Ok(()) => format!("saved snapshot {} with {} processes", snapshot.name, snapshot.processes.len()),
+                    Err(err) => format!("failed to save snapshot: {}", err),
+                });
+                self.snapshots.retain(|existing| existing.name != snapshot.name);
+                self.snapshot_base.get_or_insert_with(|| snapshot.name.clone());
+                self.snapshots.push(snapshot);
+                self.snapshot_name_input.clear();
+                Command::none()
+            }
+            Message::SelectSnapshotBase(name) => {
+                self.snapshot_base = Some(name);
+                Command::none()
+            }
+            Message::SelectSnapshotTarget(name) => {
+                self.snapshot_target = name;
+                Command::none()
+            }
+            Message::DeleteSnapshot(name) => {
+                if let Err(err) = snapshot::delete(&name) {
+                    self.report_error(format!("failed to delete snapshot: {}", err));
+                }
+                self.snapshots.retain(|snapshot| snapshot.name != name);
+                if self.snapshot_base.as_ref() == Some(&name) {
+                    self.snapshot_base = None;
+                }
+                if self.snapshot_target.as_ref() == Some(&name) {
+                    self.snapshot_target = None;
+                }
+                Command::none()
+            }
+            Message::StartRecording => {
+                match Recording::start() {
+                    Ok(recording) => {
+                        self.status_message = Some(format!("recording the process table to session {}", recording.name));
+                        self.recording = Some(recording);
+                    }
+                    Err(err) => self.report_error(format!("failed to start recording: {}", err)),
+                }
+                Command::none()
+            }
+            Message::StopRecording => {
+                if let Some(recording) = self.recording.take() {
+                    self.status_message = Some(format!("recorded {} frames to session {}", recording.frames, recording.name));
+                    self.picked_recording = Some(recording.name);
+                }
+                self.recordings = session::list();
+                Command::none()
+            }
+            Message::PickRecording(name) => {
+                self.picked_recording = Some(name);
+                Command::none()
+            }
+            Message::StartPlayback => {
+                let Some(name) = &self.picked_recording else {
+                    return Command::none();
+                };
+                match Playback::load(name) {
+                    Ok(playback) => {
+                        self.playback = Some(playback);
+                        self.selected_pid = None;
+                        self.kill_preview = None;
+                        self.properties = None;
+                        self.show_playback_frame();
+                    }
+                    Err(err) => self.report_error(err),
+                }
+                Command::none()
+            }
+            Message::TogglePlaying => {
+                if let Some(playback) = &mut self.playback {
+                    playback.playing = !playback.playing;
+                }
+                Command::none()
+            }
+            Message::SeekPlayback(position) => {
+                if let Some(playback) = &mut self.playback {
+                    playback.seek(position as usize);
+                }
+                self.show_playback_frame();
+                Command::none()
+            }
+            Message::StopPlayback => {
+                self.playback = None;
+                self.selected_pid = None;
+                self.refresh_processes();
+                self.scheduler.mark(Source::Processes, Instant::now());
+                Command::none()
+            }
+            Message::ToggleRecorder(enabled) => {
+                self.config.recorder.enabled = enabled;
+                if enabled {
+                    self.start_recorder();
+                } else {
+                    self.recorder = None;
+                }
+                Command::none()
+            }
+            // the open database picks the new retention up on its next prune
+            Message::SetRetentionDays(days) => {
+                self.config.recorder.retention_days = days.max(1);
+                if self.recorder.is_some() {
+                    self.start_recorder();
+                }
+                Command::none()
+            }
+            Message::SelectHistoryWindow(window) => {
+                self.history_window = window;
+                self.refresh_recorded();
+                Command::none()
+            }
+            Message::SelectHistoryRank(rank) => {
+                self.history_rank = rank;
+                self.refresh_recorded();
+                Command::none()
+            }
+            Message::ShowProcess(pid) => {
+                self.tab = Tab::Processes;
+                self.update(Message::JumpToProcess(pid))
+            }
+            Message::HistoryExportPathChanged(path) => {
+                self.history_export_input = path;
+                Command::none()
+            }
+            Message::ExportHistory => {
+                let path = std::path::PathBuf::from(&self.history_export_input);
+                self.status_message = self.recorder.as_ref().map(|recorder| {
+                    match recorder.export_csv(self.history_window, &path) {
+                        Ok(count) => format!("exported {} samples to {}", count, path.display()),
+                        Err(err) => format!("failed to export samples: {}", err),
+                    }
+                });
+                Command::none()
+            }
+            Message::NewRule => {
+                self.rule_draft = Some(RuleDraft::default());
+                Command::none()
+            }
+            Message::EditRule(index) => {
+                self.rule_draft = self.config.rules.get(index).map(|rule| RuleDraft::edit(index, rule));
+                Command::none()
+            }
+            // indexes shift, so an open editor is closed
+            Message::DeleteRule(index) => {
+                if index < self.config.rules.len() {
+                    self.config.rules.remove(index);
+                }
+                self.rule_draft = None;
+                Command::none()
+            }
+            Message::ToggleRule(index, enabled) => {
+                if let Some(rule) = self.config.rules.get_mut(index) {
+                    rule.enabled = enabled;
+                }
+                Command::none()
+            }
+            Message::RuleDraftChanged(field, value) => {
+                if let Some(draft) = &mut self.rule_draft {
+                    draft.set(field, value);
+                }
+                Command::none()
+            }
+            Message::SetRuleDraftAction(action) => {
+                if let Some(draft) = &mut self.rule_draft {
+                    draft.action = action;
+                }
+                Command::none()
+            }
+            // a saved rule acts again on processes it already matched, its condition may have changed
+            Message::SaveRule => {
+                let Some(draft) = &self.rule_draft else {
+                    return Command::none();
+                };
+                let existing = draft.index.filter(|&index| index < self.config.rules.len());
+                let enabled = existing.is_none_or(|index| self.config.rules[index].enabled);
+                match draft.to_rule(enabled) {
+                    Ok(rule) => {
+                        self.rule_hits.retain(|(name, _)| *name != rule.name);
+                        match existing {
+                            Some(index) => self.config.rules[index] = rule,
+                            None => self.config.rules.push(rule),
+                        }
+                        self.rule_draft = None;
+                    }
+                    Err(err) => self.report_error(err),
+                }
+                Command::none()
+            }
+            Message::CancelRule => {
+                self.rule_draft = None;
+                Command::none()
+            }
+            Message::RulesPathChanged(path) => {
+                self.rules_path_input = path;
+                Command::none()
+            }
+            Message::ExportRules => {
+                let path = std::path::PathBuf::from(&self.rules_path_input);
+                self.status_message = Some(match rules::export(&self.config.rules, &path) {
+                    Ok(()) => format!("exported {} rules to {}", self.config.rules.len(), path.display()),
+                    Err(err) => format!("failed to export rules: {}", err),
+                });
+                Command::none()
+            }
+            // an import replaces the current rules entirely
+            Message::ImportRules => {
+                let path = std::path::PathBuf::from(&self.rules_path_input);
+                self.status_message = Some(match rules::import(&path) {
+                    Ok(imported) => {
+                        self.config.rules = imported;
+                        format!("imported {} rules from {}", self.config.rules.len(), path.display())
+                    }
+                    Err(err) => format!("failed to import rules: {}", err),
+                });
+                Command::none()
+            }
+            Message::SettingsPathChanged(path) => {
+                self.settings_path_input = path;
+                Command::none()
+            }
+            Message::ExportSettings => {
+                let path = std::path::PathBuf::from(&self.settings_path_input);
+                match self.config.export(&path) {
+                    Ok(themes) => self.status_message = Some(format!("exported the settings and {} themes to {}", themes, path.display())),
+                    Err(err) => self.report_error(format!("failed to export the settings: {}", err)),
+                }
+                Command::none()
+            }
+            // replaces everything but this machine's displays, the parts read only at startup
+            // (text size, hotkey, tray icon) apply on the next start
+            Message::ImportSettings => {
+                let path = std::path::PathBuf::from(&self.settings_path_input);
+                match Config::import(&path) {
+                    Ok(mut imported) => {
+                        imported.keep_local(&self.config);
+                        self.config = imported;
+                        self.sort_column = self.config.sort.first().map_or(SortColumn::Pid, |&(column, _)| column);
+                        self.sort_ascending = self.config.sort.first().is_none_or(|&(_, ascending)| ascending);
+                        self.sort_then = self.config.sort.iter().skip(1).copied().collect();
+                        self.ui.density = self.config.layout.density(self.config.density);
+                        self.fit_rows();
+                        self.themes = themes::load_all();
+                        self.update_theme();
+                        format::set_locale(self.config.number_style, self.config.clock);
+                        self.refresh_processes();
+                        self.status_message = Some(format!("imported the settings from {}, some apply after a restart", path.display()));
+                    }
+                    Err(err) => self.report_error(format!("failed to import the settings: {}", err)),
+                }
+                Command::none()
+            }
+        }
+    }
+}
+
+// id of the process list so keyboard selection can scroll it
+fn process_list_id() -> scrollable::Id {
+    scrollable::Id::new("process-list")
+}
+
+// id of the process search box so shortcuts can focus it
+fn search_input_id() -> text_input::Id {
+    text_input::Id::new("process-search")
+}
+
+// second click on the same row within this long opens its properties
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+// window geometry used when nothing was saved for the display yet
+const DEFAULT_GEOMETRY: WindowGeometry = WindowGeometry { x: 100, y: 100, width: 1024, height: 768 };
+
+// entry point of the application
+fn main() -> iced::Result {
+    crash::install();
+    let cli = Cli::parse();
+    let config = Config::load();
+    // held until the app exits, dropping it flushes the log
+    let _log = logging::init(cli.log_level).unwrap_or_else(|err| {
+        eprintln!("can't write the log: {}", err);
+        None
+    });
+    format::set_locale(config.number_style, config.clock);
+    if let Some(iterations) = cli.bench {
+        match serde_json::to_string_pretty(&bench::run(iterations, &config)) {
+            Ok(report) => println!("{}", report),
+            Err(err) => eprintln!("{}", err),
+        }
+        return Ok(());
+    }
+    #[cfg(feature = "server")]
+    let shared = match &cli.serve {
+        Some(addr) => {
+            let shared = Shared::default();
+            // the frontend keeps it current from here on
+            shared.set_policy(cli.read_only || config.read_only, &config.critical);
+            let options = server::Options { token: cli.token.clone(), metrics: cli.metrics };
+            if let Err(err) = server::spawn(addr, shared.clone(), options) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            if cli.headless {
+                let mut config = config;
+                cli.apply(&mut config);
+                server::run_headless(shared, &config);
+            }
+            Some(shared)
+        }
+        None => None,
+    };
+    #[cfg(not(feature = "server"))]
+    let shared = None;
+    if cli.stop_daemon {
+        if !daemon::stop() {
+            eprintln!("the background monitor isn't running");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if cli.daemon {
+        let mut config = config;
+        cli.apply(&mut config);
+        if let Err(err) = daemon::run(&config) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if cli.tui {
+        let mut config = config;
+        cli.apply(&mut config);
+        if let Err(err) = tui::run(config, &cli, shared) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // a second launch only brings the running window to the front
+    if !cli.new_instance && instance::focus_or_claim(cli.open.as_ref()) {
+        return Ok(());
+    }
+    // reopen on the display and with the geometry used last time
+    let geometry = config.window_geometry.get(&config.last_display).copied();
+    let ui = Ui::new(config.text_size, config.layout.density(config.density));
+    let mut settings = Settings::with_flags((config, cli, shared));
+    settings.default_text_size = iced::Pixels(ui.text_size);
+    settings.window.exit_on_close_request = false;
+    if let Some(geometry) = geometry {
+        settings.window.size = Size::new(geometry.width as f32, geometry.height as f32);
+        settings.window.position = window::Position::Specific(iced::Point::new(geometry.x as f32, geometry.y as f32));
+    }
+    TaskManager::run(settings)
 }
\ No newline at end of file
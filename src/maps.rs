@@ -0,0 +1,146 @@
+// memory map section of the details pane - the regions of /proc/<pid>/maps with their size,
+// permissions and what backs them (a library, the heap, the stack or nothing), and the same
+// summed up per backing file, for finding out what a bloated process has mapped. linux only
+use iced::{
+    widget::{Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use std::collections::HashMap;
+
+use crate::format::format_bytes;
+use crate::Message;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub start: u64,
+    pub end: u64,
+    // "r-xp" and the like
+    pub permissions: String,
+    // the file, or a pseudo path like [heap], [stack] and [vdso], empty for anonymous memory
+    pub path: String,
+}
+
+impl Region {
+    pub fn size(&self) -> u64 {
+        self.end - self.start
+    }
+
+    pub fn backing(&self) -> &str {
+        if self.path.is_empty() {
+            "[anonymous]"
+        } else {
+            &self.path
+        }
+    }
+}
+
+// what backs a number of regions, with their total size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backing<'a> {
+    pub name: &'a str,
+    pub regions: usize,
+    pub size: u64,
+}
+
+pub fn for_pid(pid: u32) -> Result<Vec<Region>, String> {
+    if !cfg!(target_os = "linux") {
+        return Err(String::from("memory maps are only supported on Linux"));
+    }
+    let path = format!("/proc/{}/maps", pid);
+    let maps = std::fs::read_to_string(&path).map_err(|err| format!("{}: {}", path, err))?;
+    Ok(maps.lines().filter_map(parse_line).collect())
+}
+
+// "7f0c1a2b3000-7f0c1a2d5000 r-xp 00000000 08:01 1234  /usr/lib/libc.so.6", the path being
+// everything after the inode as it may contain spaces
+fn parse_line(line: &str) -> Option<Region> {
+    let mut fields = line.splitn(6, ' ');
+    let (start, end) = fields.next()?.split_once('-')?;
+    let permissions = fields.next()?.to_string();
+    let path = fields.nth(3).unwrap_or_default().trim_start().to_string();
+    Some(Region { start: u64::from_str_radix(start, 16).ok()?, end: u64::from_str_radix(end, 16).ok()?, permissions, path })
+}
+
+// the regions summed per backing, the largest first
+pub fn by_backing(regions: &[Region]) -> Vec<Backing<'_>> {
+    let mut backings: HashMap<&str, Backing> = HashMap::new();
+    for region in regions {
+        let backing = backings.entry(region.backing()).or_insert(Backing { name: region.backing(), regions: 0, size: 0 });
+        backing.regions += 1;
+        backing.size += region.size();
+    }
+    let mut backings: Vec<Backing> = backings.into_values().collect();
+    backings.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(b.name)));
+    backings
+}
+
+// the totals per backing, then every region in address order
+pub fn view(regions: &Result<Vec<Region>, String>) -> Element<'_, Message> {
+    let regions = match regions {
+        Ok(regions) => regions,
+        Err(err) => return Text::new(format!("Memory map unavailable: {}", err)).into(),
+    };
+    let total: u64 = regions.iter().map(Region::size).sum();
+    let mut rows = Column::new()
+        .spacing(5)
+        .push(Text::new(format!("{} regions, {} mapped", regions.len(), format_bytes(total as f64))))
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new("Backing").width(Length::FillPortion(4)))
+                .push(Text::new("Regions").width(Length::FillPortion(1)))
+                .push(Text::new("Size").width(Length::FillPortion(1))),
+        );
+    for backing in by_backing(regions) {
+        rows = rows.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(backing.name).width(Length::FillPortion(4)))
+                .push(Text::new(backing.regions.to_string()).width(Length::FillPortion(1)))
+                .push(Text::new(format_bytes(backing.size as f64)).width(Length::FillPortion(1))),
+        );
+    }
+    rows = rows.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new("Address").width(Length::FillPortion(2)))
+            .push(Text::new("Size").width(Length::FillPortion(1)))
+            .push(Text::new("Permissions").width(Length::FillPortion(1)))
+            .push(Text::new("Backing").width(Length::FillPortion(4))),
+    );
+    for region in regions {
+        rows = rows.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(format!("{:x}-{:x}", region.start, region.end)).width(Length::FillPortion(2)))
+                .push(Text::new(format_bytes(region.size() as f64)).width(Length::FillPortion(1)))
+                .push(Text::new(&region.permissions).width(Length::FillPortion(1)))
+                .push(Text::new(region.backing()).width(Length::FillPortion(4))),
+        );
+    }
+    Scrollable::new(rows).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_regions_and_sums_them_per_file() {
+        let maps = "\
+55d0a0000000-55d0a0002000 r--p 00000000 08:01 42                         /usr/bin/my app
+55d0a0002000-55d0a0005000 r-xp 00002000 08:01 42                         /usr/bin/my app
+55d0a1000000-55d0a1100000 rw-p 00000000 00:00 0                          [heap]
+7f0000000000-7f0000400000 rw-p 00000000 00:00 0
+7ffd00000000-7ffd00021000 rw-p 00000000 00:00 0                          [stack]
+";
+        let regions: Vec<Region> = maps.lines().filter_map(parse_line).collect();
+        assert_eq!(regions.len(), 5);
+        assert_eq!((regions[1].permissions.as_str(), regions[1].path.as_str(), regions[1].size()), ("r-xp", "/usr/bin/my app", 0x3000));
+        assert_eq!(regions[3].backing(), "[anonymous]");
+        let backings = by_backing(&regions);
+        assert_eq!(backings[0], Backing { name: "[anonymous]", regions: 1, size: 0x400000 });
+        assert_eq!(backings.iter().find(|backing| backing.name == "/usr/bin/my app").map(|backing| (backing.regions, backing.size)), Some((2, 0x5000)));
+    }
+}
@@ -0,0 +1,92 @@
+// prometheus text format of the published table, served on /metrics when --metrics is given
+//
+// machine totals are always exported, processes only for the top n by cpu and the top n by
+// memory so the number of series stays bounded however many processes run
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::collector::Published;
+use crate::process::ProcessInfo;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+// label values escape backslashes, quotes and newlines
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, samples: impl IntoIterator<Item = (String, f64)>) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, labels, value);
+    }
+}
+
+// the busiest and the largest processes, each at most once
+fn top(processes: &[ProcessInfo], count: usize) -> Vec<&ProcessInfo> {
+    let mut by_cpu: Vec<&ProcessInfo> = processes.iter().collect();
+    by_cpu.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    let mut by_memory: Vec<&ProcessInfo> = processes.iter().collect();
+    by_memory.sort_by_key(|process| std::cmp::Reverse(process.memory));
+    let pids: BTreeSet<u32> = by_cpu.iter().take(count).chain(by_memory.iter().take(count)).map(|process| process.pid).collect();
+    processes.iter().filter(|process| pids.contains(&process.pid)).collect()
+}
+
+pub fn render(published: &Published, count: usize) -> String {
+    let system = &published.system;
+    let mut out = String::new();
+    let single = |value: f64| [(String::new(), value)];
+    gauge(&mut out, "conborli_cpu_usage_percent", "CPU usage of the whole machine.", single(system.cpu as f64));
+    gauge(
+        &mut out,
+        "conborli_cpu_core_usage_percent",
+        "CPU usage of each core.",
+        system.cores.iter().enumerate().map(|(core, usage)| (format!("{{core=\"{}\"}}", core), *usage as f64)),
+    );
+    gauge(&mut out, "conborli_memory_used_bytes", "Memory in use.", single(system.memory_used as f64));
+    gauge(&mut out, "conborli_memory_total_bytes", "Installed memory.", single(system.memory_total as f64));
+    gauge(&mut out, "conborli_swap_used_bytes", "Swap in use.", single(system.swap_used as f64));
+    gauge(&mut out, "conborli_swap_total_bytes", "Swap space.", single(system.swap_total as f64));
+    gauge(&mut out, "conborli_uptime_seconds", "Seconds since boot.", single(system.uptime as f64));
+    gauge(&mut out, "conborli_processes", "Number of running processes.", single(system.processes as f64));
+
+    let top = top(&published.processes, count);
+    let labels = |process: &ProcessInfo| format!("{{pid=\"{}\",name=\"{}\",user=\"{}\"}}", process.pid, escape(&process.name), escape(&process.user));
+    gauge(
+        &mut out,
+        "conborli_process_cpu_usage_percent",
+        "CPU usage of the busiest and largest processes.",
+        top.iter().map(|process| (labels(process), process.cpu as f64)),
+    );
+    gauge(
+        &mut out,
+        "conborli_process_memory_bytes",
+        "Memory of the busiest and largest processes.",
+        top.iter().map(|process| (labels(process), (process.memory * 1024 * 1024) as f64)),
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn exports_the_top_processes_by_cpu_and_memory() {
+        let published = Published {
+            processes: vec![
+                mock::process(1, "idle", "u", 1, 0.0),
+                mock::process(2, "busy", "u", 2, 90.0),
+                mock::process(3, "big \"db\"", "u", 900, 1.0),
+            ],
+            ..Published::default()
+        };
+        let text = render(&published, 1);
+        assert!(text.contains("# TYPE conborli_memory_used_bytes gauge\nconborli_memory_used_bytes 0\n"));
+        assert!(text.contains("conborli_process_cpu_usage_percent{pid=\"2\",name=\"busy\",user=\"u\"} 90\n"));
+        assert!(text.contains("conborli_process_memory_bytes{pid=\"3\",name=\"big \\\"db\\\"\",user=\"u\"} 943718400\n"));
+        assert!(!text.contains("idle"));
+    }
+}
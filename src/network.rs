@@ -0,0 +1,105 @@
+// network tab - lists interfaces with throughput computed between refreshes
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use sysinfo::Networks;
+
+use crate::format::format_bytes;
+use crate::Message;
+
+#[derive(Debug, Clone)]
+pub struct NetworkInfo {
+    // holds info about each network interface
+    pub name: String,
+    // bytes per second received/transmitted since the previous refresh
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    // cumulative bytes since the interface came up
+    pub total_rx: u64,
+    pub total_tx: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkColumn {
+    // defines columns which can be sorted in the network tab
+    Name,
+    RxRate,
+    TxRate,
+    TotalRx,
+    TotalTx,
+}
+
+// builds the interface list from sysinfo, `elapsed` is the number of seconds since the
+// previous refresh and is used to turn the per-refresh byte counts into rates
+pub fn collect(networks: &Networks, elapsed: f64) -> Vec<NetworkInfo> {
+    networks
+        .iter()
+        .map(|(name, data)| NetworkInfo {
+            name: name.clone(),
+            rx_rate: rate(data.received(), elapsed),
+            tx_rate: rate(data.transmitted(), elapsed),
+            total_rx: data.total_received(),
+            total_tx: data.total_transmitted(),
+        })
+        .collect()
+}
+
+fn rate(bytes: u64, elapsed: f64) -> f64 {
+    if elapsed > 0.0 {
+        bytes as f64 / elapsed
+    } else {
+        0.0
+    }
+}
+
+// sorts the interfaces based on the selected column and order (asc/desc)
+pub fn sort(interfaces: &mut [NetworkInfo], column: NetworkColumn, ascending: bool) {
+    interfaces.sort_by(|a, b| {
+        let cmp = match column {
+            NetworkColumn::Name => a.name.cmp(&b.name),
+            NetworkColumn::RxRate => a.rx_rate.partial_cmp(&b.rx_rate).unwrap_or(std::cmp::Ordering::Equal),
+            NetworkColumn::TxRate => a.tx_rate.partial_cmp(&b.tx_rate).unwrap_or(std::cmp::Ordering::Equal),
+            NetworkColumn::TotalRx => a.total_rx.cmp(&b.total_rx),
+            NetworkColumn::TotalTx => a.total_tx.cmp(&b.total_tx),
+        };
+        if ascending {
+            cmp
+        } else {
+            cmp.reverse()
+        }
+    });
+}
+
+// construct the network tab layout
+pub fn view(interfaces: &[NetworkInfo]) -> Element<'_, Message> {
+    // displays buttons for sorting the interface list
+    let header = Row::new()
+        .spacing(10)
+        .push(Button::new("Interface").on_press(Message::SortNetwork(NetworkColumn::Name)).width(Length::FillPortion(2)))
+        .push(Button::new("Receive").on_press(Message::SortNetwork(NetworkColumn::RxRate)).width(Length::FillPortion(1)))
+        .push(Button::new("Send").on_press(Message::SortNetwork(NetworkColumn::TxRate)).width(Length::FillPortion(1)))
+        .push(Button::new("Total received").on_press(Message::SortNetwork(NetworkColumn::TotalRx)).width(Length::FillPortion(1)))
+        .push(Button::new("Total sent").on_press(Message::SortNetwork(NetworkColumn::TotalTx)).width(Length::FillPortion(1)));
+    // displays each interface in a row with its rates and totals
+    let rows = interfaces.iter().fold(
+        Column::new().spacing(5),
+        |column, interface| {
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(&interface.name).width(Length::FillPortion(2)))
+                    .push(Text::new(format!("{}/s", format_bytes(interface.rx_rate))).width(Length::FillPortion(1)))
+                    .push(Text::new(format!("{}/s", format_bytes(interface.tx_rate))).width(Length::FillPortion(1)))
+                    .push(Text::new(format_bytes(interface.total_rx as f64)).width(Length::FillPortion(1)))
+                    .push(Text::new(format_bytes(interface.total_tx as f64)).width(Length::FillPortion(1)))
+            )
+        },
+    );
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
@@ -0,0 +1,113 @@
+// tags and notes on programs - a color and a line of text the user attached ("staging server",
+// "safe to kill"), shown next to the name in every row of the program. kept in the config by
+// executable path so they outlive the pid and restarts, by name where the path can't be read
+use iced::{
+    alignment,
+    widget::{Button, Row, Text, TextInput},
+    theme, Color, Element, Length,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::process::ProcessInfo;
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tag {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl Tag {
+    pub const ALL: [Tag; 6] = [Tag::Red, Tag::Orange, Tag::Yellow, Tag::Green, Tag::Blue, Tag::Purple];
+
+    pub fn color(self) -> Color {
+        match self {
+            Tag::Red => Color::from_rgb8(0xe0, 0x40, 0x40),
+            Tag::Orange => Color::from_rgb8(0xf0, 0x90, 0x30),
+            Tag::Yellow => Color::from_rgb8(0xe0, 0xc0, 0x30),
+            Tag::Green => Color::from_rgb8(0x40, 0xb0, 0x50),
+            Tag::Blue => Color::from_rgb8(0x40, 0x80, 0xe0),
+            Tag::Purple => Color::from_rgb8(0xa0, 0x60, 0xd0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Note {
+    pub tag: Option<Tag>,
+    pub text: String,
+}
+
+// what a process's note is kept under
+pub fn key(process: &ProcessInfo) -> String {
+    match &process.exe {
+        Some(exe) => exe.display().to_string(),
+        None => process.name.clone(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NoteDraft {
+    // the note being edited, under its key, for the process it was opened from
+    pub key: String,
+    pub name: String,
+    pub tag: Option<Tag>,
+    pub text: String,
+}
+
+impl NoteDraft {
+    pub fn new(process: &ProcessInfo, existing: Option<&Note>) -> NoteDraft {
+        let existing = existing.cloned().unwrap_or_default();
+        NoteDraft { key: key(process), name: process.name.clone(), tag: existing.tag, text: existing.text }
+    }
+
+    // None when there is nothing left to keep
+    pub fn to_note(&self) -> Option<Note> {
+        let text = self.text.trim().to_string();
+        (self.tag.is_some() || !text.is_empty()).then_some(Note { tag: self.tag, text })
+    }
+}
+
+// the dot and text after a process name, owning its text so it outlives the borrowed style
+pub fn badge(note: &Note) -> Element<'static, Message> {
+    let mut badge = Row::new().spacing(4).align_items(alignment::Alignment::Center);
+    if let Some(tag) = note.tag {
+        badge = badge.push(Text::new("●").style(theme::Text::Color(tag.color())));
+    }
+    if !note.text.is_empty() {
+        badge = badge.push(Text::new(format!("· {}", note.text)).size(12));
+    }
+    badge.into()
+}
+
+// construct the dialog shown above the process table
+pub fn view(draft: &NoteDraft) -> Element<'_, Message> {
+    // the chosen tag is shown without a press handler so it reads as active
+    let tags = Tag::ALL.iter().fold(
+        Row::new().spacing(4).push(Button::new("None").on_press_maybe(draft.tag.is_some().then_some(Message::SetNoteTag(None)))),
+        |row, &tag| {
+            let button = Button::new(Text::new("●").style(theme::Text::Color(tag.color())));
+            row.push(if draft.tag == Some(tag) { button } else { button.on_press(Message::SetNoteTag(Some(tag))) })
+        },
+    );
+    Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new(format!("Note on {}", draft.name)))
+        .push(tags)
+        .push(
+            TextInput::new("this is the staging server", &draft.text)
+                .on_input(Message::NoteTextChanged)
+                .on_submit(Message::SaveNote)
+                .width(Length::Fixed(300.0))
+        )
+        .push(Button::new("Save").on_press(Message::SaveNote))
+        .push(Button::new("Cancel").on_press(Message::CancelNote))
+        .into()
+}
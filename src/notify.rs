@@ -0,0 +1,130 @@
+// desktop notifications and the alert sound, and the processes marked to send one when they exit
+//
+// notifications go through the platform's own tool like open.rs does: notify-send on linux,
+// osascript on macOS and a powershell toast on windows. the text is passed in environment
+// variables so it never has to be quoted for a script
+use std::collections::BTreeMap;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::process::ProcessInfo;
+use crate::run;
+
+const TITLE: &str = "CONBORLI_NOTIFY_TITLE";
+const BODY: &str = "CONBORLI_NOTIFY_BODY";
+
+fn command() -> Command {
+    if cfg!(target_os = "windows") {
+        let script = "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+            $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+            $text = $xml.GetElementsByTagName('text'); \
+            $text[0].AppendChild($xml.CreateTextNode($env:CONBORLI_NOTIFY_TITLE)) > $null; \
+            $text[1].AppendChild($xml.CreateTextNode($env:CONBORLI_NOTIFY_BODY)) > $null; \
+            [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('conborli').Show([Windows.UI.Notifications.ToastNotification]::new($xml))";
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            "display notification (system attribute \"CONBORLI_NOTIFY_BODY\") with title (system attribute \"CONBORLI_NOTIFY_TITLE\")",
+        ]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", "notify-send --app-name=conborli \"$CONBORLI_NOTIFY_TITLE\" \"$CONBORLI_NOTIFY_BODY\""]);
+        command
+    }
+}
+
+// shows the notification without waiting for it
+pub fn send(title: &str, body: &str) -> Result<(), String> {
+    let mut command = command();
+    command.env(TITLE, title).env(BODY, body);
+    run::spawn_detached(command).map(drop).map_err(|err| format!("failed to show a notification: {}", err))
+}
+
+// plays the desktop's alert sound, falling back to the freedesktop bell file on linux
+pub fn sound() -> Result<(), String> {
+    let mut command;
+    if cfg!(target_os = "windows") {
+        command = Command::new("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", "[System.Media.SystemSounds]::Exclamation.Play(); Start-Sleep -Milliseconds 500"]);
+    } else if cfg!(target_os = "macos") {
+        command = Command::new("afplay");
+        command.arg("/System/Library/Sounds/Ping.aiff");
+    } else {
+        command = Command::new("sh");
+        command.args(["-c", "canberra-gtk-play -i dialog-warning 2>/dev/null || paplay /usr/share/sounds/freedesktop/stereo/dialog-warning.oga"]);
+    }
+    run::spawn_detached(command).map(drop).map_err(|err| format!("failed to play a sound: {}", err))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exited {
+    pub pid: u32,
+    pub name: String,
+    // how long it ran after being marked
+    pub watched_for: Duration,
+}
+
+// processes marked with "Notify on exit", by pid since a job's pid means nothing after it is
+// gone, so the marks aren't saved
+#[derive(Debug, Default)]
+pub struct ExitWatches {
+    marked: BTreeMap<u32, (String, Instant)>,
+}
+
+impl ExitWatches {
+    pub fn mark(&mut self, pid: u32, name: &str, now: Instant) {
+        self.marked.insert(pid, (name.to_string(), now));
+    }
+
+    pub fn unmark(&mut self, pid: u32) {
+        self.marked.remove(&pid);
+    }
+
+    pub fn contains(&self, pid: u32) -> bool {
+        self.marked.contains_key(&pid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.marked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marked.is_empty()
+    }
+
+    // the marked processes missing from the table, which are unmarked
+    pub fn take_exited(&mut self, processes: &[ProcessInfo], now: Instant) -> Vec<Exited> {
+        let gone: Vec<u32> = self.marked.keys().copied().filter(|&pid| !processes.iter().any(|process| process.pid == pid)).collect();
+        gone.into_iter()
+            .filter_map(|pid| {
+                let (name, since) = self.marked.remove(&pid)?;
+                Some(Exited { pid, name, watched_for: now.duration_since(since) })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn marked_processes_are_reported_once_when_they_exit() {
+        let mut watches = ExitWatches::default();
+        let start = Instant::now();
+        watches.mark(2, "cargo", start);
+        watches.mark(3, "make", start);
+        let table = vec![mock::process(1, "shell", "u", 1, 0.0), mock::process(3, "make", "u", 1, 0.0)];
+        let exited = watches.take_exited(&table, start + Duration::from_secs(90));
+        assert_eq!(exited, vec![Exited { pid: 2, name: String::from("cargo"), watched_for: Duration::from_secs(90) }]);
+        assert!(watches.take_exited(&table, start).is_empty());
+        assert!(watches.contains(3));
+        assert_eq!(watches.len(), 1);
+    }
+}
@@ -0,0 +1,33 @@
+// central gate for features that reach out to other machines, off unless the user allows it.
+// the virustotal lookup and the remote hosts check it before anything is sent
+use crate::config::Config;
+
+// shown wherever a gated feature is unavailable because of the setting
+pub const DISABLED_REASON: &str = "disabled: network access is turned off in Settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnlineFeature {
+    // every feature that contacts a remote service
+    ReputationLookup,
+    RemoteHosts,
+}
+
+impl OnlineFeature {
+    pub const ALL: [OnlineFeature; 2] = [OnlineFeature::ReputationLookup, OnlineFeature::RemoteHosts];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OnlineFeature::ReputationLookup => "Executable reputation lookup",
+            OnlineFeature::RemoteHosts => "Process tables of remote hosts",
+        }
+    }
+}
+
+// every gated feature must call this before touching the network
+pub fn ensure_allowed(config: &Config, feature: OnlineFeature) -> Result<(), String> {
+    if config.allow_network_access {
+        Ok(())
+    } else {
+        Err(format!("{}: {}", feature.label(), DISABLED_REASON))
+    }
+}
@@ -0,0 +1,70 @@
+// the linux out-of-memory killer's view of a process - oom_score is how likely it is picked
+// when memory runs out, oom_score_adj shifts that from -1000 (never) to 1000 (first). raising
+// it is allowed on one's own processes, lowering it below its last value needs root
+use iced::{
+    alignment,
+    widget::{Button, Row, Text, TextInput},
+    Element, Length,
+};
+
+use crate::Message;
+
+pub const MIN_ADJ: i32 = -1000;
+pub const MAX_ADJ: i32 = 1000;
+
+// the kernel's 0 to 1000 badness, None off linux or once the process exited
+pub fn score(pid: u32) -> Option<u32> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    std::fs::read_to_string(format!("/proc/{}/oom_score", pid)).ok()?.trim().parse().ok()
+}
+
+pub fn adj(pid: u32) -> Option<i32> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    std::fs::read_to_string(format!("/proc/{}/oom_score_adj", pid)).ok()?.trim().parse().ok()
+}
+
+pub fn set_adj(pid: u32, adj: i32) -> Result<(), String> {
+    if !cfg!(target_os = "linux") {
+        return Err(String::from("the oom adjustment is only supported on Linux"));
+    }
+    std::fs::write(format!("/proc/{}/oom_score_adj", pid), adj.clamp(MIN_ADJ, MAX_ADJ).to_string()).map_err(|err| match err.kind() {
+        std::io::ErrorKind::PermissionDenied => format!("can't set the oom adjustment of {}: lowering it needs root, raising it needs the process to be yours", pid),
+        _ => format!("can't set the oom adjustment of {}: {}", pid, err),
+    })
+}
+
+// a whole number in range, e.g. "-500"
+pub fn parse_adj(input: &str) -> Option<i32> {
+    input.trim().parse().ok().filter(|adj| (MIN_ADJ..=MAX_ADJ).contains(adj))
+}
+
+// the score, the adjustment with a field to change it, and the two ends as presets
+pub fn view<'a>(pid: u32, score: Option<u32>, adj: Option<i32>, input: &'a str) -> Element<'a, Message> {
+    let unknown = || String::from("n/a");
+    Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new("OOM score").width(Length::Fixed(120.0)))
+        .push(Text::new(format!("{} (adjustment {})", score.map(|score| score.to_string()).unwrap_or_else(unknown), adj.map(|adj| adj.to_string()).unwrap_or_else(unknown))))
+        .push(TextInput::new("-1000 to 1000", input).on_input(Message::OomAdjInputChanged).width(Length::Fixed(120.0)))
+        .push(Button::new("Set").on_press_maybe(parse_adj(input).map(|adj| Message::SetOomAdj(pid, adj))))
+        .push(Button::new("Protect").on_press(Message::SetOomAdj(pid, MIN_ADJ)))
+        .push(Button::new("Kill first").on_press(Message::SetOomAdj(pid, MAX_ADJ)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjustments_are_whole_numbers_in_range() {
+        assert_eq!(parse_adj(" -500 "), Some(-500));
+        assert_eq!(parse_adj("1001"), None);
+        assert_eq!(parse_adj("high"), None);
+    }
+}
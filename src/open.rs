@@ -0,0 +1,37 @@
+// opens paths and web addresses with the platform's default handler (file manager for directories)
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+
+use crate::run;
+
+// starts the file manager on the path without waiting for it to close
+pub fn open_path(path: &Path) -> Result<(), String> {
+    launch(path.as_os_str())
+}
+
+// opens the address in the default browser
+pub fn open_url(url: &str) -> Result<(), String> {
+    launch(OsStr::new(url))
+}
+
+fn launch(target: &OsStr) -> Result<(), String> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let mut command = Command::new(program);
+    command.arg(target);
+    run::spawn_detached(command).map(drop).map_err(|err| format!("failed to run {}: {}", program, err))
+}
+
+// opens the directory that contains the executable
+pub fn open_containing_dir(exe: &Path) -> Result<(), String> {
+    let dir = exe
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", exe.display()))?;
+    open_path(dir)
+}
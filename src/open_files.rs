@@ -0,0 +1,62 @@
+// open file descriptors of a process, read from /proc/<pid>/fd (Linux only)
+//
+// windows handle enumeration needs the undocumented NtQuerySystemInformation, so other
+// platforms report the listing as unsupported instead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenFile {
+    // holds one open descriptor and what it points to
+    pub fd: u32,
+    pub target: String,
+    pub kind: FileKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Socket,
+    Pipe,
+    // anon inodes like eventfd or epoll instances
+    Other,
+}
+
+impl FileKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FileKind::File => "file",
+            FileKind::Socket => "socket",
+            FileKind::Pipe => "pipe",
+            FileKind::Other => "other",
+        }
+    }
+}
+
+// every open descriptor, sorted by number
+#[cfg(target_os = "linux")]
+pub fn for_pid(pid: u32) -> Result<Vec<OpenFile>, String> {
+    let dir = format!("/proc/{}/fd", pid);
+    let entries = std::fs::read_dir(&dir).map_err(|err| format!("{}: {}", dir, err))?;
+    let mut files: Vec<OpenFile> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let fd = entry.file_name().to_str()?.parse().ok()?;
+            let target = std::fs::read_link(entry.path()).ok()?.to_string_lossy().to_string();
+            let kind = if target.starts_with("socket:") {
+                FileKind::Socket
+            } else if target.starts_with("pipe:") {
+                FileKind::Pipe
+            } else if target.starts_with('/') {
+                FileKind::File
+            } else {
+                FileKind::Other
+            };
+            Some(OpenFile { fd, target, kind })
+        })
+        .collect();
+    files.sort_by_key(|file| file.fd);
+    Ok(files)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn for_pid(_pid: u32) -> Result<Vec<OpenFile>, String> {
+    Err(String::from("listing open files is only supported on Linux"))
+}
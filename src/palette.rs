@@ -0,0 +1,85 @@
+// command palette - Ctrl+Shift+P opens a fuzzy search over what the app can do (switch tab,
+// sort, kill the selection, change the theme, export...) and the running processes, Enter
+// runs the highlighted entry. the arrows move the highlight, Escape closes it
+use iced::{
+    widget::{text_input, Button, Column, Text, TextInput},
+    theme, Element, Length,
+};
+
+use crate::fuzzy;
+use crate::Message;
+
+// matches listed under the search box
+const SHOWN: usize = 12;
+
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    pub query: String,
+    // highlighted line among the matches
+    pub selected: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub label: String,
+    pub message: Message,
+}
+
+impl Entry {
+    pub fn new(label: impl Into<String>, message: Message) -> Entry {
+        Entry { label: label.into(), message }
+    }
+}
+
+pub fn input_id() -> text_input::Id {
+    text_input::Id::new("command-palette")
+}
+
+// the entries matching the query, best first, everything in order while it is empty
+pub fn matches(entries: Vec<Entry>, query: &str) -> Vec<Entry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return entries.into_iter().take(SHOWN).collect();
+    }
+    let mut scored: Vec<(i32, Entry)> = entries
+        .into_iter()
+        .filter_map(|entry| fuzzy::find(query, &entry.label).map(|found| (found.score, entry)))
+        .collect();
+    // stable, so equal scores keep the order actions come in
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(SHOWN).map(|(_, entry)| entry).collect()
+}
+
+// construct the palette shown above the tab
+pub fn view<'a>(palette: &Palette, matches: &[Entry]) -> Element<'a, Message> {
+    let list = matches.iter().enumerate().fold(Column::new().spacing(2), |column, (index, entry)| {
+        let style = if index == palette.selected { theme::Button::Primary } else { theme::Button::Text };
+        column.push(Button::new(Text::new(entry.label.clone())).on_press(Message::RunPaletteEntry(index)).style(style).width(Length::Fill))
+    });
+    Column::new()
+        .spacing(5)
+        .width(Length::Fixed(500.0))
+        .push(
+            TextInput::new("Type an action or a process", &palette.query)
+                .id(input_id())
+                .on_input(Message::PaletteQueryChanged)
+                .on_submit(Message::RunPaletteEntry(palette.selected))
+        )
+        .push_maybe(matches.is_empty().then(|| Text::new("Nothing matches")))
+        .push(list)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_matches_come_first() {
+        let entries = vec![Entry::new("Sort by CPU", Message::Tick), Entry::new("Go to Performance tab", Message::Tick), Entry::new("code (42)", Message::Tick)];
+        let labels = |query| matches(entries.clone(), query).into_iter().map(|entry| entry.label).collect::<Vec<_>>();
+        assert_eq!(labels("perf"), vec!["Go to Performance tab"]);
+        assert_eq!(labels("c"), vec!["code (42)", "Sort by CPU", "Go to Performance tab"]);
+        assert_eq!(labels("").len(), 3);
+    }
+}
@@ -0,0 +1,167 @@
+// performance tab - what the machine is, its machine-wide cpu, memory and gpu usage, and
+// charts of the cpu and memory over the picked range, which can be copied or saved as images
+use iced::{
+    widget::{Button, Column, ProgressBar, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use sysinfo::System;
+
+use crate::about::{self, SystemInfo};
+use crate::chart;
+use crate::energy;
+use crate::events::{Event, EventLog};
+use crate::format::{self, format_bytes};
+use crate::gpu::GpuDevice;
+use crate::timeseries::{MachineHistory, Point, Range};
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    Cpu,
+    Memory,
+    // package power, only with rapl
+    Power,
+}
+
+impl ChartKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ChartKind::Cpu => "CPU",
+            ChartKind::Memory => "Memory",
+            ChartKind::Power => "CPU package power",
+        }
+    }
+
+    pub fn points(self, history: &MachineHistory, range: Range) -> Vec<Point> {
+        match self {
+            ChartKind::Cpu => history.cpu.points(range),
+            ChartKind::Memory => history.memory.points(range),
+            ChartKind::Power => history.power.points(range),
+        }
+    }
+
+    // top of the value axis, the next 10 W above the highest point for power
+    pub fn max(self, points: &[Point]) -> f32 {
+        match self {
+            ChartKind::Cpu | ChartKind::Memory => 100.0,
+            ChartKind::Power => (points.iter().map(|point| point.value).fold(0.0, f32::max) / 10.0).floor() * 10.0 + 10.0,
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            ChartKind::Cpu | ChartKind::Memory => "%",
+            ChartKind::Power => " W",
+        }
+    }
+}
+
+// the kills and rule matches within the range, marked on every chart
+pub fn markers(events: &EventLog, range: Range) -> Vec<Event> {
+    let since = chrono::Local::now() - chrono::Duration::from_std(range.span()).unwrap_or_default();
+    events.since(since).filter(|event| event.kind.marks_charts()).cloned().collect()
+}
+
+// the chart's title with its copy and save buttons
+fn chart_title<'a>(title: &'a str, kind: ChartKind) -> Row<'a, Message> {
+    Row::new()
+        .spacing(10)
+        .push(Text::new(title).width(Length::Fill))
+        .push(Button::new("Copy chart as image").on_press(Message::CopyChart(kind)))
+        .push(Button::new("Save chart as PNG").on_press(Message::SaveChart(kind)))
+}
+
+fn chart_of(kind: ChartKind, history: &MachineHistory, markers: Vec<Event>, range: Range) -> Element<'static, Message> {
+    let points = kind.points(history, range);
+    let max = kind.max(&points);
+    chart::view(points, markers, range, max, kind.unit())
+}
+
+// label, value text and a usage bar for one resource
+fn usage_row<'a>(label: String, value: String, percent: f32) -> Row<'a, Message> {
+    Row::new()
+        .spacing(10)
+        .push(Text::new(label).width(Length::FillPortion(2)))
+        .push(ProgressBar::new(0.0..=100.0, percent).width(Length::FillPortion(3)).height(Length::Fixed(16.0)))
+        .push(Text::new(value).width(Length::FillPortion(2)))
+}
+
+fn percent_of(used: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f32 / total as f32 * 100.0
+    }
+}
+
+// construct the performance tab layout from an already refreshed system
+pub fn view<'a>(system: &System, gpus: &[GpuDevice], info: &'a SystemInfo, history: &MachineHistory, events: &EventLog, range: Range) -> Element<'a, Message> {
+    let cpu = system.global_cpu_info().cpu_usage();
+    let markers = markers(events, range);
+    let ranges = Range::ALL.iter().fold(Row::new().spacing(2), |row, &choice| {
+        let button = Button::new(choice.label());
+        row.push(if choice == range { button } else { button.on_press(Message::SelectChartRange(choice)) })
+    });
+    let mut content = Column::new()
+        .spacing(10)
+        .push(about::view(info))
+        .push(ranges)
+        .push(chart_title("CPU", ChartKind::Cpu))
+        .push(chart_of(ChartKind::Cpu, history, markers.clone(), range))
+        .push(usage_row(String::from("Total"), format!("{}%", format::decimal(f64::from(cpu), 1)), cpu));
+    for cpu in system.cpus() {
+        content = content.push(usage_row(cpu.name().to_string(), format!("{}% at {} MHz", format::decimal(f64::from(cpu.cpu_usage()), 1), format::integer(cpu.frequency())), cpu.cpu_usage()));
+    }
+
+    content = content
+        .push(chart_title("Memory", ChartKind::Memory))
+        .push(chart_of(ChartKind::Memory, history, markers.clone(), range))
+        .push(usage_row(
+            String::from("RAM"),
+            format!("{} of {}", format_bytes(system.used_memory() as f64), format_bytes(system.total_memory() as f64)),
+            percent_of(system.used_memory(), system.total_memory()),
+        ))
+        .push(usage_row(
+            String::from("Swap"),
+            format!("{} of {}", format_bytes(system.used_swap() as f64), format_bytes(system.total_swap() as f64)),
+            percent_of(system.used_swap(), system.total_swap()),
+        ));
+
+    if energy::ESTIMATED {
+        content = if history.power.points(range).is_empty() {
+            content
+                .push(Text::new("CPU package power (RAPL, split between processes as an estimate)"))
+                .push(Text::new("RAPL counters unreadable, they are root-only since Linux 5.10"))
+        } else {
+            content
+                .push(chart_title("CPU package power (RAPL, split between processes as an estimate)", ChartKind::Power))
+                .push(chart_of(ChartKind::Power, history, markers.clone(), range))
+        };
+    }
+
+    content = content.push(Text::new("GPU"));
+    if gpus.is_empty() {
+        let hint = if cfg!(feature = "nvidia") {
+            "No supported GPU found"
+        } else {
+            "No supported GPU found (NVIDIA cards need a build with the `nvidia` feature)"
+        };
+        content = content.push(Text::new(hint));
+    }
+    for gpu in gpus {
+        let utilization = gpu.utilization.unwrap_or(0) as f32;
+        let mut name = gpu.name.clone();
+        if let Some(temperature) = gpu.temperature {
+            name.push_str(&format!(" {}°C", temperature));
+        }
+        content = content
+            .push(usage_row(name, gpu.utilization.map(|busy| format!("{}%", busy)).unwrap_or_else(|| String::from("n/a")), utilization))
+            .push(usage_row(
+                String::from("  Video memory"),
+                format!("{} of {}", format_bytes(gpu.memory_used as f64), format_bytes(gpu.memory_total as f64)),
+                percent_of(gpu.memory_used, gpu.memory_total),
+            ));
+    }
+    Scrollable::new(content).into()
+}
@@ -0,0 +1,35 @@
+// finding a process by clicking its window, like xkill: xprop turns the pointer into a
+// crosshair and prints the clicked window's _NET_WM_PID. that needs X11, wayland compositors
+// don't tell other clients which window is where, so there only xwayland windows can be picked
+use std::process::Command;
+
+// blocks until a window is clicked, escape or a click on the desktop gives an error
+pub fn pick() -> Result<u32, String> {
+    if !cfg!(target_os = "linux") {
+        return Err(String::from("picking a window is only supported on linux with X11"));
+    }
+    if std::env::var_os("DISPLAY").is_none() {
+        return Err(String::from("picking a window needs X11 or XWayland"));
+    }
+    let output = Command::new("xprop").arg("_NET_WM_PID").output().map_err(|err| format!("failed to run xprop: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("xprop: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    parse_pid(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| String::from("the window doesn't name its process"))
+}
+
+// "_NET_WM_PID(CARDINAL) = 4242", or "_NET_WM_PID:  not found." for windows without one
+fn parse_pid(output: &str) -> Option<u32> {
+    output.split_once('=')?.1.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_pid_xprop_prints() {
+        assert_eq!(parse_pid("_NET_WM_PID(CARDINAL) = 4242\n"), Some(4242));
+        assert_eq!(parse_pid("_NET_WM_PID:  not found.\n"), None);
+    }
+}
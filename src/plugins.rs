@@ -0,0 +1,274 @@
+// column plugins - computed columns added without touching the table code
+//
+// a plugin implements ColumnProvider and is listed in REGISTRY. its columns can be picked,
+// sorted and copied like the built-in ones, values are computed on every process refresh
+// while the column is shown and carried in ProcessInfo::custom under the plugin's id
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::process::{ProcessInfo, SortColumn};
+use crate::scripts;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnValue {
+    // numbers sort by value, text alphabetically
+    Number(f64),
+    Text(String),
+}
+
+impl ColumnValue {
+    // numbers before text, so a column mixing both still has a total order
+    pub fn compare(&self, other: &ColumnValue) -> Ordering {
+        match (self, other) {
+            (ColumnValue::Number(a), ColumnValue::Number(b)) => a.total_cmp(b),
+            (ColumnValue::Text(a), ColumnValue::Text(b)) => a.cmp(b),
+            (ColumnValue::Number(_), ColumnValue::Text(_)) => Ordering::Less,
+            (ColumnValue::Text(_), ColumnValue::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl fmt::Display for ColumnValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnValue::Number(value) => write!(f, "{}", value),
+            ColumnValue::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+pub trait ColumnProvider: Sync {
+    // stable name stored in the config, e.g. "cgroup"
+    fn id(&self) -> &'static str;
+    // header label
+    fn title(&self) -> &'static str;
+    // None leaves the cell empty, e.g. when the value can't be read
+    fn value(&self, process: &ProcessInfo) -> Option<ColumnValue>;
+    // relative width like the built-in columns, see columns::portion
+    fn portion(&self) -> u16 {
+        1
+    }
+}
+
+// the plugins built into this binary, add new ones here
+static REGISTRY: &[&dyn ColumnProvider] = &[&Cgroup, &Threads, &Badge];
+
+// a registered plugin, it serializes as its id and only ids in the registry deserialize
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PluginId(&'static str);
+
+impl PluginId {
+    pub fn provider(self) -> &'static dyn ColumnProvider {
+        *REGISTRY.iter().find(|plugin| plugin.id() == self.0).expect("plugin ids come from the registry")
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Debug for PluginId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for PluginId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for PluginId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        find(&id).ok_or_else(|| serde::de::Error::custom(format!("no column plugin {}", id)))
+    }
+}
+
+pub fn find(id: &str) -> Option<PluginId> {
+    REGISTRY.iter().find(|plugin| plugin.id() == id).map(|plugin| PluginId(plugin.id()))
+}
+
+// one column per registered plugin, in registry order
+pub fn columns() -> impl Iterator<Item = SortColumn> {
+    REGISTRY.iter().map(|plugin| SortColumn::Plugin(PluginId(plugin.id())))
+}
+
+// computes the values of the shown plugin columns, other plugins cost nothing
+pub fn fill(processes: &mut [ProcessInfo], shown: &[SortColumn]) {
+    let plugins: Vec<PluginId> = shown
+        .iter()
+        .filter_map(|column| match column {
+            SortColumn::Plugin(id) => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for process in processes {
+        for &id in &plugins {
+            match id.provider().value(process) {
+                Some(value) => process.custom.insert(id.as_str().to_string(), value),
+                None => process.custom.remove(id.as_str()),
+            };
+        }
+    }
+}
+
+// a config may name plugins this build doesn't have. serde(default) only fills in missing
+// fields, the plugin id of a column is checked against the registry and an unknown one is an
+// error that would fail the whole config, so list entries naming one are dropped here and
+// single columns fall back to the pid, see known_column
+pub fn known_columns<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<SortColumn>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Known(SortColumn),
+        Unknown(serde::de::IgnoredAny),
+    }
+    let entries = Vec::<Entry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Known(column) => Some(column),
+            Entry::Unknown(_) => None,
+        })
+        .collect())
+}
+
+pub fn known_column<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SortColumn, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Known(SortColumn),
+        Unknown(serde::de::IgnoredAny),
+    }
+    Ok(match Entry::deserialize(deserializer)? {
+        Entry::Known(column) => column,
+        Entry::Unknown(_) => SortColumn::Pid,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn proc_file(pid: u32, name: &str) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/{}", pid, name)).ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn proc_file(_pid: u32, _name: &str) -> Option<String> {
+    None
+}
+
+// the same for the saved sort chain, a key on a missing plugin is dropped
+pub fn known_sort_keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(SortColumn, bool)>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Known((SortColumn, bool)),
+        Unknown(serde::de::IgnoredAny),
+    }
+    let entries = Vec::<Entry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Known(key) => Some(key),
+            Entry::Unknown(_) => None,
+        })
+        .collect())
+}
+
+// control group of the process, the unified hierarchy's path on cgroup v2
+struct Cgroup;
+
+impl ColumnProvider for Cgroup {
+    fn id(&self) -> &'static str {
+        "cgroup"
+    }
+
+    fn title(&self) -> &'static str {
+        "Cgroup"
+    }
+
+    fn value(&self, process: &ProcessInfo) -> Option<ColumnValue> {
+        let cgroups = proc_file(process.pid, "cgroup")?;
+        // "0::/path" on v2, otherwise the first hierarchy that has a path
+        let line = cgroups.lines().find(|line| line.starts_with("0::")).or_else(|| cgroups.lines().next())?;
+        let path = line.splitn(3, ':').nth(2)?;
+        Some(ColumnValue::Text(path.to_string()))
+    }
+
+    fn portion(&self) -> u16 {
+        2
+    }
+}
+
+// number of threads
+struct Threads;
+
+impl ColumnProvider for Threads {
+    fn id(&self) -> &'static str {
+        "threads"
+    }
+
+    fn title(&self) -> &'static str {
+        "Threads"
+    }
+
+    fn value(&self, process: &ProcessInfo) -> Option<ColumnValue> {
+        let status = proc_file(process.pid, "status")?;
+        let count = status.lines().find_map(|line| line.strip_prefix("Threads:"))?.trim().parse().ok()?;
+        Some(ColumnValue::Number(count))
+    }
+}
+
+// text a user script put on the process with badge(pid, text), see scripts.rs. scripts
+// run after the plugins so this only keeps what they set
+struct Badge;
+
+impl ColumnProvider for Badge {
+    fn id(&self) -> &'static str {
+        scripts::BADGE
+    }
+
+    fn title(&self) -> &'static str {
+        "Badge"
+    }
+
+    fn value(&self, process: &ProcessInfo) -> Option<ColumnValue> {
+        process.custom.get(scripts::BADGE).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_columns_round_trip_and_unknown_ones_are_dropped() {
+        #[derive(Deserialize)]
+        struct Columns {
+            #[serde(deserialize_with = "known_columns")]
+            columns: Vec<SortColumn>,
+            #[serde(default, deserialize_with = "known_sort_keys")]
+            sort: Vec<(SortColumn, bool)>,
+            #[serde(default = "pid", deserialize_with = "known_column")]
+            secondary: SortColumn,
+        }
+        fn pid() -> SortColumn {
+            SortColumn::Pid
+        }
+        let threads = SortColumn::Plugin(find("threads").unwrap());
+        let stored: Columns = toml::from_str(r#"columns = ["Pid", { Plugin = "threads" }, { Plugin = "jvm-heap" }]"#).unwrap();
+        assert_eq!(stored.columns, vec![SortColumn::Pid, threads]);
+        let stored: Columns = toml::from_str("columns = []\nsort = [[\"Cpu\", false], [{ Plugin = \"jvm-heap\" }, true]]").unwrap();
+        assert_eq!(stored.sort, vec![(SortColumn::Cpu, false)]);
+        let stored: Columns = toml::from_str("columns = []\nsecondary = { Plugin = \"jvm-heap\" }").unwrap();
+        assert_eq!(stored.secondary, SortColumn::Pid);
+        let stored: Columns = toml::from_str("columns = []\nsecondary = { Plugin = \"threads\" }").unwrap();
+        assert_eq!(stored.secondary, threads);
+        assert_eq!(serde_json::to_string(&threads).unwrap(), r#"{"Plugin":"threads"}"#);
+        assert_eq!(ColumnValue::Number(2.0).compare(&ColumnValue::Text(String::from("a"))), Ordering::Less);
+    }
+}
@@ -0,0 +1,67 @@
+// reads and changes process scheduling priority (nice value on unix)
+
+// lowest and highest nice values, lower means more cpu time
+pub const MIN_NICE: i32 = -20;
+pub const MAX_NICE: i32 = 19;
+
+// current nice value of the process
+#[cfg(unix)]
+pub fn get_nice(pid: u32) -> Option<i32> {
+    // getpriority can legitimately return -1, so errors are only detectable via errno, which is
+    // cleared first because a successful call leaves it as it was
+    unsafe { *errno() = 0 };
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS as _, pid as libc::id_t) };
+    if unsafe { *errno() } != 0 {
+        return None;
+    }
+    Some(nice)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn errno() -> *mut libc::c_int {
+    libc::__errno_location()
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+unsafe fn errno() -> *mut libc::c_int {
+    libc::__error()
+}
+
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+unsafe fn errno() -> *mut libc::c_int {
+    libc::__errno()
+}
+
+#[cfg(not(unix))]
+pub fn get_nice(_pid: u32) -> Option<i32> {
+    None
+}
+
+// sets the nice value, raising priority (a lower value) usually needs root
+#[cfg(unix)]
+pub fn set_nice(pid: u32, nice: i32) -> Result<(), String> {
+    let nice = nice.clamp(MIN_NICE, MAX_NICE);
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS as _, pid as libc::id_t, nice) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!("failed to set priority of {}: {}", pid, std::io::Error::last_os_error()))
+    }
+}
+
+#[cfg(not(unix))]
+pub fn set_nice(_pid: u32, _nice: i32) -> Result<(), String> {
+    Err(String::from("changing priority is not supported on this platform"))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_processes_have_no_nice_value() {
+        assert!(get_nice(std::process::id()).is_some());
+        // above the largest pid linux hands out
+        assert_eq!(get_nice(5_000_000), None);
+    }
+}
@@ -0,0 +1,725 @@
+// process model - collection from sysinfo and sorting, kept free of ui code
+use serde::{Deserialize, Serialize};
+
+use sysinfo::{Process, ProcessStatus, System, ThreadKind, Users};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::apps;
+use crate::energy;
+use crate::oom;
+use crate::plugins::{self, ColumnValue, PluginId};
+use crate::priority;
+use crate::procfs::{self, Counters};
+
+// serialized for snapshots, fields added later default when older files are read
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessInfo {
+    // holds info about each process
+    pub pid: u32,
+    // pid of the parent process, None for the first process or when the parent is unknown
+    pub ppid: Option<u32>,
+    pub name: String,
+    // name of the owning user, or the numeric id if it has no account entry
+    pub user: String,
+    // full command line, arguments separated by spaces
+    pub cmd: String,
+    // path of the executable, None when it can't be read (e.g. another user's process)
+    pub exe: Option<PathBuf>,
+    // working directory, None when it can't be read
+    pub cwd: Option<PathBuf>,
+    pub memory: u64,
+    // address space size in MB, mapped but not necessarily resident
+    pub virtual_memory: u64,
+    // part of `memory` shared with other processes in MB, None where the platform doesn't expose it
+    pub shared_memory: Option<u64>,
+    // swapped out memory in MB, linux only
+    pub swap: Option<u64>,
+    // the out-of-memory killer's badness, linux only, see oom.rs
+    pub oom_score: Option<u32>,
+    // cpu percent after smoothing, used for display and sorting
+    pub cpu: f32,
+    // cpu percent measured over the last refresh only
+    pub cpu_raw: f32,
+    // nice value, None where the platform doesn't expose one
+    pub nice: Option<i32>,
+    // cpu time consumed so far in seconds, None where the platform doesn't expose it
+    pub cpu_time: Option<u64>,
+    // seconds since the process started
+    pub uptime: u64,
+    // when it started in seconds since the epoch, 0 when unknown. with the name it tells a
+    // process apart from a later one given the same pid, see provider::Identity
+    pub start_time: u64,
+    // "runtime:name" of the container the process runs in, None on the host or when not collected
+    pub container: Option<String>,
+    // cgroup path, e.g. /user.slice/user-1000.slice/session-2.scope, only filled while the
+    // table is grouped by slice
+    pub cgroup: Option<String>,
+    // gpu utilization in percent and video memory in MB, only filled while a gpu column is shown
+    pub gpu: f32,
+    pub gpu_memory: u64,
+    // change in memory (MB) and cpu percent over the trend window, see history.rs
+    pub memory_growth: i64,
+    pub cpu_trend: f32,
+    // change in cpu percent and memory (MB) since the previous sample, see history.rs
+    pub cpu_delta: f32,
+    pub memory_delta: i64,
+    // MB per minute over the current run of memory increases, and whether the run is long
+    // enough to look like a leak
+    pub growth_rate: f32,
+    pub suspected_leak: bool,
+    // kernel thread or process of an operating system account, see `is_system`
+    pub system: bool,
+    // runs as root or SYSTEM, see `is_elevated`
+    pub elevated: bool,
+    // looked hung in this sample, see `looks_stuck`, and for long enough to be shown as not
+    // responding, see history.rs
+    pub stuck: bool,
+    pub not_responding: bool,
+    // its details couldn't be read (another user's process on a hardened system), so the
+    // zeros in its row aren't readings, see `is_access_denied`
+    pub access_denied: bool,
+    // context switches, threads and scheduler waits, see procfs.rs
+    pub counters: Option<Counters>,
+    // title of the process's main window and the description in its executable's version
+    // resource (e.g. "Minecraft Launcher" for javaw.exe), windows only
+    pub window_title: Option<String>,
+    pub description: Option<String>,
+    // power drawn since the last refresh in milliwatts and whether App Nap throttles it,
+    // macOS only, the power is also estimated on linux with rapl, see energy.rs
+    pub energy: Option<f32>,
+    pub napping: Option<bool>,
+    // a program the user opened rather than a background process, see apps.rs
+    pub app: bool,
+    // values of the shown plugin columns by plugin id, see plugins.rs
+    pub custom: BTreeMap<String, ColumnValue>,
+    // paths of the open files and ports listened on, only filled while the search box looks
+    // for them, see holders.rs
+    pub open_paths: Vec<String>,
+    pub listening_ports: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SortColumn {
+    // defines columns which can be sorted
+    Pid,
+    // parent pid, clicking the cell jumps to the parent's row
+    Ppid,
+    Name,
+    User,
+    Memory,
+    // address space size and the resident part shared with other processes
+    VirtualMemory,
+    SharedMemory,
+    // swapped out, the processes behind the thrashing rather than the resident ones
+    Swap,
+    OomScore,
+    Cpu,
+    Priority,
+    // trends over the last few minutes rather than the current value
+    MemoryGrowth,
+    CpuTrend,
+    // change since the previous sample, so fast risers show before their usage is high
+    CpuDelta,
+    MemoryDelta,
+    // total cpu time consumed and time since start
+    CpuTime,
+    Uptime,
+    // gpu utilization and video memory, see gpu.rs
+    Gpu,
+    GpuMemory,
+    // docker/podman/lxc container, see containers.rs
+    Container,
+    // memory growth per minute while it keeps rising, see history.rs
+    GrowthRate,
+    // scheduler counters from /proc, see `PROC_COUNTERS`
+    VoluntarySwitches,
+    InvoluntarySwitches,
+    Threads,
+    IoWait,
+    RunQueueWait,
+    // windows only, see `WINDOWS`
+    WindowTitle,
+    Description,
+    // macOS only, see `MACOS`, energy is estimated on linux with rapl
+    Energy,
+    AppNap,
+    // computed by a column plugin, see plugins.rs
+    Plugin(PluginId),
+}
+
+impl SortColumn {
+    // every built-in column in its default order, see `with_plugins`
+    pub const ALL: [SortColumn; 21] = [
+        SortColumn::Pid,
+        SortColumn::Ppid,
+        SortColumn::Name,
+        SortColumn::User,
+        SortColumn::Memory,
+        SortColumn::VirtualMemory,
+        SortColumn::SharedMemory,
+        SortColumn::Swap,
+        SortColumn::OomScore,
+        SortColumn::Cpu,
+        SortColumn::Priority,
+        SortColumn::MemoryGrowth,
+        SortColumn::CpuTrend,
+        SortColumn::CpuDelta,
+        SortColumn::MemoryDelta,
+        SortColumn::CpuTime,
+        SortColumn::Uptime,
+        SortColumn::Gpu,
+        SortColumn::GpuMemory,
+        SortColumn::Container,
+        SortColumn::GrowthRate,
+    ];
+
+    // offered only by linux builds with the `proc-counters` feature, for performance debugging
+    pub const PROC_COUNTERS: [SortColumn; 5] = [
+        SortColumn::VoluntarySwitches,
+        SortColumn::InvoluntarySwitches,
+        SortColumn::Threads,
+        SortColumn::IoWait,
+        SortColumn::RunQueueWait,
+    ];
+
+    // offered only on windows, where executables carry a description
+    pub const WINDOWS: [SortColumn; 2] = [SortColumn::WindowTitle, SortColumn::Description];
+
+    // offered only on macOS, for triaging by energy
+    pub const MACOS: [SortColumn; 2] = [SortColumn::Energy, SortColumn::AppNap];
+
+    // columns shown until the user picks their own, newer columns are opt-in
+    pub const DEFAULT_VISIBLE: [SortColumn; 6] = [
+        SortColumn::Pid,
+        SortColumn::Name,
+        SortColumn::User,
+        SortColumn::Memory,
+        SortColumn::Cpu,
+        SortColumn::Priority,
+    ];
+
+    // the built-in columns followed by one per registered plugin
+    pub fn with_plugins() -> Vec<SortColumn> {
+        let counters = if cfg!(all(feature = "proc-counters", target_os = "linux")) { &SortColumn::PROC_COUNTERS[..] } else { &[] };
+        let windows = if cfg!(windows) { &SortColumn::WINDOWS[..] } else { &[] };
+        // the energy estimate of a rapl build, app nap is macOS's own
+        let macos = if cfg!(target_os = "macos") {
+            &SortColumn::MACOS[..]
+        } else if energy::ESTIMATED {
+            &SortColumn::MACOS[..1]
+        } else {
+            &[]
+        };
+        SortColumn::ALL
+            .into_iter()
+            .chain(counters.iter().copied())
+            .chain(windows.iter().copied())
+            .chain(macos.iter().copied())
+            .chain(plugins::columns())
+            .collect()
+    }
+
+    // name of the column in flags and in the config, plugin columns go by their id
+    pub fn key(self) -> String {
+        match self {
+            SortColumn::Plugin(id) => id.as_str().to_string(),
+            column => format!("{:?}", column),
+        }
+    }
+
+    // header label of the column
+    pub fn title(self) -> &'static str {
+        match self {
+            SortColumn::Pid => "PID",
+            SortColumn::Ppid => "PPID",
+            SortColumn::Name => "Name",
+            SortColumn::User => "User",
+            SortColumn::Memory => "Memory (MB)",
+            SortColumn::VirtualMemory => "Virtual (MB)",
+            SortColumn::SharedMemory => "Shared (MB)",
+            SortColumn::Swap => "Swap (MB)",
+            SortColumn::OomScore => "OOM score",
+            SortColumn::Cpu => "CPU (%)",
+            SortColumn::Priority => "Priority",
+            SortColumn::MemoryGrowth => "Mem growth (MB/5m)",
+            SortColumn::CpuTrend => "CPU trend (5m)",
+            SortColumn::CpuDelta => "CPU Δ (%)",
+            SortColumn::MemoryDelta => "Mem Δ (MB)",
+            SortColumn::CpuTime => "CPU time",
+            SortColumn::Uptime => "Running for",
+            SortColumn::Gpu => "GPU (%)",
+            SortColumn::GpuMemory => "GPU memory (MB)",
+            SortColumn::Container => "Container",
+            SortColumn::GrowthRate => "Growth rate (MB/min)",
+            SortColumn::VoluntarySwitches => "Voluntary switches",
+            SortColumn::InvoluntarySwitches => "Involuntary switches",
+            SortColumn::Threads => "Threads",
+            SortColumn::IoWait => "I/O wait (ms)",
+            SortColumn::RunQueueWait => "Run queue wait (ms)",
+            SortColumn::WindowTitle => "Window title",
+            SortColumn::Description => "Description",
+            SortColumn::Energy if energy::ESTIMATED => "Energy est. (mW)",
+            SortColumn::Energy => "Energy (mW)",
+            SortColumn::AppNap => "App Nap",
+            SortColumn::Plugin(id) => id.provider().title(),
+        }
+    }
+}
+
+// builds the process list from an already refreshed sysinfo instance
+pub fn collect(system: &System, users: &Users) -> Vec<ProcessInfo> {
+    let hung = hung_window_pids();
+    let mut titles = window_titles();
+    system
+        .processes()
+        .iter()
+        .map(|(pid, process)| {
+            let user = process
+                .user_id()
+                .map(|uid| match users.get_user_by_id(uid) {
+                    Some(user) => user.name().to_string(),
+                    None => uid.to_string(),
+                })
+                .unwrap_or_default();
+            let system = is_system(process, &user);
+            let elevated = is_elevated(process, &user);
+            let stuck = looks_stuck(process, &hung);
+            ProcessInfo {
+                pid: pid.as_u32(),
+                ppid: process.parent().map(|parent| parent.as_u32()),
+                name: process.name().to_string(),
+                user,
+                cmd: process.cmd().join(" "),
+                exe: process.exe().map(Path::to_path_buf),
+                cwd: process.cwd().map(Path::to_path_buf),
+                memory: process.memory() / 1024 / 1024,
+                virtual_memory: process.virtual_memory() / 1024 / 1024,
+                // read from statm only while its column is shown, see fill_shared_memory
+                shared_memory: None,
+                swap: procfs::swap(pid.as_u32()).map(|bytes| bytes / 1024 / 1024),
+                oom_score: oom::score(pid.as_u32()),
+                cpu: process.cpu_usage(),
+                cpu_raw: process.cpu_usage(),
+                nice: priority::get_nice(pid.as_u32()),
+                cpu_time: procfs::cpu_time(pid.as_u32()),
+                uptime: process.run_time(),
+                start_time: process.start_time(),
+                container: None,
+                cgroup: None,
+                gpu: 0.0,
+                gpu_memory: 0,
+                memory_growth: 0,
+                cpu_trend: 0.0,
+                cpu_delta: 0.0,
+                memory_delta: 0,
+                growth_rate: 0.0,
+                suspected_leak: false,
+                system,
+                elevated,
+                stuck,
+                not_responding: false,
+                access_denied: is_access_denied(process),
+                counters: if cfg!(feature = "proc-counters") { procfs::counters(pid.as_u32()) } else { None },
+                window_title: titles.remove(&pid.as_u32()),
+                description: process.exe().and_then(description),
+                // filled in by energy.rs, which keeps the previous readings
+                energy: None,
+                napping: None,
+                app: false,
+                custom: BTreeMap::new(),
+                open_paths: Vec::new(),
+                listening_ports: Vec::new(),
+            }
+        })
+        .map(|mut info| {
+            info.app = apps::is_app(&info);
+            info
+        })
+        .collect()
+}
+
+// waiting in the kernel (D state) without using cpu or doing disk io, or owning a window that
+// stopped answering its messages on Windows. a short D state is normal, so one sample alone
+// doesn't count as not responding
+fn looks_stuck(process: &Process, hung_windows: &HashSet<u32>) -> bool {
+    let disk = process.disk_usage();
+    let waiting = process.status() == ProcessStatus::UninterruptibleDiskSleep
+        && process.cpu_usage() == 0.0
+        && disk.read_bytes == 0
+        && disk.written_bytes == 0;
+    waiting || hung_windows.contains(&process.pid().as_u32())
+}
+
+// owners of visible top-level windows that windows itself reports as hung, i.e. that haven't
+// pumped their message loop for a few seconds
+#[cfg(windows)]
+fn hung_window_pids() -> HashSet<u32> {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{EnumWindows, GetWindowThreadProcessId, IsHungAppWindow, IsWindowVisible};
+
+    unsafe extern "system" fn visit(window: HWND, pids: LPARAM) -> BOOL {
+        if IsWindowVisible(window) != 0 && IsHungAppWindow(window) != 0 {
+            let mut pid = 0;
+            GetWindowThreadProcessId(window, &mut pid);
+            (*(pids as *mut HashSet<u32>)).insert(pid);
+        }
+        1
+    }
+    let mut pids = HashSet::new();
+    unsafe { EnumWindows(Some(visit), &mut pids as *mut HashSet<u32> as LPARAM) };
+    pids
+}
+
+#[cfg(not(windows))]
+fn hung_window_pids() -> HashSet<u32> {
+    HashSet::new()
+}
+
+// title of the first visible, unowned top-level window of each process, which is what the
+// taskbar shows for it
+#[cfg(windows)]
+fn window_titles() -> HashMap<u32, String> {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindow, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible, GW_OWNER,
+    };
+
+    unsafe extern "system" fn visit(window: HWND, titles: LPARAM) -> BOOL {
+        let length = GetWindowTextLengthW(window);
+        if IsWindowVisible(window) == 0 || GetWindow(window, GW_OWNER) != 0 || length <= 0 {
+            return 1;
+        }
+        let mut text = vec![0u16; length as usize + 1];
+        let copied = GetWindowTextW(window, text.as_mut_ptr(), text.len() as i32);
+        let mut pid = 0;
+        GetWindowThreadProcessId(window, &mut pid);
+        let titles = &mut *(titles as *mut HashMap<u32, String>);
+        titles.entry(pid).or_insert_with(|| String::from_utf16_lossy(&text[..copied.max(0) as usize]));
+        1
+    }
+    let mut titles = HashMap::new();
+    unsafe { EnumWindows(Some(visit), &mut titles as *mut HashMap<u32, String> as LPARAM) };
+    titles
+}
+
+#[cfg(not(windows))]
+fn window_titles() -> HashMap<u32, String> {
+    HashMap::new()
+}
+
+// FileDescription of the executable's version resource, cached by path since executables
+// rarely change while they run
+#[cfg(windows)]
+fn description(exe: &Path) -> Option<String> {
+    use std::sync::{Mutex, OnceLock};
+
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, Option<String>>>> = OnceLock::new();
+    let mut cache = CACHE.get_or_init(Default::default).lock().ok()?;
+    cache.entry(exe.to_path_buf()).or_insert_with(|| read_description(exe)).clone()
+}
+
+#[cfg(windows)]
+fn read_description(exe: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+    use windows_sys::Win32::Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW};
+
+    let wide = |text: &std::ffi::OsStr| text.encode_wide().chain([0]).collect::<Vec<u16>>();
+    let path = wide(exe.as_os_str());
+    let size = unsafe { GetFileVersionInfoSizeW(path.as_ptr(), ptr::null_mut()) };
+    if size == 0 {
+        return None;
+    }
+    let mut data = vec![0u8; size as usize];
+    if unsafe { GetFileVersionInfoW(path.as_ptr(), 0, size, data.as_mut_ptr().cast()) } == 0 {
+        return None;
+    }
+    let query = |block: &str| -> Option<(*const u8, u32)> {
+        let block = wide(std::ffi::OsStr::new(block));
+        let mut value = ptr::null_mut();
+        let mut length = 0;
+        let found = unsafe { VerQueryValueW(data.as_ptr().cast(), block.as_ptr(), &mut value, &mut length) };
+        (found != 0 && !value.is_null() && length > 0).then_some((value as *const u8, length))
+    };
+    // the first language and code page the resource lists
+    let (translation, _) = query("\\VarFileInfo\\Translation")?;
+    let (language, code_page) = unsafe { (*(translation as *const u16), *(translation as *const u16).add(1)) };
+    let (text, length) = query(&format!("\\StringFileInfo\\{:04x}{:04x}\\FileDescription", language, code_page))?;
+    let text = unsafe { std::slice::from_raw_parts(text as *const u16, length as usize) };
+    let description = String::from_utf16_lossy(text).trim_end_matches('\0').trim().to_string();
+    (!description.is_empty()).then_some(description)
+}
+
+#[cfg(not(windows))]
+fn description(_exe: &Path) -> Option<String> {
+    None
+}
+
+// kernel threads, e.g. the bracketed kworker ones on Linux, and processes of the accounts the
+// operating system runs its services as
+// every running user space process maps some memory, a process without a name or an address
+// space is one whose /proc entries (or windows handle) were refused. zombies have let theirs go
+fn is_access_denied(process: &Process) -> bool {
+    process.thread_kind() != Some(ThreadKind::Kernel)
+        && process.status() != ProcessStatus::Zombie
+        && (process.name().is_empty() || process.virtual_memory() == 0)
+}
+
+fn is_system(process: &Process, user: &str) -> bool {
+    process.thread_kind() == Some(ThreadKind::Kernel) || system_account(process, user)
+}
+
+#[cfg(unix)]
+fn system_account(process: &Process, _user: &str) -> bool {
+    // regular accounts start at 1000 on Linux and 501 on macOS, 65534 is nobody
+    let first_user = if cfg!(target_os = "macos") { 501 } else { 1000 };
+    process.user_id().is_some_and(|uid| **uid < first_user || **uid == 65534)
+}
+
+// effective uid 0 on unix, so setuid programs count; the token's elevation isn't readable
+// through sysinfo on Windows, there only SYSTEM processes count
+#[cfg(unix)]
+fn is_elevated(process: &Process, _user: &str) -> bool {
+    process.effective_user_id().or(process.user_id()).is_some_and(|uid| **uid == 0)
+}
+
+#[cfg(windows)]
+fn is_elevated(_process: &Process, user: &str) -> bool {
+    user.eq_ignore_ascii_case("SYSTEM")
+}
+
+#[cfg(windows)]
+fn system_account(_process: &Process, user: &str) -> bool {
+    ["SYSTEM", "LOCAL SERVICE", "NETWORK SERVICE"].iter().any(|account| user.eq_ignore_ascii_case(account))
+}
+
+// turns "% of one core" readings into "% of the whole machine"
+pub fn scale_cpu(processes: &mut [ProcessInfo], cores: usize) {
+    let cores = cores.max(1) as f32;
+    for process in processes {
+        process.cpu /= cores;
+        process.cpu_raw /= cores;
+    }
+}
+
+// longest smoothing window offered in the settings
+pub const MAX_CPU_SMOOTHING: u32 = 10;
+
+// replaces each cpu value with an exponential moving average over roughly `window` samples,
+// `previous` holds the last average per pid and is pruned of exited processes, 1 disables it
+pub fn smooth_cpu(processes: &mut [ProcessInfo], previous: &mut HashMap<u32, f32>, window: u32) {
+    let alpha = 2.0 / (window.max(1) as f32 + 1.0);
+    let mut current = HashMap::with_capacity(processes.len());
+    for process in processes.iter_mut() {
+        if let Some(&last) = previous.get(&process.pid) {
+            process.cpu = alpha * process.cpu_raw + (1.0 - alpha) * last;
+        }
+        current.insert(process.pid, process.cpu);
+    }
+    *previous = current;
+}
+
+// compares two processes by a single column, ascending
+pub fn compare(a: &ProcessInfo, b: &ProcessInfo, column: SortColumn) -> Ordering {
+    match column {
+        SortColumn::Pid => a.pid.cmp(&b.pid),
+        SortColumn::Ppid => a.ppid.cmp(&b.ppid),
+        SortColumn::Name => a.name.cmp(&b.name),
+        SortColumn::User => a.user.cmp(&b.user),
+        SortColumn::Memory => a.memory.cmp(&b.memory),
+        SortColumn::VirtualMemory => a.virtual_memory.cmp(&b.virtual_memory),
+        SortColumn::SharedMemory => a.shared_memory.cmp(&b.shared_memory),
+        SortColumn::Swap => a.swap.cmp(&b.swap),
+        SortColumn::OomScore => a.oom_score.cmp(&b.oom_score),
+        // total_cmp keeps the order total when a reading is NaN, which sort_by relies on
+        SortColumn::Cpu => a.cpu.total_cmp(&b.cpu),
+        SortColumn::Priority => a.nice.cmp(&b.nice),
+        SortColumn::MemoryGrowth => a.memory_growth.cmp(&b.memory_growth),
+        SortColumn::CpuTrend => a.cpu_trend.total_cmp(&b.cpu_trend),
+        SortColumn::CpuDelta => a.cpu_delta.total_cmp(&b.cpu_delta),
+        SortColumn::MemoryDelta => a.memory_delta.cmp(&b.memory_delta),
+        SortColumn::CpuTime => a.cpu_time.cmp(&b.cpu_time),
+        SortColumn::Uptime => a.uptime.cmp(&b.uptime),
+        SortColumn::Gpu => a.gpu.total_cmp(&b.gpu),
+        SortColumn::GpuMemory => a.gpu_memory.cmp(&b.gpu_memory),
+        SortColumn::Container => a.container.cmp(&b.container),
+        SortColumn::GrowthRate => a.growth_rate.total_cmp(&b.growth_rate),
+        SortColumn::VoluntarySwitches => a.counters.map(|c| c.voluntary_switches).cmp(&b.counters.map(|c| c.voluntary_switches)),
+        SortColumn::InvoluntarySwitches => a.counters.map(|c| c.involuntary_switches).cmp(&b.counters.map(|c| c.involuntary_switches)),
+        SortColumn::Threads => a.counters.map(|c| c.threads).cmp(&b.counters.map(|c| c.threads)),
+        SortColumn::IoWait => a.counters.map(|c| c.io_wait).cmp(&b.counters.map(|c| c.io_wait)),
+        SortColumn::RunQueueWait => a.counters.map(|c| c.run_queue_wait).cmp(&b.counters.map(|c| c.run_queue_wait)),
+        SortColumn::WindowTitle => a.window_title.cmp(&b.window_title),
+        SortColumn::Description => a.description.cmp(&b.description),
+        SortColumn::Energy => match (a.energy, b.energy) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        },
+        SortColumn::AppNap => a.napping.cmp(&b.napping),
+        // processes without a value sort first, like None elsewhere
+        SortColumn::Plugin(id) => match (a.custom.get(id.as_str()), b.custom.get(id.as_str())) {
+            (Some(a), Some(b)) => a.compare(b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        },
+    }
+}
+
+// reads the shared memory column, one statm file per process, so only while the column is shown
+pub fn fill_shared_memory(processes: &mut [ProcessInfo]) {
+    for process in processes {
+        process.shared_memory = procfs::shared_memory(process.pid).map(|bytes| bytes / 1024 / 1024);
+    }
+}
+
+// copies the gpu, container, energy, shared memory and plugin values a fresh table skipped from
+// the previous one, new processes stay without them
+pub fn carry_expensive_columns(previous: &[ProcessInfo], processes: &mut [ProcessInfo]) {
+    let previous: HashMap<u32, &ProcessInfo> = previous.iter().map(|process| (process.pid, process)).collect();
+    for process in processes {
+        if let Some(last) = previous.get(&process.pid) {
+            process.gpu = last.gpu;
+            process.gpu_memory = last.gpu_memory;
+            process.container = last.container.clone();
+            process.cgroup = last.cgroup.clone();
+            process.energy = last.energy;
+            process.shared_memory = last.shared_memory;
+            process.napping = last.napping;
+            process.custom = last.custom.clone();
+            process.open_paths = last.open_paths.clone();
+            process.listening_ports = last.listening_ports.clone();
+        }
+    }
+}
+
+// Sorts the process list based on the selected sort column and order (asc/desc), rows that tie
+// are ordered by the secondary column and then by pid (both ascending) so they don't jitter
+pub fn sort(processes: &mut [ProcessInfo], column: SortColumn, ascending: bool, secondary: SortColumn) {
+    sort_by_keys(processes, &[(column, ascending)], secondary);
+}
+
+// sorts by a chain of (column, ascending) keys, each one breaking the ties of the one before
+pub fn sort_by_keys(processes: &mut [ProcessInfo], keys: &[(SortColumn, bool)], secondary: SortColumn) {
+    processes.sort_by(|a, b| {
+        keys.iter()
+            .fold(Ordering::Equal, |cmp, &(column, ascending)| {
+                cmp.then_with(|| {
+                    let cmp = compare(a, b, column);
+                    if ascending { cmp } else { cmp.reverse() }
+                })
+            })
+            .then_with(|| compare(a, b, secondary))
+            .then_with(|| a.pid.cmp(&b.pid))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    use crate::provider::mock;
+
+    fn column() -> impl Strategy<Value = SortColumn> {
+        prop::sample::select(SortColumn::ALL.to_vec())
+    }
+
+    // any f32 including NaN and infinities, so cpu comparisons are stressed
+    fn processes() -> impl Strategy<Value = Vec<ProcessInfo>> {
+        prop::collection::vec(
+            (
+                (0u32..1000, "[a-c]{0,2}", 0u64..5, prop::num::f32::ANY),
+                (prop::option::of(-20i32..20), -3i64..3, prop::num::f32::ANY, prop::option::of(0u64..3), 0u64..3),
+            ),
+            0..60,
+        )
+        .prop_map(|rows| {
+            rows.into_iter()
+                .map(|((pid, name, memory, cpu), (nice, memory_growth, cpu_trend, cpu_time, uptime))| ProcessInfo {
+                    nice,
+                    memory_growth,
+                    cpu_trend,
+                    cpu_time,
+                    uptime,
+                    swap: nice.map(|nice| nice.unsigned_abs() as u64),
+                    oom_score: cpu_time.map(|time| time as u32),
+                    gpu: cpu_trend,
+                    gpu_memory: memory,
+                    growth_rate: cpu_trend,
+                    cpu_delta: cpu_trend,
+                    memory_delta: memory_growth,
+                    container: nice.map(|nice| nice.to_string()),
+                    ..mock::process(pid, &name, &name, memory, cpu)
+                })
+                .collect()
+        })
+    }
+
+    proptest! {
+        // antisymmetric and transitive on every triple, i.e. a total order
+        #[test]
+        fn compare_is_a_total_order(processes in processes(), column in column()) {
+            for a in &processes {
+                prop_assert_eq!(compare(a, a, column), Ordering::Equal);
+                for b in &processes {
+                    prop_assert_eq!(compare(a, b, column), compare(b, a, column).reverse());
+                    for c in &processes {
+                        if compare(a, b, column) != Ordering::Greater && compare(b, c, column) != Ordering::Greater {
+                            prop_assert_ne!(compare(a, c, column), Ordering::Greater);
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn sort_orders_adjacent_rows(
+            mut processes in processes(),
+            column in column(),
+            secondary in column(),
+            ascending in any::<bool>(),
+        ) {
+            let count = processes.len();
+            sort(&mut processes, column, ascending, secondary);
+            prop_assert_eq!(processes.len(), count);
+            for pair in processes.windows(2) {
+                let order = compare(&pair[0], &pair[1], column);
+                prop_assert_ne!(order, if ascending { Ordering::Greater } else { Ordering::Less });
+                // ties fall back to the secondary column, then the pid
+                if order == Ordering::Equal {
+                    let tiebreak = compare(&pair[0], &pair[1], secondary).then(pair[0].pid.cmp(&pair[1].pid));
+                    prop_assert_ne!(tiebreak, Ordering::Greater);
+                }
+            }
+        }
+
+        // the order doesn't depend on the order rows arrived in, so refreshes don't reshuffle ties
+        #[test]
+        fn sort_is_deterministic(processes in processes(), column in column(), secondary in column()) {
+            let mut forward = processes.clone();
+            let mut backward = processes;
+            backward.reverse();
+            sort(&mut forward, column, false, secondary);
+            sort(&mut backward, column, false, secondary);
+            let pids = |processes: &[ProcessInfo]| processes.iter().map(|process| process.pid).collect::<Vec<_>>();
+            prop_assert_eq!(pids(&forward), pids(&backward));
+        }
+
+        // descending is ascending reversed, up to rows that compare equal
+        #[test]
+        fn descending_mirrors_ascending(processes in processes(), column in column(), secondary in column()) {
+            let mut ascending = processes.clone();
+            let mut descending = processes;
+            sort(&mut ascending, column, true, secondary);
+            sort(&mut descending, column, false, secondary);
+            descending.reverse();
+            for (a, b) in ascending.iter().zip(&descending) {
+                prop_assert_eq!(compare(a, b, column), Ordering::Equal);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+// per-process details only linux exposes, read straight from /proc
+//
+// every function returns None on other platforms or when the file can't be read, e.g. for
+// processes of other users or ones that exited since the last refresh
+use serde::{Deserialize, Serialize};
+
+// splits a /proc/<pid>/stat (or task/<tid>/stat) line into the comm and the fields after it,
+// starting at the state field; comm may contain spaces and parentheses so the split is on the last ')'
+pub fn parse_stat(stat: &str) -> Option<(String, Vec<String>)> {
+    let (head, rest) = stat.rsplit_once(')')?;
+    let (_, comm) = head.split_once('(')?;
+    Some((comm.to_string(), rest.split_whitespace().map(str::to_string).collect()))
+}
+
+#[cfg(target_os = "linux")]
+fn stat_fields(pid: u32) -> Option<Vec<String>> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat(&stat).map(|(_, fields)| fields)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn stat_fields(_pid: u32) -> Option<Vec<String>> {
+    None
+}
+
+// utime + stime from the fields returned by `parse_stat`, in clock ticks
+pub fn cpu_ticks(fields: &[String]) -> Option<u64> {
+    // utime and stime are fields 14 and 15 of the stat line
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+// cpu time spent in user and kernel mode, in seconds
+pub fn cpu_time(pid: u32) -> Option<u64> {
+    Some(cpu_ticks(&stat_fields(pid)?)? / clock_ticks()?)
+}
+
+// clock ticks per second used by the tick counts in /proc
+#[cfg(unix)]
+pub fn clock_ticks() -> Option<u64> {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    (ticks > 0).then_some(ticks as u64)
+}
+
+#[cfg(not(unix))]
+pub fn clock_ticks() -> Option<u64> {
+    None
+}
+
+// resident memory shared with other processes (file mappings and shared memory), in bytes
+#[cfg(target_os = "linux")]
+pub fn shared_memory(pid: u32) -> Option<u64> {
+    let statm = std::fs::read_to_string(format!("/proc/{}/statm", pid)).ok()?;
+    Some(statm_shared_pages(&statm)? * page_size()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn shared_memory(_pid: u32) -> Option<u64> {
+    None
+}
+
+// the third field of /proc/<pid>/statm, counted in pages
+pub fn statm_shared_pages(statm: &str) -> Option<u64> {
+    statm.split_whitespace().nth(2)?.parse().ok()
+}
+
+// memory of the process swapped out, VmSwap in /proc/<pid>/status, in bytes. kernel threads
+// have no VmSwap line
+#[cfg(target_os = "linux")]
+pub fn swap(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    Some(status_swap_kb(&status)? * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn swap(_pid: u32) -> Option<u64> {
+    None
+}
+
+// "VmSwap:\t    1234 kB"
+pub fn status_swap_kb(status: &str) -> Option<u64> {
+    status.lines().find_map(|line| line.strip_prefix("VmSwap:"))?.trim().strip_suffix("kB")?.trim().parse().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn page_size() -> Option<u64> {
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    (size > 0).then_some(size as u64)
+}
+
+// scheduler counters for the advanced columns, only read in builds with the `proc-counters`
+// feature since it is three more files per process and refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counters {
+    // context switches the process asked for (waiting on io or a lock) and ones forced by
+    // the scheduler (time slice used up)
+    pub voluntary_switches: u64,
+    pub involuntary_switches: u64,
+    pub threads: u32,
+    // time spent waiting for block io, needs delay accounting (delayacct), in milliseconds
+    pub io_wait: u64,
+    // time spent runnable but waiting for a cpu, in milliseconds
+    pub run_queue_wait: u64,
+}
+
+#[cfg(target_os = "linux")]
+pub fn counters(pid: u32) -> Option<Counters> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let (voluntary_switches, involuntary_switches, threads) = status_counters(&status)?;
+    let io_wait = stat_fields(pid)
+        .and_then(|fields| Some(io_wait_ticks(&fields)? * 1000 / clock_ticks()?))
+        .unwrap_or_default();
+    let run_queue_wait = std::fs::read_to_string(format!("/proc/{}/schedstat", pid))
+        .ok()
+        .and_then(|schedstat| schedstat_wait_ns(&schedstat))
+        .map(|ns| ns / 1_000_000)
+        .unwrap_or_default();
+    Some(Counters { voluntary_switches, involuntary_switches, threads, io_wait, run_queue_wait })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn counters(_pid: u32) -> Option<Counters> {
+    None
+}
+
+// (voluntary_ctxt_switches, nonvoluntary_ctxt_switches, Threads) from /proc/<pid>/status
+pub fn status_counters(status: &str) -> Option<(u64, u64, u32)> {
+    let value = |key: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+    Some((value("voluntary_ctxt_switches")?, value("nonvoluntary_ctxt_switches")?, value("Threads")? as u32))
+}
+
+// delayacct_blkio_ticks, field 42 of the stat line, from the fields returned by `parse_stat`
+pub fn io_wait_ticks(fields: &[String]) -> Option<u64> {
+    fields.get(39)?.parse().ok()
+}
+
+// the second field of /proc/<pid>/schedstat, run queue wait in nanoseconds
+pub fn schedstat_wait_ns(schedstat: &str) -> Option<u64> {
+    schedstat.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_come_from_status_stat_and_schedstat() {
+        let status = "Name:\tcargo\nThreads:\t12\nvoluntary_ctxt_switches:\t340\nnonvoluntary_ctxt_switches:\t7\n";
+        assert_eq!(status_counters(status), Some((340, 7, 12)));
+        assert_eq!(status_counters("Name:\tgone\n"), None);
+        assert_eq!(status_swap_kb("Name:\tcargo\nVmSwap:\t    2048 kB\nThreads:\t12\n"), Some(2048));
+        assert_eq!(status_swap_kb("Name:\tkthreadd\n"), None);
+        let stat = format!("42 (my (odd) prog) S {} 250 0", vec!["0"; 38].join(" "));
+        let (name, fields) = parse_stat(&stat).unwrap();
+        assert_eq!(name, "my (odd) prog");
+        assert_eq!(io_wait_ticks(&fields), Some(250));
+        assert_eq!(schedstat_wait_ns("81234 5000000 19\n"), Some(5_000_000));
+    }
+
+    #[test]
+    fn shared_pages_are_the_third_statm_field() {
+        assert_eq!(statm_shared_pages("5000 1200 300 10 0 900 0\n"), Some(300));
+        assert_eq!(statm_shared_pages("5000 1200"), None);
+        assert_eq!(statm_shared_pages("5000 1200 x"), None);
+    }
+}
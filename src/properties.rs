@@ -0,0 +1,142 @@
+// properties dialog - everything known about one process in one place, like the properties
+// window of the windows task manager. opened by double clicking or right clicking a row
+use chrono::{DateTime, Local};
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+use sysinfo::{Pid, System};
+
+use crate::format::{self, format_bytes, format_duration};
+use crate::process::ProcessInfo;
+use crate::Message;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Properties {
+    pub pid: u32,
+    pub name: String,
+    // titled groups of (label, value), read again on every process refresh while open
+    pub sections: Vec<(&'static str, Vec<(&'static str, String)>)>,
+}
+
+fn or_na(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("n/a"))
+}
+
+#[cfg(target_os = "linux")]
+fn read_status(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_status(_pid: u32) -> Option<String> {
+    None
+}
+
+// a "Name:   value" line of /proc/<pid>/status, e.g. the capability sets as hex masks
+fn status_field(status: Option<&str>, name: &str) -> Option<String> {
+    status?
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+        .map(|value| value.trim().to_string())
+}
+
+// a size from /proc/<pid>/status, which reports them in kB
+fn status_bytes(status: Option<&str>, name: &str) -> Option<String> {
+    let kb: f64 = status_field(status, name)?.trim_end_matches("kB").trim().parse().ok()?;
+    Some(format_bytes(kb * 1024.0))
+}
+
+impl Properties {
+    // None once the process is gone from the refreshed system
+    pub fn read(system: &System, info: &ProcessInfo) -> Option<Properties> {
+        let process = system.process(Pid::from_u32(info.pid))?;
+        let status = read_status(info.pid);
+        let status = status.as_deref();
+        let disk = process.disk_usage();
+        let started = DateTime::from_timestamp(process.start_time() as i64, 0)
+            .map(|start| format::date_time(&start.with_timezone(&Local)));
+        let identity = vec![
+            ("PID", info.pid.to_string()),
+            ("Parent PID", or_na(info.ppid.map(|ppid| ppid.to_string()))),
+            ("Name", info.name.clone()),
+            ("User", info.user.clone()),
+            ("Session", or_na(process.session_id().map(|session| session.to_string()))),
+            ("Command line", info.cmd.clone()),
+            ("Executable", or_na(info.exe.as_ref().map(|exe| exe.display().to_string()))),
+            ("Working directory", or_na(info.cwd.as_ref().map(|cwd| cwd.display().to_string()))),
+        ];
+        let resources = vec![
+            ("Resident memory", format_bytes(process.memory() as f64)),
+            ("Virtual memory", format_bytes(process.virtual_memory() as f64)),
+            ("Anonymous", or_na(status_bytes(status, "RssAnon"))),
+            ("File backed", or_na(status_bytes(status, "RssFile"))),
+            ("Shared memory", or_na(status_bytes(status, "RssShmem"))),
+            ("Swapped", or_na(status_bytes(status, "VmSwap"))),
+            ("CPU", format!("{}%", format::decimal(f64::from(info.cpu), 1))),
+            ("CPU time", or_na(info.cpu_time.map(format_duration))),
+            ("Disk read", format_bytes(disk.total_read_bytes as f64)),
+            ("Disk written", format_bytes(disk.total_written_bytes as f64)),
+        ];
+        let security = vec![
+            ("UID", or_na(process.user_id().map(|uid| uid.to_string()))),
+            ("Effective UID", or_na(process.effective_user_id().map(|uid| uid.to_string()))),
+            ("GID", or_na(process.group_id().map(|gid| gid.to_string()))),
+            ("Effective GID", or_na(process.effective_group_id().map(|gid| gid.to_string()))),
+            ("Elevated", String::from(if info.elevated { "yes" } else { "no" })),
+            ("Effective capabilities", or_na(status_field(status, "CapEff"))),
+            ("Permitted capabilities", or_na(status_field(status, "CapPrm"))),
+            ("Bounding capabilities", or_na(status_field(status, "CapBnd"))),
+        ];
+        let lifecycle = vec![
+            ("Started", or_na(started)),
+            ("Running for", format_duration(process.run_time())),
+            ("State", process.status().to_string()),
+            ("Priority", or_na(info.nice.map(|nice| nice.to_string()))),
+        ];
+        Some(Properties {
+            pid: info.pid,
+            name: info.name.clone(),
+            sections: vec![("Identity", identity), ("Resources", resources), ("Security", security), ("Lifecycle", lifecycle)],
+        })
+    }
+}
+
+// construct the properties dialog, one column per section
+pub fn view(properties: &Properties) -> Element<'_, Message> {
+    let sections = properties.sections.iter().fold(Row::new().spacing(30), |row, (title, fields)| {
+        let column = fields.iter().fold(Column::new().spacing(5).push(Text::new(*title)), |column, (label, value)| {
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(*label).width(Length::Fixed(170.0)))
+                    .push(Text::new(value.as_str()))
+            )
+        });
+        row.push(column.width(Length::Fill))
+    });
+    Column::new()
+        .spacing(10)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(format!("Properties of {} ({})", properties.name, properties.pid)))
+                .push(Button::new("Close").on_press(Message::CloseProperties))
+        )
+        .push(Scrollable::new(sections).height(Length::Fixed(300.0)))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_status_fields() {
+        let status = "Name:\tbash\nRssAnon:\t    2048 kB\nCapEff:\t0000000000000000\n";
+        assert_eq!(status_field(Some(status), "CapEff").as_deref(), Some("0000000000000000"));
+        assert_eq!(status_bytes(Some(status), "RssAnon"), Some(format_bytes(2048.0 * 1024.0)));
+        assert_eq!(status_field(Some(status), "Cap"), None);
+        assert_eq!(status_field(None, "Name"), None);
+    }
+}
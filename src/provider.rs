@@ -0,0 +1,151 @@
+// where the process table gets its rows from - sysinfo normally, an agent on another machine
+// when one is selected (see remote.rs), canned data in tests
+use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind, Users};
+
+use crate::process::{self, ProcessInfo};
+
+// what a pid stands for: a pid can be handed to a new process once its owner exits, but the
+// new one won't have the same name and start time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    // seconds since the epoch, 0 when unknown
+    pub start_time: u64,
+}
+
+impl Identity {
+    pub fn of(process: &ProcessInfo) -> Identity {
+        Identity { name: process.name.clone(), start_time: process.start_time }
+    }
+
+    // start times are only compared when both are known, e.g. an older agent doesn't send them
+    pub fn is(&self, other: &Identity) -> bool {
+        self.name == other.name && (self.start_time == 0 || other.start_time == 0 || self.start_time == other.start_time)
+    }
+}
+
+pub trait ProcessProvider {
+    // refreshes and returns every process, `system` is shared with the rest of the app
+    fn collect(&mut self, system: &mut System, users: &Users) -> Vec<ProcessInfo>;
+    // sends a kill to the process, true if it was delivered
+    fn kill(&mut self, system: &System, pid: u32) -> bool;
+    // what the pid is right now, read again rather than taken from the last table, None once
+    // no process has it
+    fn identify(&mut self, pid: u32) -> Option<Identity>;
+    // why the last collection or kill failed, only remote tables can fail
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+// reads the live processes of this machine
+pub struct SystemProvider;
+
+impl ProcessProvider for SystemProvider {
+    fn collect(&mut self, system: &mut System, users: &Users) -> Vec<ProcessInfo> {
+        // what refresh_processes reads plus the working directory, which can change at any time
+        system.refresh_processes_specifics(
+            ProcessRefreshKind::new()
+                .with_memory()
+                .with_cpu()
+                .with_disk_usage()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cwd(UpdateKind::Always),
+        );
+        process::collect(system, users)
+    }
+
+    fn kill(&mut self, system: &System, pid: u32) -> bool {
+        system
+            .process(Pid::from(pid as usize))
+            .is_some_and(|process| process.kill())
+    }
+
+    // a system of its own, so the pid is read from scratch instead of updating a cached entry
+    fn identify(&mut self, pid: u32) -> Option<Identity> {
+        let pid = Pid::from(pid as usize);
+        let mut system = System::new();
+        system.refresh_process_specifics(pid, ProcessRefreshKind::new());
+        system.process(pid).map(|process| Identity { name: process.name().to_string(), start_time: process.start_time() })
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // fixed process list, killed pids are removed from it and recorded in `killed`
+    pub struct MockProvider {
+        pub processes: Rc<RefCell<Vec<ProcessInfo>>>,
+        pub killed: Rc<RefCell<Vec<u32>>>,
+    }
+
+    impl ProcessProvider for MockProvider {
+        fn collect(&mut self, _system: &mut System, _users: &Users) -> Vec<ProcessInfo> {
+            self.processes.borrow().clone()
+        }
+
+        fn kill(&mut self, _system: &System, pid: u32) -> bool {
+            let mut processes = self.processes.borrow_mut();
+            let before = processes.len();
+            processes.retain(|process| process.pid != pid);
+            self.killed.borrow_mut().push(pid);
+            processes.len() < before
+        }
+
+        fn identify(&mut self, pid: u32) -> Option<Identity> {
+            self.processes.borrow().iter().find(|process| process.pid == pid).map(Identity::of)
+        }
+    }
+
+    // a process with the given values and everything else empty
+    pub fn process(pid: u32, name: &str, user: &str, memory: u64, cpu: f32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid: None,
+            name: name.to_string(),
+            user: user.to_string(),
+            cmd: name.to_string(),
+            exe: None,
+            cwd: None,
+            memory,
+            virtual_memory: memory,
+            shared_memory: None,
+            swap: None,
+            oom_score: None,
+            cpu,
+            cpu_raw: cpu,
+            nice: None,
+            cpu_time: None,
+            uptime: 0,
+            start_time: 0,
+            container: None,
+            cgroup: None,
+            gpu: 0.0,
+            gpu_memory: 0,
+            memory_growth: 0,
+            cpu_trend: 0.0,
+            cpu_delta: 0.0,
+            memory_delta: 0,
+            growth_rate: 0.0,
+            suspected_leak: false,
+            system: false,
+            elevated: false,
+            stuck: false,
+            not_responding: false,
+            access_denied: false,
+            counters: None,
+            window_title: None,
+            description: None,
+            energy: None,
+            napping: None,
+            app: false,
+            custom: Default::default(),
+            open_paths: Vec::new(),
+            listening_ports: Vec::new(),
+        }
+    }
+}
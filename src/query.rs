@@ -0,0 +1,404 @@
+// search box query syntax - plain words, regexes and fielded conditions, parsed once into a
+// small AST that the filter evaluates per row
+//
+//   chrome                 name, user or container contains "chrome", or the pid is 42 for "42",
+//                          or the name fuzzily matches it - "ffx" finds firefox, see fuzzy.rs
+//   /chrom.*/              the same places match the regex
+//   cpu>50 mem>=1g         numeric fields compared, memory in MB unless it has a k/m/g/t suffix
+//   user:alice name~^node  ":" contains, "=" is exact, "~" matches a regex, all ignoring case
+//   "web content"          quotes keep spaces in a word or a value
+//   :8080                  listens on the port
+//   file:app.log /etc/hosts  has a file open whose path contains it, or is it for "file="
+//   !root                  "!" negates a condition
+//   firefox or chrome      conditions are and-ed, "or" (or "|") separates alternatives
+use regex::{Regex, RegexBuilder};
+
+use crate::fuzzy;
+use crate::process::ProcessInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberField {
+    Cpu,
+    Memory,
+    VirtualMemory,
+    Gpu,
+    Pid,
+    Ppid,
+    Nice,
+    Uptime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Name,
+    User,
+    Cmd,
+    Exe,
+    Container,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    Contains,
+    Matches,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    // lowercase
+    Text(String),
+    Pattern(Regex),
+    Number(NumberField, Op, f64),
+    // lowercase, for Contains, Equal and NotEqual
+    Field(TextField, Op, String),
+    FieldPattern(TextField, Regex),
+    // looked up in the open descriptors, see holders.rs
+    Port(u16),
+    // lowercase, for Contains, Equal and NotEqual
+    File(Op, String),
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+// alternatives of and-ed terms, an empty query matches everything
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    alternatives: Vec<Vec<Term>>,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Query, String> {
+        let mut alternatives = vec![Vec::new()];
+        for word in words(text)? {
+            if word.eq_ignore_ascii_case("or") || word == "|" {
+                alternatives.push(Vec::new());
+                continue;
+            }
+            let (negated, word) = match word.strip_prefix('!') {
+                Some(rest) if !rest.is_empty() => (true, rest.to_string()),
+                _ => (false, word),
+            };
+            let condition = condition(&word)?;
+            if let Some(terms) = alternatives.last_mut() {
+                terms.push(Term { negated, condition });
+            }
+        }
+        alternatives.retain(|terms| !terms.is_empty());
+        Ok(Query { alternatives })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.is_empty()
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        self.is_empty()
+            || self
+                .alternatives
+                .iter()
+                .any(|terms| terms.iter().all(|term| term.condition.matches(process) != term.negated))
+    }
+
+    // true while a condition needs `open_paths` or `listening_ports`
+    pub fn looks_up_holders(&self) -> bool {
+        self.alternatives
+            .iter()
+            .flatten()
+            .any(|term| matches!(term.condition, Condition::Port(_) | Condition::File(..)))
+    }
+
+    // words searched for in the name, the ones fuzzy matching ranks by
+    fn words(&self) -> impl Iterator<Item = &str> {
+        self.alternatives.iter().flatten().filter(|term| !term.negated).filter_map(|term| match &term.condition {
+            Condition::Text(needle) => Some(needle.as_str()),
+            _ => None,
+        })
+    }
+
+    // true if the rows should be ordered by `score` instead of the sort columns
+    pub fn ranks(&self) -> bool {
+        self.words().next().is_some()
+    }
+
+    // how well the words match the name, higher is better
+    pub fn score(&self, name: &str) -> i32 {
+        self.words().filter_map(|word| fuzzy::find(word, name)).map(|found| found.score).sum()
+    }
+
+    // char indices of the name the words matched, for highlighting
+    pub fn name_matches(&self, name: &str) -> Vec<usize> {
+        let mut chars: Vec<usize> = self.words().filter_map(|word| fuzzy::find(word, name)).flat_map(|found| found.chars).collect();
+        chars.sort_unstable();
+        chars.dedup();
+        chars
+    }
+}
+
+// splits on whitespace, a double-quoted part keeps its spaces and loses the quotes
+fn words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("unclosed quote"));
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+fn regex(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| format!("invalid regex /{}/: {}", pattern, err))
+}
+
+fn condition(word: &str) -> Result<Condition, String> {
+    if let Some(pattern) = word.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+        return regex(pattern).map(Condition::Pattern);
+    }
+    // an absolute path like /etc/hosts, a single slash is left to the regexes being typed
+    if word.starts_with('/') && word[1..].contains('/') {
+        return Ok(Condition::File(Op::Contains, word.to_lowercase()));
+    }
+    let field_end = word.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(word.len());
+    let (field, rest) = word.split_at(field_end);
+    let Some((op, value)) = operator(rest) else {
+        return Ok(Condition::Text(word.to_lowercase()));
+    };
+    if field.is_empty() && op == Op::Contains {
+        if let Ok(port) = value.parse() {
+            return Ok(Condition::Port(port));
+        }
+    }
+    if field.eq_ignore_ascii_case("file") {
+        return match op {
+            Op::Contains | Op::Equal | Op::NotEqual => Ok(Condition::File(op, value.to_lowercase())),
+            _ => Err(format!("{} is a path, compare it with : or =", word)),
+        };
+    }
+    if let Some(field) = number_field(field) {
+        let op = match op {
+            Op::Contains => Op::Equal,
+            Op::Matches => return Err(format!("{} is a number, compare it with < > = instead of ~", word)),
+            op => op,
+        };
+        return number(field, value).map(|value| Condition::Number(field, op, value));
+    }
+    if let Some(field) = text_field(field) {
+        return match op {
+            Op::Matches => regex(value).map(|regex| Condition::FieldPattern(field, regex)),
+            Op::Contains | Op::Equal | Op::NotEqual => Ok(Condition::Field(field, op, value.to_lowercase())),
+            _ => Err(format!("{} is text, compare it with : = or ~", word)),
+        };
+    }
+    // e.g. "C:\\" or "a=b" in a name, not a field
+    Ok(Condition::Text(word.to_lowercase()))
+}
+
+// the operator at the start and the value after it
+fn operator(rest: &str) -> Option<(Op, &str)> {
+    const OPERATORS: [(&str, Op); 8] = [
+        (">=", Op::GreaterOrEqual),
+        ("<=", Op::LessOrEqual),
+        ("!=", Op::NotEqual),
+        (">", Op::Greater),
+        ("<", Op::Less),
+        ("=", Op::Equal),
+        (":", Op::Contains),
+        ("~", Op::Matches),
+    ];
+    OPERATORS
+        .iter()
+        .find_map(|&(symbol, op)| rest.strip_prefix(symbol).map(|value| (op, value)))
+        .filter(|(_, value)| !value.is_empty())
+}
+
+fn number_field(name: &str) -> Option<NumberField> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "cpu" => NumberField::Cpu,
+        "mem" | "memory" => NumberField::Memory,
+        "vmem" => NumberField::VirtualMemory,
+        "gpu" => NumberField::Gpu,
+        "pid" => NumberField::Pid,
+        "ppid" => NumberField::Ppid,
+        "nice" => NumberField::Nice,
+        "uptime" => NumberField::Uptime,
+        _ => return None,
+    })
+}
+
+fn text_field(name: &str) -> Option<TextField> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "name" => TextField::Name,
+        "user" => TextField::User,
+        "cmd" => TextField::Cmd,
+        "exe" => TextField::Exe,
+        "container" => TextField::Container,
+        _ => return None,
+    })
+}
+
+// memory in MB with an optional k/m/g/t suffix, uptime in seconds with s/m/h/d, cpu and gpu
+// with an optional %
+fn number(field: NumberField, value: &str) -> Result<f64, String> {
+    let lower = value.to_ascii_lowercase();
+    let (digits, scale) = match field {
+        NumberField::Memory | NumberField::VirtualMemory => match lower.trim_end_matches('b').char_indices().last() {
+            Some((at, 'k')) => (&lower[..at], 1.0 / 1024.0),
+            Some((at, 'm')) => (&lower[..at], 1.0),
+            Some((at, 'g')) => (&lower[..at], 1024.0),
+            Some((at, 't')) => (&lower[..at], 1024.0 * 1024.0),
+            _ => (lower.as_str(), 1.0),
+        },
+        NumberField::Uptime => match lower.char_indices().last() {
+            Some((at, 's')) => (&lower[..at], 1.0),
+            Some((at, 'm')) => (&lower[..at], 60.0),
+            Some((at, 'h')) => (&lower[..at], 3_600.0),
+            Some((at, 'd')) => (&lower[..at], 86_400.0),
+            _ => (lower.as_str(), 1.0),
+        },
+        NumberField::Cpu | NumberField::Gpu => (lower.trim_end_matches('%'), 1.0),
+        _ => (lower.as_str(), 1.0),
+    };
+    digits
+        .parse::<f64>()
+        .map(|number| number * scale)
+        .map_err(|_| format!("{} is not a number", value))
+}
+
+impl Condition {
+    fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Condition::Text(needle) => {
+                fuzzy::find(needle, &process.name).is_some()
+                    || process.user.to_lowercase().contains(needle)
+                    || process.container.as_ref().is_some_and(|container| container.to_lowercase().contains(needle))
+                    || process.pid.to_string() == *needle
+            }
+            Condition::Pattern(regex) => {
+                regex.is_match(&process.name)
+                    || regex.is_match(&process.user)
+                    || process.container.as_ref().is_some_and(|container| regex.is_match(container))
+            }
+            Condition::Number(field, op, value) => number_value(process, *field).is_some_and(|actual| compare(actual, *op, *value)),
+            Condition::Field(field, op, needle) => text_value(process, *field).is_some_and(|actual| compare_text(&actual, *op, needle)),
+            Condition::FieldPattern(field, regex) => text_value(process, *field).is_some_and(|actual| regex.is_match(&actual)),
+            Condition::Port(port) => process.listening_ports.contains(port),
+            Condition::File(Op::NotEqual, needle) => !process.open_paths.iter().any(|path| compare_text(path, Op::Equal, needle)),
+            Condition::File(op, needle) => process.open_paths.iter().any(|path| compare_text(path, *op, needle)),
+        }
+    }
+}
+
+fn number_value(process: &ProcessInfo, field: NumberField) -> Option<f64> {
+    Some(match field {
+        NumberField::Cpu => process.cpu as f64,
+        NumberField::Memory => process.memory as f64,
+        NumberField::VirtualMemory => process.virtual_memory as f64,
+        NumberField::Gpu => process.gpu as f64,
+        NumberField::Pid => process.pid as f64,
+        NumberField::Ppid => process.ppid? as f64,
+        NumberField::Nice => process.nice? as f64,
+        NumberField::Uptime => process.uptime as f64,
+    })
+}
+
+fn text_value(process: &ProcessInfo, field: TextField) -> Option<String> {
+    match field {
+        TextField::Name => Some(process.name.clone()),
+        TextField::User => Some(process.user.clone()),
+        TextField::Cmd => Some(process.cmd.clone()),
+        TextField::Exe => process.exe.as_ref().map(|exe| exe.display().to_string()),
+        TextField::Container => process.container.clone(),
+    }
+}
+
+fn compare_text(actual: &str, op: Op, needle: &str) -> bool {
+    let actual = actual.to_lowercase();
+    match op {
+        Op::Equal => actual == needle,
+        Op::NotEqual => actual != needle,
+        _ => actual.contains(needle),
+    }
+}
+
+fn compare(actual: f64, op: Op, value: f64) -> bool {
+    match op {
+        Op::Less => actual < value,
+        Op::LessOrEqual => actual <= value,
+        Op::Greater => actual > value,
+        Op::GreaterOrEqual => actual >= value,
+        Op::NotEqual => actual != value,
+        _ => actual == value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    fn matches(query: &str, process: &ProcessInfo) -> bool {
+        Query::parse(query).unwrap().matches(process)
+    }
+
+    #[test]
+    fn fielded_queries_regexes_and_alternatives() {
+        let node = ProcessInfo { cmd: String::from("node server.js"), ..mock::process(42, "node", "alice", 2048, 75.0) };
+        assert!(matches("cpu>50 mem>1g user:ali", &node));
+        assert!(!matches("cpu>50 mem>3g", &node));
+        assert!(matches("/^no.e$/ name~^NODE cmd:server", &node));
+        assert!(matches("!root pid=42", &node));
+        assert!(matches("firefox or nod", &node));
+        assert!(!matches("firefox | chrome", &node));
+        assert!(matches("uptime<1d", &node));
+        assert!(matches("\"no\" user=alice", &node));
+        assert!(Query::parse("").unwrap().is_empty());
+        assert!(Query::parse("name~(").is_err());
+        assert!(Query::parse("cpu>lots").is_err());
+        assert!(Query::parse("\"open").is_err());
+        let query = Query::parse("nde cpu>1").unwrap();
+        assert!(query.ranks() && matches("nde", &node));
+        assert_eq!(query.name_matches("node"), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn ports_and_open_files_look_up_the_holders() {
+        let server = ProcessInfo {
+            open_paths: vec![String::from("/var/log/App.log")],
+            listening_ports: vec![8080],
+            ..mock::process(42, "node", "alice", 2048, 75.0)
+        };
+        assert!(matches(":8080", &server) && !matches(":443", &server));
+        assert!(matches("file:app.log", &server) && matches("/var/log/app", &server));
+        assert!(matches("file=/var/log/app.log", &server) && !matches("file!=/var/log/app.log", &server));
+        assert!(Query::parse(":8080 or node").unwrap().looks_up_holders());
+        assert!(!Query::parse("/chrom").unwrap().looks_up_holders());
+        assert!(Query::parse("file>1").is_err());
+    }
+}
@@ -0,0 +1,486 @@
+// metrics recorder - appends machine and per-process samples to a local sqlite database
+//
+// off by default. once enabled, every process table refresh writes one row for the machine
+// and one per process, rows older than the retention are pruned about once an hour. the
+// inserts run on a writer thread with a connection of its own, which writes the samples that
+// piled up meanwhile in one transaction, so a slow disk doesn't hold up the window. the
+// history tab asks the database which processes used the most cpu or memory in a recent
+// window, links the ones still running to their row, and can export the raw samples of that
+// window as csv
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::format::{self, format_duration};
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// how often rows past the retention are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+// rows listed in the history tab
+const TOP_ROWS: usize = 20;
+
+// how long a connection waits for the other one's write to finish
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderConfig {
+    // appends a sample on every process table refresh
+    pub enabled: bool,
+    // days of samples kept in the database
+    pub retention_days: u32,
+    // database file, metrics.sqlite in the config directory when unset
+    pub path: Option<PathBuf>,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        RecorderConfig { enabled: false, retention_days: 7, path: None }
+    }
+}
+
+impl RecorderConfig {
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path.clone().or_else(|| Config::dir().map(|dir| dir.join("metrics.sqlite")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    // how far back the history tab looks
+    Hour,
+    SixHours,
+    Day,
+    Week,
+}
+
+impl Window {
+    pub const ALL: [Window; 4] = [Window::Hour, Window::SixHours, Window::Day, Window::Week];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Window::Hour => "Last hour",
+            Window::SixHours => "Last 6 hours",
+            Window::Day => "Last 24 hours",
+            Window::Week => "Last 7 days",
+        }
+    }
+
+    pub fn seconds(self) -> u64 {
+        match self {
+            Window::Hour => 3600,
+            Window::SixHours => 6 * 3600,
+            Window::Day => 24 * 3600,
+            Window::Week => 7 * 24 * 3600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rank {
+    // what the history tab orders the processes by
+    Cpu,
+    Memory,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 2] = [Rank::Cpu, Rank::Memory];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Rank::Cpu => "Most CPU",
+            Rank::Memory => "Most memory",
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Rank::Cpu => "cpu_time",
+            Rank::Memory => "memory",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Peak {
+    // highest memory a process reached within the window and when
+    pub pid: u32,
+    pub name: String,
+    pub user: String,
+    pub memory: u64,
+    pub at: u64,
+    pub average_cpu: f32,
+    // cpu seconds used within the window, the average over the time it was sampled
+    pub cpu_time: u64,
+}
+
+pub struct Recorder {
+    // reads for the history tab, the writes go through the writer thread
+    connection: Connection,
+    samples: Sender<Write>,
+    // failures of the writer thread, reported on the next record
+    errors: Receiver<String>,
+}
+
+// what the writer thread is sent
+enum Write {
+    Sample(Sample),
+    // answers once everything sent before is written
+    #[cfg(test)]
+    Flush(Sender<()>),
+}
+
+// one refresh, copied out of the table so the thread doesn't need it
+struct Sample {
+    at: u64,
+    cpu: f32,
+    memory_used: u64,
+    memory_total: u64,
+    swap_used: u64,
+    // pid, name, user, memory and cpu of each process
+    processes: Vec<(u32, String, String, u64, f32)>,
+}
+
+impl Recorder {
+    // opens or creates the database, creating its directory if needed, and starts the writer
+    pub fn open(config: &RecorderConfig) -> Result<Recorder, String> {
+        let path = config.path().ok_or("no config directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        let connection = Connection::open(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        connection.busy_timeout(BUSY_TIMEOUT).map_err(|err| err.to_string())?;
+        // readers don't block the writer and the other way round
+        connection.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(())).map_err(|err| err.to_string())?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS system_samples (
+                     at INTEGER NOT NULL,
+                     cpu REAL NOT NULL,
+                     memory_used INTEGER NOT NULL,
+                     memory_total INTEGER NOT NULL,
+                     swap_used INTEGER NOT NULL
+                 );
+                 CREATE TABLE IF NOT EXISTS process_samples (
+                     at INTEGER NOT NULL,
+                     pid INTEGER NOT NULL,
+                     name TEXT NOT NULL,
+                     user TEXT NOT NULL,
+                     memory INTEGER NOT NULL,
+                     cpu REAL NOT NULL
+                 );
+                 CREATE INDEX IF NOT EXISTS system_samples_at ON system_samples (at);
+                 CREATE INDEX IF NOT EXISTS process_samples_at ON process_samples (at);",
+            )
+            .map_err(|err| err.to_string())?;
+        let writer = Connection::open(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        writer.busy_timeout(BUSY_TIMEOUT).map_err(|err| err.to_string())?;
+        let retention = Duration::from_secs(u64::from(config.retention_days.max(1)) * 24 * 3600);
+        let (samples, received) = mpsc::channel();
+        let (failed, errors) = mpsc::channel();
+        thread::spawn(move || write_samples(writer, retention, received, failed));
+        Ok(Recorder { connection, samples, errors })
+    }
+
+    // queues one machine sample and one sample per process, all stamped with the same time
+    pub fn record(&self, system: &System, processes: &[ProcessInfo]) -> Result<(), String> {
+        if let Ok(err) = self.errors.try_recv() {
+            return Err(err);
+        }
+        let sample = Sample {
+            at: unix_now(),
+            cpu: system.global_cpu_info().cpu_usage(),
+            memory_used: system.used_memory(),
+            memory_total: system.total_memory(),
+            swap_used: system.used_swap(),
+            processes: processes
+                .iter()
+                .map(|process| (process.pid, process.name.clone(), process.user.clone(), process.memory, process.cpu_raw))
+                .collect(),
+        };
+        self.samples.send(Write::Sample(sample)).map_err(|_| String::from("the metrics writer stopped"))
+    }
+
+    // waits until the writer thread has caught up
+    #[cfg(test)]
+    fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        self.samples.send(Write::Flush(done)).unwrap();
+        wait.recv().unwrap();
+    }
+
+    // processes that used the most within the window. `at` is when the memory peak was first
+    // sampled, taken from the peak's own row. the cpu time counts one interval for a process
+    // sampled once, the time since it was sampled first otherwise
+    pub fn peaks(&self, window: Window, rank: Rank) -> Result<Vec<Peak>, String> {
+        let since = unix_now().saturating_sub(window.seconds());
+        let mut query = self
+            .connection
+            .prepare(&format!(
+                "WITH ranked AS (
+                     SELECT pid, name, user, memory, at,
+                            ROW_NUMBER() OVER (PARTITION BY pid, name ORDER BY memory DESC, at) AS place
+                     FROM process_samples WHERE at >= ?1
+                 ),
+                 totals AS (
+                     SELECT pid, name, AVG(cpu) AS average_cpu, AVG(cpu) / 100.0 * MAX(MAX(at) - MIN(at), 1) AS cpu_time
+                     FROM process_samples WHERE at >= ?1 GROUP BY pid, name
+                 )
+                 SELECT ranked.pid, ranked.name, ranked.user, ranked.memory, ranked.at, totals.average_cpu, totals.cpu_time
+                 FROM ranked JOIN totals ON totals.pid = ranked.pid AND totals.name = ranked.name
+                 WHERE ranked.place = 1 ORDER BY {} DESC LIMIT ?2",
+                rank.order_by()
+            ))
+            .map_err(|err| err.to_string())?;
+        let rows = query
+            .query_map(params![since, TOP_ROWS], |row| {
+                Ok(Peak {
+                    pid: row.get(0)?,
+                    name: row.get(1)?,
+                    user: row.get(2)?,
+                    memory: row.get(3)?,
+                    at: row.get(4)?,
+                    average_cpu: row.get::<_, f64>(5)? as f32,
+                    cpu_time: row.get::<_, f64>(6)?.round() as u64,
+                })
+            })
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|err| err.to_string())
+    }
+
+    // writes every process sample within the window as csv, returns the number of rows
+    pub fn export_csv(&self, window: Window, path: &Path) -> Result<usize, String> {
+        let since = unix_now().saturating_sub(window.seconds());
+        let mut query = self
+            .connection
+            .prepare("SELECT at, pid, name, user, memory, cpu FROM process_samples WHERE at >= ?1 ORDER BY at, pid")
+            .map_err(|err| err.to_string())?;
+        let mut rows = query.query(params![since]).map_err(|err| err.to_string())?;
+        let mut csv = String::from("at,pid,name,user,memory_mb,cpu\n");
+        let mut count = 0;
+        while let Some(row) = rows.next().map_err(|err| err.to_string())? {
+            let at: u64 = row.get(0).map_err(|err| err.to_string())?;
+            let pid: u32 = row.get(1).map_err(|err| err.to_string())?;
+            let name: String = row.get(2).map_err(|err| err.to_string())?;
+            let user: String = row.get(3).map_err(|err| err.to_string())?;
+            let memory: u64 = row.get(4).map_err(|err| err.to_string())?;
+            let cpu: f64 = row.get(5).map_err(|err| err.to_string())?;
+            csv.push_str(&format!("{},{},{},{},{},{:.1}\n", at, pid, csv_field(&name), csv_field(&user), memory, cpu));
+            count += 1;
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        fs::write(path, csv).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(count)
+    }
+}
+
+// the writer thread, until the recorder is dropped. each round writes every sample waiting in
+// one transaction
+fn write_samples(mut connection: Connection, retention: Duration, received: Receiver<Write>, failed: Sender<String>) {
+    let mut last_prune: Option<Instant> = None;
+    while let Ok(first) = received.recv() {
+        let mut samples = Vec::new();
+        #[cfg(test)]
+        let mut flushed = Vec::new();
+        for write in std::iter::once(first).chain(received.try_iter()) {
+            match write {
+                Write::Sample(sample) => samples.push(sample),
+                #[cfg(test)]
+                Write::Flush(done) => flushed.push(done),
+            }
+        }
+        let result = insert(&mut connection, &samples).and_then(|()| prune(&connection, retention, &mut last_prune));
+        if let Err(err) = result {
+            let _ = failed.send(err);
+        }
+        #[cfg(test)]
+        for done in flushed {
+            let _ = done.send(());
+        }
+    }
+}
+
+fn insert(connection: &mut Connection, samples: &[Sample]) -> Result<(), String> {
+    let transaction = connection.transaction().map_err(|err| err.to_string())?;
+    {
+        let mut system = transaction
+            .prepare_cached("INSERT INTO system_samples (at, cpu, memory_used, memory_total, swap_used) VALUES (?1, ?2, ?3, ?4, ?5)")
+            .map_err(|err| err.to_string())?;
+        let mut process = transaction
+            .prepare_cached("INSERT INTO process_samples (at, pid, name, user, memory, cpu) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+            .map_err(|err| err.to_string())?;
+        for sample in samples {
+            system
+                .execute(params![sample.at, sample.cpu, sample.memory_used, sample.memory_total, sample.swap_used])
+                .map_err(|err| err.to_string())?;
+            for (pid, name, user, memory, cpu) in &sample.processes {
+                process.execute(params![sample.at, pid, name, user, memory, cpu]).map_err(|err| err.to_string())?;
+            }
+        }
+    }
+    transaction.commit().map_err(|err| err.to_string())
+}
+
+// deletes samples past the retention, at most once per prune interval
+fn prune(connection: &Connection, retention: Duration, last_prune: &mut Option<Instant>) -> Result<(), String> {
+    if last_prune.is_some_and(|last| last.elapsed() < PRUNE_INTERVAL) {
+        return Ok(());
+    }
+    *last_prune = Some(Instant::now());
+    let cutoff = unix_now().saturating_sub(retention.as_secs());
+    for table in ["system_samples", "process_samples"] {
+        connection
+            .execute(&format!("DELETE FROM {} WHERE at < ?1", table), params![cutoff])
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default()
+}
+
+// quotes a field containing a separator, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// construct the history tab layout, `peaks` is None while recording is off. `running` holds
+// the processes still in the table, by pid and name since pids get reused
+pub fn view<'a>(
+    window: Window,
+    rank: Rank,
+    peaks: Option<&'a Result<Vec<Peak>, String>>,
+    running: &HashSet<(u32, &str)>,
+    export_path: &str,
+) -> Element<'a, Message> {
+    let Some(peaks) = peaks else {
+        return Text::new("Recording is off, it can be turned on in the settings tab").into();
+    };
+    // the selected window and ranking are shown without a press handler so they read as active
+    let windows = Window::ALL.iter().fold(Row::new().spacing(10), |row, &option| {
+        let button = Button::new(option.label());
+        row.push(if option == window { button } else { button.on_press(Message::SelectHistoryWindow(option)) })
+    });
+    let ranks = Rank::ALL.iter().fold(Row::new().spacing(10), |row, &option| {
+        let button = Button::new(option.label());
+        row.push(if option == rank { button } else { button.on_press(Message::SelectHistoryRank(option)) })
+    });
+    let export = Row::new()
+        .spacing(10)
+        .push(
+            TextInput::new("csv file", export_path)
+                .on_input(Message::HistoryExportPathChanged)
+                .width(Length::FillPortion(3))
+        )
+        .push(Button::new("Export samples").on_press(Message::ExportHistory));
+    let table = match peaks {
+        Ok(peaks) if peaks.is_empty() => Column::new().push(Text::new("No samples recorded in this window yet")),
+        Ok(peaks) => {
+            let now = unix_now();
+            let header = Row::new()
+                .spacing(10)
+                .push(Text::new("PID").width(Length::FillPortion(1)))
+                .push(Text::new("Name").width(Length::FillPortion(2)))
+                .push(Text::new("User").width(Length::FillPortion(1)))
+                .push(Text::new("Peak memory (MB)").width(Length::FillPortion(1)))
+                .push(Text::new("Peak ago").width(Length::FillPortion(1)))
+                .push(Text::new("Avg CPU (%)").width(Length::FillPortion(1)))
+                .push(Text::new("CPU time").width(Length::FillPortion(1)))
+                .push(Text::new("").width(Length::Fixed(80.0)));
+            peaks.iter().enumerate().fold(Column::new().spacing(5).push(header), |column, (index, peak)| {
+                let show = Button::new("Show").width(Length::Fixed(80.0));
+                column.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new(format!("{}. {}", index + 1, peak.pid)).width(Length::FillPortion(1)))
+                        .push(Text::new(&peak.name).width(Length::FillPortion(2)))
+                        .push(Text::new(&peak.user).width(Length::FillPortion(1)))
+                        .push(Text::new(peak.memory.to_string()).width(Length::FillPortion(1)))
+                        .push(Text::new(format_duration(now.saturating_sub(peak.at))).width(Length::FillPortion(1)))
+                        .push(Text::new(format::decimal(f64::from(peak.average_cpu), 1)).width(Length::FillPortion(1)))
+                        .push(Text::new(format_duration(peak.cpu_time)).width(Length::FillPortion(1)))
+                        .push(if running.contains(&(peak.pid, peak.name.as_str())) { show.on_press(Message::ShowProcess(peak.pid)) } else { show })
+                )
+            })
+        }
+        Err(err) => Column::new().push(Text::new(format!("failed to query recorded samples: {}", err))),
+    };
+    Column::new()
+        .spacing(10)
+        .push(windows)
+        .push(ranks)
+        .push(export)
+        .push(Text::new(match rank {
+            Rank::Cpu => "Processes by cpu time used, the ones still running can be shown in the table",
+            Rank::Memory => "Processes by highest memory, the ones still running can be shown in the table",
+        }))
+        .push(Scrollable::new(table))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn peaks_keep_the_highest_sample() {
+        let path = std::env::temp_dir().join(format!("conborli-recorder-{}.sqlite", std::process::id()));
+        let config = RecorderConfig { enabled: true, retention_days: 1, path: Some(path.clone()) };
+        let recorder = Recorder::open(&config).unwrap();
+        let system = System::new();
+        recorder.record(&system, &[mock::process(1, "build", "dev", 100, 10.0), mock::process(2, "shell", "dev", 5, 0.0)]).unwrap();
+        recorder.record(&system, &[mock::process(1, "build", "dev", 900, 30.0)]).unwrap();
+        recorder.record(&system, &[mock::process(2, "shell", "dev", 5, 50.0)]).unwrap();
+        recorder.flush();
+        let peaks = recorder.peaks(Window::Hour, Rank::Memory).unwrap();
+        let by_cpu = recorder.peaks(Window::Hour, Rank::Cpu).unwrap();
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        assert_eq!(peaks.iter().map(|peak| (peak.pid, peak.memory)).collect::<Vec<_>>(), vec![(1, 900), (2, 5)]);
+        assert_eq!(by_cpu.iter().map(|peak| peak.pid).collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn peaks_are_dated_by_their_own_sample() {
+        let path = std::env::temp_dir().join(format!("conborli-recorder-peak-{}.sqlite", std::process::id()));
+        let config = RecorderConfig { enabled: true, retention_days: 1, path: Some(path.clone()) };
+        let recorder = Recorder::open(&config).unwrap();
+        let now = unix_now();
+        for (ago, memory, cpu) in [(30, 50, 10.0), (20, 700, 20.0), (10, 100, 30.0)] {
+            recorder
+                .connection
+                .execute("INSERT INTO process_samples (at, pid, name, user, memory, cpu) VALUES (?1, 3, 'leak', 'dev', ?2, ?3)", params![now - ago, memory, cpu])
+                .unwrap();
+        }
+        let peaks = recorder.peaks(Window::Hour, Rank::Memory).unwrap();
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        assert_eq!(peaks.len(), 1);
+        assert_eq!((peaks[0].memory, peaks[0].at), (700, now - 20));
+        assert_eq!((peaks[0].average_cpu, peaks[0].cpu_time), (20.0, 4));
+    }
+}
@@ -0,0 +1,211 @@
+// remote hosts - shows the process table of another machine running `conborli --agent`
+//
+// the agent is the http api in headless mode. a background thread polls its /processes at
+// the process refresh interval so a slow network never holds up the ui, the table keeps the
+// last good answer while the agent can't be reached. hosts are only shown while the settings
+// allow network access
+//
+// the api is plain http, so the token and the table would cross the network readable by
+// anyone on the way. only loopback addresses are connected to, other machines are reached
+// through a tunnel that encrypts, e.g. `ssh -L 7878:localhost:7878 build-server` and the
+// host 127.0.0.1:7878
+use iced::{
+    alignment,
+    widget::{Button, Row, Text, TextInput},
+    Element, Length,
+};
+use serde::{Deserialize, Serialize};
+use sysinfo::{System, Users};
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::process::ProcessInfo;
+use crate::provider::{Identity, ProcessProvider};
+use crate::Message;
+
+// connecting and every read or write give up after this long
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteHost {
+    // shown in the host switcher
+    pub name: String,
+    // address the agent listens on, a loopback one like 127.0.0.1:7878
+    pub addr: String,
+    // bearer token the agent was started with, only settable in the config file
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl RemoteHost {
+    pub fn new(addr: &str) -> RemoteHost {
+        let addr = addr.trim();
+        let name = addr.rsplit_once(':').map_or(addr, |(host, _)| host);
+        RemoteHost { name: name.to_string(), addr: addr.to_string(), token: None }
+    }
+}
+
+// sends one request and returns the body of a 2xx answer
+fn request(host: &RemoteHost, method: &str, path: &str) -> Result<String, String> {
+    let addr = host
+        .addr
+        .to_socket_addrs()
+        .map_err(|err| format!("{}: {}", host.addr, err))?
+        .next()
+        .ok_or_else(|| format!("{} doesn't resolve", host.addr))?;
+    if !addr.ip().is_loopback() {
+        return Err(format!("{} isn't a loopback address, reach the agent through an ssh tunnel", host.addr));
+    }
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT).map_err(|err| format!("{}: {}", host.addr, err))?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(|err| err.to_string())?;
+    stream.set_write_timeout(Some(TIMEOUT)).map_err(|err| err.to_string())?;
+    let auth = host.token.as_ref().map(|token| format!("Authorization: Bearer {}\r\n", token)).unwrap_or_default();
+    // http/1.0 so the agent answers with a plain body and closes the connection
+    let head = format!("{} {} HTTP/1.0\r\nHost: {}\r\n{}Content-Length: 0\r\n\r\n", method, path, host.addr, auth);
+    stream.write_all(head.as_bytes()).map_err(|err| format!("{}: {}", host.addr, err))?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|err| format!("{}: {}", host.addr, err))?;
+    parse_response(&response)
+}
+
+// status line and body of an answer, errors carry the agent's message when it sent one
+fn parse_response(response: &str) -> Result<String, String> {
+    let (head, body) = response.split_once("\r\n\r\n").ok_or("incomplete answer from the agent")?;
+    let status: u16 = head
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or("the agent didn't answer with http")?;
+    if (200..300).contains(&status) {
+        return Ok(body.to_string());
+    }
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|error| error["error"].as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("status {}", status));
+    Err(format!("the agent refused: {}", message))
+}
+
+// asks the agent to kill the process, it runs the same checks as a local kill
+pub fn kill(host: &RemoteHost, pid: u32) -> Result<(), String> {
+    request(host, "POST", &format!("/processes/{}/kill", pid)).map(|_| ())
+}
+
+fn fetch(host: &RemoteHost) -> Result<Vec<ProcessInfo>, String> {
+    let body = request(host, "GET", "/processes")?;
+    serde_json::from_str(&body).map_err(|err| format!("unreadable process table from the agent: {}", err))
+}
+
+#[derive(Debug, Default)]
+struct Latest {
+    processes: Vec<ProcessInfo>,
+    error: Option<String>,
+}
+
+// process table of a remote agent, polled in the background
+pub struct RemoteProvider {
+    host: RemoteHost,
+    latest: Arc<Mutex<Latest>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl RemoteProvider {
+    pub fn connect(host: RemoteHost, interval: Duration) -> RemoteProvider {
+        let latest = Arc::new(Mutex::new(Latest::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_host, thread_latest, thread_stop) = (host.clone(), latest.clone(), stop.clone());
+        thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                let result = fetch(&thread_host);
+                if let Ok(mut latest) = thread_latest.lock() {
+                    match result {
+                        Ok(processes) => *latest = Latest { processes, error: None },
+                        Err(err) => latest.error = Some(err),
+                    }
+                }
+                thread::sleep(interval);
+            }
+        });
+        RemoteProvider { host, latest, stop }
+    }
+}
+
+impl Drop for RemoteProvider {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl ProcessProvider for RemoteProvider {
+    fn collect(&mut self, _system: &mut System, _users: &Users) -> Vec<ProcessInfo> {
+        self.latest.lock().map(|latest| latest.processes.clone()).unwrap_or_default()
+    }
+
+    fn kill(&mut self, _system: &System, pid: u32) -> bool {
+        let killed = kill(&self.host, pid);
+        if let (Err(err), Ok(mut latest)) = (&killed, self.latest.lock()) {
+            latest.error = Some(err.clone());
+        }
+        killed.is_ok()
+    }
+
+    // from the agent's latest table, which may be newer than the shown one
+    fn identify(&mut self, pid: u32) -> Option<Identity> {
+        let latest = self.latest.lock().ok()?;
+        latest.processes.iter().find(|process| process.pid == pid).map(Identity::of)
+    }
+
+    fn error(&self) -> Option<String> {
+        self.latest.lock().ok().and_then(|latest| latest.error.clone())
+    }
+}
+
+// construct the host switcher above the process table, the shown host has no press handler
+pub fn switcher<'a>(hosts: &'a [RemoteHost], selected: Option<usize>, input: &str, error: Option<String>) -> Element<'a, Message> {
+    let local = Button::new("This computer");
+    let mut row = Row::new()
+        .spacing(10)
+        .align_items(alignment::Alignment::Center)
+        .push(Text::new("Host"))
+        .push(if selected.is_none() { local } else { local.on_press(Message::SelectHost(None)) });
+    for (index, host) in hosts.iter().enumerate() {
+        let button = Button::new(host.name.as_str());
+        row = row.push(if selected == Some(index) { button } else { button.on_press(Message::SelectHost(Some(index))) });
+    }
+    if let Some(index) = selected {
+        row = row.push(Button::new("Remove host").on_press(Message::RemoveHost(index)));
+    }
+    row.push(
+        TextInput::new("tunnelled agent address, e.g. 127.0.0.1:7878", input)
+            .on_input(Message::HostInputChanged)
+            .on_submit(Message::AddHost)
+            .width(Length::Fixed(300.0))
+    )
+    .push(Button::new("Add host").on_press_maybe((!input.trim().is_empty()).then_some(Message::AddHost)))
+    .push_maybe(error.map(Text::new))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bodies_and_agent_errors() {
+        assert_eq!(parse_response("HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n[]"), Ok(String::from("[]")));
+        assert_eq!(
+            parse_response("HTTP/1.0 401 Unauthorized\r\n\r\n{\"error\":\"missing or wrong bearer token\"}"),
+            Err(String::from("the agent refused: missing or wrong bearer token"))
+        );
+        assert!(parse_response("garbage").is_err());
+        // the token would cross the network in the clear
+        let far = RemoteHost { token: Some(String::from("secret")), ..RemoteHost::new("192.0.2.1:7878") };
+        assert_eq!(kill(&far, 7), Err(String::from("192.0.2.1:7878 isn't a loopback address, reach the agent through an ssh tunnel")));
+        assert_eq!(RemoteHost::new(" build-server:7878 ").name, "build-server");
+    }
+}
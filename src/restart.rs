@@ -0,0 +1,72 @@
+// restart action - kills a process and starts it again with the same command line
+use sysinfo::{Pid, Process, System};
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::run;
+
+// how long to wait for the old process to exit before giving up
+const EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub struct RestartSpec {
+    // what is needed to start the process again, captured before it is killed
+    pub pid: u32,
+    // seconds since the epoch, a process with the pid but another start time is left alone
+    pub start_time: u64,
+    pub exe: PathBuf,
+    // arguments after the program name
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl RestartSpec {
+    // None if the executable can't be read, e.g. for another user's process
+    pub fn capture(pid: u32, process: &Process) -> Option<RestartSpec> {
+        Some(RestartSpec {
+            pid,
+            start_time: process.start_time(),
+            exe: process.exe()?.to_path_buf(),
+            args: process.cmd().iter().skip(1).cloned().collect(),
+            cwd: process.cwd().map(|cwd| cwd.to_path_buf()),
+        })
+    }
+}
+
+// kills the process, waits for it to exit and starts it again, blocks so run it off the ui thread
+pub fn restart(spec: RestartSpec) -> Result<u32, String> {
+    let pid = Pid::from(spec.pid as usize);
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        return Err(format!("process {} is no longer running", spec.pid));
+    }
+    if system.process(pid).is_some_and(|process| process.start_time() != spec.start_time) {
+        return Err(format!("process {} exited and its pid was reused, not restarting it", spec.pid));
+    }
+    let killed = system.process(pid).is_some_and(|process| process.kill());
+    if !killed {
+        return Err(format!("failed to kill process {}", spec.pid));
+    }
+    let started = Instant::now();
+    while system.refresh_process(pid) {
+        if started.elapsed() > EXIT_TIMEOUT {
+            return Err(format!("process {} did not exit, not restarting it", spec.pid));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    spawn(&spec.exe, &spec.args, spec.cwd.as_deref())
+}
+
+// starts the program without waiting for it, a working directory that is gone is skipped
+pub fn spawn(exe: &Path, args: &[String], cwd: Option<&Path>) -> Result<u32, String> {
+    let mut command = Command::new(exe);
+    command.args(args);
+    if let Some(cwd) = cwd.filter(|cwd| cwd.is_dir()) {
+        command.current_dir(cwd);
+    }
+    run::spawn_detached(command).map_err(|err| format!("failed to start {}: {}", exe.display(), err))
+}
@@ -0,0 +1,113 @@
+// per-row action menu - a compact "⋮" at the end of each row instead of an always visible
+// Kill button, so a kill takes two deliberate clicks and the space goes to the columns
+//
+// the menu is a pick list without a selection, which gives it iced's own overlay menu. it
+// always shows the "⋮" placeholder, picking an entry only sends the action
+use iced::{
+    widget::{pick_list, tooltip, PickList, Tooltip},
+    Element, Length,
+};
+
+use std::fmt;
+
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowAction {
+    Kill,
+    KillTree,
+    Suspend,
+    Resume,
+    RaisePriority,
+    LowerPriority,
+    // raises the priority for a while, see boost.rs
+    Boost,
+    // caps its cpu or memory, see limits.rs
+    Limit,
+    // reads the process every 250 ms into a panel, see close_watch.rs
+    WatchClosely,
+    // opens it next to the selected process, see compare.rs
+    Compare,
+    // its details and charts in a window of their own, see detached.rs
+    OpenWindow,
+    // a colored tag or note on the program, see notes.rs
+    Note,
+    Details,
+}
+
+impl fmt::Display for RowAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RowAction::Kill => "Kill",
+            RowAction::KillTree => "Kill tree",
+            RowAction::Suspend => "Suspend",
+            RowAction::Resume => "Resume",
+            RowAction::RaisePriority => "Raise priority",
+            RowAction::LowerPriority => "Lower priority",
+            RowAction::Boost => "Boost",
+            RowAction::Limit => "Limit CPU or memory",
+            RowAction::WatchClosely => "Watch closely",
+            RowAction::Compare => "Compare with selected",
+            RowAction::OpenWindow => "Open in new window",
+            RowAction::Note => "Tag or note",
+            RowAction::Details => "Details",
+        })
+    }
+}
+
+// entries for a row, `local` is false for an agent's processes which can only be killed,
+// and nothing at all is offered for a replayed frame. `killable` is false in read-only mode
+// too, which leaves a local row only the close watch, the comparison, its window, note and details
+pub fn actions(local: bool, killable: bool, suspended: bool) -> Vec<RowAction> {
+    let mut actions = Vec::new();
+    if killable {
+        actions.extend([RowAction::Kill, RowAction::KillTree]);
+    }
+    if local {
+        if killable {
+            actions.push(if suspended { RowAction::Resume } else { RowAction::Suspend });
+            actions.extend([RowAction::RaisePriority, RowAction::LowerPriority, RowAction::Boost, RowAction::Limit]);
+        }
+        actions.extend([RowAction::WatchClosely, RowAction::Compare, RowAction::OpenWindow, RowAction::Note, RowAction::Details]);
+    }
+    actions
+}
+
+// construct the "⋮" menu of a row
+pub fn view<'a>(pid: u32, actions: Vec<RowAction>, padding: u16) -> Element<'a, Message> {
+    let menu = PickList::new(actions, None::<RowAction>, move |action| Message::RowAction(pid, action))
+        .placeholder("⋮")
+        .handle(pick_list::Handle::None)
+        .padding(padding)
+        .width(Length::Shrink);
+    Tooltip::new(menu, "Actions", tooltip::Position::Left).into()
+}
+
+// stops (or continues) the process, which keeps its memory but gets no cpu time meanwhile
+pub fn suspend(pid: u32, suspend: bool) -> Result<(), String> {
+    let signal = if suspend { sysinfo::Signal::Stop } else { sysinfo::Signal::Continue };
+    let mut system = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_process(pid);
+    let process = system.process(pid).ok_or_else(|| format!("process {} is gone", pid))?;
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        Some(false) => Err(format!("failed to {} process {}", if suspend { "suspend" } else { "resume" }, pid)),
+        None => Err(String::from("suspending processes is not supported on this platform")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_rows_only_offer_kills_and_replayed_rows_nothing() {
+        assert_eq!(actions(false, true, false), vec![RowAction::Kill, RowAction::KillTree]);
+        assert!(actions(false, false, false).is_empty());
+        let local = actions(true, true, true);
+        assert_eq!(local.len(), 12);
+        assert!(local.contains(&RowAction::Resume) && !local.contains(&RowAction::Suspend));
+        assert_eq!(actions(true, false, false), vec![RowAction::WatchClosely, RowAction::Compare, RowAction::OpenWindow, RowAction::Note, RowAction::Details]);
+    }
+}
@@ -0,0 +1,196 @@
+// rules tab - lists the configured rules, edits one at a time and shows what they matched
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::format::format_duration;
+use crate::rules::{Condition, Rule, RuleAction};
+use crate::Message;
+
+// entries kept in the match log
+pub const LOG_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleField {
+    // text inputs of the editor
+    Name,
+    ProcessName,
+    MinMemory,
+    MinCpu,
+    Except,
+    Command,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleDraft {
+    // rule being edited, None for a new one
+    pub index: Option<usize>,
+    // fields as typed, parsed on save
+    pub name: String,
+    pub process_name: String,
+    pub min_memory: String,
+    pub min_cpu: String,
+    // comma separated process names
+    pub except: String,
+    pub action: RuleAction,
+    // only used by the command action
+    pub command: String,
+}
+
+impl RuleDraft {
+    pub fn edit(index: usize, rule: &Rule) -> RuleDraft {
+        let condition = &rule.condition;
+        RuleDraft {
+            index: Some(index),
+            name: rule.name.clone(),
+            process_name: condition.name.clone().unwrap_or_default(),
+            min_memory: condition.min_memory_mb.map(|memory| memory.to_string()).unwrap_or_default(),
+            min_cpu: condition.min_cpu.map(|cpu| cpu.to_string()).unwrap_or_default(),
+            except: condition.except.join(", "),
+            action: rule.action,
+            command: rule.command.clone(),
+        }
+    }
+
+    pub fn set(&mut self, field: RuleField, value: String) {
+        match field {
+            RuleField::Name => self.name = value,
+            RuleField::ProcessName => self.process_name = value,
+            RuleField::MinMemory => self.min_memory = value,
+            RuleField::MinCpu => self.min_cpu = value,
+            RuleField::Except => self.except = value,
+            RuleField::Command => self.command = value,
+        }
+    }
+
+    // parses the fields, new rules start enabled
+    pub fn to_rule(&self, enabled: bool) -> Result<Rule, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(String::from("the rule needs a name"));
+        }
+        let optional = |text: &str| Some(text.trim()).filter(|text| !text.is_empty()).map(str::to_string);
+        let min_memory_mb = optional(&self.min_memory)
+            .map(|memory| memory.parse().map_err(|_| format!("{} is not a memory size in MB", memory)))
+            .transpose()?;
+        let min_cpu = optional(&self.min_cpu)
+            .map(|cpu| cpu.parse().map_err(|_| format!("{} is not a cpu percent", cpu)))
+            .transpose()?;
+        let condition = Condition {
+            name: optional(&self.process_name),
+            min_memory_mb,
+            min_cpu,
+            except: self.except.split(',').filter_map(optional).collect(),
+        };
+        if condition.is_empty() {
+            return Err(String::from("the rule needs a process name, memory or cpu threshold"));
+        }
+        if self.action == RuleAction::Command && self.command.trim().is_empty() {
+            return Err(String::from("the rule needs a command to run"));
+        }
+        Ok(Rule { name: name.to_string(), enabled, condition, action: self.action, command: self.command.trim().to_string() })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleEvent {
+    // one rule match and what was done about it
+    pub at: Instant,
+    pub text: String,
+}
+
+// one line describing what the rule matches
+fn summary(condition: &Condition) -> String {
+    let mut parts = Vec::new();
+    if let Some(name) = &condition.name {
+        parts.push(format!("named {}", name));
+    }
+    if let Some(memory) = condition.min_memory_mb {
+        parts.push(format!("memory ≥ {} MB", memory));
+    }
+    if let Some(cpu) = condition.min_cpu {
+        parts.push(format!("cpu ≥ {}%", cpu));
+    }
+    if !condition.except.is_empty() {
+        parts.push(format!("except {}", condition.except.join(", ")));
+    }
+    parts.join(", ")
+}
+
+// construct the rules tab layout
+pub fn view<'a>(rules: &'a [Rule], draft: Option<&'a RuleDraft>, log: &'a VecDeque<RuleEvent>) -> Element<'a, Message> {
+    let list = rules.iter().enumerate().fold(Column::new().spacing(5), |column, (index, rule)| {
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Checkbox::new(&rule.name, rule.enabled).on_toggle(move |enabled| Message::ToggleRule(index, enabled)).width(Length::FillPortion(2)))
+                .push(Text::new(summary(&rule.condition)).width(Length::FillPortion(3)))
+                .push(Text::new(rule.action.label()).width(Length::FillPortion(1)))
+                .push(Button::new("Edit").on_press(Message::EditRule(index)))
+                .push(Button::new("Delete").on_press(Message::DeleteRule(index)))
+        )
+    });
+    let mut content = Column::new()
+        .spacing(10)
+        .push(Text::new("Rules are checked on every process table refresh"))
+        .push(list);
+
+    content = match draft {
+        Some(draft) => content.push(editor(draft)),
+        None => content.push(Button::new("New rule").on_press(Message::NewRule)),
+    };
+
+    // newest first
+    let now = Instant::now();
+    let entries = log.iter().rev().fold(Column::new().spacing(2), |column, event| {
+        column.push(Text::new(format!("{} ago  {}", format_duration(now.duration_since(event.at).as_secs()), event.text)))
+    });
+    content
+        .push(Text::new("Matches"))
+        .push(Scrollable::new(entries))
+        .into()
+}
+
+// form for the rule being edited
+fn editor(draft: &RuleDraft) -> Element<'_, Message> {
+    let field = |label: &'static str, placeholder: &'static str, value: &str, field: RuleField| {
+        Row::new()
+            .spacing(10)
+            .push(Text::new(label).width(Length::FillPortion(1)))
+            .push(
+                TextInput::new(placeholder, value)
+                    .on_input(move |value| Message::RuleDraftChanged(field, value))
+                    .width(Length::FillPortion(3))
+            )
+    };
+    // the selected action is shown without a press handler so it reads as active
+    let actions = RuleAction::ALL.iter().fold(
+        Row::new().spacing(10).push(Text::new("Action").width(Length::FillPortion(1))),
+        |row, &action| {
+            let button = Button::new(action.label());
+            row.push(if action == draft.action { button } else { button.on_press(Message::SetRuleDraftAction(action)) })
+        },
+    );
+    let mut form = Column::new()
+        .spacing(5)
+        .push(field("Rule name", "kill runaway builds", &draft.name, RuleField::Name))
+        .push(field("Process name", "any", &draft.process_name, RuleField::ProcessName))
+        .push(field("Memory at least (MB)", "any", &draft.min_memory, RuleField::MinMemory))
+        .push(field("CPU at least (%)", "any", &draft.min_cpu, RuleField::MinCpu))
+        .push(field("Except", "Xorg, systemd", &draft.except, RuleField::Except))
+        .push(actions);
+    if draft.action == RuleAction::Command {
+        form = form.push(field("Command ({pid}, {name})", "renice 19 {pid}", &draft.command, RuleField::Command));
+    }
+    form.push(
+            Row::new()
+                .spacing(10)
+                .push(Button::new("Save").on_press(Message::SaveRule))
+                .push(Button::new("Cancel").on_press(Message::CancelRule))
+        )
+        .into()
+}
@@ -0,0 +1,197 @@
+// alert/rule configuration and its portable file format
+//
+// rules are stored in the config file and can be exported to / imported from a standalone
+// toml file so the same rule set can be distributed to many machines. the format is:
+//
+//     version = 1
+//
+//     [[rules]]
+//     name = "kill runaway builds"   # shown in the ui and in logs
+//     enabled = true
+//     action = "kill"                # "log" only records a match, "notify" also shows a desktop
+//                                    # notification, "sound" plays the alert sound, "kill"
+//                                    # terminates it and "command" runs `command` below
+//     command = "renice 19 {pid}"    # run through the shell, {pid} is replaced and the pid is
+//                                    # passed as the last argument when {pid} isn't used. the name
+//                                    # is in CONBORLI_NAME, {name} stands for a quoted reference to it
+//
+//     [rules.condition]              # every field is optional, all given ones must match
+//     name = "cc1plus"               # exact process name
+//     min_memory_mb = 8192           # resident memory at or above this
+//     min_cpu = 90.0                 # cpu percent at or above this
+//     except = ["Xorg", "systemd"]   # names that never match
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::Path;
+
+use crate::process::ProcessInfo;
+use crate::run;
+
+// version written to exported files, bumped when the format changes incompatibly
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub condition: Condition,
+    #[serde(default)]
+    pub action: RuleAction,
+    // the command line of the "command" action
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub command: String,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Condition {
+    pub name: Option<String>,
+    pub min_memory_mb: Option<u64>,
+    pub min_cpu: Option<f32>,
+    pub except: Vec<String>,
+}
+
+impl Condition {
+    // a condition without a name or threshold would match every process, so it never matches
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.min_memory_mb.is_none() && self.min_cpu.is_none()
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        !self.is_empty()
+            && !self.except.contains(&process.name)
+            && self.name.as_ref().is_none_or(|name| *name == process.name)
+            && self.min_memory_mb.is_none_or(|memory| process.memory >= memory)
+            && self.min_cpu.is_none_or(|cpu| process.cpu >= cpu)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    // only record that the rule matched
+    #[default]
+    Log,
+    // and show a desktop notification
+    Notify,
+    // and play the alert sound
+    Sound,
+    // terminate the matching process
+    Kill,
+    // run the rule's command on the matching process
+    Command,
+}
+
+impl RuleAction {
+    pub const ALL: [RuleAction; 5] = [RuleAction::Log, RuleAction::Notify, RuleAction::Sound, RuleAction::Kill, RuleAction::Command];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RuleAction::Log => "Log only (dry run)",
+            RuleAction::Notify => "Notify",
+            RuleAction::Sound => "Play sound",
+            RuleAction::Kill => "Kill",
+            RuleAction::Command => "Run command",
+        }
+    }
+}
+
+// what {name} becomes, the shell reads the name from the environment so nothing in it is
+// taken for shell syntax
+const NAME: &str = if cfg!(target_os = "windows") { "\"%CONBORLI_NAME%\"" } else { "\"$CONBORLI_NAME\"" };
+
+// the command line with the process filled in, see the header for the placeholders
+fn command_line(command: &str, pid: u32) -> String {
+    let mut line = command.trim().replace("{name}", NAME);
+    if line.contains("{pid}") {
+        line = line.replace("{pid}", &pid.to_string());
+    } else {
+        line = format!("{} {}", line, pid);
+    }
+    line
+}
+
+// starts the rule's command for the process without waiting for it
+pub fn run_command(command: &str, pid: u32, name: &str) -> Result<(), String> {
+    if command.trim().is_empty() {
+        return Err(String::from("the rule has no command to run"));
+    }
+    let line = command_line(command, pid);
+    let mut shell = run::shell_command(&line, false)?;
+    shell.env("CONBORLI_PID", pid.to_string()).env("CONBORLI_NAME", name);
+    run::spawn_detached(shell).map(drop).map_err(|err| format!("failed to start {}: {}", line, err))
+}
+
+// every (rule, process) pair where an enabled rule's condition holds
+pub fn evaluate<'a>(rules: &'a [Rule], processes: &'a [ProcessInfo]) -> impl Iterator<Item = (&'a Rule, &'a ProcessInfo)> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .flat_map(move |rule| processes.iter().filter(|process| rule.condition.matches(process)).map(move |process| (rule, process)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RuleFile {
+    version: u32,
+    #[serde(default)]
+    rules: Vec<Rule>,
+}
+
+// writes the rules to a standalone file with a header describing the format
+pub fn export(rules: &[Rule], path: &Path) -> Result<(), String> {
+    let file = RuleFile { version: FORMAT_VERSION, rules: rules.to_vec() };
+    let body = toml::to_string_pretty(&file).map_err(|err| err.to_string())?;
+    let header = "# conborli rule set, see src/rules.rs for the format\n\n";
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    fs::write(path, format!("{}{}", header, body)).map_err(|err| err.to_string())
+}
+
+// reads rules from a file written by `export` (or by hand / config management)
+pub fn import(path: &Path) -> Result<Vec<Rule>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let file: RuleFile = toml::from_str(&contents).map_err(|err| format!("{}: {}", path.display(), err))?;
+    if file.version > FORMAT_VERSION {
+        return Err(format!(
+            "{} uses rule format version {}, this build understands up to {}",
+            path.display(),
+            file.version,
+            FORMAT_VERSION
+        ));
+    }
+    Ok(file.rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_get_the_pid() {
+        assert_eq!(command_line("renice 19 {pid}", 42), "renice 19 42");
+        assert_eq!(command_line("notify.sh --name {name} ", 42), format!("notify.sh --name {} 42", NAME));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn names_reach_the_command_as_text() {
+        let out = std::env::temp_dir().join(format!("conborli-rule-{}", std::process::id()));
+        let command = format!("printf %s {{name}} > {}", out.display());
+        let mut child = run::shell_command(&command_line(&command, 42), false)
+            .unwrap()
+            .env("CONBORLI_NAME", "x'; touch pwned; echo '$(id)")
+            .spawn()
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+        assert_eq!(fs::read_to_string(&out).unwrap(), "x'; touch pwned; echo '$(id)42");
+        let _ = fs::remove_file(&out);
+    }
+}
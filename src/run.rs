@@ -0,0 +1,340 @@
+// run new task dialog - starts a command like Task Manager's File > Run, optionally with a
+// nice value, the cpus it may run on and extra environment variables set before it starts,
+// like `env K=V nice -n 5 taskset -c 0-3 command` would
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Text, TextInput},
+    Element, Length,
+};
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+use crate::priority::{MAX_NICE, MIN_NICE};
+use crate::Message;
+
+// cpus a cpu_set_t can name
+const MAX_CPUS: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct RunDialog {
+    // command line typed by the user, run through the platform shell
+    pub command: String,
+    // directory to start in, the app's own when empty
+    pub working_dir: String,
+    // ask the platform for administrator rights first
+    pub elevated: bool,
+    // what the started process and its descendants are listed as, the command when empty,
+    // see jobs.rs
+    pub group_name: String,
+    // nice value, cpu list like "0-3,6" and space separated KEY=value pairs, empty to inherit
+    pub nice: String,
+    pub affinity: String,
+    pub environment: String,
+}
+
+// the dialog's optional fields, checked before anything is started
+#[derive(Debug, Default, PartialEq)]
+struct Launch {
+    nice: Option<i32>,
+    cpus: Option<Vec<usize>>,
+    environment: Vec<(String, String)>,
+}
+
+impl Launch {
+    fn parse(dialog: &RunDialog) -> Result<Launch, String> {
+        let nice = dialog.nice.trim();
+        let nice = if nice.is_empty() {
+            None
+        } else {
+            let value: i32 = nice.parse().map_err(|_| format!("{} is not a nice value", nice))?;
+            if !(MIN_NICE..=MAX_NICE).contains(&value) {
+                return Err(format!("the nice value must be between {} and {}", MIN_NICE, MAX_NICE));
+            }
+            Some(value)
+        };
+        let affinity = dialog.affinity.trim();
+        let cpus = if affinity.is_empty() { None } else { Some(parse_cpus(affinity)?) };
+        let environment = dialog
+            .environment
+            .split_whitespace()
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+                _ => Err(format!("{} is not KEY=value", pair)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Launch { nice, cpus, environment })
+    }
+
+    // the nice value and cpus are set in the child between fork and exec
+    #[cfg(target_os = "linux")]
+    fn apply(&self, command: &mut Command) -> Result<(), String> {
+        use std::os::unix::process::CommandExt;
+
+        let nice = self.nice;
+        let set = self.cpus.as_ref().map(|cpus| {
+            let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            for &cpu in cpus {
+                unsafe { libc::CPU_SET(cpu, &mut set) };
+            }
+            set
+        });
+        // only raw syscalls in here, the child is between fork and exec
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(nice) = nice {
+                    if libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(set) = &set {
+                    if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn apply(&self, command: &mut Command) -> Result<(), String> {
+        use std::os::unix::process::CommandExt;
+
+        if self.cpus.is_some() {
+            return Err(String::from("setting the cpus of a new task is only supported on Linux"));
+        }
+        if let Some(nice) = self.nice {
+            unsafe {
+                command.pre_exec(move || {
+                    if libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn apply(&self, _command: &mut Command) -> Result<(), String> {
+        if self.nice.is_some() || self.cpus.is_some() {
+            return Err(String::from("setting the priority or cpus of a new task is not supported on this platform"));
+        }
+        Ok(())
+    }
+}
+
+// "0-3,6" as [0, 1, 2, 3, 6]
+fn parse_cpus(text: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+    for part in text.split(',').map(str::trim) {
+        let number = |text: &str| text.trim().parse::<usize>().map_err(|_| format!("{} is not a cpu list like 0-3,6", text));
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => (number(part)?, number(part)?),
+        };
+        if first > last || last >= MAX_CPUS {
+            return Err(format!("{} is not a range of cpus", part));
+        }
+        cpus.extend(first..=last);
+    }
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+// starts the command without waiting for it, returning its pid
+pub fn spawn(dialog: &RunDialog) -> Result<u32, String> {
+    let command_line = dialog.command.trim();
+    if command_line.is_empty() {
+        return Err(String::from("enter a command to run"));
+    }
+    let launch = Launch::parse(dialog)?;
+    let mut command = shell_command(command_line, dialog.elevated)?;
+    // pkexec starts the command with a clean environment, so the variables go on its line
+    if dialog.elevated && cfg!(unix) && !launch.environment.is_empty() {
+        let pairs: Vec<String> = launch.environment.iter().map(|(key, value)| format!("{}='{}'", key, value.replace('\'', ""))).collect();
+        command = shell_command(&format!("env {} {}", pairs.join(" "), command_line), true)?;
+    } else {
+        command.envs(launch.environment.iter().map(|(key, value)| (key, value)));
+    }
+    launch.apply(&mut command)?;
+    let working_dir = dialog.working_dir.trim();
+    if !working_dir.is_empty() {
+        if !Path::new(working_dir).is_dir() {
+            return Err(format!("{} is not a directory", working_dir));
+        }
+        command.current_dir(working_dir);
+    }
+    spawn_detached(command).map_err(|err| format!("failed to start {}: {}", command_line, err))
+}
+
+// starts the command without waiting for it and returns its pid. the child is reaped in the
+// background so it doesn't linger as a zombie
+pub fn spawn_detached(mut command: Command) -> io::Result<u32> {
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    thread::spawn(move || child.wait());
+    Ok(pid)
+}
+
+// the task manager itself with administrator rights, as another instance
+pub fn relaunch_elevated() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|err| format!("can't find the task manager's executable: {}", err))?;
+    let mut command_line = format!("\"{}\" --new-instance", exe.display());
+    // pkexec clears the environment, the window needs the session's display back
+    if cfg!(target_os = "linux") {
+        let session: Vec<String> = ["DISPLAY", "WAYLAND_DISPLAY", "XAUTHORITY", "XDG_RUNTIME_DIR"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok().map(|value| format!("{}='{}'", name, value.replace('\'', ""))))
+            .collect();
+        command_line = format!("env {} {}", session.join(" "), command_line);
+    }
+    spawn_detached(shell_command(&command_line, true)?)
+        .map(drop)
+        .map_err(|err| format!("failed to start {}: {}", command_line, err))
+}
+
+// root, where reading other users' processes needs no further rights
+#[cfg(unix)]
+pub fn is_elevated() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+// the token's elevation isn't read, so the escalation stays offered
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    false
+}
+
+// wraps the command line in the platform shell, elevating through the usual prompt
+pub fn shell_command(command_line: &str, elevated: bool) -> Result<Command, String> {
+    let mut command;
+    if cfg!(target_os = "windows") {
+        if elevated {
+            command = Command::new("powershell");
+            command.args(["-NoProfile", "-Command", "Start-Process", "cmd", "-Verb", "RunAs", "-ArgumentList"]);
+            command.arg(format!("'/C {}'", command_line.replace('\'', "''")));
+        } else {
+            command = Command::new("cmd");
+            command.args(["/C", command_line]);
+        }
+    } else if elevated {
+        if cfg!(target_os = "macos") {
+            return Err(String::from("running elevated is not supported on macOS, use sudo in a terminal"));
+        }
+        command = Command::new("pkexec");
+        command.args(["sh", "-c", command_line]);
+    } else {
+        command = Command::new("sh");
+        command.args(["-c", command_line]);
+    }
+    Ok(command)
+}
+
+// construct the dialog shown above the process table
+pub fn view(dialog: &RunDialog) -> Element<'_, Message> {
+    Column::new()
+        .spacing(10)
+        .push(Text::new("Run new task"))
+        .push(
+            TextInput::new("Command", &dialog.command)
+                .on_input(Message::RunCommandChanged)
+                .on_submit(Message::RunTask)
+                .width(Length::Fixed(500.0))
+        )
+        .push(
+            TextInput::new("Working directory (optional)", &dialog.working_dir)
+                .on_input(Message::RunDirChanged)
+                .on_submit(Message::RunTask)
+                .width(Length::Fixed(500.0))
+        )
+        .push(
+            TextInput::new("Group name (optional)", &dialog.group_name)
+                .on_input(Message::RunGroupNameChanged)
+                .on_submit(Message::RunTask)
+                .width(Length::Fixed(500.0))
+        )
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    TextInput::new("Nice value, e.g. 10 (optional)", &dialog.nice)
+                        .on_input(Message::RunNiceChanged)
+                        .on_submit(Message::RunTask)
+                        .width(Length::Fixed(245.0))
+                )
+                .push(
+                    TextInput::new("CPUs, e.g. 0-3,6 (optional)", &dialog.affinity)
+                        .on_input(Message::RunAffinityChanged)
+                        .on_submit(Message::RunTask)
+                        .width(Length::Fixed(245.0))
+                )
+        )
+        .push(
+            TextInput::new("Environment, e.g. RUST_LOG=debug LANG=C (optional)", &dialog.environment)
+                .on_input(Message::RunEnvironmentChanged)
+                .on_submit(Message::RunTask)
+                .width(Length::Fixed(500.0))
+        )
+        .push(Checkbox::new("Run with administrator rights", dialog.elevated).on_toggle(Message::ToggleRunElevated))
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Button::new("Run").on_press(Message::RunTask))
+                .push(Button::new("Cancel").on_press(Message::CloseRunDialog))
+        )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_options_are_checked_before_starting() {
+        let dialog = |nice: &str, affinity: &str, environment: &str| RunDialog {
+            nice: nice.to_string(),
+            affinity: affinity.to_string(),
+            environment: environment.to_string(),
+            ..RunDialog::default()
+        };
+        assert_eq!(Launch::parse(&dialog("", "", "")).unwrap(), Launch::default());
+        let launch = Launch::parse(&dialog(" 10", "0-2,6,1", "RUST_LOG=debug OPTS=a=b EMPTY=")).unwrap();
+        assert_eq!(launch.nice, Some(10));
+        assert_eq!(launch.cpus, Some(vec![0, 1, 2, 6]));
+        assert_eq!(launch.environment[1], (String::from("OPTS"), String::from("a=b")));
+        assert_eq!(launch.environment[2].1, "");
+        assert!(Launch::parse(&dialog("30", "", "")).is_err());
+        assert!(Launch::parse(&dialog("", "3-1", "")).is_err());
+        assert!(Launch::parse(&dialog("", "0,x", "")).is_err());
+        assert!(Launch::parse(&dialog("", "", "NOEQUALS")).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn starts_with_the_nice_value_and_cpus() {
+        let dialog = RunDialog { command: String::from("sleep 5"), nice: String::from("5"), affinity: String::from("0"), ..RunDialog::default() };
+        let pid = spawn(&dialog).unwrap();
+        assert_eq!(crate::priority::get_nice(pid), Some(5));
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap();
+        assert!(status.lines().any(|line| line.starts_with("Cpus_allowed_list:") && line.ends_with("\t0")));
+        unsafe { libc::kill(pid as i32, libc::SIGKILL) };
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn detached_children_are_reaped() {
+        let pid = spawn_detached(Command::new("true")).unwrap();
+        let started = std::time::Instant::now();
+        while std::path::Path::new(&format!("/proc/{}", pid)).exists() {
+            assert!(started.elapsed() < std::time::Duration::from_secs(5), "process {} was left as a zombie", pid);
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(spawn_detached(Command::new("/nonexistent/program")).is_err());
+    }
+}
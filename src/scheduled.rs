@@ -0,0 +1,188 @@
+// scheduled actions - kill or restart a process after a delay or at a time of day
+//
+// pending actions live in memory only. a one second timer runs next to the refresh tick
+// while any are pending, it isn't stopped by pausing so actions still happen on time
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Text, TextInput},
+    Element, Length,
+};
+
+use crate::format::{self, format_duration};
+use crate::kill_guard;
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Kill,
+    Restart,
+}
+
+impl ActionKind {
+    pub const ALL: [ActionKind; 2] = [ActionKind::Kill, ActionKind::Restart];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ActionKind::Kill => "Kill",
+            ActionKind::Restart => "Restart",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    // the process that was selected, nothing happens if it exited in the meantime or its pid
+    // went to another process
+    Pid(kill_guard::Target),
+    // whatever runs under this name when the action is due
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduledAction {
+    // identifies the action for cancelling
+    pub id: u64,
+    pub kind: ActionKind,
+    pub target: Target,
+    pub due: DateTime<Local>,
+}
+
+impl ScheduledAction {
+    pub fn describe(&self) -> String {
+        let target = match &self.target {
+            Target::Pid(target) => target.label(),
+            Target::Name(name) => format!("processes named {}", name),
+        };
+        format!("{} {}", self.kind.label(), target)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleDialog {
+    // process the dialog was opened for
+    pub target: kill_guard::Target,
+    pub kind: ActionKind,
+    // target every process with the name instead of the pid
+    pub by_name: bool,
+    // "30m", "90s", "2h", a bare number of minutes, or a time of day like "18:00"
+    pub when: String,
+}
+
+impl ScheduleDialog {
+    pub fn new(target: kill_guard::Target) -> ScheduleDialog {
+        ScheduleDialog { target, kind: ActionKind::Kill, by_name: false, when: String::from("30m") }
+    }
+
+    pub fn to_action(&self, id: u64, now: DateTime<Local>) -> Result<ScheduledAction, String> {
+        let target = if self.by_name { Target::Name(self.target.identity.name.clone()) } else { Target::Pid(self.target.clone()) };
+        Ok(ScheduledAction { id, kind: self.kind, target, due: parse_when(&self.when, now)? })
+    }
+}
+
+// a delay from now or the next time the clock shows the given time of day
+pub fn parse_when(text: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let text = text.trim();
+    if text.contains(':') {
+        let time = NaiveTime::parse_from_str(text, "%H:%M").map_err(|_| format!("{} is not a time like 18:00", text))?;
+        let today = now.date_naive().and_time(time).and_local_timezone(Local).earliest();
+        let due = match today {
+            Some(due) if due > now => Some(due),
+            // already passed today, or skipped by a clock change
+            _ => (now.date_naive() + Duration::days(1)).and_time(time).and_local_timezone(Local).earliest(),
+        };
+        return due.ok_or_else(|| format!("{} doesn't exist in the local time zone", text));
+    }
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(split) => text.split_at(split),
+        None => (text, "m"),
+    };
+    let amount: i64 = number.parse().map_err(|_| format!("{} is not a delay like 30m or a time like 18:00", text))?;
+    let delay = match unit.trim() {
+        "s" => Duration::seconds(amount),
+        "m" | "min" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        unit => return Err(format!("unknown unit {}, use s, m or h", unit)),
+    };
+    Ok(now + delay)
+}
+
+// removes and returns the actions that are due
+pub fn take_due(pending: &mut Vec<ScheduledAction>, now: DateTime<Local>) -> Vec<ScheduledAction> {
+    let (due, waiting) = pending.drain(..).partition(|action| action.due <= now);
+    *pending = waiting;
+    due
+}
+
+// construct the dialog shown above the process table
+pub fn dialog_view(dialog: &ScheduleDialog) -> Element<'_, Message> {
+    // the selected action is shown without a press handler so it reads as active
+    let kinds = ActionKind::ALL.iter().fold(Row::new().spacing(10), |row, &kind| {
+        let button = Button::new(kind.label());
+        row.push(if kind == dialog.kind { button } else { button.on_press(Message::SetScheduleKind(kind)) })
+    });
+    Column::new()
+        .spacing(10)
+        .push(Text::new(format!("Schedule an action for {}", dialog.target.label())))
+        .push(kinds)
+        .push(
+            Checkbox::new(format!("Every process named {} at that time", dialog.target.identity.name), dialog.by_name)
+                .on_toggle(Message::ToggleScheduleByName)
+        )
+        .push(
+            TextInput::new("in 30m, 90s, 2h or at 18:00", &dialog.when)
+                .on_input(Message::ScheduleWhenChanged)
+                .on_submit(Message::ConfirmSchedule)
+                .width(Length::Fixed(300.0))
+        )
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(Button::new("Schedule").on_press(Message::ConfirmSchedule))
+                .push(Button::new("Cancel").on_press(Message::CloseSchedule))
+        )
+        .into()
+}
+
+// construct the pending actions section of the rules tab
+pub fn view(pending: &[ScheduledAction]) -> Element<'_, Message> {
+    let now = Local::now();
+    let list = pending.iter().fold(Column::new().spacing(5), |column, action| {
+        let remaining = (action.due - now).num_seconds().max(0) as u64;
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(action.describe()).width(Length::FillPortion(3)))
+                .push(Text::new(format!("at {}", format::time(&action.due))).width(Length::FillPortion(1)))
+                .push(Text::new(format!("in {}", format_duration(remaining))).width(Length::FillPortion(1)))
+                .push(Button::new("Cancel").on_press(Message::CancelScheduled(action.id)))
+        )
+    });
+    let mut content = Column::new().spacing(10).push(Text::new("Scheduled actions"));
+    content = if pending.is_empty() {
+        content.push(Text::new("Select a process and press Schedule to kill or restart it later"))
+    } else {
+        content.push(list)
+    };
+    content.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    #[test]
+    fn delays_and_times_of_day() {
+        let now = Local.with_ymd_and_hms(2026, 3, 10, 17, 30, 0).unwrap();
+        let at = |text| parse_when(text, now).map(|due| due.format("%d %H:%M:%S").to_string());
+        assert_eq!(at("30"), Ok(String::from("10 18:00:00")));
+        assert_eq!(at("90s"), Ok(String::from("10 17:31:30")));
+        assert_eq!(at("2h"), Ok(String::from("10 19:30:00")));
+        assert_eq!(at("18:00"), Ok(String::from("10 18:00:00")));
+        // a time that already passed means tomorrow
+        assert_eq!(at("09:15"), Ok(String::from("11 09:15:00")));
+        assert!(at("soon").is_err());
+        assert!(at("5d").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+// sampler scheduler - decides which data sources are due on each base tick
+//
+// a single one second tick drives everything, each source keeps its own interval so the
+// process table, disks, services, etc. can be refreshed at different rates
+//
+// while the machine is busy, or the table is huge and slow to collect, every interval is
+// stretched so the task manager doesn't add to the load. each heavy process refresh doubles
+// the stretch up to MAX_BACKOFF times the configured intervals, each light one halves it back
+//
+// on battery or while the window is hidden the battery saver stretches them further, by
+// THROTTLE_BACKOFF doublings on top
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// how often the scheduler is woken up to check for due sources
+pub const BASE_TICK: Duration = Duration::from_secs(1);
+
+// doublings of the intervals at most, i.e. 8 times slower
+const MAX_BACKOFF: u32 = 3;
+
+// doublings while throttled, i.e. 4 times slower
+const THROTTLE_BACKOFF: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    // data sources that are refreshed independently
+    Processes,
+    Network,
+    Disks,
+    Sensors,
+    Services,
+    Battery,
+}
+
+impl Source {
+    pub const ALL: [Source; 6] = [
+        Source::Processes,
+        Source::Network,
+        Source::Disks,
+        Source::Sensors,
+        Source::Services,
+        Source::Battery,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Processes => "Process table",
+            Source::Network => "Network",
+            Source::Disks => "Disks",
+            Source::Sensors => "Sensors",
+            Source::Services => "Services",
+            Source::Battery => "Battery",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshIntervals {
+    // seconds between refreshes of each source
+    pub processes: u64,
+    pub network: u64,
+    pub disks: u64,
+    pub sensors: u64,
+    pub services: u64,
+    pub battery: u64,
+}
+
+impl Default for RefreshIntervals {
+    fn default() -> Self {
+        RefreshIntervals {
+            processes: 5,
+            network: 2,
+            disks: 60,
+            sensors: 5,
+            services: 10,
+            battery: 30,
+        }
+    }
+}
+
+impl RefreshIntervals {
+    pub fn get(&self, source: Source) -> u64 {
+        match source {
+            Source::Processes => self.processes,
+            Source::Network => self.network,
+            Source::Disks => self.disks,
+            Source::Sensors => self.sensors,
+            Source::Services => self.services,
+            Source::Battery => self.battery,
+        }
+    }
+
+    // sets the interval of a source, never below one base tick
+    pub fn set(&mut self, source: Source, seconds: u64) {
+        let seconds = seconds.max(1);
+        match source {
+            Source::Processes => self.processes = seconds,
+            Source::Network => self.network = seconds,
+            Source::Disks => self.disks = seconds,
+            Source::Sensors => self.sensors = seconds,
+            Source::Services => self.services = seconds,
+            Source::Battery => self.battery = seconds,
+        }
+    }
+}
+
+// what the last process refresh saw, read by `Scheduler::adapt`
+#[derive(Debug, Clone, Copy)]
+pub struct Load {
+    // whole-machine cpu use in percent
+    pub cpu: f32,
+    pub processes: usize,
+    // time spent collecting the table
+    pub collect_time: Duration,
+}
+
+impl Load {
+    fn heavy(&self) -> bool {
+        self.cpu >= 90.0 || self.processes >= 5_000 || self.collect_time >= Duration::from_millis(500)
+    }
+
+    // below the heavy thresholds with a margin, so the rate doesn't flap at the edge
+    fn light(&self) -> bool {
+        self.cpu < 70.0 && self.processes < 4_000 && self.collect_time < Duration::from_millis(250)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    // when each source was last refreshed
+    last_run: HashMap<Source, Instant>,
+    // times the intervals are currently doubled, 0 at the configured rate
+    backoff: u32,
+    // the battery saver's extra stretch is on
+    throttled: bool,
+}
+
+impl Scheduler {
+    // sources whose interval has elapsed. they stay due until marked, so a source skipped
+    // while its tab is hidden refreshes as soon as the tab is shown
+    pub fn due(&self, intervals: &RefreshIntervals, now: Instant) -> Vec<Source> {
+        Source::ALL
+            .into_iter()
+            .filter(|&source| {
+                let interval = Duration::from_secs(self.effective(intervals, source));
+                // half a tick of slack so timer jitter doesn't push a source to the next tick
+                self.last_run.get(&source).is_none_or(|last| now.duration_since(*last) + BASE_TICK / 2 >= interval)
+            })
+            .collect()
+    }
+
+    // records a refresh of the source, scheduled or not, so it isn't refreshed again right away
+    pub fn mark(&mut self, source: Source, now: Instant) {
+        self.last_run.insert(source, now);
+    }
+
+    // slows down after a heavy refresh and speeds back up after a light one
+    pub fn adapt(&mut self, load: Load) {
+        if load.heavy() {
+            self.backoff = (self.backoff + 1).min(MAX_BACKOFF);
+        } else if load.light() {
+            self.backoff = self.backoff.saturating_sub(1);
+        }
+    }
+
+    // back to the configured intervals, e.g. when adapting is turned off
+    pub fn reset_backoff(&mut self) {
+        self.backoff = 0;
+    }
+
+    // true while the intervals are stretched, expensive columns are skipped meanwhile
+    pub fn backed_off(&self) -> bool {
+        self.backoff > 0
+    }
+
+    pub fn throttle(&mut self, throttled: bool) {
+        self.throttled = throttled;
+    }
+
+    pub fn throttled(&self) -> bool {
+        self.throttled
+    }
+
+    // seconds between refreshes of a source at the current load
+    pub fn effective(&self, intervals: &RefreshIntervals, source: Source) -> u64 {
+        let throttle = if self.throttled { THROTTLE_BACKOFF } else { 0 };
+        intervals.get(source) << (self.backoff + throttle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_stretch_under_load_and_recover() {
+        let intervals = RefreshIntervals::default();
+        let mut scheduler = Scheduler::default();
+        let heavy = Load { cpu: 99.0, processes: 300, collect_time: Duration::from_millis(20) };
+        for _ in 0..5 {
+            scheduler.adapt(heavy);
+        }
+        assert_eq!(scheduler.effective(&intervals, Source::Processes), 40);
+        // between the thresholds the rate holds
+        scheduler.adapt(Load { cpu: 80.0, ..heavy });
+        assert_eq!(scheduler.effective(&intervals, Source::Processes), 40);
+        scheduler.adapt(Load { cpu: 10.0, ..heavy });
+        assert_eq!(scheduler.effective(&intervals, Source::Processes), 20);
+        scheduler.reset_backoff();
+        assert!(!scheduler.backed_off());
+        scheduler.throttle(true);
+        assert_eq!(scheduler.effective(&intervals, Source::Processes), 20);
+    }
+
+    #[test]
+    fn sources_stay_due_until_they_have_run() {
+        let intervals = RefreshIntervals::default();
+        let mut scheduler = Scheduler::default();
+        let start = Instant::now();
+        assert_eq!(scheduler.due(&intervals, start), Source::ALL.to_vec());
+        scheduler.mark(Source::Processes, start);
+        let due = scheduler.due(&intervals, start + BASE_TICK);
+        assert!(!due.contains(&Source::Processes));
+        assert!(due.contains(&Source::Disks));
+    }
+}
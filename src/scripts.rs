@@ -0,0 +1,265 @@
+// user scripts - rhai files run on every process refresh, see the scripts tab
+//
+// scripts live in the scripts directory next to the config, one .rhai file each, and only
+// the ones enabled in the scripts tab run. a script sees the table as the `processes` array
+// of maps and can call badge(pid, text) to show text in the Badge column, kill(pid) for a
+// process in that table, and log(text) or print(text) to write to the tab's log. kills go
+// through the same checks as the kill button (kill_guard.rs). scripts can't touch files, the network
+// or other programs, and a script running for too long is stopped
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::format::format_duration;
+use crate::kill_guard::Target;
+use crate::plugins::ColumnValue;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// entries kept in the log
+const LOG_LENGTH: usize = 200;
+
+// rhai operations a script may run per refresh before it is stopped
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+// key of the badge in ProcessInfo::custom, shown by the badge column plugin
+pub const BADGE: &str = "badge";
+
+#[derive(Debug, Default)]
+struct Output {
+    // what the running script asked for, drained after each script
+    badges: Vec<(u32, String)>,
+    kills: Vec<u32>,
+    logs: Vec<String>,
+}
+
+struct Script {
+    // file name without the extension
+    name: String,
+    // the compile error is kept to show in the tab
+    ast: Result<AST, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScriptEvent {
+    pub at: Instant,
+    pub text: String,
+}
+
+pub struct Scripts {
+    engine: Engine,
+    output: Rc<RefCell<Output>>,
+    scripts: Vec<Script>,
+    pub log: VecDeque<ScriptEvent>,
+}
+
+// e.g. ~/.config/conborli/scripts
+pub fn dir() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("scripts"))
+}
+
+fn pid(value: i64) -> Option<u32> {
+    u32::try_from(value).ok()
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        let output = Rc::new(RefCell::new(Output::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(32);
+        engine.set_max_string_size(64 * 1024);
+        engine.set_max_array_size(100_000);
+        engine.set_max_map_size(1_000);
+        let logs = output.clone();
+        engine.on_print(move |text| logs.borrow_mut().logs.push(text.to_string()));
+        let logs = output.clone();
+        engine.register_fn("log", move |text: &str| logs.borrow_mut().logs.push(text.to_string()));
+        let badges = output.clone();
+        engine.register_fn("badge", move |value: i64, text: &str| {
+            if let Some(pid) = pid(value) {
+                badges.borrow_mut().badges.push((pid, text.to_string()));
+            }
+        });
+        let kills = output.clone();
+        engine.register_fn("kill", move |value: i64| {
+            if let Some(pid) = pid(value) {
+                kills.borrow_mut().kills.push(pid);
+            }
+        });
+        Scripts { engine, output, scripts: Vec::new(), log: VecDeque::new() }
+    }
+}
+
+// the fields a script can read, memory in MB and cpu in percent like the table
+fn to_map(process: &ProcessInfo) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("pid".into(), Dynamic::from(process.pid as i64));
+    map.insert("ppid".into(), process.ppid.map_or(Dynamic::UNIT, |ppid| Dynamic::from(ppid as i64)));
+    map.insert("name".into(), Dynamic::from(process.name.clone()));
+    map.insert("user".into(), Dynamic::from(process.user.clone()));
+    map.insert("cmd".into(), Dynamic::from(process.cmd.clone()));
+    map.insert("memory".into(), Dynamic::from(process.memory as i64));
+    map.insert("virtual_memory".into(), Dynamic::from(process.virtual_memory as i64));
+    map.insert("cpu".into(), Dynamic::from(process.cpu as f64));
+    map.insert("uptime".into(), Dynamic::from(process.uptime as i64));
+    map.insert("container".into(), Dynamic::from(process.container.clone().unwrap_or_default()));
+    map.insert("growth_rate".into(), Dynamic::from(process.growth_rate as f64));
+    map.insert("suspected_leak".into(), Dynamic::from(process.suspected_leak));
+    map.insert("system".into(), Dynamic::from(process.system));
+    map.insert("elevated".into(), Dynamic::from(process.elevated));
+    map.insert("not_responding".into(), Dynamic::from(process.not_responding));
+    Dynamic::from_map(map)
+}
+
+impl Scripts {
+    // reads every .rhai file in the scripts directory again, a missing directory means no scripts
+    pub fn load(&mut self) {
+        let mut files: Vec<PathBuf> = dir()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .into_iter()
+            .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "rhai"))
+            .collect();
+        files.sort();
+        self.scripts = files
+            .into_iter()
+            .map(|path| {
+                let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+                let ast = fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|source| self.engine.compile(source).map_err(|err| err.to_string()));
+                Script { name, ast }
+            })
+            .collect();
+    }
+
+    pub fn record(&mut self, text: String) {
+        self.log.push_back(ScriptEvent { at: Instant::now(), text });
+        if self.log.len() > LOG_LENGTH {
+            self.log.pop_front();
+        }
+    }
+
+    // runs the enabled scripts in name order, applies their badges and returns the kills they
+    // asked for as (script, pid)
+    pub fn run(&mut self, enabled: &[String], processes: &mut [ProcessInfo]) -> Vec<(String, Target)> {
+        let mut kills = Vec::new();
+        let scripts: Vec<(String, AST)> = self
+            .scripts
+            .iter()
+            .filter(|script| enabled.contains(&script.name))
+            .filter_map(|script| script.ast.as_ref().ok().map(|ast| (script.name.clone(), ast.clone())))
+            .collect();
+        if scripts.is_empty() {
+            return kills;
+        }
+        let table: Array = processes.iter().map(to_map).collect();
+        for (name, ast) in scripts {
+            let mut scope = Scope::new();
+            scope.push("processes", table.clone());
+            let result = self.engine.run_ast_with_scope(&mut scope, &ast);
+            let output = std::mem::take(&mut *self.output.borrow_mut());
+            for text in output.logs {
+                self.record(format!("{}: {}", name, text));
+            }
+            if let Err(err) = result {
+                self.record(format!("{} failed: {}", name, err));
+            }
+            for (pid, badge) in output.badges {
+                if let Some(process) = processes.iter_mut().find(|process| process.pid == pid) {
+                    process.custom.insert(BADGE.to_string(), ColumnValue::Text(badge));
+                }
+            }
+            // aimed at the process the script saw, a pid from elsewhere isn't killed
+            for pid in output.kills {
+                match processes.iter().find(|process| process.pid == pid) {
+                    Some(process) => kills.push((name.clone(), Target::of(process))),
+                    None => self.record(format!("{} asked to kill process {}, which isn't in the table", name, pid)),
+                }
+            }
+        }
+        kills
+    }
+}
+
+// construct the scripts tab layout
+pub fn view<'a>(scripts: &'a Scripts, enabled: &'a [String]) -> Element<'a, Message> {
+    let location = dir().map(|dir| dir.display().to_string()).unwrap_or_else(|| String::from("the config directory"));
+    let list = scripts.scripts.iter().fold(Column::new().spacing(5), |column, script| {
+        let name = script.name.clone();
+        let state = match &script.ast {
+            Ok(_) => String::from("ready"),
+            Err(err) => format!("doesn't compile: {}", err),
+        };
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    Checkbox::new(&script.name, enabled.contains(&script.name))
+                        .on_toggle(move |on| Message::ToggleScript(name.clone(), on))
+                        .width(Length::FillPortion(1))
+                )
+                .push(Text::new(state).width(Length::FillPortion(3)))
+        )
+    });
+    // newest first
+    let now = Instant::now();
+    let log = scripts.log.iter().rev().fold(Column::new().spacing(2), |column, event| {
+        column.push(Text::new(format!("{} ago  {}", format_duration(now.duration_since(event.at).as_secs()), event.text)))
+    });
+    let mut content = Column::new()
+        .spacing(10)
+        .push(Text::new(format!("Scripts are the .rhai files in {}, enabled ones run on every process refresh", location)))
+        .push(Button::new("Reload scripts").on_press(Message::ReloadScripts));
+    content = if scripts.scripts.is_empty() {
+        content.push(Text::new("No scripts yet, e.g. hogs.rhai: for p in processes { if p.memory > 2000 { badge(p.pid, \"hog\") } }"))
+    } else {
+        content.push(list)
+    };
+    content
+        .push(Text::new("Log"))
+        .push(Scrollable::new(log))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn scripts_badge_kill_and_log() {
+        let mut scripts = Scripts::default();
+        let compile = |scripts: &Scripts, source: &str| scripts.engine.compile(source).map_err(|err| err.to_string());
+        let hogs = compile(&scripts, r#"for p in processes { if p.memory > 100 { badge(p.pid, "hog"); kill(p.pid); print(p.name) } }"#);
+        let endless = compile(&scripts, "loop {}");
+        scripts.scripts = vec![
+            Script { name: String::from("hogs"), ast: hogs },
+            Script { name: String::from("endless"), ast: endless },
+            Script { name: String::from("off"), ast: compile(&scripts, "kill(1)") },
+            Script { name: String::from("stray"), ast: compile(&scripts, "kill(99)") },
+        ];
+        let mut processes = vec![mock::process(1, "small", "u", 10, 0.0), mock::process(2, "big", "u", 500, 0.0)];
+        let enabled = vec![String::from("hogs"), String::from("endless"), String::from("stray")];
+        let kills = scripts.run(&enabled, &mut processes);
+        assert_eq!(kills, vec![(String::from("hogs"), Target::of(&processes[1]))]);
+        assert_eq!(processes[1].custom.get(BADGE).map(ToString::to_string).as_deref(), Some("hog"));
+        assert!(processes[0].custom.is_empty());
+        let log: Vec<&str> = scripts.log.iter().map(|event| event.text.as_str()).collect();
+        assert_eq!(log[0], "hogs: big");
+        // the endless loop is stopped by the operation limit
+        assert!(log[1].starts_with("endless failed"));
+        assert_eq!(log[2], "stray asked to kill process 99, which isn't in the table");
+    }
+}
@@ -0,0 +1,179 @@
+// security tab - the processes worth a second look when auditing a machine: the ones running
+// as root or SYSTEM, the ones listening on an address other machines can reach, and the ones
+// started from a folder anybody can write to, where a binary can be swapped under them
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use crate::process::ProcessInfo;
+use crate::sockets::Socket;
+use crate::Message;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    Elevated,
+    // the addresses it listens on that aren't loopback
+    Exposed(Vec<SocketAddr>),
+    // the executable, whose folder is world-writable
+    WritableLocation(PathBuf),
+}
+
+impl Finding {
+    pub fn describe(&self) -> String {
+        match self {
+            Finding::Elevated => String::from("runs elevated"),
+            Finding::Exposed(addresses) => {
+                format!("listens on {}", addresses.iter().map(|address| address.to_string()).collect::<Vec<_>>().join(", "))
+            }
+            Finding::WritableLocation(exe) => format!("runs from a world-writable folder ({})", exe.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Flagged {
+    pub pid: u32,
+    pub name: String,
+    pub user: String,
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Audit {
+    pub flagged: Vec<Flagged>,
+    // why the listening sockets are missing, e.g. off linux
+    pub sockets_error: Option<String>,
+}
+
+// a socket other machines can connect to, a listening tcp one or a bound udp one (no state,
+// no peer) on anything but loopback
+fn is_exposed(socket: &Socket) -> bool {
+    let bound = socket.is_listening() || (socket.state.is_empty() && socket.remote.port() == 0);
+    bound && !socket.local.ip().is_loopback()
+}
+
+// the executable's folder can be written by anybody, from its mode bits on unix and by being
+// under the temp folder elsewhere
+pub fn world_writable(exe: &Path) -> bool {
+    let Some(dir) = exe.parent() else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(dir).is_ok_and(|metadata| metadata.permissions().mode() & 0o002 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        dir.starts_with(std::env::temp_dir())
+    }
+}
+
+// the processes with at least one finding, the most findings first then by pid. `writable`
+// is `world_writable`, passed in for the tests
+pub fn audit(processes: &[ProcessInfo], sockets: Result<Vec<(Option<u32>, Socket)>, String>, writable: impl Fn(&Path) -> bool) -> Audit {
+    let (sockets, sockets_error) = match sockets {
+        Ok(sockets) => (sockets, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let mut exposed: HashMap<u32, Vec<SocketAddr>> = HashMap::new();
+    for (pid, socket) in sockets {
+        if let Some(pid) = pid.filter(|_| is_exposed(&socket)) {
+            let addresses = exposed.entry(pid).or_default();
+            if !addresses.contains(&socket.local) {
+                addresses.push(socket.local);
+            }
+        }
+    }
+    let mut flagged: Vec<Flagged> = processes
+        .iter()
+        .filter_map(|process| {
+            let mut findings = Vec::new();
+            if process.elevated {
+                findings.push(Finding::Elevated);
+            }
+            if let Some(mut addresses) = exposed.remove(&process.pid) {
+                addresses.sort();
+                findings.push(Finding::Exposed(addresses));
+            }
+            if let Some(exe) = process.exe.as_ref().filter(|exe| writable(exe)) {
+                findings.push(Finding::WritableLocation(exe.clone()));
+            }
+            (!findings.is_empty()).then(|| Flagged { pid: process.pid, name: process.name.clone(), user: process.user.clone(), findings })
+        })
+        .collect();
+    flagged.sort_by(|a, b| b.findings.len().cmp(&a.findings.len()).then(a.pid.cmp(&b.pid)));
+    Audit { flagged, sockets_error }
+}
+
+// counts of each finding, then a row per flagged process with a button showing it in the table
+pub fn view(audit: &Audit) -> Element<'_, Message> {
+    let count = |check: fn(&Finding) -> bool| audit.flagged.iter().filter(|flagged| flagged.findings.iter().any(check)).count();
+    let mut column = Column::new().spacing(10).push(Text::new(format!(
+        "{} elevated, {} listening on external interfaces, {} running from world-writable folders",
+        count(|finding| *finding == Finding::Elevated),
+        count(|finding| matches!(finding, Finding::Exposed(_))),
+        count(|finding| matches!(finding, Finding::WritableLocation(_))),
+    )));
+    if let Some(err) = &audit.sockets_error {
+        column = column.push(Text::new(format!("Listening sockets left out: {}", err)));
+    }
+    let header = Row::new()
+        .spacing(10)
+        .push(Text::new("PID").width(Length::FillPortion(1)))
+        .push(Text::new("Name").width(Length::FillPortion(2)))
+        .push(Text::new("User").width(Length::FillPortion(2)))
+        .push(Text::new("Findings").width(Length::FillPortion(6)))
+        .push(Text::new("").width(Length::Fixed(60.0)));
+    let rows = audit.flagged.iter().fold(Column::new().spacing(5), |rows, flagged| {
+        rows.push(
+            Row::new()
+                .spacing(10)
+                .align_items(alignment::Alignment::Center)
+                .push(Text::new(flagged.pid.to_string()).width(Length::FillPortion(1)))
+                .push(Text::new(&flagged.name).width(Length::FillPortion(2)))
+                .push(Text::new(&flagged.user).width(Length::FillPortion(2)))
+                .push(Text::new(flagged.findings.iter().map(Finding::describe).collect::<Vec<_>>().join("; ")).width(Length::FillPortion(6)))
+                .push(Button::new("Show").on_press(Message::ShowProcess(flagged.pid)).width(Length::Fixed(60.0))),
+        )
+    });
+    column.push(header).push(Scrollable::new(rows)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    #[test]
+    fn flags_elevated_exposed_and_writable_processes() {
+        let socket = |local: &str, remote: &str, state| Socket { protocol: "tcp", local: local.parse().unwrap(), remote: remote.parse().unwrap(), state, inode: 0 };
+        let processes = [
+            ProcessInfo { elevated: true, ..mock::process(1, "sshd", "root", 0, 0.0) },
+            ProcessInfo { exe: Some(PathBuf::from("/tmp/x/miner")), ..mock::process(2, "miner", "alice", 0, 0.0) },
+            mock::process(3, "bash", "alice", 0, 0.0),
+            mock::process(4, "postgres", "postgres", 0, 0.0),
+        ];
+        let sockets = vec![
+            (Some(1), socket("0.0.0.0:22", "0.0.0.0:0", "LISTEN")),
+            (Some(1), socket("[::]:22", "[::]:0", "LISTEN")),
+            (Some(2), socket("10.0.0.2:5000", "1.2.3.4:443", "ESTABLISHED")),
+            // loopback only
+            (Some(4), socket("127.0.0.1:5432", "0.0.0.0:0", "LISTEN")),
+            (Some(2), socket("0.0.0.0:4000", "0.0.0.0:0", "")),
+        ];
+        let audit = audit(&processes, Ok(sockets), |exe| exe.starts_with("/tmp"));
+        let found: Vec<(u32, usize)> = audit.flagged.iter().map(|flagged| (flagged.pid, flagged.findings.len())).collect();
+        assert_eq!(found, vec![(1, 2), (2, 2)]);
+        assert_eq!(audit.flagged[0].findings[1], Finding::Exposed(vec!["0.0.0.0:22".parse().unwrap(), "[::]:22".parse().unwrap()]));
+        assert_eq!(audit.flagged[1].findings[0], Finding::Exposed(vec!["0.0.0.0:4000".parse().unwrap()]));
+        assert!(super::audit(&processes, Err(String::from("no")), |_| false).sockets_error.is_some());
+    }
+}
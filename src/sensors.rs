@@ -0,0 +1,101 @@
+// sensors tab - component temperatures with max/critical thresholds
+use iced::{
+    widget::{Column, Row, Scrollable, Text},
+    Color, Element, Length,
+};
+
+use sysinfo::Components;
+
+use crate::format;
+use crate::style::Highlights;
+use crate::Message;
+
+// how close to the critical temperature a sensor has to get before it is shown as a warning
+const WARNING_MARGIN: f32 = 10.0;
+// warning temperature for sensors that don't report a critical threshold
+const DEFAULT_WARNING: f32 = 80.0;
+
+#[derive(Debug, Clone)]
+pub struct SensorInfo {
+    // holds info about each temperature sensor, all values in celsius
+    pub label: String,
+    pub temperature: f32,
+    pub max: f32,
+    pub critical: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl SensorInfo {
+    // how worrying the current temperature is relative to the sensor's thresholds
+    pub fn level(&self) -> Level {
+        match self.critical {
+            Some(critical) if self.temperature >= critical => Level::Critical,
+            Some(critical) if self.temperature >= critical - WARNING_MARGIN => Level::Warning,
+            None if self.temperature >= DEFAULT_WARNING => Level::Warning,
+            _ => Level::Normal,
+        }
+    }
+}
+
+// builds the sensor list from sysinfo
+pub fn collect(components: &Components) -> Vec<SensorInfo> {
+    components
+        .iter()
+        .map(|component| SensorInfo {
+            label: component.label().to_string(),
+            temperature: component.temperature(),
+            max: component.max(),
+            critical: component.critical(),
+        })
+        .collect()
+}
+
+fn level_color(level: Level, colors: Highlights) -> Color {
+    match level {
+        Level::Normal => Color::from_rgb(0.6, 0.9, 0.6),
+        Level::Warning => colors.warning,
+        Level::Critical => colors.critical,
+    }
+}
+
+// construct the sensors tab layout
+pub fn view(sensors: &[SensorInfo], colors: Highlights) -> Element<'_, Message> {
+    if sensors.is_empty() {
+        return Text::new("No temperature sensors found").into();
+    }
+    let header = Row::new()
+        .spacing(10)
+        .push(Text::new("Sensor").width(Length::FillPortion(3)))
+        .push(Text::new("Temperature").width(Length::FillPortion(1)))
+        .push(Text::new("Max").width(Length::FillPortion(1)))
+        .push(Text::new("Critical").width(Length::FillPortion(1)));
+    // displays each sensor with its temperature colored by how close it is to critical
+    let rows = sensors.iter().fold(
+        Column::new().spacing(5),
+        |column, sensor| {
+            let critical = sensor
+                .critical
+                .map(|critical| format!("{} °C", format::decimal(f64::from(critical), 1)))
+                .unwrap_or_else(|| String::from("-"));
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(&sensor.label).width(Length::FillPortion(3)))
+                    .push(Text::new(format!("{} °C", format::decimal(f64::from(sensor.temperature), 1))).style(level_color(sensor.level(), colors)).width(Length::FillPortion(1)))
+                    .push(Text::new(format!("{} °C", format::decimal(f64::from(sensor.max), 1))).width(Length::FillPortion(1)))
+                    .push(Text::new(critical).width(Length::FillPortion(1)))
+            )
+        },
+    );
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
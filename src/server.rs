@@ -0,0 +1,271 @@
+// embedded http api, started with --serve
+//
+// answers from what the collector last published, so reads never wait for a refresh.
+// every response is json except /metrics and the /stream websocket, errors are an object
+// with an "error" field
+use serde::Serialize;
+use serde_json::json;
+use sysinfo::{Pid, System, Users};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use std::collections::HashMap;
+use std::net::ToSocketAddrs;
+use std::thread;
+use std::time::Duration;
+
+use crate::collector::{self, Shared};
+use crate::config::Config;
+use crate::kill_guard::{self, Policy, Target};
+use crate::metrics;
+use crate::process::{self, SortColumn};
+use crate::provider::SystemProvider;
+use crate::stream;
+
+const JSON: &str = "application/json";
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    // bearer token every request must carry, None lets local clients in, which is only
+    // allowed on loopback addresses
+    pub token: Option<String>,
+    // processes per ranking exported on /metrics, None leaves the endpoint off
+    pub metrics: Option<usize>,
+}
+
+// binds the address and serves requests on a background thread until the app exits
+pub fn spawn(addr: &str, shared: Shared, options: Options) -> Result<(), String> {
+    if options.token.is_none() && !loopback(addr)? {
+        return Err(format!("{} is reachable from other machines, serving it needs a --token", addr));
+    }
+    let server = Server::http(addr).map_err(|err| format!("can't listen on {}: {}", addr, err))?;
+    thread::Builder::new()
+        .name(String::from("http api"))
+        .spawn(move || {
+            for request in server.incoming_requests() {
+                handle(request, &shared, &options);
+            }
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn handle(request: Request, shared: &Shared, options: &Options) {
+    let allowed = authorized(&request, options.token.as_deref());
+    // the query string isn't used by any endpoint
+    let path = request.url().split('?').next().unwrap_or_default().to_string();
+    if allowed && path.trim_end_matches('/') == "/stream" {
+        if let Some(key) = stream::key(&request) {
+            stream::upgrade(request, &key, shared.clone());
+            return;
+        }
+    }
+    let (status, content_type, body) = if allowed {
+        route(request.method(), &path, shared, options)
+    } else {
+        match options.token {
+            Some(_) => (401, JSON, error("missing or wrong bearer token")),
+            None => (403, JSON, error("requests from web pages or through a host name other than localhost need a --token")),
+        }
+    };
+    tracing::info!(method = %request.method(), path, status, "api request");
+    let content_type = Header::from_bytes("Content-Type", content_type).expect("static header is valid");
+    let response = Response::from_string(body).with_status_code(status).with_header(content_type);
+    // the client may have gone away already, nothing to do about it
+    let _ = request.respond(response);
+}
+
+// whether every address the text resolves to is a loopback one
+fn loopback(addr: &str) -> Result<bool, String> {
+    let addrs: Vec<_> = addr.to_socket_addrs().map_err(|err| format!("can't listen on {}: {}", addr, err))?.collect();
+    Ok(!addrs.is_empty() && addrs.iter().all(|addr| addr.ip().is_loopback()))
+}
+
+// without a configured token only local clients are let in. any web page the user opens can
+// send requests to loopback, so requests carrying an origin (which browsers add to every
+// cross-site post and websocket) are refused, and so are host names other than a loopback
+// one, which is what a page gets after rebinding its own name to 127.0.0.1
+fn authorized(request: &Request, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return header(request, "Origin").is_none() && header(request, "Host").is_some_and(loopback_host);
+    };
+    request
+        .headers()
+        .iter()
+        .filter(|header| header.field.equiv("Authorization"))
+        .filter_map(|header| header.value.as_str().strip_prefix("Bearer "))
+        .any(|given| same(given.as_bytes(), token.as_bytes()))
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request.headers().iter().find(|header| header.field.equiv(name)).map(|header| header.value.as_str())
+}
+
+// whether a host header names localhost or a loopback address, with or without the port
+fn loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+// compares in time that depends only on the length, so the token can't be guessed byte by byte
+fn same(given: &[u8], token: &[u8]) -> bool {
+    given.len() == token.len() && given.iter().zip(token).fold(0, |differ, (a, b)| differ | (a ^ b)) == 0
+}
+
+fn error(text: &str) -> String {
+    json!({ "error": text }).to_string()
+}
+
+fn to_json(value: &impl Serialize) -> String {
+    serde_json::to_string(value).unwrap_or_else(|err| error(&err.to_string()))
+}
+
+// status code, content type and body for a request
+fn route(method: &Method, path: &str, shared: &Shared, options: &Options) -> (u16, &'static str, String) {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    if let (Method::Get, ["metrics"], Some(count)) = (method, segments.as_slice(), options.metrics) {
+        return (200, metrics::CONTENT_TYPE, metrics::render(&shared.read(), count));
+    }
+    let (status, body) = api(method, &segments, shared);
+    (status, JSON, body)
+}
+
+// the json endpoints
+fn api(method: &Method, segments: &[&str], shared: &Shared) -> (u16, String) {
+    match (method, segments) {
+        (Method::Get, ["system"]) => (200, to_json(&shared.read().system)),
+        (Method::Get, ["processes"]) => (200, to_json(&shared.read().processes)),
+        (Method::Get, ["processes", pid]) => {
+            let Ok(pid) = pid.parse::<u32>() else {
+                return (400, error("the pid must be a number"));
+            };
+            match shared.read().processes.iter().find(|process| process.pid == pid) {
+                Some(process) => (200, to_json(process)),
+                None => (404, error(&format!("no process {}", pid))),
+            }
+        }
+        (Method::Post, ["processes", pid, "kill"]) => {
+            let Ok(pid) = pid.parse::<u32>() else {
+                return (400, error("the pid must be a number"));
+            };
+            match kill(pid, shared) {
+                Ok(()) => (200, json!({ "killed": pid }).to_string()),
+                Err(kill_guard::Refusal::Exited) => (404, error(&format!("no process {}", pid))),
+                Err(kill_guard::Refusal::Failed) => (500, error(&format!("failed to kill process {}", pid))),
+                Err(refusal) => (403, error(&refusal.to_string())),
+            }
+        }
+        (_, ["stream"]) => (400, error("/stream only accepts websocket connections")),
+        (_, ["system"] | ["processes"] | ["processes", _] | ["processes", _, "kill"]) => (405, error("method not allowed")),
+        _ => (404, error("no such endpoint")),
+    }
+}
+
+// kills the process the published table has under the pid, with the same checks as the
+// gui. critical processes are never killed from here, there is no kill preview to confirm
+fn kill(pid: u32, shared: &Shared) -> Result<(), kill_guard::Refusal> {
+    let (target, policy, processes) = {
+        let published = shared.read();
+        let process = published.processes.iter().find(|process| process.pid == pid).ok_or(kill_guard::Refusal::Exited)?;
+        let policy = Policy { read_only: published.read_only, local: true, critical: published.critical.clone() };
+        (Target::of(process), policy, vec![process.clone()])
+    };
+    let mut system = System::new();
+    system.refresh_process(Pid::from(pid as usize));
+    kill_guard::kill(&target, &policy, &processes, &mut SystemProvider, &system)
+}
+
+// collects and publishes forever at the process table interval, used when there is no window
+pub fn run_headless(shared: Shared, config: &Config) -> ! {
+    let mut system = System::new();
+    let users = Users::new_with_refreshed_list();
+    let mut provider = SystemProvider;
+    let mut cpu_history = HashMap::new();
+    let interval = Duration::from_secs(config.refresh_intervals.processes.max(1));
+    shared.set_policy(config.read_only, &config.critical);
+    collector::warm_up(&mut system);
+    loop {
+        system.refresh_cpu();
+        system.refresh_memory();
+        let mut processes = collector::collect(&mut provider, &mut system, &users, &mut cpu_history, config);
+        process::sort(&mut processes, SortColumn::Pid, true, SortColumn::Pid);
+        shared.publish(&system, &processes);
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+    use tiny_http::TestRequest;
+
+    #[test]
+    fn routes_answer_from_the_published_table() {
+        let shared = Shared::default();
+        shared.publish(&System::new(), &[mock::process(7, "editor", "u", 10, 2.0)]);
+        let (status, _, body) = route(&Method::Get, "/processes", &shared, &Options::default());
+        assert_eq!(status, 200);
+        let processes: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(processes[0]["name"], "editor");
+        assert_eq!(route(&Method::Get, "/processes/7/", &shared, &Options::default()).0, 200);
+        assert_eq!(route(&Method::Get, "/processes/8", &shared, &Options::default()).0, 404);
+        assert_eq!(route(&Method::Get, "/processes/x", &shared, &Options::default()).0, 400);
+        assert_eq!(route(&Method::Get, "/system", &shared, &Options::default()).0, 200);
+        assert_eq!(route(&Method::Delete, "/processes", &shared, &Options::default()).0, 405);
+        assert_eq!(route(&Method::Get, "/nope", &shared, &Options::default()).0, 404);
+        // /metrics only exists when it was asked for
+        assert_eq!(route(&Method::Get, "/metrics", &shared, &Options::default()).0, 404);
+        assert_eq!(route(&Method::Get, "/metrics", &shared, &Options { metrics: Some(5), ..Options::default() }).1, metrics::CONTENT_TYPE);
+        assert_eq!(route(&Method::Post, "/processes/8/kill", &shared, &Options::default()).0, 404);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kills_are_checked_against_the_current_policy() {
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let shared = Shared::default();
+        shared.publish(&System::new(), &[mock::process(std::process::id(), "conborli", "u", 10, 0.0), mock::process(child.id(), "sleep", "u", 10, 0.0)]);
+        let kill = |pid: u32| route(&Method::Post, &format!("/processes/{}/kill", pid), &shared, &Options::default());
+        assert_eq!(kill(std::process::id()).0, 403);
+        // read-only mode turned on after the server started still counts
+        shared.set_policy(true, &[]);
+        assert_eq!(kill(child.id()), (403, JSON, error("read-only mode is on")));
+        shared.set_policy(false, &[String::from("sleep")]);
+        assert_eq!(kill(child.id()), (403, JSON, error("critical processes are only killed from the kill preview")));
+        shared.set_policy(false, &[]);
+        assert_eq!(kill(child.id()).0, 200);
+        assert!(child.wait().is_ok_and(|status| !status.success()));
+    }
+
+    #[test]
+    fn tokens_are_required_off_loopback() {
+        assert_eq!(loopback("127.0.0.1:7878"), Ok(true));
+        assert_eq!(loopback("[::1]:7878"), Ok(true));
+        assert_eq!(loopback("0.0.0.0:7878"), Ok(false));
+        assert!(spawn("0.0.0.0:0", Shared::default(), Options::default()).is_err());
+        assert!(same(b"secret", b"secret"));
+        assert!(!same(b"secreT", b"secret"));
+        assert!(!same(b"secret!", b"secret"));
+    }
+
+    #[test]
+    fn web_pages_need_a_token() {
+        let request = |headers: &[(&str, &str)]| {
+            let request = headers.iter().fold(TestRequest::new().with_method(Method::Post), |request, (field, value)| {
+                request.with_header(Header::from_bytes(*field, *value).unwrap())
+            });
+            Request::from(request)
+        };
+        assert!(authorized(&request(&[("Host", "127.0.0.1:7878")]), None));
+        assert!(authorized(&request(&[("Host", "localhost")]), None));
+        assert!(authorized(&request(&[("Host", "[::1]:7878")]), None));
+        // a page posting to the api, and one that rebound its name to loopback
+        assert!(!authorized(&request(&[("Host", "127.0.0.1:7878"), ("Origin", "https://example.com")]), None));
+        assert!(!authorized(&request(&[("Host", "attacker.example:7878")]), None));
+        assert!(!authorized(&request(&[]), None));
+        assert!(authorized(&request(&[("Host", "attacker.example"), ("Authorization", "Bearer secret")]), Some("secret")));
+    }
+}
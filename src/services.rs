@@ -0,0 +1,185 @@
+// services tab - system services with their state and start/stop/restart actions
+//
+// backed by `systemctl` on Linux and `sc` on Windows, other platforms show an empty list
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text},
+    Color, Element, Length,
+};
+
+use std::process::Command;
+
+use crate::style::Highlights;
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceState {
+    Running,
+    Stopped,
+    Failed,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceInfo {
+    // holds info about each service
+    pub name: String,
+    pub description: String,
+    pub state: ServiceState,
+    // raw state as reported by the service manager, e.g. "exited" or "START_PENDING"
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    fn verb(self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+        }
+    }
+}
+
+// lists every service known to the service manager
+pub fn collect() -> Result<Vec<ServiceInfo>, String> {
+    if cfg!(target_os = "windows") {
+        run("sc", &["query", "state=", "all"]).map(|output| parse_sc(&output))
+    } else if cfg!(target_os = "linux") {
+        run("systemctl", &["list-units", "--type=service", "--all", "--no-legend", "--no-pager", "--plain"])
+            .map(|output| parse_systemctl(&output))
+    } else {
+        Err(String::from("services are not supported on this platform"))
+    }
+}
+
+// performs the action, blocking until the service manager answers (which may include an auth prompt)
+pub fn perform(name: &str, action: ServiceAction) -> Result<(), String> {
+    if cfg!(target_os = "windows") {
+        // sc has no restart, so stop and then start
+        if action != ServiceAction::Start {
+            run("sc", &["stop", name])?;
+        }
+        if action != ServiceAction::Stop {
+            run("sc", &["start", name])?;
+        }
+        Ok(())
+    } else {
+        run("systemctl", &[action.verb(), name]).map(|_| ())
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|err| format!("failed to run {}: {}", program, err))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+// each line is "UNIT LOAD ACTIVE SUB DESCRIPTION..."
+fn parse_systemctl(output: &str) -> Vec<ServiceInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let _load = fields.next()?;
+            let active = fields.next()?;
+            let sub = fields.next()?.to_string();
+            let description = fields.collect::<Vec<_>>().join(" ");
+            let state = match active {
+                "active" if sub == "running" => ServiceState::Running,
+                "failed" => ServiceState::Failed,
+                "inactive" => ServiceState::Stopped,
+                _ => ServiceState::Other,
+            };
+            Some(ServiceInfo { name, description, state, detail: sub })
+        })
+        .collect()
+}
+
+// blocks of "SERVICE_NAME: x", "DISPLAY_NAME: y", "STATE : 4 RUNNING" lines
+fn parse_sc(output: &str) -> Vec<ServiceInfo> {
+    let mut services = Vec::new();
+    let mut current: Option<ServiceInfo> = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("SERVICE_NAME:") {
+            services.extend(current.take());
+            current = Some(ServiceInfo {
+                name: name.trim().to_string(),
+                description: String::new(),
+                state: ServiceState::Other,
+                detail: String::new(),
+            });
+        } else if let Some(service) = current.as_mut() {
+            if let Some(display) = line.strip_prefix("DISPLAY_NAME:") {
+                service.description = display.trim().to_string();
+            } else if let Some(state) = line.strip_prefix("STATE") {
+                let detail = state.split_whitespace().last().unwrap_or_default().to_string();
+                service.state = match detail.as_str() {
+                    "RUNNING" => ServiceState::Running,
+                    "STOPPED" => ServiceState::Stopped,
+                    _ => ServiceState::Other,
+                };
+                service.detail = detail;
+            }
+        }
+    }
+    services.extend(current);
+    services
+}
+
+fn state_color(state: ServiceState, colors: Highlights) -> Color {
+    match state {
+        ServiceState::Running => Color::from_rgb(0.6, 0.9, 0.6),
+        ServiceState::Stopped => Color::from_rgb(0.7, 0.7, 0.7),
+        ServiceState::Failed => colors.critical,
+        ServiceState::Other => colors.warning,
+    }
+}
+
+// construct the services tab layout
+pub fn view<'a>(services: &'a [ServiceInfo], error: Option<&'a str>, colors: Highlights) -> Element<'a, Message> {
+    if let Some(error) = error {
+        return Text::new(error).into();
+    }
+    let header = Row::new()
+        .spacing(10)
+        .push(Text::new("Service").width(Length::FillPortion(2)))
+        .push(Text::new("State").width(Length::FillPortion(1)))
+        .push(Text::new("Description").width(Length::FillPortion(3)))
+        .push(Text::new("Actions").width(Length::FillPortion(2)));
+    // displays each service with buttons for the actions that make sense in its state
+    let rows = services.iter().fold(
+        Column::new().spacing(5),
+        |column, service| {
+            let running = service.state == ServiceState::Running;
+            let start = Button::new("Start").on_press_maybe((!running).then(|| Message::ServiceAction(service.name.clone(), ServiceAction::Start)));
+            let stop = Button::new("Stop").on_press_maybe(running.then(|| Message::ServiceAction(service.name.clone(), ServiceAction::Stop)));
+            let restart = Button::new("Restart").on_press(Message::ServiceAction(service.name.clone(), ServiceAction::Restart));
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(&service.name).width(Length::FillPortion(2)))
+                    .push(Text::new(&service.detail).style(state_color(service.state, colors)).width(Length::FillPortion(1)))
+                    .push(Text::new(&service.description).width(Length::FillPortion(3)))
+                    .push(Row::new().spacing(5).push(start).push(stop).push(restart).width(Length::FillPortion(2)))
+            )
+        },
+    );
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
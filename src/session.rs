@@ -0,0 +1,204 @@
+// session recordings - a flight recorder for resource spikes that don't happen while anyone
+// is watching
+//
+// while recording, the full process table of every refresh is appended to a file in the
+// sessions directory next to the config, one json line per frame. playing a recording back
+// shows its frames in the processes tab instead of the live table, with a slider to move
+// through them
+use chrono::{DateTime, Local};
+use iced::{
+    alignment,
+    widget::{Button, PickList, Row, Slider, Text},
+    Element, Length,
+};
+use serde::{Deserialize, Serialize};
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::format::{self, format_bytes};
+use crate::process::ProcessInfo;
+use crate::Message;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Frame {
+    // unix time of the refresh
+    pub at: u64,
+    pub processes: Vec<ProcessInfo>,
+}
+
+// e.g. ~/.config/conborli/sessions
+fn dir() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("sessions"))
+}
+
+fn file(name: &str) -> Option<PathBuf> {
+    dir().map(|dir| dir.join(format!("{}.jsonl", name)))
+}
+
+// names of the stored recordings, newest first since they are named after their start time
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "jsonl"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    names.sort_by(|a, b| b.cmp(a));
+    names
+}
+
+fn clock(at: u64) -> String {
+    DateTime::from_timestamp(at as i64, 0)
+        .map(|at| format::time(&at.with_timezone(&Local)))
+        .unwrap_or_default()
+}
+
+pub struct Recording {
+    pub name: String,
+    file: File,
+    pub frames: usize,
+    pub bytes: u64,
+}
+
+impl Recording {
+    // a new file named after the current time
+    pub fn start() -> Result<Recording, String> {
+        let name = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+        let path = file(&name).ok_or("no config directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(Recording { name, file, frames: 0, bytes: 0 })
+    }
+
+    // one line per frame, written at once so a crash loses at most the last frame
+    pub fn write(&mut self, processes: &[ProcessInfo]) -> Result<(), String> {
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default();
+        let mut line = serde_json::to_string(&Frame { at, processes: processes.to_vec() }).map_err(|err| err.to_string())?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes()).map_err(|err| err.to_string())?;
+        self.frames += 1;
+        self.bytes += line.len() as u64;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub name: String,
+    pub frames: Vec<Frame>,
+    // frame shown in the processes tab
+    pub position: usize,
+    // steps a frame on every process refresh
+    pub playing: bool,
+}
+
+// frames in file order, lines that don't parse (e.g. the last one of a recording cut short)
+// are skipped
+fn parse(contents: &str) -> Vec<Frame> {
+    contents.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+}
+
+impl Playback {
+    pub fn load(name: &str) -> Result<Playback, String> {
+        let path = file(name).ok_or("no config directory on this platform")?;
+        let contents = fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let frames = parse(&contents);
+        if frames.is_empty() {
+            return Err(format!("recording {} has no frames", name));
+        }
+        Ok(Playback { name: name.to_string(), frames, position: 0, playing: false })
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frames[self.position]
+    }
+
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(self.frames.len() - 1);
+    }
+
+    // moves to the next frame while playing, pausing on the last one
+    pub fn step(&mut self) {
+        if !self.playing {
+            return;
+        }
+        if self.position + 1 < self.frames.len() {
+            self.position += 1;
+        } else {
+            self.playing = false;
+        }
+    }
+}
+
+// construct the row above the process table: record and play while live, the slider while
+// playing back
+pub fn bar_view<'a>(
+    recording: Option<&'a Recording>,
+    playback: Option<&'a Playback>,
+    recordings: &'a [String],
+    picked: Option<&'a String>,
+) -> Element<'a, Message> {
+    let row = Row::new().spacing(10).align_items(alignment::Alignment::Center);
+    if let Some(playback) = playback {
+        let last = playback.frames.len() - 1;
+        return row
+            .push(Text::new(format!("Replaying {}", playback.name)))
+            .push(Button::new(if playback.playing { "Pause" } else { "Play" }).on_press(Message::TogglePlaying))
+            .push_maybe((last > 0).then(|| {
+                Slider::new(0..=last as u32, playback.position as u32, Message::SeekPlayback).width(Length::Fixed(300.0))
+            }))
+            .push(Text::new(format!("frame {} of {} at {}", playback.position + 1, last + 1, clock(playback.frame().at))))
+            .push(Button::new("Back to live").on_press(Message::StopPlayback))
+            .into();
+    }
+    let row = match recording {
+        Some(recording) => row
+            .push(Text::new(format!(
+                "● Recording {}: {} frames, {}",
+                recording.name,
+                recording.frames,
+                format_bytes(recording.bytes as f64)
+            )))
+            .push(Button::new("Stop recording").on_press(Message::StopRecording)),
+        None => row.push(Button::new("● Record").on_press(Message::StartRecording)),
+    };
+    row.push(
+        PickList::new(recordings, picked.cloned(), Message::PickRecording)
+            .placeholder("Pick a recording")
+            .width(Length::Fixed(220.0))
+    )
+    .push(Button::new("Replay").on_press_maybe(picked.map(|_| Message::StartPlayback)))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn playback_skips_a_cut_off_frame_and_pauses_at_the_end() {
+        let frame = |at, pid| serde_json::to_string(&Frame { at, processes: vec![mock::process(pid, "job", "u", 10, 1.0)] }).unwrap();
+        let contents = format!("{}\n{}\n{{\"at\": 3, \"proc", frame(1, 10), frame(2, 11));
+        let mut playback = Playback { name: String::from("test"), frames: parse(&contents), position: 0, playing: true };
+        assert_eq!(playback.frames.len(), 2);
+        playback.step();
+        assert_eq!(playback.frame().processes[0].pid, 11);
+        playback.step();
+        assert!(!playback.playing);
+        assert_eq!(playback.position, 1);
+        playback.seek(9);
+        assert_eq!(playback.position, 1);
+    }
+}
@@ -0,0 +1,446 @@
+// settings tab - edits the persisted config
+use iced::{
+    widget::{Button, Checkbox, Column, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+
+use crate::accent::ThemeChoice;
+use crate::config::Config;
+use crate::format::{Clock, NumberStyle};
+use crate::online::{self, OnlineFeature};
+use crate::process::SortColumn;
+use crate::scheduler::Source;
+use crate::style::{Density, MAX_TEXT_SIZE, MIN_TEXT_SIZE};
+use crate::summary::SummaryFormat;
+use crate::themes;
+use crate::{Message, TaskManager};
+
+// smallest and largest zoom offered by the -/+ buttons
+const MIN_SCALE: f64 = 0.5;
+const MAX_SCALE: f64 = 3.0;
+
+// construct the settings tab layout
+pub fn view(app: &TaskManager) -> Element<'_, Message> {
+    let config = &app.config;
+    let current_display = app.current_display.as_str();
+    // ui scale with buttons to step it by 10%
+    let scale = config.scale_for(current_display);
+    let scale_row = Row::new()
+        .spacing(10)
+        .push(Text::new("UI scale").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press(Message::SetUiScale((config.ui_scale - 0.1).max(MIN_SCALE))))
+        .push(Text::new(format!("{:.0}%", config.ui_scale * 100.0)))
+        .push(Button::new("+").on_press(Message::SetUiScale((config.ui_scale + 0.1).min(MAX_SCALE))))
+        .push(Text::new(format!("(effective on this display: {:.0}%)", scale * 100.0)));
+
+    // default text size, stepped by 1px, and the row height and spacing of the views
+    let restart = (f32::from(config.text_size) != app.ui.text_size).then(|| Text::new("(applies after a restart)"));
+    let text_row = Row::new()
+        .spacing(10)
+        .push(Text::new("Text size").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press_maybe((config.text_size > MIN_TEXT_SIZE).then(|| Message::SetTextSize(config.text_size - 1))))
+        .push(Text::new(format!("{}px", config.text_size)))
+        .push(Button::new("+").on_press_maybe((config.text_size < MAX_TEXT_SIZE).then(|| Message::SetTextSize(config.text_size + 1))))
+        .push_maybe(restart);
+    let density_row = Density::ALL
+        .iter()
+        .fold(Row::new().spacing(10).push(Text::new("Density").width(Length::FillPortion(2))), |row, &density| {
+            let button = Button::new(density.label());
+            row.push(if density == config.density { button } else { button.on_press(Message::SetDensity(density)) })
+        });
+
+    // separators of decimals and thousands, and the clock of times of day
+    let number_row = NumberStyle::ALL
+        .iter()
+        .fold(Row::new().spacing(10).push(Text::new("Numbers").width(Length::FillPortion(2))), |row, &style| {
+            let button = Button::new(style.label());
+            row.push(if style == config.number_style { button } else { button.on_press(Message::SetNumberStyle(style)) })
+        });
+    let clock_row = Clock::ALL
+        .iter()
+        .fold(Row::new().spacing(10).push(Text::new("Clock").width(Length::FillPortion(2))), |row, &clock| {
+            let button = Button::new(clock.label());
+            row.push(if clock == config.clock { button } else { button.on_press(Message::SetClock(clock)) })
+        });
+
+    // global shortcut, applied when pressing enter or the apply button
+    let mut hotkey_row = Row::new()
+        .spacing(10)
+        .push(Text::new("Show window shortcut").width(Length::FillPortion(2)))
+        .push(
+            TextInput::new("ctrl+shift+Escape", &app.hotkey_input)
+                .on_input(Message::HotkeyInputChanged)
+                .on_submit(Message::ApplyHotkey)
+                .width(Length::FillPortion(2))
+        )
+        .push(Button::new("Apply").on_press(Message::ApplyHotkey));
+    if let Some(status) = &app.hotkey_status {
+        hotkey_row = hotkey_row.push(Text::new(status));
+    }
+
+    // tray icon and what closing or starting the app does with it
+    let tray_row = Row::new()
+        .spacing(20)
+        .push(Checkbox::new("Show tray icon", config.tray_icon).on_toggle(Message::ToggleTrayIcon))
+        .push(Checkbox::new("Close to tray", config.close_to_tray).on_toggle(Message::ToggleCloseToTray))
+        .push(Checkbox::new("Start minimized", config.start_minimized).on_toggle(Message::ToggleStartMinimized));
+
+    // the entry is written right away, the minimized box edits the installed one
+    let autostart_row = Row::new()
+        .spacing(20)
+        .push(Checkbox::new("Start on login", app.autostart.is_some()).on_toggle(Message::ToggleAutostart))
+        .push(
+            Checkbox::new("Minimized to tray", app.autostart.is_some_and(|autostart| autostart.minimized))
+                .on_toggle_maybe(app.autostart.is_some().then_some(Message::ToggleAutostartMinimized))
+        );
+
+    // theme and accent color, with a note when the desktop doesn't report one
+    let mut accent_row = ThemeChoice::ALL
+        .iter()
+        .fold(Row::new().spacing(10).push(Text::new("Theme")), |row, &choice| {
+            let button = Button::new(choice.label());
+            let active = choice == config.theme && config.custom_theme.is_none();
+            row.push(if active { button } else { button.on_press(Message::SetTheme(choice)) })
+        })
+        .push(
+            // a theme file brings its own primary color
+            Checkbox::new("Use system accent color", config.use_system_accent)
+                .on_toggle_maybe(config.custom_theme.is_none().then_some(Message::ToggleSystemAccent))
+        );
+    if config.use_system_accent && config.custom_theme.is_none() && app.system_accent.is_none() {
+        accent_row = accent_row.push(Text::new("(no accent color found, using the default)"));
+    }
+
+    // theme files, the ones that don't parse can't be picked and say why
+    let location = themes::dir().map(|dir| dir.display().to_string()).unwrap_or_else(|| String::from("the config directory"));
+    let theme_files = app.themes.iter().fold(
+        Row::new().spacing(10).push(Text::new("Theme files")),
+        |row, theme| {
+            let button = Button::new(theme.name.as_str());
+            let active = config.custom_theme.as_deref() == Some(theme.name.as_str());
+            row.push(if active || theme.colors.is_err() { button } else { button.on_press(Message::SetCustomTheme(theme.name.clone())) })
+        },
+    )
+    .push(Button::new("Reload themes").on_press(Message::ReloadThemes));
+    let mut theme_notes = app
+        .themes
+        .iter()
+        .filter_map(|theme| theme.colors.as_ref().err().map(|err| format!("{} doesn't parse: {}", theme.name, err)))
+        .fold(Column::new().spacing(5), |column, note| column.push(Text::new(note)));
+    if let Some(name) = config.custom_theme.as_ref().filter(|name| !app.themes.iter().any(|theme| &theme.name == *name)) {
+        theme_notes = theme_notes.push(Text::new(format!("{} isn't in {}, using the base theme", name, location)));
+    }
+    if app.themes.is_empty() {
+        theme_notes = theme_notes.push(Text::new(format!("Add .toml or .json theme files to {}", location)));
+    }
+
+    // refresh interval of each data source
+    let intervals = Source::ALL.iter().fold(
+        Column::new().spacing(5),
+        |column, &source| {
+            let seconds = config.refresh_intervals.get(source);
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(source.label()).width(Length::FillPortion(2)))
+                    .push(Button::new("-").on_press(Message::SetRefreshInterval(source, seconds.saturating_sub(1))))
+                    .push(Text::new(format!("{} s", seconds)))
+                    .push(Button::new("+").on_press(Message::SetRefreshInterval(source, seconds + 1)))
+            )
+        },
+    );
+
+    let adaptive_row = Checkbox::new("Refresh less often while the system is under heavy load", config.adaptive_refresh)
+        .on_toggle(Message::ToggleAdaptiveRefresh);
+    let battery_row = Checkbox::new("Refresh less often and pause the charts on battery or while the window is hidden", config.battery_saver)
+        .on_toggle(Message::ToggleBatterySaver);
+    // minimized or hidden windows always pause, unless rules, watches or recordings need the table
+    let unfocused_row = Checkbox::new("Pause updates while the window isn't focused", config.pause_when_unfocused)
+        .on_toggle(Message::TogglePauseWhenUnfocused);
+
+    // cpu column smoothing, 1 sample means raw values
+    let samples = config.cpu_smoothing;
+    let smoothing_row = Row::new()
+        .spacing(10)
+        .push(Text::new("CPU smoothing").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press(Message::SetCpuSmoothing(samples.saturating_sub(1))))
+        .push(Text::new(if samples > 1 { format!("{} samples", samples) } else { String::from("off") }))
+        .push(Button::new("+").on_press(Message::SetCpuSmoothing(samples + 1)));
+
+    // memory increases in a row that flag a process as a suspected leak
+    let leak_samples = config.leak_samples;
+    let leak_row = Row::new()
+        .spacing(10)
+        .push(Text::new("Suspect a leak after").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press(Message::SetLeakSamples(leak_samples.saturating_sub(1))))
+        .push(Text::new(format!("{} growing samples in a row", leak_samples)))
+        .push(Button::new("+").on_press(Message::SetLeakSamples(leak_samples + 1)));
+
+    // tiebreaker column, the current one is shown without a press handler
+    let secondary_row = SortColumn::ALL.iter().fold(
+        Row::new()
+            .spacing(10)
+            .push(Text::new("Break sort ties by").width(Length::FillPortion(2))),
+        |row, &column| {
+            let button = Button::new(column.title());
+            row.push(if column == config.secondary_sort {
+                button
+            } else {
+                button.on_press(Message::SetSecondarySort(column))
+            })
+        },
+    );
+
+    // keeps rows from moving under the pointer
+    let freeze_row = Checkbox::new("Don't reorder the process list while pointing at it or a row is selected", config.freeze_order)
+        .on_toggle(Message::ToggleFreezeOrder);
+    // a boost from the row menu, with the time it lasts
+    let boost_minutes = config.boost_minutes;
+    let boost_row = Row::new()
+        .spacing(10)
+        .push(Text::new("Boost for").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press(Message::SetBoostMinutes(boost_minutes.saturating_sub(5))))
+        .push(Text::new(if boost_minutes > 0 { format!("{} minutes", boost_minutes) } else { String::from("until ended") }))
+        .push(Button::new("+").on_press(Message::SetBoostMinutes(boost_minutes + 5)))
+        .push(Checkbox::new("Lower the user's other processes meanwhile", config.boost_lowers_others).on_toggle(Message::ToggleBoostLowersOthers));
+    let undo_row = Checkbox::new("Wait 5 seconds before killing, with a button to undo", config.undo_kills)
+        .on_toggle(Message::ToggleUndoKills);
+    // a --read-only run stays read-only
+    let read_only_row = Checkbox::new("Read-only: refuse killing, renicing, suspending and other changes to processes", config.read_only)
+        .on_toggle_maybe((!app.overrides.read_only()).then_some(Message::ToggleReadOnly));
+    let heat_row = Checkbox::new("Show the top CPU and memory users above the process list", config.heat_panel)
+        .on_toggle(Message::ToggleHeatPanel);
+    let totals_row = Checkbox::new("Show memory totals and average CPU of the listed processes", config.totals_row)
+        .on_toggle(Message::ToggleTotalsRow);
+    let bar_cells_row = Checkbox::new("Draw CPU and memory cells as bars", config.bar_cells)
+        .on_toggle(Message::ToggleBarCells);
+    // memory use that brings up the low memory banner, stepped by 5 points
+    let low_memory = config.low_memory_percent;
+    let low_memory_row = Row::new()
+        .spacing(10)
+        .push(Text::new("Low memory banner at").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press(Message::SetLowMemoryThreshold(low_memory.saturating_sub(5))))
+        .push(Text::new(if low_memory > 0 { format!("{}% in use", low_memory) } else { String::from("never") }))
+        .push(Button::new("+").on_press_maybe((low_memory < 95).then(|| Message::SetLowMemoryThreshold(low_memory + 5))));
+    let low_memory_window_row = Checkbox::new("Bring the window back from the tray when memory runs low", config.low_memory_shows_window)
+        .on_toggle(Message::ToggleLowMemoryShowsWindow);
+    // peaks, top users, kills and alerts of the run, written to the summaries folder on exit
+    let summary_row = SummaryFormat::ALL
+        .iter()
+        .fold(Row::new().spacing(10).push(Text::new("Session summary on exit").width(Length::FillPortion(2))), |row, &format| {
+            let button = Button::new(format.label());
+            row.push(if format == config.summary_on_exit { button } else { button.on_press(Message::SetSummaryOnExit(format)) })
+        });
+    let background_row = Checkbox::new("Keep collecting history and checking alert rules after the window closes", config.background_monitor)
+        .on_toggle(Message::ToggleBackgroundMonitor);
+    // below this window width the process table drops the pid and memory columns and puts
+    // the name on a line of its own, stepped by 100 px
+    let narrow_width = config.narrow_width;
+    let narrow_row = Row::new()
+        .spacing(10)
+        .push(Text::new("Narrow table below").width(Length::FillPortion(2)))
+        .push(Button::new("-").on_press(Message::SetNarrowWidth(narrow_width.saturating_sub(100))))
+        .push(Text::new(if narrow_width > 0 { format!("{} px wide", narrow_width) } else { String::from("never") }))
+        .push(Button::new("+").on_press(Message::SetNarrowWidth(narrow_width + 100)));
+    let icons_row = Checkbox::new("Show program icons next to process names", config.process_icons)
+        .on_toggle(Message::ToggleProcessIcons);
+    let load_title_row = Checkbox::new("Show the load average and uptime in the window title", config.load_in_title)
+        .on_toggle(Message::ToggleLoadInTitle);
+
+    // one core at full load is 100% by default, like top; the alternative caps the total at 100%
+    let cpu_scale_row = Checkbox::new("Show CPU as % of the whole machine instead of one core", config.cpu_of_whole_machine)
+        .on_toggle(Message::ToggleCpuOfWholeMachine);
+
+    // thresholds below which a process counts as idle and is hidden
+    let noise = config.noise_filter;
+    let noise_row = Row::new()
+        .spacing(10)
+        .push(Checkbox::new("Hide low-activity processes", noise.enabled).on_toggle(Message::ToggleNoiseFilter).width(Length::FillPortion(2)))
+        .push(Text::new("below"))
+        .push(Button::new("-").on_press(Message::SetNoiseMinCpu(noise.min_cpu - 0.1)))
+        .push(Text::new(format!("{:.1}% CPU", noise.min_cpu)))
+        .push(Button::new("+").on_press(Message::SetNoiseMinCpu(noise.min_cpu + 0.1)))
+        .push(Text::new("and"))
+        .push(Button::new("-").on_press(Message::SetNoiseMinMemory(noise.min_memory_mb.saturating_sub(10))))
+        .push(Text::new(format!("{} MB", noise.min_memory_mb)))
+        .push(Button::new("+").on_press(Message::SetNoiseMinMemory(noise.min_memory_mb + 10)));
+
+    // network access gate and the state of every feature behind it
+    let online_features = OnlineFeature::ALL.iter().fold(
+        Column::new()
+            .spacing(5)
+            .push(Checkbox::new("Allow network access", config.allow_network_access).on_toggle(Message::ToggleNetworkAccess)),
+        |column, &feature| {
+            let state = match online::ensure_allowed(config, feature) {
+                Ok(()) => String::from("allowed"),
+                Err(_) => String::from(online::DISABLED_REASON),
+            };
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(feature.label()).width(Length::FillPortion(2)))
+                    .push(Text::new(state).width(Length::FillPortion(3)))
+            )
+        },
+    );
+
+    // names left out of the table and the rules
+    let ignored = config.ignored.names().iter().fold(
+        Row::new().spacing(10).push(Text::new("Ignored processes").width(Length::FillPortion(2))),
+        |row, name| row.push(Button::new(Text::new(format!("{} ✕", name))).on_press(Message::RemoveIgnored(name.clone()))),
+    );
+    let ignore_row = Row::new()
+        .spacing(10)
+        .push(
+            TextInput::new("process name, e.g. chrome_crashpad_handler", &app.ignore_input)
+                .on_input(Message::IgnoreInputChanged)
+                .on_submit(Message::AddIgnored)
+                .width(Length::Fixed(350.0))
+        )
+        .push(Button::new("Ignore").on_press_maybe((!app.ignore_input.trim().is_empty()).then_some(Message::AddIgnored)));
+
+    // names added to the built-in critical processes, killing one asks for an extra confirmation
+    let critical = config.critical.iter().fold(
+        Row::new().spacing(10).push(Text::new("Critical processes").width(Length::FillPortion(2))),
+        |row, name| row.push(Button::new(Text::new(format!("{} ✕", name))).on_press(Message::RemoveCritical(name.clone()))),
+    );
+    let critical_row = Row::new()
+        .spacing(10)
+        .push(
+            TextInput::new("process name, e.g. sshd", &app.critical_input)
+                .on_input(Message::CriticalInputChanged)
+                .on_submit(Message::AddCritical)
+                .width(Length::Fixed(350.0))
+        )
+        .push(Button::new("Protect").on_press_maybe((!app.critical_input.trim().is_empty()).then_some(Message::AddCritical)));
+
+    // views opened from the tray menu, typed as a link or taken from the processes tab
+    let links = config.tray_links.iter().enumerate().fold(
+        Row::new().spacing(10).push(Text::new("Views in the tray menu").width(Length::FillPortion(2))),
+        |row, (index, link)| row.push(Button::new(Text::new(format!("{} ✕", link))).on_press(Message::RemoveTrayLink(index))),
+    );
+    let links_row = Row::new()
+        .spacing(10)
+        .push(
+            TextInput::new("link, e.g. processes?sort=cpu&order=desc", &app.tray_link_input)
+                .on_input(Message::TrayLinkInputChanged)
+                .on_submit(Message::AddTrayLink)
+                .width(Length::Fixed(350.0))
+        )
+        .push(Button::new("Add").on_press_maybe((!app.tray_link_input.trim().is_empty()).then_some(Message::AddTrayLink)))
+        .push(Button::new("Add the processes tab's view").on_press(Message::AddProcessesView));
+
+    // rule set export/import, for sharing the same rules between machines
+    let rules_row = Row::new()
+        .spacing(10)
+        .push(Text::new(format!("{} rules", config.rules.len())).width(Length::FillPortion(2)))
+        .push(
+            TextInput::new("rules file", &app.rules_path_input)
+                .on_input(Message::RulesPathChanged)
+                .width(Length::FillPortion(3))
+        )
+        .push(Button::new("Export").on_press(Message::ExportRules))
+        .push(Button::new("Import").on_press(Message::ImportRules));
+
+    // the whole configuration with the theme files, for moving a setup to another machine
+    let settings_row = Row::new()
+        .spacing(10)
+        .push(Text::new("All settings").width(Length::FillPortion(2)))
+        .push(
+            TextInput::new("settings file", &app.settings_path_input)
+                .on_input(Message::SettingsPathChanged)
+                .width(Length::FillPortion(3))
+        )
+        .push(Button::new("Export settings").on_press(Message::ExportSettings))
+        .push(Button::new("Import settings").on_press(Message::ImportSettings));
+
+    // metrics recorder, the samples are browsed in the history tab
+    let recorder = &config.recorder;
+    let database = recorder
+        .path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| String::from("unavailable"));
+    let recorder_row = Row::new()
+        .spacing(10)
+        .push(Checkbox::new("Record metrics to a local database", recorder.enabled).on_toggle(Message::ToggleRecorder).width(Length::FillPortion(2)))
+        .push(Text::new("keep"))
+        .push(Button::new("-").on_press(Message::SetRetentionDays(recorder.retention_days.saturating_sub(1))))
+        .push(Text::new(format!("{} days", recorder.retention_days)))
+        .push(Button::new("+").on_press(Message::SetRetentionDays(recorder.retention_days + 1)))
+        .push(Text::new(format!("in {}", database)));
+
+    // remembered window geometry for each display with a button to forget it
+    let displays = config.window_geometry.iter().fold(
+        Column::new().spacing(5),
+        |column, (name, geometry)| {
+            let marker = if name == current_display { " (current)" } else { "" };
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(format!("{}{}", name, marker)).width(Length::FillPortion(2)))
+                    .push(Text::new(format!("{}x{} at {}, {}", geometry.width, geometry.height, geometry.x, geometry.y)).width(Length::FillPortion(2)))
+                    .push(Button::new("Forget").on_press(Message::ForgetDisplayGeometry(name.clone())))
+            )
+        },
+    );
+
+    // display bounds and per-display scale are edited in the config file
+    let path = Config::path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| String::from("unavailable"));
+
+    let content = Column::new()
+        .spacing(app.ui.spacing())
+        .push(scale_row)
+        .push(text_row)
+        .push(density_row)
+        .push(number_row)
+        .push(clock_row)
+        .push(hotkey_row)
+        .push(tray_row)
+        .push(autostart_row)
+        .push(accent_row)
+        .push(theme_files)
+        .push(theme_notes)
+        .push(Text::new("Refresh intervals"))
+        .push(intervals)
+        .push(adaptive_row)
+        .push(battery_row)
+        .push(unfocused_row)
+        .push(smoothing_row)
+        .push(cpu_scale_row)
+        .push(noise_row)
+        .push(ignored)
+        .push(ignore_row)
+        .push(critical)
+        .push(critical_row)
+        .push(links)
+        .push(links_row)
+        .push(leak_row)
+        .push(freeze_row)
+        .push(boost_row)
+        .push(undo_row)
+        .push(read_only_row)
+        .push(heat_row)
+        .push(totals_row)
+        .push(bar_cells_row)
+        .push(summary_row)
+        .push(background_row)
+        .push(low_memory_row)
+        .push(low_memory_window_row)
+        .push(narrow_row)
+        .push(icons_row)
+        .push(load_title_row)
+        .push(secondary_row)
+        .push(online_features)
+        .push(Text::new("Rules"))
+        .push(rules_row)
+        .push(recorder_row)
+        .push(Text::new("Settings file"))
+        .push(settings_row)
+        .push(Text::new("Window geometry per display"))
+        .push(displays)
+        .push(Text::new(format!("Displays and per-display scale can be configured in {}", path)));
+
+    Scrollable::new(content).into()
+}
@@ -0,0 +1,115 @@
+// send signal dialog - any posix signal rather than just a kill, since many daemons reload
+// their config on SIGHUP or reopen their logs on SIGUSR1 (unix only)
+use iced::{
+    widget::{Button, PickList, Row, Text},
+    Element, Length,
+};
+use libc::c_int;
+
+use std::fmt;
+
+use crate::kill_guard::Target;
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signal {
+    pub number: c_int,
+    pub name: &'static str,
+    // what it usually means to the receiver
+    pub meaning: &'static str,
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}) - {}", self.name, self.number, self.meaning)
+    }
+}
+
+const fn signal(number: c_int, name: &'static str, meaning: &'static str) -> Signal {
+    Signal { number, name, meaning }
+}
+
+// the signals posix defines, numbers differ between platforms so they come from libc
+pub const ALL: [Signal; 28] = [
+    signal(libc::SIGHUP, "SIGHUP", "hangup, daemons often reload their config"),
+    signal(libc::SIGINT, "SIGINT", "interrupt, like ctrl+c"),
+    signal(libc::SIGQUIT, "SIGQUIT", "quit with a core dump"),
+    signal(libc::SIGILL, "SIGILL", "illegal instruction"),
+    signal(libc::SIGTRAP, "SIGTRAP", "debugger trap"),
+    signal(libc::SIGABRT, "SIGABRT", "abort with a core dump"),
+    signal(libc::SIGBUS, "SIGBUS", "bus error"),
+    signal(libc::SIGFPE, "SIGFPE", "arithmetic error"),
+    signal(libc::SIGKILL, "SIGKILL", "kill, can't be caught"),
+    signal(libc::SIGUSR1, "SIGUSR1", "user defined, often reopens log files"),
+    signal(libc::SIGSEGV, "SIGSEGV", "segmentation fault"),
+    signal(libc::SIGUSR2, "SIGUSR2", "user defined"),
+    signal(libc::SIGPIPE, "SIGPIPE", "broken pipe"),
+    signal(libc::SIGALRM, "SIGALRM", "timer expired"),
+    signal(libc::SIGTERM, "SIGTERM", "terminate politely"),
+    signal(libc::SIGCHLD, "SIGCHLD", "child changed state"),
+    signal(libc::SIGCONT, "SIGCONT", "continue after a stop"),
+    signal(libc::SIGSTOP, "SIGSTOP", "stop, can't be caught"),
+    signal(libc::SIGTSTP, "SIGTSTP", "stop from the terminal, like ctrl+z"),
+    signal(libc::SIGTTIN, "SIGTTIN", "background read from the terminal"),
+    signal(libc::SIGTTOU, "SIGTTOU", "background write to the terminal"),
+    signal(libc::SIGURG, "SIGURG", "urgent socket data"),
+    signal(libc::SIGXCPU, "SIGXCPU", "cpu time limit exceeded"),
+    signal(libc::SIGXFSZ, "SIGXFSZ", "file size limit exceeded"),
+    signal(libc::SIGVTALRM, "SIGVTALRM", "virtual timer expired"),
+    signal(libc::SIGPROF, "SIGPROF", "profiling timer expired"),
+    signal(libc::SIGWINCH, "SIGWINCH", "terminal resized"),
+    signal(libc::SIGSYS, "SIGSYS", "bad system call"),
+];
+
+// kill(0) and kill(-1) would reach whole groups of processes, so only real pids are accepted
+pub fn send(pid: u32, signal: Signal) -> Result<(), String> {
+    let pid = libc::pid_t::try_from(pid)
+        .ok()
+        .filter(|&pid| pid > 0)
+        .ok_or_else(|| format!("{} isn't a valid pid", pid))?;
+    if unsafe { libc::kill(pid, signal.number) } == 0 {
+        Ok(())
+    } else {
+        Err(format!("failed to send {} to {}: {}", signal.name, pid, std::io::Error::last_os_error()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalDialog {
+    // the process as it was when the dialog opened, a reused pid isn't signalled
+    pub target: Target,
+    pub signal: Option<Signal>,
+}
+
+impl SignalDialog {
+    pub fn new(target: Target) -> SignalDialog {
+        SignalDialog { target, signal: None }
+    }
+}
+
+// construct the dialog, a signal is picked before it can be sent
+pub fn view(dialog: &SignalDialog) -> Element<'_, Message> {
+    Row::new()
+        .spacing(10)
+        .push(Text::new(format!("Send a signal to {}", dialog.target.label())))
+        .push(PickList::new(&ALL[..], dialog.signal, Message::SelectSignal).placeholder("Pick a signal").width(Length::Fixed(420.0)))
+        .push(Button::new("Send").on_press_maybe(dialog.signal.map(|_| Message::SendSignal)))
+        .push(Button::new("Cancel").on_press(Message::CloseSignals))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sends_signals_by_number() {
+        let numbers: std::collections::HashSet<c_int> = ALL.iter().map(|signal| signal.number).collect();
+        assert_eq!(numbers.len(), ALL.len());
+        // SIGCHLD is ignored unless handled, so it is safe to send to ourselves
+        let child = ALL.iter().find(|signal| signal.name == "SIGCHLD").unwrap();
+        assert_eq!(send(std::process::id(), *child), Ok(()));
+        assert!(send(u32::MAX, *child).is_err());
+        assert!(send(0, *child).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+// simulated process table - a made up but deterministic population for demos and integration
+// tests, started with --simulate in a build with the `sim` feature. every collection is one
+// tick: some processes exit, new ones are spawned and cpu use follows a wave per process, all
+// from a seeded generator so the same spec gives the same tables every run
+//
+// the spec is comma separated key=value pairs, every key optional:
+//
+//     seed=7          generator seed
+//     processes=100   processes in the first table
+//     spawn=1.5       processes started per tick on average
+//     exit=0.01       chance of each process exiting per tick
+//     period=30       ticks of one cpu wave
+//
+// pids start above the largest pid linux hands out, so an action that goes to the operating
+// system instead of the provider (renice, suspend) can't reach a real process
+use sysinfo::{System, Users};
+
+use std::f32::consts::TAU;
+
+use crate::process::ProcessInfo;
+use crate::provider::{Identity, ProcessProvider};
+
+const FIRST_PID: u32 = 5_000_000;
+
+// larger specs are cut down to these, a typo shouldn't allocate the whole memory
+const MAX_PROCESSES: usize = 100_000;
+const MAX_SPAWN: f32 = 1_000.0;
+
+const NAMES: [&str; 12] = ["firefox", "bash", "postgres", "node", "python3", "cargo", "rustc", "java", "nginx", "sshd", "chrome", "code"];
+const USERS: [&str; 3] = ["alice", "bob", "root"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimSpec {
+    pub seed: u64,
+    pub processes: usize,
+    pub spawn: f32,
+    pub exit: f32,
+    pub period: u32,
+}
+
+impl Default for SimSpec {
+    fn default() -> Self {
+        SimSpec { seed: 1, processes: 100, spawn: 1.0, exit: 0.01, period: 30 }
+    }
+}
+
+impl SimSpec {
+    pub fn parse(text: &str) -> Result<SimSpec, String> {
+        let mut spec = SimSpec::default();
+        for pair in text.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| format!("expected key=value, got {:?}", pair))?;
+            let invalid = || format!("invalid value {:?} for {}", value, key);
+            match key.trim() {
+                "seed" => spec.seed = value.trim().parse().map_err(|_| invalid())?,
+                "processes" => spec.processes = value.trim().parse().map_err(|_| invalid())?,
+                "spawn" => spec.spawn = value.trim().parse().map_err(|_| invalid())?,
+                "exit" => spec.exit = value.trim().parse().map_err(|_| invalid())?,
+                "period" => spec.period = value.trim().parse().map_err(|_| invalid())?,
+                _ => return Err(format!("unknown key {:?}, expected seed, processes, spawn, exit or period", key)),
+            }
+        }
+        if !(0.0..=1.0).contains(&spec.exit) {
+            return Err(String::from("exit is a chance, between 0 and 1"));
+        }
+        if !spec.spawn.is_finite() || spec.spawn < 0.0 {
+            return Err(String::from("spawn is a number of processes, it can't be negative"));
+        }
+        spec.processes = spec.processes.min(MAX_PROCESSES);
+        spec.spawn = spec.spawn.min(MAX_SPAWN);
+        spec.period = spec.period.max(1);
+        Ok(spec)
+    }
+}
+
+// splitmix64, small and the same on every platform
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // in [0, 1)
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound.max(1) as u64) as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Simulated {
+    info: ProcessInfo,
+    // cpu percent the wave centers on, how far it swings and where in the wave it starts
+    cpu: f32,
+    swing: f32,
+    phase: f32,
+    // memory drifts by up to this many MB a tick, a few processes only grow
+    drift: u64,
+    leaking: bool,
+}
+
+pub struct SimProvider {
+    spec: SimSpec,
+    rng: Rng,
+    tick: u64,
+    next_pid: u32,
+    processes: Vec<Simulated>,
+}
+
+impl SimProvider {
+    pub fn new(spec: SimSpec) -> SimProvider {
+        let mut provider = SimProvider { rng: Rng(spec.seed), tick: 0, next_pid: FIRST_PID, processes: Vec::new(), spec };
+        for _ in 0..provider.spec.processes {
+            provider.spawn();
+        }
+        provider
+    }
+
+    fn spawn(&mut self) {
+        let pid = self.next_pid;
+        self.next_pid += 1;
+        // the first process is the root of the tree, the others hang off any earlier one
+        let ppid = (!self.processes.is_empty()).then(|| self.processes[self.rng.below(self.processes.len())].info.pid);
+        let name = NAMES[self.rng.below(NAMES.len())];
+        let user = USERS[self.rng.below(USERS.len())];
+        let memory = 5 + self.rng.below(1500) as u64;
+        let busy = self.rng.unit() < 0.1;
+        let info = ProcessInfo {
+            pid,
+            ppid,
+            name: name.to_string(),
+            user: user.to_string(),
+            cmd: format!("{} --simulated {}", name, pid),
+            memory,
+            virtual_memory: memory * 4,
+            nice: Some(0),
+            cpu_time: Some(0),
+            system: user == "root",
+            elevated: user == "root",
+            ..ProcessInfo::default()
+        };
+        self.processes.push(Simulated {
+            info,
+            cpu: if busy { 40.0 + self.rng.unit() * 50.0 } else { self.rng.unit() * 3.0 },
+            swing: if busy { 10.0 + self.rng.unit() * 30.0 } else { self.rng.unit() * 2.0 },
+            phase: self.rng.unit(),
+            drift: self.rng.below(4) as u64,
+            leaking: self.rng.unit() < 0.02,
+        });
+    }
+
+    // one tick of exits, spawns and new readings
+    fn step(&mut self) {
+        self.tick += 1;
+        let exit = self.spec.exit;
+        let mut rng = self.rng.clone();
+        self.processes.retain(|_| rng.unit() >= exit);
+        self.rng = rng;
+        let mut spawns = self.spec.spawn.trunc() as usize;
+        if self.rng.unit() < self.spec.spawn.fract() {
+            spawns += 1;
+        }
+        for _ in 0..spawns {
+            self.spawn();
+        }
+        let period = self.spec.period as f32;
+        for process in &mut self.processes {
+            let wave = ((self.tick as f32 / period + process.phase) * TAU).sin();
+            let cpu = (process.cpu + process.swing * wave).max(0.0);
+            process.info.cpu = cpu;
+            process.info.cpu_raw = cpu;
+            process.info.uptime += 1;
+            // roughly, as if it had always run at its average
+            process.info.cpu_time = Some((process.info.uptime as f32 * process.cpu / 100.0) as u64);
+            let drift = self.rng.below(2 * process.drift as usize + 1) as u64;
+            process.info.memory = if process.leaking {
+                process.info.memory + process.drift + 1
+            } else {
+                (process.info.memory + drift).saturating_sub(process.drift).max(1)
+            };
+        }
+    }
+}
+
+impl ProcessProvider for SimProvider {
+    fn collect(&mut self, _system: &mut System, _users: &Users) -> Vec<ProcessInfo> {
+        self.step();
+        self.processes.iter().map(|process| process.info.clone()).collect()
+    }
+
+    fn kill(&mut self, _system: &System, pid: u32) -> bool {
+        let before = self.processes.len();
+        self.processes.retain(|process| process.info.pid != pid);
+        self.processes.len() < before
+    }
+
+    fn identify(&mut self, pid: u32) -> Option<Identity> {
+        self.processes.iter().find(|process| process.info.pid == pid).map(|process| Identity::of(&process.info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(spec: &SimSpec, ticks: usize) -> Vec<Vec<(u32, u64, u32)>> {
+        let mut provider = SimProvider::new(spec.clone());
+        let (mut system, users) = (System::new(), Users::new());
+        (0..ticks)
+            .map(|_| provider.collect(&mut system, &users).iter().map(|process| (process.pid, process.memory, process.cpu.to_bits())).collect())
+            .collect()
+    }
+
+    #[test]
+    fn the_same_spec_gives_the_same_tables() {
+        let spec = SimSpec::parse("seed=7, processes=50, spawn=2.5, exit=0.05, period=10").unwrap();
+        assert_eq!(spec, SimSpec { seed: 7, processes: 50, spawn: 2.5, exit: 0.05, period: 10 });
+        let first = tables(&spec, 20);
+        assert_eq!(first, tables(&spec, 20));
+        assert_ne!(first, tables(&SimSpec { seed: 8, ..spec.clone() }, 20));
+        // processes came and went, and every pid is out of reach of real ones
+        assert!(first[19].iter().any(|&(pid, _, _)| pid >= FIRST_PID + 50));
+        assert!(first[0].iter().any(|&(pid, _, _)| !first[19].iter().any(|row| row.0 == pid)));
+        assert!(first.iter().flatten().all(|&(pid, _, _)| pid >= FIRST_PID));
+
+        assert!(SimSpec::parse("exit=2").is_err());
+        assert!(SimSpec::parse("exit=NaN").is_err());
+        assert!(SimSpec::parse("spawn=inf").is_err());
+        assert!(SimSpec::parse("spawn=NaN").is_err());
+        assert_eq!(SimSpec::parse("processes=99999999999, spawn=1e30").unwrap(), SimSpec { processes: MAX_PROCESSES, spawn: MAX_SPAWN, ..SimSpec::default() });
+        assert!(SimSpec::parse("colour=red").is_err());
+        assert_eq!(SimSpec::parse("").unwrap(), SimSpec::default());
+    }
+}
@@ -0,0 +1,111 @@
+// the processes tab grouped the way systemd organizes them on linux - slices (user.slice,
+// system.slice), their services and the scopes of sessions and apps, each heading with the
+// cpu and memory of everything under it. read from the cgroup of each process, so only
+// while the grouping is on
+use std::collections::{BTreeMap, HashSet};
+
+use crate::apps::{Group, ListRow};
+use crate::cgroups;
+use crate::process::ProcessInfo;
+
+// the v2 path, or the one of systemd's own v1 hierarchy, None off linux or when unreadable
+pub fn for_pid(pid: u32) -> Option<String> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let (unified, mut controllers) = cgroups::parse_cgroup_file(&std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?);
+    controllers.remove("name=systemd").or(unified)
+}
+
+#[derive(Debug, Default)]
+struct Unit<'a> {
+    // processes in the unit itself, its children's are under their headings
+    processes: Vec<&'a ProcessInfo>,
+    // everything under the unit
+    count: usize,
+    cpu: f32,
+    memory: u64,
+}
+
+// a heading per unit with its processes after it, the units in depth-first order of their
+// names and the processes in the order of `rows`. processes without a cgroup go under "/"
+pub fn list<'a>(rows: &[&'a ProcessInfo], collapsed: &HashSet<Group>) -> Vec<ListRow<'a>> {
+    // by path components, which a BTreeMap orders parents first then their children
+    let mut units: BTreeMap<Vec<&str>, Unit<'a>> = BTreeMap::new();
+    for &process in rows {
+        let path: Vec<&str> = process.cgroup.as_deref().unwrap_or("/").split('/').filter(|part| !part.is_empty()).collect();
+        for depth in 0..=path.len() {
+            // the root only gets a heading for the processes in it
+            if depth == 0 && !path.is_empty() {
+                continue;
+            }
+            let unit = units.entry(path[..depth].to_vec()).or_default();
+            unit.count += 1;
+            unit.cpu += process.cpu;
+            unit.memory += process.memory;
+        }
+        if let Some(unit) = units.get_mut(&path) {
+            unit.processes.push(process);
+        }
+    }
+    let mut list = Vec::with_capacity(units.len() + rows.len());
+    let mut folded: Option<Vec<&str>> = None;
+    for (path, unit) in units {
+        if folded.as_ref().is_some_and(|folded| path.len() > folded.len() && path.starts_with(folded)) {
+            continue;
+        }
+        let group = Group::Unit(format!("/{}", path.join("/")));
+        let is_collapsed = collapsed.contains(&group);
+        list.push(ListRow::Unit { group, depth: path.len().saturating_sub(1), count: unit.count, cpu: unit.cpu, memory: unit.memory, collapsed: is_collapsed });
+        if is_collapsed {
+            folded = Some(path);
+        } else {
+            list.extend(unit.processes.into_iter().map(ListRow::Process));
+        }
+    }
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::mock;
+
+    #[test]
+    fn units_nest_sum_their_processes_and_fold() {
+        let at = |pid, cgroup: &str| ProcessInfo { cgroup: Some(cgroup.to_string()), ..mock::process(pid, "p", "alice", 10, 1.0) };
+        let processes = [
+            at(1, "/init.scope"),
+            at(2, "/user.slice/user-1000.slice/session-2.scope"),
+            at(3, "/user.slice/user-1000.slice/session-2.scope"),
+            at(4, "/user.slice-extra"),
+            mock::process(5, "kthreadd", "root", 0, 0.0),
+        ];
+        let rows: Vec<&ProcessInfo> = processes.iter().collect();
+        let headings = |list: &[ListRow]| -> Vec<(String, usize)> {
+            list.iter()
+                .filter_map(|row| match row {
+                    ListRow::Unit { group: Group::Unit(path), count, .. } => Some((path.clone(), *count)),
+                    _ => None,
+                })
+                .collect()
+        };
+        let list = list(&rows, &HashSet::new());
+        assert_eq!(
+            headings(&list),
+            vec![
+                (String::from("/"), 1),
+                (String::from("/init.scope"), 1),
+                (String::from("/user.slice"), 2),
+                (String::from("/user.slice/user-1000.slice"), 2),
+                (String::from("/user.slice/user-1000.slice/session-2.scope"), 2),
+                (String::from("/user.slice-extra"), 1),
+            ]
+        );
+        assert_eq!(list.len(), 11);
+        let folded = super::list(&rows, &HashSet::from([Group::Unit(String::from("/user.slice"))]));
+        assert_eq!(headings(&folded).len(), 4);
+        assert_eq!(folded.len(), 7);
+    }
+}
@@ -0,0 +1,260 @@
+// named snapshots of the process table, stored as toml files next to the config, and the
+// snapshots tab comparing one against the live table or another snapshot
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text, TextInput},
+    Element, Length,
+};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::diff;
+use crate::format::{self, format_duration};
+use crate::process::ProcessInfo;
+use crate::Message;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    // name given when it was taken, the file name is made from it
+    pub name: String,
+    // unix time it was taken
+    pub taken: u64,
+    pub processes: Vec<ProcessInfo>,
+}
+
+impl Snapshot {
+    pub fn take(name: &str, processes: &[ProcessInfo]) -> Snapshot {
+        let taken = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default();
+        Snapshot { name: name.trim().to_string(), taken, processes: processes.to_vec() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    // how a process differs between the two sides
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    fn label(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffRow {
+    pub change: Change,
+    pub pid: u32,
+    pub name: String,
+    // new minus old, a removed process counts as dropping to zero
+    pub memory_delta: i64,
+    pub cpu_delta: f32,
+}
+
+// directory with one file per snapshot, e.g. ~/.config/conborli/snapshots
+fn dir() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("snapshots"))
+}
+
+// file of a snapshot, see stem
+fn file(name: &str) -> Option<PathBuf> {
+    dir().map(|dir| dir.join(format!("{}.toml", stem(name))))
+}
+
+// the name with every byte that doesn't belong in a file name, and `_` itself, written as `_`
+// and two hex digits, so different names never share a file
+fn stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c.to_string()
+            } else {
+                c.to_string().bytes().map(|byte| format!("_{:02x}", byte)).collect()
+            }
+        })
+        .collect()
+}
+
+// every stored snapshot, oldest first, unreadable files are skipped
+pub fn load_all() -> Vec<Snapshot> {
+    let Some(entries) = dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|contents| toml::from_str(&contents).ok())
+        .collect();
+    snapshots.sort_by_key(|snapshot| snapshot.taken);
+    snapshots
+}
+
+// writes the snapshot, replacing one with the same name
+pub fn save(snapshot: &Snapshot) -> Result<(), String> {
+    let path = file(&snapshot.name).ok_or("no config directory on this platform")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    let contents = toml::to_string(snapshot).map_err(|err| err.to_string())?;
+    fs::write(&path, contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+// removes the file holding the snapshot, found by the name inside it since older versions
+// named the files differently
+pub fn delete(name: &str) -> Result<(), String> {
+    let dir = dir().ok_or("no config directory on this platform")?;
+    let entries = fs::read_dir(&dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+    let path = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let stored = fs::read_to_string(path).ok().and_then(|contents| toml::from_str::<Snapshot>(&contents).ok());
+            stored.is_some_and(|snapshot| snapshot.name == name)
+        })
+        .ok_or_else(|| format!("no snapshot named {:?}", name))?;
+    fs::remove_file(&path).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+// added, removed and changed processes with their deltas, largest memory change first
+pub fn rows(old: &[ProcessInfo], new: &[ProcessInfo]) -> Vec<DiffRow> {
+    let old_by_pid: HashMap<u32, &ProcessInfo> = old.iter().map(|process| (process.pid, process)).collect();
+    let new_by_pid: HashMap<u32, &ProcessInfo> = new.iter().map(|process| (process.pid, process)).collect();
+    let changes = diff::diff(old, new);
+    let row = |change, before: Option<&ProcessInfo>, after: Option<&ProcessInfo>| {
+        let process = after.or(before).expect("a diffed pid is on at least one side");
+        DiffRow {
+            change,
+            pid: process.pid,
+            name: process.name.clone(),
+            memory_delta: after.map_or(0, |p| p.memory as i64) - before.map_or(0, |p| p.memory as i64),
+            cpu_delta: after.map_or(0.0, |p| p.cpu) - before.map_or(0.0, |p| p.cpu),
+        }
+    };
+    let mut rows: Vec<DiffRow> = changes
+        .added
+        .iter()
+        .map(|pid| row(Change::Added, None, new_by_pid.get(pid).copied()))
+        .chain(changes.removed.iter().map(|pid| row(Change::Removed, old_by_pid.get(pid).copied(), None)))
+        .chain(
+            changes
+                .changed
+                .iter()
+                .map(|pid| row(Change::Changed, old_by_pid.get(pid).copied(), new_by_pid.get(pid).copied())),
+        )
+        .collect();
+    rows.sort_by_key(|row| std::cmp::Reverse(row.memory_delta.abs()));
+    rows
+}
+
+// construct the snapshots tab layout, `target` None compares against the live table
+pub fn view<'a>(
+    snapshots: &'a [Snapshot],
+    live: &'a [ProcessInfo],
+    name_input: &str,
+    base: Option<&str>,
+    target: Option<&str>,
+) -> Element<'a, Message> {
+    let take = Row::new()
+        .spacing(10)
+        .push(
+            TextInput::new("snapshot name", name_input)
+                .on_input(Message::SnapshotNameChanged)
+                .on_submit(Message::TakeSnapshot)
+                .width(Length::FillPortion(3))
+        )
+        .push(Button::new("Take snapshot").on_press_maybe((!name_input.trim().is_empty()).then_some(Message::TakeSnapshot)));
+
+    // each snapshot can be the base or the side compared against it, the chosen ones have no handler
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default();
+    let list = snapshots.iter().fold(Column::new().spacing(5), |column, snapshot| {
+        let name = snapshot.name.as_str();
+        let as_base = Button::new("Compare from");
+        let as_target = Button::new("Compare to");
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(name).width(Length::FillPortion(2)))
+                .push(Text::new(format!("{} processes, {} ago", snapshot.processes.len(), format_duration(now.saturating_sub(snapshot.taken)))).width(Length::FillPortion(2)))
+                .push(if base == Some(name) { as_base } else { as_base.on_press(Message::SelectSnapshotBase(name.to_string())) })
+                .push(if target == Some(name) { as_target } else { as_target.on_press(Message::SelectSnapshotTarget(Some(name.to_string()))) })
+                .push(Button::new("Delete").on_press(Message::DeleteSnapshot(name.to_string())))
+        )
+    });
+    let live_button = Button::new("Compare to live");
+    let list = list.push(if target.is_none() { live_button } else { live_button.on_press(Message::SelectSnapshotTarget(None)) });
+
+    let find = |name: &str| snapshots.iter().find(|snapshot| snapshot.name == name);
+    let comparison: Element<'a, Message> = match base.and_then(find) {
+        None => Text::new("Pick a snapshot to compare from").into(),
+        Some(old) => {
+            let (new, new_label) = match target.and_then(find) {
+                Some(snapshot) => (snapshot.processes.as_slice(), snapshot.name.as_str()),
+                None => (live, "live"),
+            };
+            let changes = rows(&old.processes, new);
+            let header = Row::new()
+                .spacing(10)
+                .push(Text::new("Change").width(Length::FillPortion(1)))
+                .push(Text::new("PID").width(Length::FillPortion(1)))
+                .push(Text::new("Name").width(Length::FillPortion(2)))
+                .push(Text::new("Memory (MB)").width(Length::FillPortion(1)))
+                .push(Text::new("CPU (%)").width(Length::FillPortion(1)));
+            let table = changes.into_iter().fold(Column::new().spacing(5).push(header), |column, row| {
+                column.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new(row.change.label()).width(Length::FillPortion(1)))
+                        .push(Text::new(row.pid.to_string()).width(Length::FillPortion(1)))
+                        .push(Text::new(row.name).width(Length::FillPortion(2)))
+                        .push(Text::new(format::signed_integer(row.memory_delta)).width(Length::FillPortion(1)))
+                        .push(Text::new(format::signed_decimal(f64::from(row.cpu_delta), 1)).width(Length::FillPortion(1)))
+                )
+            });
+            Column::new()
+                .spacing(10)
+                .push(Text::new(format!("{} → {}", old.name, new_label)))
+                .push(Scrollable::new(table))
+                .into()
+        }
+    };
+
+    Column::new()
+        .spacing(10)
+        .push(take)
+        .push(list)
+        .push(comparison)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn stored_snapshot_diffs_like_the_original() {
+        let old = Snapshot::take("before", &[mock::process(1, "a", "u", 100, 1.0), mock::process(2, "b", "u", 50, 0.0)]);
+        let stored: Snapshot = toml::from_str(&toml::to_string(&old).unwrap()).unwrap();
+        let new = [mock::process(1, "a", "u", 300, 1.0), mock::process(3, "c", "u", 10, 0.0)];
+        let summary: Vec<_> = rows(&stored.processes, &new).into_iter().map(|row| (row.change, row.pid, row.memory_delta)).collect();
+        assert_eq!(summary, vec![(Change::Changed, 1, 200), (Change::Removed, 2, -50), (Change::Added, 3, 10)]);
+    }
+
+    #[test]
+    fn different_names_get_different_files() {
+        let names = ["a b", "a_b", "a/b", "a_20b", "a-b", "été"];
+        let stems: Vec<String> = names.iter().map(|name| stem(name)).collect();
+        assert_eq!(stems, ["a_20b", "a_5fb", "a_2fb", "a_5f20b", "a-b", "été"]);
+        assert!(stems.iter().all(|stem| stem.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')));
+    }
+}
@@ -0,0 +1,182 @@
+// sockets owned by a process, read from procfs (Linux only)
+//
+// /proc/<pid>/fd links sockets as "socket:[inode]", and /proc/net/{tcp,tcp6,udp,udp6} list
+// every socket with its inode, so matching the two gives the process's sockets
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Socket {
+    // holds one tcp/udp socket of a process
+    pub protocol: &'static str,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    // tcp state like "ESTABLISHED" or "LISTEN", empty for udp
+    pub state: &'static str,
+    // kernel inode of the socket, which the owner's fd links point at
+    pub inode: u64,
+}
+
+impl Socket {
+    // a tcp socket waiting for connections
+    pub fn is_listening(&self) -> bool {
+        self.state == "LISTEN"
+    }
+
+    // a connection to a peer
+    pub fn is_connected(&self) -> bool {
+        self.state == "ESTABLISHED"
+    }
+}
+
+// every tcp and udp socket of the process
+pub fn for_pid(pid: u32) -> Result<Vec<Socket>, String> {
+    let inodes = socket_inodes(pid)?;
+    Ok(all_sockets()?
+        .into_iter()
+        .filter(|socket| inodes.contains(&socket.inode))
+        .collect())
+}
+
+// every socket on the machine with the pid owning it, None when the owner isn't readable
+// (other users' processes without root) or the socket has no process (e.g. TIME_WAIT)
+pub fn all_with_owners() -> Result<Vec<(Option<u32>, Socket)>, String> {
+    let sockets = all_sockets()?;
+    let mut owners: HashMap<u64, u32> = HashMap::new();
+    let entries = fs::read_dir("/proc").map_err(|err| format!("/proc: {}", err))?;
+    for pid in entries.filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok()) {
+        // processes that exited meanwhile or aren't ours simply have no sockets here
+        for inode in socket_inodes(pid).unwrap_or_default() {
+            owners.entry(inode).or_insert(pid);
+        }
+    }
+    Ok(sockets
+        .into_iter()
+        .map(|socket| (owners.get(&socket.inode).copied(), socket))
+        .collect())
+}
+
+// the local port of every listening tcp socket and bound udp socket, by inode
+pub fn listening_ports() -> Result<HashMap<u64, u16>, String> {
+    Ok(all_sockets()?
+        .into_iter()
+        .filter(|socket| socket.is_listening() || socket.state.is_empty())
+        .map(|socket| (socket.inode, socket.local.port()))
+        .collect())
+}
+
+// the inode in a "socket:[inode]" descriptor link
+pub fn inode(target: &str) -> Option<u64> {
+    target.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+}
+
+// the sockets in the kernel tables, regardless of owner
+fn all_sockets() -> Result<Vec<Socket>, String> {
+    if !cfg!(target_os = "linux") {
+        return Err(String::from("socket listing is only supported on Linux"));
+    }
+    let mut sockets = Vec::new();
+    for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+        // a table may be missing, e.g. with ipv6 disabled
+        let Ok(table) = fs::read_to_string(format!("/proc/net/{}", protocol)) else {
+            continue;
+        };
+        sockets.extend(parse_table(protocol, &table));
+    }
+    Ok(sockets)
+}
+
+// inodes of the sockets among the process's open file descriptors
+fn socket_inodes(pid: u32) -> Result<HashSet<u64>, String> {
+    let dir = format!("/proc/{}/fd", pid);
+    let entries = fs::read_dir(&dir).map_err(|err| format!("{}: {}", dir, err))?;
+    Ok(entries
+        .filter_map(|entry| fs::read_link(entry.ok()?.path()).ok())
+        .filter_map(|target| inode(&target.to_string_lossy()))
+        .collect())
+}
+
+// each line is "sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ..."
+fn parse_table(protocol: &'static str, table: &str) -> Vec<Socket> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local = parse_address(fields.get(1)?)?;
+            let remote = parse_address(fields.get(2)?)?;
+            let state = if protocol.starts_with("tcp") { tcp_state(fields.get(3)?) } else { "" };
+            let inode = fields.get(9)?.parse().ok()?;
+            Some(Socket { protocol, local, remote, state, inode })
+        })
+        .collect()
+}
+
+// "0100007F:1F90" is 127.0.0.1:8080, the kernel prints the address as 32 bit words in native
+// byte order, so each word's native bytes are the address bytes
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for i in 0..address.len() / 8 {
+        let word = u32::from_str_radix(address.get(i * 8..i * 8 + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn tcp_state(code: &str) -> &'static str {
+    match code {
+        "01" => "ESTABLISHED",
+        "02" => "SYN_SENT",
+        "03" => "SYN_RECV",
+        "04" => "FIN_WAIT1",
+        "05" => "FIN_WAIT2",
+        "06" => "TIME_WAIT",
+        "07" => "CLOSE",
+        "08" => "CLOSE_WAIT",
+        "09" => "LAST_ACK",
+        "0A" => "LISTEN",
+        "0B" => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the kernel writes the address words in native byte order, these lines are from x86
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn kernel_tables_parse_into_sockets() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+                   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0\n   \
+                   1: 0100007F:9C41 0100007F:1538 01 00000000:00000000 00:00000000 00000000  1000        0 4243 1 0000000000000000 20 4 30 10 -1\n   \
+                   2: garbage\n";
+        let sockets = parse_table("tcp", tcp);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0], Socket { protocol: "tcp", local: "127.0.0.1:8080".parse().unwrap(), remote: "0.0.0.0:0".parse().unwrap(), state: "LISTEN", inode: 4242 });
+        assert!(sockets[1].is_connected());
+        assert_eq!(sockets[1].remote, "127.0.0.1:5432".parse().unwrap());
+
+        let udp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops\n  \
+                    5: 00000000000000000000000001000000:0035 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 77 2 0000000000000000 0\n";
+        let sockets = parse_table("udp6", udp6);
+        assert_eq!(sockets[0].local, "[::1]:53".parse().unwrap());
+        assert_eq!(sockets[0].state, "");
+    }
+
+    #[test]
+    fn descriptor_links_name_the_socket_inode() {
+        assert_eq!(inode("socket:[4242]"), Some(4242));
+        assert_eq!(inode("pipe:[4242]"), None);
+        assert_eq!(inode("/dev/null"), None);
+    }
+}
@@ -0,0 +1,71 @@
+// kills that wait a few seconds before the signal is sent, with an undo button in a banner,
+// for when a refresh moved the wrong row under the pointer right before the click
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Text},
+    Element,
+};
+
+use std::time::{Duration, Instant};
+
+use crate::kill_guard::Target;
+use crate::Message;
+
+// time to undo a kill
+pub const DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct PendingKill {
+    // the process the kill was asked for, a reused pid by then is left alone
+    pub target: Target,
+    // the process and its descendants
+    pub tree: bool,
+    pub due: Instant,
+}
+
+impl PendingKill {
+    pub fn new(target: Target, tree: bool, now: Instant) -> PendingKill {
+        PendingKill { target, tree, due: now + DELAY }
+    }
+}
+
+// removes and returns the kills whose time has come
+pub fn take_due(pending: &mut Vec<PendingKill>, now: Instant) -> Vec<PendingKill> {
+    let (due, waiting) = std::mem::take(pending).into_iter().partition(|kill| kill.due <= now);
+    *pending = waiting;
+    due
+}
+
+// one line per waiting kill with its countdown and undo button
+pub fn view(pending: &[PendingKill], now: Instant) -> Element<'_, Message> {
+    pending
+        .iter()
+        .fold(Column::new().spacing(4), |column, kill| {
+            let left = kill.due.saturating_duration_since(now).as_secs_f32().ceil();
+            let what = if kill.tree { format!("{} and its children", kill.target.label()) } else { kill.target.label() };
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(alignment::Alignment::Center)
+                    .push(Text::new(format!("Killing {} in {} s", what, left)))
+                    .push(Button::new("Undo").on_press(Message::UndoKill(kill.target.pid)))
+            )
+        })
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Identity;
+
+    #[test]
+    fn only_kills_past_their_delay_are_taken() {
+        let now = Instant::now();
+        let target = |pid: u32, name: &str| Target { pid, identity: Identity { name: name.to_string(), start_time: 0 }, confirmed_critical: false };
+        let mut pending = vec![PendingKill::new(target(1, "a"), false, now), PendingKill::new(target(2, "b"), true, now + DELAY)];
+        assert!(take_due(&mut pending, now).is_empty());
+        let due = take_due(&mut pending, now + DELAY);
+        assert_eq!((due.len(), due[0].target.pid, pending[0].target.pid), (1, 1, 2));
+    }
+}
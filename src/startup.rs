@@ -0,0 +1,85 @@
+// the first scan off the ui thread, so the window shows right away. the first sample of every
+// process can take seconds on a slow machine, and cpu usage needs a second one after the
+// minimum interval. until both are in the body is a skeleton of the table with the progress
+use iced::{
+    widget::{Column, Container, ProgressBar, Row, Space, Text},
+    Element, Length,
+};
+use sysinfo::System;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::style::{self, Ui};
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    // taking the first sample of every process
+    Reading,
+    // waiting out the interval cpu usage is measured over
+    Sampling,
+}
+
+impl Stage {
+    fn label(self) -> &'static str {
+        match self {
+            Stage::Reading => "Reading processes…",
+            Stage::Sampling => "Measuring CPU usage…",
+        }
+    }
+
+    // the table is built right after sampling, so that is most of the way
+    fn progress(self) -> f32 {
+        match self {
+            Stage::Reading => 0.2,
+            Stage::Sampling => 0.7,
+        }
+    }
+}
+
+// the system with its first sample, handed from the scan to the ui thread once. messages
+// have to be cloneable, so it travels in a shared slot
+#[derive(Clone)]
+pub struct Scanned(Arc<Mutex<Option<System>>>);
+
+impl Scanned {
+    pub fn take(&self) -> Option<System> {
+        self.0.lock().ok().and_then(|mut system| system.take())
+    }
+}
+
+impl fmt::Debug for Scanned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Scanned")
+    }
+}
+
+// the first sample, blocking
+pub fn read() -> Scanned {
+    let mut system = System::new();
+    system.refresh_cpu();
+    system.refresh_processes();
+    Scanned(Arc::new(Mutex::new(Some(system))))
+}
+
+// blocking as well, the table is refreshed once it returns
+pub fn wait_for_cpu() {
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+}
+
+// placeholder rows in the shape of the table under the progress bar
+const SKELETON_ROWS: usize = 12;
+
+pub fn view(stage: Stage, ui: Ui) -> Element<'static, Message> {
+    let bar = |portion: u16| Container::new(Space::new(Length::Fill, Length::Fixed(ui.row_height() * 0.6))).width(Length::FillPortion(portion)).style(style::pinned_row);
+    let rows = (0..SKELETON_ROWS).fold(Column::new().spacing(ui.spacing()), |column, _| {
+        column.push(Row::new().spacing(20).push(bar(1)).push(bar(4)).push(bar(1)).push(bar(2)))
+    });
+    Column::new()
+        .spacing(10)
+        .push(Text::new(stage.label()))
+        .push(ProgressBar::new(0.0..=1.0, stage.progress()).height(Length::Fixed(8.0)))
+        .push(rows)
+        .into()
+}
@@ -0,0 +1,163 @@
+// websocket on /stream - pushes what changed in the process table after every refresh
+//
+// each message is a json object with the pids that disappeared and the full rows of the
+// processes that appeared or changed since the previous message, the first message lists
+// the whole table as added. messages from the client are never read
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use tiny_http::{Header, Request, Response, StatusCode};
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+use crate::collector::Shared;
+use crate::diff;
+use crate::process::ProcessInfo;
+
+// how often a client's thread looks for a newer table
+const POLL: Duration = Duration::from_millis(250);
+
+// appended to the client's key for the handshake, fixed by rfc 6455
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+#[derive(Debug, Serialize)]
+struct Changes<'a> {
+    // publish count of the table the message brings the client up to
+    generation: u64,
+    added: Vec<&'a ProcessInfo>,
+    removed: Vec<u32>,
+    updated: Vec<&'a ProcessInfo>,
+}
+
+// the client's handshake key, None if the request doesn't ask for a websocket
+pub fn key(request: &Request) -> Option<String> {
+    let header = |name: &'static str| request.headers().iter().find(|header| header.field.equiv(name));
+    let upgrade = header("Upgrade").is_some_and(|header| header.value.as_str().eq_ignore_ascii_case("websocket"));
+    upgrade.then(|| header("Sec-WebSocket-Key")).flatten().map(|header| header.value.as_str().trim().to_string())
+}
+
+// browsers send the page's origin with every websocket handshake and let any page connect to
+// loopback, so only clients without one or from the server's own origin get the table
+fn same_origin(request: &Request) -> bool {
+    let header = |name: &'static str| request.headers().iter().find(|header| header.field.equiv(name)).map(|header| header.value.as_str());
+    match header("Origin") {
+        None => true,
+        Some(origin) => {
+            let host = origin.split_once("://").map_or(origin, |(_, host)| host);
+            header("Host").is_some_and(|own| own.eq_ignore_ascii_case(host))
+        }
+    }
+}
+
+fn accept(key: &str) -> String {
+    STANDARD.encode(Sha1::digest(format!("{}{}", key, GUID)))
+}
+
+// finishes the handshake and streams from a thread of its own until the client goes away.
+// handshakes from another site's page are refused
+pub fn upgrade(request: Request, key: &str, shared: Shared) {
+    if !same_origin(&request) {
+        tracing::info!(path = request.url(), status = 403, "api request");
+        let _ = request.respond(Response::from_string("websocket connections from other sites are refused").with_status_code(403));
+        return;
+    }
+    let accept = Header::from_bytes("Sec-WebSocket-Accept", accept(key)).expect("base64 is a valid header value");
+    let response = Response::empty(StatusCode(101)).with_header(accept);
+    let mut socket = request.upgrade("websocket", response);
+    thread::spawn(move || {
+        let mut previous: Vec<ProcessInfo> = Vec::new();
+        let mut seen = 0;
+        loop {
+            let message = {
+                let published = shared.read();
+                if published.generation == seen {
+                    None
+                } else {
+                    seen = published.generation;
+                    let message = changes(&previous, &published.processes, seen);
+                    previous = published.processes.clone();
+                    message
+                }
+            };
+            if let Some(message) = message {
+                if socket.write_all(&frame(&message)).and_then(|_| socket.flush()).is_err() {
+                    return;
+                }
+            }
+            thread::sleep(POLL);
+        }
+    });
+}
+
+// the message taking a client from `old` to `new`, None when nothing changed
+fn changes(old: &[ProcessInfo], new: &[ProcessInfo], generation: u64) -> Option<String> {
+    let changes = diff::diff(old, new);
+    if changes == diff::ProcessDiff::default() {
+        return None;
+    }
+    let by_pid: HashMap<u32, &ProcessInfo> = new.iter().map(|process| (process.pid, process)).collect();
+    let rows = |pids: &[u32]| pids.iter().filter_map(|pid| by_pid.get(pid).copied()).collect();
+    let message = Changes { generation, added: rows(&changes.added), removed: changes.removed, updated: rows(&changes.changed) };
+    serde_json::to_string(&message).ok()
+}
+
+// one unmasked text frame, servers never mask
+fn frame(text: &str) -> Vec<u8> {
+    let payload = text.as_bytes();
+    let mut frame = vec![0x81];
+    match payload.len() {
+        length @ 0..=125 => frame.push(length as u8),
+        length @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+    use tiny_http::TestRequest;
+
+    #[test]
+    fn handshake_and_messages() {
+        // the example from rfc 6455
+        assert_eq!(accept("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        assert_eq!(frame("hi"), vec![0x81, 2, b'h', b'i']);
+        assert_eq!(frame(&"x".repeat(300))[..4], [0x81, 126, 1, 44]);
+
+        let old = [mock::process(1, "a", "u", 10, 0.0), mock::process(2, "b", "u", 10, 0.0)];
+        let new = [mock::process(1, "a", "u", 20, 0.0), mock::process(3, "c", "u", 10, 0.0)];
+        let message: serde_json::Value = serde_json::from_str(&changes(&old, &new, 4).unwrap()).unwrap();
+        assert_eq!(message["generation"], 4);
+        assert_eq!(message["added"][0]["pid"], 3);
+        assert_eq!(message["removed"], serde_json::json!([2]));
+        assert_eq!(message["updated"][0]["memory"], 20);
+        assert_eq!(changes(&new, &new, 5), None);
+    }
+
+    #[test]
+    fn other_sites_cant_open_the_stream() {
+        let request = |headers: &[(&str, &str)]| {
+            let request = headers.iter().fold(TestRequest::new().with_path("/stream"), |request, (field, value)| {
+                request.with_header(Header::from_bytes(*field, *value).unwrap())
+            });
+            Request::from(request)
+        };
+        assert!(same_origin(&request(&[("Host", "127.0.0.1:7878")])));
+        assert!(same_origin(&request(&[("Host", "127.0.0.1:7878"), ("Origin", "http://127.0.0.1:7878")])));
+        assert!(!same_origin(&request(&[("Host", "127.0.0.1:7878"), ("Origin", "https://example.com")])));
+        assert!(!same_origin(&request(&[("Origin", "http://127.0.0.1:7878")])));
+    }
+}
@@ -0,0 +1,192 @@
+// custom widget styles shared by the views, and the sizes the views take from the text size
+// and density settings
+use iced::widget::{button, container};
+use iced::{theme, Border, Color, Theme};
+use serde::{Deserialize, Serialize};
+
+// text sizes offered by the settings tab, in logical pixels
+pub const DEFAULT_TEXT_SIZE: u16 = 16;
+pub const MIN_TEXT_SIZE: u16 = 10;
+pub const MAX_TEXT_SIZE: u16 = 32;
+
+// line height iced gives text relative to its size
+const LINE_HEIGHT: f32 = 1.3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    Compact,
+    #[default]
+    Normal,
+    Comfortable,
+}
+
+impl Density {
+    pub const ALL: [Density; 3] = [Density::Compact, Density::Normal, Density::Comfortable];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Density::Compact => "Compact",
+            Density::Normal => "Normal",
+            Density::Comfortable => "Comfortable",
+        }
+    }
+}
+
+// shared style context of the views, built from the config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ui {
+    // size the window was started with, iced only reads it at startup
+    pub text_size: f32,
+    pub density: Density,
+    // set from the picked theme file
+    pub colors: Highlights,
+}
+
+impl Default for Ui {
+    fn default() -> Self {
+        Ui { text_size: DEFAULT_TEXT_SIZE as f32, density: Density::Normal, colors: Highlights::default() }
+    }
+}
+
+impl Ui {
+    // sizes out of range in a hand-edited config are clamped
+    pub fn new(text_size: u16, density: Density) -> Ui {
+        Ui { text_size: f32::from(text_size.clamp(MIN_TEXT_SIZE, MAX_TEXT_SIZE)), density, colors: Highlights::default() }
+    }
+
+    // height of a table row, one line of text plus room for a button around it
+    pub fn row_height(self) -> f32 {
+        let room = match self.density {
+            Density::Compact => 7.0,
+            Density::Normal => 13.2,
+            Density::Comfortable => 22.0,
+        };
+        (self.text_size * LINE_HEIGHT + room).round()
+    }
+
+    // space between the rows and sections of a view
+    pub fn spacing(self) -> u16 {
+        match self.density {
+            Density::Compact => 4,
+            Density::Normal => 10,
+            Density::Comfortable => 16,
+        }
+    }
+
+    // padding inside buttons placed in table rows
+    pub fn button_padding(self) -> u16 {
+        match self.density {
+            Density::Compact => 2,
+            Density::Normal => 5,
+            Density::Comfortable => 8,
+        }
+    }
+
+    // padding around the whole window content
+    pub fn window_padding(self) -> u16 {
+        match self.density {
+            Density::Compact => 12,
+            Density::Normal => 30,
+            Density::Comfortable => 40,
+        }
+    }
+}
+
+// colors a custom theme can set that iced's palette has no place for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Highlights {
+    // selected table row and column header buttons, None keeps the theme's primary color
+    pub row: Option<Color>,
+    pub header: Option<Color>,
+    // values close to or past a limit, e.g. sensor temperatures and failed services
+    pub warning: Color,
+    pub critical: Color,
+}
+
+impl Default for Highlights {
+    fn default() -> Self {
+        Highlights {
+            row: None,
+            header: None,
+            warning: Color::from_rgb(1.0, 0.75, 0.2),
+            critical: Color::from_rgb(1.0, 0.3, 0.3),
+        }
+    }
+}
+
+impl Highlights {
+    // the ones that go with the high contrast theme, text on them stays white
+    pub fn high_contrast() -> Highlights {
+        Highlights {
+            row: Some(Color::from_rgb8(0x00, 0x3c, 0xff)),
+            header: Some(Color::from_rgb8(0x30, 0x30, 0x30)),
+            warning: Color::from_rgb8(0xff, 0xff, 0x00),
+            critical: Color::from_rgb8(0xff, 0x60, 0x60),
+        }
+    }
+}
+
+// background of the selected row in a table
+pub fn selected_row(color: Option<Color>) -> impl Fn(&Theme) -> container::Appearance {
+    move |theme| {
+        let palette = theme.extended_palette();
+        container::Appearance {
+            background: Some(color.unwrap_or(palette.primary.weak.color).into()),
+            text_color: Some(if color.is_some() { palette.background.base.text } else { palette.primary.weak.text }),
+            border: Border::with_radius(4),
+            ..Default::default()
+        }
+    }
+}
+
+// background of a pinned row, kept quieter than the selection
+pub fn pinned_row(theme: &Theme) -> container::Appearance {
+    let palette = theme.extended_palette();
+    container::Appearance {
+        background: Some(palette.background.weak.color.into()),
+        border: Border::with_radius(4),
+        ..Default::default()
+    }
+}
+
+// a process that looks hung, its text in the warning color like the greyed out rows of the
+// windows task manager
+pub fn not_responding_row(color: Color) -> impl Fn(&Theme) -> container::Appearance {
+    move |_| container::Appearance { text_color: Some(color), ..Default::default() }
+}
+
+// column header button, the primary style unless the theme gives a header color
+pub fn header(color: Option<Color>) -> theme::Button {
+    match color {
+        Some(color) => theme::Button::custom(HeaderButton(color)),
+        None => theme::Button::Primary,
+    }
+}
+
+struct HeaderButton(Color);
+
+impl button::StyleSheet for HeaderButton {
+    type Style = Theme;
+
+    fn active(&self, theme: &Theme) -> button::Appearance {
+        button::Appearance {
+            background: Some(self.0.into()),
+            text_color: theme.palette().text,
+            border: Border::with_radius(2),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_density_keeps_the_default_row_height() {
+        assert_eq!(Ui::default().row_height(), 34.0);
+        let compact = Ui::new(12, Density::Compact);
+        assert!(compact.row_height() < Ui::default().row_height());
+    }
+}
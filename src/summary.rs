@@ -0,0 +1,275 @@
+// session summary - what happened while the task manager ran: the peak machine cpu and memory,
+// the processes that used the most, kills, alerts and how many processes came and went. shown
+// on demand and, when picked in settings, written to the summaries folder of the config
+// directory on exit as markdown or json
+use chrono::{DateTime, Local};
+use iced::{
+    alignment,
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::churn::Churn;
+use crate::config::Config;
+use crate::events::{EventKind, EventLog};
+use crate::format;
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// processes listed per resource
+const TOP: usize = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFormat {
+    #[default]
+    Off,
+    Markdown,
+    Json,
+}
+
+impl SummaryFormat {
+    pub const ALL: [SummaryFormat; 3] = [SummaryFormat::Off, SummaryFormat::Markdown, SummaryFormat::Json];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SummaryFormat::Off => "Off",
+            SummaryFormat::Markdown => "Markdown",
+            SummaryFormat::Json => "JSON",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Offender {
+    pub name: String,
+    // the instance that used the most
+    pub pid: u32,
+    pub cpu: f32,
+    // MB
+    pub memory: u64,
+}
+
+// the peaks seen so far, recorded at every refresh of this machine
+#[derive(Debug)]
+pub struct Session {
+    started: DateTime<Local>,
+    peak_cpu: Option<(f32, DateTime<Local>)>,
+    peak_memory: Option<(f32, DateTime<Local>)>,
+    // peak cpu and peak memory of each program by name, each with the pid it was seen on
+    cpu: HashMap<String, Offender>,
+    memory: HashMap<String, Offender>,
+}
+
+impl Session {
+    pub fn new(started: DateTime<Local>) -> Session {
+        Session { started, peak_cpu: None, peak_memory: None, cpu: HashMap::new(), memory: HashMap::new() }
+    }
+
+    // from an already refreshed system
+    pub fn record(&mut self, system: &System, processes: &[ProcessInfo], now: DateTime<Local>) {
+        let cpu = system.global_cpu_info().cpu_usage();
+        if self.peak_cpu.is_none_or(|(peak, _)| cpu > peak) {
+            self.peak_cpu = Some((cpu, now));
+        }
+        if system.total_memory() > 0 {
+            let memory = system.used_memory() as f32 / system.total_memory() as f32 * 100.0;
+            if self.peak_memory.is_none_or(|(peak, _)| memory > peak) {
+                self.peak_memory = Some((memory, now));
+            }
+        }
+        self.record_processes(processes);
+    }
+
+    fn record_processes(&mut self, processes: &[ProcessInfo]) {
+        let offender = |process: &ProcessInfo| Offender { name: process.name.clone(), pid: process.pid, cpu: process.cpu, memory: process.memory };
+        for process in processes {
+            match self.cpu.get_mut(&process.name) {
+                Some(peak) if peak.cpu >= process.cpu => {}
+                Some(peak) => *peak = offender(process),
+                None => {
+                    self.cpu.insert(process.name.clone(), offender(process));
+                }
+            }
+            match self.memory.get_mut(&process.name) {
+                Some(peak) if peak.memory >= process.memory => {}
+                Some(peak) => *peak = offender(process),
+                None => {
+                    self.memory.insert(process.name.clone(), offender(process));
+                }
+            }
+        }
+    }
+
+    pub fn summary(&self, events: &EventLog, churn: &Churn, now: DateTime<Local>) -> Summary {
+        let top = |offenders: &HashMap<String, Offender>, key: fn(&Offender) -> f64| {
+            let mut top: Vec<Offender> = offenders.values().cloned().collect();
+            top.sort_by(|a, b| key(b).total_cmp(&key(a)).then_with(|| a.name.cmp(&b.name)));
+            top.truncate(TOP);
+            top
+        };
+        let texts = |kind: EventKind| events.since(self.started).filter(|event| event.kind == kind).map(|event| event.line()).collect();
+        Summary {
+            started: self.started,
+            ended: now,
+            peak_cpu: self.peak_cpu,
+            peak_memory: self.peak_memory,
+            top_cpu: top(&self.cpu, |offender| f64::from(offender.cpu)),
+            top_memory: top(&self.memory, |offender| offender.memory as f64),
+            kills: events.total(EventKind::Kill),
+            alerts: events.total(EventKind::Alert),
+            errors: events.total(EventKind::Error),
+            started_processes: churn.started,
+            exited_processes: churn.exited,
+            kill_log: texts(EventKind::Kill),
+            alert_log: texts(EventKind::Alert),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub started: DateTime<Local>,
+    pub ended: DateTime<Local>,
+    // machine-wide percent and when it was reached
+    pub peak_cpu: Option<(f32, DateTime<Local>)>,
+    pub peak_memory: Option<(f32, DateTime<Local>)>,
+    pub top_cpu: Vec<Offender>,
+    pub top_memory: Vec<Offender>,
+    pub kills: u64,
+    pub alerts: u64,
+    pub errors: u64,
+    pub started_processes: u64,
+    pub exited_processes: u64,
+    // the kills and alerts still in the event log, which keeps the last thousand events
+    pub kill_log: Vec<String>,
+    pub alert_log: Vec<String>,
+}
+
+impl Summary {
+    fn duration(&self) -> String {
+        format::format_duration(self.ended.signed_duration_since(self.started).num_seconds().max(0) as u64)
+    }
+
+    fn peak(peak: Option<(f32, DateTime<Local>)>) -> String {
+        peak.map(|(value, at)| format!("{}% at {}", format::decimal(f64::from(value), 1), format::time(&at))).unwrap_or_else(|| String::from("n/a"))
+    }
+
+    // the overview lines the panel and the markdown share
+    fn overview(&self) -> Vec<String> {
+        vec![
+            format!("Peak CPU: {}", Summary::peak(self.peak_cpu)),
+            format!("Peak memory: {}", Summary::peak(self.peak_memory)),
+            format!("Processes started: {}, exited: {}", self.started_processes, self.exited_processes),
+            format!("Kills: {}, alerts: {}, errors: {}", self.kills, self.alerts, self.errors),
+        ]
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut text = format!(
+            "# Session summary\n\n{} to {} ({})\n\n",
+            self.started.format("%Y-%m-%d %H:%M:%S"),
+            self.ended.format("%Y-%m-%d %H:%M:%S"),
+            self.duration()
+        );
+        for line in self.overview() {
+            text.push_str(&format!("- {}\n", line));
+        }
+        let table = |title: &str, offenders: &[Offender]| {
+            let rows: String = offenders
+                .iter()
+                .map(|offender| format!("| {} | {} | {} | {} |\n", offender.name.replace('|', "\\|"), offender.pid, format::decimal(f64::from(offender.cpu), 1), offender.memory))
+                .collect();
+            format!("\n## {}\n\n| Process | PID | CPU (%) | Memory (MB) |\n| --- | --- | --- | --- |\n{}", title, rows)
+        };
+        text.push_str(&table("Top CPU", &self.top_cpu));
+        text.push_str(&table("Top memory", &self.top_memory));
+        for (title, lines) in [("Kills", &self.kill_log), ("Alerts", &self.alert_log)] {
+            if !lines.is_empty() {
+                text.push_str(&format!("\n## {}\n\n", title));
+                for line in lines {
+                    text.push_str(&format!("- {}\n", line));
+                }
+            }
+        }
+        text
+    }
+
+    // writes it to the summaries folder, named after when the session started
+    pub fn save(&self, format: SummaryFormat) -> Result<PathBuf, String> {
+        let (extension, text) = match format {
+            SummaryFormat::Off => return Err(String::from("no format picked")),
+            SummaryFormat::Markdown => ("md", self.markdown()),
+            SummaryFormat::Json => ("json", serde_json::to_string_pretty(self).map_err(|err| err.to_string())?),
+        };
+        let dir = Config::dir().ok_or("no config directory on this system")?.join("summaries");
+        fs::create_dir_all(&dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+        let path = dir.join(format!("session-{}.{}", self.started.format("%Y%m%d-%H%M%S"), extension));
+        fs::write(&path, text).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(path)
+    }
+}
+
+pub fn view(summary: &Summary) -> Element<'_, Message> {
+    let offenders = |title: &'static str, offenders: &[Offender], value: fn(&Offender) -> String| {
+        offenders.iter().fold(Column::new().spacing(2).push(Text::new(title)), |column, offender| {
+            column.push(Text::new(format!("{} ({})  {}", offender.name, offender.pid, value(offender))))
+        })
+    };
+    let overview = summary.overview().into_iter().fold(Column::new().spacing(2), |column, line| column.push(Text::new(line)));
+    Column::new()
+        .spacing(10)
+        .push(
+            Row::new()
+                .spacing(10)
+                .align_items(alignment::Alignment::Center)
+                .push(Text::new(format!("Session summary, {} since {}", summary.duration(), format::time(&summary.started))).width(Length::Fill))
+                .push(Button::new("Save as Markdown").on_press(Message::SaveSessionSummary(SummaryFormat::Markdown)))
+                .push(Button::new("Save as JSON").on_press(Message::SaveSessionSummary(SummaryFormat::Json)))
+                .push(Button::new("Close").on_press(Message::CloseSessionSummary)),
+        )
+        .push(
+            Scrollable::new(
+                Row::new()
+                    .spacing(30)
+                    .push(overview)
+                    .push(offenders("Top CPU", &summary.top_cpu, |offender| format!("{}%", format::decimal(f64::from(offender.cpu), 1))))
+                    .push(offenders("Top memory", &summary.top_memory, |offender| format!("{} MB", format::integer(offender.memory)))),
+            )
+            .height(Length::Fixed(150.0)),
+        )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn summaries_keep_each_program_at_its_peak() {
+        let start = Local::now();
+        let mut session = Session::new(start);
+        session.record_processes(&[mock::process(1, "cargo", "u", 100, 90.0), mock::process(2, "firefox", "u", 900, 5.0)]);
+        session.record_processes(&[mock::process(3, "cargo", "u", 300, 10.0), mock::process(2, "firefox", "u", 800, 20.0)]);
+        let mut events = EventLog::default();
+        events.push(EventKind::Kill, "You killed cargo (3)");
+        let summary = session.summary(&events, &Churn::default(), start + chrono::Duration::seconds(90));
+
+        let top = |offenders: &[Offender]| offenders.iter().map(|offender| (offender.pid, offender.cpu, offender.memory)).collect::<Vec<_>>();
+        assert_eq!(top(&summary.top_cpu), vec![(1, 90.0, 100), (2, 20.0, 800)]);
+        assert_eq!(top(&summary.top_memory), vec![(2, 5.0, 900), (3, 10.0, 300)]);
+        assert_eq!((summary.kills, summary.alerts), (1, 0));
+        let markdown = summary.markdown();
+        assert!(markdown.contains("(0:01:30)"));
+        assert!(markdown.contains("| cargo | 1 |"));
+        assert!(markdown.contains("You killed cargo (3)"));
+        assert!(serde_json::to_string(&summary).unwrap().contains("\"kills\":1"));
+    }
+}
@@ -0,0 +1,893 @@
+// drives `TaskManager::update` with scripted messages against the mock provider
+use iced::keyboard::Modifiers;
+use iced::multi_window::Application;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crate::apps::Group;
+use crate::columns::{self, ColumnFormats, NumberFormat};
+use crate::config::Config;
+use crate::events::EventKind;
+use crate::filter::Pin;
+use crate::jobs::Job;
+use crate::kill_guard;
+use crate::layout::Layout;
+use crate::notes;
+use crate::process::{ProcessInfo, SortColumn};
+use crate::rule_editor::RuleField;
+use crate::row_menu::RowAction;
+use crate::rules::RuleAction;
+use crate::provider::mock::{self, MockProvider};
+use crate::sim::{SimProvider, SimSpec};
+use crate::{Message, Tab, TaskManager};
+
+struct Harness {
+    app: TaskManager,
+    // shared with the provider, so tests can change the "system" between refreshes
+    processes: Rc<RefCell<Vec<ProcessInfo>>>,
+    killed: Rc<RefCell<Vec<u32>>>,
+}
+
+impl Harness {
+    fn new(processes: Vec<ProcessInfo>) -> Harness {
+        let processes = Rc::new(RefCell::new(processes));
+        let killed = Rc::new(RefCell::new(Vec::new()));
+        let provider = MockProvider { processes: processes.clone(), killed: killed.clone() };
+        // raw cpu values so the expected numbers are the ones given
+        let config = Config { cpu_smoothing: 1, ..Config::default() };
+        let mut app = TaskManager::with_provider(config, Box::new(provider));
+        app.refresh_processes();
+        Harness { app, processes, killed }
+    }
+
+    // runs the messages in order, dropping the returned commands
+    fn send(&mut self, messages: impl IntoIterator<Item = Message>) -> &mut Harness {
+        for message in messages {
+            let _ = self.app.update(message);
+        }
+        self
+    }
+
+    // the process the table shows with the pid, as a kill aimed at it
+    fn target(&self, pid: u32) -> kill_guard::Target {
+        self.app.target_of(pid).expect("the pid is in the table")
+    }
+
+    // pids of the rows in the order the table shows them
+    fn visible_pids(&self) -> Vec<u32> {
+        self.app.visible_processes().iter().map(|process| process.pid).collect()
+    }
+}
+
+fn sample() -> Vec<ProcessInfo> {
+    vec![
+        mock::process(30, "firefox", "alice", 900, 12.0),
+        mock::process(10, "bash", "alice", 5, 0.0),
+        mock::process(20, "postgres", "postgres", 300, 3.5),
+    ]
+}
+
+#[test]
+fn starts_sorted_by_pid_ascending() {
+    let harness = Harness::new(sample());
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+}
+
+#[test]
+fn sorting_the_same_column_twice_toggles_the_order() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::Sort(SortColumn::Memory)]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    harness.send([Message::Sort(SortColumn::Memory)]);
+    assert_eq!(harness.visible_pids(), vec![30, 20, 10]);
+    harness.send([Message::Sort(SortColumn::Name)]);
+    assert!(harness.app.sort_ascending);
+    assert_eq!(harness.visible_pids(), vec![10, 30, 20]);
+}
+
+#[test]
+fn shift_clicking_a_header_adds_a_sort_key() {
+    let mut harness = Harness::new(vec![
+        mock::process(10, "b", "root", 50, 1.0),
+        mock::process(20, "a", "root", 50, 2.0),
+        mock::process(30, "c", "root", 10, 3.0),
+    ]);
+    harness.send([
+        Message::Sort(SortColumn::Memory),
+        Message::ModifiersChanged(Modifiers::SHIFT),
+        Message::Sort(SortColumn::Name),
+        Message::Sort(SortColumn::Name),
+    ]);
+    assert_eq!(harness.visible_pids(), vec![30, 10, 20]);
+    assert_eq!(harness.app.config.sort, vec![(SortColumn::Memory, true), (SortColumn::Name, false)]);
+    // a plain click starts over
+    harness.send([Message::ModifiersChanged(Modifiers::default()), Message::Sort(SortColumn::Cpu)]);
+    assert_eq!(harness.app.config.sort, vec![(SortColumn::Cpu, true)]);
+}
+
+#[test]
+fn sort_by_index_uses_the_visible_columns() {
+    let mut harness = Harness::new(sample());
+    // the fifth visible column is cpu
+    harness.send([Message::SortByIndex(4), Message::SortByIndex(4)]);
+    assert_eq!(harness.app.sort_column, SortColumn::Cpu);
+    assert_eq!(harness.visible_pids(), vec![30, 20, 10]);
+}
+
+#[test]
+fn search_filters_by_name_user_and_pid() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SearchChanged(String::from("FIRE"))]);
+    assert_eq!(harness.visible_pids(), vec![30]);
+    harness.send([Message::SearchChanged(String::from("alice"))]);
+    assert_eq!(harness.visible_pids(), vec![10, 30]);
+    harness.send([Message::SearchChanged(String::from("20"))]);
+    assert_eq!(harness.visible_pids(), vec![20]);
+    harness.send([Message::SearchChanged(String::new())]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    harness.send([Message::SearchChanged(String::from("user:alice !/^fire/"))]);
+    assert_eq!(harness.visible_pids(), vec![10]);
+}
+
+#[test]
+fn search_words_rank_the_closest_names_first() {
+    let mut harness = Harness::new(vec![
+        mock::process(10, "vscode", "alice", 5, 0.0),
+        mock::process(20, "code", "alice", 5, 0.0),
+        mock::process(30, "firefox", "alice", 5, 0.0),
+    ]);
+    harness.send([Message::SearchChanged(String::from("code"))]);
+    assert_eq!(harness.visible_pids(), vec![20, 10]);
+    harness.send([Message::SearchChanged(String::from("ffx"))]);
+    assert_eq!(harness.visible_pids(), vec![30]);
+    assert_eq!(harness.app.filter.name_matches("firefox"), vec![0, 4, 6]);
+}
+
+#[test]
+fn details_tab_sorts_and_searches_on_its_own() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::GridSort(SortColumn::Memory), Message::GridSort(SortColumn::Memory), Message::GridSearchChanged(String::from("user:alice"))]);
+    let processes = harness.app.processes.clone();
+    let rows: Vec<u32> = harness.app.grid.rows(&processes).iter().map(|process| process.pid).collect();
+    assert_eq!(rows, vec![30, 10]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+}
+
+#[test]
+fn totals_follow_the_filter() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SearchChanged(String::from("user:alice"))]);
+    let visible = harness.app.visible_processes();
+    let formatter = harness.app.formatter();
+    assert_eq!(columns::total_text(&visible, SortColumn::Memory, formatter), "Σ 905");
+    assert_eq!(columns::total_text(&visible, SortColumn::Cpu, formatter), "avg 6.0");
+    assert_eq!(columns::total_text(&visible, SortColumn::Name, formatter), "2 processes");
+}
+
+#[test]
+fn column_formats_apply_to_cells_totals_and_headers() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::SearchChanged(String::from("user:alice")),
+        Message::SetColumnFormat(SortColumn::Memory, NumberFormat::Gibibytes),
+        Message::SetColumnFormat(SortColumn::Cpu, NumberFormat::Whole),
+        Message::SetColumnFormat(SortColumn::Pid, NumberFormat::ZeroPadded),
+        // not offered by the column
+        Message::SetColumnFormat(SortColumn::Name, NumberFormat::Percent),
+    ]);
+    let visible = harness.app.visible_processes();
+    let formatter = harness.app.formatter();
+    assert_eq!(columns::total_text(&visible, SortColumn::Memory, formatter), "Σ 0.88");
+    assert_eq!(columns::total_text(&visible, SortColumn::Cpu, formatter), "avg 6");
+    assert_eq!(columns::row_text(visible[0], &[SortColumn::Pid, SortColumn::Name], formatter), "0000010\tbash");
+    assert_eq!(columns::header_label(SortColumn::Memory, &[], formatter), "Memory (GiB)");
+    assert_eq!(harness.app.config.column_formats.get(SortColumn::Name), None);
+
+    harness.send([
+        Message::SetColumnFormat(SortColumn::Memory, NumberFormat::Megabytes),
+        Message::SetColumnFormat(SortColumn::Cpu, NumberFormat::OneDecimal),
+        Message::SetColumnFormat(SortColumn::Pid, NumberFormat::Plain),
+    ]);
+    assert_eq!(harness.app.config.column_formats, ColumnFormats::default());
+}
+
+#[test]
+fn grouped_apps_come_first_and_groups_fold() {
+    let mut processes = sample();
+    processes[2].app = true;
+    let mut harness = Harness::new(processes);
+    harness.send([Message::ToggleGroupApps(true)]);
+    assert_eq!(harness.visible_pids(), vec![20, 10, 30]);
+    harness.send([Message::ToggleGroup(Group::Background)]);
+    assert_eq!(harness.visible_pids(), vec![20]);
+}
+
+#[test]
+fn unreadable_processes_are_folded_away_at_the_end() {
+    let mut processes = sample();
+    processes[2].access_denied = true;
+    let mut harness = Harness::new(processes);
+    assert_eq!(harness.visible_pids(), vec![10, 30]);
+    harness.send([Message::ToggleGroup(Group::Denied)]);
+    assert_eq!(harness.visible_pids(), vec![10, 30, 20]);
+    assert_eq!(columns::cell_text(harness.app.visible_processes()[2], SortColumn::Memory, harness.app.formatter()), "–");
+}
+
+#[test]
+fn virtual_and_shared_memory_columns_sort_and_show_unknown_values() {
+    let mut processes = sample();
+    processes[0].virtual_memory = 50;
+    processes[1].virtual_memory = 900;
+    processes[2].virtual_memory = 7;
+    processes[0].shared_memory = Some(40);
+    processes[1].shared_memory = Some(2);
+    let mut harness = Harness::new(processes);
+    harness.send([Message::Sort(SortColumn::VirtualMemory)]);
+    assert_eq!(harness.visible_pids(), vec![20, 30, 10]);
+    // an unknown shared size sorts below every known one
+    harness.send([Message::Sort(SortColumn::SharedMemory)]);
+    assert_eq!(harness.visible_pids(), vec![20, 10, 30]);
+    let formatter = harness.app.formatter();
+    let cells: Vec<String> = harness.app.visible_processes().iter().map(|process| columns::cell_text(process, SortColumn::SharedMemory, formatter)).collect();
+    assert_eq!(cells, ["n/a", "2", "40"]);
+    assert_eq!(columns::cell_text(harness.app.visible_processes()[1], SortColumn::VirtualMemory, formatter), "900");
+}
+
+#[test]
+fn noise_filter_hides_idle_processes() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::ToggleNoiseFilter(true)]);
+    assert_eq!(harness.visible_pids(), vec![20, 30]);
+    assert_eq!(harness.app.hidden_process_count(), 1);
+}
+
+#[test]
+fn kill_goes_through_the_preview() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SelectProcess(20), Message::KillSelected]);
+    assert!(harness.killed.borrow().is_empty());
+    assert_eq!(harness.app.kill_preview.as_ref().map(|preview| preview.pid), Some(20));
+
+    harness.send([Message::KillProcess(harness.target(20))]);
+    assert_eq!(*harness.killed.borrow(), vec![20]);
+    assert!(harness.app.kill_preview.is_none());
+    assert_eq!(harness.visible_pids(), vec![10, 30]);
+}
+
+#[test]
+fn critical_processes_need_an_acknowledged_preview() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::CriticalInputChanged(String::from("Postgres")),
+        Message::AddCritical,
+        Message::KillUserProcesses(String::from("postgres")),
+    ]);
+    assert!(harness.killed.borrow().is_empty());
+
+    harness.send([Message::SelectProcess(20), Message::KillSelected]);
+    let preview = harness.app.kill_preview.as_ref().unwrap();
+    assert_eq!((preview.critical.clone(), preview.acknowledged), (vec![String::from("postgres (20)")], false));
+    harness.send([Message::AcknowledgeCritical(true)]);
+    assert!(harness.app.kill_preview.as_ref().is_some_and(|preview| preview.acknowledged));
+
+    // a kill that didn't come through the preview is refused, the acknowledged one goes through
+    let preview = harness.app.kill_preview.clone().unwrap();
+    harness.send([Message::KillProcess(harness.target(20))]);
+    assert!(harness.killed.borrow().is_empty());
+    assert_eq!(harness.app.status_message.as_deref(), Some("Didn't kill postgres (20), critical processes are only killed from the kill preview"));
+    harness.send([Message::OpenDump(20), Message::ConfirmDump]);
+    assert_eq!(
+        harness.app.status_message.as_deref(),
+        Some("Didn't dump and kill postgres (20), critical processes are only killed from the kill preview")
+    );
+    harness.send([Message::KillProcess(kill_guard::Target { pid: 20, identity: preview.identity, confirmed_critical: preview.acknowledged })]);
+    assert_eq!(*harness.killed.borrow(), vec![20]);
+}
+
+#[test]
+fn read_only_mode_refuses_kills_until_turned_off() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::ToggleReadOnly(true), Message::KillProcess(harness.target(20)), Message::RowAction(30, RowAction::Kill)]);
+    assert!(harness.killed.borrow().is_empty());
+    assert!(harness.app.kill_preview.is_none());
+    assert_eq!(harness.app.status_message.as_deref(), Some("read-only mode, processes can't be changed"));
+
+    harness.send([Message::ToggleReadOnly(false), Message::KillProcess(harness.target(20))]);
+    assert_eq!(*harness.killed.borrow(), vec![20]);
+}
+
+#[test]
+fn read_only_mode_refuses_to_watch_processes() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::ToggleReadOnly(true), Message::WatchProcess(20)]);
+    assert!(harness.app.config.watched.is_empty());
+    assert_eq!(harness.app.status_message.as_deref(), Some("read-only mode, processes can't be changed"));
+}
+
+#[test]
+fn read_only_mode_stops_waiting_kills_and_dumps() {
+    let mut harness = Harness::new(sample());
+    harness.app.config.undo_kills = true;
+    harness.send([Message::KillProcess(harness.target(20))]);
+    harness.app.pending_kills[0].due = std::time::Instant::now();
+    // set without the switch, which would also drop the waiting kill
+    harness.app.config.read_only = true;
+    harness.send([Message::SendPendingKills, Message::OpenDump(30)]);
+    assert!(harness.killed.borrow().is_empty());
+    assert!(harness.app.dump_dialog.is_none());
+
+    // the kill itself checks again
+    let target = harness.target(20);
+    harness.app.kill_now(&target, false);
+    assert!(harness.killed.borrow().is_empty());
+    assert_eq!(harness.app.status_message.as_deref(), Some("Didn't kill postgres (20), read-only mode is on"));
+}
+
+#[test]
+fn kills_and_exits_go_to_the_event_log() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::KillProcess(harness.target(20))]);
+    let events: Vec<(EventKind, String)> = harness.app.events.iter().map(|event| (event.kind, event.text.clone())).collect();
+    assert_eq!(events, vec![
+        (EventKind::Kill, String::from("You killed postgres (20)")),
+        (EventKind::Exited, String::from("postgres (20)")),
+    ]);
+}
+
+#[test]
+fn delayed_kills_can_be_undone() {
+    let mut harness = Harness::new(sample());
+    harness.app.config.undo_kills = true;
+    harness.send([Message::KillProcess(harness.target(20)), Message::UndoKill(20), Message::KillProcess(harness.target(30)), Message::SendPendingKills]);
+    assert!(harness.killed.borrow().is_empty());
+    harness.app.pending_kills[0].due = std::time::Instant::now();
+    harness.send([Message::SendPendingKills]);
+    assert_eq!(*harness.killed.borrow(), vec![30]);
+    assert!(harness.app.pending_kills.is_empty());
+}
+
+#[test]
+fn kills_leave_a_reused_pid_alone() {
+    let mut processes = sample();
+    processes[1].start_time = 1_600_000_000;
+    processes[2].start_time = 1_600_000_000;
+    let mut harness = Harness::new(processes);
+    // postgres exits and a new one gets its pid before the next refresh
+    harness.processes.borrow_mut()[2].start_time = 1_700_000_000;
+    harness.send([Message::KillProcess(harness.target(20))]);
+    assert!(harness.killed.borrow().is_empty());
+    assert_eq!(harness.app.status_message.as_deref(), Some("Didn't kill postgres (20), its pid now belongs to a newer process, postgres"));
+
+    // and firefox is replaced while its kill waits for the undo delay
+    harness.app.config.undo_kills = true;
+    harness.send([Message::KillProcess(harness.target(30))]);
+    harness.processes.borrow_mut()[0].name = String::from("intruder");
+    harness.app.pending_kills[0].due = std::time::Instant::now();
+    harness.send([Message::SendPendingKills]);
+    assert!(harness.killed.borrow().is_empty());
+    assert_eq!(harness.app.status_message.as_deref(), Some("Didn't kill firefox (30), its pid now belongs to a newer process, intruder"));
+
+    // the preview keeps the process it was opened for, a refresh showing the new one changes nothing
+    harness.app.config.undo_kills = false;
+    harness.send([Message::RowAction(10, RowAction::Kill)]);
+    let preview = harness.app.kill_preview.clone().unwrap();
+    harness.processes.borrow_mut()[1].start_time = 1_700_000_000;
+    harness.app.refresh_processes();
+    harness.send([Message::KillProcess(kill_guard::Target { pid: preview.pid, identity: preview.identity, confirmed_critical: false })]);
+    assert!(harness.killed.borrow().is_empty());
+}
+
+#[test]
+fn scheduled_kills_keep_the_process_they_were_set_for() {
+    let mut processes = sample();
+    processes[0].start_time = 1_600_000_000;
+    let mut harness = Harness::new(processes);
+    harness.send([Message::OpenSchedule(30), Message::ScheduleWhenChanged(String::from("1s")), Message::ConfirmSchedule]);
+    assert_eq!(harness.app.scheduled.len(), 1);
+    // firefox is replaced by another process with the same pid before the action is due
+    harness.processes.borrow_mut()[0].start_time = 1_700_000_000;
+    harness.app.scheduled[0].due = chrono::Local::now();
+    harness.send([Message::RunDueActions]);
+    assert!(harness.killed.borrow().is_empty());
+    assert!(harness.app.scheduled.is_empty());
+}
+
+#[test]
+fn cancelled_kill_sends_nothing() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::RowAction(30, RowAction::Kill), Message::CancelKill]);
+    assert!(harness.killed.borrow().is_empty());
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+}
+
+#[test]
+fn kill_user_processes_only_kills_that_user() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::ConfirmKillUserProcesses(String::from("alice")),
+        Message::KillUserProcesses(String::from("alice")),
+    ]);
+    let mut killed = harness.killed.borrow().clone();
+    killed.sort();
+    assert_eq!(killed, vec![10, 30]);
+    assert_eq!(harness.visible_pids(), vec![20]);
+}
+
+#[test]
+fn selection_moves_within_the_filtered_list() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SearchChanged(String::from("alice")), Message::MoveSelection(1)]);
+    assert_eq!(harness.app.selected_pid, Some(10));
+    harness.send([Message::MoveSelection(1), Message::MoveSelection(1)]);
+    assert_eq!(harness.app.selected_pid, Some(30));
+}
+
+#[test]
+fn refresh_picks_up_new_processes() {
+    let mut harness = Harness::new(sample());
+    harness.processes.borrow_mut().push(mock::process(40, "cargo", "alice", 50, 80.0));
+    harness.send([Message::RefreshNow]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30, 40]);
+}
+
+#[test]
+fn refresh_now_works_while_paused() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::TogglePause]);
+    assert!(harness.app.paused);
+    harness.processes.borrow_mut().retain(|process| process.pid != 10);
+    harness.send([Message::RefreshNow]);
+    assert_eq!(harness.visible_pids(), vec![20, 30]);
+    harness.send([Message::TogglePause]);
+    assert!(!harness.app.paused);
+}
+
+#[test]
+fn selection_freezes_the_order_until_cleared() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::Sort(SortColumn::Memory), Message::Sort(SortColumn::Memory), Message::SelectProcess(20)]);
+    assert_eq!(harness.visible_pids(), vec![30, 20, 10]);
+
+    // bash overtakes everyone and a new process appears, neither moves the rows yet
+    harness.processes.borrow_mut()[1].memory = 5000;
+    harness.processes.borrow_mut().push(mock::process(40, "cargo", "alice", 2000, 80.0));
+    harness.send([Message::RefreshNow]);
+    assert_eq!(harness.visible_pids(), vec![30, 20, 10]);
+    let bash = harness.app.processes.iter().find(|process| process.pid == 10).unwrap();
+    assert_eq!(bash.memory, 5000);
+
+    harness.send([Message::ClearSelection]);
+    assert_eq!(harness.visible_pids(), vec![10, 40, 30, 20]);
+}
+
+#[test]
+fn hovering_freezes_the_order_and_leaving_thaws_it() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::ListHovered(true)]);
+    harness.processes.borrow_mut().push(mock::process(5, "init", "root", 10, 0.0));
+    harness.send([Message::RefreshNow]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    harness.send([Message::ListHovered(false)]);
+    assert_eq!(harness.visible_pids(), vec![5, 10, 20, 30]);
+}
+
+#[test]
+fn leak_filter_shows_processes_that_keep_growing() {
+    let mut harness = Harness::new(sample());
+    harness.app.config.leak_samples = 2;
+    for memory in [400, 410, 420] {
+        harness.processes.borrow_mut()[0].memory = memory;
+        // bash shrinks once, which breaks its run
+        harness.processes.borrow_mut()[1].memory = if memory == 410 { 4 } else { memory };
+        harness.app.refresh_processes();
+    }
+    harness.send([Message::ToggleLeakFilter(true)]);
+    assert_eq!(harness.visible_pids(), vec![30]);
+}
+
+#[test]
+fn kill_rules_kill_and_log_rules_only_record() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::NewRule,
+        Message::RuleDraftChanged(RuleField::Name, String::from("no firefox")),
+        Message::RuleDraftChanged(RuleField::ProcessName, String::from("firefox")),
+        Message::SetRuleDraftAction(RuleAction::Kill),
+        Message::SaveRule,
+        Message::NewRule,
+        Message::RuleDraftChanged(RuleField::Name, String::from("big")),
+        Message::RuleDraftChanged(RuleField::MinMemory, String::from("200")),
+        Message::RuleDraftChanged(RuleField::Except, String::from("firefox")),
+        Message::SaveRule,
+    ]);
+    harness.app.refresh_processes();
+    assert_eq!(*harness.killed.borrow(), vec![30]);
+    let log: Vec<&str> = harness.app.rule_log.iter().map(|event| event.text.as_str()).collect();
+    assert_eq!(log, vec!["no firefox killed firefox (30)", "big matched postgres (20)"]);
+    // matches are only acted on once per pid
+    harness.app.refresh_processes();
+    assert_eq!(harness.app.rule_log.len(), 2);
+}
+
+#[test]
+fn command_rules_need_a_command_and_are_held_back_in_read_only_mode() {
+    let mut harness = Harness::new(sample());
+    harness.app.config.read_only = true;
+    harness.send([
+        Message::NewRule,
+        Message::RuleDraftChanged(RuleField::Name, String::from("renice postgres")),
+        Message::RuleDraftChanged(RuleField::ProcessName, String::from("postgres")),
+        Message::SetRuleDraftAction(RuleAction::Command),
+        Message::SaveRule,
+    ]);
+    assert!(harness.app.config.rules.is_empty());
+    harness.send([Message::RuleDraftChanged(RuleField::Command, String::from("renice 19 {pid}")), Message::SaveRule]);
+    assert_eq!(harness.app.config.rules[0].command, "renice 19 {pid}");
+    harness.app.refresh_processes();
+    let log: Vec<&str> = harness.app.rule_log.iter().map(|event| event.text.as_str()).collect();
+    assert_eq!(log, vec!["renice postgres matched postgres (20), no command run in read-only mode"]);
+}
+
+#[test]
+fn column_widths_are_stored_only_when_changed() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SetColumnWidth(SortColumn::Pid, 3), Message::SetColumnWidth(SortColumn::Name, 2)]);
+    let widths = &harness.app.config.column_widths;
+    assert_eq!((widths.portion(SortColumn::Pid), widths.portion(SortColumn::Name)), (3, 2));
+    assert_eq!(toml::to_string(&harness.app.config.column_widths).unwrap().trim(), "Pid = 3");
+}
+
+#[test]
+fn system_processes_are_one_toggle_away() {
+    let kworker = ProcessInfo { system: true, ..mock::process(2, "kworker/0:1", "root", 0, 0.0) };
+    let mut harness = Harness::new(sample().into_iter().chain([kworker]).collect());
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    assert_eq!(harness.app.hidden_system_count(), 1);
+    harness.send([Message::ToggleSystemProcesses(true)]);
+    assert_eq!(harness.visible_pids(), vec![2, 10, 20, 30]);
+}
+
+#[test]
+fn pinned_processes_stay_on_top() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::Pin(Pin::Name(String::from("postgres")))]);
+    assert_eq!(harness.visible_pids(), vec![20, 10, 30]);
+    // descending by pid
+    harness.send([Message::Sort(SortColumn::Pid)]);
+    assert_eq!(harness.visible_pids(), vec![20, 30, 10]);
+    harness.send([Message::Unpin(20)]);
+    assert_eq!(harness.visible_pids(), vec![30, 20, 10]);
+    assert!(harness.app.config.pinned.is_empty());
+}
+
+#[test]
+fn pid_pins_end_with_the_process() {
+    let mut harness = Harness::new(sample());
+    let postgres = harness.processes.borrow()[2].clone();
+    harness.send([Message::Pin(Pin::of_process(&postgres))]);
+    assert_eq!(harness.visible_pids(), vec![20, 10, 30]);
+    // a newer process with the same pid isn't pinned
+    harness.processes.borrow_mut()[2].start_time = 1_700_000_000;
+    harness.send([Message::RefreshNow]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    assert!(harness.app.config.pinned.is_empty());
+}
+
+#[test]
+fn presets_restore_search_and_sort() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::SearchChanged(String::from("alice")),
+        Message::Sort(SortColumn::Memory),
+        Message::PresetInputChanged(String::from("mine")),
+        Message::SavePreset,
+        Message::SearchChanged(String::new()),
+        Message::Sort(SortColumn::Pid),
+    ]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    harness.send([Message::ApplyPreset(String::from("mine"))]);
+    assert_eq!(harness.visible_pids(), vec![10, 30]);
+    assert_eq!(harness.app.sort_column, SortColumn::Memory);
+}
+
+#[test]
+fn ignored_names_skip_the_table_and_the_rules() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::IgnoreInputChanged(String::from("FireFox")),
+        Message::AddIgnored,
+        Message::NewRule,
+        Message::RuleDraftChanged(RuleField::Name, String::from("big")),
+        Message::RuleDraftChanged(RuleField::MinMemory, String::from("200")),
+        Message::SaveRule,
+    ]);
+    harness.app.refresh_processes();
+    assert_eq!(harness.visible_pids(), vec![10, 20]);
+    let log: Vec<&str> = harness.app.rule_log.iter().map(|event| event.text.as_str()).collect();
+    assert_eq!(log, vec!["big matched postgres (20)"]);
+}
+
+#[test]
+fn jumping_to_a_parent_selects_it_unless_it_is_filtered_out() {
+    let child = ProcessInfo { ppid: Some(10), ..mock::process(40, "cargo", "alice", 50, 1.0) };
+    let mut harness = Harness::new(sample().into_iter().chain([child]).collect());
+    harness.send([Message::JumpToProcess(10)]);
+    assert_eq!(harness.app.selected_pid, Some(10));
+    harness.send([Message::SearchChanged(String::from("cargo")), Message::JumpToProcess(10)]);
+    assert_eq!(harness.app.status_message.as_deref(), Some("bash (10) is hidden by the current filters"));
+}
+
+#[test]
+fn ppid_column_sorts_by_parent_and_leaves_orphans_blank() {
+    let mut processes = sample();
+    processes[0].ppid = Some(10);
+    processes[2].ppid = Some(1);
+    let mut harness = Harness::new(processes);
+    // processes without a parent first
+    harness.send([Message::Sort(SortColumn::Ppid)]);
+    assert_eq!(harness.visible_pids(), vec![10, 20, 30]);
+    let formatter = harness.app.formatter();
+    let cells: Vec<String> = harness.app.visible_processes().iter().map(|process| columns::cell_text(process, SortColumn::Ppid, formatter)).collect();
+    assert_eq!(cells, ["", "1", "10"]);
+}
+
+#[test]
+fn elevated_filter_keeps_root_processes() {
+    let sshd = ProcessInfo { elevated: true, system: true, ..mock::process(1, "sshd", "root", 8, 0.0) };
+    let mut harness = Harness::new(sample().into_iter().chain([sshd]).collect());
+    harness.send([Message::ToggleElevatedFilter(true)]);
+    assert_eq!(harness.visible_pids(), vec![1]);
+}
+
+#[test]
+fn double_clicking_a_row_opens_its_properties() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SelectProcess(20), Message::SelectProcess(30)]);
+    assert!(harness.app.properties.is_none());
+    // the mock's processes aren't in the sysinfo table, so this process stands in
+    let own = std::process::id();
+    harness.processes.borrow_mut().push(mock::process(own, "conborli", "alice", 10, 0.0));
+    harness.app.system.refresh_processes();
+    harness.app.refresh_processes();
+    harness.send([Message::SelectProcess(own), Message::SelectProcess(own)]);
+    assert_eq!(harness.app.properties.as_ref().map(|properties| properties.pid), Some(own));
+    harness.send([Message::CloseProperties]);
+    assert!(harness.app.properties.is_none());
+}
+
+#[test]
+fn processes_stuck_for_several_samples_are_not_responding() {
+    let mut harness = Harness::new(sample());
+    let not_responding = |harness: &Harness| {
+        harness.app.processes.iter().filter(|process| process.not_responding).map(|process| process.pid).collect::<Vec<_>>()
+    };
+    harness.processes.borrow_mut()[0].stuck = true;
+    harness.processes.borrow_mut()[1].stuck = true;
+    harness.app.refresh_processes();
+    harness.app.refresh_processes();
+    assert!(not_responding(&harness).is_empty());
+    // bash recovers before the third sample
+    harness.processes.borrow_mut()[1].stuck = false;
+    harness.app.refresh_processes();
+    assert_eq!(not_responding(&harness), vec![30]);
+}
+
+#[test]
+fn resorting_scrolls_along_with_the_selected_row() {
+    let mut harness = Harness::new(sample());
+    let row = harness.app.list_window.row_height;
+    // a viewport one row tall scrolled to the bottom
+    harness.app.list_window.height = row;
+    harness.app.list_window.offset = 2.0 * row;
+    // firefox moves from the last row (by pid) to the middle one (by name)
+    harness.send([Message::SelectProcess(30), Message::Sort(SortColumn::Name)]);
+    assert_eq!(harness.visible_pids(), vec![10, 30, 20]);
+    assert_eq!(harness.app.list_window.offset, row);
+    assert_eq!(harness.app.selected_pid, Some(30));
+    // a refresh that keeps the order leaves the scroll position alone
+    harness.send([Message::RefreshNow]);
+    assert_eq!(harness.app.list_window.offset, row);
+}
+
+#[test]
+fn kill_tree_kills_the_children_before_the_parent() {
+    let mut processes = sample();
+    processes[0].ppid = Some(20);
+    processes[2].ppid = Some(10);
+    let mut harness = Harness::new(processes);
+    harness.send([Message::RowAction(10, RowAction::KillTree)]);
+    assert!(harness.killed.borrow().is_empty());
+    assert!(harness.app.kill_preview.as_ref().is_some_and(|preview| preview.tree));
+
+    harness.send([Message::KillTree(harness.target(10))]);
+    assert_eq!(*harness.killed.borrow(), vec![30, 20, 10]);
+    assert!(harness.visible_pids().is_empty());
+}
+
+#[test]
+fn minimizing_pauses_collection_unless_something_watches() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::WindowFocused(false), Message::WindowMinimized(Some(true))]);
+    assert!(harness.app.is_idle());
+    harness.send([Message::NotifyOnExit(20)]);
+    assert!(!harness.app.is_idle());
+    harness.send([Message::CancelExitNotification(20), Message::WindowFocused(true)]);
+    assert!(!harness.app.minimized && !harness.app.is_idle());
+}
+
+#[test]
+fn the_global_shortcut_brings_back_a_window_closed_to_the_tray() {
+    let mut harness = Harness::new(sample());
+    harness.app.window_hidden = true;
+    assert!(harness.app.is_idle());
+    harness.send([Message::HotkeyPressed]);
+    assert!(!harness.app.window_hidden && !harness.app.is_idle());
+}
+
+#[test]
+fn ctrl_tab_cycles_through_the_tabs_and_wraps() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::CycleTab(-1)]);
+    assert_eq!(harness.app.tab, Tab::Settings);
+    harness.send([Message::CycleTab(1), Message::CycleTab(1)]);
+    assert_eq!(harness.app.tab, Tab::Details);
+}
+
+#[test]
+fn simple_mode_leaves_advanced_tabs_and_keeps_the_configured_columns() {
+    let mut harness = Harness::new(sample());
+    let configured = harness.app.config.columns.clone();
+    harness.send([Message::SelectTab(Tab::Rules), Message::SetLayout(Layout::Simple)]);
+    assert_eq!(harness.app.tab, Tab::Processes);
+    harness.send([Message::CycleTab(1), Message::CycleTab(1)]);
+    assert_eq!(harness.app.tab, Tab::Settings);
+    assert_eq!(harness.app.shown_columns(), vec![SortColumn::Name, SortColumn::Cpu, SortColumn::Memory]);
+    harness.send([Message::SetLayout(Layout::Advanced)]);
+    assert_eq!(harness.app.shown_columns(), configured);
+}
+
+#[test]
+fn comparing_pairs_the_selected_process_with_the_picked_one() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::RowAction(20, RowAction::Compare)]);
+    assert_eq!((harness.app.comparison, harness.app.selected_pid), (None, Some(20)));
+    harness.send([Message::RowAction(30, RowAction::Compare)]);
+    assert_eq!(harness.app.comparison, Some([20, 30]));
+    harness.send([Message::CloseComparison]);
+    assert_eq!(harness.app.comparison, None);
+}
+
+#[test]
+fn notes_are_kept_per_program_and_cleared_when_emptied() {
+    let mut harness = Harness::new(sample());
+    harness.send([
+        Message::RowAction(20, RowAction::Note),
+        Message::SetNoteTag(Some(notes::Tag::Green)),
+        Message::NoteTextChanged(String::from(" staging database ")),
+        Message::SaveNote,
+    ]);
+    let note = notes::Note { tag: Some(notes::Tag::Green), text: String::from("staging database") };
+    assert_eq!(harness.app.config.notes.get("postgres"), Some(&note));
+    harness.send([Message::RowAction(20, RowAction::Note)]);
+    assert_eq!(harness.app.note_draft.as_ref().map(|draft| draft.text.as_str()), Some("staging database"));
+    harness.send([Message::SetNoteTag(None), Message::NoteTextChanged(String::new()), Message::SaveNote]);
+    assert!(harness.app.config.notes.is_empty());
+}
+
+#[test]
+fn the_palette_runs_the_highlighted_match() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::OpenPalette, Message::PaletteQueryChanged(String::from("postgres")), Message::OpenSelectedProperties]);
+    assert!(harness.app.palette.is_none());
+    assert_eq!(harness.app.selected_pid, Some(20));
+    harness.send([Message::SelectTab(Tab::Settings), Message::OpenPalette, Message::PaletteQueryChanged(String::from("go to proc"))]);
+    harness.send([Message::MoveSelection(5), Message::RunPaletteEntry(0)]);
+    assert_eq!(harness.app.tab, Tab::Processes);
+}
+
+#[test]
+fn dragging_a_header_onto_another_moves_the_column() {
+    let mut harness = Harness::new(sample());
+    harness.app.config.columns = vec![SortColumn::Pid, SortColumn::Name, SortColumn::User, SortColumn::Cpu];
+    harness.send([Message::DragColumn(SortColumn::Pid), Message::DropColumn(SortColumn::User)]);
+    assert_eq!(harness.app.config.columns, vec![SortColumn::Name, SortColumn::User, SortColumn::Pid, SortColumn::Cpu]);
+    harness.send([Message::DragColumn(SortColumn::Cpu), Message::EndColumnDrag, Message::DropColumn(SortColumn::Name)]);
+    assert_eq!(harness.app.config.columns[3], SortColumn::Cpu);
+    harness.send([Message::ToggleColumn(SortColumn::Memory, true)]);
+    assert_eq!(harness.app.config.columns.last(), Some(&SortColumn::Memory));
+}
+
+#[test]
+fn processes_open_in_their_own_windows_until_closed() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::RowAction(20, RowAction::OpenWindow)]);
+    let id = *harness.app.detached.keys().next().unwrap();
+    assert_eq!(harness.app.title(id), "postgres (20) - Task Manager");
+    harness.send([Message::DetachedClosed(id)]);
+    assert!(harness.app.detached.is_empty());
+}
+
+#[test]
+fn shortcuts_act_on_the_focused_window() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::SelectProcess(30), Message::RowAction(20, RowAction::OpenWindow)]);
+    let id = *harness.app.detached.keys().next().unwrap();
+    // Delete in the process's own window doesn't reach the main window's selection
+    harness.send([Message::DetachedFocused(id, true), Message::Shortcut(Box::new(Message::KillSelected))]);
+    assert!(harness.app.kill_preview.is_none());
+    harness.send([Message::Shortcut(Box::new(Message::ClearSelection))]);
+    assert!(harness.app.detached.is_empty());
+    assert_eq!(harness.app.selected_pid, Some(30));
+    harness.send([Message::WindowFocused(true), Message::Shortcut(Box::new(Message::KillSelected))]);
+    assert!(harness.app.kill_preview.is_some());
+}
+
+#[test]
+fn process_windows_let_go_of_a_reused_pid() {
+    let mut processes = sample();
+    processes[2].start_time = 1_600_000_000;
+    let mut harness = Harness::new(processes);
+    harness.send([Message::RowAction(20, RowAction::OpenWindow)]);
+    let detached = harness.app.detached.values().next().unwrap().clone();
+    assert_eq!(detached.process(&harness.app.processes).map(|process| process.pid), Some(20));
+    harness.processes.borrow_mut()[2].start_time = 1_700_000_000;
+    harness.send([Message::RefreshNow]);
+    assert!(detached.process(&harness.app.processes).is_none());
+}
+
+#[test]
+fn lookups_and_remote_hosts_wait_for_network_access() {
+    let mut harness = Harness::new(sample());
+    harness.send([Message::LookUpHash(String::from("ba7816bf"))]);
+    assert_eq!(
+        harness.app.status_message.as_deref(),
+        Some("Executable reputation lookup: disabled: network access is turned off in Settings")
+    );
+    harness.send([Message::HostInputChanged(String::from("127.0.0.1:7878")), Message::AddHost]);
+    assert_eq!(harness.app.host, None);
+    assert_eq!(harness.app.status_message.as_deref(), Some("Process tables of remote hosts: disabled: network access is turned off in Settings"));
+}
+
+#[test]
+fn terminating_a_job_kills_its_processes() {
+    let mut harness = Harness::new(sample());
+    harness.app.jobs.push(Job::new(String::from("browser"), 30, std::time::Instant::now()));
+    harness.app.refresh_processes();
+    harness.send([Message::TerminateJob(0)]);
+    assert_eq!(*harness.killed.borrow(), vec![30]);
+}
+
+// a simulated population instead of the fixed sample, for what has to hold over many refreshes
+fn simulated(spec: &str) -> TaskManager {
+    let config = Config { cpu_smoothing: 1, ..Config::default() };
+    let mut app = TaskManager::with_provider(config, Box::new(SimProvider::new(SimSpec::parse(spec).unwrap())));
+    app.refresh_processes();
+    app
+}
+
+#[test]
+fn simulated_tables_stay_sorted_filtered_and_alerted_as_processes_come_and_go() {
+    let mut app = simulated("seed=3, processes=80, spawn=2, exit=0.03, period=8");
+    let _ = app.update(Message::Sort(SortColumn::Cpu));
+    let _ = app.update(Message::SearchChanged(String::from("python3")));
+    for message in [Message::NewRule, Message::RuleDraftChanged(RuleField::Name, String::from("busy")), Message::RuleDraftChanged(RuleField::MinCpu, String::from("60")), Message::SaveRule] {
+        let _ = app.update(message);
+    }
+    for _ in 0..20 {
+        app.refresh_processes();
+        let visible = app.visible_processes();
+        assert!(!visible.is_empty());
+        assert!(visible.iter().all(|process| process.name == "python3"));
+        assert!(visible.windows(2).all(|pair| pair[0].cpu <= pair[1].cpu));
+    }
+    // every busy process matched once, however often it went over the threshold
+    let alerts: Vec<&str> = app.rule_log.iter().map(|event| event.text.as_str()).collect();
+    assert!(!alerts.is_empty());
+    assert!(alerts.iter().all(|text| text.starts_with("busy matched")));
+    let unique: HashSet<&str> = alerts.iter().copied().collect();
+    assert_eq!(unique.len(), alerts.len());
+    assert!(app.events.iter().any(|event| event.kind == EventKind::Started));
+    assert!(app.events.iter().any(|event| event.kind == EventKind::Exited));
+}
@@ -0,0 +1,176 @@
+// user themes - color files picked in the settings tab
+//
+// each .toml or .json file in the themes directory next to the config is one theme, named
+// after the file. colors are hex strings and the ones left out come from the base theme:
+//
+//   base = "dark"
+//   background = "#1e1e2e"
+//   text = "#cdd6f4"
+//   primary = "#89b4fa"
+//   row = "#313244"       # selected row in tables
+//   header = "#45475a"    # column header buttons
+//   warning = "#f9e2af"
+//   critical = "#f38ba8"
+use iced::{theme::Palette, Color, Theme};
+use serde::{Deserialize, Deserializer};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::accent::ThemeChoice;
+use crate::config::Config;
+use crate::style::Highlights;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hex(pub Color);
+
+impl<'de> Deserialize<'de> for Hex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_hex(&text).map(Hex).ok_or_else(|| serde::de::Error::custom(format!("{} isn't a #rrggbb color", text)))
+    }
+}
+
+// "#rrggbb" or "#rrggbbaa"
+fn parse_hex(text: &str) -> Option<Color> {
+    let digits = text.trim().strip_prefix('#')?;
+    if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(digits.get(index..index + 2)?, 16).ok();
+    let alpha = if digits.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, f32::from(alpha) / 255.0))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeColors {
+    // built-in theme the missing colors are taken from
+    pub base: ThemeChoice,
+    pub background: Option<Hex>,
+    pub text: Option<Hex>,
+    pub primary: Option<Hex>,
+    pub success: Option<Hex>,
+    pub danger: Option<Hex>,
+    pub row: Option<Hex>,
+    pub header: Option<Hex>,
+    pub warning: Option<Hex>,
+    pub critical: Option<Hex>,
+}
+
+impl ThemeColors {
+    pub fn theme(&self, name: &str) -> Theme {
+        let base = self.base.base().palette();
+        let pick = |color: Option<Hex>, fallback: Color| color.map_or(fallback, |Hex(color)| color);
+        Theme::custom(
+            name.to_string(),
+            Palette {
+                background: pick(self.background, base.background),
+                text: pick(self.text, base.text),
+                primary: pick(self.primary, base.primary),
+                success: pick(self.success, base.success),
+                danger: pick(self.danger, base.danger),
+            },
+        )
+    }
+
+    // the colors iced's palette has no place for
+    pub fn highlights(&self) -> Highlights {
+        let default = Highlights::default();
+        Highlights {
+            row: self.row.map(|Hex(color)| color),
+            header: self.header.map(|Hex(color)| color),
+            warning: self.warning.map_or(default.warning, |Hex(color)| color),
+            critical: self.critical.map_or(default.critical, |Hex(color)| color),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomTheme {
+    // file name without the extension
+    pub name: String,
+    // the parse error is kept to show in the settings tab
+    pub colors: Result<ThemeColors, String>,
+}
+
+// e.g. ~/.config/conborli/themes
+pub fn dir() -> Option<PathBuf> {
+    Config::dir().map(|dir| dir.join("themes"))
+}
+
+fn parse(path: &Path) -> Result<ThemeColors, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    if path.extension().is_some_and(|extension| extension == "json") {
+        serde_json::from_str(&contents).map_err(|err| err.to_string())
+    } else {
+        toml::from_str(&contents).map_err(|err| err.to_string())
+    }
+}
+
+// the theme files in name order, a missing directory means no themes
+fn theme_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "toml" || extension == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+pub fn load_all() -> Vec<CustomTheme> {
+    theme_files()
+        .iter()
+        .map(|path| CustomTheme {
+            name: path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default(),
+            colors: parse(path),
+        })
+        .collect()
+}
+
+// file names and contents of every theme, for the settings export
+pub fn read_files() -> Result<BTreeMap<String, String>, String> {
+    theme_files()
+        .iter()
+        .map(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            fs::read_to_string(path).map(|contents| (name, contents)).map_err(|err| format!("{}: {}", path.display(), err))
+        })
+        .collect()
+}
+
+// writes theme files from a settings import, replacing themes of the same name
+pub fn write_files(files: &BTreeMap<String, String>) -> Result<(), String> {
+    let dir = dir().ok_or("no config directory on this platform")?;
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    for (name, contents) in files {
+        // only plain theme file names, an imported file can't write elsewhere
+        let plain = Path::new(name).file_name().is_some_and(|file| file == name.as_str());
+        if !plain || !(name.ends_with(".toml") || name.ends_with(".json")) {
+            return Err(format!("{} isn't a theme file name", name));
+        }
+        fs::write(dir.join(name), contents).map_err(|err| format!("{}: {}", name, err))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_files_fill_gaps_from_the_base() {
+        let colors: ThemeColors = toml::from_str("base = \"light\"\nbackground = \"#102030\"\ncritical = \"#ff000080\"").unwrap();
+        let palette = colors.theme("night").palette();
+        assert_eq!(palette.background, Color::from_rgb8(0x10, 0x20, 0x30));
+        assert_eq!(palette.text, Theme::Light.palette().text);
+        let highlights = colors.highlights();
+        assert_eq!(highlights.critical.a, 128.0 / 255.0);
+        assert_eq!(highlights.warning, Highlights::default().warning);
+        assert!(toml::from_str::<ThemeColors>("text = \"red\"").is_err());
+        assert!(serde_json::from_str::<ThemeColors>("{\"rows\": \"#000000\"}").is_err());
+    }
+}
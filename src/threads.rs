@@ -0,0 +1,100 @@
+// threads of one process, read from /proc/<pid>/task on linux since sysinfo doesn't list them
+//
+// per-thread cpu is the difference of the thread's cpu ticks between two samples, so the first
+// sample of a process shows 0% for every thread
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::procfs;
+
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    // holds info about each thread
+    pub tid: u32,
+    pub name: String,
+    // single letter scheduler state, e.g. "R" running or "S" sleeping
+    pub state: String,
+    // percent of one core since the previous sample
+    pub cpu: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadColumn {
+    // defines columns which can be sorted in the thread list
+    Tid,
+    Name,
+    State,
+    Cpu,
+}
+
+#[derive(Debug, Default)]
+pub struct ThreadSampler {
+    // process the samples belong to, a different pid starts over
+    pid: Option<u32>,
+    last_ticks: HashMap<u32, u64>,
+    last_at: Option<Instant>,
+}
+
+impl ThreadSampler {
+    // reads the threads of the process, Err if the platform has no thread listing
+    pub fn sample(&mut self, pid: u32, now: Instant) -> Result<Vec<ThreadInfo>, String> {
+        if self.pid != Some(pid) {
+            *self = ThreadSampler { pid: Some(pid), ..ThreadSampler::default() };
+        }
+        let stats = read_threads(pid)?;
+        let elapsed = self.last_at.map(|last| now.duration_since(last).as_secs_f32()).unwrap_or(0.0);
+        let ticks_per_second = procfs::clock_ticks().unwrap_or(100) as f32;
+        let threads = stats
+            .iter()
+            .map(|(tid, name, state, ticks)| {
+                let cpu = match self.last_ticks.get(tid) {
+                    Some(&last) if elapsed > 0.0 => ticks.saturating_sub(last) as f32 / ticks_per_second / elapsed * 100.0,
+                    _ => 0.0,
+                };
+                ThreadInfo { tid: *tid, name: name.clone(), state: state.clone(), cpu }
+            })
+            .collect();
+        self.last_ticks = stats.into_iter().map(|(tid, _, _, ticks)| (tid, ticks)).collect();
+        self.last_at = Some(now);
+        Ok(threads)
+    }
+}
+
+// (tid, name, state, cpu ticks) of every thread
+#[cfg(target_os = "linux")]
+fn read_threads(pid: u32) -> Result<Vec<(u32, String, String, u64)>, String> {
+    let dir = format!("/proc/{}/task", pid);
+    let entries = std::fs::read_dir(&dir).map_err(|err| format!("{}: {}", dir, err))?;
+    Ok(entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let tid = entry.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            let (name, fields) = procfs::parse_stat(&stat)?;
+            Some((tid, name, fields.first()?.clone(), procfs::cpu_ticks(&fields)?))
+        })
+        .collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_threads(_pid: u32) -> Result<Vec<(u32, String, String, u64)>, String> {
+    Err(String::from("thread listing is only supported on Linux"))
+}
+
+// sorts the threads based on the selected column and order (asc/desc)
+pub fn sort(threads: &mut [ThreadInfo], column: ThreadColumn, ascending: bool) {
+    threads.sort_by(|a, b| {
+        let cmp = match column {
+            ThreadColumn::Tid => a.tid.cmp(&b.tid),
+            ThreadColumn::Name => a.name.cmp(&b.name),
+            ThreadColumn::State => a.state.cmp(&b.state),
+            ThreadColumn::Cpu => a.cpu.total_cmp(&b.cpu),
+        }
+        .then(a.tid.cmp(&b.tid));
+        if ascending {
+            cmp
+        } else {
+            cmp.reverse()
+        }
+    });
+}
@@ -0,0 +1,156 @@
+// machine-wide usage over time for the performance charts, kept at a few resolutions - each
+// level averages the samples of its step into one point and keeps a fixed number of points,
+// so the hour takes as little memory as the last minute
+use chrono::{DateTime, Local};
+use sysinfo::System;
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// points kept per level, every range shows this many
+const POINTS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    // start of the step the value is the average of
+    pub time: DateTime<Local>,
+    pub value: f32,
+}
+
+// time span a chart shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Range {
+    #[default]
+    Minute,
+    FiveMinutes,
+    Hour,
+}
+
+impl Range {
+    pub const ALL: [Range; 3] = [Range::Minute, Range::FiveMinutes, Range::Hour];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Range::Minute => "60 s",
+            Range::FiveMinutes => "5 min",
+            Range::Hour => "1 h",
+        }
+    }
+
+    // time one point averages over
+    pub fn step(self) -> Duration {
+        match self {
+            Range::Minute => Duration::from_secs(1),
+            Range::FiveMinutes => Duration::from_secs(5),
+            Range::Hour => Duration::from_secs(60),
+        }
+    }
+
+    pub fn span(self) -> Duration {
+        self.step() * POINTS as u32
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Level {
+    step: Duration,
+    points: VecDeque<Point>,
+    // start, sum and count of the samples of the step still being filled
+    bucket: Option<(DateTime<Local>, f32, u32)>,
+}
+
+impl Level {
+    fn push(&mut self, time: DateTime<Local>, value: f32) {
+        match &mut self.bucket {
+            Some((start, sum, count)) if elapsed(*start, time) < self.step => {
+                *sum += value;
+                *count += 1;
+            }
+            bucket => {
+                if let Some((start, sum, count)) = bucket.replace((time, value, 1)) {
+                    self.points.push_back(Point { time: start, value: sum / count as f32 });
+                    if self.points.len() > POINTS {
+                        self.points.pop_front();
+                    }
+                }
+            }
+        }
+    }
+
+    // the finished points followed by the one being filled, so the chart reaches the present
+    fn points(&self) -> Vec<Point> {
+        let pending = self.bucket.map(|(time, sum, count)| Point { time, value: sum / count as f32 });
+        self.points.iter().copied().chain(pending).collect()
+    }
+}
+
+fn elapsed(from: DateTime<Local>, to: DateTime<Local>) -> Duration {
+    to.signed_duration_since(from).to_std().unwrap_or_default()
+}
+
+// one value at every resolution
+#[derive(Debug, Clone)]
+pub struct Series {
+    levels: Vec<Level>,
+}
+
+impl Default for Series {
+    fn default() -> Self {
+        Series {
+            levels: Range::ALL.iter().map(|range| Level { step: range.step(), points: VecDeque::new(), bucket: None }).collect(),
+        }
+    }
+}
+
+impl Series {
+    pub fn push(&mut self, time: DateTime<Local>, value: f32) {
+        for level in &mut self.levels {
+            level.push(time, value);
+        }
+    }
+
+    pub fn points(&self, range: Range) -> Vec<Point> {
+        let index = Range::ALL.iter().position(|&r| r == range).unwrap_or_default();
+        self.levels[index].points()
+    }
+}
+
+// what the performance tab charts, in percent
+#[derive(Debug, Default)]
+pub struct MachineHistory {
+    pub cpu: Series,
+    pub memory: Series,
+    // watts of the cpu packages, only with rapl, see energy.rs
+    pub power: Series,
+}
+
+impl MachineHistory {
+    // from an already refreshed system
+    pub fn record(&mut self, system: &System, time: DateTime<Local>) {
+        self.cpu.push(time, system.global_cpu_info().cpu_usage());
+        let total = system.total_memory();
+        if total > 0 {
+            self.memory.push(time, system.used_memory() as f32 / total as f32 * 100.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coarser_levels_average_their_steps() {
+        let start = Local::now();
+        let mut series = Series::default();
+        for second in 0..130 {
+            series.push(start + chrono::Duration::seconds(second), second as f32);
+        }
+        let minute = series.points(Range::Minute);
+        assert_eq!(minute.len(), POINTS + 1);
+        assert_eq!(minute.last().map(|point| point.value), Some(129.0));
+        let hour = series.points(Range::Hour);
+        // two finished minutes and the one being filled
+        assert_eq!(hour.iter().map(|point| point.value).collect::<Vec<_>>(), vec![29.5, 89.5, 124.5]);
+    }
+}
@@ -0,0 +1,203 @@
+// tray icon - cpu and memory in the tooltip, a menu to show/hide the window, open the views
+// saved in the settings tab (see links.rs) and quit
+//
+// on linux the icon is a StatusNotifierItem served over d-bus, which KDE, most panels and
+// GNOME with the appindicator extension show. other platforms have no tray support yet
+use iced::futures::{channel::mpsc, SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Mutex, OnceLock};
+
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayEvent {
+    // clicking the icon or its show/hide item
+    ToggleWindow,
+    // the saved view at this index of `Config::tray_links`
+    OpenLink(usize),
+    Quit,
+}
+
+// sending side for the tray thread, and the receiver until the subscription takes it
+type Events = (std_mpsc::Sender<TrayEvent>, Mutex<Option<std_mpsc::Receiver<TrayEvent>>>);
+
+// menu actions from the tray thread, picked up by the subscription
+fn events() -> &'static Events {
+    static EVENTS: OnceLock<Events> = OnceLock::new();
+    EVENTS.get_or_init(|| {
+        let (sender, receiver) = std_mpsc::channel();
+        (sender, Mutex::new(Some(receiver)))
+    })
+}
+
+pub struct Tray {
+    #[cfg(target_os = "linux")]
+    handle: Option<ksni::blocking::Handle<linux::Icon>>,
+}
+
+impl Tray {
+    // no icon, used until `start` is called for the real window
+    pub fn inactive() -> Tray {
+        Tray {
+            #[cfg(target_os = "linux")]
+            handle: None,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn start(links: Vec<String>) -> Result<Tray, String> {
+        use ksni::blocking::TrayMethods;
+
+        let icon = linux::Icon { tooltip: String::new(), links, sender: events().0.clone() };
+        let handle = icon.spawn().map_err(|err| format!("failed to show the tray icon: {}", err))?;
+        Ok(Tray { handle: Some(handle) })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn start(_links: Vec<String>) -> Result<Tray, String> {
+        Err(String::from("the tray icon is only available on Linux"))
+    }
+
+    pub fn is_active(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.handle.is_some();
+        #[cfg(not(target_os = "linux"))]
+        return false;
+    }
+
+    // replaces the tooltip text, e.g. "CPU 12% · RAM 3.1 GiB of 15.5 GiB"
+    pub fn set_tooltip(&self, tooltip: String) {
+        #[cfg(target_os = "linux")]
+        if let Some(handle) = &self.handle {
+            handle.update(|icon| icon.tooltip = tooltip);
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = tooltip;
+    }
+
+    // replaces the saved views listed in the menu
+    pub fn set_links(&self, links: Vec<String>) {
+        #[cfg(target_os = "linux")]
+        if let Some(handle) = &self.handle {
+            handle.update(|icon| icon.links = links);
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = links;
+    }
+}
+
+// removes the icon when the tray is switched off
+#[cfg(target_os = "linux")]
+impl Drop for Tray {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            let _ = handle.shutdown();
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::sync::mpsc::Sender;
+
+    use super::TrayEvent;
+
+    pub struct Icon {
+        pub tooltip: String,
+        pub links: Vec<String>,
+        pub sender: Sender<TrayEvent>,
+    }
+
+    impl ksni::Tray for Icon {
+        fn id(&self) -> String {
+            String::from("conborli")
+        }
+
+        fn title(&self) -> String {
+            String::from("Task Manager")
+        }
+
+        fn icon_name(&self) -> String {
+            String::from("utilities-system-monitor")
+        }
+
+        fn tool_tip(&self) -> ksni::ToolTip {
+            ksni::ToolTip { title: String::from("Task Manager"), description: self.tooltip.clone(), ..Default::default() }
+        }
+
+        fn activate(&mut self, _x: i32, _y: i32) {
+            let _ = self.sender.send(TrayEvent::ToggleWindow);
+        }
+
+        fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+            use ksni::menu::StandardItem;
+
+            let mut items = vec![StandardItem {
+                label: String::from("Show/hide window"),
+                activate: Box::new(|icon: &mut Icon| {
+                    let _ = icon.sender.send(TrayEvent::ToggleWindow);
+                }),
+                ..Default::default()
+            }
+            .into()];
+            if !self.links.is_empty() {
+                items.push(ksni::MenuItem::Separator);
+            }
+            for (index, link) in self.links.iter().enumerate() {
+                items.push(
+                    StandardItem {
+                        label: format!("Open {}", link),
+                        activate: Box::new(move |icon: &mut Icon| {
+                            let _ = icon.sender.send(TrayEvent::OpenLink(index));
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                );
+            }
+            items.extend([
+                ksni::MenuItem::Separator,
+                StandardItem {
+                    label: String::from("Quit"),
+                    icon_name: String::from("application-exit"),
+                    activate: Box::new(|icon: &mut Icon| {
+                        let _ = icon.sender.send(TrayEvent::Quit);
+                    }),
+                    ..Default::default()
+                }
+                .into(),
+            ]);
+            items
+        }
+    }
+}
+
+// emits a message for every tray menu action
+pub fn subscription() -> Subscription<Message> {
+    struct TrayEvents;
+
+    subscription::channel(std::any::TypeId::of::<TrayEvents>(), 10, |mut output| async move {
+        // the std receiver blocks, so it gets its own thread and forwards events here
+        let (sender, mut forwarded) = mpsc::unbounded();
+        if let Some(receiver) = events().1.lock().ok().and_then(|mut receiver| receiver.take()) {
+            std::thread::spawn(move || {
+                while let Ok(event) = receiver.recv() {
+                    if sender.unbounded_send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        loop {
+            match forwarded.next().await {
+                Some(event) => {
+                    let _ = output.send(Message::Tray(event)).await;
+                }
+                // no forwarding thread, nothing more will arrive
+                None => iced::futures::future::pending::<()>().await,
+            }
+        }
+    })
+}
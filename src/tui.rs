@@ -0,0 +1,348 @@
+// terminal frontend, started with --tui - the process table over the same collection, sorting
+// and filtering as the gui, for machines reached over ssh
+//
+// keys: arrows, page up/down, home and end move the selection, / searches, s and S pick the
+// next or previous sort column, r reverses the order, x or delete kills after a y, q quits
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Cell, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+use sysinfo::{System, Users};
+
+use std::collections::HashMap;
+use std::io::{self, stdout};
+use std::time::{Duration, Instant};
+
+use crate::cli::Cli;
+use crate::collector::{self, Shared};
+use crate::columns::{self, Formatter};
+use crate::config::Config;
+use crate::filter::Filter;
+use crate::format::format_bytes;
+use crate::holders;
+use crate::kill_guard::{self, Policy, Target};
+use crate::plugins;
+use crate::process::{self, ProcessInfo, SortColumn};
+use crate::provider::{ProcessProvider, SystemProvider};
+
+// rows moved by page up and page down
+const PAGE: isize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    // keys go to the search text
+    Search,
+    // waiting for y before killing the process
+    ConfirmKill(Target),
+}
+
+struct App {
+    config: Config,
+    system: System,
+    users: Users,
+    provider: Box<dyn ProcessProvider>,
+    cpu_history: HashMap<u32, f32>,
+    processes: Vec<ProcessInfo>,
+    filter: Filter,
+    sort_column: SortColumn,
+    sort_ascending: bool,
+    // followed by pid so refreshes don't move the selection to another process
+    selected: Option<u32>,
+    mode: Mode,
+    // result of the last kill
+    status: Option<String>,
+}
+
+// puts the terminal back however the loop ends, panics included
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(stdout(), LeaveAlternateScreen);
+    }
+}
+
+pub fn run(config: Config, cli: &Cli, shared: Option<Shared>) -> io::Result<()> {
+    let mut app = App::new(config, Box::new(SystemProvider));
+    if let Some(column) = cli.sort {
+        app.sort_column = column;
+    }
+    app.sort_ascending = !cli.desc;
+    if let Some(text) = &cli.filter {
+        app.filter.text = text.clone();
+    }
+    collector::warm_up(&mut app.system);
+    app.refresh();
+
+    enable_raw_mode()?;
+    let _guard = TerminalGuard;
+    execute!(stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+    let interval = Duration::from_secs(app.config.refresh_intervals.processes.max(1));
+    let mut refreshed = Instant::now();
+    loop {
+        if let Some(shared) = &shared {
+            shared.publish(&app.system, &app.processes);
+        }
+        terminal.draw(|frame| app.draw(frame))?;
+        if event::poll(interval.saturating_sub(refreshed.elapsed()))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && app.key(key) {
+                    return Ok(());
+                }
+            }
+        }
+        if refreshed.elapsed() >= interval {
+            app.refresh();
+            refreshed = Instant::now();
+        }
+    }
+}
+
+impl App {
+    fn new(config: Config, provider: Box<dyn ProcessProvider>) -> App {
+        App {
+            config,
+            system: System::new(),
+            users: Users::new_with_refreshed_list(),
+            provider,
+            cpu_history: HashMap::new(),
+            processes: Vec::new(),
+            filter: Filter::default(),
+            sort_column: SortColumn::Pid,
+            sort_ascending: true,
+            selected: None,
+            mode: Mode::Normal,
+            status: None,
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.system.refresh_cpu();
+        self.system.refresh_memory();
+        self.processes = collector::collect(self.provider.as_mut(), &mut self.system, &self.users, &mut self.cpu_history, &self.config);
+        plugins::fill(&mut self.processes, &self.config.columns);
+        if self.filter.looks_up_holders() {
+            holders::fill(&mut self.processes);
+        }
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        process::sort(&mut self.processes, self.sort_column, self.sort_ascending, self.config.secondary_sort);
+    }
+
+    // processes that pass the search and the low-activity filter, in their sorted order
+    fn visible(&self) -> Vec<&ProcessInfo> {
+        self.processes
+            .iter()
+            .filter(|process| (self.config.show_system_processes || !process.system) && !self.config.ignored.ignores(process))
+            .filter(|process| self.filter.matches(process) && !self.config.noise_filter.hides(process))
+            .collect()
+    }
+
+    // moves within the visible rows, starting from the top if the selection isn't among them
+    fn move_selection(&mut self, delta: isize) {
+        let visible = self.visible();
+        if visible.is_empty() {
+            self.selected = None;
+            return;
+        }
+        let current = self.selected.and_then(|pid| visible.iter().position(|process| process.pid == pid));
+        let index = match current {
+            Some(index) => (index as isize + delta).clamp(0, visible.len() as isize - 1) as usize,
+            None => 0,
+        };
+        self.selected = Some(visible[index].pid);
+    }
+
+    // the visible columns in order, stepping past either end wraps around
+    fn cycle_sort(&mut self, step: isize) {
+        let columns = &self.config.columns;
+        if columns.is_empty() {
+            return;
+        }
+        let next = match columns.iter().position(|&column| column == self.sort_column) {
+            Some(index) => (index as isize + step).rem_euclid(columns.len() as isize) as usize,
+            None => 0,
+        };
+        self.sort_column = columns[next];
+        self.sort();
+    }
+
+    // handles one key press, true when the app should quit
+    fn key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return true;
+        }
+        match self.mode.clone() {
+            Mode::Search => match key.code {
+                KeyCode::Enter => self.mode = Mode::Normal,
+                KeyCode::Esc => {
+                    self.filter.text.clear();
+                    self.mode = Mode::Normal;
+                }
+                KeyCode::Backspace => {
+                    self.filter.text.pop();
+                }
+                KeyCode::Char(c) => self.filter.text.push(c),
+                _ => {}
+            },
+            Mode::ConfirmKill(target) => {
+                if key.code == KeyCode::Char('y') {
+                    // the same checks as the gui, critical processes are left to its kill preview
+                    let policy = Policy { read_only: self.config.read_only, local: true, critical: self.config.critical.clone() };
+                    self.status = Some(match kill_guard::kill(&target, &policy, &self.processes, self.provider.as_mut(), &self.system) {
+                        Ok(()) => format!("killed {}", target.label()),
+                        Err(refusal) => refusal.message("kill", &target),
+                    });
+                    self.refresh();
+                }
+                self.mode = Mode::Normal;
+            }
+            Mode::Normal => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return true,
+                KeyCode::Up => self.move_selection(-1),
+                KeyCode::Down => self.move_selection(1),
+                KeyCode::PageUp => self.move_selection(-PAGE),
+                KeyCode::PageDown => self.move_selection(PAGE),
+                KeyCode::Home => self.move_selection(isize::MIN / 2),
+                KeyCode::End => self.move_selection(isize::MAX / 2),
+                KeyCode::Char('/') => self.mode = Mode::Search,
+                KeyCode::Char('s') => self.cycle_sort(1),
+                KeyCode::Char('S') => self.cycle_sort(-1),
+                KeyCode::Char('r') => {
+                    self.sort_ascending = !self.sort_ascending;
+                    self.sort();
+                }
+                KeyCode::Char('x') | KeyCode::Delete if self.config.read_only => {
+                    self.status = Some(String::from("read-only mode, processes can't be killed"));
+                }
+                KeyCode::Char('x') | KeyCode::Delete => {
+                    let selected = self.selected.and_then(|pid| self.processes.iter().find(|process| process.pid == pid));
+                    if let Some(process) = selected {
+                        self.mode = Mode::ConfirmKill(Target::of(process));
+                    }
+                }
+                _ => {}
+            },
+        }
+        false
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [summary, table, footer] = Layout::vertical([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)])
+            .areas(frame.size());
+        let visible = self.visible();
+        frame.render_widget(
+            Paragraph::new(format!(
+                "CPU {:.0}% · RAM {} of {} · {} of {} processes",
+                self.system.global_cpu_info().cpu_usage(),
+                format_bytes(self.system.used_memory() as f64),
+                format_bytes(self.system.total_memory() as f64),
+                visible.len(),
+                self.processes.len()
+            )),
+            summary,
+        );
+
+        let columns = &self.config.columns;
+        let formatter = Formatter { formats: &self.config.column_formats, total_memory: self.system.total_memory() / 1024 / 1024 };
+        let header = Row::new(columns.iter().map(|&column| Cell::from(columns::header_label(column, &[(self.sort_column, self.sort_ascending)], formatter))))
+        .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = visible
+            .iter()
+            .map(|process| Row::new(columns.iter().map(|&column| Cell::from(columns::cell_text(process, column, formatter)))));
+        let widths = columns.iter().map(|&column| Constraint::Fill(self.config.column_widths.portion(column)));
+        let mut state = TableState::default()
+            .with_selected(self.selected.and_then(|pid| visible.iter().position(|process| process.pid == pid)));
+        frame.render_stateful_widget(
+            Table::new(rows, widths).header(header).highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            table,
+            &mut state,
+        );
+
+        let hint = match &self.mode {
+            Mode::Search => format!("search: {}▏  enter keeps it, esc clears it", self.filter.text),
+            Mode::ConfirmKill(target) => format!("kill {}? y to confirm, any other key cancels", target.label()),
+            Mode::Normal => {
+                let search = if self.filter.text.is_empty() { String::new() } else { format!("search: {}  ", self.filter.text) };
+                let status = self.status.as_deref().map(|status| format!("{}  ", status)).unwrap_or_default();
+                let kill = if self.config.read_only { "read-only" } else { "x kill" };
+                format!("{}{}/ search  s/S sort  r reverse  {}  q quit", status, search, kill)
+            }
+        };
+        frame.render_widget(Paragraph::new(Line::from(hint)), footer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::{self, MockProvider};
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn press(app: &mut App, code: KeyCode) -> bool {
+        app.key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn search_sort_and_kill_from_the_keyboard() {
+        let killed = Rc::new(RefCell::new(Vec::new()));
+        let provider = MockProvider {
+            processes: Rc::new(RefCell::new(vec![
+                mock::process(1, "shell", "u", 5, 0.0),
+                mock::process(2, "cargo", "u", 900, 50.0),
+                mock::process(3, "cargo-watch", "u", 20, 1.0),
+            ])),
+            killed: killed.clone(),
+        };
+        let mut app = App::new(Config::default(), Box::new(provider));
+        app.config.noise_filter.enabled = false;
+        app.refresh();
+
+        press(&mut app, KeyCode::Char('/'));
+        for c in "cargo".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.visible().len(), 2);
+
+        // pid, then name, reversed puts cargo-watch first
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Char('r'));
+        assert_eq!(app.sort_column, SortColumn::Name);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.selected, Some(3));
+
+        press(&mut app, KeyCode::Char('x'));
+        press(&mut app, KeyCode::Char('n'));
+        assert!(killed.borrow().is_empty());
+        press(&mut app, KeyCode::Char('x'));
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(*killed.borrow(), vec![3]);
+        assert_eq!(app.visible().len(), 1);
+
+        // critical processes are refused, and so is everything once read-only mode is on
+        app.config.critical = vec![String::from("cargo")];
+        press(&mut app, KeyCode::Home);
+        press(&mut app, KeyCode::Char('x'));
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(app.status.as_deref(), Some("Didn't kill cargo (2), critical processes are only killed from the kill preview"));
+        app.config.critical.clear();
+        press(&mut app, KeyCode::Char('x'));
+        app.config.read_only = true;
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(*killed.borrow(), vec![3]);
+        assert!(press(&mut app, KeyCode::Char('q')));
+    }
+}
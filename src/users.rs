@@ -0,0 +1,128 @@
+// users tab - processes grouped by owning user with per-user totals
+use iced::{
+    widget::{Button, Column, Row, Scrollable, Text},
+    Element, Length,
+};
+
+use std::collections::{BTreeMap, HashSet};
+use std::process::Command;
+
+use crate::critical;
+use crate::format;
+use crate::kill_guard::Target;
+use crate::process::ProcessInfo;
+use crate::row_menu::RowAction;
+use crate::Message;
+
+#[derive(Debug, Clone)]
+pub struct UserSummary<'a> {
+    // holds the totals of every process owned by one user
+    pub name: String,
+    pub processes: Vec<&'a ProcessInfo>,
+    pub memory: u64,
+    pub cpu: f32,
+}
+
+// groups processes by user, sorted by user name
+pub fn aggregate(processes: &[ProcessInfo]) -> Vec<UserSummary<'_>> {
+    let mut users: BTreeMap<&str, UserSummary> = BTreeMap::new();
+    for process in processes {
+        let summary = users.entry(&process.user).or_insert_with(|| UserSummary {
+            name: process.user.clone(),
+            processes: Vec::new(),
+            memory: 0,
+            cpu: 0.0,
+        });
+        summary.processes.push(process);
+        summary.memory += process.memory;
+        summary.cpu += process.cpu;
+    }
+    users.into_values().collect()
+}
+
+// ends every session of the user, blocks until the session manager answers
+pub fn sign_out(user: &str) -> Result<(), String> {
+    if !cfg!(target_os = "linux") {
+        return Err(String::from("signing users out is only supported with systemd-logind"));
+    }
+    let output = Command::new("loginctl")
+        .args(["terminate-user", user])
+        .output()
+        .map_err(|err| format!("failed to run loginctl: {}", err))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("loginctl failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+// construct the users tab layout, `confirm_kill` is the user whose kill all is awaiting confirmation
+pub fn view<'a>(processes: &'a [ProcessInfo], expanded: &HashSet<String>, confirm_kill: Option<&str>, critical: &[String]) -> Element<'a, Message> {
+    let header = Row::new()
+        .spacing(10)
+        .push(Text::new("User").width(Length::FillPortion(2)))
+        .push(Text::new("Processes").width(Length::FillPortion(1)))
+        .push(Text::new("Memory (MB)").width(Length::FillPortion(1)))
+        .push(Text::new("CPU (%)").width(Length::FillPortion(1)))
+        .push(Text::new("Actions").width(Length::FillPortion(2)));
+    let rows = aggregate(processes).into_iter().fold(
+        Column::new().spacing(5),
+        |column, user| {
+            let is_expanded = expanded.contains(&user.name);
+            let toggle = if is_expanded { "▾" } else { "▸" };
+            // killing everything a user owns needs a second press
+            let kill_all = if confirm_kill == Some(user.name.as_str()) {
+                Button::new("Confirm kill all").on_press(Message::KillUserProcesses(user.name.clone()))
+            } else {
+                Button::new("Kill all").on_press(Message::ConfirmKillUserProcesses(user.name.clone()))
+            };
+            let mut column = column.push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Button::new(Text::new(format!("{} {}", toggle, user.name)))
+                            .on_press(Message::ToggleUserExpanded(user.name.clone()))
+                            .width(Length::FillPortion(2))
+                    )
+                    .push(Text::new(user.processes.len().to_string()).width(Length::FillPortion(1)))
+                    .push(Text::new(user.memory.to_string()).width(Length::FillPortion(1)))
+                    .push(Text::new(format::decimal(f64::from(user.cpu), 1)).width(Length::FillPortion(1)))
+                    .push(
+                        Row::new()
+                            .spacing(5)
+                            .push(kill_all)
+                            .push(Button::new("Sign out").on_press(Message::SignOutUser(user.name.clone())))
+                            .width(Length::FillPortion(2))
+                    )
+            );
+            // the user's processes, indented under their summary row
+            if is_expanded {
+                for process in &user.processes {
+                    // critical processes go through the kill preview and its extra confirmation
+                    let kill = if critical::is_critical(process, critical) {
+                        Message::RowAction(process.pid, RowAction::Kill)
+                    } else {
+                        Message::KillProcess(Target::of(process))
+                    };
+                    column = column.push(
+                        Row::new()
+                            .spacing(10)
+                            .padding([0, 0, 0, 30])
+                            .push(Text::new(format!("{}  {}", process.pid, process.name)).width(Length::FillPortion(2)))
+                            .push(Text::new("").width(Length::FillPortion(1)))
+                            .push(Text::new(process.memory.to_string()).width(Length::FillPortion(1)))
+                            .push(Text::new(format::decimal(f64::from(process.cpu), 1)).width(Length::FillPortion(1)))
+                            .push(Button::new("Kill").on_press(kill))
+                            .push(Text::new("").width(Length::FillPortion(2)))
+                    );
+                }
+            }
+            column
+        },
+    );
+    Column::new()
+        .spacing(10)
+        .push(header)
+        .push(Scrollable::new(rows))
+        .into()
+}
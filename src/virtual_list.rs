@@ -0,0 +1,108 @@
+// windowed rendering of long lists - only rows inside the scroll viewport are built
+//
+// every row has the same fixed height so the rows outside the viewport can be replaced by
+// two spacers of the right height, keeping the scrollbar and offsets exact
+use iced::widget::{scrollable, Column, Scrollable, Space};
+use iced::{Element, Length};
+
+use std::ops::Range;
+
+use crate::style::Ui;
+
+// rows built above and below the viewport so fast scrolling doesn't show gaps
+const OVERSCAN: usize = 10;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ListWindow {
+    // scroll offset from the top and height of the viewport, as last reported by the scrollable
+    pub offset: f32,
+    pub height: f32,
+    // height of every row in logical pixels, from the text size and density settings
+    pub row_height: f32,
+}
+
+impl Default for ListWindow {
+    // a generous guess until the first scroll event, so the first frame isn't empty
+    fn default() -> Self {
+        ListWindow { offset: 0.0, height: 1200.0, row_height: Ui::default().row_height() }
+    }
+}
+
+impl ListWindow {
+    pub fn update(&mut self, viewport: scrollable::Viewport) {
+        self.offset = viewport.absolute_offset().y;
+        self.height = viewport.bounds().height;
+    }
+
+    // the offset kept within a list of `len` rows. a list that shrank under the offset isn't
+    // scrolled back by a scroll event, iced sends none when the content fits the viewport
+    pub fn offset(&self, len: usize) -> f32 {
+        self.offset.min(len as f32 * self.row_height - self.height).max(0.0)
+    }
+
+    // indices of the rows to build out of `len`
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let first = ((self.offset(len) / self.row_height).floor() as usize).saturating_sub(OVERSCAN);
+        let shown = (self.height / self.row_height).ceil() as usize + 2 * OVERSCAN + 1;
+        first.min(len)..(first + shown).min(len)
+    }
+
+    // offset that keeps a row that moved from index `from` to `to` at the same height on
+    // screen, None if it didn't move
+    pub fn follow(&self, from: usize, to: usize) -> Option<f32> {
+        (from != to).then(|| (self.offset + (to as f32 - from as f32) * self.row_height).max(0.0))
+    }
+
+    // offset that brings row `index` of `len` into view, None if it is already visible
+    pub fn scroll_to(&self, index: usize, len: usize) -> Option<f32> {
+        let top = index as f32 * self.row_height;
+        let offset = self.offset(len);
+        if top < offset {
+            Some(top)
+        } else if top + self.row_height > offset + self.height {
+            Some(top + self.row_height - self.height)
+        } else {
+            None
+        }
+    }
+}
+
+// scrollable list of `items` where only the visible ones go through `row`
+pub fn view<'a, T, Message: 'a>(
+    id: scrollable::Id,
+    items: &[T],
+    window: &ListWindow,
+    on_scroll: fn(scrollable::Viewport) -> Message,
+    row: impl Fn(&T) -> Element<'a, Message>,
+) -> Element<'a, Message> {
+    let range = window.range(items.len());
+    let above = range.start as f32 * window.row_height;
+    let below = (items.len() - range.end) as f32 * window.row_height;
+    let rows = items[range].iter().fold(
+        Column::new().push(Space::with_height(Length::Fixed(above))),
+        |column, item| column.push(row(item)),
+    );
+    Scrollable::new(rows.push(Space::with_height(Length::Fixed(below))).width(Length::Fill))
+        .id(id)
+        .height(Length::Fill)
+        .on_scroll(on_scroll)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrunk_lists_are_not_scrolled_past_their_end() {
+        let window = ListWindow { offset: 5000.0, height: 100.0, row_height: 10.0 };
+        assert_eq!(window.range(1000), 490..521);
+        // a search left three rows under an offset far down the old list
+        assert_eq!(window.offset(3), 0.0);
+        assert_eq!(window.range(3), 0..3);
+        assert_eq!(window.offset(20), 100.0);
+        assert_eq!(window.range(20), 0..20);
+        assert_eq!(window.scroll_to(2, 3), None);
+        assert_eq!(window.scroll_to(0, 20), Some(0.0));
+    }
+}
@@ -0,0 +1,224 @@
+// watchdog - starts watched programs again when they exit
+//
+// watch entries are stored in the config. on every process table refresh each entry is
+// matched to a live process, by the pid it was last seen with or else by executable and
+// arguments. an entry without a live process is started again after a backoff that doubles
+// with every restart that didn't stay up for STABLE_AFTER, up to MAX_BACKOFF
+use iced::{
+    widget::{Button, Column, Row, Text},
+    Element, Length,
+};
+use serde::{Deserialize, Serialize};
+use sysinfo::Process;
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::format::format_duration;
+use crate::process::ProcessInfo;
+use crate::restart;
+use crate::Message;
+
+// a restart that stays up this long resets the backoff
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// entries kept in the restart log
+const LOG_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WatchEntry {
+    // process name when it was watched, for display
+    pub name: String,
+    // what is started again, like a restart
+    pub exe: PathBuf,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl WatchEntry {
+    // None if the executable can't be read, e.g. for another user's process
+    pub fn capture(process: &Process) -> Option<WatchEntry> {
+        Some(WatchEntry {
+            name: process.name().to_string(),
+            exe: process.exe()?.to_path_buf(),
+            args: process.cmd().iter().skip(1).cloned().collect(),
+            cwd: process.cwd().map(|cwd| cwd.to_path_buf()),
+        })
+    }
+
+    // same executable and arguments, `cmd` is the full argv as sysinfo reads it, so arguments
+    // with spaces in them are told apart
+    pub fn matches(&self, exe: Option<&Path>, cmd: &[String]) -> bool {
+        exe == Some(self.exe.as_path()) && cmd.iter().skip(1).eq(self.args.iter())
+    }
+}
+
+#[derive(Debug, Default)]
+struct WatchState {
+    // live process of the entry and since when it is known to run
+    pid: Option<u32>,
+    since: Option<Instant>,
+    // restarts in a row that didn't stay up, decides the backoff
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestartEvent {
+    pub at: Instant,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    states: HashMap<WatchEntry, WatchState>,
+    pub log: VecDeque<RestartEvent>,
+}
+
+// 1s, 2s, 4s, ... up to the maximum
+fn backoff(failures: u32) -> Duration {
+    Duration::from_secs(1u64 << failures.min(16)).min(MAX_BACKOFF)
+}
+
+impl Watchdog {
+    // checks every entry against the fresh table and starts the ones that are due, `argv`
+    // gives the full command line of a pid in the table
+    pub fn supervise<'a>(
+        &mut self,
+        entries: &[WatchEntry],
+        processes: &[ProcessInfo],
+        argv: impl Fn(u32) -> Option<&'a [String]>,
+        now: Instant,
+    ) {
+        self.states.retain(|entry, _| entries.contains(entry));
+        for entry in entries {
+            let state = self.states.entry(entry.clone()).or_default();
+            let alive = state.pid.is_some_and(|pid| processes.iter().any(|process| process.pid == pid));
+            if !alive {
+                state.pid = processes
+                    .iter()
+                    .find(|process| argv(process.pid).is_some_and(|cmd| entry.matches(process.exe.as_deref(), cmd)))
+                    .map(|process| process.pid);
+                state.since = state.pid.map(|_| now);
+            }
+            if state.pid.is_some() {
+                if state.since.is_some_and(|since| now.duration_since(since) >= STABLE_AFTER) {
+                    state.failures = 0;
+                }
+                state.retry_at = None;
+                continue;
+            }
+            let retry_at = *state.retry_at.get_or_insert_with(|| now + backoff(state.failures));
+            if now < retry_at {
+                continue;
+            }
+            let text = match restart::spawn(&entry.exe, &entry.args, entry.cwd.as_deref()) {
+                Ok(pid) => {
+                    state.pid = Some(pid);
+                    state.since = Some(now);
+                    format!("{} exited, started again as process {}", entry.name, pid)
+                }
+                Err(err) => format!("{} exited, {}", entry.name, err),
+            };
+            state.failures += 1;
+            state.retry_at = None;
+            self.log.push_back(RestartEvent { at: now, text });
+            if self.log.len() > LOG_LENGTH {
+                self.log.pop_front();
+            }
+        }
+    }
+
+    // pid the entry runs as, if it is running
+    fn pid(&self, entry: &WatchEntry) -> Option<u32> {
+        self.states.get(entry).and_then(|state| state.pid)
+    }
+}
+
+// construct the watched processes section of the rules tab
+pub fn view<'a>(entries: &'a [WatchEntry], watchdog: &'a Watchdog) -> Element<'a, Message> {
+    let list = entries.iter().enumerate().fold(Column::new().spacing(5), |column, (index, entry)| {
+        let state = match watchdog.pid(entry) {
+            Some(pid) => format!("running as {}", pid),
+            None => String::from("waiting to restart"),
+        };
+        column.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(&entry.name).width(Length::FillPortion(1)))
+                .push(Text::new(format!("{} {}", entry.exe.display(), entry.args.join(" "))).width(Length::FillPortion(3)))
+                .push(Text::new(state).width(Length::FillPortion(1)))
+                .push(Button::new("Unwatch").on_press(Message::Unwatch(index)))
+        )
+    });
+    // newest first
+    let now = Instant::now();
+    let log = watchdog.log.iter().rev().fold(Column::new().spacing(2), |column, event| {
+        column.push(Text::new(format!("{} ago  {}", format_duration(now.duration_since(event.at).as_secs()), event.text)))
+    });
+    let mut content = Column::new().spacing(10).push(Text::new("Watched processes"));
+    content = if entries.is_empty() {
+        content.push(Text::new("Select a process and press Watch to restart it whenever it exits"))
+    } else {
+        content.push(list)
+    };
+    content.push(log).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let delays: Vec<u64> = [0, 1, 2, 8, 9, 40].into_iter().map(|failures| backoff(failures).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 256, 300, 300]);
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn entries_match_the_whole_argv() {
+        let entry = WatchEntry { name: String::from("app"), exe: PathBuf::from("/opt/app"), args: argv(&["--title", "my app"]), cwd: None };
+        let exe = Some(Path::new("/opt/app"));
+        assert!(entry.matches(exe, &argv(&["app", "--title", "my app"])));
+        // the same text split differently is another command line
+        assert!(!entry.matches(exe, &argv(&["app", "--title", "my", "app"])));
+        assert!(!entry.matches(exe, &argv(&["app", "--title"])));
+        assert!(!entry.matches(Some(Path::new("/usr/bin/app")), &argv(&["app", "--title", "my app"])));
+        assert!(!entry.matches(None, &argv(&["app", "--title", "my app"])));
+    }
+
+    #[test]
+    fn exited_entries_are_started_again_with_a_growing_backoff() {
+        let entry = WatchEntry { name: String::from("app"), exe: PathBuf::from("/nonexistent/app"), args: Vec::new(), cwd: None };
+        let entries = [entry.clone()];
+        let mut running = mock::process(40, "app", "u", 10, 0.0);
+        running.exe = Some(entry.exe.clone());
+        let cmd = argv(&["app"]);
+        let lookup = |pid: u32| (pid == 40).then_some(cmd.as_slice());
+        let mut watchdog = Watchdog::default();
+        let start = Instant::now();
+
+        watchdog.supervise(&entries, &[running], lookup, start);
+        assert_eq!(watchdog.pid(&entry), Some(40));
+        assert!(watchdog.log.is_empty());
+
+        // gone, the first restart waits a second and the next one two more
+        watchdog.supervise(&entries, &[], lookup, start + Duration::from_millis(500));
+        assert!(watchdog.log.is_empty());
+        watchdog.supervise(&entries, &[], lookup, start + Duration::from_millis(1500));
+        assert_eq!(watchdog.log.len(), 1);
+        assert!(watchdog.log[0].text.starts_with("app exited, failed to start /nonexistent/app"));
+        watchdog.supervise(&entries, &[], lookup, start + Duration::from_millis(2500));
+        assert_eq!(watchdog.log.len(), 1);
+        watchdog.supervise(&entries, &[], lookup, start + Duration::from_millis(4600));
+        assert_eq!(watchdog.log.len(), 2);
+        assert_eq!(watchdog.pid(&entry), None);
+    }
+}
@@ -0,0 +1,59 @@
+// mini mode - a small floating always-on-top window with the totals and the busiest processes,
+// fed by the same refresh as the main window
+use iced::{
+    widget::{Button, Column, Row, Text},
+    Element, Length,
+};
+
+use crate::config::{Config, WindowGeometry};
+use crate::format::{self, format_bytes};
+use crate::process::ProcessInfo;
+use crate::Message;
+
+// size of the mini window in logical pixels
+pub const MINI_WIDTH: u32 = 280;
+pub const MINI_HEIGHT: u32 = 190;
+
+// processes listed in the mini window
+const MINI_TOP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compact {
+    // layouts that replace the full window
+    Mini,
+}
+
+// values shown above the processes
+pub struct Totals {
+    pub cpu: f32,
+    pub used_memory: u64,
+    pub total_memory: u64,
+}
+
+// where the mini window goes: where it was last dragged to, else where the window is
+pub fn mini_geometry(config: &Config, window: WindowGeometry) -> WindowGeometry {
+    let (x, y) = config.mini_position.unwrap_or((window.x, window.y));
+    WindowGeometry { x, y, width: MINI_WIDTH, height: MINI_HEIGHT }
+}
+
+// construct the mini window layout, the busiest processes by cpu under the totals
+pub fn mini_view(stats: Totals, processes: &[ProcessInfo]) -> Element<'_, Message> {
+    let mut busiest: Vec<&ProcessInfo> = processes.iter().collect();
+    busiest.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    let top = busiest.into_iter().take(MINI_TOP).fold(Column::new().spacing(2), |column, process| {
+        column.push(
+            Row::new()
+                .push(Text::new(&process.name).width(Length::Fill))
+                .push(Text::new(format!("{}%", format::decimal(f64::from(process.cpu), 1))))
+        )
+    });
+    Column::new()
+        .spacing(5)
+        .padding(8)
+        .width(Length::Fill)
+        .push(Text::new(format!("CPU {}%", format::decimal(f64::from(stats.cpu), 1))))
+        .push(Text::new(format!("Mem {} / {}", format_bytes(stats.used_memory as f64), format_bytes(stats.total_memory as f64))))
+        .push(top)
+        .push(Button::new("Full window").on_press(Message::SetCompact(None)))
+        .into()
+}